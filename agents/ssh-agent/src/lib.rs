@@ -2,25 +2,35 @@
 //! - Cross-platform agent (UNIX sockets on Unix, Named Pipes on Windows)
 //! - Implements SSH Agent protocol subset:
 //!   - request_identities
-//!   - sign_request (ed25519)
+//!   - sign_request (ed25519, including `ssh-ed25519-cert-v01@openssh.com` certificates)
+//!   - add_identity / remove_identity / remove_all_identities (ed25519), for `ssh-add`
+//!   - add_id_constrained with the confirm and lifetime constraints (`ssh-add -c` / `-t`)
+//!   - lock / unlock with a passphrase (`ssh-add -x` / `-X`)
 //! - Loads SSH keys (ed25519) from Persona vault (CredentialType::SshKey)
+//! - Optionally saves keys added with `ssh-add` to the vault (PERSONA_AGENT_PERSIST_ADDED_KEYS)
 //! - Unlocks using master password from env PERSONA_MASTER_PASSWORD (if required)
+//! - Writes scheduled vault backups when `config.toml` beside the vault enables `auto_backup`
+//! - Optionally asks a webhook to approve signatures instead of the local confirmation prompt
+//!   (PERSONA_APPROVAL_WEBHOOK, PERSONA_APPROVAL_TIMEOUT_SECS)
 //! - Advanced policy enforcement: per-host, per-key, time-based restrictions
 //!
 //! NOTE: This is an early MVP; enhanced policies/approvals in progress.
 
 pub mod policy;
+pub mod status;
 pub mod transport;
 
 use anyhow::{anyhow, Context, Result};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use persona_core::storage::{BackupSchedule, BackupScheduler, Database};
 use persona_core::{
-    BiometricPlatform, BiometricPrompt, BiometricProvider, PersonaError, RedactedLoggerBuilder,
-    Repository,
+    ApprovalContext, ApprovalOutcome, BiometricPrompt, BiometricProvider, PasswordHasher,
+    PersonaError, RedactedLoggerBuilder, RemoteApprover, Repository,
 };
 use policy::{PolicyEnforcer, SignatureDecision};
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use tracing::{info, warn, Level};
 use transport::{default_agent_path, AgentListener, AgentStream};
 
@@ -29,6 +39,9 @@ pub async fn run_agent() -> Result<()> {
         .include_target(false)
         .init()?;
 
+    // Refuse to start with a broken policy file rather than run without the intended rules
+    let enforcer = PolicyEnforcer::load()?;
+
     let socket_path = default_agent_path();
     let db_path = resolve_persona_db_path();
 
@@ -40,31 +53,40 @@ pub async fn run_agent() -> Result<()> {
     if endpoint == "unknown" {
         endpoint = socket_path.display().to_string();
     }
-    info!("persona-ssh-agent listening at {}", endpoint);
+    info!(
+        "persona-ssh-agent {} listening at {}",
+        persona_core::LONG_VERSION,
+        endpoint
+    );
     println!("SSH_AUTH_SOCK={}", endpoint);
 
     // Write state files
-    let state_dir = std::env::var("PERSONA_AGENT_STATE_DIR")
-        .ok()
-        .map(PathBuf::from)
-        .unwrap_or_else(|| {
-            dirs::home_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join(".persona")
-        });
+    let state_dir = status::agent_state_dir();
     let _ = std::fs::create_dir_all(&state_dir);
-    let sock_file = state_dir.join("ssh-agent.sock");
-    let pid_file = state_dir.join("ssh-agent.pid");
+    let sock_file = state_dir.join(status::SOCKET_FILE);
+    let pid_file = state_dir.join(status::PID_FILE);
     let _ = std::fs::write(&sock_file, &endpoint);
     let _ = std::fs::write(&pid_file, std::process::id().to_string());
 
     // Load keys from Persona
-    let mut agent = Agent::new();
+    let mut agent = Agent::with_policy(enforcer);
     agent
         .load_keys_from_persona(&db_path)
         .await
         .map_err(|e| anyhow!(e))?;
-    info!("Loaded {} SSH keys from Persona", agent.keys.len());
+    info!("Loaded {} SSH keys from Persona", agent.key_count());
+
+    // Drop keys added with `ssh-add -t` once their lifetime is up, even if no client asks
+    let sweeper = agent.clone_shallow();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(EXPIRY_SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            sweeper.prune_expired();
+        }
+    });
+
+    spawn_scheduled_backups(&db_path).await;
 
     loop {
         let stream = listener.accept().await?;
@@ -77,6 +99,37 @@ pub async fn run_agent() -> Result<()> {
     }
 }
 
+/// Start automatic backups if the workspace config beside the vault asks for them.
+///
+/// Problems are logged rather than fatal: the agent keeps serving keys without backups.
+async fn spawn_scheduled_backups(db_path: &Path) {
+    let schedule = match BackupSchedule::for_database(db_path) {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("Scheduled backups disabled: {}", e);
+            return;
+        }
+    };
+    if !db_path.exists() {
+        return;
+    }
+    match Database::from_file(db_path).await {
+        Ok(db) => {
+            info!(
+                "Backing up the vault every {}s to {}",
+                schedule.interval.as_secs(),
+                schedule.directory.display()
+            );
+            BackupScheduler::new(schedule).spawn(db, None);
+        }
+        Err(e) => warn!("Scheduled backups disabled: {}", e),
+    }
+}
+
+/// How often the agent checks for keys whose lifetime has run out
+const EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
 pub async fn handle_connection(agent: &mut Agent, mut stream: AgentStream) -> Result<()> {
     use byteorder::{BigEndian, ByteOrder};
     loop {
@@ -99,7 +152,80 @@ pub async fn handle_connection(agent: &mut Agent, mut stream: AgentStream) -> Re
             }
             13 => {
                 // SSH_AGENTC_SIGN_REQUEST
-                let resp = agent.sign_response(&pkt[1..])?;
+                let resp = agent.sign_response(&pkt[1..]).await?;
+                stream.write_all(&resp).await?;
+            }
+            17..=19 | 25 if agent.is_locked() => {
+                // A locked agent refuses key changes until unlocked
+                stream.write_all(&failure_packet()).await?;
+            }
+            17 => {
+                // SSH_AGENTC_ADD_IDENTITY
+                let resp = match agent.add_identity(&pkt[1..]).await {
+                    Ok(()) => success_packet(),
+                    Err(e) => {
+                        warn!("Failed to add identity: {}", e);
+                        failure_packet()
+                    }
+                };
+                stream.write_all(&resp).await?;
+            }
+            25 => {
+                // SSH_AGENTC_ADD_ID_CONSTRAINED
+                let resp = match agent.add_constrained_identity(&pkt[1..]).await {
+                    Ok(()) => success_packet(),
+                    Err(e) => {
+                        warn!("Failed to add identity: {}", e);
+                        failure_packet()
+                    }
+                };
+                stream.write_all(&resp).await?;
+            }
+            18 => {
+                // SSH_AGENTC_REMOVE_IDENTITY
+                let resp = match agent.remove_identity(&pkt[1..]) {
+                    Ok(true) => success_packet(),
+                    Ok(false) => failure_packet(),
+                    Err(e) => {
+                        warn!("Failed to remove identity: {}", e);
+                        failure_packet()
+                    }
+                };
+                stream.write_all(&resp).await?;
+            }
+            19 => {
+                // SSH_AGENTC_REMOVE_ALL_IDENTITIES
+                let resp = match agent.remove_all_identities() {
+                    Ok(()) => success_packet(),
+                    Err(e) => {
+                        warn!("Failed to remove identities: {}", e);
+                        failure_packet()
+                    }
+                };
+                stream.write_all(&resp).await?;
+            }
+            22 => {
+                // SSH_AGENTC_LOCK
+                let resp = match agent.lock(&pkt[1..]) {
+                    Ok(true) => success_packet(),
+                    Ok(false) => failure_packet(),
+                    Err(e) => {
+                        warn!("Failed to lock agent: {}", e);
+                        failure_packet()
+                    }
+                };
+                stream.write_all(&resp).await?;
+            }
+            23 => {
+                // SSH_AGENTC_UNLOCK
+                let resp = match agent.unlock(&pkt[1..]) {
+                    Ok(true) => success_packet(),
+                    Ok(false) => failure_packet(),
+                    Err(e) => {
+                        warn!("Failed to unlock agent: {}", e);
+                        failure_packet()
+                    }
+                };
                 stream.write_all(&resp).await?;
             }
             other => {
@@ -122,25 +248,120 @@ pub struct AgentKey {
     pub secret_seed: [u8; 32], // ed25519 seed
     pub identity_id: uuid::Uuid,
     pub credential_id: uuid::Uuid,
+    /// Added with `ssh-add -c`: every signature is confirmed, whatever the policy allows
+    pub require_confirm: bool,
+    /// Added with `ssh-add -t`: the key is dropped from the agent at this time
+    pub expires_at: Option<Instant>,
+    /// OpenSSH certificate for the key, offered to clients instead of the bare key
+    pub certificate_blob: Option<Vec<u8>>,
+}
+
+impl AgentKey {
+    /// Key for a vault SSH credential.
+    ///
+    /// `private_key` holds the base64 ed25519 seed and `public_key` the OpenSSH public line,
+    /// either a bare `ssh-ed25519` key or an ed25519 certificate issued for it.
+    fn from_ssh_key_data(
+        ssh: &persona_core::models::SshKeyData,
+        comment: String,
+        identity_id: uuid::Uuid,
+        credential_id: uuid::Uuid,
+    ) -> Result<Self> {
+        let secret_seed: [u8; 32] = BASE64
+            .decode(ssh.private_key.expose())
+            .ok()
+            .and_then(|seed| seed.try_into().ok())
+            .ok_or_else(|| anyhow!("invalid SSH seed size"))?;
+        let (public_blob, certificate_blob) = parse_openssh_public_key(&ssh.public_key)
+            .ok_or_else(|| anyhow!("invalid OpenSSH public key"))?;
+        if certificate_blob.is_some() && public_blob != ed25519_public_blob(&secret_seed)? {
+            anyhow::bail!("certificate was not issued for this key");
+        }
+        Ok(Self {
+            public_blob,
+            comment,
+            secret_seed,
+            identity_id,
+            credential_id,
+            require_confirm: false,
+            expires_at: None,
+            certificate_blob,
+        })
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Blob listed to clients: the certificate if there is one, else the bare key
+    fn advertised_blob(&self) -> &[u8] {
+        self.certificate_blob
+            .as_deref()
+            .unwrap_or(&self.public_blob)
+    }
+
+    /// Whether a client's key blob, bare or certificate, names this key
+    fn matches_blob(&self, blob: &[u8]) -> bool {
+        self.public_blob == blob || self.certificate_blob.as_deref() == Some(blob)
+    }
+}
+
+/// Asks the user to allow a signature; the default prompts on the terminal
+pub type ConfirmPrompt = Arc<dyn Fn(&str) -> Result<bool> + Send + Sync>;
+
+/// Signing policy and agent lock state, shared by every connection under one mutex
+struct PolicyGuarded {
+    enforcer: PolicyEnforcer,
+    /// Set by `ssh-add -x`; a locked agent lists no keys and signs nothing
+    locked: bool,
+    /// Argon2 hash of the passphrase that unlocks the agent
+    lock_hash: Option<String>,
 }
 
 pub struct Agent {
-    keys: Vec<AgentKey>,
-    policy: Arc<Mutex<PolicyEnforcer>>,
+    /// Shared with every `clone_shallow` copy, so all connections see keys added or removed
+    /// over any one of them
+    keys: Arc<RwLock<Vec<AgentKey>>>,
+    policy: Arc<Mutex<PolicyGuarded>>,
     biometric_provider: Arc<dyn BiometricProvider>,
+    confirm_prompt: ConfirmPrompt,
+    /// Approves signatures from another device in place of `confirm_prompt`
+    remote_approver: Option<Arc<dyn RemoteApprover>>,
+    /// Save keys added with `ssh-add` to the vault as well as holding them in memory
+    persist_added_keys: bool,
 }
 
 impl Agent {
     pub fn new() -> Self {
-        let enforcer = PolicyEnforcer::from_env();
-        // Use mock provider by default; desktop/mobile apps can inject real implementation
-        let biometric_provider: Arc<dyn BiometricProvider> =
-            Arc::new(persona_core::MockBiometricProvider::default());
+        Self::with_policy(PolicyEnforcer::from_env())
+    }
+
+    /// Agent enforcing `enforcer`, e.g. one loaded with [`PolicyEnforcer::load`]
+    pub fn with_policy(enforcer: PolicyEnforcer) -> Self {
+        // Touch ID / Windows Hello / polkit when usable; otherwise an unavailable mock, so keys
+        // that require biometrics fall back to the confirmation prompt instead of passing
+        let biometric_provider = persona_core::platform_biometric_provider().unwrap_or_else(|| {
+            Arc::new(persona_core::MockBiometricProvider {
+                available: false,
+                ..Default::default()
+            })
+        });
+
+        let persist_added_keys = std::env::var("PERSONA_AGENT_PERSIST_ADDED_KEYS")
+            .map(|v| v != "0" && v.to_lowercase() != "false")
+            .unwrap_or(false);
 
         Self {
-            keys: Vec::new(),
-            policy: Arc::new(Mutex::new(enforcer)),
+            keys: Arc::new(RwLock::new(Vec::new())),
+            policy: Arc::new(Mutex::new(PolicyGuarded {
+                enforcer,
+                locked: false,
+                lock_hash: None,
+            })),
             biometric_provider,
+            confirm_prompt: Arc::new(prompt_confirm_blocking),
+            remote_approver: remote_approver_from_env(),
+            persist_added_keys,
         }
     }
     pub fn clone_shallow(&self) -> Self {
@@ -148,7 +369,121 @@ impl Agent {
             keys: self.keys.clone(),
             policy: self.policy.clone(),
             biometric_provider: self.biometric_provider.clone(),
+            confirm_prompt: self.confirm_prompt.clone(),
+            remote_approver: self.remote_approver.clone(),
+            persist_added_keys: self.persist_added_keys,
+        }
+    }
+
+    /// Whether keys added with `ssh-add` are also saved to the vault
+    pub fn set_persist_added_keys(&mut self, persist: bool) {
+        self.persist_added_keys = persist;
+    }
+
+    /// Replace the terminal prompt used to confirm signatures, e.g. with a desktop dialog
+    pub fn set_confirm_prompt(&mut self, prompt: ConfirmPrompt) {
+        self.confirm_prompt = prompt;
+    }
+
+    /// Ask `approver` to approve signatures instead of the confirmation prompt (`None` restores
+    /// the prompt)
+    pub fn set_remote_approver(&mut self, approver: Option<Arc<dyn RemoteApprover>>) {
+        self.remote_approver = approver;
+    }
+
+    /// Number of keys the agent currently offers
+    pub fn key_count(&self) -> usize {
+        self.keys.read().map(|keys| keys.len()).unwrap_or(0)
+    }
+
+    /// Whether the agent was locked with `ssh-add -x`
+    pub fn is_locked(&self) -> bool {
+        // A poisoned lock is treated as locked so it never exposes keys
+        self.policy
+            .lock()
+            .map(|guarded| guarded.locked)
+            .unwrap_or(true)
+    }
+
+    /// Handle SSH_AGENTC_LOCK; `false` if the agent is already locked
+    pub fn lock(&self, mut payload: &[u8]) -> Result<bool> {
+        let passphrase = read_ssh_string(&mut payload)?;
+        if self.is_locked() {
+            return Ok(false);
+        }
+        // Hash before taking the lock so slow hashing does not stall other connections
+        let hash = PasswordHasher::new()
+            .hash_password(&String::from_utf8_lossy(&passphrase))
+            .map_err(|e| anyhow!(e))?;
+        let mut guarded = self
+            .policy
+            .lock()
+            .map_err(|_| anyhow!("Policy lock poisoned"))?;
+        if guarded.locked {
+            return Ok(false);
         }
+        guarded.locked = true;
+        guarded.lock_hash = Some(hash);
+        info!("Agent locked");
+        Ok(true)
+    }
+
+    /// Handle SSH_AGENTC_UNLOCK; `false` if the agent is not locked or the passphrase is wrong
+    pub fn unlock(&self, mut payload: &[u8]) -> Result<bool> {
+        let passphrase = read_ssh_string(&mut payload)?;
+        let hash = {
+            let guarded = self
+                .policy
+                .lock()
+                .map_err(|_| anyhow!("Policy lock poisoned"))?;
+            match (&guarded.locked, &guarded.lock_hash) {
+                (true, Some(hash)) => hash.clone(),
+                _ => return Ok(false),
+            }
+        };
+        let matches = PasswordHasher::new()
+            .verify_password(&String::from_utf8_lossy(&passphrase), &hash)
+            .map_err(|e| anyhow!(e))?;
+        if !matches {
+            warn!("Agent unlock failed: wrong passphrase");
+            return Ok(false);
+        }
+        let mut guarded = self
+            .policy
+            .lock()
+            .map_err(|_| anyhow!("Policy lock poisoned"))?;
+        // Only clear the lock this passphrase was checked against
+        if guarded.lock_hash.as_deref() == Some(hash.as_str()) {
+            guarded.locked = false;
+            guarded.lock_hash = None;
+            info!("Agent unlocked");
+        }
+        Ok(true)
+    }
+
+    /// Drop keys whose `ssh-add -t` lifetime has run out; returns how many were dropped
+    pub fn prune_expired(&self) -> usize {
+        let Ok(mut keys) = self.keys.write() else {
+            return 0;
+        };
+        let now = Instant::now();
+        let before = keys.len();
+        keys.retain(|k| {
+            let expired = k.is_expired(now);
+            if expired {
+                info!("SSH key '{}' expired and was removed", k.comment);
+            }
+            !expired
+        });
+        before - keys.len()
+    }
+
+    fn push_key(&self, key: AgentKey) -> Result<()> {
+        self.keys
+            .write()
+            .map_err(|_| anyhow!("Key lock poisoned"))?
+            .push(key);
+        Ok(())
     }
 
     pub async fn load_keys_from_persona(&mut self, db_path: &PathBuf) -> persona_core::Result<()> {
@@ -157,7 +492,7 @@ impl Agent {
             return Ok(());
         }
         use persona_core::models::{CredentialData, CredentialType};
-        use persona_core::{Database, PersonaService};
+        use persona_core::{Database, PersonaService, SecretString};
 
         let db = Database::from_file(db_path).await?;
         db.migrate().await?;
@@ -165,7 +500,7 @@ impl Agent {
         let mut unlocked = true;
         if service.has_users().await? {
             if let Ok(pass) = std::env::var("PERSONA_MASTER_PASSWORD") {
-                match service.authenticate_user(&pass).await? {
+                match service.authenticate_user(&SecretString::new(pass)).await? {
                     persona_core::auth::authentication::AuthResult::Success => {}
                     _ => {
                         unlocked = false;
@@ -187,33 +522,10 @@ impl Agent {
                     if let Some(CredentialData::SshKey(ssh)) =
                         service.get_credential_data(&cred.id).await?
                     {
-                        // ssh.private_key is base64 seed; ssh.public_key is OpenSSH text
-                        let seed_bytes = match BASE64.decode(&ssh.private_key) {
-                            Ok(b) if b.len() == 32 => {
-                                let mut arr = [0u8; 32];
-                                arr.copy_from_slice(&b);
-                                arr
-                            }
-                            _ => {
-                                warn!("Invalid SSH seed size for credential {}", cred.id);
-                                continue;
-                            }
-                        };
-                        // Build public blob from OpenSSH public text
-                        let public_blob =
-                            if let Some(blob) = parse_openssh_pub_to_blob(&ssh.public_key) {
-                                blob
-                            } else {
-                                warn!("Invalid OpenSSH public key for credential {}", cred.id);
-                                continue;
-                            };
-                        self.keys.push(AgentKey {
-                            public_blob,
-                            comment: cred.name.clone(),
-                            secret_seed: seed_bytes,
-                            identity_id: id.id,
-                            credential_id: cred.id,
-                        });
+                        match AgentKey::from_ssh_key_data(&ssh, cred.name.clone(), id.id, cred.id) {
+                            Ok(key) => self.push_key(key)?,
+                            Err(e) => warn!("Skipping SSH credential {}: {}", cred.id, e),
+                        }
                     }
                 }
             }
@@ -238,78 +550,214 @@ impl Agent {
         }
         let mut seed = [0u8; 32];
         seed.copy_from_slice(&decoded);
-        let signing = ed25519_dalek::SigningKey::from_bytes(&seed);
-        let pub_bytes = signing.verifying_key().to_bytes();
-        let mut public_blob = Vec::new();
-        write_ssh_string(&mut public_blob, b"ssh-ed25519")
-            .map_err(|e| anyhow!(PersonaError::CryptographicError(e.to_string())))?;
-        write_ssh_string(&mut public_blob, &pub_bytes)
+        let public_blob = ed25519_public_blob(&seed)
             .map_err(|e| anyhow!(PersonaError::CryptographicError(e.to_string())))?;
         let comment = std::env::var("PERSONA_AGENT_TEST_KEY_COMMENT")
             .unwrap_or_else(|_| "Test Key".to_string());
-        self.keys.push(AgentKey {
+        self.push_key(AgentKey {
             public_blob,
             comment,
             secret_seed: seed,
             identity_id: uuid::Uuid::new_v4(),
             credential_id: uuid::Uuid::new_v4(),
-        });
+            require_confirm: false,
+            expires_at: None,
+            certificate_blob: None,
+        })?;
         Ok(true)
     }
 
+    /// Handle SSH_AGENTC_ADD_IDENTITY. Only ed25519 keys are supported.
+    ///
+    /// Adding a key the agent already holds updates its comment, as OpenSSH's agent does.
+    pub async fn add_identity(&self, payload: &[u8]) -> Result<()> {
+        self.add_key(payload, false).await
+    }
+
+    /// Handle SSH_AGENTC_ADD_ID_CONSTRAINED.
+    ///
+    /// The confirm and lifetime constraints are supported. Keys with any other constraint
+    /// (such as destination restrictions) are refused rather than added without it.
+    pub async fn add_constrained_identity(&self, payload: &[u8]) -> Result<()> {
+        self.add_key(payload, true).await
+    }
+
+    async fn add_key(&self, mut payload: &[u8], constrained: bool) -> Result<()> {
+        use byteorder::ReadBytesExt;
+        // payload: string "ssh-ed25519", string ENC(A), string k || ENC(A), string comment
+        let key_type = read_ssh_string(&mut payload)?;
+        if key_type != b"ssh-ed25519" {
+            anyhow::bail!(
+                "unsupported key type {}",
+                String::from_utf8_lossy(&key_type)
+            );
+        }
+        let public = read_ssh_string(&mut payload)?;
+        let secret = read_ssh_string(&mut payload)?;
+        let comment = String::from_utf8_lossy(&read_ssh_string(&mut payload)?).into_owned();
+        // followed, for constrained adds, by constraint bytes and their arguments
+        let mut require_confirm = false;
+        let mut expires_at = None;
+        while constrained && !payload.is_empty() {
+            match payload.read_u8()? {
+                // SSH_AGENT_CONSTRAIN_LIFETIME: uint32 seconds
+                1 => {
+                    let seconds = payload.read_u32::<byteorder::BigEndian>()?;
+                    expires_at = Some(Instant::now() + Duration::from_secs(seconds.into()));
+                }
+                // SSH_AGENT_CONSTRAIN_CONFIRM
+                2 => require_confirm = true,
+                other => anyhow::bail!("unsupported key constraint {}", other),
+            }
+        }
+        if public.len() != 32 || secret.len() != 64 || secret[32..] != public[..] {
+            anyhow::bail!("malformed ed25519 key");
+        }
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(&secret[..32]);
+        let signing = ed25519_dalek::SigningKey::from_bytes(&seed);
+        if signing.verifying_key().to_bytes()[..] != public[..] {
+            anyhow::bail!("ed25519 public key does not match its private key");
+        }
+
+        let mut public_blob = Vec::new();
+        write_ssh_string(&mut public_blob, b"ssh-ed25519")?;
+        write_ssh_string(&mut public_blob, &public)?;
+
+        {
+            let mut keys = self
+                .keys
+                .write()
+                .map_err(|_| anyhow!("Key lock poisoned"))?;
+            if let Some(existing) = keys.iter_mut().find(|k| k.public_blob == public_blob) {
+                existing.comment = comment;
+                existing.require_confirm = require_confirm;
+                existing.expires_at = expires_at;
+                return Ok(());
+            }
+        }
+
+        let (identity_id, credential_id) = if self.persist_added_keys {
+            persist_added_key(&seed, &public_blob, &comment).await?
+        } else {
+            (uuid::Uuid::new_v4(), uuid::Uuid::new_v4())
+        };
+        info!("Added SSH key '{}'", comment);
+
+        let mut keys = self
+            .keys
+            .write()
+            .map_err(|_| anyhow!("Key lock poisoned"))?;
+        // Another connection may have added the same key while this one was saving it
+        if !keys.iter().any(|k| k.public_blob == public_blob) {
+            keys.push(AgentKey {
+                public_blob,
+                comment,
+                secret_seed: seed,
+                identity_id,
+                credential_id,
+                require_confirm,
+                expires_at,
+                certificate_blob: None,
+            });
+        }
+        Ok(())
+    }
+
+    /// Handle SSH_AGENTC_REMOVE_IDENTITY; `false` if the agent does not hold the key.
+    ///
+    /// The key is only dropped from the agent; a copy stored in the vault is left alone.
+    pub fn remove_identity(&self, mut payload: &[u8]) -> Result<bool> {
+        let key_blob = read_ssh_string(&mut payload)?;
+        let mut keys = self
+            .keys
+            .write()
+            .map_err(|_| anyhow!("Key lock poisoned"))?;
+        let before = keys.len();
+        keys.retain(|k| !k.matches_blob(&key_blob));
+        Ok(keys.len() < before)
+    }
+
+    /// Handle SSH_AGENTC_REMOVE_ALL_IDENTITIES
+    pub fn remove_all_identities(&self) -> Result<()> {
+        self.keys
+            .write()
+            .map_err(|_| anyhow!("Key lock poisoned"))?
+            .clear();
+        Ok(())
+    }
+
     fn identities_answer(&self) -> Result<Vec<u8>> {
         use byteorder::{BigEndian, WriteBytesExt};
         // packet: len(4) type(1)=12 count(u32) repeated [string key_blob, string comment]
+        self.prune_expired();
+        let keys = self.keys.read().map_err(|_| anyhow!("Key lock poisoned"))?;
+        // A locked agent answers with no keys
+        let listed: &[AgentKey] = if self.is_locked() { &[] } else { &keys };
         let mut payload = Vec::new();
         payload.push(12u8);
-        payload.write_u32::<BigEndian>(self.keys.len() as u32)?;
-        for k in &self.keys {
-            write_ssh_string(&mut payload, &k.public_blob)?;
+        payload.write_u32::<BigEndian>(listed.len() as u32)?;
+        for k in listed {
+            write_ssh_string(&mut payload, k.advertised_blob())?;
             write_ssh_string(&mut payload, k.comment.as_bytes())?;
         }
         Ok(wrap_packet(payload))
     }
 
-    fn sign_response(&self, mut payload: &[u8]) -> Result<Vec<u8>> {
+    async fn sign_response(&self, mut payload: &[u8]) -> Result<Vec<u8>> {
         use byteorder::{BigEndian, ReadBytesExt};
         // sign_request payload: string key_blob, string data, flags(u32)
         let key_blob = read_ssh_string(&mut payload)?;
         let data_to_sign = read_ssh_string(&mut payload)?;
         let _flags = payload.read_u32::<BigEndian>().unwrap_or(0);
-        // Find key
+        if self.is_locked() {
+            tracing::warn!("Signature refused: agent is locked");
+            return Ok(failure_packet());
+        }
+        // Find key; copy it out so the key list is not locked during prompts
+        self.prune_expired();
         let key = self
             .keys
+            .read()
+            .map_err(|_| anyhow!("Key lock poisoned"))?
             .iter()
-            .find(|k| k.public_blob == key_blob)
-            .ok_or_else(|| anyhow::anyhow!("Key not found"))?;
+            .find(|k| k.matches_blob(&key_blob))
+            .cloned();
+        let Some(key) = key else {
+            // Unknown or expired key: refuse without dropping the connection
+            tracing::warn!("Signature refused: key not found");
+            return Ok(failure_packet());
+        };
 
         // Get target hostname
         let hostname = current_target_host();
 
-        // Policy enforcement using PolicyEnforcer
-        let mut policy_enforcer = self
+        // Policy enforcement using PolicyEnforcer; the lock is not held while asking the user
+        let decision = self
             .policy
             .lock()
-            .map_err(|_| anyhow!("Policy lock poisoned"))?;
-        match policy_enforcer.check_signature(&key.credential_id, hostname.as_deref())? {
+            .map_err(|_| anyhow!("Policy lock poisoned"))?
+            .enforcer
+            .check_signature(&key.credential_id, hostname.as_deref())?;
+        let confirmed = match decision {
             SignatureDecision::Denied { reason } => {
                 tracing::warn!("Signature denied: {}", reason);
+                audit_sign(&key, hostname.as_deref(), SignEvent::Denied(&reason));
                 return Ok(failure_packet());
             }
             SignatureDecision::RequireBiometric { reason } => {
-                drop(policy_enforcer); // Release lock before biometric check
-
                 // Check if biometric is available
-                if !self.biometric_provider.is_available(detect_platform()) {
+                if !self.biometric_provider.is_available(None) {
                     tracing::warn!(
                         "Biometric required but not available, falling back to confirmation"
                     );
                     let prompt = format!(
-                        "Biometric unavailable. Allow SSH signature for '{}'? [y/N] ",
-                        hostname.as_deref().unwrap_or("unknown host")
+                        "Biometric unavailable. {}",
+                        signature_prompt(&key.comment, hostname.as_deref())
                     );
-                    if !prompt_confirm_blocking(&prompt)? {
+                    if !self.confirm(&key, hostname.as_deref(), &prompt).await? {
                         tracing::warn!("Signature denied by user (reason: {})", reason);
+                        audit_sign(&key, hostname.as_deref(), SignEvent::UserRejected(&reason));
                         return Ok(failure_packet());
                     }
                 } else {
@@ -320,55 +768,77 @@ impl Agent {
                             "SSH signature requested for {}",
                             hostname.as_deref().unwrap_or("unknown host")
                         ),
-                        platform: detect_platform(),
+                        platform: None,
                     };
 
                     match self.biometric_provider.authenticate(&prompt) {
                         Ok(result) if result.verified => {
                             tracing::info!("Biometric authentication successful");
                         }
-                        Ok(_) => {
-                            tracing::warn!("Biometric authentication failed");
+                        Ok(result) => {
+                            let reason = result.error.as_deref().unwrap_or("not verified");
+                            tracing::warn!("Biometric authentication failed: {}", reason);
+                            audit_sign(&key, hostname.as_deref(), SignEvent::BiometricFailed(reason));
                             return Ok(failure_packet());
                         }
                         Err(e) => {
                             tracing::error!("Biometric authentication error: {}", e);
+                            audit_sign(
+                                &key,
+                                hostname.as_deref(),
+                                SignEvent::BiometricFailed(&e.to_string()),
+                            );
                             return Ok(failure_packet());
                         }
                     }
                 }
-
-                policy_enforcer = self
-                    .policy
-                    .lock()
-                    .map_err(|_| anyhow!("Policy lock poisoned"))?;
+                true
             }
             SignatureDecision::RequireConfirm { reason } => {
-                drop(policy_enforcer); // Release lock before prompt
-
-                let prompt = if let Some(ref host) = hostname {
-                    format!("Allow SSH signature for host '{}'? [y/N] ", host)
-                } else {
-                    "Allow SSH signature? [y/N] ".to_string()
-                };
-
-                if !prompt_confirm_blocking(&prompt)? {
+                let prompt = signature_prompt(&key.comment, hostname.as_deref());
+                if !self.confirm(&key, hostname.as_deref(), &prompt).await? {
                     tracing::warn!("Signature denied by user (reason: {})", reason);
+                    audit_sign(&key, hostname.as_deref(), SignEvent::UserRejected(&reason));
                     return Ok(failure_packet());
                 }
-
-                policy_enforcer = self
-                    .policy
-                    .lock()
-                    .map_err(|_| anyhow!("Policy lock poisoned"))?;
+                true
             }
-            SignatureDecision::Allowed => {
-                // Proceed with signing
+            SignatureDecision::Allowed => false,
+        };
+
+        // Keys added with `ssh-add -c` are confirmed on every use, whatever the policy decided
+        if key.require_confirm && !confirmed {
+            let prompt = signature_prompt(&key.comment, hostname.as_deref());
+            if !self.confirm(&key, hostname.as_deref(), &prompt).await? {
+                tracing::warn!("Signature denied by user (key requires confirmation)");
+                audit_sign(
+                    &key,
+                    hostname.as_deref(),
+                    SignEvent::UserRejected("key requires confirmation"),
+                );
+                return Ok(failure_packet());
             }
         }
 
+        // The agent may have been locked while the user was being asked
+        let mut policy_enforcer = self
+            .policy
+            .lock()
+            .map_err(|_| anyhow!("Policy lock poisoned"))?;
+        if policy_enforcer.locked {
+            tracing::warn!("Signature refused: agent is locked");
+            audit_sign(
+                &key,
+                hostname.as_deref(),
+                SignEvent::Denied("agent is locked"),
+            );
+            return Ok(failure_packet());
+        }
+
         // Record the signature for tracking
-        policy_enforcer.record_signature(&key.credential_id, hostname.as_deref());
+        policy_enforcer
+            .enforcer
+            .record_signature(&key.credential_id, hostname.as_deref());
         drop(policy_enforcer); // Release lock before signing
 
         // ed25519 sign
@@ -376,10 +846,7 @@ impl Agent {
         let signing = SigningKey::from_bytes(&key.secret_seed);
         let sig: Signature = signing.sign(&data_to_sign);
         // Audit sign operation (best-effort, include SHA256 of signed data)
-        if let Err(e) = audit_sign_with_digest(&key.identity_id, &key.credential_id, &data_to_sign)
-        {
-            tracing::warn!("audit sign failed: {}", e);
-        }
+        audit_sign(&key, hostname.as_deref(), SignEvent::Signed(&data_to_sign));
         // Build signature blob: string algo, string signature (raw) for ed25519
         let mut sig_blob = Vec::new();
         write_ssh_string(&mut sig_blob, b"ssh-ed25519")?;
@@ -390,37 +857,121 @@ impl Agent {
         write_ssh_string(&mut out, &sig_blob)?;
         Ok(wrap_packet(out))
     }
+
+    /// Ask the remote approver when one is configured, otherwise the confirmation prompt. An
+    /// unreachable approver falls back to the prompt; denials and timeouts do not.
+    async fn confirm(&self, key: &AgentKey, hostname: Option<&str>, prompt: &str) -> Result<bool> {
+        let Some(approver) = &self.remote_approver else {
+            return (self.confirm_prompt)(prompt);
+        };
+        let context = ApprovalContext::new("ssh_sign", prompt)
+            .with_credential(key.identity_id, key.credential_id);
+        let outcome = approver.request_approval(&context).await;
+        let mut log = outcome.audit_log(&context);
+        if let Some(host) = hostname {
+            log = log.with_metadata("host".to_string(), host.to_string());
+        }
+        if let Err(e) = record_audit_log(log) {
+            tracing::warn!("audit approval failed: {}", e);
+        }
+
+        match outcome {
+            ApprovalOutcome::Approved => Ok(true),
+            ApprovalOutcome::Unavailable { reason } => {
+                tracing::warn!("Remote approval unavailable ({}), asking locally", reason);
+                (self.confirm_prompt)(prompt)
+            }
+            ApprovalOutcome::Denied { .. } | ApprovalOutcome::TimedOut => Ok(false),
+        }
+    }
+}
+
+/// Webhook approver from `PERSONA_APPROVAL_WEBHOOK`, if set
+fn remote_approver_from_env() -> Option<Arc<dyn RemoteApprover>> {
+    let config = persona_core::ApprovalConfig::from_env()?;
+    #[cfg(feature = "remote-approval")]
+    {
+        match persona_core::WebhookApprover::new(config) {
+            Ok(approver) => Some(Arc::new(approver)),
+            Err(e) => {
+                warn!("Remote approval disabled: {}", e);
+                None
+            }
+        }
+    }
+    #[cfg(not(feature = "remote-approval"))]
+    {
+        warn!(
+            "PERSONA_APPROVAL_WEBHOOK ({}) ignored: built without remote-approval",
+            config.endpoint
+        );
+        None
+    }
+}
+
+/// Outcome of a sign request, as recorded in the audit log
+enum SignEvent<'a> {
+    /// Signed the given data
+    Signed(&'a [u8]),
+    /// Refused by policy, with the reason
+    Denied(&'a str),
+    /// Biometric authentication did not succeed
+    BiometricFailed(&'a str),
+    /// The user declined the confirmation prompt
+    UserRejected(&'a str),
 }
 
-fn audit_sign_with_digest(
-    identity_id: &uuid::Uuid,
-    credential_id: &uuid::Uuid,
-    data: &[u8],
-) -> Result<()> {
-    use persona_core::models::{AuditAction, AuditLog, ResourceType};
+impl SignEvent<'_> {
+    fn audit_log(&self, key: &AgentKey, hostname: Option<&str>) -> persona_core::models::AuditLog {
+        use persona_core::models::{AuditAction, AuditLog, ResourceType};
+        let (action, success) = match self {
+            SignEvent::Signed(_) => ("ssh_sign", true),
+            SignEvent::Denied(_) => ("ssh_sign_denied", false),
+            SignEvent::BiometricFailed(_) => ("ssh_sign_biometric_failed", false),
+            SignEvent::UserRejected(_) => ("ssh_sign_user_rejected", false),
+        };
+        let mut log = AuditLog::new(
+            AuditAction::Custom(action.to_string()),
+            ResourceType::Credential,
+            success,
+        )
+        .with_identity_id(Some(key.identity_id))
+        .with_credential_id(Some(key.credential_id));
+        if let Some(host) = hostname {
+            log = log.with_metadata("host".to_string(), host.to_string());
+        }
+        match self {
+            SignEvent::Signed(data) => {
+                let digest = ring::digest::digest(&ring::digest::SHA256, data);
+                log.with_metadata("data_sha256".to_string(), hex::encode(digest.as_ref()))
+            }
+            SignEvent::Denied(reason)
+            | SignEvent::BiometricFailed(reason)
+            | SignEvent::UserRejected(reason) => {
+                log.with_metadata("reason".to_string(), reason.to_string())
+            }
+        }
+    }
+}
+
+/// Audit the outcome of a sign request (best-effort)
+fn audit_sign(key: &AgentKey, hostname: Option<&str>, event: SignEvent) {
+    if let Err(e) = record_audit_log(event.audit_log(key, hostname)) {
+        tracing::warn!("audit sign failed: {}", e);
+    }
+}
+
+fn record_audit_log(log: persona_core::models::AuditLog) -> Result<()> {
     use persona_core::storage::AuditLogRepository;
-    // Compute SHA256 of data
-    let digest = ring::digest::digest(&ring::digest::SHA256, data);
-    let data_sha256 = hex::encode(digest.as_ref());
     // Determine DB path
     let db_path = resolve_persona_db_path();
 
     // Best-effort background audit: never block the agent request handler, and avoid
     // nested `block_on` when running inside an existing Tokio runtime (tests included).
-    let identity_id = *identity_id;
-    let credential_id = *credential_id;
     let fut = async move {
         let db = persona_core::storage::Database::from_file(&db_path).await?;
         db.migrate().await?;
         let repo = AuditLogRepository::new(db);
-        let log = AuditLog::new(
-            AuditAction::Custom("ssh_sign".to_string()),
-            ResourceType::Credential,
-            true,
-        )
-        .with_identity_id(Some(identity_id))
-        .with_credential_id(Some(credential_id))
-        .with_metadata("data_sha256".to_string(), data_sha256);
         let _ = repo.create(&log).await;
         Ok::<(), anyhow::Error>(())
     };
@@ -440,6 +991,84 @@ fn audit_sign_with_digest(
     Ok(())
 }
 
+/// Save a key added with `ssh-add` to the vault under the active identity (or the first one).
+///
+/// Returns the identity and credential the key was stored as.
+async fn persist_added_key(
+    seed: &[u8; 32],
+    public_blob: &[u8],
+    comment: &str,
+) -> Result<(uuid::Uuid, uuid::Uuid)> {
+    use persona_core::models::{CredentialData, CredentialType, SecurityLevel, SshKeyData};
+    use persona_core::storage::WorkspaceRepository;
+    use persona_core::{Database, PersonaService, SecretString};
+
+    let db = Database::from_file(resolve_persona_db_path()).await?;
+    db.migrate().await?;
+    let mut service = PersonaService::new(db.clone()).await?;
+    if service.has_users().await? {
+        let pass = std::env::var("PERSONA_MASTER_PASSWORD")
+            .map_err(|_| anyhow!("vault is locked and PERSONA_MASTER_PASSWORD not set"))?;
+        if service.authenticate_user(&SecretString::new(pass)).await?
+            != persona_core::auth::authentication::AuthResult::Success
+        {
+            anyhow::bail!("failed to unlock vault");
+        }
+    }
+
+    // A key removed with `ssh-add -d` and added again is already in the vault
+    for identity in service.get_identities().await? {
+        for cred in service.get_credentials_for_identity(&identity.id).await? {
+            if cred.credential_type != CredentialType::SshKey {
+                continue;
+            }
+            if let Some(CredentialData::SshKey(ssh)) = service.get_credential_data(&cred.id).await?
+            {
+                if parse_openssh_public_key(&ssh.public_key)
+                    .is_some_and(|(blob, _)| blob == public_blob)
+                {
+                    return Ok((identity.id, cred.id));
+                }
+            }
+        }
+    }
+
+    let active = WorkspaceRepository::new(db)
+        .find_all()
+        .await?
+        .pop()
+        .and_then(|ws| ws.active_identity_id);
+    let identities = service.get_identities().await?;
+    let identity = identities
+        .iter()
+        .find(|identity| Some(identity.id) == active)
+        .or_else(|| identities.first())
+        .ok_or_else(|| anyhow!("vault has no identity to store the key under"))?;
+
+    let name = if comment.is_empty() {
+        "SSH Key (ssh-add)".to_string()
+    } else {
+        comment.to_string()
+    };
+    let data = SshKeyData {
+        private_key: SecretString::new(BASE64.encode(seed)),
+        public_key: format!("ssh-ed25519 {}", BASE64.encode(public_blob)),
+        key_type: "ed25519".to_string(),
+        passphrase: None,
+    };
+    let cred = service
+        .create_credential(
+            identity.id,
+            name,
+            CredentialType::SshKey,
+            SecurityLevel::High,
+            &CredentialData::SshKey(data),
+        )
+        .await?;
+    info!("Saved SSH key to identity '{}'", identity.name);
+    Ok((identity.id, cred.id))
+}
+
 fn wrap_packet(payload: Vec<u8>) -> Vec<u8> {
     use byteorder::{BigEndian, ByteOrder};
     let len = payload.len() as u32;
@@ -471,16 +1100,50 @@ fn read_ssh_string(buf: &mut &[u8]) -> Result<Vec<u8>> {
     Ok(s.to_vec())
 }
 
-fn parse_openssh_pub_to_blob(s: &str) -> Option<Vec<u8>> {
+/// Key type of OpenSSH ed25519 certificates
+const ED25519_CERT_TYPE: &str = "ssh-ed25519-cert-v01@openssh.com";
+
+/// Bare key blob and, for a certificate, the certificate blob of an OpenSSH public key line
+fn parse_openssh_public_key(s: &str) -> Option<(Vec<u8>, Option<Vec<u8>>)> {
     // "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAI.... [comment]"
     let mut parts = s.split_whitespace();
     let algo = parts.next()?;
-    if algo != "ssh-ed25519" {
-        return None;
+    let decoded = BASE64.decode(parts.next()?).ok()?;
+    match algo {
+        "ssh-ed25519" => Some((decoded, None)),
+        ED25519_CERT_TYPE => {
+            // string type, string nonce, string pk, then serial, principals, validity, ...
+            let mut cert = decoded.as_slice();
+            if read_ssh_string(&mut cert).ok()? != ED25519_CERT_TYPE.as_bytes() {
+                return None;
+            }
+            read_ssh_string(&mut cert).ok()?;
+            let public = read_ssh_string(&mut cert).ok()?;
+            if public.len() != 32 {
+                return None;
+            }
+            let mut public_blob = Vec::new();
+            write_ssh_string(&mut public_blob, b"ssh-ed25519").ok()?;
+            write_ssh_string(&mut public_blob, &public).ok()?;
+            Some((public_blob, Some(decoded)))
+        }
+        _ => None,
     }
-    let b64 = parts.next()?;
-    let decoded = BASE64.decode(b64).ok()?;
-    Some(decoded)
+}
+
+/// `ssh-ed25519` public key blob for an ed25519 seed
+fn ed25519_public_blob(seed: &[u8; 32]) -> Result<Vec<u8>> {
+    let public = ed25519_dalek::SigningKey::from_bytes(seed)
+        .verifying_key()
+        .to_bytes();
+    let mut blob = Vec::new();
+    write_ssh_string(&mut blob, b"ssh-ed25519")?;
+    write_ssh_string(&mut blob, &public)?;
+    Ok(blob)
+}
+
+fn success_packet() -> Vec<u8> {
+    wrap_packet(vec![6u8])
 }
 
 fn failure_packet() -> Vec<u8> {
@@ -491,6 +1154,17 @@ fn failure_packet() -> Vec<u8> {
     out
 }
 
+/// Question asked before signing with `comment`'s key
+fn signature_prompt(comment: &str, host: Option<&str>) -> String {
+    match host {
+        Some(host) => format!(
+            "Allow SSH signature with key '{}' for host '{}'? [y/N] ",
+            comment, host
+        ),
+        None => format!("Allow SSH signature with key '{}'? [y/N] ", comment),
+    }
+}
+
 fn prompt_confirm_blocking(prompt: &str) -> Result<bool> {
     use std::io::{Read, Write};
     // Prefer /dev/tty for interactive consent
@@ -524,55 +1198,15 @@ fn current_target_host() -> Option<String> {
             .and_then(|value| value.split_whitespace().next().map(|s| s.to_string()))
     }
 
-    fn parse_host_from_command(command: &str) -> Option<String> {
-        let mut fallback = None;
-        for raw_token in command.split_whitespace() {
-            let token = raw_token.trim_matches(|c| c == '"' || c == '\'');
-            if token.is_empty()
-                || token.starts_with('-')
-                || token.eq_ignore_ascii_case("ssh")
-                || token.eq_ignore_ascii_case("ssh.exe")
-                || token.starts_with('$')
-            {
-                continue;
-            }
-
-            let candidate = if let Some(idx) = token.rfind('@') {
-                token[idx + 1..].to_string()
-            } else if token.contains('/') || token.contains('=') {
-                continue;
-            } else {
-                token.to_string()
-            };
-
-            let is_hostname = candidate
-                .chars()
-                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'));
-            if !is_hostname {
-                continue;
-            }
-            if candidate.contains('.') || candidate.contains(':') {
-                return Some(candidate);
-            }
-            if fallback.is_none() {
-                fallback = Some(candidate);
-            }
-        }
-        fallback
-    }
-
-    if let Ok(value) = std::env::var("PERSONA_AGENT_TARGET_HOST") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            return Some(trimmed.to_string());
-        }
-    }
-
-    for var in ["PERSONA_AGENT_TARGET_HOST_HINT", "PERSONA_AGENT_SSH_DEST"] {
+    for var in [
+        "PERSONA_AGENT_TARGET_HOST",
+        "PERSONA_AGENT_TARGET_HOST_HINT",
+        "PERSONA_AGENT_SSH_DEST",
+    ] {
         if let Ok(value) = std::env::var(var) {
             let trimmed = value.trim();
             if !trimmed.is_empty() {
-                return Some(trimmed.to_string());
+                return Some(canonical_host(trimmed).unwrap_or_else(|| trimmed.to_string()));
             }
         }
     }
@@ -600,6 +1234,70 @@ fn current_target_host() -> Option<String> {
     None
 }
 
+/// ssh options whose value is the next argument, e.g. `-p 2222`
+const SSH_OPTIONS_WITH_VALUE: &str = "BbcDEeFIiJLlmOoPpQRSWw";
+
+fn parse_host_from_command(command: &str) -> Option<String> {
+    let mut fallback = None;
+    let mut skip_value = false;
+    for raw_token in command.split_whitespace() {
+        let token = raw_token.trim_matches(|c| c == '"' || c == '\'');
+        if std::mem::take(&mut skip_value) {
+            continue;
+        }
+        if let Some(option) = token.strip_prefix('-') {
+            skip_value = option.len() == 1 && SSH_OPTIONS_WITH_VALUE.contains(option);
+            continue;
+        }
+        if token.is_empty()
+            || token.eq_ignore_ascii_case("ssh")
+            || token.eq_ignore_ascii_case("ssh.exe")
+            || token.starts_with('$')
+            || (!token.contains('@') && (token.contains('/') || token.contains('=')))
+        {
+            continue;
+        }
+
+        let Some(candidate) = canonical_host(token) else {
+            continue;
+        };
+        if candidate.contains('.') || candidate.contains(':') {
+            return Some(candidate);
+        }
+        if fallback.is_none() {
+            fallback = Some(candidate);
+        }
+    }
+    fallback
+}
+
+/// Host named by an ssh destination such as `git@github.com`, `host:22` or
+/// `user@[2001:db8::1]:2222`.
+///
+/// Drops the user and port, strips the brackets around an IPv6 literal and lowercases the name.
+/// `None` if what remains is not a plausible host name or address.
+fn canonical_host(destination: &str) -> Option<String> {
+    let host = match destination.rfind('@') {
+        Some(idx) => &destination[idx + 1..],
+        None => destination,
+    };
+    let host = if let Some(bracketed) = host.strip_prefix('[') {
+        // `[address]` or `[address]:port`
+        bracketed.split_once(']')?.0
+    } else if host.matches(':').count() == 1 {
+        // `host:port`, or scp-style `host:path`; more colons mean a bare IPv6 address
+        host.split(':').next()?
+    } else {
+        host
+    };
+
+    let is_host = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'));
+    is_host.then(|| host.to_ascii_lowercase())
+}
+
 pub(crate) fn is_host_in_known_hosts(host: &str) -> bool {
     let custom = std::env::var("PERSONA_KNOWN_HOSTS_FILE").ok();
     let paths = custom
@@ -635,26 +1333,182 @@ fn resolve_persona_db_path() -> PathBuf {
         })
 }
 
-fn detect_platform() -> Option<BiometricPlatform> {
-    #[cfg(target_os = "macos")]
-    {
-        // Try to detect Touch ID vs Face ID
-        // In a real implementation, you'd check hardware capabilities
-        Some(BiometricPlatform::TouchId)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_key() -> AgentKey {
+        AgentKey {
+            public_blob: Vec::new(),
+            comment: "test".to_string(),
+            secret_seed: [7u8; 32],
+            identity_id: uuid::Uuid::new_v4(),
+            credential_id: uuid::Uuid::new_v4(),
+            require_confirm: false,
+            expires_at: None,
+            certificate_blob: None,
+        }
     }
 
-    #[cfg(target_os = "windows")]
-    {
-        Some(BiometricPlatform::WindowsHello)
+    #[test]
+    fn refused_signatures_are_audited_as_failures() {
+        use persona_core::models::AuditAction;
+        let key = test_key();
+        let cases = [
+            (SignEvent::Denied("Rate limit"), "ssh_sign_denied"),
+            (
+                SignEvent::BiometricFailed("not verified"),
+                "ssh_sign_biometric_failed",
+            ),
+            (
+                SignEvent::UserRejected("declined"),
+                "ssh_sign_user_rejected",
+            ),
+        ];
+        for (event, action) in cases {
+            let log = event.audit_log(&key, Some("github.com"));
+            assert_eq!(log.action, AuditAction::Custom(action.to_string()));
+            assert!(!log.success);
+            assert_eq!(log.credential_id, Some(key.credential_id));
+            assert_eq!(
+                log.metadata.get("host").map(String::as_str),
+                Some("github.com")
+            );
+            assert!(log.metadata.contains_key("reason"));
+        }
     }
 
-    #[cfg(target_os = "linux")]
-    {
-        Some(BiometricPlatform::LinuxSecretService)
+    #[test]
+    fn signatures_are_audited_with_a_digest() {
+        let key = test_key();
+        let log = SignEvent::Signed(b"data").audit_log(&key, None);
+        assert!(log.success);
+        assert!(!log.metadata.contains_key("host"));
+        assert_eq!(log.metadata["data_sha256"].len(), 64);
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
-    {
-        None
+    #[test]
+    fn canonical_host_strips_user_port_and_brackets() {
+        assert_eq!(canonical_host("[::1]").as_deref(), Some("::1"));
+        assert_eq!(
+            canonical_host("user@[2001:db8::1]:2222").as_deref(),
+            Some("2001:db8::1")
+        );
+        assert_eq!(
+            canonical_host("git@github.com").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(
+            canonical_host("Example.COM:22").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            canonical_host("2001:db8::1").as_deref(),
+            Some("2001:db8::1")
+        );
+        assert_eq!(
+            canonical_host("git@github.com:org/repo.git").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(canonical_host("user@"), None);
+        assert_eq!(canonical_host("[2001:db8::1"), None);
+    }
+
+    #[test]
+    fn host_is_parsed_from_ssh_commands() {
+        assert_eq!(
+            parse_host_from_command("ssh -p 2222 deploy@[2001:db8::1]:2222 uptime").as_deref(),
+            Some("2001:db8::1")
+        );
+        assert_eq!(
+            parse_host_from_command("ssh -i ~/.ssh/id_ed25519 -l admin bastion").as_deref(),
+            Some("bastion")
+        );
+        assert_eq!(
+            parse_host_from_command("ssh -o StrictHostKeyChecking=no git@github.com").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(parse_host_from_command("ssh -v"), None);
+    }
+
+    /// Vault data for an ed25519 key whose public line is a certificate for `cert_seed`'s key
+    fn cert_key_data(seed: &[u8; 32], cert_seed: &[u8; 32]) -> persona_core::models::SshKeyData {
+        let public = ed25519_dalek::SigningKey::from_bytes(cert_seed)
+            .verifying_key()
+            .to_bytes();
+        let mut cert = Vec::new();
+        write_ssh_string(&mut cert, ED25519_CERT_TYPE.as_bytes()).unwrap();
+        write_ssh_string(&mut cert, &[0x5a; 32]).unwrap();
+        write_ssh_string(&mut cert, &public).unwrap();
+        // Serial, principals, validity and CA signature are opaque to the agent
+        cert.extend_from_slice(&[0u8; 16]);
+        persona_core::models::SshKeyData {
+            private_key: persona_core::SecretString::new(BASE64.encode(seed)),
+            public_key: format!("{} {} deploy@ci", ED25519_CERT_TYPE, BASE64.encode(&cert)),
+            key_type: "ed25519".to_string(),
+            passphrase: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn certificate_key_is_listed_and_signs_by_certificate_blob() {
+        use byteorder::{BigEndian, ReadBytesExt};
+        use ed25519_dalek::Verifier;
+
+        let seed = [9u8; 32];
+        let key = AgentKey::from_ssh_key_data(
+            &cert_key_data(&seed, &seed),
+            "deploy".to_string(),
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+        )
+        .unwrap();
+        let cert_blob = key.certificate_blob.clone().unwrap();
+        assert_eq!(key.public_blob, ed25519_public_blob(&seed).unwrap());
+
+        let agent = Agent::with_policy(PolicyEnforcer::new(policy::SigningPolicy::default()));
+        agent.push_key(key).unwrap();
+
+        let answer = agent.identities_answer().unwrap();
+        let mut body = &answer[5..];
+        assert_eq!(answer[4], 12);
+        assert_eq!(body.read_u32::<BigEndian>().unwrap(), 1);
+        let listed = read_ssh_string(&mut body).unwrap();
+        assert_eq!(listed, cert_blob);
+        assert_eq!(
+            read_ssh_string(&mut listed.as_slice()).unwrap(),
+            ED25519_CERT_TYPE.as_bytes()
+        );
+
+        let mut request = Vec::new();
+        write_ssh_string(&mut request, &cert_blob).unwrap();
+        write_ssh_string(&mut request, b"challenge").unwrap();
+        request.extend_from_slice(&[0, 0, 0, 0]);
+        let response = agent.sign_response(&request).await.unwrap();
+        assert_eq!(response[4], 14);
+        let mut signature_blob = &read_ssh_string(&mut &response[5..]).unwrap()[..];
+        assert_eq!(
+            read_ssh_string(&mut signature_blob).unwrap(),
+            b"ssh-ed25519"
+        );
+        let signature = read_ssh_string(&mut signature_blob).unwrap();
+        ed25519_dalek::SigningKey::from_bytes(&seed)
+            .verifying_key()
+            .verify(
+                b"challenge",
+                &ed25519_dalek::Signature::from_slice(&signature).unwrap(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn certificate_for_another_key_is_rejected() {
+        let result = AgentKey::from_ssh_key_data(
+            &cert_key_data(&[9u8; 32], &[10u8; 32]),
+            "deploy".to_string(),
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+        );
+        assert!(result.is_err());
     }
 }
@@ -2,5 +2,19 @@ use persona_ssh_agent::run_agent;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    run_agent().await
+    match std::env::args().nth(1).as_deref() {
+        Some("--version" | "-V") => {
+            println!("persona-ssh-agent {}", persona_core::LONG_VERSION);
+            Ok(())
+        }
+        // Machine-readable variant of --version
+        Some("--build-info") => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&persona_core::build_info())?
+            );
+            Ok(())
+        }
+        _ => run_agent().await,
+    }
 }
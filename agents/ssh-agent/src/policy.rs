@@ -7,17 +7,21 @@
 //! - Usage counting and rate limiting
 
 use crate::is_host_in_known_hosts;
-use anyhow::Result;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use chrono::NaiveTime;
+use persona_core::PersonaError;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 /// Policy configuration for SSH key usage
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct SigningPolicy {
     /// Global settings
+    #[serde(default)]
     pub global: GlobalPolicy,
 
     /// Per-key policies (key: credential_id)
@@ -31,6 +35,7 @@ pub struct SigningPolicy {
 
 /// Global agent policy settings
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct GlobalPolicy {
     /// Require user confirmation for every signature
     #[serde(default)]
@@ -55,10 +60,54 @@ pub struct GlobalPolicy {
     /// Deny all signatures (emergency lockdown)
     #[serde(default)]
     pub deny_all: bool,
+
+    /// Hostnames any key may sign for (glob patterns, empty = all allowed)
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+
+    /// Hostnames no key may sign for (glob patterns, takes precedence over allowed)
+    #[serde(default)]
+    pub denied_hosts: Vec<String>,
+
+    /// Hours any key may be used (24h format, e.g., "09:00-18:00")
+    #[serde(default, deserialize_with = "time_range")]
+    pub allow_hours: Option<String>,
+
+    /// Signatures allowed per key within a sliding window (applies to each key separately)
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// At most `max_signatures` signatures within any `window_secs` seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimit {
+    /// Signatures allowed per window (0 = unlimited)
+    pub max_signatures: u32,
+
+    /// Window length in seconds
+    pub window_secs: u64,
+}
+
+impl RateLimit {
+    /// Parse the `PERSONA_AGENT_RATE_LIMIT` form `<signatures>/<seconds>`, e.g. "10/60"
+    pub fn parse(value: &str) -> Option<Self> {
+        let (max_signatures, window_secs) = value.split_once('/')?;
+        let limit = Self {
+            max_signatures: max_signatures.trim().parse().ok()?,
+            window_secs: window_secs.trim().parse().ok()?,
+        };
+        (limit.window_secs > 0).then_some(limit)
+    }
+
+    fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs)
+    }
 }
 
 /// Per-key policy settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct KeyPolicy {
     /// Allow this key to be used
     #[serde(default = "default_true")]
@@ -85,14 +134,43 @@ pub struct KeyPolicy {
     pub max_uses_per_day: u32,
 
     /// Allowed time range (24h format, e.g., "09:00-17:00")
-    #[serde(default)]
+    #[serde(default, alias = "allow_hours", deserialize_with = "time_range")]
     pub allowed_time_range: Option<String>,
+
+    /// Signatures allowed within a sliding window (overrides the global rate limit)
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 fn default_true() -> bool {
     true
 }
 
+/// Reject malformed time ranges while parsing, so the error points at the offending line
+fn time_range<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<String>, D::Error> {
+    let range = Option::<String>::deserialize(deserializer)?;
+    if let Some(range) = &range {
+        if parse_time_range(range).is_none() {
+            return Err(serde::de::Error::custom(format!(
+                "invalid time range '{}' (expected HH:MM-HH:MM)",
+                range
+            )));
+        }
+    }
+    Ok(range)
+}
+
+/// Parse a range like "09:00-17:00"
+fn parse_time_range(range: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = range.split_once('-')?;
+    Some((
+        NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?,
+        NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?,
+    ))
+}
+
 impl Default for KeyPolicy {
     fn default() -> Self {
         Self {
@@ -103,12 +181,14 @@ impl Default for KeyPolicy {
             require_biometric: false,
             max_uses_per_day: 0,
             allowed_time_range: None,
+            rate_limit: None,
         }
     }
 }
 
 /// Per-host policy settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct HostPolicy {
     /// Allow connections to this host
     #[serde(default = "default_true")]
@@ -157,6 +237,8 @@ struct KeyUsageState {
     daily_count: u32,
     last_reset: Instant,
     total_count: u64,
+    /// Signatures within the current rate-limit window, oldest first
+    recent: VecDeque<Instant>,
 }
 
 #[derive(Debug)]
@@ -175,30 +257,69 @@ impl PolicyEnforcer {
         }
     }
 
-    /// Load policy from file
-    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
-        let policy: SigningPolicy = toml::from_str(&content)?;
+    /// Load and validate a policy file.
+    ///
+    /// A file that does not parse fails with `PersonaError::ConfigurationError` naming the
+    /// offending line.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read policy file {}", path.display()))?;
+        let policy: SigningPolicy = toml::from_str(&content).map_err(|e| {
+            let location = e
+                .span()
+                .map(|span| {
+                    let line = content[..span.start].matches('\n').count() + 1;
+                    let text = content.lines().nth(line - 1).unwrap_or_default().trim();
+                    format!(" at line {} (`{}`)", line, text)
+                })
+                .unwrap_or_default();
+            PersonaError::ConfigurationError(format!(
+                "Invalid agent policy {}{}: {}",
+                path.display(),
+                location,
+                e.message()
+            ))
+        })?;
         Ok(Self::new(policy))
     }
 
-    /// Load policy from environment or use defaults
-    pub fn from_env() -> Self {
-        let policy_path = std::env::var("PERSONA_AGENT_POLICY_FILE")
-            .ok()
+    /// Load the agent's policy at startup.
+    ///
+    /// Reads `PERSONA_AGENT_POLICY_FILE` if set, else `~/.persona/agent-policy.toml` if it
+    /// exists, else builds the policy from environment variables. A policy file that cannot be
+    /// read or parsed is an error rather than being skipped.
+    pub fn load() -> Result<Self> {
+        let policy_path = std::env::var_os("PERSONA_AGENT_POLICY_FILE")
+            .filter(|path| !path.is_empty())
             .map(PathBuf::from)
-            .or_else(|| dirs::home_dir().map(|h| h.join(".persona").join("agent-policy.toml")));
+            .or_else(|| {
+                dirs::home_dir()
+                    .map(|h| h.join(".persona").join("agent-policy.toml"))
+                    .filter(|path| path.exists())
+            });
 
-        if let Some(path) = policy_path {
-            if path.exists() {
-                if let Ok(enforcer) = Self::from_file(&path) {
-                    tracing::info!("Loaded policy from {}", path.display());
-                    return enforcer;
-                }
+        match policy_path {
+            Some(path) => {
+                let enforcer = Self::from_file(&path)?;
+                tracing::info!("Loaded policy from {}", path.display());
+                Ok(enforcer)
             }
+            None => Ok(Self::new(Self::env_policy())),
         }
+    }
+
+    /// Like [`PolicyEnforcer::load`], but falls back to environment variables if the policy
+    /// file is unusable
+    pub fn from_env() -> Self {
+        Self::load().unwrap_or_else(|e| {
+            tracing::warn!("Ignoring agent policy file: {:#}", e);
+            Self::new(Self::env_policy())
+        })
+    }
 
-        // Build from environment variables (backward compatibility)
+    /// Policy built from environment variables (backward compatibility)
+    fn env_policy() -> SigningPolicy {
         let mut policy = SigningPolicy::default();
 
         policy.global.require_confirm = std::env::var("PERSONA_AGENT_REQUIRE_CONFIRM")
@@ -218,7 +339,17 @@ impl PolicyEnforcer {
             .map(|v| v == "1" || v.to_lowercase() == "true")
             .unwrap_or(false);
 
-        Self::new(policy)
+        if let Ok(value) = std::env::var("PERSONA_AGENT_RATE_LIMIT") {
+            policy.global.rate_limit = RateLimit::parse(&value);
+            if policy.global.rate_limit.is_none() {
+                tracing::warn!(
+                    "Ignoring PERSONA_AGENT_RATE_LIMIT={:?}; expected <signatures>/<seconds>",
+                    value
+                );
+            }
+        }
+
+        policy
     }
 
     /// Check if a signature request should be allowed
@@ -240,6 +371,31 @@ impl PolicyEnforcer {
             });
         }
 
+        // Global host restrictions
+        if let Some(hostname) = hostname {
+            if self.matches_any_pattern(hostname, &self.policy.global.denied_hosts) {
+                return Ok(SignatureDecision::Denied {
+                    reason: format!("Host '{}' is denied by policy", hostname),
+                });
+            }
+
+            if !self.policy.global.allowed_hosts.is_empty()
+                && !self.matches_any_pattern(hostname, &self.policy.global.allowed_hosts)
+            {
+                return Ok(SignatureDecision::Denied {
+                    reason: format!("Host '{}' is not in the allowed hosts", hostname),
+                });
+            }
+        }
+
+        if let Some(ref time_range) = self.policy.global.allow_hours {
+            if !self.is_within_time_range(time_range) {
+                return Ok(SignatureDecision::Denied {
+                    reason: format!("Signing not allowed at this time (allowed: {})", time_range),
+                });
+            }
+        }
+
         if self.policy.global.enforce_known_hosts {
             match (hostname, is_known_host) {
                 (Some(_), true) => {}
@@ -298,8 +454,25 @@ impl PolicyEnforcer {
             }
         }
 
-        // Check per-key policy
+        // Per-key sliding window rate limit
         let key_id = credential_id.to_string();
+        if let Some(limit) = self.rate_limit_for(&key_id) {
+            let usage = self.state.key_usage.entry(*credential_id).or_default();
+            usage.prune_recent(limit.window());
+
+            if usage.recent.len() >= limit.max_signatures as usize {
+                return Ok(SignatureDecision::Denied {
+                    reason: format!(
+                        "Rate limit exceeded for key: {} signatures in the last {}s (limit {})",
+                        usage.recent.len(),
+                        limit.window_secs,
+                        limit.max_signatures
+                    ),
+                });
+            }
+        }
+
+        // Check per-key policy
         if let Some(key_policy) = self.policy.key_policies.get(&key_id) {
             if !key_policy.enabled {
                 return Ok(SignatureDecision::Denied {
@@ -453,9 +626,14 @@ impl PolicyEnforcer {
         self.state.signature_timestamps.push(Instant::now());
 
         // Update key usage
+        let rate_limit = self.rate_limit_for(&credential_id.to_string());
         let usage = self.state.key_usage.entry(*credential_id).or_default();
         usage.daily_count += 1;
         usage.total_count += 1;
+        if let Some(limit) = rate_limit {
+            usage.recent.push_back(Instant::now());
+            usage.prune_recent(limit.window());
+        }
 
         // Update host usage
         if let Some(hostname) = hostname {
@@ -469,6 +647,16 @@ impl PolicyEnforcer {
         }
     }
 
+    /// Rate limit for a key: its own, else the global one (`None` when unlimited)
+    fn rate_limit_for(&self, key_id: &str) -> Option<RateLimit> {
+        self.policy
+            .key_policies
+            .get(key_id)
+            .and_then(|p| p.rate_limit)
+            .or(self.policy.global.rate_limit)
+            .filter(|limit| limit.max_signatures > 0)
+    }
+
     fn cleanup_old_timestamps(&mut self) {
         let one_hour_ago = Instant::now() - Duration::from_secs(3600);
         self.state
@@ -499,28 +687,17 @@ impl PolicyEnforcer {
     }
 
     fn is_within_time_range(&self, time_range: &str) -> bool {
-        // Parse time range like "09:00-17:00"
-        let parts: Vec<&str> = time_range.split('-').collect();
-        if parts.len() != 2 {
+        let Some((start, end)) = parse_time_range(time_range) else {
             return true; // Invalid format, allow by default
-        }
-
+        };
         let now = chrono::Local::now().time();
 
-        let start_time = chrono::NaiveTime::parse_from_str(parts[0], "%H:%M").ok();
-        let end_time = chrono::NaiveTime::parse_from_str(parts[1], "%H:%M").ok();
-
-        match (start_time, end_time) {
-            (Some(start), Some(end)) => {
-                if start <= end {
-                    // Normal range: 09:00-17:00
-                    now >= start && now <= end
-                } else {
-                    // Overnight range: 22:00-06:00
-                    now >= start || now <= end
-                }
-            }
-            _ => true, // Invalid times, allow by default
+        if start <= end {
+            // Normal range: 09:00-17:00
+            now >= start && now <= end
+        } else {
+            // Overnight range: 22:00-06:00
+            now >= start || now <= end
         }
     }
 }
@@ -534,6 +711,16 @@ impl KeyUsageState {
             self.last_reset = now;
         }
     }
+
+    fn prune_recent(&mut self, window: Duration) {
+        while self
+            .recent
+            .front()
+            .is_some_and(|signed| signed.elapsed() >= window)
+        {
+            self.recent.pop_front();
+        }
+    }
 }
 
 impl HostUsageState {
@@ -553,6 +740,7 @@ impl Default for KeyUsageState {
             daily_count: 0,
             last_reset: Instant::now(),
             total_count: 0,
+            recent: VecDeque::new(),
         }
     }
 }
@@ -675,4 +863,172 @@ mod tests {
             .unwrap();
         assert!(matches!(decision, SignatureDecision::Denied { .. }));
     }
+
+    #[test]
+    fn test_policy_file_sections() {
+        let cred_id = Uuid::new_v4();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent-policy.toml");
+        std::fs::write(
+            &path,
+            format!(
+                r#"
+[global]
+allowed_hosts = ["*.github.com", "gitlab.com"]
+denied_hosts = ["gist.github.com"]
+allow_hours = "00:00-23:59"
+
+[key_policies."{}"]
+require_biometric = true
+"#,
+                cred_id
+            ),
+        )
+        .unwrap();
+
+        let mut enforcer = PolicyEnforcer::from_file(&path).unwrap();
+        let other_key = Uuid::new_v4();
+        assert!(matches!(
+            enforcer
+                .check_signature(&other_key, Some("api.github.com"))
+                .unwrap(),
+            SignatureDecision::Allowed
+        ));
+        assert!(matches!(
+            enforcer
+                .check_signature(&other_key, Some("gist.github.com"))
+                .unwrap(),
+            SignatureDecision::Denied { .. }
+        ));
+        assert!(matches!(
+            enforcer
+                .check_signature(&other_key, Some("evil.com"))
+                .unwrap(),
+            SignatureDecision::Denied { .. }
+        ));
+        assert!(matches!(
+            enforcer
+                .check_signature(&cred_id, Some("gitlab.com"))
+                .unwrap(),
+            SignatureDecision::RequireBiometric { .. }
+        ));
+    }
+
+    #[test]
+    fn test_global_allow_hours() {
+        // A one-hour window starting two hours from now never contains the current time
+        let start = chrono::Local::now().time() + chrono::Duration::hours(2);
+        let end = start + chrono::Duration::hours(1);
+        let mut policy = SigningPolicy::default();
+        policy.global.allow_hours =
+            Some(format!("{}-{}", start.format("%H:%M"), end.format("%H:%M")));
+
+        let mut enforcer = PolicyEnforcer::new(policy);
+        let decision = enforcer.check_signature(&Uuid::new_v4(), None).unwrap();
+        assert!(matches!(decision, SignatureDecision::Denied { .. }));
+    }
+
+    #[test]
+    fn test_invalid_policy_file_names_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent-policy.toml");
+
+        std::fs::write(
+            &path,
+            "[global]\nrequire_confirm = true\nallow_hours = \"9am-6pm\"\n",
+        )
+        .unwrap();
+        let err = PolicyEnforcer::from_file(&path).err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<PersonaError>(),
+            Some(PersonaError::ConfigurationError(_))
+        ));
+        let message = err.to_string();
+        assert!(message.contains("line 3"), "{}", message);
+        assert!(message.contains("9am-6pm"), "{}", message);
+
+        std::fs::write(&path, "[global]\nrequire_confrim = true\n").unwrap();
+        let message = PolicyEnforcer::from_file(&path).err().unwrap().to_string();
+        assert!(message.contains("line 2"), "{}", message);
+        assert!(message.contains("require_confrim"), "{}", message);
+    }
+
+    #[test]
+    fn test_per_key_rate_limit() {
+        let mut policy = SigningPolicy::default();
+        policy.global.rate_limit = Some(RateLimit {
+            max_signatures: 2,
+            window_secs: 60,
+        });
+        let busy_key = Uuid::new_v4();
+        let strict_key = Uuid::new_v4();
+        policy.key_policies.insert(
+            strict_key.to_string(),
+            KeyPolicy {
+                rate_limit: Some(RateLimit {
+                    max_signatures: 1,
+                    window_secs: 60,
+                }),
+                ..KeyPolicy::default()
+            },
+        );
+
+        let mut enforcer = PolicyEnforcer::new(policy);
+        for _ in 0..2 {
+            let decision = enforcer.check_signature(&busy_key, None).unwrap();
+            assert!(matches!(decision, SignatureDecision::Allowed));
+            enforcer.record_signature(&busy_key, None);
+        }
+        match enforcer.check_signature(&busy_key, None).unwrap() {
+            SignatureDecision::Denied { reason } => {
+                assert!(
+                    reason.contains("2 signatures in the last 60s"),
+                    "{}",
+                    reason
+                )
+            }
+            other => panic!("expected denial, got {:?}", other),
+        }
+
+        // Limits are counted per key, and a key's own limit overrides the global one
+        let decision = enforcer.check_signature(&strict_key, None).unwrap();
+        assert!(matches!(decision, SignatureDecision::Allowed));
+        enforcer.record_signature(&strict_key, None);
+        let decision = enforcer.check_signature(&strict_key, None).unwrap();
+        assert!(matches!(decision, SignatureDecision::Denied { .. }));
+    }
+
+    #[test]
+    fn test_rate_limit_window_expires() {
+        let mut policy = SigningPolicy::default();
+        policy.global.rate_limit = RateLimit::parse("1/1");
+        let cred_id = Uuid::new_v4();
+
+        let mut enforcer = PolicyEnforcer::new(policy);
+        enforcer.record_signature(&cred_id, None);
+        let decision = enforcer.check_signature(&cred_id, None).unwrap();
+        assert!(matches!(decision, SignatureDecision::Denied { .. }));
+
+        std::thread::sleep(Duration::from_millis(1100));
+        let decision = enforcer.check_signature(&cred_id, None).unwrap();
+        assert!(matches!(decision, SignatureDecision::Allowed));
+    }
+
+    #[test]
+    fn test_rate_limit_parse() {
+        assert_eq!(
+            RateLimit::parse("10/60"),
+            Some(RateLimit {
+                max_signatures: 10,
+                window_secs: 60
+            })
+        );
+        assert_eq!(RateLimit::parse("10"), None);
+        assert_eq!(RateLimit::parse("10/0"), None);
+        assert_eq!(RateLimit::parse("ten/60"), None);
+
+        let policy: SigningPolicy =
+            toml::from_str("[global.rate_limit]\nmax_signatures = 10\nwindow_secs = 60\n").unwrap();
+        assert_eq!(policy.global.rate_limit, RateLimit::parse("10/60"));
+    }
 }
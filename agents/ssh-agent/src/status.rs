@@ -0,0 +1,131 @@
+//! Runtime status of a running agent, read from its state files.
+//!
+//! `run_agent` records its socket path and PID in the state directory. Front ends (CLI,
+//! desktop) read those files and, on Unix, ask the agent over its socket how many keys it holds.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Socket (or named pipe) path file in the state directory
+pub const SOCKET_FILE: &str = "ssh-agent.sock";
+
+/// PID file in the state directory
+pub const PID_FILE: &str = "ssh-agent.pid";
+
+/// What can be observed about the agent without talking to the vault
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshAgentStatus {
+    pub running: bool,
+    pub socket_path: Option<String>,
+    pub pid: Option<u32>,
+    /// Keys the agent offers; `None` if it could not be asked
+    pub key_count: Option<usize>,
+    pub state_dir: String,
+}
+
+/// Directory holding the agent's state files (`PERSONA_AGENT_STATE_DIR`, else `~/.persona`)
+pub fn agent_state_dir() -> PathBuf {
+    std::env::var("PERSONA_AGENT_STATE_DIR")
+        .ok()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(".persona")
+        })
+}
+
+/// Read the state files and, if the socket is reachable, the agent's key count.
+///
+/// `running_hint` lets a caller that started the agent itself report it as running before the
+/// state files appear.
+pub fn read_agent_status(running_hint: bool) -> SshAgentStatus {
+    let dir = agent_state_dir();
+    let read_trimmed = |name: &str| {
+        std::fs::read_to_string(dir.join(name))
+            .ok()
+            .map(|contents| contents.trim().to_string())
+            .filter(|contents| !contents.is_empty())
+    };
+    let socket_path = read_trimmed(SOCKET_FILE);
+    let pid = read_trimmed(PID_FILE).and_then(|pid| pid.parse::<u32>().ok());
+    let key_count = socket_path
+        .as_deref()
+        .and_then(|sock| query_agent_key_count(sock).ok());
+
+    SshAgentStatus {
+        running: running_hint || socket_path.is_some() || pid.is_some(),
+        socket_path,
+        pid,
+        key_count,
+        state_dir: dir.to_string_lossy().to_string(),
+    }
+}
+
+/// Ask the agent at `sock_path` how many identities it offers
+#[cfg(unix)]
+pub fn query_agent_key_count(sock_path: &str) -> Result<usize> {
+    use byteorder::{BigEndian, ByteOrder};
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(sock_path)
+        .with_context(|| format!("Failed to connect to agent at {}", sock_path))?;
+    // SSH_AGENTC_REQUEST_IDENTITIES: len=1, type 11
+    let mut pkt = vec![0u8; 5];
+    BigEndian::write_u32(&mut pkt[0..4], 1);
+    pkt[4] = 11;
+    stream.write_all(&pkt)?;
+    let mut len_buf = [0u8; 4];
+    stream.read_exact(&mut len_buf)?;
+    let resp_len = BigEndian::read_u32(&len_buf) as usize;
+    let mut resp = vec![0u8; resp_len];
+    stream.read_exact(&mut resp)?;
+    if resp.is_empty() || resp[0] != 12 {
+        bail!("Unexpected agent response");
+    }
+    if resp.len() < 5 {
+        bail!("Malformed agent response");
+    }
+    Ok(BigEndian::read_u32(&resp[1..5]) as usize)
+}
+
+/// Named pipes are not queried; status falls back to the state files
+#[cfg(not(unix))]
+pub fn query_agent_key_count(_sock_path: &str) -> Result<usize> {
+    bail!("Agent key count not supported on this platform")
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixListener;
+
+    #[test]
+    fn test_key_count_from_identities_answer() {
+        let dir = tempfile::tempdir().unwrap();
+        let sock = dir.path().join("agent.sock");
+        let listener = UnixListener::bind(&sock).unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 5];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!(request, [0, 0, 0, 1, 11]);
+            // SSH_AGENT_IDENTITIES_ANSWER with a count of 3 (keys omitted)
+            stream.write_all(&[0, 0, 0, 5, 12, 0, 0, 0, 3]).unwrap();
+        });
+
+        let count = query_agent_key_count(sock.to_str().unwrap()).unwrap();
+        assert_eq!(count, 3);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_unreachable_socket_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let sock = dir.path().join("missing.sock");
+        assert!(query_agent_key_count(sock.to_str().unwrap()).is_err());
+    }
+}
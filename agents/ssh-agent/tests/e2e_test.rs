@@ -315,7 +315,12 @@ mod unix_e2e {
         io::{Cursor, Read, Write},
         os::unix::net::UnixStream as StdUnixStream,
         path::PathBuf,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
         thread,
+        time::Duration,
     };
     use tokio::{net::UnixStream, runtime::Runtime};
 
@@ -377,6 +382,335 @@ mod unix_e2e {
         env::remove_var("PERSONA_AGENT_TEST_KEY_COMMENT");
     }
 
+    #[test]
+    fn test_ssh_add_and_remove_are_shared_across_connections() {
+        let seed = [0x17u8; 32];
+        let signing = SigningKey::from_bytes(&seed);
+        let verifying_bytes = signing.verifying_key().to_bytes();
+        let expected_blob = encode_ssh_ed25519_public(&verifying_bytes);
+
+        let mut agent = Agent::new();
+        agent.set_persist_added_keys(false);
+        let (mut first, first_thread) = spawn_connection(&agent);
+        let (mut second, second_thread) = spawn_connection(&agent);
+
+        // ssh-add over one connection
+        let mut add = vec![17u8];
+        write_ssh_string_bytes(&mut add, b"ssh-ed25519");
+        write_ssh_string_bytes(&mut add, &verifying_bytes);
+        write_ssh_string_bytes(&mut add, &[&seed[..], &verifying_bytes[..]].concat());
+        write_ssh_string_bytes(&mut add, b"added@laptop");
+        assert_eq!(send_message(&mut first, &add), vec![6u8]);
+
+        // is visible to, and usable from, another
+        let (key_blob, comment) = request_agent_identities(&mut second);
+        assert_eq!(key_blob, expected_blob);
+        assert_eq!(comment, "added@laptop");
+        let payload = b"signed with an ssh-add key";
+        let signature = request_signature(&mut second, &key_blob, payload);
+        verify_signature(&signature, &verifying_bytes, payload);
+
+        // A key whose public half does not match is refused
+        let mut bad = vec![17u8];
+        write_ssh_string_bytes(&mut bad, b"ssh-ed25519");
+        write_ssh_string_bytes(&mut bad, &[0x01u8; 32]);
+        write_ssh_string_bytes(&mut bad, &[&seed[..], &[0x01u8; 32][..]].concat());
+        write_ssh_string_bytes(&mut bad, b"bogus");
+        assert_eq!(send_message(&mut first, &bad), vec![5u8]);
+
+        let mut remove = vec![18u8];
+        write_ssh_string_bytes(&mut remove, &expected_blob);
+        assert_eq!(send_message(&mut second, &remove), vec![6u8]);
+        assert_eq!(agent.key_count(), 0);
+        // Removing a key the agent does not hold fails
+        assert_eq!(send_message(&mut first, &remove), vec![5u8]);
+
+        assert_eq!(send_message(&mut first, &add), vec![6u8]);
+        assert_eq!(agent.key_count(), 1);
+        assert_eq!(send_message(&mut second, &[19u8]), vec![6u8]);
+        assert_eq!(agent.key_count(), 0);
+
+        drop(first);
+        drop(second);
+        first_thread.join().expect("first connection finished");
+        second_thread.join().expect("second connection finished");
+    }
+
+    #[test]
+    fn test_locked_agent_hides_keys_until_unlocked() {
+        let seed = [0x23u8; 32];
+        let verifying_bytes = SigningKey::from_bytes(&seed).verifying_key().to_bytes();
+        let key_blob = encode_ssh_ed25519_public(&verifying_bytes);
+
+        let mut agent = Agent::new();
+        agent.set_persist_added_keys(false);
+        let (mut first, first_thread) = spawn_connection(&agent);
+        let (mut second, second_thread) = spawn_connection(&agent);
+
+        let mut add = vec![17u8];
+        write_ssh_string_bytes(&mut add, b"ssh-ed25519");
+        write_ssh_string_bytes(&mut add, &verifying_bytes);
+        write_ssh_string_bytes(&mut add, &[&seed[..], &verifying_bytes[..]].concat());
+        write_ssh_string_bytes(&mut add, b"locked@laptop");
+        assert_eq!(send_message(&mut first, &add), vec![6u8]);
+
+        let lock = passphrase_message(22, b"hunter2");
+        assert_eq!(send_message(&mut first, &lock), vec![6u8]);
+        assert!(agent.is_locked());
+        // Locking twice fails, as with OpenSSH's agent
+        assert_eq!(send_message(&mut second, &lock), vec![5u8]);
+
+        // Another connection sees no keys and cannot sign or change keys
+        assert_eq!(send_message(&mut second, &[11u8]), vec![12u8, 0, 0, 0, 0]);
+        let mut sign = vec![13u8];
+        write_ssh_string_bytes(&mut sign, &key_blob);
+        write_ssh_string_bytes(&mut sign, b"data");
+        sign.write_u32::<BigEndian>(0).expect("flags");
+        assert_eq!(send_message(&mut second, &sign), vec![5u8]);
+        assert_eq!(send_message(&mut second, &[19u8]), vec![5u8]);
+
+        assert_eq!(
+            send_message(&mut second, &passphrase_message(23, b"wrong")),
+            vec![5u8]
+        );
+        assert!(agent.is_locked());
+        assert_eq!(
+            send_message(&mut second, &passphrase_message(23, b"hunter2")),
+            vec![6u8]
+        );
+        assert!(!agent.is_locked());
+        // Unlocking an unlocked agent fails
+        assert_eq!(
+            send_message(&mut first, &passphrase_message(23, b"hunter2")),
+            vec![5u8]
+        );
+
+        let (listed_blob, comment) = request_agent_identities(&mut first);
+        assert_eq!(listed_blob, key_blob);
+        assert_eq!(comment, "locked@laptop");
+        let signature = request_signature(&mut second, &key_blob, b"data");
+        verify_signature(&signature, &verifying_bytes, b"data");
+
+        drop(first);
+        drop(second);
+        first_thread.join().expect("first connection finished");
+        second_thread.join().expect("second connection finished");
+    }
+
+    #[test]
+    fn test_confirm_constrained_key_prompts_on_every_signature() {
+        let seed = [0x31u8; 32];
+        let verifying_bytes = SigningKey::from_bytes(&seed).verifying_key().to_bytes();
+        let key_blob = encode_ssh_ed25519_public(&verifying_bytes);
+
+        let prompts = Arc::new(Mutex::new(Vec::<String>::new()));
+        let allow = Arc::new(AtomicBool::new(false));
+        let mut agent = Agent::new();
+        agent.set_persist_added_keys(false);
+        agent.set_confirm_prompt({
+            let prompts = prompts.clone();
+            let allow = allow.clone();
+            Arc::new(move |prompt: &str| {
+                prompts.lock().unwrap().push(prompt.to_string());
+                Ok(allow.load(Ordering::SeqCst))
+            })
+        });
+        let (mut client, agent_thread) = spawn_connection(&agent);
+
+        // ssh-add -c: SSH_AGENTC_ADD_ID_CONSTRAINED with SSH_AGENT_CONSTRAIN_CONFIRM
+        let mut add = vec![25u8];
+        write_ssh_string_bytes(&mut add, b"ssh-ed25519");
+        write_ssh_string_bytes(&mut add, &verifying_bytes);
+        write_ssh_string_bytes(&mut add, &[&seed[..], &verifying_bytes[..]].concat());
+        write_ssh_string_bytes(&mut add, b"confirm@laptop");
+        add.push(2);
+        assert_eq!(send_message(&mut client, &add), vec![6u8]);
+
+        let mut sign = vec![13u8];
+        write_ssh_string_bytes(&mut sign, &key_blob);
+        write_ssh_string_bytes(&mut sign, b"data");
+        sign.write_u32::<BigEndian>(0).expect("flags");
+        assert_eq!(send_message(&mut client, &sign), vec![5u8]);
+
+        allow.store(true, Ordering::SeqCst);
+        let signature = request_signature(&mut client, &key_blob, b"data");
+        verify_signature(&signature, &verifying_bytes, b"data");
+        {
+            let prompts = prompts.lock().unwrap();
+            assert_eq!(prompts.len(), 2);
+            assert!(prompts.iter().all(|p| p.contains("'confirm@laptop'")));
+        }
+
+        // Constraints the agent cannot enforce are refused, not ignored
+        let mut destination = add.clone();
+        destination.pop();
+        destination.push(255);
+        write_ssh_string_bytes(&mut destination, b"restrict-destination-v00@openssh.com");
+        assert_eq!(send_message(&mut client, &destination), vec![5u8]);
+
+        // Re-adding without -c drops the constraint
+        let mut plain = add.clone();
+        plain[0] = 17;
+        plain.pop();
+        assert_eq!(send_message(&mut client, &plain), vec![6u8]);
+        request_signature(&mut client, &key_blob, b"data");
+        assert_eq!(prompts.lock().unwrap().len(), 2);
+
+        drop(client);
+        agent_thread.join().expect("agent thread finished");
+    }
+
+    #[test]
+    fn test_remote_approver_answers_before_local_prompt() {
+        use persona_core::auth::{ApprovalContext, ApprovalOutcome, RemoteApprover};
+
+        struct Switch(Arc<Mutex<ApprovalOutcome>>);
+
+        #[async_trait::async_trait]
+        impl RemoteApprover for Switch {
+            async fn request_approval(&self, _context: &ApprovalContext) -> ApprovalOutcome {
+                self.0.lock().unwrap().clone()
+            }
+        }
+
+        let seed = [0x52u8; 32];
+        let verifying_bytes = SigningKey::from_bytes(&seed).verifying_key().to_bytes();
+        let key_blob = encode_ssh_ed25519_public(&verifying_bytes);
+
+        let local_prompts = Arc::new(Mutex::new(0usize));
+        let outcome = Arc::new(Mutex::new(ApprovalOutcome::Denied {
+            reason: Some("not me".to_string()),
+        }));
+        let mut agent = Agent::new();
+        agent.set_persist_added_keys(false);
+        agent.set_confirm_prompt({
+            let local_prompts = local_prompts.clone();
+            Arc::new(move |_prompt: &str| {
+                *local_prompts.lock().unwrap() += 1;
+                Ok(true)
+            })
+        });
+        agent.set_remote_approver(Some(Arc::new(Switch(outcome.clone()))));
+        let (mut client, agent_thread) = spawn_connection(&agent);
+
+        let mut add = vec![25u8];
+        write_ssh_string_bytes(&mut add, b"ssh-ed25519");
+        write_ssh_string_bytes(&mut add, &verifying_bytes);
+        write_ssh_string_bytes(&mut add, &[&seed[..], &verifying_bytes[..]].concat());
+        write_ssh_string_bytes(&mut add, b"remote@laptop");
+        add.push(2);
+        assert_eq!(send_message(&mut client, &add), vec![6u8]);
+
+        // A remote denial is final; the local prompt is never shown
+        let mut sign = vec![13u8];
+        write_ssh_string_bytes(&mut sign, &key_blob);
+        write_ssh_string_bytes(&mut sign, b"data");
+        sign.write_u32::<BigEndian>(0).expect("flags");
+        assert_eq!(send_message(&mut client, &sign), vec![5u8]);
+        assert_eq!(*local_prompts.lock().unwrap(), 0);
+
+        *outcome.lock().unwrap() = ApprovalOutcome::Approved;
+        let signature = request_signature(&mut client, &key_blob, b"data");
+        verify_signature(&signature, &verifying_bytes, b"data");
+        assert_eq!(*local_prompts.lock().unwrap(), 0);
+
+        // An unreachable approver falls back to the local prompt
+        *outcome.lock().unwrap() = ApprovalOutcome::Unavailable {
+            reason: "connection refused".to_string(),
+        };
+        request_signature(&mut client, &key_blob, b"data");
+        assert_eq!(*local_prompts.lock().unwrap(), 1);
+
+        drop(client);
+        agent_thread.join().expect("agent thread finished");
+    }
+
+    #[test]
+    fn test_key_lifetime_expires_for_every_connection() {
+        let seed = [0x47u8; 32];
+        let verifying_bytes = SigningKey::from_bytes(&seed).verifying_key().to_bytes();
+        let key_blob = encode_ssh_ed25519_public(&verifying_bytes);
+
+        let mut agent = Agent::new();
+        agent.set_persist_added_keys(false);
+        let (mut first, first_thread) = spawn_connection(&agent);
+
+        // ssh-add -t 1: SSH_AGENTC_ADD_ID_CONSTRAINED with SSH_AGENT_CONSTRAIN_LIFETIME
+        let mut add = vec![25u8];
+        write_ssh_string_bytes(&mut add, b"ssh-ed25519");
+        write_ssh_string_bytes(&mut add, &verifying_bytes);
+        write_ssh_string_bytes(&mut add, &[&seed[..], &verifying_bytes[..]].concat());
+        write_ssh_string_bytes(&mut add, b"short-lived");
+        add.push(1);
+        add.write_u32::<BigEndian>(1).expect("lifetime");
+        assert_eq!(send_message(&mut first, &add), vec![6u8]);
+        assert_eq!(request_agent_identities(&mut first).1, "short-lived");
+
+        thread::sleep(Duration::from_millis(1200));
+
+        // A connection opened after expiry does not see the key
+        let (mut second, second_thread) = spawn_connection(&agent);
+        assert_eq!(send_message(&mut second, &[11u8]), vec![12u8, 0, 0, 0, 0]);
+        assert_eq!(agent.key_count(), 0);
+
+        // Nor can an older connection still sign with it, and the connection stays usable
+        let mut sign = vec![13u8];
+        write_ssh_string_bytes(&mut sign, &key_blob);
+        write_ssh_string_bytes(&mut sign, b"data");
+        sign.write_u32::<BigEndian>(0).expect("flags");
+        assert_eq!(send_message(&mut first, &sign), vec![5u8]);
+        assert_eq!(send_message(&mut first, &[11u8]), vec![12u8, 0, 0, 0, 0]);
+
+        drop(first);
+        drop(second);
+        first_thread.join().expect("first connection finished");
+        second_thread.join().expect("second connection finished");
+    }
+
+    fn passphrase_message(msg_type: u8, passphrase: &[u8]) -> Vec<u8> {
+        let mut message = vec![msg_type];
+        write_ssh_string_bytes(&mut message, passphrase);
+        message
+    }
+
+    /// Serve one connection from a shallow clone of `agent` on its own thread
+    fn spawn_connection(agent: &Agent) -> (StdUnixStream, thread::JoinHandle<()>) {
+        let (server_std, client) = StdUnixStream::pair().expect("stream pair");
+        server_std
+            .set_nonblocking(true)
+            .expect("server nonblocking");
+        let mut agent_clone = agent.clone_shallow();
+        let handle = thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("agent runtime");
+            runtime.block_on(async move {
+                let server_stream = UnixStream::from_std(server_std).expect("to tokio stream");
+                handle_connection(&mut agent_clone, AgentStream::Unix(server_stream))
+                    .await
+                    .expect("handle connection");
+            });
+        });
+        (client, handle)
+    }
+
+    /// Send one agent message and return the reply payload
+    fn send_message(stream: &mut StdUnixStream, payload: &[u8]) -> Vec<u8> {
+        let mut packet = Vec::new();
+        packet
+            .write_u32::<BigEndian>(payload.len() as u32)
+            .expect("len");
+        packet.extend_from_slice(payload);
+        stream.write_all(&packet).expect("send message");
+
+        let mut len_buf = [0u8; 4];
+        stream.read_exact(&mut len_buf).expect("reply len");
+        let mut resp = vec![0u8; BigEndian::read_u32(&len_buf) as usize];
+        stream.read_exact(&mut resp).expect("reply payload");
+        resp
+    }
+
     fn request_agent_identities(stream: &mut StdUnixStream) -> (Vec<u8>, String) {
         let mut request = vec![0u8; 5];
         BigEndian::write_u32(&mut request[0..4], 1);
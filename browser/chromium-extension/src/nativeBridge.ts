@@ -13,6 +13,13 @@ export interface HelloResponsePayload {
     paired?: boolean;
     session_id?: string | null;
     session_expires_at_ms?: number | null;
+    vault_fingerprint?: VaultFingerprint | null;
+}
+
+export interface VaultFingerprint {
+    emoji: string;
+    words: string[];
+    hex: string;
 }
 
 export interface SuggestionItem {
@@ -1,15 +1,21 @@
 //! Persona WASM Crypto Module
 //!
 //! 为浏览器扩展提供加密操作的WebAssembly模块
-//! 支持密码哈希、密钥派生、对称加密等功能
+//! 支持密码哈希、密钥派生、对称加密、TOTP验证码、密码强度估算、确定性站点密码等功能
 
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 
 mod crypto;
+mod otp;
+mod password;
+mod site_password;
 mod utils;
 
 pub use crypto::*;
+pub use otp::*;
+pub use password::*;
+pub use site_password::*;
 pub use utils::*;
 
 /// 初始化WASM模块
@@ -0,0 +1,134 @@
+//! 一次性密码模块
+//!
+//! 与`persona_core::otp`相同的HOTP(RFC 4226)/TOTP(RFC 6238)算法，
+//! 让扩展无需经过原生bridge即可显示导出密钥的验证码
+
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use data_encoding::{BASE32, BASE32_NOPAD};
+use hmac::{Hmac, Mac};
+
+/// 验证码位数范围
+const MIN_DIGITS: u32 = 4;
+const MAX_DIGITS: u32 = 10;
+
+/// 当前TOTP验证码
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TotpNow {
+    pub code: String,
+    /// 当前时间步剩余秒数
+    pub remaining_seconds: u32,
+    pub period: u32,
+}
+
+/// 计算当前TOTP验证码，返回`{ code, remaining_seconds, period }`
+#[wasm_bindgen]
+pub fn totp_now(
+    secret_base32: &str,
+    digits: u32,
+    period: u32,
+    algorithm: &str,
+) -> Result<JsValue, JsValue> {
+    let unix_time = (js_sys::Date::now() / 1000.0) as u64;
+    let now = totp_at(secret_base32, digits, period, algorithm, unix_time)
+        .map_err(|e| JsValue::from_str(&e))?;
+    serde_wasm_bindgen::to_value(&now).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// 指定Unix时间的TOTP验证码
+pub fn totp_at(
+    secret_base32: &str,
+    digits: u32,
+    period: u32,
+    algorithm: &str,
+    unix_time: u64,
+) -> Result<TotpNow, String> {
+    let secret = decode_totp_secret(secret_base32)?;
+    let period = period.max(1);
+    let value = hotp(&secret, unix_time / period as u64, algorithm)?;
+
+    Ok(TotpNow {
+        code: format_code(value, digits),
+        remaining_seconds: period - (unix_time % period as u64) as u32,
+        period,
+    })
+}
+
+/// 解码base32密钥，容忍空白、小写和缺失的填充
+fn decode_totp_secret(secret: &str) -> Result<Vec<u8>, String> {
+    let normalized: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect::<String>()
+        .trim_matches('=')
+        .to_string();
+    BASE32_NOPAD
+        .decode(normalized.as_bytes())
+        .or_else(|_| BASE32.decode(normalized.as_bytes()))
+        .map_err(|e| format!("Invalid base32 secret: {}", e))
+}
+
+/// RFC 4226动态截断，返回31位值
+fn hotp(secret: &[u8], counter: u64, algorithm: &str) -> Result<u32, String> {
+    let msg = counter.to_be_bytes();
+    let hash = match algorithm.to_ascii_uppercase().as_str() {
+        "SHA256" => hmac_digest::<Hmac<sha2::Sha256>>(secret, &msg)?,
+        "SHA512" => hmac_digest::<Hmac<sha2::Sha512>>(secret, &msg)?,
+        _ => hmac_digest::<Hmac<sha1::Sha1>>(secret, &msg)?,
+    };
+
+    let offset = (hash.last().copied().unwrap_or(0) & 0x0f) as usize;
+    let slice = hash
+        .get(offset..offset + 4)
+        .ok_or_else(|| "Invalid HMAC output".to_string())?;
+    Ok(((slice[0] as u32 & 0x7f) << 24)
+        | ((slice[1] as u32) << 16)
+        | ((slice[2] as u32) << 8)
+        | slice[3] as u32)
+}
+
+fn format_code(value: u32, digits: u32) -> String {
+    let digits = digits.clamp(MIN_DIGITS, MAX_DIGITS);
+    let value = value as u64 % 10_u64.pow(digits);
+    format!("{:0width$}", value, width = digits as usize)
+}
+
+fn hmac_digest<M: Mac + hmac::digest::KeyInit>(key: &[u8], msg: &[u8]) -> Result<Vec<u8>, String> {
+    let mut mac = <M as Mac>::new_from_slice(key).map_err(|e| format!("Invalid secret: {}", e))?;
+    mac.update(msg);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    /// RFC 6238附录B的SHA1密钥 "12345678901234567890"
+    const RFC6238_SHA1_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[wasm_bindgen_test]
+    fn test_totp_rfc6238_sha1_vector() {
+        let now = totp_at(RFC6238_SHA1_SECRET, 8, 30, "SHA1", 59).unwrap();
+        assert_eq!(now.code, "94287082");
+        assert_eq!(now.remaining_seconds, 1);
+        assert_eq!(now.period, 30);
+
+        let now = totp_at(RFC6238_SHA1_SECRET, 8, 30, "SHA1", 1111111109).unwrap();
+        assert_eq!(now.code, "07081804");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_totp_now_returns_struct() {
+        let value = totp_now("gezd gnbv gy3t qojq gezd gnbv gy3t qojq", 6, 30, "sha1").unwrap();
+        let now: TotpNow = serde_wasm_bindgen::from_value(value).unwrap();
+        assert_eq!(now.code.len(), 6);
+        assert!(now.remaining_seconds >= 1 && now.remaining_seconds <= 30);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_totp_rejects_invalid_secret() {
+        assert!(totp_at("not base32!", 6, 30, "SHA1", 59).is_err());
+    }
+}
@@ -0,0 +1,33 @@
+//! 密码强度模块
+//!
+//! 使用与`persona-core`共享的`persona-password`crate，保证CLI、桌面端和扩展的评分一致
+
+use persona_password::strength;
+use wasm_bindgen::prelude::*;
+
+pub use strength::{estimate_entropy_bits, PasswordStrength, MIN_RECOMMENDED_LENGTH};
+
+/// 估算密码强度，返回`{ entropy_bits, score, warnings }`，score范围0–4
+#[wasm_bindgen]
+pub fn password_strength(password: &str) -> JsValue {
+    serde_wasm_bindgen::to_value(&strength::password_strength(password)).unwrap_or(JsValue::NULL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn test_password_strength_matches_core() {
+        let value = password_strength("password123");
+        let result: PasswordStrength = serde_wasm_bindgen::from_value(value).unwrap();
+        assert_eq!(result.score, 1);
+        assert!(result.warnings.iter().any(|w| w.contains("common")));
+
+        let value = password_strength("v9#Lq2!xR7@mWz4p");
+        let result: PasswordStrength = serde_wasm_bindgen::from_value(value).unwrap();
+        assert_eq!(result.score, 4);
+        assert!(result.warnings.is_empty());
+    }
+}
@@ -0,0 +1,43 @@
+//! 确定性站点密码模块
+//!
+//! 使用与`persona-core`共享的`persona-password`crate，与CLI
+//! `persona credential generate --deterministic`生成完全相同的密码
+
+use persona_password::deterministic;
+use wasm_bindgen::prelude::*;
+
+pub use deterministic::{SitePasswordPolicy, MAX_SITE_PASSWORD_LENGTH, MIN_SITE_PASSWORD_LENGTH};
+
+/// 由主密钥、站点和计数器派生密码(HKDF-SHA256)，始终包含大小写字母
+#[wasm_bindgen]
+pub fn derive_site_password(
+    master_secret: &str,
+    site: &str,
+    counter: u32,
+    length: usize,
+    digits: bool,
+    symbols: bool,
+) -> Result<String, JsValue> {
+    let policy = SitePasswordPolicy {
+        length,
+        digits,
+        symbols,
+        ..SitePasswordPolicy::default()
+    };
+    deterministic::derive_site_password(master_secret.as_bytes(), site, counter, &policy)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn test_site_password_matches_core_vector() {
+        let password =
+            derive_site_password("correct horse battery staple", "example.com", 1, 20, true, true)
+                .unwrap();
+        assert_eq!(password, "5d=H)u3gl4un[Yh<J?pL");
+    }
+}
@@ -6,8 +6,10 @@ use serde_json::Value;
 use std::collections::HashMap;
 use tracing::info;
 
+use crate::commands::auth;
 use crate::config::CliConfig;
-use persona_core::{Database, Identity, IdentityType, PersonaService};
+use crate::utils::open_database;
+use persona_core::{Identity, IdentityType, PersonaService, SecretString};
 
 #[derive(Args, Clone)]
 pub struct AddArgs {
@@ -320,7 +322,7 @@ fn validate_identity(identity: &Identity) -> Result<()> {
 async fn save_identity(identity: &Identity, config: &CliConfig) -> Result<()> {
     // Open database
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
     // Ensure schema
@@ -339,26 +341,15 @@ async fn save_identity(identity: &Identity, config: &CliConfig) -> Result<()> {
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
-            .await
-            .map_err(|e| anyhow!("Failed to authenticate user: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => {
-                // proceed
-            }
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+        auth::unlock(&mut service).await?;
     } else {
         let password = Password::new()
             .with_prompt("Set a new master password")
             .with_confirmation("Confirm master password", "Passwords don't match")
             .interact()?;
+        let password = SecretString::new(password);
         let _ = service
-            .initialize_user(&password)
+            .initialize_user(&password, None)
             .await
             .map_err(|e| anyhow!("Failed to initialize user: {}", e))?;
     }
@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use colored::*;
+use std::sync::Arc;
+use tabled::{settings::Style, Table, Tabled};
+
+use crate::utils::open_database;
+use crate::{commands::wallet::parse_network, config::CliConfig, utils::core_ext::CoreResultExt};
+use persona_core::{
+    models::address_book::AddressBookEntry, storage::AddressBookRepository, PersonaError,
+};
+
+#[derive(Args, Debug)]
+pub struct AddressBookArgs {
+    #[command(subcommand)]
+    command: AddressBookCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum AddressBookCommand {
+    /// Save a labeled recipient address
+    Add {
+        /// Label to use instead of the address (e.g. with `wallet create-transaction --to`)
+        label: String,
+
+        /// Recipient address; validated (including checksum) for the network
+        address: String,
+
+        /// Network (bitcoin, ethereum, solana, ...)
+        #[arg(short, long)]
+        network: String,
+
+        /// Notes about the recipient
+        #[arg(long)]
+        notes: Option<String>,
+    },
+    /// List saved addresses
+    List {
+        /// Only show addresses for this network
+        #[arg(short, long)]
+        network: Option<String>,
+    },
+    /// Remove a saved address
+    #[command(alias = "remove")]
+    Rm {
+        /// Label of the entry to remove
+        label: String,
+    },
+}
+
+#[derive(Tabled)]
+struct AddressBookTable {
+    #[tabled(rename = "Label")]
+    label: String,
+    #[tabled(rename = "Network")]
+    network: String,
+    #[tabled(rename = "Address")]
+    address: String,
+    #[tabled(rename = "Notes")]
+    notes: String,
+}
+
+pub async fn execute(args: AddressBookArgs, config: &CliConfig) -> Result<()> {
+    let repo = open_repository(config).await?;
+
+    match args.command {
+        AddressBookCommand::Add {
+            label,
+            address,
+            network,
+            notes,
+        } => {
+            let network = parse_network(&network)?;
+            let entry = AddressBookEntry::new(label, address, network).with_notes(notes);
+            let created = repo.create(&entry).await.into_anyhow()?;
+            println!(
+                "{} Saved {} as '{}' ({})",
+                "✓".green().bold(),
+                created.address,
+                created.label.bold(),
+                created.network
+            );
+        }
+        AddressBookCommand::List { network } => {
+            let network = network.as_deref().map(parse_network).transpose()?;
+            let entries = repo.list(network.as_ref()).await.into_anyhow()?;
+            if entries.is_empty() {
+                println!("No saved addresses.");
+                return Ok(());
+            }
+
+            let rows: Vec<AddressBookTable> = entries
+                .into_iter()
+                .map(|entry| AddressBookTable {
+                    label: entry.label,
+                    network: entry.network.to_string(),
+                    address: entry.address,
+                    notes: entry.notes.unwrap_or_default(),
+                })
+                .collect();
+            println!("{}", Table::new(&rows).with(Style::modern()));
+        }
+        AddressBookCommand::Rm { label } => {
+            if !repo.delete_by_label(&label).await.into_anyhow()? {
+                return Err(PersonaError::NotFound(format!(
+                    "No address book entry labeled '{}'",
+                    label
+                ))
+                .into());
+            }
+            println!("{} Removed '{}'", "✓".green().bold(), label);
+        }
+    }
+    Ok(())
+}
+
+async fn open_repository(config: &CliConfig) -> Result<AddressBookRepository> {
+    let db_path = config.get_database_path();
+    let db = open_database(&db_path)
+        .await
+        .into_anyhow()
+        .with_context(|| format!("Failed to open database at {}", db_path.display()))?;
+    db.migrate()
+        .await
+        .into_anyhow()
+        .context("Failed to run database migrations")?;
+    Ok(AddressBookRepository::new(Arc::new(db)))
+}
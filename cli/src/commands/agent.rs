@@ -0,0 +1,38 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+
+use crate::commands::ssh::print_agent_status;
+use persona_ssh_agent::status::read_agent_status;
+
+#[derive(Args, Debug)]
+pub struct AgentArgs {
+    #[command(subcommand)]
+    command: AgentCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum AgentCommand {
+    /// Report the running SSH agent's socket, PID and key count
+    Status {
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+        /// Shorthand for `--format json`
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
+    },
+}
+
+pub async fn execute(args: AgentArgs) -> Result<()> {
+    match args.command {
+        AgentCommand::Status { format, json } => {
+            let status = read_agent_status(false);
+            match if json { "json" } else { format.as_str() } {
+                "text" => print_agent_status(&status),
+                "json" => println!("{}", serde_json::to_string_pretty(&status)?),
+                other => anyhow::bail!("Unsupported format '{}' (expected text or json)", other),
+            }
+            Ok(())
+        }
+    }
+}
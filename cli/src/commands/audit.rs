@@ -0,0 +1,267 @@
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use colored::*;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::utils::open_database;
+use crate::{commands::auth, config::CliConfig, utils::core_ext::CoreResultExt};
+#[cfg(feature = "hibp")]
+use persona_core::breach::HibpClient;
+use persona_core::{
+    breach::{BloomFilter, DEFAULT_FALSE_POSITIVE_RATE},
+    service::BreachedCredential,
+    PersonaError, PersonaService,
+};
+
+#[derive(Args, Debug)]
+pub struct AuditArgs {
+    #[command(subcommand)]
+    command: AuditCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum AuditCommand {
+    /// Check stored passwords against an offline breached-password database
+    Passwords {
+        /// Breach database file (defaults to `security.breach_db_path` or the workspace copy)
+        #[arg(long, value_name = "FILE")]
+        breach_db: Option<PathBuf>,
+
+        /// Query the HIBP range API instead (sends only a 5-character SHA-1 prefix per password)
+        #[arg(long)]
+        online: bool,
+
+        /// Timeout in seconds for each online request
+        #[arg(long, default_value_t = 10, requires = "online")]
+        timeout: u64,
+    },
+    /// Show the vault-wide security score and the weakest credentials
+    Score {
+        /// Number of lowest-scoring credentials to list
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Compile a downloaded breach list into a breach database
+    BuildBreachDb {
+        /// Source list: HIBP `SHA1:count` lines or one plaintext password per line
+        source: PathBuf,
+
+        /// Output file (defaults to the configured breach database path)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Target false-positive rate; lower is more accurate but larger on disk
+        #[arg(long, default_value_t = DEFAULT_FALSE_POSITIVE_RATE)]
+        false_positive_rate: f64,
+    },
+}
+
+pub async fn execute(args: AuditArgs, config: &CliConfig) -> Result<()> {
+    match args.command {
+        AuditCommand::Passwords {
+            breach_db,
+            online,
+            timeout,
+        } => {
+            if online {
+                audit_passwords_online(config, breach_db, Duration::from_secs(timeout)).await
+            } else {
+                audit_passwords(config, breach_db).await
+            }
+        }
+        AuditCommand::Score { limit } => show_score(config, limit).await,
+        AuditCommand::BuildBreachDb {
+            source,
+            output,
+            false_positive_rate,
+        } => build_breach_db(config, source, output, false_positive_rate),
+    }
+}
+
+pub(crate) async fn audit_passwords(config: &CliConfig, breach_db: Option<PathBuf>) -> Result<()> {
+    let breach_db = breach_db.unwrap_or_else(|| config.get_breach_db_path());
+    if !breach_db.exists() {
+        return Err(PersonaError::ConfigurationError(format!(
+            "Breach database not found: {} (build one with `persona audit build-breach-db`)",
+            breach_db.display()
+        ))
+        .into());
+    }
+
+    let mut service = open_service(config).await?;
+    audit_with_breach_db(&mut service, &breach_db).await
+}
+
+async fn audit_with_breach_db(service: &mut PersonaService, breach_db: &Path) -> Result<()> {
+    service
+        .load_breach_db(breach_db)
+        .into_anyhow()
+        .with_context(|| format!("Failed to load breach database: {}", breach_db.display()))?;
+    let breached = service.audit_breached_passwords().await.into_anyhow()?;
+    report(&breached, "the breach database");
+    Ok(())
+}
+
+#[cfg(feature = "hibp")]
+pub(crate) async fn audit_passwords_online(
+    config: &CliConfig,
+    breach_db: Option<PathBuf>,
+    timeout: Duration,
+) -> Result<()> {
+    let client = HibpClient::new(timeout).into_anyhow()?;
+    let mut service = open_service(config).await?;
+    service.enable_online_breach_check(client);
+    println!(
+        "{}",
+        "Checking passwords against HIBP (only 5-character hash prefixes are sent)...".dimmed()
+    );
+
+    match service.audit_breached_passwords_online().await {
+        Ok(breached) => {
+            report(&breached, "Have I Been Pwned");
+            Ok(())
+        }
+        Err(err) if matches!(err.downcast_ref(), Some(PersonaError::Io(_))) => {
+            // Offline or blocked: fall back to the local database when there is one
+            let breach_db = breach_db.unwrap_or_else(|| config.get_breach_db_path());
+            if !breach_db.exists() {
+                return Err(err);
+            }
+            println!(
+                "{} {}; falling back to {}",
+                "⚠️".yellow(),
+                err,
+                breach_db.display()
+            );
+            audit_with_breach_db(&mut service, &breach_db).await
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(not(feature = "hibp"))]
+pub(crate) async fn audit_passwords_online(
+    _config: &CliConfig,
+    _breach_db: Option<PathBuf>,
+    _timeout: Duration,
+) -> Result<()> {
+    Err(PersonaError::ConfigurationError(
+        "This build does not include the online HIBP check (enable the `hibp` feature)".to_string(),
+    )
+    .into())
+}
+
+fn report(breached: &[BreachedCredential], source: &str) {
+    if breached.is_empty() {
+        println!(
+            "{} No stored passwords found in {}",
+            "✓".green().bold(),
+            source
+        );
+        return;
+    }
+
+    println!(
+        "{} {} password(s) appear in {}:",
+        "⚠️".yellow(),
+        breached.len(),
+        source
+    );
+    for entry in breached {
+        match entry.occurrences {
+            Some(count) => println!(
+                "  {} ({}) - seen {} times",
+                entry.name.bold(),
+                entry.credential_id,
+                count
+            ),
+            None => println!("  {} ({})", entry.name.bold(), entry.credential_id),
+        }
+    }
+    if breached.iter().any(|entry| entry.occurrences.is_none()) {
+        println!(
+            "{}",
+            "Matches may rarely be false positives; rotate these passwords anyway.".dimmed()
+        );
+    }
+}
+
+async fn show_score(config: &CliConfig, limit: usize) -> Result<()> {
+    let service = open_service(config).await?;
+    let mut vault = service.vault_security_score().await.into_anyhow()?;
+    println!(
+        "{} {}/100 across {} credential(s)",
+        "Vault security score:".bold(),
+        vault.score,
+        vault.credentials.len()
+    );
+
+    vault.credentials.sort_by_key(|c| c.score);
+    let weakest: Vec<_> = vault
+        .credentials
+        .iter()
+        .take(limit)
+        .filter(|c| c.score < 100)
+        .collect();
+    if weakest.is_empty() {
+        return Ok(());
+    }
+    println!("\nLowest-scoring credentials:");
+    for credential in weakest {
+        println!(
+            "  {:>3}  {} ({})",
+            credential.score,
+            credential.name.bold(),
+            credential.credential_id
+        );
+    }
+    Ok(())
+}
+
+fn build_breach_db(
+    config: &CliConfig,
+    source: PathBuf,
+    output: Option<PathBuf>,
+    false_positive_rate: f64,
+) -> Result<()> {
+    let output = output.unwrap_or_else(|| config.get_breach_db_path());
+    let filter = BloomFilter::build_from_file(&source, false_positive_rate)
+        .into_anyhow()
+        .with_context(|| format!("Failed to read breach list: {}", source.display()))?;
+    filter
+        .save(&output)
+        .into_anyhow()
+        .with_context(|| format!("Failed to write breach database: {}", output.display()))?;
+
+    println!(
+        "{} Built breach database with {} entries at {}",
+        "✓".green().bold(),
+        filter.len(),
+        output.display()
+    );
+    println!(
+        "  Size: {} KiB, expected false-positive rate: {:.4}%",
+        filter.size_bytes() / 1024,
+        filter.false_positive_rate() * 100.0
+    );
+    Ok(())
+}
+
+/// Open the vault, unlocking it if a master password is set
+async fn open_service(config: &CliConfig) -> Result<PersonaService> {
+    let db_path = config.get_database_path();
+    let db = open_database(&db_path)
+        .await
+        .into_anyhow()
+        .with_context(|| format!("Failed to connect to database: {}", db_path.display()))?;
+    db.migrate()
+        .await
+        .into_anyhow()
+        .context("Failed to run database migrations")?;
+    let mut service = PersonaService::new(db).await.into_anyhow()?;
+    if service.has_users().await.into_anyhow()? {
+        auth::unlock(&mut service).await?;
+    }
+    Ok(service)
+}
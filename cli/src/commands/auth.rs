@@ -0,0 +1,340 @@
+use anyhow::{Context, Result};
+use colored::*;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+use crate::utils::core_ext::{auth_failed, CoreResultExt};
+use persona_core::{auth::authentication::AuthResult, PersonaError, PersonaService, SecretString};
+
+/// Environment variable read when no terminal is attached (CI/automation)
+pub const MASTER_PASSWORD_ENV: &str = "PERSONA_MASTER_PASSWORD";
+
+/// Environment variable holding the new master password for `persona passwd` without a terminal
+pub const NEW_MASTER_PASSWORD_ENV: &str = "PERSONA_NEW_MASTER_PASSWORD";
+
+/// Wrong passwords accepted in one interactive unlock before giving up
+const MAX_PROMPTS: u32 = 3;
+
+/// Master password that decrypted a vault encrypted at rest; later unlocks reuse it instead of
+/// asking again
+static VAULT_PASSPHRASE: OnceLock<SecretString> = OnceLock::new();
+
+/// Source of master password input
+pub trait PasswordPrompt {
+    fn read_password(&mut self, prompt: &str) -> Result<String>;
+}
+
+/// Reads the password from the terminal without echo
+pub struct TerminalPrompt;
+
+impl PasswordPrompt for TerminalPrompt {
+    fn read_password(&mut self, prompt: &str) -> Result<String> {
+        rpassword::prompt_password(format!("{}: ", prompt)).context("Failed to read password")
+    }
+}
+
+/// Unlock the vault with the master password.
+///
+/// Prompts on an interactive terminal and falls back to `PERSONA_MASTER_PASSWORD`
+/// otherwise (or when `PERSONA_NON_INTERACTIVE` is set). On success the service holds the unlocked session for the rest of
+/// the command.
+pub async fn unlock(service: &mut PersonaService) -> Result<()> {
+    unlock_with_prompt(service, "Enter master password to unlock").await
+}
+
+/// Same as [`unlock`] with a custom prompt
+pub async fn unlock_with_prompt(service: &mut PersonaService, prompt: &str) -> Result<()> {
+    if let Some(password) = VAULT_PASSPHRASE.get() {
+        return unlock_with_password(service, password).await;
+    }
+    if std::io::stdin().is_terminal() && !non_interactive() {
+        return unlock_interactive(service, &mut TerminalPrompt, prompt).await;
+    }
+
+    let password = read_master_password(prompt)?;
+    unlock_with_password(service, &password).await
+}
+
+/// Read the master password once: from the terminal when attached, else from
+/// `PERSONA_MASTER_PASSWORD`. Returns the remembered passphrase if the vault was decrypted
+/// with one.
+pub fn read_master_password(prompt: &str) -> Result<SecretString> {
+    if let Some(password) = VAULT_PASSPHRASE.get() {
+        return Ok(password.clone());
+    }
+    if std::io::stdin().is_terminal() && !non_interactive() {
+        return TerminalPrompt.read_password(prompt).map(SecretString::new);
+    }
+    std::env::var(MASTER_PASSWORD_ENV)
+        .map(SecretString::new)
+        .map_err(|_| {
+            PersonaError::AuthenticationFailed(format!(
+                "No terminal available; set {} to unlock",
+                MASTER_PASSWORD_ENV
+            ))
+            .into()
+        })
+}
+
+/// Remember the master password that decrypted the vault file for the rest of the command
+#[cfg(feature = "sqlcipher")]
+pub fn remember_vault_passphrase(password: &SecretString) {
+    let _ = VAULT_PASSPHRASE.set(password.clone());
+}
+
+/// Unlock with an already-read master password, without prompting again
+pub async fn unlock_with_password(
+    service: &mut PersonaService,
+    password: &SecretString,
+) -> Result<()> {
+    match service.authenticate_user(password).await.into_anyhow()? {
+        AuthResult::Success => Ok(()),
+        AuthResult::AccountLocked => Err(account_locked()),
+        AuthResult::PasswordChangeRequired if service.is_unlocked() => {
+            Err(PersonaError::AuthenticationFailed(
+                "Recovery code accepted, but a new master password must be set; run the command again in a terminal with an unused recovery code".to_string(),
+            )
+            .into())
+        }
+        AuthResult::PasswordChangeRequired => Err(reset_pending()),
+        other => Err(auth_failed(other)),
+    }
+}
+
+/// Prompt until the password is accepted, reporting the attempts left before lockout
+pub async fn unlock_interactive(
+    service: &mut PersonaService,
+    input: &mut impl PasswordPrompt,
+    prompt: &str,
+) -> Result<()> {
+    if let Some(phrase) = service.verification_phrase().await.into_anyhow()? {
+        eprintln!(
+            "{} {}",
+            "Verification phrase:".dimmed(),
+            phrase.cyan().bold()
+        );
+    }
+    for _ in 0..MAX_PROMPTS {
+        let password = SecretString::new(input.read_password(prompt)?);
+        match service.authenticate_user(&password).await.into_anyhow()? {
+            AuthResult::Success => return Ok(()),
+            AuthResult::InvalidCredentials => {
+                let remaining = service
+                    .remaining_auth_attempts()
+                    .await
+                    .into_anyhow()?
+                    .unwrap_or(0);
+                if remaining == 0 {
+                    return Err(account_locked());
+                }
+                eprintln!("{} {}", "✗".red(), attempt_feedback(remaining));
+            }
+            AuthResult::AccountLocked => return Err(account_locked()),
+            AuthResult::PasswordChangeRequired if service.is_unlocked() => {
+                return complete_recovery(service, input).await;
+            }
+            AuthResult::PasswordChangeRequired => return Err(reset_pending()),
+            other => return Err(auth_failed(other)),
+        }
+    }
+    Err(auth_failed(AuthResult::InvalidCredentials))
+}
+
+/// After a recovery code unlocks the vault, set the new master password it demands
+async fn complete_recovery(
+    service: &mut PersonaService,
+    input: &mut impl PasswordPrompt,
+) -> Result<()> {
+    eprintln!(
+        "{} Recovery code accepted. It cannot be used again.",
+        "✓".green()
+    );
+    let password = prompt_new_password(input)?;
+    service
+        .reset_master_password(&password)
+        .await
+        .into_anyhow()?;
+    eprintln!("{} Master password updated.", "✓".green());
+    Ok(())
+}
+
+/// Read a new master password: asked twice on an interactive terminal, else taken from
+/// `PERSONA_NEW_MASTER_PASSWORD`
+pub fn read_new_master_password() -> Result<SecretString> {
+    if std::io::stdin().is_terminal() && !non_interactive() {
+        return prompt_new_password(&mut TerminalPrompt);
+    }
+    match std::env::var(NEW_MASTER_PASSWORD_ENV) {
+        Ok(password) if !password.is_empty() => Ok(SecretString::new(password)),
+        _ => Err(PersonaError::InvalidInput(format!(
+            "No terminal available; set {} to the new master password",
+            NEW_MASTER_PASSWORD_ENV
+        ))
+        .into()),
+    }
+}
+
+/// Ask for a non-empty new password until it is typed the same way twice
+fn prompt_new_password(input: &mut impl PasswordPrompt) -> Result<SecretString> {
+    loop {
+        let password = input.read_password("New master password")?;
+        if password.is_empty() {
+            eprintln!("{} The master password cannot be empty.", "✗".red());
+            continue;
+        }
+        if input.read_password("Confirm new master password")? == password {
+            return Ok(SecretString::new(password));
+        }
+        eprintln!("{} Passwords don't match.", "✗".red());
+    }
+}
+
+fn non_interactive() -> bool {
+    std::env::var("PERSONA_NON_INTERACTIVE")
+        .map(|val| val == "1" || val.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+fn attempt_feedback(remaining: u32) -> String {
+    match remaining {
+        1 => "Incorrect master password. 1 attempt left before the vault locks.".to_string(),
+        n => format!(
+            "Incorrect master password. {} attempts left before the vault locks.",
+            n
+        ),
+    }
+}
+
+fn reset_pending() -> anyhow::Error {
+    PersonaError::AuthenticationFailed(
+        "A master password reset is pending; unlock with a recovery code to set a new one"
+            .to_string(),
+    )
+    .into()
+}
+
+fn account_locked() -> anyhow::Error {
+    PersonaError::Locked("Too many failed attempts; the account is temporarily locked".to_string())
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use persona_core::{auth::authentication::MAX_FAILED_ATTEMPTS, Database};
+    use std::collections::VecDeque;
+
+    struct ScriptedPrompt {
+        answers: VecDeque<String>,
+        reads: u32,
+    }
+
+    impl ScriptedPrompt {
+        fn new(answers: &[&str]) -> Self {
+            Self {
+                answers: answers.iter().map(|answer| answer.to_string()).collect(),
+                reads: 0,
+            }
+        }
+    }
+
+    impl PasswordPrompt for ScriptedPrompt {
+        fn read_password(&mut self, _prompt: &str) -> Result<String> {
+            self.reads += 1;
+            Ok(self.answers.pop_front().expect("no more input"))
+        }
+    }
+
+    async fn service_with_user() -> PersonaService {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let mut service = PersonaService::new(db).await.unwrap();
+        service
+            .initialize_user(&SecretString::from("correct"), None)
+            .await
+            .unwrap();
+        service.lock();
+        service
+    }
+
+    #[tokio::test]
+    async fn retries_until_the_password_is_accepted() {
+        let mut service = service_with_user().await;
+        let mut prompt = ScriptedPrompt::new(&["wrong", "also wrong", "correct"]);
+
+        unlock_interactive(&mut service, &mut prompt, "unlock")
+            .await
+            .unwrap();
+        assert_eq!(prompt.reads, 3);
+        assert!(service.is_unlocked());
+        assert_eq!(
+            service.remaining_auth_attempts().await.unwrap(),
+            Some(MAX_FAILED_ATTEMPTS)
+        );
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_prompts() {
+        let mut service = service_with_user().await;
+        let mut prompt = ScriptedPrompt::new(&["a", "b", "c", "correct"]);
+
+        assert!(unlock_interactive(&mut service, &mut prompt, "unlock")
+            .await
+            .is_err());
+        assert_eq!(prompt.reads, MAX_PROMPTS);
+        assert_eq!(
+            service.remaining_auth_attempts().await.unwrap(),
+            Some(MAX_FAILED_ATTEMPTS - MAX_PROMPTS)
+        );
+    }
+
+    #[tokio::test]
+    async fn stops_prompting_once_the_account_locks() {
+        let mut service = service_with_user().await;
+        for _ in 0..MAX_FAILED_ATTEMPTS - 1 {
+            service
+                .authenticate_user(&SecretString::from("wrong"))
+                .await
+                .unwrap();
+        }
+        assert_eq!(service.remaining_auth_attempts().await.unwrap(), Some(1));
+
+        let mut prompt = ScriptedPrompt::new(&["wrong", "correct"]);
+        let err = unlock_interactive(&mut service, &mut prompt, "unlock")
+            .await
+            .unwrap_err();
+        assert_eq!(prompt.reads, 1);
+        assert!(err.to_string().contains("locked"));
+        assert_eq!(service.remaining_auth_attempts().await.unwrap(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn recovery_code_prompts_for_a_new_password() {
+        let mut service = service_with_user().await;
+        service
+            .authenticate_user(&SecretString::from("correct"))
+            .await
+            .unwrap();
+        let codes = service.generate_recovery_codes().await.unwrap();
+        service.lock();
+
+        let mut prompt = ScriptedPrompt::new(&[&codes[0], "fresh", "typo", "fresh", "fresh"]);
+        unlock_interactive(&mut service, &mut prompt, "unlock")
+            .await
+            .unwrap();
+        assert_eq!(prompt.reads, 5);
+        assert!(service.is_unlocked());
+
+        service.lock();
+        let mut prompt = ScriptedPrompt::new(&["fresh"]);
+        unlock_interactive(&mut service, &mut prompt, "unlock")
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn feedback_pluralises_attempts() {
+        assert!(attempt_feedback(1).contains("1 attempt left"));
+        assert!(attempt_feedback(3).contains("3 attempts left"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::utils::open_database;
 use crate::{config::CliConfig, utils::core_ext::CoreResultExt};
 use anyhow::{anyhow, bail, Context, Result};
 use clap::{Args, Subcommand};
@@ -7,7 +8,7 @@ use persona_core::{
     models::auto_lock_policy::{
         AutoLockPolicy, AutoLockSecurityLevel, AutoLockUseCase, PolicyConfiguration,
     },
-    storage::{AutoLockPolicyRepository, Database},
+    storage::AutoLockPolicyRepository,
 };
 use std::sync::Arc;
 use tabled::{settings::Style, Table, Tabled};
@@ -81,6 +82,14 @@ pub enum AutoLockCommand {
         #[arg(long)]
         force_sensitive: bool,
 
+        /// Lock on system sleep and resume (default: true)
+        #[arg(long)]
+        lock_on_sleep: Option<bool>,
+
+        /// Lock when the OS screen locks (default: true)
+        #[arg(long)]
+        lock_on_screen_lock: Option<bool>,
+
         /// Create from predefined use case (personal|corporate|public|developer|high-security)
         #[arg(long, short)]
         use_case: Option<String>,
@@ -129,6 +138,14 @@ pub enum AutoLockCommand {
         /// Enable/disable force sensitive lock
         #[arg(long)]
         force_sensitive: Option<bool>,
+
+        /// Enable/disable locking on system sleep and resume
+        #[arg(long)]
+        lock_on_sleep: Option<bool>,
+
+        /// Enable/disable locking when the OS screen locks
+        #[arg(long)]
+        lock_on_screen_lock: Option<bool>,
     },
     /// Delete an auto-lock policy
     Delete {
@@ -285,6 +302,9 @@ pub async fn handle_auto_lock(args: AutoLockArgs, config: &CliConfig) -> Result<
                     formatter.print_info(&format!("Warning Time: {} seconds", p.warning_time_secs));
                     formatter
                         .print_info(&format!("Force Lock Sensitive: {}", p.force_lock_sensitive));
+                    formatter.print_info(&format!("Lock On Sleep: {}", p.lock_on_sleep));
+                    formatter
+                        .print_info(&format!("Lock On Screen Lock: {}", p.lock_on_screen_lock));
                     formatter.print_info(&format!("Security Score: {}/100", p.security_score()));
                     formatter.print_info(&format!("Active: {}", p.is_active));
                     formatter.print_info(&format!(
@@ -307,6 +327,8 @@ pub async fn handle_auto_lock(args: AutoLockArgs, config: &CliConfig) -> Result<
             warnings,
             warning_time,
             force_sensitive,
+            lock_on_sleep,
+            lock_on_screen_lock,
             use_case,
         } => {
             let policy = if let Some(use_case) = use_case {
@@ -314,6 +336,12 @@ pub async fn handle_auto_lock(args: AutoLockArgs, config: &CliConfig) -> Result<
                 let mut p = AutoLockPolicy::recommended_for_use_case(parsed);
                 p.name = name;
                 p.description = description;
+                if let Some(l) = lock_on_sleep {
+                    p.lock_on_sleep = l;
+                }
+                if let Some(l) = lock_on_screen_lock {
+                    p.lock_on_screen_lock = l;
+                }
                 p
             } else {
                 let config = PolicyConfiguration {
@@ -329,6 +357,8 @@ pub async fn handle_auto_lock(args: AutoLockArgs, config: &CliConfig) -> Result<
                     force_lock_sensitive: force_sensitive,
                     activity_grace_period_secs: 5,
                     background_check_interval_secs: 30,
+                    lock_on_sleep: lock_on_sleep.unwrap_or(true),
+                    lock_on_screen_lock: lock_on_screen_lock.unwrap_or(true),
                     is_active: true,
                 };
 
@@ -359,6 +389,8 @@ pub async fn handle_auto_lock(args: AutoLockArgs, config: &CliConfig) -> Result<
             warnings,
             warning_time,
             force_sensitive,
+            lock_on_sleep,
+            lock_on_screen_lock,
         } => {
             let mut policy = repo
                 .find_by_id(&policy_id)
@@ -380,6 +412,8 @@ pub async fn handle_auto_lock(args: AutoLockArgs, config: &CliConfig) -> Result<
                 force_lock_sensitive: policy.force_lock_sensitive,
                 activity_grace_period_secs: policy.activity_grace_period_secs,
                 background_check_interval_secs: policy.background_check_interval_secs,
+                lock_on_sleep: policy.lock_on_sleep,
+                lock_on_screen_lock: policy.lock_on_screen_lock,
                 is_active: policy.is_active,
             };
 
@@ -414,6 +448,12 @@ pub async fn handle_auto_lock(args: AutoLockArgs, config: &CliConfig) -> Result<
             if let Some(f) = force_sensitive {
                 config.force_lock_sensitive = f;
             }
+            if let Some(l) = lock_on_sleep {
+                config.lock_on_sleep = l;
+            }
+            if let Some(l) = lock_on_screen_lock {
+                config.lock_on_screen_lock = l;
+            }
 
             policy.update(config);
 
@@ -682,7 +722,7 @@ pub async fn handle_auto_lock(args: AutoLockArgs, config: &CliConfig) -> Result<
 
 async fn init_repository(config: &CliConfig) -> Result<AutoLockPolicyRepository> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .into_anyhow()
         .with_context(|| format!("Failed to open database at {}", db_path.display()))?;
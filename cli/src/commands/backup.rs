@@ -0,0 +1,173 @@
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use colored::*;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    config::CliConfig,
+    utils::{
+        confirm_action, core_ext::CoreResultExt, format_file_size, lock_vault, open_database,
+        print_safety_backup,
+    },
+};
+use persona_core::{
+    models::{AuditAction, AuditLog, ResourceType},
+    storage::{
+        backup_file_name, create_backup, prune_backups, restore_backup, verify_backup,
+        AuditLogRepository, BackupGuard,
+    },
+    Database, Repository,
+};
+
+#[derive(Args, Debug)]
+pub struct BackupArgs {
+    #[command(subcommand)]
+    command: BackupCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum BackupCommand {
+    /// Write a verified copy of the vault database
+    Create {
+        /// Backup file (or directory to write it in); defaults to the configured backup directory
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Replace the vault database with a backup after checking its recorded hash
+    Restore {
+        /// Backup file written by `persona backup create`
+        path: PathBuf,
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+pub async fn execute(args: BackupArgs, config: &CliConfig) -> Result<()> {
+    match args.command {
+        BackupCommand::Create { output } => create(output, config).await,
+        BackupCommand::Restore { path, yes } => restore(&path, yes, config).await,
+    }
+}
+
+async fn create(output: Option<PathBuf>, config: &CliConfig) -> Result<()> {
+    let db = open_existing(&config.get_database_path()).await?;
+    let directory = &config.backup.directory;
+    let path = match output {
+        Some(path) if path.is_dir() => path.join(backup_file_name("manual")),
+        Some(path) => path,
+        None => directory.join(backup_file_name("manual")),
+    };
+
+    // A consistent snapshot is taken even while another process has the vault open
+    let info = create_backup(&db, &path)
+        .await
+        .into_anyhow()
+        .context("Failed to create backup")?;
+    let log = AuditLog::new(AuditAction::BackupCreated, ResourceType::Backup, true)
+        .with_resource_id(Some(info.path.display().to_string()));
+    AuditLogRepository::new(db.clone())
+        .create(&log)
+        .await
+        .into_anyhow()?;
+
+    println!(
+        "{} Backup written to {}",
+        "✓".green().bold(),
+        info.path.display().to_string().cyan()
+    );
+    println!("  Size:    {}", format_file_size(info.size));
+    println!("  SHA-256: {}", info.sha256);
+
+    let in_backup_directory = info.path.parent() == Some(directory.as_path());
+    if in_backup_directory && config.backup.max_backups > 0 {
+        let removed = prune_backups(directory, config.backup.max_backups as usize).into_anyhow()?;
+        if !removed.is_empty() {
+            println!(
+                "  Pruned {} old backup(s) beyond max_backups = {}",
+                removed.len(),
+                config.backup.max_backups
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn restore(path: &Path, yes: bool, config: &CliConfig) -> Result<()> {
+    let _lock = lock_vault(config, "backup restore")?;
+    let info = verify_backup(path)
+        .await
+        .into_anyhow()
+        .with_context(|| format!("Refusing to restore {}", path.display()))?;
+    println!(
+        "{} Backup verified ({}, SHA-256 {})",
+        "✓".green().bold(),
+        format_file_size(info.size),
+        info.sha256
+    );
+
+    let db_path = config.get_database_path();
+    if !yes
+        && !confirm_action(
+            &format!(
+                "Replace the vault at {} with this backup? Changes made since it was taken will be lost",
+                db_path.display()
+            ),
+            false,
+        )?
+    {
+        println!("Cancelled. Use --yes to skip confirmation.");
+        return Ok(());
+    }
+
+    if db_path.exists() {
+        if let Some(dir) = config.destructive_backup_dir() {
+            let current = open_existing(&db_path).await?;
+            let snapshot = BackupGuard::new(dir)
+                .snapshot(&current, "pre_restore")
+                .await
+                .into_anyhow()?;
+            current.pool().close().await;
+            if let Some(snapshot) = snapshot {
+                print_safety_backup(&snapshot);
+            }
+        }
+    }
+
+    restore_backup(path, &db_path)
+        .await
+        .into_anyhow()
+        .context("Failed to restore backup")?;
+
+    // Bring an older backup up to this version's schema, as opening the vault would
+    let restored = open_existing(&db_path).await?;
+    restored
+        .migrate()
+        .await
+        .into_anyhow()
+        .context("Failed to run database migrations on the restored vault")?;
+    let log = AuditLog::new(AuditAction::BackupRestored, ResourceType::Backup, true)
+        .with_resource_id(Some(path.display().to_string()));
+    AuditLogRepository::new(restored.clone())
+        .create(&log)
+        .await
+        .into_anyhow()?;
+
+    println!(
+        "{} Restored {} from {}",
+        "✓".green().bold(),
+        db_path.display().to_string().cyan(),
+        path.display()
+    );
+    Ok(())
+}
+
+async fn open_existing(db_path: &Path) -> Result<Database> {
+    if !db_path.exists() {
+        anyhow::bail!("No vault database at {}", db_path.display());
+    }
+    open_database(db_path)
+        .await
+        .into_anyhow()
+        .with_context(|| format!("Failed to connect to database: {}", db_path.display()))
+}
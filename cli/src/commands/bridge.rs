@@ -1,12 +1,10 @@
 use anyhow::{anyhow, Context, Result};
 use base64::Engine as _;
 use clap::Args;
-use data_encoding::{BASE32, BASE32_NOPAD};
 use hmac::{Hmac, Mac};
 use rand::rngs::OsRng;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
-use sha2::Sha512;
 use sha2::Sha256;
 use std::fs;
 use std::path::Path;
@@ -15,10 +13,13 @@ use std::process::{Command, Stdio};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tracing::{debug, info, warn};
 use url::Url;
+use zeroize::{Zeroize, Zeroizing};
 
-use persona_core::models::{CredentialData, CredentialType, TwoFactorData};
+use persona_core::models::{Credential, CredentialData, CredentialType, OtpType, TwoFactorData};
 use persona_core::storage::{CredentialRepository, WorkspaceRepository};
-use persona_core::{Database, PersonaService, Repository};
+use persona_core::{Database, PersonaService, Repository, SecretString, VaultFingerprint};
+
+use crate::utils::open_database;
 
 /// Native Messaging host for the Persona browser extension.
 ///
@@ -144,13 +145,18 @@ struct FillPayload {
     /// Required for fill operations to prevent background credential exfiltration.
     #[serde(default)]
     user_gesture: bool,
+    /// Also return the current code of the credential's linked TOTP, if it has one.
+    #[serde(default)]
+    include_totp: bool,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "snake_case")]
 struct FillResponse {
     username: Option<String>,
-    password: Option<String>,
+    password: Option<SecretString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    totp: Option<TotpResponse>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -167,8 +173,10 @@ struct TotpPayload {
 #[serde(rename_all = "snake_case")]
 struct TotpResponse {
     code: String,
+    /// 0 for HOTP codes, which stay valid until used
     remaining_seconds: u32,
     period: u32,
+    otp_type: OtpType,
 }
 
 #[derive(Debug, Deserialize)]
@@ -196,6 +204,13 @@ pub async fn execute(args: BridgeArgs) -> Result<()> {
 
     if let Some(code) = args.approve_code {
         approve_pairing(&state_dir, &code)?;
+        if let Some(fingerprint) = vault_fingerprint(&db_path).await {
+            println!(
+                "Vault fingerprint: {} ({}); check the extension shows the same",
+                fingerprint.emoji,
+                fingerprint.words.join(", ")
+            );
+        }
         return Ok(());
     }
 
@@ -229,7 +244,7 @@ pub async fn execute(args: BridgeArgs) -> Result<()> {
         };
 
         let request_id = req.request_id.clone();
-        let resp = handle_request(&db_path, &state_dir, req)
+        let mut resp = handle_request(&db_path, &state_dir, req)
             .await
             .unwrap_or_else(|e| BridgeResponse::<serde_json::Value> {
                 request_id,
@@ -240,6 +255,10 @@ pub async fn execute(args: BridgeArgs) -> Result<()> {
             });
 
         write_frame(&mut stdout, &resp).await?;
+        // Fill responses carry the decrypted password
+        if let Some(payload) = resp.payload.as_mut() {
+            wipe_json(payload);
+        }
     }
 
     Ok(())
@@ -268,12 +287,14 @@ async fn handle_request(
             };
 
             let payload = serde_json::json!({
-                "server_version": "0.1.0",
+                "server_version": persona_core::VERSION,
+                "build": persona_core::build_info(),
                 "capabilities": ["status", "pairing_request", "pairing_finalize", "get_suggestions", "request_fill", "get_totp", "copy"],
                 "pairing_required": require_pairing && session.is_none(),
                 "paired": session.is_some(),
                 "session_id": session.as_ref().map(|s| s.session_id.clone()),
                 "session_expires_at_ms": session.as_ref().map(|s| s.expires_at_ms),
+                "vault_fingerprint": vault_fingerprint(db_path).await,
             });
             Ok(ok(req.request_id, "hello_response", payload))
         }
@@ -351,6 +372,7 @@ async fn handle_request(
                 .ok()
                 .filter(|s| !s.trim().is_empty())
                 .ok_or_else(|| anyhow!("locked: PERSONA_MASTER_PASSWORD not set"))?;
+            let master_password = SecretString::new(master_password);
 
             // Open DB + unlock.
             let db = open_db(db_path).await?;
@@ -386,12 +408,13 @@ async fn handle_request(
             }
 
             // Security: Origin binding - verify the request origin matches the credential's URL.
-            let origin_valid = validate_origin_binding(&host, cred.url.as_deref());
+            let origin_valid = validate_origin_binding(&host, &cred);
             if !origin_valid {
                 warn!(
                     origin = %parsed.origin,
                     host = %host,
                     cred_url = ?cred.url,
+                    allowed_origins = ?cred.allowed_origins(),
                     item_id = %parsed.item_id,
                     "fill request rejected: origin mismatch"
                 );
@@ -400,20 +423,7 @@ async fn handle_request(
                 ));
             }
 
-            let fill = match data {
-                CredentialData::Password(p) => FillResponse {
-                    username: cred.username.clone().or(p.email.clone()),
-                    password: Some(p.password),
-                },
-                CredentialData::Raw(_) => FillResponse {
-                    username: cred.username.clone(),
-                    password: None,
-                },
-                _ => FillResponse {
-                    username: cred.username.clone(),
-                    password: None,
-                },
-            };
+            let fill = build_fill_response(&service, &cred, data, parsed.include_totp).await?;
 
             // Audit log: successful fill
             info!(
@@ -423,6 +433,7 @@ async fn handle_request(
                 item_id = %parsed.item_id,
                 item_name = %cred.name,
                 user_gesture = parsed.user_gesture,
+                with_totp = fill.totp.is_some(),
                 "credential fill completed"
             );
 
@@ -451,6 +462,7 @@ async fn handle_request(
                 .ok()
                 .filter(|s| !s.trim().is_empty())
                 .ok_or_else(|| anyhow!("locked: PERSONA_MASTER_PASSWORD not set"))?;
+            let master_password = SecretString::new(master_password);
 
             let db = open_db(db_path).await?;
             let active_identity_id = get_active_identity_id(&db).await;
@@ -478,15 +490,16 @@ async fn handle_request(
                 return Err(anyhow!("unsupported_credential_type"));
             }
 
-            if cred.url.is_none() {
+            if cred.url.is_none() && cred.allowed_origins().is_empty() {
                 return Err(anyhow!("origin_binding_required: totp entries must have a URL set"));
             }
 
-            if !validate_origin_binding(&host, cred.url.as_deref()) {
+            if !validate_origin_binding(&host, &cred) {
                 warn!(
                     origin = %parsed.origin,
                     host = %host,
                     cred_url = ?cred.url,
+                    allowed_origins = ?cred.allowed_origins(),
                     item_id = %parsed.item_id,
                     "totp request rejected: origin mismatch"
                 );
@@ -505,7 +518,11 @@ async fn handle_request(
                 _ => return Err(anyhow!("unsupported_credential_type")),
             };
 
-            let (code, remaining_seconds, period) = generate_totp_code_from_data(&tf)?;
+            // HOTP entries hand out the next code and persist the advanced counter
+            let current = service
+                .two_factor_code(&item_id)
+                .await
+                .map_err(|e| anyhow!("totp_failed: {e}"))?;
 
             info!(
                 event = "bridge_totp_success",
@@ -520,9 +537,10 @@ async fn handle_request(
                 req.request_id,
                 "totp_response",
                 serde_json::to_value(TotpResponse {
-                    code,
-                    remaining_seconds,
-                    period,
+                    code: current.code,
+                    remaining_seconds: current.remaining,
+                    period: tf.period.max(1),
+                    otp_type: tf.otp_type,
                 })?,
             ))
         }
@@ -552,6 +570,7 @@ async fn handle_request(
                 .ok()
                 .filter(|s| !s.trim().is_empty())
                 .ok_or_else(|| anyhow!("locked: PERSONA_MASTER_PASSWORD not set"))?;
+            let master_password = SecretString::new(master_password);
 
             let db = open_db(db_path).await?;
             let active_identity_id = get_active_identity_id(&db).await;
@@ -575,11 +594,12 @@ async fn handle_request(
                 }
             }
 
-            if !validate_origin_binding(&host, cred.url.as_deref()) {
+            if !validate_origin_binding(&host, &cred) {
                 warn!(
                     origin = %parsed.origin,
                     host = %host,
                     cred_url = ?cred.url,
+                    allowed_origins = ?cred.allowed_origins(),
                     item_id = %parsed.item_id,
                     field = %field,
                     "copy request rejected: origin mismatch"
@@ -589,7 +609,7 @@ async fn handle_request(
                 ));
             }
 
-            let text = match field.as_str() {
+            let text = Zeroizing::new(match field.as_str() {
                 "username" => cred
                     .username
                     .clone()
@@ -604,7 +624,7 @@ async fn handle_request(
                         .await?
                         .ok_or_else(|| anyhow!("not_found"))?;
                     match data {
-                        CredentialData::Password(p) => p.password,
+                        CredentialData::Password(p) => p.password.expose().to_string(),
                         _ => return Err(anyhow!("unsupported_credential_type")),
                     }
                 }
@@ -624,7 +644,7 @@ async fn handle_request(
                     code
                 }
                 other => return Err(anyhow!("invalid_payload: unknown field '{other}'")),
-            };
+            });
 
             copy_text_to_clipboard(&text)?;
 
@@ -702,7 +722,7 @@ fn resolve_state_dir(override_path: Option<PathBuf>) -> PathBuf {
 }
 
 async fn open_db(db_path: &PathBuf) -> Result<Database> {
-    let db = Database::from_file(db_path).await?;
+    let db = open_database(db_path).await?;
     db.migrate().await?;
     Ok(db)
 }
@@ -784,6 +804,29 @@ fn save_state(state_dir: &Path, state: &BridgeStateFile) -> Result<()> {
     Ok(())
 }
 
+/// Extensions paired with this machine, as reported by `persona status`
+#[derive(Debug, Serialize)]
+pub(crate) struct PairingSummary {
+    pub extension_id: String,
+    pub paired_at_ms: i64,
+    pub has_session: bool,
+}
+
+/// Read pairings from the default (or `PERSONA_BRIDGE_STATE_DIR`) state directory
+pub(crate) fn read_pairings() -> Result<Vec<PairingSummary>> {
+    let mut state = load_state(&resolve_state_dir(None))?;
+    purge_expired(&mut state);
+    Ok(state
+        .pairings
+        .into_iter()
+        .map(|pairing| PairingSummary {
+            extension_id: pairing.extension_id,
+            paired_at_ms: pairing.paired_at_ms,
+            has_session: pairing.session.is_some(),
+        })
+        .collect())
+}
+
 fn normalize_pairing_code(code: &str) -> String {
     code.trim().replace(' ', "").to_ascii_uppercase()
 }
@@ -1061,7 +1104,7 @@ async fn compute_status(db_path: &PathBuf) -> Result<(bool, Option<String>)> {
         // If a master password is available, try to authenticate to report "unlocked".
         if let Ok(pw) = std::env::var("PERSONA_MASTER_PASSWORD") {
             if !pw.trim().is_empty() {
-                let auth = service.authenticate_user(&pw).await?;
+                let auth = service.authenticate_user(&SecretString::new(pw)).await?;
                 auth != persona_core::auth::authentication::AuthResult::Success
             } else {
                 true
@@ -1086,6 +1129,24 @@ async fn compute_status(db_path: &PathBuf) -> Result<(bool, Option<String>)> {
     Ok((locked, active_identity))
 }
 
+/// Fingerprint the extension shows so the user can confirm it reached the expected vault.
+///
+/// Best-effort: `None` when there is no vault yet, so `hello` still works before `persona init`.
+async fn vault_fingerprint(db_path: &Path) -> Option<VaultFingerprint> {
+    if !db_path.exists() {
+        return None;
+    }
+    let db = open_db(&db_path.to_path_buf()).await.ok()?;
+    let service = PersonaService::new(db).await.ok()?;
+    match service.vault_fingerprint().await {
+        Ok(fingerprint) => Some(fingerprint),
+        Err(e) => {
+            warn!("Failed to compute vault fingerprint: {e}");
+            None
+        }
+    }
+}
+
 async fn get_credential_suggestions(db_path: &PathBuf, host: &str) -> Result<Vec<SuggestionItem>> {
     let db = open_db(db_path).await?;
     let active_identity_id = get_active_identity_id(&db).await;
@@ -1106,28 +1167,32 @@ async fn get_credential_suggestions(db_path: &PathBuf, host: &str) -> Result<Vec
             _ => continue,
         };
 
-        if cred.url.is_none() {
-            continue;
-        }
-
         // Calculate match strength based on URL similarity.
-        let match_strength = compute_match_strength(host, cred.url.as_deref().unwrap_or_default());
+        let match_strength = credential_match_strength(host, &cred);
 
         if match_strength == 0 {
             continue;
         }
 
-        out.push(SuggestionItem {
-            item_id: cred.id.to_string(),
-            title: cred.name,
-            username_hint: cred.username,
-            match_strength,
-            credential_type: kind.to_string(),
-        });
+        out.push((
+            SuggestionItem {
+                item_id: cred.id.to_string(),
+                title: cred.name,
+                username_hint: cred.username,
+                match_strength,
+                credential_type: kind.to_string(),
+            },
+            cred.last_accessed,
+        ));
     }
 
-    // Sort by match strength descending.
-    out.sort_by(|a, b| b.match_strength.cmp(&a.match_strength));
+    // Sort by match strength descending, most recently used first among equals.
+    out.sort_by(|(a, a_used), (b, b_used)| {
+        b.match_strength
+            .cmp(&a.match_strength)
+            .then_with(|| b_used.cmp(a_used))
+    });
+    let out: Vec<SuggestionItem> = out.into_iter().map(|(item, _)| item).collect();
 
     debug!(
         host = %host,
@@ -1152,19 +1217,10 @@ async fn get_active_identity_id(db: &Database) -> Option<uuid::Uuid> {
 /// - 100: Exact host match (e.g., "github.com" == "github.com")
 /// - 90: Subdomain match (e.g., "api.github.com" matches "github.com")
 /// - 80: Host contained in URL (e.g., "github.com" in "https://github.com/login")
-/// - 60: TLD+1 match (e.g., "www.github.com" matches "github.com")
+/// - 60: Same registrable domain (eTLD+1, e.g., "login.example.co.uk" matches "www.example.co.uk")
 /// - 0: No match
 fn compute_match_strength(request_host: &str, cred_url: &str) -> u8 {
-    // Extract host from credential URL.
-    let cred_host = match Url::parse(cred_url) {
-        Ok(url) => url.host_str().map(|s| s.to_lowercase()),
-        Err(_) => {
-            // Try treating it as a bare hostname.
-            Some(cred_url.to_lowercase())
-        }
-    };
-
-    let cred_host = match cred_host {
+    let cred_host = match credential_host(cred_url) {
         Some(h) => h,
         None => return 0,
     };
@@ -1176,62 +1232,78 @@ fn compute_match_strength(request_host: &str, cred_url: &str) -> u8 {
         return 100;
     }
 
-    // Request is subdomain of credential host (e.g., api.github.com -> github.com).
-    if req_host.ends_with(&format!(".{cred_host}")) {
+    // Request is subdomain of credential host (e.g., api.github.com -> github.com). Only
+    // below a registrable domain: every host is a "subdomain" of "com" or "co.uk".
+    if req_host.ends_with(&format!(".{cred_host}")) && registrable_domain(&cred_host).is_some() {
         return 90;
     }
 
     // Credential is subdomain of request host (e.g., github.com -> www.github.com).
-    if cred_host.ends_with(&format!(".{req_host}")) {
+    if cred_host.ends_with(&format!(".{req_host}")) && registrable_domain(&req_host).is_some() {
         return 90;
     }
 
-    // Check if they share the same registrable domain (TLD+1).
-    // Simple heuristic: compare last two parts.
-    let req_parts: Vec<&str> = req_host.split('.').collect();
-    let cred_parts: Vec<&str> = cred_host.split('.').collect();
-
-    if req_parts.len() >= 2 && cred_parts.len() >= 2 {
-        let req_tld1 = format!(
-            "{}.{}",
-            req_parts[req_parts.len() - 2],
-            req_parts[req_parts.len() - 1]
-        );
-        let cred_tld1 = format!(
-            "{}.{}",
-            cred_parts[cred_parts.len() - 2],
-            cred_parts[cred_parts.len() - 1]
-        );
-
-        if req_tld1 == cred_tld1 {
+    // Same registrable domain per the public suffix list, so "example.co.uk" and
+    // "other.co.uk" or "alice.github.io" and "bob.github.io" are different sites.
+    if let (Some(req_site), Some(cred_site)) = (
+        registrable_domain(&req_host),
+        registrable_domain(&cred_host),
+    ) {
+        if req_site == cred_site {
             return 60;
         }
     }
 
-    // Fallback: simple contains check (legacy behavior).
-    if cred_url.contains(&req_host) {
-        return 80;
+    0
+}
+
+/// Lowercased host of a stored URL or origin; bare entries ("github.com", "github.com:443")
+/// are read as https URLs. Hosts are only ever compared whole, never as substrings.
+fn credential_host(cred_url: &str) -> Option<String> {
+    let host = |url: Url| url.host_str().map(|s| s.to_lowercase());
+    Url::parse(cred_url).ok().and_then(host).or_else(|| {
+        Url::parse(&format!("https://{cred_url}"))
+            .ok()
+            .and_then(host)
+    })
+}
+
+/// Registrable domain (eTLD+1) of a host, e.g. "example.co.uk" for "www.example.co.uk".
+///
+/// `None` for IP addresses and for hosts that are themselves a public suffix ("co.uk",
+/// "github.io").
+fn registrable_domain(host: &str) -> Option<&str> {
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return None;
     }
+    psl::domain_str(host)
+}
 
-    0
+/// Best match strength between the request host and the origins a credential is bound to: its
+/// URL and the extra allowed origins in its metadata (`persona credential set-origins`).
+fn credential_match_strength(request_host: &str, cred: &Credential) -> u8 {
+    cred.url
+        .iter()
+        .cloned()
+        .chain(cred.allowed_origins())
+        .map(|origin| compute_match_strength(request_host, &origin))
+        .max()
+        .unwrap_or(0)
 }
 
 /// Validate that the request origin is allowed to access the credential.
 ///
 /// Security: This prevents credential filling on mismatched domains.
-fn validate_origin_binding(request_host: &str, cred_url: Option<&str>) -> bool {
-    let cred_url = match cred_url {
-        Some(url) => url,
-        // No URL stored = no origin binding (allow any).
-        // This is intentional for credentials without a URL.
-        None => return true,
-    };
-
-    let match_strength = compute_match_strength(request_host, cred_url);
+fn validate_origin_binding(request_host: &str, cred: &Credential) -> bool {
+    // No URL or allowed origins stored = no origin binding (allow any).
+    // This is intentional for credentials without a URL.
+    if cred.url.is_none() && cred.allowed_origins().is_empty() {
+        return true;
+    }
 
-    // Require at least TLD+1 match (60+) for fill operations.
+    // Require at least TLD+1 match (60+) against any bound origin for fill operations.
     // This is stricter than suggestions (which show anything > 0).
-    match_strength >= 60
+    credential_match_strength(request_host, cred) >= 60
 }
 
 fn origin_to_host(origin: &str) -> Result<String> {
@@ -1242,65 +1314,46 @@ fn origin_to_host(origin: &str) -> Result<String> {
         .ok_or_else(|| anyhow!("invalid_origin"))
 }
 
-fn generate_totp_code_from_data(data: &TwoFactorData) -> Result<(String, u32, u32)> {
-    let secret_bytes = decode_totp_secret(&data.secret_key)?;
-    let now = chrono::Utc::now();
-    let period = data.period.max(1) as u64;
-    let timestamp = now.timestamp().max(0) as u64;
-    let counter = timestamp / period;
-    let digits = data.digits.clamp(4, 10) as u32;
-    let code_num = hotp(&secret_bytes, counter, &data.algorithm)?;
-    let modulo = 10_u32.pow(digits);
-    let value = code_num % modulo;
-    let code = format!("{:0width$}", value, width = digits as usize);
-    let remaining = (period - (timestamp % period)) as u32;
-    Ok((code, remaining, data.period.max(1)))
-}
+/// Fill payload for a password credential, with the linked TOTP code when requested.
+async fn build_fill_response(
+    service: &PersonaService,
+    cred: &Credential,
+    data: CredentialData,
+    include_totp: bool,
+) -> Result<FillResponse> {
+    let (username, password) = match data {
+        CredentialData::Password(p) => (cred.username.clone().or(p.email), Some(p.password)),
+        _ => (cred.username.clone(), None),
+    };
 
-fn hotp(secret: &[u8], counter: u64, algorithm: &str) -> Result<u32> {
-    let msg = counter.to_be_bytes();
-    let algo = algorithm.to_ascii_uppercase();
-    let hash = if algo == "SHA256" {
-        type HmacSha256 = Hmac<sha2::Sha256>;
-        let mut mac = HmacSha256::new_from_slice(secret).context("invalid secret")?;
-        mac.update(&msg);
-        mac.finalize().into_bytes().to_vec()
-    } else if algo == "SHA512" {
-        type HmacSha512 = Hmac<Sha512>;
-        let mut mac = HmacSha512::new_from_slice(secret).context("invalid secret")?;
-        mac.update(&msg);
-        mac.finalize().into_bytes().to_vec()
+    let linked = if include_totp {
+        service.linked_totp(&cred.id).await?
     } else {
-        type HmacSha1 = Hmac<sha1::Sha1>;
-        let mut mac = HmacSha1::new_from_slice(secret).context("invalid secret")?;
-        mac.update(&msg);
-        mac.finalize().into_bytes().to_vec()
+        None
+    };
+    let totp = match linked {
+        Some(tf) => {
+            let (code, remaining_seconds, period) = generate_totp_code_from_data(&tf)?;
+            Some(TotpResponse {
+                code,
+                remaining_seconds,
+                period,
+                otp_type: tf.otp_type,
+            })
+        }
+        None => None,
     };
 
-    let offset = (hash.last().copied().unwrap_or(0) & 0x0f) as usize;
-    if offset + 4 > hash.len() {
-        return Err(anyhow!("invalid_hmac_output"));
-    }
-    let slice = &hash[offset..offset + 4];
-    let binary = ((slice[0] as u32 & 0x7f) << 24)
-        | ((slice[1] as u32) << 16)
-        | ((slice[2] as u32) << 8)
-        | slice[3] as u32;
-    Ok(binary)
+    Ok(FillResponse {
+        username,
+        password,
+        totp,
+    })
 }
 
-fn decode_totp_secret(secret: &str) -> Result<Vec<u8>> {
-    let normalized: String = secret
-        .chars()
-        .filter(|c| !c.is_whitespace())
-        .map(|c| c.to_ascii_uppercase())
-        .collect::<String>()
-        .trim_matches('=')
-        .to_string();
-    BASE32_NOPAD
-        .decode(normalized.as_bytes())
-        .or_else(|_| BASE32.decode(normalized.as_bytes()))
-        .map_err(|e| anyhow!("invalid_base32_secret: {e}"))
+fn generate_totp_code_from_data(data: &TwoFactorData) -> Result<(String, u32, u32)> {
+    let current = persona_core::otp::totp_code(data).map_err(|e| anyhow!("totp_failed: {e}"))?;
+    Ok((current.code, current.remaining, data.period.max(1)))
 }
 
 fn copy_text_to_clipboard(text: &str) -> Result<()> {
@@ -1374,14 +1427,263 @@ async fn read_frame<R: AsyncReadExt + Unpin>(reader: &mut R) -> Result<Option<Ve
     Ok(Some(buf))
 }
 
+/// Overwrite every string in a response payload once it has been sent
+fn wipe_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(s) => s.zeroize(),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(wipe_json),
+        serde_json::Value::Object(map) => map.values_mut().for_each(wipe_json),
+        _ => {}
+    }
+}
+
 async fn write_frame<W: AsyncWriteExt + Unpin, T: Serialize>(
     writer: &mut W,
     msg: &T,
 ) -> Result<()> {
-    let payload = serde_json::to_vec(msg)?;
+    let payload = Zeroizing::new(serde_json::to_vec(msg)?);
     let len = payload.len() as u32;
     writer.write_all(&len.to_le_bytes()).await?;
     writer.write_all(&payload).await?;
     writer.flush().await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use persona_core::models::{OtpType, PasswordCredentialData, SecurityLevel};
+    use persona_core::IdentityType;
+
+    #[tokio::test]
+    async fn fill_returns_linked_totp_code_on_request() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let mut service = PersonaService::new(db).await.unwrap();
+        let salt = service.generate_salt();
+        service
+            .unlock(&SecretString::from("test_password"), &salt)
+            .unwrap();
+
+        let identity = service
+            .create_identity("Work".to_string(), IdentityType::Work)
+            .await
+            .unwrap();
+        let password = PasswordCredentialData {
+            password: "correct horse".into(),
+            email: Some("octocat@example.com".to_string()),
+            security_questions: vec![],
+        };
+        let cred = service
+            .create_credential(
+                identity.id,
+                "GitHub".to_string(),
+                CredentialType::Password,
+                SecurityLevel::High,
+                &CredentialData::Password(password.clone()),
+            )
+            .await
+            .unwrap();
+        let totp = service
+            .create_credential(
+                identity.id,
+                "GitHub 2FA".to_string(),
+                CredentialType::TwoFactor,
+                SecurityLevel::High,
+                &CredentialData::TwoFactor(TwoFactorData {
+                    secret_key: "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ".into(),
+                    issuer: "GitHub".to_string(),
+                    account_name: "octocat".to_string(),
+                    algorithm: "SHA1".to_string(),
+                    digits: 6,
+                    period: 30,
+                    otp_type: OtpType::Totp,
+                    counter: 0,
+                    time_offset_seconds: 0,
+                }),
+            )
+            .await
+            .unwrap();
+        let cred = service.link_totp(&cred.id, &totp.id).await.unwrap();
+
+        let without = build_fill_response(
+            &service,
+            &cred,
+            CredentialData::Password(password.clone()),
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(without.totp.is_none());
+        assert_eq!(without.username.as_deref(), Some("octocat@example.com"));
+
+        let with = build_fill_response(&service, &cred, CredentialData::Password(password), true)
+            .await
+            .unwrap();
+        let code = with.totp.expect("linked code");
+        assert_eq!(code.code.len(), 6);
+        assert_eq!(code.period, 30);
+        assert!(with.password.is_some());
+
+        let json = serde_json::to_value(&without).unwrap();
+        assert!(json.get("totp").is_none());
+    }
+
+    fn bound_to(url: Option<&str>, allowed_origins: &[&str]) -> Credential {
+        let mut credential = Credential::new(
+            uuid::Uuid::new_v4(),
+            "Login".to_string(),
+            CredentialType::Password,
+            SecurityLevel::Medium,
+            Vec::new(),
+            None,
+        );
+        credential.url = url.map(str::to_string);
+        credential.set_allowed_origins(allowed_origins.iter().map(|o| o.to_string()).collect());
+        credential
+    }
+
+    #[test]
+    fn test_match_strength_uses_the_public_suffix_list() {
+        // Plain .com: subdomains share github.com
+        assert_eq!(
+            compute_match_strength("github.com", "https://github.com/login"),
+            100
+        );
+        assert_eq!(
+            compute_match_strength("api.github.com", "https://github.com"),
+            90
+        );
+        assert_eq!(
+            compute_match_strength("gist.github.com", "https://www.github.com"),
+            60
+        );
+        assert_eq!(
+            compute_match_strength("github.com", "https://gitlab.com"),
+            0
+        );
+
+        // Multi-label suffix: the registrable domain is example.co.uk, not co.uk
+        assert_eq!(
+            compute_match_strength("login.example.co.uk", "https://www.example.co.uk"),
+            60
+        );
+        assert_eq!(
+            compute_match_strength("example.co.uk", "https://other.co.uk"),
+            0
+        );
+        assert!(!validate_origin_binding(
+            "www.attacker.co.uk",
+            &bound_to(Some("https://www.bank.co.uk"), &[])
+        ));
+
+        // github.io is a public suffix, so each user's pages are a separate site
+        assert_eq!(
+            compute_match_strength("docs.alice.github.io", "https://www.alice.github.io"),
+            60
+        );
+        assert_eq!(
+            compute_match_strength("bob.github.io", "https://alice.github.io"),
+            0
+        );
+        assert!(!validate_origin_binding(
+            "evil.github.io",
+            &bound_to(Some("https://alice.github.io"), &[])
+        ));
+        assert!(validate_origin_binding(
+            "alice.github.io",
+            &bound_to(Some("https://alice.github.io/blog"), &[])
+        ));
+
+        // Addresses have no registrable domain
+        assert_eq!(compute_match_strength("10.0.1.1", "https://192.168.1.1"), 0);
+    }
+
+    #[test]
+    fn test_match_strength_never_matches_substrings() {
+        assert_eq!(compute_match_strength("hub.com", "https://github.com"), 0);
+        assert_eq!(compute_match_strength("ithub.com", "https://github.com"), 0);
+        assert_eq!(compute_match_strength("github.co", "https://github.com"), 0);
+        assert_eq!(
+            compute_match_strength("attacker.io", "https://example.com/?next=attacker.io"),
+            0
+        );
+        assert_eq!(
+            compute_match_strength("attacker.io", "https://example.com/attacker.io/login"),
+            0
+        );
+        assert!(!validate_origin_binding(
+            "hub.com",
+            &bound_to(Some("https://github.com"), &[])
+        ));
+
+        // Bare hostnames compare as hosts
+        assert_eq!(compute_match_strength("github.com", "github.com"), 100);
+        assert_eq!(compute_match_strength("github.com", "GitHub.com:443"), 100);
+        assert_eq!(
+            compute_match_strength("api.github.com", "github.com/login"),
+            90
+        );
+        assert_eq!(compute_match_strength("hub.com", "github.com"), 0);
+    }
+
+    #[test]
+    fn test_allowed_origins_extend_the_origin_binding() {
+        let github = bound_to(Some("https://github.com/login"), &["github.community"]);
+        assert!(validate_origin_binding("github.com", &github));
+        assert!(validate_origin_binding("github.community", &github));
+        assert!(validate_origin_binding("support.github.community", &github));
+        assert!(!validate_origin_binding(
+            "github.community.evil.com",
+            &github
+        ));
+        assert_eq!(credential_match_strength("github.community", &github), 100);
+        assert_eq!(credential_match_strength("gitlab.com", &github), 0);
+
+        // Lookalikes of an extra origin get nothing, whether contained in it or containing it
+        assert_eq!(credential_match_strength("hub.community", &github), 0);
+        assert_eq!(credential_match_strength("ithub.community", &github), 0);
+        assert!(!validate_origin_binding("hub.community", &github));
+        assert!(!validate_origin_binding("github.communityx.com", &github));
+
+        // Allowed origins alone bind a credential without a URL
+        let oauth_only = bound_to(None, &["https://login.example.co.uk"]);
+        assert!(validate_origin_binding("www.example.co.uk", &oauth_only));
+        assert!(!validate_origin_binding("www.other.co.uk", &oauth_only));
+        assert!(validate_origin_binding(
+            "anything.test",
+            &bound_to(None, &[])
+        ));
+    }
+
+    #[test]
+    fn test_public_suffix_origins_match_no_subdomains() {
+        assert_eq!(compute_match_strength("bank.com", "com"), 0);
+        assert_eq!(compute_match_strength("attacker.co.uk", "https://co.uk"), 0);
+        assert_eq!(compute_match_strength("alice.github.io", "github.io"), 0);
+        assert!(!validate_origin_binding(
+            "evil.com",
+            &bound_to(Some("https://bank.example"), &["com"])
+        ));
+    }
+
+    #[test]
+    fn test_wipe_json_clears_every_string() {
+        let mut payload = serde_json::json!({
+            "username": "octocat",
+            "password": "correct horse",
+            "totp": { "code": "123456", "period": 30 },
+            "items": ["a", "b"],
+        });
+        wipe_json(&mut payload);
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "username": "",
+                "password": "",
+                "totp": { "code": "", "period": 30 },
+                "items": ["", ""],
+            })
+        );
+    }
+}
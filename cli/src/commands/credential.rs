@@ -1,13 +1,25 @@
 use anyhow::{anyhow, Context, Result};
 use clap::{Args, Subcommand, ValueEnum};
 use colored::*;
+use std::path::PathBuf;
+use std::time::Duration;
 use tabled::{Table, Tabled};
 use uuid::Uuid;
 
-use crate::{config::CliConfig, utils::core_ext::CoreResultExt};
+use crate::utils::open_database;
+use crate::{
+    commands::{audit, auth},
+    config::CliConfig,
+    utils::{confirm_action, core_ext::CoreResultExt, report_safety_backups},
+};
 use persona_core::{
     models::{Credential, CredentialData, CredentialType, PasswordCredentialData, SecurityLevel},
-    Database, Identity, PersonaService,
+    service::{
+        CredentialSecurityScore, PasswordAuditOptions, PasswordAuditReport,
+        DEFAULT_MIN_PASSWORD_ENTROPY_BITS, DEFAULT_PASSWORD_MAX_AGE_DAYS,
+    },
+    Identity, PasswordGenerator, PasswordGeneratorOptions, PersonaError, PersonaService,
+    SecretString, SitePasswordPolicy,
 };
 
 #[derive(Args, Debug)]
@@ -27,7 +39,7 @@ pub enum CredentialCommand {
         #[arg(short, long)]
         name: String,
         /// Credential type
-        #[arg(short, long, default_value = "password")]
+        #[arg(short = 't', long, default_value = "password")]
         credential_type: CredentialTypeOption,
         /// Security level (critical/high/medium/low)
         #[arg(long, default_value = "high")]
@@ -38,6 +50,9 @@ pub enum CredentialCommand {
         /// Optional URL or service
         #[arg(long)]
         url: Option<String>,
+        /// Optional free-form notes
+        #[arg(long)]
+        notes: Option<String>,
         /// Prompt for password/secret in terminal
         #[arg(long)]
         prompt_secret: bool,
@@ -54,7 +69,7 @@ pub enum CredentialCommand {
         #[arg(short, long)]
         identity: Option<String>,
         /// Credential type filter
-        #[arg(short, long)]
+        #[arg(short = 't', long)]
         credential_type: Option<String>,
         /// Show only favorites
         #[arg(long)]
@@ -62,6 +77,9 @@ pub enum CredentialCommand {
         /// Output as json/yaml
         #[arg(short, long, default_value = "table")]
         format: String,
+        /// Also list credentials in the trash
+        #[arg(long)]
+        include_trashed: bool,
     },
     /// Show decrypted credential details
     Show {
@@ -72,7 +90,15 @@ pub enum CredentialCommand {
         #[arg(long)]
         reveal: bool,
     },
-    /// Remove a credential
+    /// List a credential's previous passwords, kept when it is rotated
+    History {
+        /// Credential UUID
+        id: Uuid,
+        /// Decrypt the Nth previous password (1 = the one before the current)
+        #[arg(long, value_name = "N")]
+        reveal: Option<usize>,
+    },
+    /// Move a credential to the trash
     Remove {
         /// Credential UUID
         #[arg(long)]
@@ -81,6 +107,131 @@ pub enum CredentialCommand {
         #[arg(short, long)]
         yes: bool,
     },
+    /// List credentials in the trash
+    Trash {
+        /// Output as json/yaml
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+    /// Take a credential out of the trash
+    Restore {
+        /// Credential UUID
+        #[arg(long)]
+        id: Uuid,
+    },
+    /// Permanently delete trashed credentials
+    EmptyTrash {
+        /// Only purge credentials trashed more than this many days ago
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u32>,
+        /// Skip confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Link a TOTP credential to a password credential
+    LinkTotp {
+        /// Password credential UUID
+        #[arg(long)]
+        id: Uuid,
+        /// Two-factor (TOTP) credential UUID
+        #[arg(long)]
+        totp_id: Uuid,
+    },
+    /// Remove the TOTP link from a password credential
+    UnlinkTotp {
+        /// Password credential UUID
+        #[arg(long)]
+        id: Uuid,
+    },
+    /// Change the extra origins (besides its URL) the browser bridge may fill a credential on
+    SetOrigins {
+        /// Credential UUID
+        id: Uuid,
+        /// Origin or host to allow, e.g. github.community (repeatable)
+        #[arg(long, value_name = "ORIGIN")]
+        add: Vec<String>,
+        /// Origin or host to stop allowing (repeatable)
+        #[arg(long, value_name = "ORIGIN")]
+        remove: Vec<String>,
+        /// Remove every extra origin
+        #[arg(long, conflicts_with_all = ["add", "remove"])]
+        clear: bool,
+    },
+    /// Encrypt a file and attach it to a credential
+    Attach {
+        /// Credential UUID
+        #[arg(long)]
+        id: Uuid,
+        /// File to attach
+        file: PathBuf,
+        /// Name to store the file under (defaults to the file's name)
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// List the files attached to a credential
+    ListAttachments {
+        /// Credential UUID
+        #[arg(long)]
+        id: Uuid,
+    },
+    /// Decrypt an attachment and write it to a file
+    Extract {
+        /// Attachment UUID
+        #[arg(long)]
+        attachment: Uuid,
+        /// Output path (defaults to the attachment's filename in the current directory)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Overwrite the output file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Audit password credentials for reuse, weakness and age (or known breaches with --breaches)
+    Audit {
+        /// Passwords estimated below this many bits of entropy are reported as weak
+        #[arg(long, value_name = "BITS", default_value_t = DEFAULT_MIN_PASSWORD_ENTROPY_BITS)]
+        min_entropy: f64,
+        /// Passwords not replaced for more than this many days are reported as stale
+        #[arg(long, value_name = "DAYS", default_value_t = DEFAULT_PASSWORD_MAX_AGE_DAYS)]
+        max_age: i64,
+        /// Print the report as JSON
+        #[arg(long, conflicts_with = "breaches")]
+        json: bool,
+        /// Report password credentials that appear in known breaches and how often instead
+        #[arg(long)]
+        breaches: bool,
+        /// Query the HIBP range API (sends only a 5-character SHA-1 prefix per password)
+        #[arg(long, requires = "breaches")]
+        online: bool,
+        /// Offline breach database (defaults to `security.breach_db_path` or the workspace copy)
+        #[arg(long, value_name = "FILE", requires = "breaches")]
+        breach_db: Option<PathBuf>,
+        /// Timeout in seconds for each online request
+        #[arg(long, default_value_t = 10, requires = "online")]
+        timeout: u64,
+    },
+    /// Generate a password, optionally derived from the master password and a site
+    Generate {
+        /// Reproduce the same password from the master password, site and counter instead of
+        /// generating a random one
+        #[arg(long, requires = "site")]
+        deterministic: bool,
+        /// Site identifier, e.g. example.com
+        #[arg(long)]
+        site: Option<String>,
+        /// Bump to rotate a deterministic password without changing the master password
+        #[arg(long, default_value_t = 1)]
+        counter: u32,
+        /// Password length
+        #[arg(short, long, default_value_t = 20)]
+        length: usize,
+        /// Leave out digits
+        #[arg(long)]
+        no_digits: bool,
+        /// Leave out symbols
+        #[arg(long)]
+        no_symbols: bool,
+    },
 }
 
 #[derive(Clone, Debug, ValueEnum)]
@@ -133,6 +284,22 @@ impl From<SecurityLevelOption> for SecurityLevel {
     }
 }
 
+#[derive(Tabled)]
+struct AttachmentRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Filename")]
+    filename: String,
+    #[tabled(rename = "Type")]
+    mime_type: String,
+    #[tabled(rename = "Size")]
+    size: String,
+    #[tabled(rename = "SHA-256")]
+    content_hash: String,
+    #[tabled(rename = "Added")]
+    created_at: String,
+}
+
 #[derive(Tabled)]
 struct CredentialRow {
     #[tabled(rename = "ID")]
@@ -158,6 +325,7 @@ pub async fn execute(args: CredentialArgs, config: &CliConfig) -> Result<()> {
             security_level,
             username,
             url,
+            notes,
             prompt_secret,
             secret,
             favorite,
@@ -170,6 +338,7 @@ pub async fn execute(args: CredentialArgs, config: &CliConfig) -> Result<()> {
                 security_level,
                 username,
                 url,
+                notes,
                 prompt_secret,
                 secret,
                 favorite,
@@ -181,16 +350,176 @@ pub async fn execute(args: CredentialArgs, config: &CliConfig) -> Result<()> {
             credential_type,
             favorite,
             format,
-        } => list_credentials(config, identity, credential_type, favorite, format).await?,
+            include_trashed,
+        } => {
+            list_credentials(
+                config,
+                identity,
+                credential_type,
+                favorite,
+                format,
+                include_trashed,
+            )
+            .await?
+        }
         CredentialCommand::Show { id, reveal } => show_credential(config, id, reveal).await?,
+        CredentialCommand::History { id, reveal } => credential_history(config, id, reveal).await?,
         CredentialCommand::Remove { id, yes } => remove_credential(config, id, yes).await?,
+        CredentialCommand::Trash { format } => list_trash(config, format).await?,
+        CredentialCommand::Restore { id } => restore_credential(config, id).await?,
+        CredentialCommand::EmptyTrash { older_than, yes } => {
+            empty_trash(config, older_than, yes).await?
+        }
+        CredentialCommand::LinkTotp { id, totp_id } => link_totp(config, id, totp_id).await?,
+        CredentialCommand::UnlinkTotp { id } => unlink_totp(config, id).await?,
+        CredentialCommand::SetOrigins {
+            id,
+            add,
+            remove,
+            clear,
+        } => set_origins(config, id, add, remove, clear).await?,
+        CredentialCommand::Attach { id, file, name } => attach_file(config, id, file, name).await?,
+        CredentialCommand::ListAttachments { id } => list_attachments(config, id).await?,
+        CredentialCommand::Extract {
+            attachment,
+            output,
+            force,
+        } => extract_attachment(config, attachment, output, force).await?,
+        CredentialCommand::Audit {
+            min_entropy,
+            max_age,
+            json,
+            breaches,
+            online,
+            breach_db,
+            timeout,
+        } => match (breaches, online) {
+            (false, _) => {
+                let options = PasswordAuditOptions {
+                    min_entropy_bits: min_entropy,
+                    max_age_days: max_age,
+                };
+                audit_passwords(config, &options, json).await?
+            }
+            (true, false) => audit::audit_passwords(config, breach_db).await?,
+            (true, true) => {
+                audit::audit_passwords_online(config, breach_db, Duration::from_secs(timeout))
+                    .await?
+            }
+        },
+        CredentialCommand::Generate {
+            deterministic,
+            site,
+            counter,
+            length,
+            no_digits,
+            no_symbols,
+        } => {
+            let policy = SitePasswordPolicy {
+                length,
+                digits: !no_digits,
+                symbols: !no_symbols,
+                ..SitePasswordPolicy::default()
+            };
+            match site.filter(|_| deterministic) {
+                Some(site) => generate_site_password(config, &site, counter, &policy).await?,
+                None => generate_random_password(&policy)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn audit_passwords(
+    config: &CliConfig,
+    options: &PasswordAuditOptions,
+    json: bool,
+) -> Result<()> {
+    let service = init_service(config).await?;
+    let report = service
+        .password_audit_report(options)
+        .await
+        .into_anyhow()
+        .context("Failed to audit passwords")?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_password_audit(&report);
     }
     Ok(())
 }
 
+fn print_password_audit(report: &PasswordAuditReport) {
+    println!("🔍 Checked {} password credential(s)", report.checked);
+    if report.is_clean() {
+        println!("{} No reused, weak or stale passwords", "✓".green().bold());
+        return;
+    }
+
+    if !report.reused.is_empty() {
+        println!(
+            "\n{} Reused passwords ({} group(s)):",
+            "⚠️".yellow(),
+            report.reused.len()
+        );
+        for group in &report.reused {
+            let names: Vec<String> = group
+                .iter()
+                .map(|c| format!("{} ({})", c.name.bold(), c.credential_id))
+                .collect();
+            println!("  • {}", names.join(", "));
+        }
+    }
+    if !report.weak.is_empty() {
+        println!(
+            "\n{} Weak passwords (below {} bits):",
+            "⚠️".yellow(),
+            report.options.min_entropy_bits
+        );
+        for weak in &report.weak {
+            println!(
+                "  {} ({}) - {:.0} bits",
+                weak.credential.name.bold(),
+                weak.credential.credential_id,
+                weak.entropy_bits
+            );
+        }
+    }
+    if !report.stale.is_empty() {
+        println!(
+            "\n{} Stale passwords (not replaced in {} days):",
+            "⚠️".yellow(),
+            report.options.max_age_days
+        );
+        for stale in &report.stale {
+            println!(
+                "  {} ({}) - last changed {} days ago",
+                stale.credential.name.bold(),
+                stale.credential.credential_id,
+                stale.age_days
+            );
+        }
+    }
+}
+
 async fn init_service(config: &CliConfig) -> Result<PersonaService> {
+    let mut service = open_service(config).await?;
+    if !service.is_unlocked() {
+        auth::unlock(&mut service).await?;
+    }
+    Ok(service)
+}
+
+/// Open and configure the vault without unlocking it
+async fn open_service(config: &CliConfig) -> Result<PersonaService> {
+    if config.demo {
+        return PersonaService::demo()
+            .await
+            .into_anyhow()
+            .context("Failed to open demo vault");
+    }
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .into_anyhow()
         .with_context(|| format!("Failed to connect to database: {}", db_path.display()))?;
@@ -198,10 +527,24 @@ async fn init_service(config: &CliConfig) -> Result<PersonaService> {
         .await
         .into_anyhow()
         .context("Failed to run database migrations")?;
-    let mut service = PersonaService::new(db)
+    let mut service = PersonaService::new(db.clone())
         .await
         .into_anyhow()
         .context("Failed to create PersonaService")?;
+    service.set_note_secret_scanning(config.security.scan_notes_for_secrets);
+    service.set_secret_history_retention(config.security.password_history);
+    service.set_max_attachment_size(config.security.max_attachment_size);
+    service
+        .init_attachment_storage(config.get_attachments_path(), db)
+        .await
+        .into_anyhow()
+        .context("Failed to open attachment storage")?;
+    service.set_backup_before_destructive(config.destructive_backup_dir());
+    for schema in &config.credential_types {
+        service
+            .register_custom_credential_type(schema.clone())
+            .with_context(|| format!("Invalid custom credential type '{}'", schema.type_name))?;
+    }
 
     if service
         .has_users()
@@ -209,20 +552,12 @@ async fn init_service(config: &CliConfig) -> Result<PersonaService> {
         .into_anyhow()
         .context("Failed to check users")?
     {
-        let password = dialoguer::Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
-            .await
-            .into_anyhow()
-            .context("Failed to authenticate user")?
-        {
-            persona_core::auth::authentication::AuthResult::Success => Ok(service),
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+        Ok(service)
     } else {
-        anyhow::bail!("Workspace not initialized. Run `persona init` first");
+        Err(PersonaError::ConfigurationError(
+            "Workspace not initialized. Run `persona init` first".to_string(),
+        )
+        .into())
     }
 }
 
@@ -234,6 +569,7 @@ async fn add_credential(
     security_level: SecurityLevelOption,
     username: Option<String>,
     url: Option<String>,
+    notes: Option<String>,
     prompt_secret: bool,
     secret: Option<String>,
     favorite: bool,
@@ -257,31 +593,31 @@ async fn add_credential(
     };
 
     let credential_data = CredentialData::Password(PasswordCredentialData {
-        password: secret_value.clone(),
+        password: SecretString::new(secret_value),
         email: None,
         security_questions: Vec::new(),
     });
 
-    let mut created = service
-        .create_credential(
-            identity.id,
-            name.clone(),
-            credential_type.into(),
-            security_level.into(),
-            &credential_data,
-        )
+    let mut credential = Credential::new(
+        identity.id,
+        name.clone(),
+        credential_type.into(),
+        security_level.into(),
+        Vec::new(),
+        None,
+    );
+    credential.username = username.clone();
+    credential.url = url.clone();
+    credential.is_favorite = favorite;
+    credential.notes = notes.filter(|n| !n.trim().is_empty());
+    let result = service
+        .create_credential_checked(credential, &credential_data)
         .await
         .into_anyhow()
         .context("Failed to create credential")?;
-
-    created.username = username.clone();
-    created.url = url.clone();
-    created.is_favorite = favorite;
-    service
-        .update_credential(&created)
-        .await
-        .into_anyhow()
-        .context("Failed to update credential metadata")?;
+    for warning in &result.warnings {
+        println!("{} {}", "⚠".yellow(), warning.yellow());
+    }
 
     println!(
         "{} Created credential '{}' for identity '{}'",
@@ -299,10 +635,14 @@ async fn list_credentials(
     credential_type: Option<String>,
     favorite_only: bool,
     format: String,
+    include_trashed: bool,
 ) -> Result<()> {
     let mut service = init_service(config).await?;
-    let credentials = if let Some(identity_name) = identity_name {
-        let identity = resolve_identity(&mut service, &identity_name).await?;
+    let identity = match identity_name {
+        Some(identity_name) => Some(resolve_identity(&mut service, &identity_name).await?),
+        None => None,
+    };
+    let mut credentials = if let Some(identity) = &identity {
         service
             .get_credentials_for_identity(&identity.id)
             .await
@@ -315,6 +655,18 @@ async fn list_credentials(
             .into_anyhow()
             .context("Failed to fetch credentials")?
     };
+    if include_trashed {
+        let trashed = service
+            .get_trashed_credentials()
+            .await
+            .into_anyhow()
+            .context("Failed to fetch trashed credentials")?;
+        credentials.extend(
+            trashed
+                .into_iter()
+                .filter(|cred| identity.as_ref().is_none_or(|i| cred.identity_id == i.id)),
+        );
+    }
 
     let filtered: Vec<Credential> = credentials
         .into_iter()
@@ -347,7 +699,13 @@ async fn list_credentials(
                     credential_type: cred.credential_type.to_string(),
                     identity: cred.identity_id.to_string(),
                     username: cred.username.clone().unwrap_or_default(),
-                    favorite: if cred.is_favorite { "★" } else { "" }.into(),
+                    favorite: if cred.is_trashed() {
+                        "trashed".dimmed().to_string()
+                    } else if cred.is_favorite {
+                        "★".into()
+                    } else {
+                        String::new()
+                    },
                 })
                 .collect();
             println!("{}", Table::new(rows));
@@ -370,7 +728,7 @@ async fn show_credential(config: &CliConfig, id: Uuid, reveal: bool) -> Result<(
         .get_credential(&id)
         .await
         .into_anyhow()?
-        .ok_or_else(|| anyhow!("Credential {} not found", id))?;
+        .ok_or_else(|| PersonaError::NotFound(format!("Credential {} not found", id)))?;
     println!("{} {}", "Credential:".bold(), credential.name.cyan());
     println!("  ID: {}", credential.id);
     println!("  Type: {}", credential.credential_type);
@@ -386,6 +744,45 @@ async fn show_credential(config: &CliConfig, id: Uuid, reveal: bool) -> Result<(
         if credential.is_favorite { "yes" } else { "no" }
     );
     println!("  Security level: {}", credential.security_level);
+    if let Some(score) = service.credential_security_score(&id).await.into_anyhow()? {
+        print_security_score(&score);
+    }
+    if let Some(totp_id) = credential.linked_totp_id {
+        println!("  Linked 2FA: {}", totp_id);
+        if let Some(code) = service.linked_totp_code(&id).await.into_anyhow()? {
+            println!(
+                "  Current code: {} ({}s remaining)",
+                code.code.green().bold(),
+                code.remaining
+            );
+        }
+    }
+
+    if let CredentialType::Custom(type_name) = &credential.credential_type {
+        if service.custom_credential_types().get(type_name).is_some() {
+            let reveal = reveal
+                && dialoguer::Confirm::new()
+                    .with_prompt("Reveal secret fields? (visible on screen)")
+                    .interact()?;
+            if let Some(view) = service
+                .format_custom_credential(&id, reveal)
+                .await
+                .into_anyhow()?
+            {
+                if let Some(summary) = &view.summary {
+                    println!("  Summary: {}", summary);
+                }
+                for field in &view.fields {
+                    if field.secret && reveal {
+                        println!("  {}: {}", field.label, field.value.blue());
+                    } else {
+                        println!("  {}: {}", field.label, field.value);
+                    }
+                }
+            }
+            return Ok(());
+        }
+    }
 
     if reveal {
         let confirm = dialoguer::Confirm::new()
@@ -395,16 +792,17 @@ async fn show_credential(config: &CliConfig, id: Uuid, reveal: bool) -> Result<(
             if let Some(data) = service.get_credential_data(&id).await.into_anyhow()? {
                 match data {
                     CredentialData::Password(password) => {
-                        println!("  Password: {}", password.password.blue());
+                        println!("  Password: {}", password.password.expose().blue());
                     }
                     CredentialData::ApiKey(api) => {
-                        println!("  API Key: {}", api.api_key.blue());
+                        println!("  API Key: {}", api.api_key.expose().blue());
                     }
                     CredentialData::SshKey(ssh) => {
-                        println!("  Private Key: {}", ssh.private_key);
+                        println!("  Private Key: {}", ssh.private_key.expose());
                     }
                     other => {
-                        println!("  Data: {:?}", other);
+                        // Debug output redacts secrets, so print the canonical encoding instead
+                        println!("  Data: {}", other.to_canonical_json()?["data"]);
                     }
                 }
             }
@@ -413,11 +811,94 @@ async fn show_credential(config: &CliConfig, id: Uuid, reveal: bool) -> Result<(
     Ok(())
 }
 
+async fn credential_history(config: &CliConfig, id: Uuid, reveal: Option<usize>) -> Result<()> {
+    let service = init_service(config).await?;
+    let credential = service
+        .get_credential(&id)
+        .await
+        .into_anyhow()?
+        .ok_or_else(|| PersonaError::NotFound(format!("Credential {} not found", id)))?;
+    let history = service.previous_secret_history(&id).await.into_anyhow()?;
+
+    let Some(n) = reveal else {
+        println!(
+            "{} {}",
+            "Previous passwords:".bold(),
+            credential.name.cyan()
+        );
+        if history.is_empty() {
+            println!("  {}", "None kept".dimmed());
+        }
+        for (index, replaced_at) in history.iter().enumerate() {
+            println!(
+                "  {}  replaced {}",
+                index + 1,
+                replaced_at.format("%Y-%m-%d %H:%M UTC")
+            );
+        }
+        return Ok(());
+    };
+
+    if n == 0 || n > history.len() {
+        anyhow::bail!(
+            "Credential '{}' has {} previous password(s) kept; choose 1 to {}",
+            credential.name,
+            history.len(),
+            history.len().max(1)
+        );
+    }
+    if !confirm_action(
+        &format!("Reveal previous password #{}? (visible on screen)", n),
+        true,
+    )? {
+        return Ok(());
+    }
+    match service.get_previous_secret(&id, n).await.into_anyhow()? {
+        Some(CredentialData::Password(password)) => {
+            println!("  Password #{}: {}", n, password.password.expose().blue());
+        }
+        Some(other) => println!("  Data #{}: {}", n, other.to_canonical_json()?["data"]),
+        None => anyhow::bail!("Previous password #{} is no longer kept", n),
+    }
+    Ok(())
+}
+
+fn print_security_score(score: &CredentialSecurityScore) {
+    let label = format!("{}/100", score.score);
+    let label = match score.score {
+        80..=100 => label.green(),
+        50..=79 => label.yellow(),
+        _ => label.red(),
+    };
+    println!("  Security score: {}", label);
+
+    let context = &score.context;
+    if context
+        .password_entropy_bits
+        .is_some_and(|bits| bits < 60.0)
+    {
+        println!("    - {}", "weak password".yellow());
+    }
+    if context.reuse_count > 0 {
+        println!(
+            "    - {}",
+            format!(
+                "password reused by {} other credential(s)",
+                context.reuse_count
+            )
+            .yellow()
+        );
+    }
+    if !context.has_two_factor {
+        println!("    - no linked 2FA");
+    }
+}
+
 async fn remove_credential(config: &CliConfig, id: Uuid, yes: bool) -> Result<()> {
-    let mut service = init_service(config).await?;
+    let service = init_service(config).await?;
     if !yes {
         let confirm = dialoguer::Confirm::new()
-            .with_prompt(format!("Remove credential {}?", id))
+            .with_prompt(format!("Move credential {} to the trash?", id))
             .default(false)
             .interact()?;
         if !confirm {
@@ -425,19 +906,367 @@ async fn remove_credential(config: &CliConfig, id: Uuid, yes: bool) -> Result<()
             return Ok(());
         }
     }
-    let deleted = service.delete_credential(&id).await.into_anyhow()?;
-    if deleted {
-        println!("{} Removed credential {}", "✓".green(), id);
+    if service.delete_credential(&id).await.into_anyhow()? {
+        println!("{} Moved credential {} to the trash", "✓".green(), id);
+        println!(
+            "  Undo with {}",
+            format!("persona credential restore --id {}", id).cyan()
+        );
     } else {
         println!("{} Credential {} not found", "⚠".yellow(), id);
     }
     Ok(())
 }
 
+#[derive(Tabled)]
+struct TrashRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Type")]
+    credential_type: String,
+    #[tabled(rename = "Identity")]
+    identity: String,
+    #[tabled(rename = "Deleted")]
+    deleted_at: String,
+}
+
+async fn list_trash(config: &CliConfig, format: String) -> Result<()> {
+    let service = init_service(config).await?;
+    let trashed = service
+        .get_trashed_credentials()
+        .await
+        .into_anyhow()
+        .context("Failed to fetch trashed credentials")?;
+
+    match format.as_str() {
+        "table" => {
+            if trashed.is_empty() {
+                println!("{}", "The trash is empty.".yellow());
+                return Ok(());
+            }
+            let rows: Vec<TrashRow> = trashed
+                .iter()
+                .map(|cred| TrashRow {
+                    id: cred.id.to_string(),
+                    name: cred.name.clone(),
+                    credential_type: cred.credential_type.to_string(),
+                    identity: cred.identity_id.to_string(),
+                    deleted_at: cred
+                        .deleted_at
+                        .map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_default(),
+                })
+                .collect();
+            println!("{}", Table::new(rows));
+        }
+        "json" => println!("{}", serde_json::to_string_pretty(&trashed)?),
+        "yaml" => println!("{}", serde_yaml::to_string(&trashed)?),
+        other => anyhow::bail!("Unsupported format: {}", other),
+    }
+    Ok(())
+}
+
+async fn restore_credential(config: &CliConfig, id: Uuid) -> Result<()> {
+    let service = init_service(config).await?;
+    if service.restore_credential(&id).await.into_anyhow()? {
+        println!("{} Restored credential {}", "✓".green(), id);
+    } else {
+        println!("{} Credential {} is not in the trash", "⚠".yellow(), id);
+    }
+    Ok(())
+}
+
+async fn empty_trash(config: &CliConfig, older_than: Option<u32>, yes: bool) -> Result<()> {
+    let service = init_service(config).await?;
+    if !yes {
+        let prompt = match older_than {
+            Some(days) => format!(
+                "Permanently delete credentials trashed more than {} day(s) ago?",
+                days
+            ),
+            None => "Permanently delete every credential in the trash?".to_string(),
+        };
+        if !dialoguer::Confirm::new()
+            .with_prompt(prompt)
+            .default(false)
+            .interact()?
+        {
+            println!("{}", "Aborted.".yellow());
+            return Ok(());
+        }
+    }
+    let mut events = service.subscribe_events();
+    let purged = service
+        .purge_trash(chrono::Duration::days(older_than.unwrap_or(0).into()))
+        .await
+        .into_anyhow()?;
+    report_safety_backups(&mut events);
+    println!(
+        "{} Permanently deleted {} credential(s) from the trash",
+        "✓".green(),
+        purged
+    );
+    Ok(())
+}
+
+async fn link_totp(config: &CliConfig, id: Uuid, totp_id: Uuid) -> Result<()> {
+    let service = init_service(config).await?;
+    let credential = service.link_totp(&id, &totp_id).await.into_anyhow()?;
+    println!(
+        "{} Linked TOTP {} to {}",
+        "✓".green(),
+        totp_id,
+        credential.name.cyan()
+    );
+    Ok(())
+}
+
+async fn unlink_totp(config: &CliConfig, id: Uuid) -> Result<()> {
+    let service = init_service(config).await?;
+    let credential = service.unlink_totp(&id).await.into_anyhow()?;
+    println!(
+        "{} Removed TOTP link from {}",
+        "✓".green(),
+        credential.name.cyan()
+    );
+    Ok(())
+}
+
+async fn set_origins(
+    config: &CliConfig,
+    id: Uuid,
+    add: Vec<String>,
+    remove: Vec<String>,
+    clear: bool,
+) -> Result<()> {
+    let service = init_service(config).await?;
+    let mut credential = service
+        .get_credential(&id)
+        .await
+        .into_anyhow()?
+        .ok_or_else(|| PersonaError::NotFound(format!("Credential {} not found", id)))?;
+
+    let mut origins = if clear {
+        Vec::new()
+    } else {
+        credential.allowed_origins()
+    };
+    for origin in &remove {
+        let host = origin_host(origin)?;
+        origins.retain(|existing| *existing != host);
+    }
+    for origin in &add {
+        let host = origin_host(origin)?;
+        if is_public_suffix(&host) {
+            return Err(anyhow!(
+                "'{}' is a public suffix shared by unrelated sites; allow a domain under it instead",
+                host
+            ));
+        }
+        if !origins.contains(&host) {
+            origins.push(host);
+        }
+    }
+
+    if origins != credential.allowed_origins() {
+        credential.set_allowed_origins(origins.clone());
+        service
+            .update_credential(&credential)
+            .await
+            .into_anyhow()
+            .context("Failed to update credential")?;
+    }
+    println!(
+        "{} Allowed origins for {}",
+        "✓".green(),
+        credential.name.cyan()
+    );
+    match &credential.url {
+        Some(url) => println!("  {} {}", url, "(URL)".dimmed()),
+        None => println!("  {}", "No URL set".dimmed()),
+    }
+    for origin in &origins {
+        println!("  {}", origin);
+    }
+    Ok(())
+}
+
+/// Host of an origin given as a URL or a bare host name, lowercased
+fn origin_host(origin: &str) -> Result<String> {
+    let trimmed = origin.trim();
+    let url = match url::Url::parse(trimmed) {
+        Ok(url) if url.has_host() => url,
+        _ => url::Url::parse(&format!("https://{trimmed}"))
+            .with_context(|| format!("Invalid origin '{}'", origin))?,
+    };
+    url.host_str()
+        .filter(|host| !host.is_empty())
+        .map(str::to_lowercase)
+        .ok_or_else(|| anyhow!("Invalid origin '{}'", origin))
+}
+
+/// Whether `host` is itself a public suffix ("com", "co.uk", "github.io"), not a site
+fn is_public_suffix(host: &str) -> bool {
+    host.parse::<std::net::IpAddr>().is_err() && psl::suffix_str(host) == Some(host)
+}
+
+async fn attach_file(
+    config: &CliConfig,
+    id: Uuid,
+    file: PathBuf,
+    name: Option<String>,
+) -> Result<()> {
+    let filename = match name {
+        Some(name) => name,
+        None => file
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow!("Cannot determine a filename for {}", file.display()))?
+            .to_string(),
+    };
+    let size = std::fs::metadata(&file)
+        .with_context(|| format!("Failed to read {}", file.display()))?
+        .len();
+    if size > config.security.max_attachment_size {
+        anyhow::bail!(
+            "{} is {} bytes; attachments are limited to {} bytes (security.max_attachment_size)",
+            file.display(),
+            size,
+            config.security.max_attachment_size
+        );
+    }
+    let content = zeroize::Zeroizing::new(
+        std::fs::read(&file).with_context(|| format!("Failed to read {}", file.display()))?,
+    );
+
+    let mut service = init_service(config).await?;
+    let credential = service
+        .get_credential(&id)
+        .await
+        .into_anyhow()?
+        .ok_or_else(|| PersonaError::NotFound(format!("Credential {} not found", id)))?;
+    let attachment_id = service
+        .add_attachment(id, &filename, &content)
+        .await
+        .into_anyhow()
+        .context("Failed to attach file")?;
+
+    println!(
+        "{} Attached '{}' to {} ({})",
+        "✓".green(),
+        filename.bright_green(),
+        credential.name.cyan(),
+        attachment_id
+    );
+    Ok(())
+}
+
+async fn list_attachments(config: &CliConfig, id: Uuid) -> Result<()> {
+    let service = init_service(config).await?;
+    let attachments = service.list_attachments(&id).await.into_anyhow()?;
+    if attachments.is_empty() {
+        println!("{}", "No attachments.".yellow());
+        return Ok(());
+    }
+
+    let rows: Vec<AttachmentRow> = attachments
+        .iter()
+        .map(|attachment| AttachmentRow {
+            id: attachment.id.to_string(),
+            filename: attachment.filename.clone(),
+            mime_type: attachment.mime_type.clone(),
+            size: attachment.size.to_string(),
+            content_hash: attachment.content_hash.chars().take(16).collect(),
+            created_at: attachment.created_at.format("%Y-%m-%d %H:%M").to_string(),
+        })
+        .collect();
+    println!("{}", Table::new(rows));
+    Ok(())
+}
+
+async fn extract_attachment(
+    config: &CliConfig,
+    attachment_id: Uuid,
+    output: Option<PathBuf>,
+    force: bool,
+) -> Result<()> {
+    let service = init_service(config).await?;
+    let (attachment, content) = service
+        .get_attachment(&attachment_id)
+        .await
+        .into_anyhow()?
+        .ok_or_else(|| PersonaError::NotFound(format!("Attachment {} not found", attachment_id)))?;
+    let content = zeroize::Zeroizing::new(content);
+
+    // Stored names are reduced to a bare filename, so this stays in the current directory
+    let output = output.unwrap_or_else(|| PathBuf::from(&attachment.filename));
+    if output.exists() && !force {
+        anyhow::bail!(
+            "{} already exists; pass --force to overwrite it",
+            output.display()
+        );
+    }
+    std::fs::write(&output, content.as_slice())
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    println!(
+        "{} Extracted '{}' to {} (SHA-256 verified)",
+        "✓".green(),
+        attachment.filename.bright_green(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Derive the stateless password for `site`. The master password is checked against the vault
+/// first so a typo can't silently produce a different password.
+async fn generate_site_password(
+    config: &CliConfig,
+    site: &str,
+    counter: u32,
+    policy: &SitePasswordPolicy,
+) -> Result<()> {
+    let mut service = open_service(config).await?;
+    let master_password = auth::read_master_password("Enter master password")?;
+    if !service.is_unlocked() {
+        auth::unlock_with_password(&mut service, &master_password).await?;
+    }
+    let password = service.derive_site_password(&master_password, site, counter, policy)?;
+
+    println!(
+        "{} Password for {} (counter {}): {}",
+        "✓".green().bold(),
+        site.bright_green(),
+        counter,
+        password.expose().cyan().bold()
+    );
+    Ok(())
+}
+
+fn generate_random_password(policy: &SitePasswordPolicy) -> Result<()> {
+    let options = PasswordGeneratorOptions {
+        length: policy.length,
+        include_lowercase: policy.lowercase,
+        include_uppercase: policy.uppercase,
+        include_numbers: policy.digits,
+        include_symbols: policy.symbols,
+        pronounceable: false,
+    };
+    let generated = PasswordGenerator::generate_with_entropy(&options)?;
+    println!(
+        "{} Generated password: {}",
+        "✓".green().bold(),
+        generated.password.cyan().bold()
+    );
+    Ok(())
+}
+
 async fn resolve_identity(service: &mut PersonaService, name: &str) -> Result<Identity> {
     service
         .get_identity_by_name(name)
         .await
         .into_anyhow()?
-        .ok_or_else(|| anyhow!("Identity '{}' not found", name))
+        .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()).into())
 }
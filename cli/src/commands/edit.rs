@@ -1,15 +1,17 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use clap::Args;
 use colored::*;
 use dialoguer::{Confirm, Input, MultiSelect, Select};
 use serde_json::Value;
 use std::collections::HashMap;
 
+use crate::commands::auth;
 use crate::config::CliConfig;
+use crate::utils::open_database;
 use persona_core::{
     models::{Identity as CoreIdentity, IdentityType},
     storage::IdentityRepository,
-    Database, PersonaService,
+    PersonaError, PersonaService,
 };
 use uuid::Uuid;
 
@@ -57,7 +59,7 @@ pub async fn execute(args: EditArgs, config: &CliConfig) -> Result<()> {
 
     // Check if identity exists
     if !identity_exists(&args.name, config).await? {
-        anyhow::bail!("Identity '{}' not found", args.name);
+        return Err(PersonaError::IdentityNotFound(args.name.clone()).into());
     }
 
     // Load current identity data
@@ -119,7 +121,7 @@ struct Identity {
 
 async fn identity_exists(name: &str, config: &CliConfig) -> Result<bool> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -133,22 +135,12 @@ async fn identity_exists(name: &str, config: &CliConfig) -> Result<bool> {
         .await
         .map_err(|e| anyhow::anyhow!("Failed to check users: {}", e))?
     {
-        use dialoguer::Password;
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        Ok(service
+            .get_identity_by_name(name)
             .await
-            .map_err(|e| anyhow::anyhow!("Auth failed: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => Ok(service
-                .get_identity_by_name(name)
-                .await
-                .map_err(|e| anyhow::anyhow!("Lookup failed: {}", e))?
-                .is_some()),
-            _ => Ok(false),
-        }
+            .map_err(|e| anyhow::anyhow!("Lookup failed: {}", e))?
+            .is_some())
     } else {
         Ok(IdentityRepository::new(db)
             .find_by_name(name)
@@ -160,7 +152,7 @@ async fn identity_exists(name: &str, config: &CliConfig) -> Result<bool> {
 
 async fn load_identity(name: &str, config: &CliConfig) -> Result<Identity> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -174,28 +166,18 @@ async fn load_identity(name: &str, config: &CliConfig) -> Result<Identity> {
         .await
         .map_err(|e| anyhow::anyhow!("Failed to check users: {}", e))?
     {
-        use dialoguer::Password;
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        service
+            .get_identity_by_name(name)
             .await
-            .map_err(|e| anyhow::anyhow!("Auth failed: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => service
-                .get_identity_by_name(name)
-                .await
-                .map_err(|e| anyhow::anyhow!("Lookup failed: {}", e))?
-                .with_context(|| format!("Identity '{}' not found", name))?,
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+            .map_err(|e| anyhow::anyhow!("Lookup failed: {}", e))?
+            .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()))?
     } else {
         IdentityRepository::new(db)
             .find_by_name(name)
             .await
             .map_err(|e| anyhow::anyhow!("Lookup failed: {}", e))?
-            .with_context(|| format!("Identity '{}' not found", name))?
+            .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()))?
     };
 
     Ok(Identity {
@@ -580,9 +562,8 @@ fn show_changes_summary(identity: &Identity) -> Result<()> {
 }
 
 async fn save_identity(identity: &Identity, config: &CliConfig) -> Result<()> {
-    use dialoguer::Password;
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -596,17 +577,7 @@ async fn save_identity(identity: &Identity, config: &CliConfig) -> Result<()> {
         .await
         .map_err(|e| anyhow::anyhow!("Failed to check users: {}", e))?
     {
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
-            .await
-            .map_err(|e| anyhow::anyhow!("Auth failed: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => {}
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+        auth::unlock(&mut service).await?;
     }
 
     // Load current identity (by id if present)
@@ -615,13 +586,13 @@ async fn save_identity(identity: &Identity, config: &CliConfig) -> Result<()> {
             .get_identity(&id)
             .await
             .map_err(|e| anyhow::anyhow!("Lookup failed: {}", e))?
-            .with_context(|| format!("Identity not found by id {}", id))?
+            .ok_or_else(|| PersonaError::IdentityNotFound(id.to_string()))?
     } else {
         service
             .get_identity_by_name(&identity.name)
             .await
             .map_err(|e| anyhow::anyhow!("Lookup failed: {}", e))?
-            .with_context(|| "Identity not found".to_string())?
+            .ok_or_else(|| PersonaError::IdentityNotFound(identity.name.clone()))?
     };
 
     // Apply changes
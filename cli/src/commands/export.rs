@@ -1,15 +1,27 @@
 use anyhow::{anyhow, Context, Result};
-use clap::Args;
+use clap::{Args, ValueEnum};
 use colored::*;
 use dialoguer::{Confirm, MultiSelect};
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::sync::Arc;
 
+use crate::utils::open_database;
+use crate::commands::auth;
 use crate::config::CliConfig;
-use crate::utils::file_crypto::encrypt_file_inplace;
+use crate::utils::file_crypto::{
+    encrypt_file_inplace, EncryptionParams, ExportCipher, ExportManifest, KdfCost,
+};
 use crate::utils::progress::create_progress_bar;
+use crate::utils::vault_archive::{
+    ArchivedAttachment, ArchivedCredential, ArchivedIdentity, VaultArchive,
+    VAULT_ARCHIVE_EXTENSION,
+};
+use crate::utils::core_ext::CoreResultExt;
 use dialoguer::Password;
+use persona_core::storage::CryptoWalletRepository;
 use persona_core::Repository;
-use persona_core::{Database, PersonaService};
+use persona_core::{Credential, CredentialData, PersonaError, PersonaService};
 
 #[derive(Args)]
 pub struct ExportArgs {
@@ -20,9 +32,9 @@ pub struct ExportArgs {
     #[arg(short, long)]
     output: Option<PathBuf>,
 
-    /// Export format (json, yaml, csv)
-    #[arg(short, long, default_value = "json")]
-    format: String,
+    /// Export format (json, yaml, csv, vault)
+    #[arg(short, long, value_enum, default_value_t = ExportFormat::Json)]
+    format: ExportFormat,
 
     /// Include sensitive data
     #[arg(long)]
@@ -32,6 +44,22 @@ pub struct ExportArgs {
     #[arg(short, long)]
     encrypt: bool,
 
+    /// Key derivation cost for encryption (standard, high, max)
+    #[arg(long, value_enum, default_value_t = KdfCost::Standard, requires = "encrypt")]
+    kdf_cost: KdfCost,
+
+    /// Argon2 memory in MiB, overriding --kdf-cost
+    #[arg(long, requires = "encrypt")]
+    kdf_memory: Option<u32>,
+
+    /// Argon2 passes, overriding --kdf-cost
+    #[arg(long, requires = "encrypt")]
+    kdf_iterations: Option<u32>,
+
+    /// Cipher for encryption (aes-256-gcm, chacha20-poly1305)
+    #[arg(long, value_enum, default_value_t = ExportCipher::Aes256Gcm, requires = "encrypt")]
+    cipher: ExportCipher,
+
     /// Compression level (0-9, 0=no compression)
     #[arg(long, default_value = "6")]
     compression: u8,
@@ -39,6 +67,104 @@ pub struct ExportArgs {
     /// Interactive selection mode
     #[arg(short, long)]
     interactive: bool,
+
+    /// Credential columns for CSV exports [default: name,url,username,type,identity,updated_at]
+    #[arg(long, value_enum, value_delimiter = ',')]
+    fields: Option<Vec<CsvField>>,
+
+    /// Add a `secret` column with decrypted secrets to CSV exports
+    #[arg(long)]
+    include_secrets: bool,
+}
+
+/// A column of the credential CSV inventory; secrets are never one of them
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CsvField {
+    Id,
+    Name,
+    Identity,
+    Type,
+    Url,
+    Username,
+    Tags,
+    #[value(name = "security_level")]
+    SecurityLevel,
+    Favorite,
+    #[value(name = "created_at")]
+    CreatedAt,
+    #[value(name = "updated_at")]
+    UpdatedAt,
+}
+
+const DEFAULT_CSV_FIELDS: [CsvField; 6] = [
+    CsvField::Name,
+    CsvField::Url,
+    CsvField::Username,
+    CsvField::Type,
+    CsvField::Identity,
+    CsvField::UpdatedAt,
+];
+
+impl CsvField {
+    fn header(self) -> &'static str {
+        match self {
+            CsvField::Id => "id",
+            CsvField::Name => "name",
+            CsvField::Identity => "identity",
+            CsvField::Type => "type",
+            CsvField::Url => "url",
+            CsvField::Username => "username",
+            CsvField::Tags => "tags",
+            CsvField::SecurityLevel => "security_level",
+            CsvField::Favorite => "favorite",
+            CsvField::CreatedAt => "created_at",
+            CsvField::UpdatedAt => "updated_at",
+        }
+    }
+
+    fn value(self, identity: &str, credential: &Credential) -> String {
+        match self {
+            CsvField::Id => credential.id.to_string(),
+            CsvField::Name => credential.name.clone(),
+            CsvField::Identity => identity.to_string(),
+            CsvField::Type => credential.credential_type.to_string(),
+            CsvField::Url => credential.url.clone().unwrap_or_default(),
+            CsvField::Username => credential.username.clone().unwrap_or_default(),
+            CsvField::Tags => credential.tags.join(";"),
+            CsvField::SecurityLevel => credential.security_level.to_string(),
+            CsvField::Favorite => credential.is_favorite.to_string(),
+            CsvField::CreatedAt => credential.created_at.to_rfc3339(),
+            CsvField::UpdatedAt => credential.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Yaml,
+    Csv,
+    /// Encrypted `.personavault` archive with credentials, attachments and wallets, for
+    /// moving a vault to another machine
+    Vault,
+}
+
+impl ExportFormat {
+    fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Yaml => "yaml",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Vault => "vault",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Vault => VAULT_ARCHIVE_EXTENSION,
+            other => other.name(),
+        }
+    }
 }
 
 pub async fn execute(args: ExportArgs, config: &CliConfig) -> Result<()> {
@@ -59,12 +185,24 @@ pub async fn execute(args: ExportArgs, config: &CliConfig) -> Result<()> {
         println!("{}", "No identities to export.".yellow());
         return Ok(());
     }
+    if (args.fields.is_some() || args.include_secrets) && args.format != ExportFormat::Csv {
+        return Err(PersonaError::InvalidInput(
+            "--fields and --include-secrets only apply to --format csv".to_string(),
+        )
+        .into());
+    }
+
+    // Check encryption parameters before asking anything; vault archives are always sealed
+    let vault = args.format == ExportFormat::Vault;
+    let encryption = (args.encrypt || vault)
+        .then(|| encryption_params(&args))
+        .transpose()?;
 
     // Determine output file
     let output_path = determine_output_path(&args, &identity_names)?;
 
     // Show export summary
-    show_export_summary(&identity_names, &output_path, &args)?;
+    show_export_summary(&identity_names, &output_path, &args, encryption.as_ref())?;
 
     // Confirm export
     if !Confirm::new()
@@ -76,8 +214,8 @@ pub async fn execute(args: ExportArgs, config: &CliConfig) -> Result<()> {
         return Ok(());
     }
 
-    // Warn about sensitive data
-    if args.include_sensitive {
+    // Warn about sensitive data; vault archives never hold it in plaintext
+    if (args.include_sensitive && !vault) || args.include_secrets {
         println!();
         println!(
             "{}",
@@ -96,7 +234,7 @@ pub async fn execute(args: ExportArgs, config: &CliConfig) -> Result<()> {
     }
 
     // Perform export
-    perform_export(&identity_names, &output_path, &args, config).await?;
+    perform_export(&identity_names, &output_path, &args, encryption, config).await?;
 
     println!();
     println!("{} Export completed successfully!", "✓".green().bold());
@@ -131,7 +269,7 @@ async fn select_identities_interactive(config: &CliConfig) -> Result<Vec<String>
 
 async fn get_all_identity_names(config: &CliConfig) -> Result<Vec<String>> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -145,20 +283,11 @@ async fn get_all_identity_names(config: &CliConfig) -> Result<Vec<String>> {
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        service
+            .get_identities()
             .await
-            .map_err(|e| anyhow!("Auth failed: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => service
-                .get_identities()
-                .await
-                .map_err(|e| anyhow!("Failed to fetch identities: {}", e))?,
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+            .map_err(|e| anyhow!("Failed to fetch identities: {}", e))?
     } else {
         // If no users configured yet, read via repository
         persona_core::storage::IdentityRepository::new(db)
@@ -174,7 +303,7 @@ async fn validate_identity_names(names: &[String], config: &CliConfig) -> Result
 
     for name in names {
         if !all_identities.contains(name) {
-            anyhow::bail!("Identity '{}' not found", name);
+            return Err(PersonaError::IdentityNotFound(name.to_string()).into());
         }
     }
 
@@ -191,14 +320,16 @@ fn determine_output_path(args: &ExportArgs, identity_names: &[String]) -> Result
     let filename = if identity_names.len() == 1 {
         format!(
             "persona_export_{}_{}.{}",
-            identity_names[0], timestamp, args.format
+            identity_names[0],
+            timestamp,
+            args.format.extension()
         )
     } else {
         format!(
             "persona_export_{}_{}.{}",
             identity_names.len(),
             timestamp,
-            args.format
+            args.format.extension()
         )
     };
 
@@ -209,6 +340,7 @@ fn show_export_summary(
     identity_names: &[String],
     output_path: &PathBuf,
     args: &ExportArgs,
+    encryption: Option<&EncryptionParams>,
 ) -> Result<()> {
     println!("{}", "Export Summary:".yellow().bold());
     println!(
@@ -220,24 +352,32 @@ fn show_export_summary(
         "  Output file: {}",
         output_path.display().to_string().cyan()
     );
-    println!("  Format: {}", args.format.cyan());
-    println!(
-        "  Include sensitive: {}",
-        if args.include_sensitive {
-            "Yes".red()
-        } else {
-            "No".green()
-        }
-    );
-    println!(
-        "  Encryption: {}",
-        if args.encrypt {
-            "Yes".green()
-        } else {
-            "No".dimmed()
+    println!("  Format: {}", args.format.name().cyan());
+    if args.format == ExportFormat::Vault {
+        println!(
+            "  Contents: {}",
+            "credentials, attachments and wallets, sealed under a passphrase".green()
+        );
+    } else {
+        if args.format == ExportFormat::Csv {
+            let fields = args.fields.as_deref().unwrap_or(&DEFAULT_CSV_FIELDS);
+            let headers: Vec<&str> = fields.iter().map(|field| field.header()).collect();
+            println!("  Fields: {}", headers.join(",").cyan());
         }
-    );
-    if args.compression > 0 {
+        println!(
+            "  Include sensitive: {}",
+            if args.include_sensitive || args.include_secrets {
+                "Yes".red()
+            } else {
+                "No".green()
+            }
+        );
+    }
+    match encryption {
+        Some(params) => println!("  Encryption: {}", params.to_string().green()),
+        None => println!("  Encryption: {}", "No".dimmed()),
+    }
+    if args.compression > 0 && args.format != ExportFormat::Vault {
         println!(
             "  Compression: Level {}",
             args.compression.to_string().cyan()
@@ -252,6 +392,7 @@ async fn perform_export(
     identity_names: &[String],
     output_path: &PathBuf,
     args: &ExportArgs,
+    encryption: Option<EncryptionParams>,
     config: &CliConfig,
 ) -> Result<()> {
     let pb = create_progress_bar(identity_names.len() as u64, "Exporting identities");
@@ -262,12 +403,17 @@ async fn perform_export(
     }
 
     // Export based on format
-    match args.format.as_str() {
-        "json" => export_json(identity_names, output_path, args, config, &pb).await?,
-        "yaml" => export_yaml(identity_names, output_path, args, config, &pb).await?,
-        "csv" => export_csv(identity_names, output_path, args, config, &pb).await?,
-        _ => anyhow::bail!("Unsupported export format: {}", args.format),
-    }
+    let credentials_count = match args.format {
+        ExportFormat::Json => export_json(identity_names, output_path, args, config, &pb).await?,
+        ExportFormat::Yaml => export_yaml(identity_names, output_path, args, config, &pb).await?,
+        ExportFormat::Csv => export_csv(identity_names, output_path, args, config, &pb).await?,
+        ExportFormat::Vault => {
+            let params = encryption.context("Vault archives are always encrypted")?;
+            export_vault(identity_names, output_path, args, params, config).await?;
+            pb.finish_with_message("Export completed");
+            return Ok(());
+        }
+    };
 
     pb.finish_with_message("Export completed");
 
@@ -277,27 +423,164 @@ async fn perform_export(
     }
 
     // Apply encryption if requested
-    if args.encrypt {
+    if let Some(params) = encryption {
         let passphrase = Password::new()
             .with_prompt("Enter export passphrase")
             .with_confirmation("Confirm passphrase", "Passphrases do not match")
             .interact()?;
-        encrypt_file_inplace(output_path, &passphrase, None)?;
+        let manifest =
+            ExportManifest::new(args.format.name(), identity_names.len(), credentials_count);
+        encrypt_file_inplace(output_path, &passphrase, manifest, params)?;
     }
 
     Ok(())
 }
 
+/// Write a sealed `.personavault` archive; nothing decrypted ever reaches the disk
+async fn export_vault(
+    identity_names: &[String],
+    output_path: &PathBuf,
+    args: &ExportArgs,
+    params: EncryptionParams,
+    config: &CliConfig,
+) -> Result<()> {
+    let db_path = config.get_database_path();
+    let db = open_database(&db_path)
+        .await
+        .map_err(|e| anyhow!("Failed to open database: {}", e))?;
+    db.migrate()
+        .await
+        .map_err(|e| anyhow!("Failed to run migrations: {}", e))?;
+    let mut service = PersonaService::new(db.clone()).await.into_anyhow()?;
+    if !service.has_users().await.into_anyhow()? {
+        return Err(PersonaError::ConfigurationError(
+            "Vault archives need a vault with a master password. Run `persona init` first"
+                .to_string(),
+        )
+        .into());
+    }
+    service
+        .init_attachment_storage(config.get_attachments_path(), db.clone())
+        .await
+        .into_anyhow()
+        .context("Failed to open attachment storage")?;
+    auth::unlock(&mut service).await?;
+
+    // Exporting every identity takes every wallet along, including unassigned ones
+    let whole_vault = args.names.is_empty() && !args.interactive;
+    let wallets = CryptoWalletRepository::new(Arc::new(db));
+    let archive =
+        collect_vault_archive(&service, &wallets, identity_names, whole_vault).await?;
+
+    let passphrase = Password::new()
+        .with_prompt("Enter archive passphrase (independent of the master password)")
+        .with_confirmation("Confirm passphrase", "Passphrases do not match")
+        .interact()?;
+    let sealed = archive.seal(&passphrase, params)?;
+    std::fs::write(output_path, sealed).context("Failed to write vault archive")?;
+
+    println!(
+        "{} Sealed {} identities, {} credentials, {} attachments and {} wallets",
+        "✓".green(),
+        archive.identities.len(),
+        archive.credentials_count(),
+        archive.attachments_count(),
+        archive.wallets.len()
+    );
+    Ok(())
+}
+
+/// Gather the named identities with their decrypted credentials and attachments, plus their
+/// wallets (all wallets when `whole_vault`)
+pub(crate) async fn collect_vault_archive(
+    service: &PersonaService,
+    wallets: &CryptoWalletRepository,
+    identity_names: &[String],
+    whole_vault: bool,
+) -> Result<VaultArchive> {
+    let mut archive = VaultArchive::new();
+    for name in identity_names {
+        let identity = service
+            .get_identity_by_name(name)
+            .await
+            .into_anyhow()?
+            .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()))?;
+        let mut credentials = Vec::new();
+        for mut credential in service
+            .get_credentials_for_identity(&identity.id)
+            .await
+            .into_anyhow()?
+        {
+            let data = service
+                .get_credential_data(&credential.id)
+                .await
+                .into_anyhow()?
+                .with_context(|| format!("Credential '{}' has no data", credential.name))?
+                .to_canonical_json()?;
+            let mut attachments = Vec::new();
+            for attachment in service
+                .get_attachments(&credential.id)
+                .await
+                .into_anyhow()?
+            {
+                if let Some((attachment, content)) =
+                    service.get_attachment(&attachment.id).await.into_anyhow()?
+                {
+                    attachments.push(ArchivedAttachment::new(attachment.filename, &content));
+                }
+            }
+            credential.encrypted_data.clear();
+            credential.wrapped_item_key = None;
+            credentials.push(ArchivedCredential {
+                credential,
+                data,
+                attachments,
+            });
+        }
+        archive.identities.push(ArchivedIdentity {
+            identity,
+            credentials,
+        });
+    }
+
+    let identity_ids: HashSet<_> = archive.identities.iter().map(|i| i.identity.id).collect();
+    archive.wallets = wallets
+        .find_all()
+        .await
+        .into_anyhow()?
+        .into_iter()
+        .filter(|wallet| whole_vault || identity_ids.contains(&wallet.identity_id))
+        .collect();
+    Ok(archive)
+}
+
+/// Cipher and KDF cost from `--cipher`, `--kdf-cost` and its overrides
+fn encryption_params(args: &ExportArgs) -> Result<EncryptionParams> {
+    let mut kdf = args.kdf_cost.params();
+    if let Some(mib) = args.kdf_memory {
+        kdf.mem_kib = mib.saturating_mul(1024);
+    }
+    if let Some(iterations) = args.kdf_iterations {
+        kdf.iterations = iterations;
+    }
+    kdf.validate()?;
+    Ok(EncryptionParams {
+        kdf,
+        cipher: args.cipher,
+    })
+}
+
+/// Returns the number of credentials written.
 async fn export_json(
     identity_names: &[String],
     output_path: &PathBuf,
     args: &ExportArgs,
     config: &CliConfig,
     pb: &indicatif::ProgressBar,
-) -> Result<()> {
+) -> Result<usize> {
     // Open service (may require unlock)
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -311,16 +594,13 @@ async fn export_json(
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock (for credential export)")
-            .interact()?;
-        matches!(
-            service
-                .authenticate_user(&password)
-                .await
-                .map_err(|e| anyhow!("Auth failed: {}", e))?,
-            persona_core::auth::authentication::AuthResult::Success
+        // Without an unlocked vault the export still includes identities, just not credentials
+        auth::unlock_with_prompt(
+            &mut service,
+            "Enter master password to unlock (for credential export)",
         )
+        .await
+        .is_ok()
     } else {
         true
     };
@@ -335,6 +615,7 @@ async fn export_json(
         "identities": []
     });
 
+    let mut credentials_count = 0;
     for (i, name) in identity_names.iter().enumerate() {
         // Load identity detail
         let identity = if unlocked {
@@ -348,7 +629,7 @@ async fn export_json(
                 .await
                 .map_err(|e| anyhow!("Failed to load identity '{}': {}", name, e))?
         }
-        .with_context(|| format!("Identity '{}' not found", name))?;
+        .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()))?;
 
         // Collect credentials metadata and optionally data
         let mut credentials_json = Vec::new();
@@ -380,7 +661,8 @@ async fn export_json(
                         .await
                         .map_err(|e| anyhow!("Failed to load credential data: {}", e))?
                     {
-                        let json_data = serde_json::to_value(&data)
+                        let json_data = data
+                            .to_canonical_json()
                             .unwrap_or(serde_json::json!({"raw": "unserializable"}));
                         entry
                             .as_object_mut()
@@ -398,6 +680,7 @@ async fn export_json(
             }
         }
 
+        credentials_count += credentials_json.len();
         let identity_data = serde_json::json!({
             "id": identity.id.to_string(),
             "name": identity.name,
@@ -423,7 +706,7 @@ async fn export_json(
     let json_content = serde_json::to_string_pretty(&export_data)?;
     std::fs::write(output_path, json_content).context("Failed to write JSON export file")?;
 
-    Ok(())
+    Ok(credentials_count)
 }
 
 async fn export_yaml(
@@ -432,10 +715,10 @@ async fn export_yaml(
     args: &ExportArgs,
     config: &CliConfig,
     pb: &indicatif::ProgressBar,
-) -> Result<()> {
+) -> Result<usize> {
     // First export as JSON, then convert to YAML
     let temp_json = output_path.with_extension("temp.json");
-    export_json(identity_names, &temp_json, args, config, pb).await?;
+    let credentials_count = export_json(identity_names, &temp_json, args, config, pb).await?;
 
     let json_content = std::fs::read_to_string(&temp_json)?;
     let json_value: serde_json::Value = serde_json::from_str(&json_content)?;
@@ -446,18 +729,19 @@ async fn export_yaml(
     // Clean up temp file
     let _ = std::fs::remove_file(&temp_json);
 
-    Ok(())
+    Ok(credentials_count)
 }
 
+/// One row per credential, sorted by identity then name; returns the number of rows
 async fn export_csv(
     identity_names: &[String],
     output_path: &PathBuf,
-    _args: &ExportArgs,
+    args: &ExportArgs,
     config: &CliConfig,
     pb: &indicatif::ProgressBar,
-) -> Result<()> {
+) -> Result<usize> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -471,43 +755,100 @@ async fn export_csv(
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
-            .await
-            .map_err(|e| anyhow!("Auth failed: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => {}
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+        auth::unlock(&mut service).await?;
     }
-    let mut csv_content = String::new();
-    csv_content.push_str("Name,Type,Description,Email,Created,Modified\n");
 
+    let mut rows = Vec::new();
     for (i, name) in identity_names.iter().enumerate() {
         let identity = service
             .get_identity_by_name(name)
             .await
             .map_err(|e| anyhow!("Failed to load identity '{}': {}", name, e))?
-            .with_context(|| format!("Identity '{}' not found", name))?;
-        csv_content.push_str(&format!(
-            "{},{},{},{},{},{}\n",
-            identity.name,
-            identity.identity_type.to_string(),
-            identity.description.unwrap_or_default().replace(',', " "),
-            identity.email.unwrap_or_default(),
-            identity.created_at.format("%Y-%m-%d %H:%M:%S"),
-            identity.updated_at.format("%Y-%m-%d %H:%M:%S")
-        ));
+            .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()))?;
+        for credential in service
+            .get_credentials_for_identity(&identity.id)
+            .await
+            .into_anyhow()?
+        {
+            let secret = if args.include_secrets {
+                service
+                    .get_credential_data(&credential.id)
+                    .await
+                    .into_anyhow()?
+                    .map(|data| secret_text(&data))
+                    .transpose()?
+            } else {
+                None
+            };
+            rows.push((identity.name.clone(), credential, secret));
+        }
 
         pb.set_position(i as u64 + 1);
     }
 
+    let fields = args.fields.as_deref().unwrap_or(&DEFAULT_CSV_FIELDS);
+    let csv_content = credential_csv(&mut rows, fields, args.include_secrets);
     std::fs::write(output_path, csv_content).context("Failed to write CSV export file")?;
 
-    Ok(())
+    Ok(rows.len())
+}
+
+/// The secret `credential show --reveal` would print
+fn secret_text(data: &CredentialData) -> Result<String> {
+    Ok(match data {
+        CredentialData::Password(password) => password.password.expose().to_string(),
+        CredentialData::ApiKey(api) => api.api_key.expose().to_string(),
+        CredentialData::SshKey(ssh) => ssh.private_key.expose().to_string(),
+        CredentialData::TwoFactor(totp) => totp.secret_key.expose().to_string(),
+        other => other.to_canonical_json()?["data"].to_string(),
+    })
+}
+
+/// Render `(identity, credential, secret)` rows as RFC 4180 CSV, sorted for stable diffs
+fn credential_csv(
+    rows: &mut [(String, Credential, Option<String>)],
+    fields: &[CsvField],
+    include_secrets: bool,
+) -> String {
+    rows.sort_by(|(a_identity, a, _), (b_identity, b, _)| {
+        (a_identity, &a.name, a.id).cmp(&(b_identity, &b.name, b.id))
+    });
+
+    let mut header: Vec<&str> = fields.iter().map(|field| field.header()).collect();
+    if include_secrets {
+        header.push("secret");
+    }
+    let mut out = header.join(",");
+    out.push_str("\r\n");
+    for (identity, credential, secret) in rows.iter() {
+        let mut record: Vec<String> = fields
+            .iter()
+            .map(|field| csv_escape(&field.value(identity, credential)))
+            .collect();
+        if include_secrets {
+            record.push(csv_escape(secret.as_deref().unwrap_or_default()));
+        }
+        out.push_str(&record.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Quote a field when it contains a separator, quote or line break.
+///
+/// Cells a spreadsheet would evaluate as a formula (leading `=`, `+`, `-`, `@`, tab or CR)
+/// get a `'` prefix so opening the export cannot run anything a credential name or URL smuggled in.
+fn csv_escape(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
 }
 
 fn compress_file(file_path: &PathBuf, level: u8) -> Result<()> {
@@ -559,3 +900,77 @@ fn show_export_info(output_path: &PathBuf) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use persona_core::models::{CredentialType, SecurityLevel};
+
+    fn credential(name: &str, url: Option<&str>) -> Credential {
+        let mut credential = Credential::new(
+            uuid::Uuid::new_v4(),
+            name.to_string(),
+            CredentialType::Password,
+            SecurityLevel::High,
+            Vec::new(),
+            None,
+        );
+        credential.url = url.map(str::to_string);
+        credential
+    }
+
+    #[test]
+    fn credential_csv_escapes_and_sorts() {
+        let mut rows = vec![
+            ("work".to_string(), credential("vpn", None), None),
+            (
+                "home".to_string(),
+                credential("say \"hi\"", Some("https://a.example/?x=1,2")),
+                None,
+            ),
+            ("home".to_string(), credential("bank\nline", None), None),
+        ];
+        let csv = credential_csv(
+            &mut rows,
+            &[CsvField::Identity, CsvField::Name, CsvField::Url],
+            false,
+        );
+        assert_eq!(
+            csv,
+            "identity,name,url\r\n\
+             home,\"bank\nline\",\r\n\
+             home,\"say \"\"hi\"\"\",\"https://a.example/?x=1,2\"\r\n\
+             work,vpn,\r\n"
+        );
+    }
+
+    #[test]
+    fn csv_escape_neutralizes_formulas() {
+        assert_eq!(
+            csv_escape("=HYPERLINK(\"x\")"),
+            "\"'=HYPERLINK(\"\"x\"\")\""
+        );
+        assert_eq!(csv_escape("+1"), "'+1");
+        assert_eq!(csv_escape("-2+3"), "'-2+3");
+        assert_eq!(csv_escape("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_escape("\tcmd"), "'\tcmd");
+        assert_eq!(csv_escape("\rcmd"), "\"'\rcmd\"");
+        assert_eq!(csv_escape("a=b"), "a=b");
+        assert_eq!(csv_escape(""), "");
+    }
+
+    #[test]
+    fn credential_csv_adds_secrets_only_when_asked() {
+        let mut rows = vec![(
+            "home".to_string(),
+            credential("bank", None),
+            Some("hunter2".to_string()),
+        )];
+        let without = credential_csv(&mut rows, &DEFAULT_CSV_FIELDS, false);
+        assert!(without.starts_with("name,url,username,type,identity,updated_at\r\n"));
+        assert!(!without.contains("hunter2"));
+
+        let with = credential_csv(&mut rows, &[CsvField::Name], true);
+        assert_eq!(with, "name,secret\r\nbank,hunter2\r\n");
+    }
+}
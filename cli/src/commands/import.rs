@@ -1,22 +1,54 @@
 use anyhow::{Context, Result};
-use clap::Args;
+use clap::{Args, ValueEnum};
 use colored::*;
 use dialoguer::{Confirm, MultiSelect};
-use std::path::PathBuf;
-
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use zeroize::Zeroizing;
+
+use crate::utils::open_database;
+use crate::utils::file_crypto::{
+    decrypt_bytes, inspect_encrypted, is_encrypted, EncryptionParams, ExportManifest,
+};
+use crate::utils::password_manager::{parse_1pux, parse_bitwarden, ExternalItem};
 use crate::utils::progress::create_progress_bar;
-use crate::{config::CliConfig, utils::core_ext::CoreResultExt};
-use dialoguer::Password;
+use crate::utils::ssh_config::{openssh_ed25519_seed, SshConfig, SshHost};
+use crate::utils::vault_archive::{is_vault_archive, VaultArchive, VAULT_ARCHIVE_EXTENSION};
+use crate::utils::{lock_vault, print_safety_backup};
+use crate::{
+    commands::{auth, totp},
+    config::CliConfig,
+    utils::core_ext::CoreResultExt,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use persona_core::{
-    models::IdentityType,
-    storage::{IdentityRepository, Repository},
-    Database, PersonaService,
+    models::{
+        Credential, CredentialData, CredentialType, IdentityType, PasswordCredentialData,
+        SecurityLevel, ServerConfigData, SshKeyData,
+    },
+    otp,
+    storage::{CryptoWalletRepository, IdentityRepository, Repository},
+    PersonaError, PersonaService, SecretString,
 };
 
 #[derive(Args)]
 pub struct ImportArgs {
     /// Import file path
-    file: PathBuf,
+    #[arg(required_unless_present = "from_ssh_config")]
+    file: Option<PathBuf>,
+
+    /// Import hosts from an OpenSSH client config (default: ~/.ssh/config) as server credentials
+    #[arg(long, value_name = "PATH", num_args = 0..=1, conflicts_with = "file")]
+    from_ssh_config: Option<Option<PathBuf>>,
+
+    /// Read another password manager's export instead of a Persona export
+    #[arg(long, value_enum, conflicts_with = "from_ssh_config")]
+    format: Option<ExternalFormat>,
+
+    /// Identity that receives the credentials imported from an SSH config
+    #[arg(long, requires = "from_ssh_config")]
+    identity: Option<String>,
 
     /// Import mode (merge, replace, skip)
     #[arg(short, long, default_value = "merge")]
@@ -43,22 +75,58 @@ pub struct ImportArgs {
     interactive: bool,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExternalFormat {
+    /// Bitwarden unencrypted JSON export
+    Bitwarden,
+    /// 1Password 1pux archive
+    #[value(name = "1pux")]
+    OnePux,
+}
+
+impl ExternalFormat {
+    /// Tag put on every imported credential
+    fn tag(self) -> &'static str {
+        match self {
+            ExternalFormat::Bitwarden => "bitwarden",
+            ExternalFormat::OnePux => "1password",
+        }
+    }
+}
+
 pub async fn execute(args: ImportArgs, config: &CliConfig) -> Result<()> {
+    if let Some(ssh_config) = &args.from_ssh_config {
+        let path = match ssh_config {
+            Some(path) => path.clone(),
+            None => dirs::home_dir()
+                .context("Cannot locate the home directory")?
+                .join(".ssh")
+                .join("config"),
+        };
+        return import_ssh_config(&path, &args, config).await;
+    }
+    let file = args
+        .file
+        .clone()
+        .context("An import file or --from-ssh-config is required")?;
+    if let Some(format) = args.format {
+        return import_password_manager(&file, format, &args, config).await;
+    }
+    if file
+        .extension()
+        .is_some_and(|ext| ext == VAULT_ARCHIVE_EXTENSION)
+        || std::fs::read(&file).is_ok_and(|bytes| is_vault_archive(&bytes))
+    {
+        return import_vault_archive(&file, &args, config).await;
+    }
+
     println!("{}", "📥 Importing identities...".cyan().bold());
     println!();
 
-    // Validate import file
-    validate_import_file(&args.file)?;
-
-    // Decrypt file if needed
-    let import_file = if args.decrypt {
-        decrypt_import_file(&args.file, config)?
-    } else {
-        args.file.clone()
-    };
-
-    // Parse import data
-    let import_data = parse_import_file(&import_file)?;
+    // Validate the whole file before anything is written
+    let report = validate_import(&file, args.decrypt, read_import_passphrase)?;
+    print_validation(&report);
+    let import_data = report.data;
 
     // Show import summary
     show_import_summary(&import_data, &args)?;
@@ -93,6 +161,13 @@ pub async fn execute(args: ImportArgs, config: &CliConfig) -> Result<()> {
         }
     }
 
+    // Keep other exclusive operations out until the import finishes
+    let _lock = if args.dry_run {
+        None
+    } else {
+        Some(lock_vault(config, "import")?)
+    };
+
     // Create backup if requested
     if args.backup && !args.dry_run {
         create_backup(config).await?;
@@ -125,6 +200,8 @@ struct ImportData {
     version: String,
     created: String,
     identities: Vec<ImportIdentity>,
+    /// Credential entries nested under the identities; checked against the manifest only
+    credentials: Vec<serde_json::Value>,
 }
 
 #[derive(Debug, Clone)]
@@ -146,7 +223,7 @@ struct ImportConflict {
     new_data: String,
 }
 
-fn validate_import_file(file_path: &PathBuf) -> Result<()> {
+fn validate_import_file(file_path: &Path) -> Result<()> {
     if !file_path.exists() {
         anyhow::bail!("Import file does not exist: {}", file_path.display());
     }
@@ -170,35 +247,196 @@ fn validate_import_file(file_path: &PathBuf) -> Result<()> {
     Ok(())
 }
 
-fn decrypt_import_file(file_path: &PathBuf, _config: &CliConfig) -> Result<PathBuf> {
-    use crate::utils::file_crypto::decrypt_file_to_temp;
+/// Export format major version this build reads (`export_info.version`)
+const SUPPORTED_EXPORT_MAJOR: u32 = 1;
+
+/// What an import file contains, established before anything is written
+struct ImportReport {
+    /// Payload format: json, yaml or csv
+    format: String,
+    /// Cipher and KDF cost read from the header; `None` for plain files
+    encryption: Option<EncryptionParams>,
+    /// Verified manifest; `None` for plain files and legacy encrypted exports
+    manifest: Option<ExportManifest>,
+    data: ImportData,
+}
+
+/// Check an import file end to end before anything is written.
+///
+/// Refuses unknown formats, corrupt or truncated content, exports from newer builds and
+/// encrypted files that fail their integrity check. The passphrase is only requested once
+/// the file's structure checks out.
+fn validate_import(
+    file_path: &Path,
+    decrypt: bool,
+    passphrase: impl FnOnce() -> Result<String>,
+) -> Result<ImportReport> {
+    validate_import_file(file_path)?;
+    let bytes = std::fs::read(file_path).context("Failed to read import file")?;
+    let extension = file_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("json")
+        .to_ascii_lowercase();
+
+    let encrypted = is_encrypted(&bytes);
+    if encrypted && !decrypt {
+        return Err(incompatible(
+            "The file is an encrypted Persona export",
+            "Re-run with --decrypt",
+        ));
+    }
+    if decrypt && !encrypted {
+        return Err(incompatible(
+            "The file is not an encrypted Persona export",
+            "Re-run without --decrypt",
+        ));
+    }
+
+    let (content, format, manifest, encryption) = if encrypted {
+        if let Some(manifest) = inspect_encrypted(&bytes)?.manifest {
+            check_import_format(&manifest.format)?;
+        }
+        let decrypted = decrypt_bytes(&bytes, &passphrase()?)?;
+        let format = decrypted
+            .manifest
+            .as_ref()
+            .map_or(extension, |manifest| manifest.format.clone());
+        (
+            decrypted.plaintext,
+            format,
+            decrypted.manifest,
+            Some(decrypted.params),
+        )
+    } else {
+        (Zeroizing::new(bytes), extension, None, None)
+    };
+
+    let text = std::str::from_utf8(&content).map_err(|_| {
+        incompatible(
+            "The file is not a text export",
+            "It may be corrupt, or not a Persona export at all",
+        )
+    })?;
+    let data = parse_import_content(text, &format)?;
+    if format != "csv" {
+        check_export_version(&data.version)?;
+    }
+    if let Some(manifest) = &manifest {
+        verify_manifest_counts(manifest, &data)?;
+    }
+
+    Ok(ImportReport {
+        format,
+        encryption,
+        manifest,
+        data,
+    })
+}
+
+/// Refusal for a file this build cannot import, with what to do about it
+fn incompatible(problem: impl std::fmt::Display, suggestion: &str) -> anyhow::Error {
+    PersonaError::Validation(format!("{}. {}", problem, suggestion)).into()
+}
+
+fn check_export_version(version: &str) -> Result<()> {
+    let major = version
+        .split('.')
+        .next()
+        .and_then(|major| major.parse::<u32>().ok());
+    match major {
+        Some(SUPPORTED_EXPORT_MAJOR) => Ok(()),
+        Some(major) if major > SUPPORTED_EXPORT_MAJOR => Err(incompatible(
+            format!(
+                "Export format version {} is newer than this build supports ({}.x)",
+                version, SUPPORTED_EXPORT_MAJOR
+            ),
+            "Upgrade persona to import it",
+        )),
+        _ => Err(incompatible(
+            format!("Unsupported export format version '{}'", version),
+            "Re-export it with `persona export`",
+        )),
+    }
+}
+
+fn read_import_passphrase() -> Result<String> {
     use dialoguer::Password;
     println!("{} Decrypting import file...", "🔓".to_string());
-    let passphrase = Password::new()
+    Ok(Password::new()
         .with_prompt("Enter import passphrase")
-        .interact()?;
-    let out = decrypt_file_to_temp(file_path, &passphrase)?;
-    println!("{} File decrypted", "✓".green());
-    Ok(out)
+        .interact()?)
 }
 
-fn parse_import_file(file_path: &PathBuf) -> Result<ImportData> {
-    let content = std::fs::read_to_string(file_path).context("Failed to read import file")?;
+fn print_validation(report: &ImportReport) {
+    let identities = report.data.identities.len();
+    match (&report.manifest, &report.encryption) {
+        (Some(manifest), Some(params)) => println!(
+            "{} File decrypted and verified ({} identities, {} credentials; {})",
+            "✓".green(),
+            manifest.identities_count,
+            manifest.credentials_count,
+            params
+        ),
+        (None, Some(_)) => println!(
+            "{} File decrypted (legacy export without integrity manifest)",
+            "⚠️".yellow()
+        ),
+        (_, None) => println!(
+            "{} Validated {} export ({} identities)",
+            "✓".green(),
+            report.format,
+            identities
+        ),
+    }
+}
 
-    // Determine format by extension
-    let format = file_path
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .unwrap_or("json");
+/// Reject exports whose content disagrees with what their manifest promised
+fn verify_manifest_counts(manifest: &ExportManifest, import_data: &ImportData) -> Result<()> {
+    if import_data.identities.len() != manifest.identities_count {
+        return Err(PersonaError::Validation(format!(
+            "Export manifest lists {} identities but the file contains {}",
+            manifest.identities_count,
+            import_data.identities.len()
+        ))
+        .into());
+    }
+    if import_data.credentials.len() != manifest.credentials_count {
+        return Err(PersonaError::Validation(format!(
+            "Export manifest lists {} credentials but the file contains {}",
+            manifest.credentials_count,
+            import_data.credentials.len()
+        ))
+        .into());
+    }
+    Ok(())
+}
 
+fn check_import_format(format: &str) -> Result<()> {
     match format {
-        "json" => parse_json_import(&content),
-        "yaml" | "yml" => parse_yaml_import(&content),
-        "csv" => parse_csv_import(&content),
-        _ => anyhow::bail!("Unsupported import format: {}", format),
+        "json" | "yaml" | "yml" | "csv" => Ok(()),
+        _ => Err(incompatible(
+            format!("Unsupported import format '{}'", format),
+            "Use a .json, .yaml or .csv export",
+        )),
     }
 }
 
+fn parse_import_content(content: &str, format: &str) -> Result<ImportData> {
+    check_import_format(format)?;
+    let parsed = match format {
+        "json" => parse_json_import(content),
+        "yaml" | "yml" => parse_yaml_import(content),
+        _ => parse_csv_import(content),
+    };
+    parsed.map_err(|err| {
+        incompatible(
+            format!("The file is corrupt or not a Persona export ({:#})", err),
+            "Re-export it with `persona export`",
+        )
+    })
+}
+
 fn parse_json_import(content: &str) -> Result<ImportData> {
     let json_value: serde_json::Value =
         serde_json::from_str(content).context("Failed to parse JSON import file")?;
@@ -225,7 +463,11 @@ fn parse_json_import(content: &str) -> Result<ImportData> {
         .context("Missing or invalid identities array")?;
 
     let mut identities = Vec::new();
+    let mut credentials = Vec::new();
     for identity_value in identities_array {
+        if let Some(entries) = identity_value.get("credentials").and_then(|v| v.as_array()) {
+            credentials.extend(entries.iter().cloned());
+        }
         let identity = ImportIdentity {
             name: identity_value
                 .get("name")
@@ -269,6 +511,7 @@ fn parse_json_import(content: &str) -> Result<ImportData> {
         version,
         created,
         identities,
+        credentials,
     })
 }
 
@@ -287,8 +530,11 @@ fn parse_csv_import(content: &str) -> Result<ImportData> {
     let mut identities = Vec::new();
     let mut lines = content.lines();
 
-    // Skip header
-    lines.next();
+    // Credential inventories from `persona export --format csv` are for auditing only
+    let header = lines.next().unwrap_or_default();
+    if !header.starts_with("Name,Type") {
+        anyhow::bail!("Expected an identity CSV with a Name,Type,Description,Email header");
+    }
 
     for line in lines {
         let fields: Vec<&str> = line.split(',').collect();
@@ -314,12 +560,15 @@ fn parse_csv_import(content: &str) -> Result<ImportData> {
         version: "csv".to_string(),
         created: chrono::Utc::now().to_rfc3339(),
         identities,
+        credentials: Vec::new(),
     })
 }
 
 fn show_import_summary(import_data: &ImportData, args: &ImportArgs) -> Result<()> {
     println!("{}", "Import Summary:".yellow().bold());
-    println!("  File: {}", args.file.display().to_string().cyan());
+    if let Some(file) = &args.file {
+        println!("  File: {}", file.display().to_string().cyan());
+    }
     println!("  Format version: {}", import_data.version.cyan());
     println!("  Created: {}", import_data.created.dimmed());
     println!(
@@ -387,7 +636,7 @@ async fn check_import_conflicts(
     let mut conflicts = Vec::new();
 
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -395,19 +644,14 @@ async fn check_import_conflicts(
         .map_err(|e| anyhow::anyhow!("Failed to run migrations: {}", e))?;
     let mut service = PersonaService::new(db.clone()).await.into_anyhow()?;
     let names = if service.has_users().await.into_anyhow()? {
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service.authenticate_user(&password).await.into_anyhow()? {
-            persona_core::auth::authentication::AuthResult::Success => service
-                .get_identities()
-                .await
-                .into_anyhow()?
-                .into_iter()
-                .map(|i| i.name)
-                .collect::<Vec<_>>(),
-            _ => Vec::new(),
-        }
+        auth::unlock(&mut service).await?;
+        service
+            .get_identities()
+            .await
+            .into_anyhow()?
+            .into_iter()
+            .map(|i| i.name)
+            .collect::<Vec<_>>()
     } else {
         IdentityRepository::new(db)
             .find_all()
@@ -531,33 +775,41 @@ async fn perform_dry_run(
     Ok(())
 }
 
-async fn perform_import(
-    identities: &[ImportIdentity],
-    args: &ImportArgs,
-    config: &CliConfig,
-) -> Result<()> {
-    let pb = create_progress_bar(identities.len() as u64, "Importing identities");
-
-    // Open DB + service and unlock if needed
+/// Open the vault, unlocking it if a master password is set
+async fn open_service(config: &CliConfig) -> Result<PersonaService> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to open database: {}", e))?;
     db.migrate()
         .await
         .map_err(|e| anyhow::anyhow!("Failed to run migrations: {}", e))?;
-    let mut service = PersonaService::new(db.clone()).await.into_anyhow()?;
+    let mut service = PersonaService::new(db).await.into_anyhow()?;
     if service.has_users().await.into_anyhow()? {
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service.authenticate_user(&password).await.into_anyhow()? {
-            persona_core::auth::authentication::AuthResult::Success => {}
-            other => anyhow::bail!("Authentication failed: {:?}", other),
+        auth::unlock(&mut service).await?;
+    }
+    // Without a master password, identities are imported without encryption
+    Ok(service)
+}
+
+async fn perform_import(
+    identities: &[ImportIdentity],
+    args: &ImportArgs,
+    config: &CliConfig,
+) -> Result<()> {
+    let mut service = open_service(config).await?;
+    // An explicit --backup already covers replacing existing identities
+    if args.mode == "replace" && !args.backup {
+        service.set_backup_before_destructive(config.destructive_backup_dir());
+        if let Some(path) = service
+            .backup_before_destructive("import_replace")
+            .await
+            .into_anyhow()?
+        {
+            print_safety_backup(&path);
         }
-    } else {
-        // If no users configured, initialize one? For import we allow creating identities without encryption.
     }
+    let pb = create_progress_bar(identities.len() as u64, "Importing identities");
 
     for (i, identity) in identities.iter().enumerate() {
         // Check existing
@@ -650,3 +902,915 @@ async fn perform_import(
     pb.finish_with_message("Import completed");
     Ok(())
 }
+
+/// A host from an SSH config and the key file it will be linked to
+struct SshImportPlan {
+    host: SshHost,
+    /// First `IdentityFile` that exists, which is the one ssh would offer first
+    key_file: Option<PathBuf>,
+}
+
+async fn import_ssh_config(path: &Path, args: &ImportArgs, config: &CliConfig) -> Result<()> {
+    println!("{}", "📥 Importing hosts from SSH config...".cyan().bold());
+    println!();
+
+    let ssh_config = SshConfig::load(path)?;
+    for warning in &ssh_config.warnings {
+        println!("{} {}", "⚠️".yellow(), warning);
+    }
+
+    let plans: Vec<SshImportPlan> = ssh_config
+        .hosts()
+        .into_iter()
+        .map(|host| {
+            let key_file = host.identity_files.iter().find(|p| p.is_file()).cloned();
+            SshImportPlan { host, key_file }
+        })
+        .collect();
+    if plans.is_empty() {
+        println!("{}", "No concrete hosts found in the SSH config.".yellow());
+        return Ok(());
+    }
+
+    println!("{}", "Import Summary:".yellow().bold());
+    println!("  SSH config: {}", path.display().to_string().cyan());
+    println!("  Hosts: {}", plans.len().to_string().cyan());
+    println!();
+    for plan in &plans {
+        show_ssh_plan(plan);
+    }
+    println!();
+
+    if args.dry_run {
+        println!(
+            "{} Dry run completed; the vault was not changed.",
+            "✓".green().bold()
+        );
+        return Ok(());
+    }
+
+    let identity_name = args.identity.as_deref().ok_or_else(|| {
+        PersonaError::InvalidInput("--identity is required to import an SSH config".to_string())
+    })?;
+    if !args.force
+        && !Confirm::new()
+            .with_prompt(format!(
+                "Import {} hosts into '{}'?",
+                plans.len(),
+                identity_name
+            ))
+            .default(true)
+            .interact()?
+    {
+        println!("{}", "Import cancelled.".yellow());
+        return Ok(());
+    }
+    let _lock = lock_vault(config, "import")?;
+    if args.backup {
+        create_backup(config).await?;
+    }
+
+    let service = open_service(config).await?;
+    let identity = service
+        .get_identity_by_name(identity_name)
+        .await
+        .into_anyhow()?
+        .ok_or_else(|| PersonaError::IdentityNotFound(identity_name.to_string()))?;
+    let existing: HashSet<String> = service
+        .get_credentials_for_identity(&identity.id)
+        .await
+        .into_anyhow()?
+        .into_iter()
+        .filter(|c| matches!(c.credential_type, CredentialType::ServerConfig))
+        .map(|c| c.name)
+        .collect();
+
+    let mut key_ids: HashMap<PathBuf, Uuid> = HashMap::new();
+    let mut imported = 0;
+    for plan in &plans {
+        let host = &plan.host;
+        if existing.contains(&host.alias) {
+            println!(
+                "  {} {} (already in vault)",
+                "-".dimmed(),
+                host.alias.dimmed()
+            );
+            continue;
+        }
+
+        let ssh_key_id = match &plan.key_file {
+            Some(key_file) => match key_ids.get(key_file) {
+                Some(id) => Some(*id),
+                None => {
+                    let id = import_ssh_key(&service, identity.id, key_file).await?;
+                    key_ids.insert(key_file.clone(), id);
+                    Some(id)
+                }
+            },
+            None => None,
+        };
+
+        let mut additional_config = HashMap::new();
+        additional_config.insert("ssh_config_host".to_string(), host.alias.clone());
+        let data = ServerConfigData {
+            hostname: host.hostname.clone(),
+            ip_address: None,
+            port: host.port,
+            protocol: "ssh".to_string(),
+            username: host.user.clone().unwrap_or_default(),
+            password: None,
+            ssh_key_id,
+            additional_config,
+        };
+        let mut credential = Credential::new(
+            identity.id,
+            host.alias.clone(),
+            CredentialType::ServerConfig,
+            SecurityLevel::High,
+            Vec::new(),
+            None,
+        );
+        credential.username = host.user.clone();
+        credential.tags = vec!["ssh-config".to_string()];
+        service
+            .create_credential_full(credential, &CredentialData::ServerConfig(data))
+            .await
+            .into_anyhow()?;
+        println!("  {} {}", "✓".green(), host.alias.cyan());
+        imported += 1;
+    }
+
+    println!();
+    println!(
+        "{} Imported {} hosts and {} SSH keys into '{}'",
+        "✓".green().bold(),
+        imported.to_string().cyan(),
+        key_ids.len().to_string().cyan(),
+        identity.name.cyan()
+    );
+    Ok(())
+}
+
+fn show_ssh_plan(plan: &SshImportPlan) {
+    let host = &plan.host;
+    let target = match &host.user {
+        Some(user) => format!("{}@{}:{}", user, host.hostname, host.port),
+        None => format!("{}:{}", host.hostname, host.port),
+    };
+    println!("  {} {}", host.alias.cyan(), target.dimmed());
+    match &plan.key_file {
+        Some(key_file) => {
+            let agent_ready = std::fs::read_to_string(key_file)
+                .map(|pem| openssh_ed25519_seed(&pem).is_some())
+                .unwrap_or(false);
+            println!("    key: {}", key_file.display());
+            if !agent_ready {
+                println!(
+                    "    {}",
+                    "stored as-is; the agent only loads unencrypted ed25519 keys".dimmed()
+                );
+            }
+        }
+        None if !host.identity_files.is_empty() => {
+            println!(
+                "    {}",
+                "key: none of the IdentityFile paths exist".yellow()
+            );
+        }
+        None => {}
+    }
+}
+
+/// Store a private key file as an SSH key credential and return its id
+async fn import_ssh_key(
+    service: &PersonaService,
+    identity_id: Uuid,
+    key_file: &Path,
+) -> Result<Uuid> {
+    let pem = std::fs::read_to_string(key_file)
+        .with_context(|| format!("Failed to read SSH key {}", key_file.display()))?;
+    let mut public_path = key_file.as_os_str().to_owned();
+    public_path.push(".pub");
+    let public_key = std::fs::read_to_string(PathBuf::from(public_path))
+        .map(|line| line.trim().to_string())
+        .unwrap_or_default();
+    let key_type = public_key
+        .split_whitespace()
+        .next()
+        .map(|t| t.trim_start_matches("ssh-").to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let private_key = ssh_private_key(pem);
+    let name = key_file
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "SSH Key (imported)".to_string());
+    let credential = service
+        .create_credential(
+            identity_id,
+            name,
+            CredentialType::SshKey,
+            SecurityLevel::High,
+            &CredentialData::SshKey(SshKeyData {
+                private_key,
+                public_key,
+                key_type,
+                passphrase: None,
+            }),
+        )
+        .await
+        .into_anyhow()?;
+    Ok(credential.id)
+}
+
+/// Unencrypted ed25519 keys are stored as their seed, the form the agent loads; other keys
+/// are kept verbatim so nothing is lost
+fn ssh_private_key(pem: String) -> SecretString {
+    match openssh_ed25519_seed(&pem) {
+        Some(seed) => SecretString::new(BASE64.encode(seed)),
+        None => SecretString::new(pem),
+    }
+}
+
+/// What duplicates are detected by: (name, url, username)
+type CredentialKey = (String, Option<String>, Option<String>);
+
+/// Import a Bitwarden or 1Password export, one identity per vault or folder.
+///
+/// Credentials already present under the same (name, url, username) are skipped, and records
+/// that cannot be read are reported and skipped rather than aborting the import.
+async fn import_password_manager(
+    file: &Path,
+    format: ExternalFormat,
+    args: &ImportArgs,
+    config: &CliConfig,
+) -> Result<()> {
+    println!(
+        "{}",
+        "📥 Importing from a password manager export...".cyan().bold()
+    );
+    println!();
+
+    validate_import_file(file)?;
+    let bytes = std::fs::read(file).context("Failed to read import file")?;
+    let export = match format {
+        ExternalFormat::Bitwarden => parse_bitwarden(&String::from_utf8_lossy(&bytes)),
+        ExternalFormat::OnePux => parse_1pux(&bytes),
+    }
+    .map_err(|err| incompatible(format!("{:#}", err), "Check the file and --format"))?;
+    for problem in &export.malformed {
+        println!("{} Skipping malformed record {}", "⚠️".yellow(), problem);
+    }
+
+    let mut vaults: BTreeMap<&str, usize> = BTreeMap::new();
+    for item in &export.items {
+        *vaults.entry(item.vault.as_str()).or_default() += 1;
+    }
+    println!("{}", "Import Summary:".yellow().bold());
+    println!("  File: {}", file.display().to_string().cyan());
+    println!("  Items: {}", export.items.len().to_string().cyan());
+    if export.unsupported > 0 {
+        println!(
+            "  Unsupported items (cards, notes, ...): {}",
+            export.unsupported.to_string().dimmed()
+        );
+    }
+    for (vault, count) in &vaults {
+        println!("  {} {} ({} items)", "→".dimmed(), vault.cyan(), count);
+    }
+    println!();
+
+    if export.items.is_empty() {
+        println!("{}", "Nothing to import.".yellow());
+        return Ok(());
+    }
+    if args.dry_run {
+        println!(
+            "{} Dry run completed; the vault was not changed.",
+            "✓".green().bold()
+        );
+        return Ok(());
+    }
+    if !args.force
+        && !Confirm::new()
+            .with_prompt(format!(
+                "Import {} items into {} identities?",
+                export.items.len(),
+                vaults.len()
+            ))
+            .default(true)
+            .interact()?
+    {
+        println!("{}", "Import cancelled.".yellow());
+        return Ok(());
+    }
+    let _lock = lock_vault(config, "import")?;
+    if args.backup {
+        create_backup(config).await?;
+    }
+
+    let service = open_service(config).await?;
+    let mut identities: HashMap<String, (Uuid, HashSet<CredentialKey>)> = HashMap::new();
+    let (mut duplicates, mut malformed) = (0, export.malformed.len());
+    let mut pending = Vec::new();
+    for item in &export.items {
+        if !identities.contains_key(&item.vault) {
+            let entry = load_import_identity(&service, &item.vault).await?;
+            identities.insert(item.vault.clone(), entry);
+        }
+        let (identity_id, seen) = identities
+            .get_mut(&item.vault)
+            .expect("identity loaded above");
+
+        let credentials = match external_credentials(item, *identity_id, format) {
+            Ok(credentials) => credentials,
+            Err(err) => {
+                println!(
+                    "{} Skipping malformed record '{}': {:#}",
+                    "⚠️".yellow(),
+                    item.name,
+                    err
+                );
+                malformed += 1;
+                continue;
+            }
+        };
+        for (credential, data) in credentials {
+            let key = (
+                credential.name.clone(),
+                credential.url.clone(),
+                credential.username.clone(),
+            );
+            if !seen.insert(key) {
+                duplicates += 1;
+                continue;
+            }
+            pending.push((credential, data));
+        }
+    }
+    // One transaction: a failure part-way through stores none of the credentials
+    let (credentials, data): (Vec<_>, Vec<_>) = pending.into_iter().unzip();
+    let imported = service
+        .create_credentials(credentials.into_iter().zip(&data).collect())
+        .await
+        .into_anyhow()?
+        .len();
+
+    println!(
+        "{} Imported {} credentials into {} identities; skipped {} duplicates and {} malformed records",
+        "✓".green().bold(),
+        imported.to_string().cyan(),
+        identities.len().to_string().cyan(),
+        duplicates.to_string().cyan(),
+        malformed.to_string().cyan()
+    );
+    Ok(())
+}
+
+/// Find or create the identity for a vault, with the keys of the credentials it already has
+async fn load_import_identity(
+    service: &PersonaService,
+    name: &str,
+) -> Result<(Uuid, HashSet<CredentialKey>)> {
+    let identity = match service.get_identity_by_name(name).await.into_anyhow()? {
+        Some(identity) => identity,
+        None => service
+            .create_identity(name.to_string(), IdentityType::Personal)
+            .await
+            .into_anyhow()?,
+    };
+    let existing = service
+        .get_credentials_for_identity(&identity.id)
+        .await
+        .into_anyhow()?
+        .into_iter()
+        .map(|c| (c.name, c.url, c.username))
+        .collect();
+    Ok((identity.id, existing))
+}
+
+/// The password, TOTP and SSH key credentials an exported item turns into
+fn external_credentials(
+    item: &ExternalItem,
+    identity_id: Uuid,
+    format: ExternalFormat,
+) -> Result<Vec<(Credential, CredentialData)>> {
+    let new_credential = |name: String, credential_type: CredentialType| {
+        let mut credential = Credential::new(
+            identity_id,
+            name,
+            credential_type,
+            SecurityLevel::High,
+            Vec::new(),
+            None,
+        );
+        credential.url = item.url.clone();
+        credential.username = item.username.clone();
+        credential.notes = item.notes.clone();
+        credential.tags = vec![format.tag().to_string()];
+        credential
+    };
+
+    let mut credentials = Vec::new();
+    if let Some(password) = &item.password {
+        credentials.push((
+            new_credential(item.name.clone(), CredentialType::Password),
+            CredentialData::Password(PasswordCredentialData {
+                password: SecretString::new(password.clone()),
+                email: None,
+                security_questions: Vec::new(),
+            }),
+        ));
+    }
+    if let Some(seed) = &item.totp {
+        let template = if seed.starts_with("otpauth://") {
+            totp::parse_otpauth_uri(seed)?
+        } else {
+            totp::TotpTemplate::from_secret(seed.replace(' ', ""))
+        };
+        let mut data = template.finalize()?.to_data();
+        otp::decode_secret(data.secret_key.expose())
+            .map_err(|e| anyhow::anyhow!("invalid TOTP secret: {}", e))?;
+        if data.issuer.is_empty() {
+            data.issuer = item.name.clone();
+        }
+        if let (Some(username), true) = (&item.username, data.account_name == "TOTP") {
+            data.account_name = username.clone();
+        }
+        credentials.push((
+            new_credential(format!("{} (TOTP)", item.name), CredentialType::TwoFactor),
+            CredentialData::TwoFactor(data),
+        ));
+    }
+    if let Some(key) = &item.ssh_key {
+        credentials.push((
+            new_credential(item.name.clone(), CredentialType::SshKey),
+            CredentialData::SshKey(SshKeyData {
+                private_key: ssh_private_key(key.private_key.clone()),
+                public_key: key.public_key.clone(),
+                key_type: key.key_type.clone(),
+                passphrase: None,
+            }),
+        ));
+    }
+    if credentials.is_empty() {
+        anyhow::bail!("no password, TOTP seed or SSH key");
+    }
+    Ok(credentials)
+}
+
+/// What restoring a vault archive changed
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ArchiveRestoreReport {
+    identities_created: usize,
+    credentials_imported: usize,
+    duplicates: usize,
+    attachments: usize,
+    wallets_imported: usize,
+    wallets_skipped: usize,
+}
+
+/// Restore a `.personavault` archive written by `persona export --format vault`
+async fn import_vault_archive(file: &Path, args: &ImportArgs, config: &CliConfig) -> Result<()> {
+    println!("{}", "📥 Importing vault archive...".cyan().bold());
+    println!();
+
+    validate_import_file(file)?;
+    let bytes = std::fs::read(file).context("Failed to read import file")?;
+    let archive = VaultArchive::open(&bytes, || {
+        Ok(dialoguer::Password::new()
+            .with_prompt("Enter archive passphrase")
+            .interact()?)
+    })?;
+    println!(
+        "{} Archive verified and decrypted ({} identities, {} credentials, {} attachments, {} wallets)",
+        "✓".green(),
+        archive.identities.len(),
+        archive.credentials_count(),
+        archive.attachments_count(),
+        archive.wallets.len()
+    );
+    for archived in &archive.identities {
+        println!(
+            "  {} {} ({} credentials)",
+            "→".dimmed(),
+            archived.identity.name.cyan(),
+            archived.credentials.len()
+        );
+    }
+    println!();
+
+    if args.dry_run {
+        println!(
+            "{} Dry run completed; the vault was not changed.",
+            "✓".green().bold()
+        );
+        return Ok(());
+    }
+    if !args.force
+        && !Confirm::new()
+            .with_prompt("Restore this archive into the current vault?")
+            .default(true)
+            .interact()?
+    {
+        println!("{}", "Import cancelled.".yellow());
+        return Ok(());
+    }
+    let _lock = lock_vault(config, "import")?;
+    if args.backup {
+        create_backup(config).await?;
+    }
+
+    let db = open_database(config.get_database_path())
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to open database: {}", e))?;
+    db.migrate()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run migrations: {}", e))?;
+    let mut service = PersonaService::new(db.clone()).await.into_anyhow()?;
+    if !service.has_users().await.into_anyhow()? {
+        return Err(PersonaError::ConfigurationError(
+            "Vault archives can only be restored into a vault with a master password. Run `persona init` first"
+                .to_string(),
+        )
+        .into());
+    }
+    service.set_max_attachment_size(config.security.max_attachment_size);
+    service
+        .init_attachment_storage(config.get_attachments_path(), db.clone())
+        .await
+        .into_anyhow()
+        .context("Failed to open attachment storage")?;
+    auth::unlock(&mut service).await?;
+    let wallets = CryptoWalletRepository::new(std::sync::Arc::new(db));
+
+    let report = restore_vault_archive(&mut service, &wallets, &archive).await?;
+    println!(
+        "{} Restored {} credentials ({} attachments) and {} wallets; created {} identities",
+        "✓".green().bold(),
+        report.credentials_imported.to_string().cyan(),
+        report.attachments.to_string().cyan(),
+        report.wallets_imported.to_string().cyan(),
+        report.identities_created.to_string().cyan()
+    );
+    if report.duplicates + report.wallets_skipped > 0 {
+        println!(
+            "  Skipped {} credentials and {} wallets already in the vault",
+            report.duplicates, report.wallets_skipped
+        );
+    }
+    Ok(())
+}
+
+/// Write an opened archive into an unlocked vault.
+///
+/// Identities are matched by name and credentials deduplicated by (name, url, username), so
+/// restoring the same archive twice changes nothing. Credential data is re-encrypted under this
+/// vault's master key. Every record is decoded before anything is written.
+async fn restore_vault_archive(
+    service: &mut PersonaService,
+    wallets: &CryptoWalletRepository,
+    archive: &VaultArchive,
+) -> Result<ArchiveRestoreReport> {
+    let decoded = archive
+        .identities
+        .iter()
+        .map(|archived| {
+            archived
+                .credentials
+                .iter()
+                .map(|c| {
+                    let data = CredentialData::from_canonical_json(c.data.clone()).map_err(|e| {
+                        incompatible(
+                            format!("Credential '{}' could not be read ({})", c.credential.name, e),
+                            "Upgrade persona to import this archive",
+                        )
+                    })?;
+                    let attachments = c
+                        .attachments
+                        .iter()
+                        .map(|a| Ok((a.filename.as_str(), a.content()?)))
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok((data, attachments))
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut report = ArchiveRestoreReport::default();
+    let mut identity_ids = HashMap::new();
+    for (archived, decoded) in archive.identities.iter().zip(decoded) {
+        let identity = match service
+            .get_identity_by_name(&archived.identity.name)
+            .await
+            .into_anyhow()?
+        {
+            Some(existing) => existing,
+            None => {
+                let mut identity = archived.identity.clone();
+                if service.get_identity(&identity.id).await.into_anyhow()?.is_some() {
+                    identity.id = Uuid::new_v4();
+                }
+                report.identities_created += 1;
+                service.create_identity_full(identity).await.into_anyhow()?
+            }
+        };
+        identity_ids.insert(archived.identity.id, identity.id);
+
+        let mut seen: HashSet<CredentialKey> = service
+            .get_credentials_for_identity(&identity.id)
+            .await
+            .into_anyhow()?
+            .into_iter()
+            .map(|c| (c.name, c.url, c.username))
+            .collect();
+        for (archived_credential, (data, attachments)) in
+            archived.credentials.iter().zip(decoded)
+        {
+            let mut credential = archived_credential.credential.clone();
+            let key = (
+                credential.name.clone(),
+                credential.url.clone(),
+                credential.username.clone(),
+            );
+            if !seen.insert(key) {
+                report.duplicates += 1;
+                continue;
+            }
+            credential.identity_id = identity.id;
+            if service
+                .get_credential(&credential.id)
+                .await
+                .into_anyhow()?
+                .is_some()
+            {
+                credential.id = Uuid::new_v4();
+            }
+            let created = service
+                .create_credential_full(credential, &data)
+                .await
+                .into_anyhow()?;
+            report.credentials_imported += 1;
+            for (filename, content) in attachments {
+                service
+                    .add_attachment(created.id, filename, &content)
+                    .await
+                    .into_anyhow()?;
+                report.attachments += 1;
+            }
+        }
+    }
+
+    for wallet in &archive.wallets {
+        if wallets.find_by_id(&wallet.id).await.into_anyhow()?.is_some() {
+            report.wallets_skipped += 1;
+            continue;
+        }
+        let mut wallet = wallet.clone();
+        if let Some(id) = identity_ids.get(&wallet.identity_id) {
+            wallet.identity_id = *id;
+        }
+        wallets.create(&wallet).await.into_anyhow()?;
+        report.wallets_imported += 1;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::file_crypto::{encrypt_file_inplace, EncryptionParams, KdfParams};
+
+    const EXPORT: &str = r#"{
+        "export_info": {"version": "1.0", "created": "2024-01-01T00:00:00Z"},
+        "identities": [{"name": "work", "type": "work"}, {"name": "home"}]
+    }"#;
+
+    fn fast_kdf() -> EncryptionParams {
+        EncryptionParams {
+            kdf: KdfParams {
+                mem_kib: 1024,
+                iterations: 3,
+                parallelism: 1,
+            },
+            ..EncryptionParams::default()
+        }
+    }
+
+    fn write(dir: &Path, name: &str, content: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn encrypted_export(dir: &Path, manifest: ExportManifest) -> PathBuf {
+        let path = write(dir, "export.json", EXPORT.as_bytes());
+        encrypt_file_inplace(&path, "pass", manifest, fast_kdf()).unwrap();
+        path
+    }
+
+    fn no_passphrase() -> Result<String> {
+        panic!("passphrase requested for a file that should have been rejected first")
+    }
+
+    fn assert_rejected(result: Result<ImportReport>, needle: &str) {
+        let err = result.err().expect("file should be rejected");
+        assert!(
+            matches!(
+                err.downcast_ref::<PersonaError>(),
+                Some(PersonaError::Validation(_))
+            ),
+            "{:#}",
+            err
+        );
+        assert!(err.to_string().contains(needle), "{}", err);
+    }
+
+    #[test]
+    fn accepts_plain_and_encrypted_exports() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain = write(dir.path(), "plain.json", EXPORT.as_bytes());
+        let report = validate_import(&plain, false, no_passphrase).unwrap();
+        assert_eq!(report.format, "json");
+        assert_eq!(report.data.identities.len(), 2);
+
+        let encrypted = encrypted_export(dir.path(), ExportManifest::new("json", 2, 0));
+        let report = validate_import(&encrypted, true, || Ok("pass".to_string())).unwrap();
+        assert_eq!(report.encryption, Some(fast_kdf()));
+        assert_eq!(report.manifest.unwrap().identities_count, 2);
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain = write(
+            dir.path(),
+            "plain.json",
+            &EXPORT.as_bytes()[..EXPORT.len() / 2],
+        );
+        assert_rejected(validate_import(&plain, false, no_passphrase), "corrupt");
+
+        let encrypted = encrypted_export(dir.path(), ExportManifest::new("json", 2, 0));
+        let bytes = std::fs::read(&encrypted).unwrap();
+        std::fs::write(&encrypted, &bytes[..bytes.len() - 10]).unwrap();
+        assert_rejected(
+            validate_import(&encrypted, true, no_passphrase),
+            "integrity check",
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let newer = write(
+            dir.path(),
+            "newer.json",
+            EXPORT.replace("\"1.0\"", "\"2.0\"").as_bytes(),
+        );
+        assert_rejected(
+            validate_import(&newer, false, no_passphrase),
+            "Upgrade persona",
+        );
+
+        let mut manifest = ExportManifest::new("json", 2, 0);
+        manifest.schema_version += 1;
+        let encrypted = encrypted_export(dir.path(), manifest);
+        assert_rejected(
+            validate_import(&encrypted, true, no_passphrase),
+            "Upgrade persona",
+        );
+    }
+
+    #[test]
+    fn rejects_tampered_and_mismatched_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let encrypted = encrypted_export(dir.path(), ExportManifest::new("json", 2, 0));
+        let mut bytes = std::fs::read(&encrypted).unwrap();
+        let last = bytes.len() - 40;
+        bytes[last] ^= 0x01;
+        std::fs::write(&encrypted, &bytes).unwrap();
+        assert_rejected(
+            validate_import(&encrypted, true, || Ok("pass".to_string())),
+            "integrity check",
+        );
+
+        let encrypted = encrypted_export(dir.path(), ExportManifest::new("json", 2, 0));
+        assert_rejected(
+            validate_import(&encrypted, false, no_passphrase),
+            "--decrypt",
+        );
+
+        // The manifest promised credentials the payload does not contain
+        let missing = encrypted_export(dir.path(), ExportManifest::new("json", 2, 3));
+        assert_rejected(
+            validate_import(&missing, true, || Ok("pass".to_string())),
+            "lists 3 credentials but the file contains 0",
+        );
+
+        let binary = write(dir.path(), "garbage.json", &[0xff, 0xfe, 0x00, 0x01]);
+        assert_rejected(
+            validate_import(&binary, false, no_passphrase),
+            "not a text export",
+        );
+    }
+
+    async fn vault(password: &str, attachments: &Path) -> (PersonaService, CryptoWalletRepository) {
+        let db = persona_core::Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let mut service = PersonaService::new(db.clone()).await.unwrap();
+        service
+            .initialize_user(&SecretString::from(password), None)
+            .await
+            .unwrap();
+        service
+            .init_attachment_storage(attachments, db.clone())
+            .await
+            .unwrap();
+        (service, CryptoWalletRepository::new(std::sync::Arc::new(db)))
+    }
+
+    #[tokio::test]
+    async fn vault_archive_round_trips_between_vaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut source, source_wallets) = vault("source master", &dir.path().join("a")).await;
+        let alice = source
+            .create_identity("alice".to_string(), IdentityType::Personal)
+            .await
+            .unwrap();
+        let password = CredentialData::Password(PasswordCredentialData {
+            password: SecretString::from("hunter2"),
+            email: None,
+            security_questions: Vec::new(),
+        });
+        let bank = source
+            .create_credential(
+                alice.id,
+                "bank".to_string(),
+                CredentialType::Password,
+                SecurityLevel::High,
+                &password,
+            )
+            .await
+            .unwrap();
+        source
+            .add_attachment(bank.id, "sheet.txt", b"recovery sheet")
+            .await
+            .unwrap();
+        let wallet = persona_core::models::CryptoWallet::new_watch_only(
+            alice.id,
+            "cold".to_string(),
+            persona_core::models::BlockchainNetwork::Bitcoin,
+            "xpub-test".to_string(),
+        );
+        source_wallets.create(&wallet).await.unwrap();
+
+        let archive = crate::commands::export::collect_vault_archive(
+            &source,
+            &source_wallets,
+            &["alice".to_string()],
+            false,
+        )
+        .await
+        .unwrap();
+        let sealed = archive.seal("archive pass", fast_kdf()).unwrap();
+        assert!(!sealed.windows(7).any(|w| w == b"hunter2"));
+        let opened = VaultArchive::open(&sealed, || Ok("archive pass".to_string())).unwrap();
+
+        // A vault with a different master password reads everything back
+        let (mut target, target_wallets) = vault("target master", &dir.path().join("b")).await;
+        let report = restore_vault_archive(&mut target, &target_wallets, &opened)
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            ArchiveRestoreReport {
+                identities_created: 1,
+                credentials_imported: 1,
+                attachments: 1,
+                wallets_imported: 1,
+                ..ArchiveRestoreReport::default()
+            }
+        );
+        let restored = target.get_identity_by_name("alice").await.unwrap().unwrap();
+        let credential = target
+            .get_credentials_for_identity(&restored.id)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            target.get_credential_data(&credential.id).await.unwrap(),
+            Some(password)
+        );
+        let attachment = target.get_attachments(&credential.id).await.unwrap().remove(0);
+        let (_, content) = target.get_attachment(&attachment.id).await.unwrap().unwrap();
+        assert_eq!(content, b"recovery sheet");
+        assert_eq!(target_wallets.find_all().await.unwrap().len(), 1);
+
+        // Restoring again only reports duplicates
+        let again = restore_vault_archive(&mut target, &target_wallets, &opened)
+            .await
+            .unwrap();
+        assert_eq!((again.duplicates, again.wallets_skipped), (1, 1));
+        assert_eq!(again.credentials_imported, 0);
+    }
+}
@@ -6,8 +6,12 @@ use std::path::PathBuf;
 use tracing::warn;
 
 use crate::config::CliConfig;
+use crate::utils::open_database;
 use crate::utils::{create_directory, validate_workspace_path};
-use persona_core::{Database, PersonaService, Repository};
+use persona_core::{
+    calibrate_argon2, storage::create_private_dir_all, PasswordHashParams, PersonaService,
+    Repository, SecretString,
+};
 
 #[derive(Args)]
 pub struct InitArgs {
@@ -30,8 +34,15 @@ pub struct InitArgs {
     /// Backup directory path
     #[arg(long)]
     backup_dir: Option<PathBuf>,
+
+    /// Tune master password hashing cost to this machine (takes a few seconds)
+    #[arg(long)]
+    calibrate: bool,
 }
 
+/// Time a master password hash should take when `--calibrate` is used
+const CALIBRATION_TARGET_MS: u64 = 500;
+
 pub async fn execute(args: InitArgs, _config: &CliConfig) -> Result<()> {
     println!("{}", "🚀 Initializing Persona workspace...".cyan().bold());
     println!();
@@ -42,8 +53,8 @@ pub async fn execute(args: InitArgs, _config: &CliConfig) -> Result<()> {
     // Validate workspace path
     validate_workspace_path(&workspace_path)?;
 
-    // Create workspace directory
-    create_directory(&workspace_path).context("Failed to create workspace directory")?;
+    // Create workspace directory (owner-only when newly created)
+    create_private_dir_all(&workspace_path).context("Failed to create workspace directory")?;
 
     println!(
         "{} Workspace directory: {}",
@@ -74,7 +85,11 @@ pub async fn execute(args: InitArgs, _config: &CliConfig) -> Result<()> {
     initialize_config(&workspace_path, encryption_enabled, args.backup_dir)?;
 
     // Initialize database
-    initialize_database(&workspace_path, master_password.as_deref()).await?;
+    let hash_params = match &master_password {
+        Some(_) if args.calibrate => Some(calibrate_hash_params()?),
+        _ => None,
+    };
+    initialize_database(&workspace_path, master_password.as_ref(), hash_params).await?;
 
     println!();
     println!(
@@ -123,10 +138,13 @@ fn determine_workspace_path(path: Option<PathBuf>, yes: bool) -> Result<PathBuf>
     Ok(PathBuf::from(path_str))
 }
 
-fn get_master_password(provided_password: Option<String>, yes: bool) -> Result<Option<String>> {
+fn get_master_password(
+    provided_password: Option<String>,
+    yes: bool,
+) -> Result<Option<SecretString>> {
     if let Some(password) = provided_password {
         warn!("Using master password from command line is not recommended for security reasons");
-        return Ok(Some(password));
+        return Ok(Some(SecretString::new(password)));
     }
 
     if yes {
@@ -138,7 +156,7 @@ fn get_master_password(provided_password: Option<String>, yes: bool) -> Result<O
             password.bright_yellow().bold()
         );
         println!("{}", "Please save this password securely!".red().bold());
-        return Ok(Some(password));
+        return Ok(Some(SecretString::new(password)));
     }
 
     // Interactive mode
@@ -147,7 +165,7 @@ fn get_master_password(provided_password: Option<String>, yes: bool) -> Result<O
         .with_confirmation("Confirm master password", "Passwords don't match")
         .interact()?;
 
-    Ok(Some(password))
+    Ok(Some(SecretString::new(password)))
 }
 
 fn generate_random_password() -> String {
@@ -195,6 +213,7 @@ version = "0.1.0"
 encryption_enabled = {}
 auto_lock_timeout = 300  # seconds
 require_biometric = false
+permission_check = "warn"  # warn, refuse or off when the vault is readable by other users
 
 [backup]
 enabled = true
@@ -202,6 +221,7 @@ directory = "{}"
 auto_backup = true
 backup_interval = 86400  # seconds (24 hours)
 max_backups = 30
+backup_before_destructive = false  # snapshot before deletes, migrations and replacing imports
 
 [sync]
 enabled = false
@@ -232,14 +252,29 @@ max_files = 5
     Ok(())
 }
 
+fn calibrate_hash_params() -> Result<PasswordHashParams> {
+    println!("{} Calibrating password hashing...", "⏳".yellow());
+    let params = calibrate_argon2(CALIBRATION_TARGET_MS)
+        .map_err(|e| anyhow::anyhow!("Calibration failed: {}", e))?;
+    println!(
+        "{} Argon2id: {} MiB, {} iterations, {} lane(s)",
+        "✓".green().bold(),
+        params.memory_kib / 1024,
+        params.iterations,
+        params.parallelism
+    );
+    Ok(params)
+}
+
 async fn initialize_database(
     workspace_path: &PathBuf,
-    master_password: Option<&str>,
+    master_password: Option<&SecretString>,
+    hash_params: Option<PasswordHashParams>,
 ) -> Result<()> {
     let db_path = workspace_path.join("identities.db");
 
     // Initialize SQLite database with proper schema using persona-core
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to open workspace DB: {}", e))?;
 
@@ -266,12 +301,21 @@ async fn initialize_database(
             .map_err(|e| anyhow::anyhow!("Workspace lookup failed: {}", e))?
             .is_none()
         {
-            let ws = Workspace::new(workspace_path.clone(), name);
+            let mut ws = Workspace::new(workspace_path.clone(), name);
+            let phrase = ws.settings.reset_verification_phrase();
             // Persist; repo will choose proper schema (legacy/v2)
             let _ = repo
                 .create(&ws)
                 .await
                 .map_err(|e| anyhow::anyhow!("Workspace creation failed: {}", e))?;
+
+            println!(
+                "{} Verification phrase: {}",
+                "✓".green().bold(),
+                phrase.cyan().bold()
+            );
+            println!("  Persona shows this phrase before asking for your master password.");
+            println!("  If it is missing or different, do not enter your password.");
         }
     }
 
@@ -282,7 +326,7 @@ async fn initialize_database(
             .map_err(|e| anyhow::anyhow!("Failed to create PersonaService: {}", e))?;
 
         // Initialize first-time user
-        match service.initialize_user(password).await {
+        match service.initialize_user(password, hash_params).await {
             Ok(_user_id) => {
                 println!("{} Initialized user authentication", "✓".green().bold());
             }
@@ -3,11 +3,17 @@ use clap::Args;
 use colored::*;
 use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tabled::{Table, Tabled};
+use uuid::Uuid;
 
+use crate::commands::auth;
 use crate::config::CliConfig;
-use persona_core::{Database, Identity as CoreIdentity, PersonaService, Repository};
+use crate::utils::open_database;
+use persona_core::{
+    storage::{CredentialRepository, CredentialSearchHit},
+    Credential, Identity as CoreIdentity, PersonaService, Repository,
+};
 
 #[derive(Args)]
 pub struct ListArgs {
@@ -19,7 +25,8 @@ pub struct ListArgs {
     #[arg(short, long)]
     tag: Option<String>,
 
-    /// Search in names and descriptions
+    /// Search identity names and descriptions, and credential names, usernames, URLs, notes
+    /// and tags; identities owning a matching credential are listed too
     #[arg(short, long)]
     search: Option<String>,
 
@@ -42,6 +49,10 @@ pub struct ListArgs {
     /// Reverse sort order
     #[arg(long)]
     reverse: bool,
+
+    /// List the most recently used credentials instead of identities (10 unless a count is given)
+    #[arg(long, value_name = "COUNT", num_args = 0..=1, default_missing_value = "10")]
+    recent: Option<u32>,
 }
 
 #[derive(Debug, Tabled)]
@@ -83,14 +94,18 @@ struct DetailedIdentityRow {
 }
 
 pub async fn execute(args: ListArgs, config: &CliConfig) -> Result<()> {
+    if let Some(limit) = args.recent {
+        return list_recent(limit, &args.format, config).await;
+    }
+
     println!("{}", "📋 Listing identities...".cyan().bold());
     println!();
 
-    // Fetch identities from database
-    let mut identities = fetch_identities(config).await?;
+    // Fetch identities (and credential matches for --search) from database
+    let (mut identities, hits) = fetch_identities(config, args.search.as_deref()).await?;
 
     // Apply filters
-    identities = apply_filters(identities, &args)?;
+    identities = apply_filters(identities, &hits, &args)?;
 
     // Sort identities
     sort_identities(&mut identities, &args.sort_by, args.reverse)?;
@@ -105,7 +120,10 @@ pub async fn execute(args: ListArgs, config: &CliConfig) -> Result<()> {
 
     // Display results
     match args.format.as_str() {
-        "table" => display_table(&identities, args.detailed)?,
+        "table" => {
+            display_table(&identities, args.detailed)?;
+            display_credential_hits(&identities, &hits);
+        }
         "json" => display_json(&identities)?,
         "yaml" => display_yaml(&identities)?,
         "csv" => display_csv(&identities, args.detailed)?,
@@ -121,8 +139,99 @@ pub async fn execute(args: ListArgs, config: &CliConfig) -> Result<()> {
     Ok(())
 }
 
+#[derive(Debug, Tabled)]
+struct RecentRow {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Identity")]
+    identity: String,
+    #[tabled(rename = "Type")]
+    credential_type: String,
+    #[tabled(rename = "Username")]
+    username: String,
+    #[tabled(rename = "Last used")]
+    last_used: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RecentCredential {
+    id: Uuid,
+    name: String,
+    identity: String,
+    credential_type: String,
+    username: Option<String>,
+    last_used: String,
+}
+
+/// `--recent`: credentials ordered by when they were last opened
+async fn list_recent(limit: u32, format: &str, config: &CliConfig) -> Result<()> {
+    let (identities, credentials) = fetch_recent(config, limit).await?;
+    let names: HashMap<Uuid, String> = identities
+        .into_iter()
+        .map(|identity| (identity.id, identity.name))
+        .collect();
+    let recent: Vec<RecentCredential> = credentials
+        .into_iter()
+        .map(|credential| RecentCredential {
+            id: credential.id,
+            identity: names
+                .get(&credential.identity_id)
+                .cloned()
+                .unwrap_or_else(|| "-".to_string()),
+            credential_type: credential.credential_type.to_string().to_lowercase(),
+            username: credential.username,
+            last_used: credential
+                .last_accessed
+                .map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default(),
+            name: credential.name,
+        })
+        .collect();
+
+    match format {
+        "table" => {
+            if recent.is_empty() {
+                println!("{}", "No credentials have been used yet.".yellow());
+                return Ok(());
+            }
+            println!("{}", "🕘 Recently used credentials".cyan().bold());
+            println!();
+            let rows: Vec<RecentRow> = recent
+                .iter()
+                .map(|credential| RecentRow {
+                    name: credential.name.clone(),
+                    identity: credential.identity.clone(),
+                    credential_type: credential.credential_type.clone(),
+                    username: credential.username.as_deref().unwrap_or("-").to_string(),
+                    last_used: credential.last_used.clone(),
+                })
+                .collect();
+            println!("{}", Table::new(rows));
+        }
+        "json" => println!("{}", serde_json::to_string_pretty(&recent)?),
+        "yaml" => println!("{}", serde_yaml::to_string(&recent)?),
+        "csv" => {
+            println!("Name,Identity,Type,Username,Last used");
+            for credential in &recent {
+                println!(
+                    "{},{},{},{},{}",
+                    credential.name,
+                    credential.identity,
+                    credential.credential_type,
+                    credential.username.as_deref().unwrap_or(""),
+                    credential.last_used
+                );
+            }
+        }
+        _ => anyhow::bail!("Unsupported output format: {}", format),
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct Identity {
+    #[serde(skip)]
+    id: Uuid,
     name: String,
     identity_type: String,
     description: String,
@@ -135,11 +244,30 @@ struct Identity {
     attributes: HashMap<String, Value>,
 }
 
-async fn fetch_identities(config: &CliConfig) -> Result<Vec<Identity>> {
-    use dialoguer::Password;
+async fn fetch_identities(
+    config: &CliConfig,
+    search: Option<&str>,
+) -> Result<(Vec<Identity>, Vec<CredentialSearchHit>)> {
+    if config.demo {
+        let service = PersonaService::demo()
+            .await
+            .map_err(|e| anyhow!("Failed to open demo vault: {}", e))?;
+        let items = service
+            .get_identities()
+            .await
+            .map_err(|e| anyhow!("Failed to fetch identities: {}", e))?;
+        let hits = match search {
+            Some(query) => service
+                .search_credentials_ranked(query)
+                .await
+                .map_err(|e| anyhow!("Failed to search credentials: {}", e))?,
+            None => Vec::new(),
+        };
+        return Ok((items.into_iter().map(map_identity).collect(), hits));
+    }
     // Open DB
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to connect to database: {}", e))?;
     db.migrate()
@@ -151,55 +279,122 @@ async fn fetch_identities(config: &CliConfig) -> Result<Vec<Identity>> {
     let mut service = PersonaService::new(db)
         .await
         .map_err(|e| anyhow!("Failed to create PersonaService: {}", e))?;
-    let items: Vec<CoreIdentity> = if service
+    let (items, hits): (Vec<CoreIdentity>, Vec<CredentialSearchHit>) = if service
         .has_users()
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        let items = service
+            .get_identities()
             .await
-            .map_err(|e| anyhow!("Failed to authenticate user: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => service
-                .get_identities()
+            .map_err(|e| anyhow!("Failed to fetch identities: {}", e))?;
+        let hits = match search {
+            Some(query) => service
+                .search_credentials_ranked(query)
                 .await
-                .map_err(|e| anyhow!("Failed to fetch identities: {}", e))?,
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+                .map_err(|e| anyhow!("Failed to search credentials: {}", e))?,
+            None => Vec::new(),
+        };
+        (items, hits)
     } else {
         // Fallback: when no users set up, read directly via repository (data is not encrypted)
-        let repo = persona_core::storage::IdentityRepository::new(db_clone);
-        repo.find_all()
+        let repo = persona_core::storage::IdentityRepository::new(db_clone.clone());
+        let items = repo
+            .find_all()
             .await
-            .map_err(|e| anyhow!("Failed to read identities: {}", e))?
+            .map_err(|e| anyhow!("Failed to read identities: {}", e))?;
+        let hits = match search {
+            Some(query) => CredentialRepository::new(db_clone)
+                .search_full_text(query)
+                .await
+                .map_err(|e| anyhow!("Failed to search credentials: {}", e))?,
+            None => Vec::new(),
+        };
+        (items, hits)
     };
-    let mapped: Vec<Identity> = items
-        .into_iter()
-        .map(|id| Identity {
-            name: id.name,
-            identity_type: id.identity_type.to_string().to_lowercase(),
-            description: id.description.unwrap_or_default(),
-            email: id.email,
-            phone: id.phone,
-            tags: id.tags,
-            active: id.is_active,
-            created: id.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-            modified: id.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-            attributes: id
-                .attributes
-                .into_iter()
-                .map(|(k, v)| (k, Value::String(v)))
-                .collect(),
-        })
-        .collect();
-    Ok(mapped)
+    Ok((items.into_iter().map(map_identity).collect(), hits))
+}
+
+async fn fetch_recent(
+    config: &CliConfig,
+    limit: u32,
+) -> Result<(Vec<CoreIdentity>, Vec<Credential>)> {
+    if config.demo {
+        let service = PersonaService::demo()
+            .await
+            .map_err(|e| anyhow!("Failed to open demo vault: {}", e))?;
+        return recent_from_service(&service, limit).await;
+    }
+    let db = open_database(&config.get_database_path())
+        .await
+        .map_err(|e| anyhow!("Failed to connect to database: {}", e))?;
+    db.migrate()
+        .await
+        .map_err(|e| anyhow!("Failed to run database migrations: {}", e))?;
+    let mut service = PersonaService::new(db.clone())
+        .await
+        .map_err(|e| anyhow!("Failed to create PersonaService: {}", e))?;
+    if service
+        .has_users()
+        .await
+        .map_err(|e| anyhow!("Failed to check users: {}", e))?
+    {
+        auth::unlock(&mut service).await?;
+        return recent_from_service(&service, limit).await;
+    }
+    // No users set up: read directly via the repositories (data is not encrypted)
+    let identities = persona_core::storage::IdentityRepository::new(db.clone())
+        .find_all()
+        .await
+        .map_err(|e| anyhow!("Failed to read identities: {}", e))?;
+    let credentials = CredentialRepository::new(db)
+        .find_recently_used(limit)
+        .await
+        .map_err(|e| anyhow!("Failed to read recently used credentials: {}", e))?;
+    Ok((identities, credentials))
+}
+
+async fn recent_from_service(
+    service: &PersonaService,
+    limit: u32,
+) -> Result<(Vec<CoreIdentity>, Vec<Credential>)> {
+    let identities = service
+        .get_identities()
+        .await
+        .map_err(|e| anyhow!("Failed to fetch identities: {}", e))?;
+    let credentials = service
+        .recently_used(limit)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch recently used credentials: {}", e))?;
+    Ok((identities, credentials))
+}
+
+fn map_identity(id: CoreIdentity) -> Identity {
+    Identity {
+        id: id.id,
+        name: id.name,
+        identity_type: id.identity_type.to_string().to_lowercase(),
+        description: id.description.unwrap_or_default(),
+        email: id.email,
+        phone: id.phone,
+        tags: id.tags,
+        active: id.is_active,
+        created: id.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        modified: id.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        attributes: id
+            .attributes
+            .into_iter()
+            .map(|(k, v)| (k, Value::String(v)))
+            .collect(),
+    }
 }
 
-fn apply_filters(mut identities: Vec<Identity>, args: &ListArgs) -> Result<Vec<Identity>> {
+fn apply_filters(
+    mut identities: Vec<Identity>,
+    hits: &[CredentialSearchHit],
+    args: &ListArgs,
+) -> Result<Vec<Identity>> {
     // Filter by active only
     if args.active_only {
         identities.retain(|id| id.active);
@@ -226,10 +421,12 @@ fn apply_filters(mut identities: Vec<Identity>, args: &ListArgs) -> Result<Vec<I
     // Search filter
     if let Some(ref search_term) = args.search {
         let search_lower = search_term.to_lowercase();
+        let owners: HashSet<Uuid> = hits.iter().map(|hit| hit.credential.identity_id).collect();
         identities.retain(|id| {
             id.name.to_lowercase().contains(&search_lower)
                 || id.description.to_lowercase().contains(&search_lower)
                 || id.identity_type.to_lowercase().contains(&search_lower)
+                || owners.contains(&id.id)
         });
     }
 
@@ -307,6 +504,37 @@ fn display_table(identities: &[Identity], detailed: bool) -> Result<()> {
     Ok(())
 }
 
+/// Credentials matching `--search` among the listed identities, most relevant first
+fn display_credential_hits(identities: &[Identity], hits: &[CredentialSearchHit]) {
+    let owners: HashMap<Uuid, &str> = identities
+        .iter()
+        .map(|id| (id.id, id.name.as_str()))
+        .collect();
+    let shown: Vec<_> = hits
+        .iter()
+        .filter_map(|hit| Some((hit, *owners.get(&hit.credential.identity_id)?)))
+        .collect();
+    if shown.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", "Matching credentials:".yellow().bold());
+    for (hit, identity) in shown {
+        let field = hit
+            .matched_field
+            .map(|field| field.label())
+            .unwrap_or("any");
+        println!(
+            "  {} {} ({} match, score {})",
+            hit.credential.name.cyan(),
+            format!("[{}]", identity).dimmed(),
+            field,
+            hit.score
+        );
+    }
+}
+
 fn display_json(identities: &[Identity]) -> Result<()> {
     let json = serde_json::to_string_pretty(identities)?;
     println!("{}", json);
@@ -0,0 +1,172 @@
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use colored::*;
+use indicatif::ProgressBar;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::utils::open_database;
+use crate::{
+    config::CliConfig,
+    utils::{core_ext::CoreResultExt, format_file_size, lock_vault, progress::create_progress_bar},
+};
+use persona_core::{VacuumReport, SEARCH_INDEX_BATCH_SIZE};
+
+#[derive(Args, Debug)]
+pub struct MaintenanceArgs {
+    #[command(subcommand)]
+    command: MaintenanceCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum MaintenanceCommand {
+    /// Reclaim space left by deletes and check the database for corruption
+    Vacuum {
+        /// Release free pages without rebuilding the file (needs one full vacuum first)
+        #[arg(long)]
+        incremental: bool,
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    /// Rebuild the full-text search index from the credentials table
+    Reindex {
+        /// Credentials indexed per batch
+        #[arg(long, default_value_t = SEARCH_INDEX_BATCH_SIZE)]
+        batch_size: u64,
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+}
+
+pub async fn execute(args: MaintenanceArgs, config: &CliConfig) -> Result<()> {
+    match args.command {
+        MaintenanceCommand::Vacuum {
+            incremental,
+            format,
+        } => vacuum(config, incremental, &format).await,
+        MaintenanceCommand::Reindex { batch_size, format } => {
+            reindex(config, batch_size, &format).await
+        }
+    }
+}
+
+async fn vacuum(config: &CliConfig, incremental: bool, format: &str) -> Result<()> {
+    if !matches!(format, "text" | "json") {
+        anyhow::bail!("Unsupported format '{}' (expected text or json)", format);
+    }
+    let db_path = config.get_database_path();
+    if !db_path.exists() {
+        anyhow::bail!("No database at {}", db_path.display());
+    }
+    let _lock = lock_vault(config, "vacuum")?;
+    let db = open_database(&db_path)
+        .await
+        .into_anyhow()
+        .with_context(|| format!("Failed to connect to database: {}", db_path.display()))?;
+
+    let report = db.compact(incremental).await.into_anyhow()?;
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+
+    if !report.integrity_errors.is_empty() {
+        anyhow::bail!(
+            "Integrity check found {} problem(s); restore from a backup",
+            report.integrity_errors.len()
+        );
+    }
+    Ok(())
+}
+
+async fn reindex(config: &CliConfig, batch_size: u64, format: &str) -> Result<()> {
+    if !matches!(format, "text" | "json") {
+        anyhow::bail!("Unsupported format '{}' (expected text or json)", format);
+    }
+    if batch_size == 0 {
+        anyhow::bail!("--batch-size must be at least 1");
+    }
+    let db_path = config.get_database_path();
+    if !db_path.exists() {
+        anyhow::bail!("No database at {}", db_path.display());
+    }
+    let _lock = lock_vault(config, "reindex")?;
+    let db = open_database(&db_path)
+        .await
+        .into_anyhow()
+        .with_context(|| format!("Failed to connect to database: {}", db_path.display()))?;
+
+    // Ctrl-C stops the rebuild between batches; its transaction is rolled back
+    let cancel = Arc::new(AtomicBool::new(false));
+    let on_interrupt = tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancel.store(true, Ordering::Relaxed);
+            }
+        }
+    });
+    let mut pb: Option<ProgressBar> = None;
+    let result = db
+        .rebuild_search_index(batch_size, &cancel, |indexed, total| {
+            if format == "text" {
+                pb.get_or_insert_with(|| create_progress_bar(total, "Indexing credentials"))
+                    .set_position(indexed);
+            }
+        })
+        .await;
+    on_interrupt.abort();
+    if let Some(pb) = pb {
+        pb.finish_and_clear();
+    }
+
+    let Some(report) = result.into_anyhow()? else {
+        anyhow::bail!("Reindex interrupted; the previous search index was left unchanged");
+    };
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "{} Search index rebuilt: {} of {} credentials indexed",
+            "✓".green().bold(),
+            report.indexed.to_string().cyan(),
+            report.credentials
+        );
+    }
+    Ok(())
+}
+
+fn print_report(report: &VacuumReport) {
+    let kind = if report.incremental {
+        "Incremental vacuum"
+    } else {
+        "Vacuum"
+    };
+    println!(
+        "{} {} complete: {} → {} ({} reclaimed)",
+        "✓".green().bold(),
+        kind,
+        format_file_size(report.before.total_bytes() as u64),
+        format_file_size(report.after.total_bytes() as u64),
+        format_file_size(report.reclaimed_bytes() as u64).cyan()
+    );
+    if report.after.free_pages > 0 {
+        println!(
+            "  {} still free ({} pages)",
+            format_file_size(report.after.free_bytes() as u64),
+            report.after.free_pages
+        );
+    }
+
+    if report.integrity_errors.is_empty() {
+        println!("{} Integrity check passed", "✓".green().bold());
+    } else {
+        println!("{} Integrity check failed:", "✗".red().bold());
+        for problem in &report.integrity_errors {
+            println!("  {}", problem.red());
+        }
+    }
+}
@@ -1,36 +1,99 @@
-use crate::utils::core_ext::CoreResultExt;
+use crate::config::CliConfig;
+use crate::utils::open_database;
+use crate::utils::{core_ext::CoreResultExt, lock_vault, print_safety_backup};
 use anyhow::{Context, Result};
-use clap::Args;
+use clap::{Args, Subcommand};
 use colored::*;
 use persona_core::{
     models::{AuditAction, AuditLog, ResourceType, Workspace},
-    storage::{AuditLogRepository, WorkspaceRepository},
+    storage::{AuditLogRepository, BackupGuard, MigrationStatus, WorkspaceRepository},
     Database, Repository,
 };
+use std::path::Path;
 
 #[derive(Args, Debug)]
 pub struct MigrateArgs {
     /// Force run migrations even if the database appears up-to-date
     #[arg(long)]
     force: bool,
+
+    /// Apply the pending migrations; without this the command only lists them
+    #[arg(long)]
+    apply: bool,
+
+    #[command(subcommand)]
+    action: Option<MigrateAction>,
+}
+
+#[derive(Subcommand, Debug)]
+enum MigrateAction {
+    /// Convert a plaintext vault database to whole-file SQLCipher encryption
+    EncryptDb {
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
 }
 
-pub async fn execute(args: MigrateArgs, config: &crate::config::CliConfig) -> Result<()> {
+pub async fn execute(args: MigrateArgs, config: &CliConfig) -> Result<()> {
+    if let Some(MigrateAction::EncryptDb { yes }) = args.action {
+        return encrypt_db(yes, config).await;
+    }
+
+    let db_path = config.get_database_path();
+    if !args.apply {
+        let db = open_database(&db_path)
+            .await
+            .into_anyhow()
+            .with_context(|| format!("Failed to open database at {}", db_path.display()))?;
+        let status = db.migration_status().await.into_anyhow()?;
+        print_migration_status(&status);
+        if !status.is_up_to_date() && !status.is_newer_than_build() {
+            println!();
+            println!("Run {} to apply them.", "persona migrate --apply".cyan());
+        }
+        return Ok(());
+    }
+
     println!("{}", "🗃  Running database migrations...".cyan().bold());
+    let _lock = lock_vault(config, "migrate")?;
 
     // Open DB
-    let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .into_anyhow()
         .with_context(|| format!("Failed to open database at {}", db_path.display()))?;
+    let status = db.migration_status().await.into_anyhow()?;
+
+    // Snapshot first when migrations would change an existing vault
+    if let Some(dir) = config.destructive_backup_dir() {
+        if status.current.is_some() && !status.is_up_to_date() {
+            if let Some(path) = BackupGuard::new(dir)
+                .snapshot(&db, "migrate")
+                .await
+                .into_anyhow()?
+            {
+                print_safety_backup(&path);
+            }
+        }
+    }
 
-    // Run migrations (idempotent)
+    // Each pending migration runs in its own transaction; applied ones are skipped
     db.migrate()
         .await
         .into_anyhow()
         .context("Failed to run migrations")?;
-    println!("{} Applied migrations (if any)", "✓".green().bold());
+    if status.is_up_to_date() {
+        println!("{} Schema already up to date", "✓".green().bold());
+    }
+    for migration in &status.pending {
+        println!(
+            "{} Applied migration {:03} {}",
+            "✓".green().bold(),
+            migration.version,
+            migration.description
+        );
+    }
 
     // Ensure Workspace row exists and is v2-compatible
     let repo = WorkspaceRepository::new(db.clone());
@@ -90,3 +153,154 @@ pub async fn execute(args: MigrateArgs, config: &crate::config::CliConfig) -> Re
     println!("{}", "Done.".green().bold());
     Ok(())
 }
+
+/// Current and latest schema versions, then the migrations still to apply
+fn print_migration_status(status: &MigrationStatus) {
+    println!("{}", "Schema migrations:".yellow().bold());
+    match status.current {
+        Some(current) => println!("  Current: {}", current.to_string().cyan()),
+        None => println!("  Current: {}", "none (new database)".dimmed()),
+    }
+    println!("  Latest:  {}", status.latest.to_string().cyan());
+    if status.is_newer_than_build() {
+        println!(
+            "{} This vault was migrated by a newer Persona; upgrade persona to use it.",
+            "⚠".yellow()
+        );
+    } else if status.is_up_to_date() {
+        println!("{} Schema up to date", "✓".green().bold());
+    } else {
+        println!();
+        println!("{}", "Pending migrations:".yellow().bold());
+        for migration in &status.pending {
+            println!("  {:03} {}", migration.version, migration.description);
+        }
+    }
+}
+
+/// Encrypt the whole vault file under the master password.
+///
+/// The encrypted copy is written beside the database, checked, and renamed over it, so an
+/// interrupted conversion leaves the plaintext vault in place.
+async fn encrypt_db(yes: bool, config: &CliConfig) -> Result<()> {
+    let _lock = lock_vault(config, "migrate encrypt-db")?;
+    let db_path = config.get_database_path();
+    if !db_path.exists() {
+        anyhow::bail!("No vault database at {}", db_path.display());
+    }
+    if Database::is_encrypted_file(&db_path).into_anyhow()? {
+        println!(
+            "{} {} is already encrypted at rest",
+            "✓".green().bold(),
+            db_path.display()
+        );
+        return Ok(());
+    }
+    encrypt_plaintext(&db_path, yes, config).await
+}
+
+#[cfg(feature = "sqlcipher")]
+async fn encrypt_plaintext(db_path: &Path, yes: bool, config: &CliConfig) -> Result<()> {
+    use crate::{commands::auth, utils::confirm_action};
+    use persona_core::PersonaService;
+
+    let db = Database::from_file(db_path)
+        .await
+        .into_anyhow()
+        .with_context(|| format!("Failed to open database at {}", db_path.display()))?;
+    db.migrate()
+        .await
+        .into_anyhow()
+        .context("Failed to run migrations")?;
+    let mut service = PersonaService::new(db.clone()).await.into_anyhow()?;
+    if !service.has_users().await.into_anyhow()? {
+        anyhow::bail!("This vault has no master password; create one with `persona init`");
+    }
+    let password = auth::read_master_password("Enter master password to encrypt the vault")?;
+    auth::unlock_with_password(&mut service, &password).await?;
+
+    if !yes
+        && !confirm_action(
+            &format!(
+                "Encrypt {} under the master password? Only builds with SQLCipher support can open it afterwards",
+                db_path.display()
+            ),
+            false,
+        )?
+    {
+        println!("Cancelled. Use --yes to skip confirmation.");
+        return Ok(());
+    }
+
+    let file_name = db_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let staged = db_path.with_file_name(format!(".{}.encrypting", file_name));
+    remove_if_exists(&staged)?;
+    let written = async {
+        db.export_encrypted(&staged, &password).await?;
+        let copy = Database::from_file_encrypted(&staged, &password).await?;
+        let errors = copy.integrity_check().await?;
+        let version = copy.schema_version().await?;
+        if !errors.is_empty() || version != db.schema_version().await? {
+            anyhow::bail!(
+                "The encrypted copy failed verification: {}",
+                errors.join("; ")
+            );
+        }
+        let log = AuditLog::new(AuditAction::DatabaseMigration, ResourceType::Database, true)
+            .with_resource_id(Some(db_path.display().to_string()));
+        AuditLogRepository::new(copy.clone()).create(&log).await?;
+        copy.close().await;
+        Ok(())
+    }
+    .await;
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&staged);
+        return Err(e).context("Failed to encrypt the vault; it is unchanged");
+    }
+
+    drop(service);
+    db.close().await;
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let mut journal = db_path.as_os_str().to_owned();
+        journal.push(suffix);
+        remove_if_exists(Path::new(&journal))?;
+    }
+    std::fs::rename(&staged, db_path)
+        .with_context(|| format!("Failed to replace {}", db_path.display()))?;
+
+    println!(
+        "{} {} is now encrypted at rest",
+        "✓".green().bold(),
+        db_path.display().to_string().cyan()
+    );
+    println!("  Names, URLs, usernames and metadata are no longer readable from the file.");
+    println!(
+        "  {} Backups in {} made before now are still plaintext; delete them once a new backup exists.",
+        "⚠".yellow(),
+        config.backup.directory.display()
+    );
+    println!(
+        "  {} Recovery codes cannot open an encrypted vault; keep the master password safe.",
+        "⚠".yellow()
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+async fn encrypt_plaintext(_db_path: &Path, _yes: bool, _config: &CliConfig) -> Result<()> {
+    anyhow::bail!(
+        "Whole-database encryption needs SQLCipher; rebuild with `cargo build --features sqlcipher`"
+    )
+}
+
+#[cfg(feature = "sqlcipher")]
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e).with_context(|| format!("Failed to remove {}", path.display())),
+    }
+}
@@ -1,5 +1,10 @@
 pub mod add;
+pub mod address_book;
+pub mod agent;
+pub mod audit;
+pub mod auth;
 pub mod auto_lock;
+pub mod backup;
 pub mod bridge;
 pub mod credential;
 pub mod edit;
@@ -7,12 +12,18 @@ pub mod export;
 pub mod import;
 pub mod init;
 pub mod list;
+pub mod maintenance;
 pub mod migrate;
+pub mod passwd;
 pub mod password;
+pub mod recovery;
 pub mod remove;
 pub mod show;
 pub mod ssh;
+pub mod status;
 pub mod switch;
 pub mod totp;
 pub mod tui;
+pub mod verification_phrase;
+pub mod version;
 pub mod wallet;
@@ -0,0 +1,134 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::*;
+
+use crate::{
+    commands::auth,
+    config::CliConfig,
+    utils::{core_ext::CoreResultExt, lock_vault, open_database},
+};
+use persona_core::{Database, MasterPasswordChange, PersonaService, SecretString};
+
+#[derive(Args, Debug)]
+pub struct PasswdArgs {}
+
+/// Change the master password.
+///
+/// The current password is read like any unlock (terminal, else `PERSONA_MASTER_PASSWORD`);
+/// the new one is asked twice on a terminal, else read from `PERSONA_NEW_MASTER_PASSWORD`.
+/// Everything is re-encrypted in one transaction, so an interrupted change leaves the vault
+/// under the old password. A vault encrypted at rest is rekeyed first and rekeyed back if the
+/// change then fails, so its file always opens with the password that unlocks it.
+pub async fn execute(_args: PasswdArgs, config: &CliConfig) -> Result<()> {
+    let _lock = lock_vault(config, "passwd")?;
+    let db_path = config.get_database_path();
+    let encrypted = Database::is_encrypted_file(&db_path).into_anyhow()?;
+    let (mut service, db) = open_service(config, open_database(&db_path).await).await?;
+    if !service.has_users().await.into_anyhow()? {
+        anyhow::bail!("This vault has no master password; create one with `persona init`");
+    }
+
+    let current = auth::read_master_password("Current master password")?;
+    auth::unlock_with_password(&mut service, &current).await?;
+    let new = auth::read_new_master_password()?;
+    if new.expose() == current.expose() {
+        anyhow::bail!("The new master password is the same as the current one");
+    }
+
+    println!("{}", "Re-encrypting the vault...".dimmed());
+    let summary = if encrypted {
+        drop(service);
+        db.close().await;
+        change_encrypted(config, &current, &new).await?
+    } else {
+        service
+            .change_master_password(&current, &new)
+            .await
+            .into_anyhow()
+            .context("Master password was not changed")?
+    };
+
+    println!("{} Master password changed.", "✓".green().bold());
+    println!("  Credentials re-encrypted: {}", summary.credentials);
+    println!("  Previous secrets:         {}", summary.previous_secrets);
+    println!("  Attachments:              {}", summary.attachments);
+    println!("  Wallet keys:              {}", summary.wallets);
+    if summary.revoked_recovery_codes > 0 {
+        println!();
+        println!(
+            "{} {} unused recovery code(s) no longer work; run `persona recovery codes generate` for new ones.",
+            "⚠".yellow(),
+            summary.revoked_recovery_codes
+        );
+    }
+    Ok(())
+}
+
+/// Rekey the database file to `new`, then change the password inside it
+#[cfg(feature = "sqlcipher")]
+async fn change_encrypted(
+    config: &CliConfig,
+    current: &SecretString,
+    new: &SecretString,
+) -> Result<MasterPasswordChange> {
+    let db_path = config.get_database_path();
+    Database::rekey_file(&db_path, current, new)
+        .await
+        .into_anyhow()
+        .context("Failed to re-encrypt the database file; the master password was not changed")?;
+
+    let changed = async {
+        let (mut service, db) =
+            open_service(config, Database::from_file_encrypted(&db_path, new).await).await?;
+        auth::unlock_with_password(&mut service, current).await?;
+        let summary = service
+            .change_master_password(current, new)
+            .await
+            .into_anyhow();
+        drop(service);
+        db.close().await;
+        summary
+    }
+    .await;
+    match changed {
+        Ok(summary) => Ok(summary),
+        Err(e) => {
+            Database::rekey_file(&db_path, new, current)
+                .await
+                .into_anyhow()
+                .context("The change failed and the database file could not be returned to the old password; it now opens with the new password while the vault inside still unlocks with the old one")?;
+            Err(e.context("Master password was not changed"))
+        }
+    }
+}
+
+/// Encrypted vaults cannot be opened without SQLCipher, so this is never reached
+#[cfg(not(feature = "sqlcipher"))]
+async fn change_encrypted(
+    _config: &CliConfig,
+    _current: &SecretString,
+    _new: &SecretString,
+) -> Result<MasterPasswordChange> {
+    anyhow::bail!("This build has no SQLCipher support; rebuild with `--features sqlcipher`")
+}
+
+async fn open_service(
+    config: &CliConfig,
+    db: persona_core::Result<Database>,
+) -> Result<(PersonaService, Database)> {
+    let db_path = config.get_database_path();
+    let db = db
+        .into_anyhow()
+        .with_context(|| format!("Failed to connect to database: {}", db_path.display()))?;
+    db.migrate()
+        .await
+        .into_anyhow()
+        .context("Failed to run database migrations")?;
+    let mut service = PersonaService::new(db.clone()).await.into_anyhow()?;
+    service
+        .init_attachment_storage(config.get_attachments_path(), db.clone())
+        .await
+        .into_anyhow()
+        .context("Failed to open attachment storage")?;
+    Ok((service, db))
+}
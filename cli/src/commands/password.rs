@@ -1,9 +1,9 @@
 use std::collections::HashSet;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Args, Subcommand, ValueEnum};
 use colored::*;
-use persona_core::{PasswordGenerator, PasswordGeneratorOptions};
+use persona_core::{password_strength, PasswordGenerator, PasswordGeneratorOptions, SecretString};
 
 use crate::config::CliConfig;
 
@@ -17,6 +17,14 @@ pub struct PasswordArgs {
 pub enum PasswordCommand {
     /// Generate one or more passwords
     Generate(GenerateArgs),
+    /// Estimate the strength of an existing password
+    Check(CheckArgs),
+}
+
+#[derive(Args, Clone)]
+pub struct CheckArgs {
+    /// Password to check (prompted for when omitted, so it stays out of shell history)
+    pub password: Option<String>,
 }
 
 #[derive(Args, Clone)]
@@ -59,6 +67,7 @@ pub enum CharacterSet {
 pub async fn execute(args: PasswordArgs, _config: &CliConfig) -> Result<()> {
     match args.command {
         PasswordCommand::Generate(opts) => generate_password(opts),
+        PasswordCommand::Check(opts) => check_password(opts),
     }
 }
 
@@ -79,23 +88,31 @@ fn generate_password(args: GenerateArgs) -> Result<()> {
     };
 
     for idx in 1..=args.count {
-        let password = PasswordGenerator::generate(&options)?;
+        let generated = PasswordGenerator::generate_with_entropy(&options)?;
         if args.count == 1 {
             println!(
                 "{} Generated password (length {}): {}",
                 "✓".green().bold(),
                 args.length,
-                password.cyan().bold()
+                generated.password.cyan().bold()
             );
         } else {
             println!(
                 "{} {}",
                 format!("[{}]", idx).dimmed(),
-                password.cyan().bold()
+                generated.password.cyan().bold()
             );
         }
     }
 
+    let entropy = options.entropy_bits();
+    println!(
+        "{} Estimated entropy: {} bits ({})",
+        "ℹ".blue(),
+        format!("{:.0}", entropy).cyan(),
+        entropy_rating(entropy)
+    );
+
     if args.pronounceable {
         println!(
             "{} Pronounceable mode enabled – alternating consonant/vowel pattern.",
@@ -105,3 +122,50 @@ fn generate_password(args: GenerateArgs) -> Result<()> {
 
     Ok(())
 }
+
+fn check_password(args: CheckArgs) -> Result<()> {
+    let password = match args.password {
+        Some(password) => SecretString::from(password),
+        None => SecretString::from(
+            rpassword::prompt_password("Password to check: ").context("Failed to read password")?,
+        ),
+    };
+    let strength = password_strength(password.expose());
+
+    println!(
+        "{} Strength: {}/4 ({})",
+        "ℹ".blue(),
+        strength.score,
+        score_rating(strength.score)
+    );
+    println!(
+        "{} Estimated entropy: {} bits (upper bound)",
+        "ℹ".blue(),
+        format!("{:.0}", strength.entropy_bits).cyan()
+    );
+    for warning in &strength.warnings {
+        println!("{} {}", "⚠".yellow(), warning);
+    }
+
+    Ok(())
+}
+
+fn score_rating(score: u8) -> ColoredString {
+    match score {
+        0 => "very weak".red().bold(),
+        1 => "weak".red(),
+        2 => "fair".yellow(),
+        3 => "strong".green(),
+        _ => "very strong".green().bold(),
+    }
+}
+
+/// Rough guidance for an entropy estimate, for offline attacks against a fast hash
+fn entropy_rating(bits: f64) -> ColoredString {
+    match bits {
+        b if b < 50.0 => "weak".red(),
+        b if b < 80.0 => "fair".yellow(),
+        b if b < 112.0 => "strong".green(),
+        _ => "very strong".green().bold(),
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use colored::*;
+
+use crate::utils::open_database;
+use crate::{
+    commands::auth,
+    config::CliConfig,
+    utils::{confirm_action, core_ext::CoreResultExt},
+};
+use persona_core::PersonaService;
+
+#[derive(Args, Debug)]
+pub struct RecoveryArgs {
+    #[command(subcommand)]
+    command: RecoveryCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum RecoveryCommand {
+    /// One-time codes that unlock the vault if the master password is lost
+    Codes {
+        #[command(subcommand)]
+        command: CodesCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CodesCommand {
+    /// Create a set of recovery codes
+    Generate,
+    /// Replace all recovery codes; the old ones stop working
+    Regenerate {
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+pub async fn execute(args: RecoveryArgs, config: &CliConfig) -> Result<()> {
+    let RecoveryCommand::Codes { command } = args.command;
+    let mut service = open_service(config).await?;
+    let Some(unused) = service.unused_recovery_codes().await.into_anyhow()? else {
+        anyhow::bail!("Recovery codes need a master password; this vault has none");
+    };
+
+    match command {
+        CodesCommand::Generate if unused > 0 => {
+            anyhow::bail!(
+                "{} unused recovery code(s) already exist; use `persona recovery codes regenerate` to replace them",
+                unused
+            );
+        }
+        CodesCommand::Generate => {}
+        CodesCommand::Regenerate { yes } => {
+            if unused > 0
+                && !yes
+                && !confirm_action(
+                    &format!(
+                        "Replace recovery codes? The {} unused code(s) will stop working",
+                        unused
+                    ),
+                    false,
+                )?
+            {
+                println!("Cancelled. Use --yes to skip confirmation.");
+                return Ok(());
+            }
+        }
+    }
+
+    auth::unlock(&mut service).await?;
+    let codes = service.generate_recovery_codes().await.into_anyhow()?;
+
+    println!(
+        "{} Generated {} recovery codes:",
+        "✓".green().bold(),
+        codes.len()
+    );
+    println!();
+    for code in &codes {
+        println!("  {}", code.bold());
+    }
+    println!();
+    println!(
+        "{}",
+        "Store these somewhere safe. They are shown only once.".yellow()
+    );
+    println!("  Each code unlocks the vault once in place of the master password,");
+    println!("  after which you must choose a new master password.");
+    Ok(())
+}
+
+async fn open_service(config: &CliConfig) -> Result<PersonaService> {
+    let db_path = config.get_database_path();
+    let db = open_database(&db_path)
+        .await
+        .into_anyhow()
+        .with_context(|| format!("Failed to connect to database: {}", db_path.display()))?;
+    db.migrate()
+        .await
+        .into_anyhow()
+        .context("Failed to run database migrations")?;
+    PersonaService::new(db).await.into_anyhow()
+}
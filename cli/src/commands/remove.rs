@@ -3,11 +3,14 @@ use clap::Args;
 use colored::*;
 use dialoguer::{Confirm, Input};
 
+use crate::commands::auth;
 use crate::config::CliConfig;
+use crate::utils::open_database;
+use crate::utils::report_safety_backups;
 use persona_core::models::{AuditAction, AuditLog, ResourceType};
 use persona_core::{
     storage::{IdentityRepository, WorkspaceRepository},
-    Database, PersonaService, Repository,
+    PersonaError, PersonaService, Repository,
 };
 
 #[derive(Args)]
@@ -38,7 +41,7 @@ pub async fn execute(args: RemoveArgs, config: &CliConfig) -> Result<()> {
 
     // Check if identity exists
     if !identity_exists(&args.name, config).await? {
-        anyhow::bail!("Identity '{}' not found", args.name);
+        return Err(PersonaError::IdentityNotFound(args.name.clone()).into());
     }
 
     // Check if it's the active identity
@@ -106,7 +109,7 @@ pub async fn execute(args: RemoveArgs, config: &CliConfig) -> Result<()> {
 
 async fn identity_exists(name: &str, config: &CliConfig) -> Result<bool> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -120,22 +123,12 @@ async fn identity_exists(name: &str, config: &CliConfig) -> Result<bool> {
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        use dialoguer::Password;
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        Ok(service
+            .get_identity_by_name(name)
             .await
-            .map_err(|e| anyhow!("Failed to authenticate: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => Ok(service
-                .get_identity_by_name(name)
-                .await
-                .map_err(|e| anyhow!("Lookup failed: {}", e))?
-                .is_some()),
-            _ => Ok(false),
-        }
+            .map_err(|e| anyhow!("Lookup failed: {}", e))?
+            .is_some())
     } else {
         Ok(IdentityRepository::new(db)
             .find_by_name(name)
@@ -147,7 +140,7 @@ async fn identity_exists(name: &str, config: &CliConfig) -> Result<bool> {
 
 async fn is_active_identity(name: &str, config: &CliConfig) -> Result<bool> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -175,7 +168,7 @@ async fn is_active_identity(name: &str, config: &CliConfig) -> Result<bool> {
 
 async fn show_removal_summary(name: &str, config: &CliConfig) -> Result<()> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -189,28 +182,18 @@ async fn show_removal_summary(name: &str, config: &CliConfig) -> Result<()> {
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        use dialoguer::Password;
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        service
+            .get_identity_by_name(name)
             .await
-            .map_err(|e| anyhow!("Failed to authenticate: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => service
-                .get_identity_by_name(name)
-                .await
-                .map_err(|e| anyhow!("Lookup failed: {}", e))?,
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+            .map_err(|e| anyhow!("Lookup failed: {}", e))?
     } else {
         IdentityRepository::new(db)
             .find_by_name(name)
             .await
             .map_err(|e| anyhow!("Lookup failed: {}", e))?
     }
-    .with_context(|| format!("Identity '{}' not found", name))?;
+    .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()))?;
 
     println!("{}", "Identity to be removed:".yellow().bold());
     println!("  Name: {}", identity.name.cyan());
@@ -249,7 +232,7 @@ async fn create_backup(name: &str, config: &CliConfig) -> Result<()> {
 
     // Export identity data via persona-core if unlocked; otherwise write minimal stub
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -269,35 +252,23 @@ async fn create_backup(name: &str, config: &CliConfig) -> Result<()> {
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        use dialoguer::Password;
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        if let Some(identity) = service
+            .get_identity_by_name(name)
             .await
-            .map_err(|e| anyhow!("Failed to authenticate: {}", e))?
+            .map_err(|e| anyhow!("Lookup failed: {}", e))?
         {
-            persona_core::auth::authentication::AuthResult::Success => {
-                if let Some(identity) = service
-                    .get_identity_by_name(name)
-                    .await
-                    .map_err(|e| anyhow!("Lookup failed: {}", e))?
-                {
-                    let export = service
-                        .export_identity(&identity.id)
-                        .await
-                        .map_err(|e| anyhow!("Export failed: {}", e))?;
-                    serde_json::json!({
-                        "identity": export.identity,
-                        "credentials": export.credentials,
-                        "backup_created": chrono::Utc::now().to_rfc3339()
-                    })
-                } else {
-                    anyhow::bail!("Identity '{}' not found", name);
-                }
-            }
-            other => anyhow::bail!("Authentication failed: {:?}", other),
+            let export = service
+                .export_identity(&identity.id)
+                .await
+                .map_err(|e| anyhow!("Export failed: {}", e))?;
+            serde_json::json!({
+                "identity": export.identity,
+                "credentials": export.credentials,
+                "backup_created": chrono::Utc::now().to_rfc3339()
+            })
+        } else {
+            return Err(PersonaError::IdentityNotFound(name.to_string()).into());
         }
     } else {
         // Without unlock, write minimal metadata
@@ -306,7 +277,7 @@ async fn create_backup(name: &str, config: &CliConfig) -> Result<()> {
             .find_by_name(name)
             .await
             .map_err(|e| anyhow!("Lookup failed: {}", e))?
-            .with_context(|| format!("Identity '{}' not found", name))?;
+            .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()))?;
         serde_json::json!({
             "identity": identity,
             "credentials": [],
@@ -324,7 +295,7 @@ async fn create_backup(name: &str, config: &CliConfig) -> Result<()> {
     );
 
     // Audit backup creation
-    let audit_db = Database::from_file(&config.get_database_path())
+    let audit_db = open_database(&config.get_database_path())
         .await
         .map_err(|e| anyhow!("Failed to open database for audit: {}", e))?;
     audit_db
@@ -346,7 +317,7 @@ async fn perform_removal(name: &str, purge: bool, config: &CliConfig) -> Result<
     println!("{} Removing identity data...", "🔄".to_string());
 
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -360,26 +331,20 @@ async fn perform_removal(name: &str, purge: bool, config: &CliConfig) -> Result<
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        use dialoguer::Password;
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
-            .await
-            .map_err(|e| anyhow!("Failed to authenticate: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => {}
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+        auth::unlock(&mut service).await?;
     }
+    // Attachment files are deleted with the identity's credentials
+    service
+        .init_attachment_storage(config.get_attachments_path(), db.clone())
+        .await
+        .map_err(|e| anyhow!("Failed to open attachment storage: {}", e))?;
 
     // Locate identity
     let identity = service
         .get_identity_by_name(name)
         .await
         .map_err(|e| anyhow!("Lookup failed: {}", e))?
-        .with_context(|| format!("Identity '{}' not found", name))?;
+        .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()))?;
 
     // Update workspace active if needed (v2 schema)
     let repo = WorkspaceRepository::new(db.clone());
@@ -399,10 +364,13 @@ async fn perform_removal(name: &str, purge: bool, config: &CliConfig) -> Result<
     }
 
     // Delete identity
+    service.set_backup_before_destructive(config.destructive_backup_dir());
+    let mut events = service.subscribe_events();
     let _ = service
         .delete_identity(&identity.id)
         .await
         .map_err(|e| anyhow!("Failed to delete identity: {}", e))?;
+    report_safety_backups(&mut events);
 
     if purge {
         println!("{} Purging all associated data...", "🧹".to_string());
@@ -433,7 +401,7 @@ async fn show_post_removal_info(config: &CliConfig) -> Result<()> {
 
 async fn get_remaining_identities_count(config: &CliConfig) -> Result<usize> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -447,22 +415,12 @@ async fn get_remaining_identities_count(config: &CliConfig) -> Result<usize> {
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        use dialoguer::Password;
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        Ok(service
+            .get_identities()
             .await
-            .map_err(|e| anyhow!("Failed to authenticate: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => Ok(service
-                .get_identities()
-                .await
-                .map_err(|e| anyhow!("Failed to fetch identities: {}", e))?
-                .len()),
-            _ => Ok(0),
-        }
+            .map_err(|e| anyhow!("Failed to fetch identities: {}", e))?
+            .len())
     } else {
         Ok(0)
     }
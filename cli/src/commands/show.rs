@@ -1,18 +1,23 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 use clap::Args;
 use colored::*;
 use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
 
+use crate::commands::auth;
 use crate::config::CliConfig;
+use crate::utils::open_database;
 use persona_core::{
-    storage::IdentityRepository, Database, Identity as CoreIdentity, PersonaService,
+    models::{ChangeHistory, FieldChange},
+    storage::IdentityRepository,
+    Credential, Identity as CoreIdentity, PersonaError, PersonaService, SECRET_CHANGE_FIELD,
 };
+use uuid::Uuid;
 
 #[derive(Args)]
 pub struct ShowArgs {
-    /// Identity name to show
+    /// Identity name to show (a credential ID with --history)
     name: String,
 
     /// Output format (table, json, yaml)
@@ -22,9 +27,17 @@ pub struct ShowArgs {
     /// Show sensitive information (requires confirmation)
     #[arg(long)]
     show_sensitive: bool,
+
+    /// Print the change timeline of a credential instead
+    #[arg(long)]
+    history: bool,
 }
 
 pub async fn execute(args: ShowArgs, config: &CliConfig) -> Result<()> {
+    if args.history {
+        return show_credential_history(&args, config).await;
+    }
+
     println!(
         "{} Showing identity '{}'...",
         "👤".to_string(),
@@ -63,10 +76,20 @@ struct IdentityDetails {
 }
 
 async fn fetch_identity_details(name: &str, config: &CliConfig) -> Result<IdentityDetails> {
-    use dialoguer::Password;
+    if config.demo {
+        let service = PersonaService::demo()
+            .await
+            .map_err(|e| anyhow!("Failed to open demo vault: {}", e))?;
+        let id = service
+            .get_identity_by_name(name)
+            .await
+            .map_err(|e| anyhow!("Failed to fetch identity: {}", e))?
+            .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()))?;
+        return Ok(identity_details(id));
+    }
     // Open DB
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to connect to database: {}", e))?;
     db.migrate()
@@ -82,20 +105,11 @@ async fn fetch_identity_details(name: &str, config: &CliConfig) -> Result<Identi
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        service
+            .get_identity_by_name(name)
             .await
-            .map_err(|e| anyhow!("Failed to authenticate user: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => service
-                .get_identity_by_name(name)
-                .await
-                .map_err(|e| anyhow!("Failed to fetch identity: {}", e))?,
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+            .map_err(|e| anyhow!("Failed to fetch identity: {}", e))?
     } else {
         // Fallback to direct repository read for non-authenticated DB
         let repo = IdentityRepository::new(db);
@@ -104,8 +118,12 @@ async fn fetch_identity_details(name: &str, config: &CliConfig) -> Result<Identi
             .map_err(|e| anyhow!("Failed to fetch identity: {}", e))?
     };
 
-    let id = maybe.with_context(|| format!("Identity '{}' not found", name))?;
-    Ok(IdentityDetails {
+    let id = maybe.ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()))?;
+    Ok(identity_details(id))
+}
+
+fn identity_details(id: CoreIdentity) -> IdentityDetails {
+    IdentityDetails {
         name: id.name.clone(),
         identity_type: id.identity_type.to_string(),
         description: id.description.unwrap_or_default(),
@@ -123,7 +141,7 @@ async fn fetch_identity_details(name: &str, config: &CliConfig) -> Result<Identi
         // Usage tracking not implemented yet; leave placeholders
         last_used: None,
         usage_count: 0,
-    })
+    }
 }
 
 fn display_table_format(identity: &IdentityDetails, show_sensitive: bool) -> Result<()> {
@@ -216,6 +234,126 @@ fn display_yaml_format(identity: &IdentityDetails) -> Result<()> {
     Ok(())
 }
 
+/// One step of a credential's timeline
+#[derive(Debug, Serialize)]
+struct TimelineEntry {
+    timestamp: String,
+    change: String,
+    user: Option<String>,
+    fields: Vec<FieldChange>,
+}
+
+impl TimelineEntry {
+    fn from_history(entry: ChangeHistory) -> Self {
+        let mut fields: Vec<FieldChange> = entry.changes_summary.into_values().collect();
+        fields.sort_by(|a, b| a.field_name.cmp(&b.field_name));
+        Self {
+            timestamp: entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+            change: entry.change_type.to_string(),
+            user: entry.user_id,
+            fields,
+        }
+    }
+}
+
+async fn show_credential_history(args: &ShowArgs, config: &CliConfig) -> Result<()> {
+    let id = Uuid::parse_str(&args.name).map_err(|_| {
+        PersonaError::InvalidInput(format!(
+            "--history expects a credential ID, got '{}'",
+            args.name
+        ))
+    })?;
+
+    let service = if config.demo {
+        PersonaService::demo()
+            .await
+            .map_err(|e| anyhow!("Failed to open demo vault: {}", e))?
+    } else {
+        let db = open_database(&config.get_database_path())
+            .await
+            .map_err(|e| anyhow!("Failed to connect to database: {}", e))?;
+        db.migrate()
+            .await
+            .map_err(|e| anyhow!("Failed to run database migrations: {}", e))?;
+        let mut service = PersonaService::new(db)
+            .await
+            .map_err(|e| anyhow!("Failed to create PersonaService: {}", e))?;
+        auth::unlock(&mut service).await?;
+        service
+    };
+
+    let credential: Credential = service
+        .get_credential(&id)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch credential: {}", e))?
+        .ok_or_else(|| PersonaError::NotFound(format!("Credential {} not found", id)))?;
+    let mut timeline: Vec<TimelineEntry> = service
+        .get_change_history(&id)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch change history: {}", e))?
+        .into_iter()
+        .map(TimelineEntry::from_history)
+        .collect();
+    timeline.push(TimelineEntry {
+        timestamp: credential
+            .created_at
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string(),
+        change: "created".to_string(),
+        user: None,
+        fields: Vec::new(),
+    });
+
+    match args.format.as_str() {
+        "table" => display_timeline(&credential, &timeline),
+        "json" => println!("{}", serde_json::to_string_pretty(&timeline)?),
+        "yaml" => println!("{}", serde_yaml::to_string(&timeline)?),
+        _ => anyhow::bail!("Unsupported output format: {}", args.format),
+    }
+    Ok(())
+}
+
+fn display_timeline(credential: &Credential, timeline: &[TimelineEntry]) {
+    println!(
+        "📜 History of '{}' (most recent first)",
+        credential.name.bright_cyan().bold()
+    );
+    println!();
+    for entry in timeline {
+        let by = entry
+            .user
+            .as_deref()
+            .map(|user| format!(" by {}", user))
+            .unwrap_or_default();
+        println!(
+            "  {}  {}{}",
+            entry.timestamp.dimmed(),
+            entry.change.yellow().bold(),
+            by.dimmed()
+        );
+        for field in &entry.fields {
+            if field.field_name == SECRET_CHANGE_FIELD || field.field_name == "notes" {
+                println!("      {}: {}", field.field_name.dimmed(), "changed".cyan());
+            } else {
+                println!(
+                    "      {}: {} → {}",
+                    field.field_name.dimmed(),
+                    display_value(&field.old_value).red(),
+                    display_value(&field.new_value).green()
+                );
+            }
+        }
+    }
+}
+
+fn display_value(value: &str) -> String {
+    if value.is_empty() {
+        "(empty)".to_string()
+    } else {
+        format!("\"{}\"", value)
+    }
+}
+
 fn is_sensitive_attribute(key: &str) -> bool {
     let sensitive_keys = [
         "password",
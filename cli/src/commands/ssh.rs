@@ -1,12 +1,17 @@
 use anyhow::{Context, Result};
+use crate::commands::auth;
 use crate::utils::core_ext::CoreResultExt;
+use crate::utils::open_database;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use clap::{Args, Subcommand};
 use colored::*;
 use dialoguer::{Confirm, Password};
 use persona_core::{
     models::{CredentialData, CredentialType, Identity as CoreIdentity, SecurityLevel, SshKeyData},
-    Database, PersonaService,
+    PersonaError, PersonaService, SecretString,
+};
+use persona_ssh_agent::status::{
+    agent_state_dir, read_agent_status, SshAgentStatus, PID_FILE, SOCKET_FILE,
 };
 use uuid::Uuid;
 
@@ -142,7 +147,7 @@ pub async fn execute(args: SshArgs, config: &crate::config::CliConfig) -> Result
 
 async fn ensure_service(config: &crate::config::CliConfig) -> Result<PersonaService> {
     let db_path = config.get_database_path();
-    let db: persona_core::Database = Database::from_file::<std::path::PathBuf>(db_path.to_owned())
+    let db: persona_core::Database = open_database(db_path.to_owned())
         .await
         .into_anyhow()
         .context("Failed to open database")?;
@@ -151,13 +156,7 @@ async fn ensure_service(config: &crate::config::CliConfig) -> Result<PersonaServ
         .await
         .context("Failed to create PersonaService")?;
     if service.has_users().await? {
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service.authenticate_user(&password).await? {
-            persona_core::auth::authentication::AuthResult::Success => {}
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+        auth::unlock(&mut service).await?;
     }
     Ok(service)
 }
@@ -166,7 +165,7 @@ async fn resolve_identity(service: &PersonaService, name: &str) -> Result<CoreId
     service
         .get_identity_by_name(name)
         .await?
-        .with_context(|| format!("Identity '{}' not found", name))
+        .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()).into())
 }
 
 async fn generate_key(
@@ -199,7 +198,7 @@ async fn generate_key(
 
     let name = label.unwrap_or_else(|| format!("SSH Key ({})", identity.name));
     let mut data = SshKeyData {
-        private_key: private_b64,
+        private_key: SecretString::new(private_b64),
         public_key: openssh_pub.clone(),
         key_type: "ed25519".to_string(),
         passphrase: None,
@@ -339,71 +338,24 @@ async fn start_agent(config: &crate::config::CliConfig, print_export: bool) -> R
     Ok(())
 }
 
-fn agent_status(config: &crate::config::CliConfig) -> Result<()> {
-    let state_dir = std::env::var("PERSONA_AGENT_STATE_DIR")
-        .ok()
-        .map(std::path::PathBuf::from)
-        .unwrap_or_else(|| {
-            dirs::home_dir()
-                .unwrap_or_else(|| std::path::PathBuf::from("."))
-                .join(".persona")
-        });
-    let sock_file = state_dir.join("ssh-agent.sock");
-    let pid_file = state_dir.join("ssh-agent.pid");
-    let mut running = false;
-    if sock_file.exists() {
-        let sock = std::fs::read_to_string(&sock_file).unwrap_or_default();
-        println!("{} {}", "Socket:".yellow(), sock.trim().cyan());
-        running = true;
-    }
-    if pid_file.exists() {
-        let pid = std::fs::read_to_string(&pid_file).unwrap_or_default();
-        println!("{} {}", "PID:".yellow(), pid.trim().cyan());
-        running = true;
-    }
-    // Try to query agent identities
-    if let Ok(sock) = std::env::var("SSH_AUTH_SOCK") {
-        if let Ok(count) = query_agent_identities(&sock) {
-            println!("{} {}", "Agent keys:".yellow(), count.to_string().cyan());
-        }
-    } else if sock_file.exists() {
-        let sock = std::fs::read_to_string(&sock_file).unwrap_or_default();
-        if let Ok(count) = query_agent_identities(sock.trim()) {
-            println!("{} {}", "Agent keys:".yellow(), count.to_string().cyan());
-        }
-    }
-    if !running {
-        println!("{}", "persona-ssh-agent is not running.".yellow());
-    }
+fn agent_status(_config: &crate::config::CliConfig) -> Result<()> {
+    print_agent_status(&read_agent_status(false));
     Ok(())
 }
 
-fn query_agent_identities(sock_path: &str) -> Result<usize> {
-    use byteorder::{BigEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
-    use std::io::{Read, Write};
-    use std::os::unix::net::UnixStream;
-    let mut stream = UnixStream::connect(sock_path)
-        .with_context(|| format!("Failed to connect to agent at {}", sock_path))?;
-    // Build request: len(4) + type(1)=11
-    let mut pkt = vec![0u8; 5];
-    BigEndian::write_u32(&mut pkt[0..4], 1);
-    pkt[4] = 11u8;
-    stream.write_all(&pkt)?;
-    // Read response len
-    let mut len_buf = [0u8; 4];
-    stream.read_exact(&mut len_buf)?;
-    let resp_len = BigEndian::read_u32(&len_buf) as usize;
-    let mut resp = vec![0u8; resp_len];
-    stream.read_exact(&mut resp)?;
-    if resp.is_empty() || resp[0] != 12 {
-        anyhow::bail!("Unexpected agent response");
+pub(crate) fn print_agent_status(status: &SshAgentStatus) {
+    if let Some(sock) = &status.socket_path {
+        println!("{} {}", "Socket:".yellow(), sock.cyan());
     }
-    // parse count
-    if resp.len() < 5 {
-        anyhow::bail!("Malformed agent response");
+    if let Some(pid) = status.pid {
+        println!("{} {}", "PID:".yellow(), pid.to_string().cyan());
+    }
+    if let Some(count) = status.key_count {
+        println!("{} {}", "Agent keys:".yellow(), count.to_string().cyan());
+    }
+    if !status.running {
+        println!("{}", "persona-ssh-agent is not running.".yellow());
     }
-    let count = BigEndian::read_u32(&resp[1..5]) as usize;
-    Ok(count)
 }
 
 async fn run_with_host(
@@ -416,14 +368,7 @@ async fn run_with_host(
         anyhow::bail!("Provide a command after --");
     }
     // Write host to state file
-    let state_dir = std::env::var("PERSONA_AGENT_STATE_DIR")
-        .ok()
-        .map(std::path::PathBuf::from)
-        .unwrap_or_else(|| {
-            dirs::home_dir()
-                .unwrap_or_else(|| std::path::PathBuf::from("."))
-                .join(".persona")
-        });
+    let state_dir = agent_state_dir();
     std::fs::create_dir_all(&state_dir).ok();
     let host_file = state_dir.join("agent-target-host");
     std::fs::write(&host_file, host).context("Failed to write agent target host")?;
@@ -477,7 +422,7 @@ async fn import_seed(
     let public_openssh = encode_ssh_ed25519_public(&pub_bytes, None);
     let name = label.unwrap_or_else(|| "SSH Key (imported)".to_string());
     let ssh_data = SshKeyData {
-        private_key: BASE64.encode(arr),
+        private_key: SecretString::new(BASE64.encode(arr)),
         public_key: public_openssh.clone(),
         key_type: "ed25519".to_string(),
         passphrase: None,
@@ -518,15 +463,8 @@ async fn export_pubkey(id: uuid::Uuid, config: &crate::config::CliConfig) -> Res
 
 fn stop_agent() -> Result<()> {
     use std::process::Command;
-    let state_dir = std::env::var("PERSONA_AGENT_STATE_DIR")
-        .ok()
-        .map(std::path::PathBuf::from)
-        .unwrap_or_else(|| {
-            dirs::home_dir()
-                .unwrap_or_else(|| std::path::PathBuf::from("."))
-                .join(".persona")
-        });
-    let pid_file = state_dir.join("ssh-agent.pid");
+    let state_dir = agent_state_dir();
+    let pid_file = state_dir.join(PID_FILE);
     if !pid_file.exists() {
         println!("{}", "No agent PID file found.".yellow());
         return Ok(());
@@ -542,7 +480,7 @@ fn stop_agent() -> Result<()> {
             println!("{} Stopped persona-ssh-agent (pid {})", "✓".green(), pid);
             // Cleanup sock/pid files
             let _ = std::fs::remove_file(pid_file);
-            let sock_file = state_dir.join("ssh-agent.sock");
+            let sock_file = state_dir.join(SOCKET_FILE);
             let _ = std::fs::remove_file(sock_file);
         }
         Ok(_) => {
@@ -0,0 +1,237 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Args;
+use colored::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::commands::bridge::{self, PairingSummary};
+use crate::utils::open_database;
+use crate::{
+    config::CliConfig,
+    utils::{core_ext::CoreResultExt, vault_permission_issues},
+};
+use persona_core::{
+    storage::{IdentityRepository, LockHolder, PermissionIssue, VaultLock, WorkspaceRepository},
+    Database, PersonaService, Repository,
+};
+use persona_ssh_agent::status::{read_agent_status, SshAgentStatus};
+
+#[derive(Args, Debug)]
+pub struct StatusArgs {
+    /// Output format (text, json)
+    #[arg(short, long, default_value = "text")]
+    format: String,
+}
+
+/// Whether the master password gate is in place. The CLI never keeps a vault
+/// unlocked between commands, so this reports what the next command will face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum VaultState {
+    /// No database in the workspace yet
+    Missing,
+    /// No master password set; commands run without unlocking
+    NoMasterPassword,
+    /// Commands prompt for the master password
+    Locked,
+    /// Too many failed attempts; unlocking is refused until the lockout expires
+    LockedOut,
+}
+
+impl std::fmt::Display for VaultState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            VaultState::Missing => "no database",
+            VaultState::NoMasterPassword => "no master password set",
+            VaultState::Locked => "locked (master password required)",
+            VaultState::LockedOut => "locked out after failed attempts",
+        };
+        f.write_str(label)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ActiveIdentity {
+    id: Uuid,
+    /// Only read when the vault has no master password, like `persona switch`
+    name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct StatusReport {
+    workspace: PathBuf,
+    database: PathBuf,
+    /// Vault paths other users can access
+    permissions: Vec<PermissionIssue>,
+    vault: VaultState,
+    /// Process running an exclusive operation (migrate, import, vacuum) on the vault
+    exclusive_lock: Option<LockHolder>,
+    schema_version: Option<i64>,
+    latest_schema_version: i64,
+    active_identity: Option<ActiveIdentity>,
+    ssh_agent: SshAgentStatus,
+    bridge_pairings: Vec<PairingSummary>,
+    backup_directory: PathBuf,
+    last_backup: Option<DateTime<Utc>>,
+}
+
+pub async fn execute(args: StatusArgs, config: &CliConfig) -> Result<()> {
+    let report = gather(config).await?;
+    match args.format.as_str() {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        "text" => print_report(&report),
+        other => anyhow::bail!("Unsupported format '{}' (expected text or json)", other),
+    }
+    Ok(())
+}
+
+/// Collect the report without unlocking, migrating or creating anything
+async fn gather(config: &CliConfig) -> Result<StatusReport> {
+    let database = config.get_database_path();
+    let mut report = StatusReport {
+        workspace: config.workspace.path.clone(),
+        database: database.clone(),
+        permissions: vault_permission_issues(config),
+        vault: VaultState::Missing,
+        exclusive_lock: VaultLock::holder(&config.workspace.path),
+        schema_version: None,
+        latest_schema_version: Database::latest_schema_version(),
+        active_identity: None,
+        ssh_agent: read_agent_status(false),
+        bridge_pairings: bridge::read_pairings().unwrap_or_default(),
+        backup_directory: config.backup.directory.clone(),
+        last_backup: last_backup(&config.backup.directory),
+    };
+    if !database.exists() {
+        return Ok(report);
+    }
+
+    let db = open_database(&database)
+        .await
+        .into_anyhow()
+        .with_context(|| format!("Failed to connect to database: {}", database.display()))?;
+    report.schema_version = db.schema_version().await.into_anyhow()?;
+    if report.schema_version.is_none() {
+        return Ok(report);
+    }
+
+    let service = PersonaService::new(db.clone()).await.into_anyhow()?;
+    report.vault = match service.remaining_auth_attempts().await.into_anyhow()? {
+        None => VaultState::NoMasterPassword,
+        Some(0) => VaultState::LockedOut,
+        Some(_) => VaultState::Locked,
+    };
+
+    let workspace = WorkspaceRepository::new(db.clone())
+        .find_by_path(&config.workspace.path.to_string_lossy())
+        .await
+        .into_anyhow()?;
+    if let Some(id) = workspace.and_then(|ws| ws.active_identity_id) {
+        let name = if report.vault == VaultState::NoMasterPassword {
+            IdentityRepository::new(db)
+                .find_by_id(&id)
+                .await
+                .into_anyhow()?
+                .map(|identity| identity.name)
+        } else {
+            None
+        };
+        report.active_identity = Some(ActiveIdentity { id, name });
+    }
+
+    Ok(report)
+}
+
+/// Modification time of the newest file in the backup directory
+fn last_backup(directory: &Path) -> Option<DateTime<Utc>> {
+    std::fs::read_dir(directory)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .filter_map(|metadata| metadata.modified().ok())
+        .max()
+        .map(DateTime::<Utc>::from)
+}
+
+fn print_report(report: &StatusReport) {
+    let row = |label: &str, value: String| println!("{:<16} {}", label.bold(), value);
+
+    row("Workspace:", report.workspace.display().to_string());
+    row("Database:", report.database.display().to_string());
+    row(
+        "Permissions:",
+        match report.permissions.len() {
+            0 => "owner-only".to_string(),
+            n => format!("{} path(s) accessible by other users", n),
+        },
+    );
+    for issue in &report.permissions {
+        println!(
+            "{:<16} {} ({})",
+            "",
+            issue.to_string().yellow(),
+            issue.fix_command().dimmed()
+        );
+    }
+    row("Vault:", report.vault.to_string());
+    if let Some(holder) = &report.exclusive_lock {
+        row(
+            "In use:",
+            format!("PID {} ({})", holder.pid, holder.operation)
+                .yellow()
+                .to_string(),
+        );
+    }
+    row(
+        "Schema:",
+        match report.schema_version {
+            Some(version) if version < report.latest_schema_version => format!(
+                "{} (migrations pending, latest is {})",
+                version, report.latest_schema_version
+            ),
+            Some(version) => version.to_string(),
+            None => "not migrated".to_string(),
+        },
+    );
+    row(
+        "Active identity:",
+        match &report.active_identity {
+            Some(ActiveIdentity {
+                name: Some(name), ..
+            }) => name.clone(),
+            Some(ActiveIdentity { id, name: None }) => id.to_string(),
+            None => "none".to_string(),
+        },
+    );
+
+    let agent = &report.ssh_agent;
+    row(
+        "SSH agent:",
+        match (agent.running, agent.key_count) {
+            (false, _) => "not running".to_string(),
+            (true, Some(count)) => format!("running, {} key(s)", count),
+            (true, None) => "running (keys unavailable)".to_string(),
+        },
+    );
+
+    row(
+        "Bridge:",
+        match report.bridge_pairings.len() {
+            0 => "no paired extensions".to_string(),
+            n => format!("{} paired extension(s)", n),
+        },
+    );
+    for pairing in &report.bridge_pairings {
+        println!("{:<16} {}", "", pairing.extension_id.dimmed());
+    }
+
+    row(
+        "Last backup:",
+        match report.last_backup {
+            Some(at) => at.format("%Y-%m-%d %H:%M UTC").to_string(),
+            None => format!("never (in {})", report.backup_directory.display()),
+        },
+    );
+}
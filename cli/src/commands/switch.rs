@@ -5,11 +5,13 @@ use dialoguer::{Confirm, Select};
 use std::collections::HashMap;
 use tracing::info;
 
+use crate::commands::auth;
 use crate::config::CliConfig;
+use crate::utils::open_database;
 use persona_core::models::{AuditAction, AuditLog, ResourceType};
 use persona_core::{
     storage::{IdentityRepository, WorkspaceRepository},
-    Database, PersonaService, Repository,
+    PersonaError, PersonaService, Repository,
 };
 
 #[derive(Args)]
@@ -42,6 +44,9 @@ pub async fn execute(args: SwitchArgs, config: &CliConfig) -> Result<()> {
         args.name.context("Identity name is required")?
     };
 
+    // Resolve the stored name ("work" switches to "Work"), which also verifies it exists
+    let target_identity = resolve_identity_name(&target_identity, config).await?;
+
     // Get current active identity
     let current_identity = get_current_identity(config).await?;
 
@@ -57,9 +62,6 @@ pub async fn execute(args: SwitchArgs, config: &CliConfig) -> Result<()> {
         }
     }
 
-    // Verify target identity exists
-    verify_identity_exists(&target_identity, config).await?;
-
     // Show confirmation if not forced
     if !args.force {
         let confirmation_message = if let Some(current) = &current_identity {
@@ -101,7 +103,7 @@ pub async fn execute(args: SwitchArgs, config: &CliConfig) -> Result<()> {
 async fn get_current_identity(config: &CliConfig) -> Result<Option<String>> {
     // Read workspace.active_identity_id; map to identity name
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -168,9 +170,10 @@ async fn select_identity_interactive(config: &CliConfig) -> Result<String> {
     Ok(identity_names[selection].clone())
 }
 
-async fn verify_identity_exists(name: &str, config: &CliConfig) -> Result<()> {
+/// Stored name of the identity `name` refers to; lookups ignore case and accents
+async fn resolve_identity_name(name: &str, config: &CliConfig) -> Result<String> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -180,38 +183,26 @@ async fn verify_identity_exists(name: &str, config: &CliConfig) -> Result<()> {
         .await
         .map_err(|e| anyhow!("Failed to create PersonaService: {}", e))?;
     let mut service = service;
-    let exists = if service
+    let identity = if service
         .has_users()
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        use dialoguer::Password;
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        service
+            .get_identity_by_name(name)
             .await
-            .map_err(|e| anyhow!("Failed to authenticate user: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => service
-                .get_identity_by_name(name)
-                .await
-                .map_err(|e| anyhow!("Failed to lookup identity: {}", e))?
-                .is_some(),
-            _ => false,
-        }
+            .map_err(|e| anyhow!("Failed to lookup identity: {}", e))?
     } else {
         IdentityRepository::new(db)
             .find_by_name(name)
             .await
             .map_err(|e| anyhow!("Failed to lookup identity: {}", e))?
-            .is_some()
     };
-    if !exists {
-        anyhow::bail!("Identity '{}' not found", name);
+    match identity {
+        Some(identity) => Ok(identity.name),
+        None => Err(PersonaError::IdentityNotFound(name.to_string()).into()),
     }
-    Ok(())
 }
 
 async fn perform_switch(
@@ -226,7 +217,7 @@ async fn perform_switch(
 
     // 1. Resolve target identity id
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -240,28 +231,19 @@ async fn perform_switch(
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        use dialoguer::Password;
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        service
+            .get_identity_by_name(target_identity)
             .await
-            .map_err(|e| anyhow!("Failed to authenticate user: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => service
-                .get_identity_by_name(target_identity)
-                .await
-                .map_err(|e| anyhow!("Failed to load identity: {}", e))?,
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+            .map_err(|e| anyhow!("Failed to load identity: {}", e))?
     } else {
         IdentityRepository::new(db.clone())
             .find_by_name(target_identity)
             .await
             .map_err(|e| anyhow!("Failed to load identity: {}", e))?
     };
-    let identity = identity.with_context(|| format!("Identity '{}' not found", target_identity))?;
+    let identity =
+        identity.ok_or_else(|| PersonaError::IdentityNotFound(target_identity.to_string()))?;
 
     // 2. Update workspace.active_identity_id (v2 schema; legacy no-op via repo fallback)
     let repo = WorkspaceRepository::new(db.clone());
@@ -336,7 +318,7 @@ struct IdentityInfo {
 
 async fn fetch_available_identities(config: &CliConfig) -> Result<HashMap<String, IdentityInfo>> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .map_err(|e| anyhow!("Failed to open database: {}", e))?;
     db.migrate()
@@ -350,21 +332,11 @@ async fn fetch_available_identities(config: &CliConfig) -> Result<HashMap<String
         .await
         .map_err(|e| anyhow!("Failed to check users: {}", e))?
     {
-        use dialoguer::Password;
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
+        auth::unlock(&mut service).await?;
+        service
+            .get_identities()
             .await
-            .map_err(|e| anyhow!("Failed to authenticate user: {}", e))?
-        {
-            persona_core::auth::authentication::AuthResult::Success => service
-                .get_identities()
-                .await
-                .map_err(|e| anyhow!("Failed to fetch identities: {}", e))?,
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+            .map_err(|e| anyhow!("Failed to fetch identities: {}", e))?
     } else {
         IdentityRepository::new(db)
             .find_all()
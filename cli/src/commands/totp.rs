@@ -3,17 +3,21 @@ use std::{path::PathBuf, time::Duration};
 use anyhow::{anyhow, bail, Context, Result};
 use clap::{Args, Subcommand};
 use colored::*;
-use data_encoding::{BASE32, BASE32_NOPAD};
-use hmac::{Hmac, Mac};
 use image::GenericImageView;
 use persona_core::{
-    models::{CredentialData, CredentialType, SecurityLevel, TwoFactorData},
-    Database, PersonaService,
+    models::{Credential, CredentialData, CredentialType, OtpType, SecurityLevel, TwoFactorData},
+    otp,
+    service::totp_import,
+    PersonaError, PersonaService, TotpVerification,
 };
 use rqrr::PreparedImage;
 use uuid::Uuid;
 
-use crate::{config::CliConfig, utils::core_ext::CoreResultExt};
+use crate::{
+    commands::auth,
+    config::CliConfig,
+    utils::{core_ext::CoreResultExt, open_database},
+};
 
 #[derive(Args, Debug)]
 pub struct TotpArgs {
@@ -60,6 +64,21 @@ pub enum TotpCommand {
         /// Hash algorithm (SHA1/SHA256/SHA512)
         #[arg(long)]
         algorithm: Option<String>,
+        /// Counter-based (HOTP) entry instead of time-based
+        #[arg(long)]
+        hotp: bool,
+        /// Initial HOTP counter
+        #[arg(long)]
+        counter: Option<u64>,
+    },
+    /// Import accounts from otpauth:// or Google Authenticator otpauth-migration:// exports
+    Import {
+        /// Identity name to store the credentials under
+        #[arg(short, long)]
+        identity: String,
+        /// otpauth:// or otpauth-migration:// URIs, or paths to QR code images
+        #[arg(required = true)]
+        sources: Vec<String>,
     },
     /// Generate a TOTP code for a stored credential
     Code {
@@ -70,6 +89,34 @@ pub enum TotpCommand {
         #[arg(long)]
         watch: bool,
     },
+    /// Generate the next code for a counter-based (HOTP) credential
+    Next {
+        /// Credential UUID (must be an HOTP entry)
+        id: Uuid,
+    },
+    /// Check a code entered for a TOTP credential, e.g. when enrolling a device
+    Verify {
+        /// Credential UUID (must be a TOTP entry)
+        id: Uuid,
+        /// Code to check
+        code: String,
+        /// Time steps of clock drift to accept either way
+        #[arg(
+            long,
+            default_value_t = otp::DEFAULT_VERIFY_SKEW_STEPS,
+            value_parser = clap::value_parser!(u32).range(..=otp::MAX_VERIFY_SKEW_STEPS as i64)
+        )]
+        skew: u32,
+    },
+    /// Correct for a device whose clock is off, using two consecutive codes shown on it
+    Calibrate {
+        /// Credential UUID (must be a TOTP entry)
+        id: Uuid,
+        /// Code the device displayed just before the current one
+        previous_code: String,
+        /// Code currently displayed by the device
+        code: String,
+    },
 }
 
 pub async fn execute(args: TotpArgs, config: &CliConfig) -> Result<()> {
@@ -86,63 +133,56 @@ pub async fn execute(args: TotpArgs, config: &CliConfig) -> Result<()> {
             digits,
             period,
             algorithm,
+            hotp,
+            counter,
         } => {
-            setup_totp(
-                config, identity, name, qr, otpauth, secret, issuer, account, url, digits, period,
+            let mut template = TotpTemplate {
+                secret,
+                issuer,
+                account,
                 algorithm,
-            )
-            .await?
+                digits,
+                period,
+                otp_type: hotp.then_some(OtpType::Hotp),
+                counter,
+            };
+            if let Some(path) = qr {
+                template.merge(parse_otpauth_uri(&decode_qr_file(&path)?)?);
+            }
+            if let Some(uri) = otpauth {
+                template.merge(parse_otpauth_uri(&uri)?);
+            }
+            setup_totp(config, identity, name, template, url).await?
+        }
+        TotpCommand::Import { identity, sources } => {
+            import_accounts(config, identity, sources).await?
         }
         TotpCommand::Code { id, watch } => generate_codes(config, id, watch).await?,
+        TotpCommand::Next { id } => next_hotp_code(config, id).await?,
+        TotpCommand::Verify { id, code, skew } => verify_code(config, id, code, skew).await?,
+        TotpCommand::Calibrate {
+            id,
+            previous_code,
+            code,
+        } => calibrate(config, id, previous_code, code).await?,
     }
     Ok(())
 }
 
+/// Store a new one-time password credential.
+///
+/// `template` holds the command-line overrides, already merged with any QR/otpauth values.
 async fn setup_totp(
     config: &CliConfig,
     identity_name: String,
     display_name: Option<String>,
-    qr: Option<PathBuf>,
-    otpauth: Option<String>,
-    secret: Option<String>,
-    issuer_override: Option<String>,
-    account_override: Option<String>,
+    template: TotpTemplate,
     url: Option<String>,
-    digits_override: Option<u8>,
-    period_override: Option<u32>,
-    algorithm_override: Option<String>,
 ) -> Result<()> {
     println!("{}", "🔐 Setting up TOTP credential...".cyan());
     let mut service = init_service(config).await?;
     let identity = resolve_identity(&mut service, &identity_name).await?;
 
-    let mut template = TotpTemplate::default();
-    if let Some(path) = qr {
-        let uri = decode_qr_file(&path)?;
-        template.merge(parse_otpauth_uri(&uri)?);
-    }
-    if let Some(uri) = otpauth {
-        template.merge(parse_otpauth_uri(&uri)?);
-    }
-    if let Some(secret) = secret {
-        template.secret = Some(secret);
-    }
-    if let Some(issuer) = issuer_override {
-        template.issuer = Some(issuer);
-    }
-    if let Some(account) = account_override {
-        template.account = Some(account);
-    }
-    if let Some(digits) = digits_override {
-        template.digits = Some(digits);
-    }
-    if let Some(period) = period_override {
-        template.period = Some(period);
-    }
-    if let Some(algo) = algorithm_override {
-        template.algorithm = Some(algo);
-    }
-
     let final_template = template.finalize()?;
     let origin_url = url.map(|s| normalize_origin_url(&s)).transpose()?;
 
@@ -159,27 +199,16 @@ async fn setup_totp(
         })
         .unwrap_or_else(|| final_template.account.clone());
 
-    let data = CredentialData::TwoFactor(TwoFactorData {
-        secret_key: final_template.secret.clone(),
-        issuer: final_template.issuer.clone(),
-        account_name: final_template.account.clone(),
-        algorithm: final_template.algorithm.clone(),
-        digits: final_template.digits,
-        period: final_template.period,
-    });
-
-    let mut credential = service
-        .create_credential(
-            identity.id,
-            credential_name.clone(),
-            CredentialType::TwoFactor,
-            SecurityLevel::High,
-            &data,
-        )
-        .await
-        .into_anyhow()
-        .context("Failed to create TOTP credential")?;
+    let data = CredentialData::TwoFactor(final_template.to_data());
 
+    let mut credential = Credential::new(
+        identity.id,
+        credential_name.clone(),
+        CredentialType::TwoFactor,
+        SecurityLevel::High,
+        Vec::new(),
+        None,
+    );
     credential.username = Some(final_template.account.clone());
     if let Some(url) = origin_url {
         credential.url = Some(url);
@@ -193,11 +222,11 @@ async fn setup_totp(
     credential
         .metadata
         .insert("digits".into(), final_template.digits.to_string());
-    service
-        .update_credential(&credential)
+    let credential = service
+        .create_credential_full(credential, &data)
         .await
         .into_anyhow()
-        .context("Failed to update TOTP metadata")?;
+        .context("Failed to create TOTP credential")?;
 
     println!(
         "{} Saved TOTP credential '{}' for identity '{}'",
@@ -206,13 +235,67 @@ async fn setup_totp(
         identity.name.bright_cyan()
     );
 
-    let (code, remaining) = generate_totp_code(&final_template)?;
+    if final_template.otp_type == OtpType::Hotp {
+        println!(
+            "Counter-based entry; run `persona totp next {}` to generate a code",
+            credential.id
+        );
+    } else {
+        let current = otp::totp_code(&final_template.to_data()).into_anyhow()?;
+        println!(
+            "Current code: {} (valid for {}s)",
+            current.code.bold().bright_blue(),
+            current.remaining
+        );
+    }
+
+    Ok(())
+}
+
+/// Store every account found in `sources`, one credential each
+async fn import_accounts(
+    config: &CliConfig,
+    identity_name: String,
+    sources: Vec<String>,
+) -> Result<()> {
+    let mut service = init_service(config).await?;
+    let identity = resolve_identity(&mut service, &identity_name).await?;
+
+    let (mut imported, mut skipped) = (0, 0);
+    for (index, source) in sources.iter().enumerate() {
+        // URIs carry secrets, so errors name them by position only
+        let (uri, label) = if source.contains("://") {
+            (source.clone(), format!("URI #{}", index + 1))
+        } else {
+            let path = PathBuf::from(source);
+            (decode_qr_file(&path)?, path.display().to_string())
+        };
+        let result = service
+            .import_otp_uri(identity.id, &uri)
+            .await
+            .into_anyhow()
+            .with_context(|| format!("Failed to import {}", label))?;
+        for credential in &result.imported {
+            println!("  {} {}", "+".green(), credential.name);
+        }
+        for name in &result.skipped {
+            println!("  {} {} (already present)", "=".dimmed(), name.dimmed());
+        }
+        imported += result.imported.len();
+        skipped += result.skipped.len();
+    }
+
     println!(
-        "Current code: {} (valid for {}s)",
-        code.bold().bright_blue(),
-        remaining
+        "{} Imported {} account(s) into '{}'{}",
+        "✓".green(),
+        imported,
+        identity.name.bright_cyan(),
+        if skipped > 0 {
+            format!(", skipped {} already present", skipped)
+        } else {
+            String::new()
+        }
     );
-
     Ok(())
 }
 
@@ -237,7 +320,7 @@ async fn generate_codes(config: &CliConfig, id: Uuid, watch: bool) -> Result<()>
         .get_credential(&id)
         .await
         .into_anyhow()?
-        .ok_or_else(|| anyhow!("Credential {} not found", id))?;
+        .ok_or_else(|| PersonaError::NotFound(format!("Credential {} not found", id)))?;
     if !matches!(credential.credential_type, CredentialType::TwoFactor) {
         bail!("Credential {} is not a TOTP entry", id);
     }
@@ -250,25 +333,32 @@ async fn generate_codes(config: &CliConfig, id: Uuid, watch: bool) -> Result<()>
         CredentialData::TwoFactor(data) => data,
         _ => bail!("Credential {} does not contain TOTP data", id),
     };
+    if data.otp_type == OtpType::Hotp {
+        bail!(
+            "Credential {} is counter-based; use `persona totp next {}`",
+            id,
+            id
+        );
+    }
 
     if watch {
         loop {
-            let (code, remaining) = generate_totp_code_from_data(&data)?;
+            let current = otp::totp_code(&data).into_anyhow()?;
             println!(
                 "{} → {} ({}s remaining)",
                 chrono::Utc::now().format("%H:%M:%S"),
-                code.bold().bright_blue(),
-                remaining
+                current.code.bold().bright_blue(),
+                current.remaining
             );
             std::thread::sleep(Duration::from_secs(1));
         }
     } else {
-        let (code, remaining) = generate_totp_code_from_data(&data)?;
+        let current = otp::totp_code(&data).into_anyhow()?;
         println!(
             "TOTP code for {}: {} ({}s remaining)",
             credential.name.bright_cyan(),
-            code.bold().bright_blue(),
-            remaining
+            current.code.bold().bright_blue(),
+            current.remaining
         );
     }
     // unreachable if watch loop
@@ -277,17 +367,83 @@ async fn generate_codes(config: &CliConfig, id: Uuid, watch: bool) -> Result<()>
     Ok(())
 }
 
+async fn next_hotp_code(config: &CliConfig, id: Uuid) -> Result<()> {
+    let service = init_service(config).await?;
+    let code = service
+        .next_hotp_code(&id)
+        .await
+        .into_anyhow()
+        .context("Failed to generate HOTP code")?;
+    println!("HOTP code: {}", code.bold().bright_blue());
+    Ok(())
+}
+
+async fn verify_code(config: &CliConfig, id: Uuid, code: String, skew: u32) -> Result<()> {
+    let service = init_service(config).await?;
+    let verification = service
+        .verify_totp(&id, &code, skew)
+        .await
+        .into_anyhow()
+        .context("Failed to verify TOTP code")?;
+    match verification {
+        TotpVerification::Accepted { step } => {
+            println!("{} Code is valid (time step {})", "✓".green(), step);
+            Ok(())
+        }
+        TotpVerification::Replayed { step } => Err(PersonaError::Validation(format!(
+            "Code for time step {} was already used",
+            step
+        ))
+        .into()),
+        TotpVerification::Rejected => Err(PersonaError::Validation(format!(
+            "Code does not match within {} time step(s) of the current one",
+            skew
+        ))
+        .into()),
+    }
+}
+
+async fn calibrate(
+    config: &CliConfig,
+    id: Uuid,
+    previous_code: String,
+    code: String,
+) -> Result<()> {
+    let service = init_service(config).await?;
+    let offset = service
+        .calibrate_totp(&id, &previous_code, &code)
+        .await
+        .into_anyhow()
+        .context("Failed to calibrate TOTP credential")?;
+    if offset == 0 {
+        println!("{}", "✓ Clock is in sync; no correction needed".green());
+    } else {
+        println!("{} Stored a clock correction of {:+}s", "✓".green(), offset);
+    }
+    Ok(())
+}
+
 #[derive(Default)]
-struct TotpTemplate {
+pub(crate) struct TotpTemplate {
     secret: Option<String>,
     issuer: Option<String>,
     account: Option<String>,
     algorithm: Option<String>,
     digits: Option<u8>,
     period: Option<u32>,
+    otp_type: Option<OtpType>,
+    counter: Option<u64>,
 }
 
 impl TotpTemplate {
+    /// A template holding only a bare base32 secret
+    pub(crate) fn from_secret(secret: String) -> Self {
+        Self {
+            secret: Some(secret),
+            ..Self::default()
+        }
+    }
+
     fn merge(&mut self, other: TotpTemplate) {
         if self.secret.is_none() {
             self.secret = other.secret;
@@ -307,9 +463,15 @@ impl TotpTemplate {
         if self.period.is_none() {
             self.period = other.period;
         }
+        if self.otp_type.is_none() {
+            self.otp_type = other.otp_type;
+        }
+        if self.counter.is_none() {
+            self.counter = other.counter;
+        }
     }
 
-    fn finalize(self) -> Result<FinalTotpConfig> {
+    pub(crate) fn finalize(self) -> Result<FinalTotpConfig> {
         let secret = self
             .secret
             .ok_or_else(|| anyhow!("Secret not provided via QR/otpauth/--secret"))?;
@@ -325,71 +487,53 @@ impl TotpTemplate {
             algorithm: algorithm.to_uppercase(),
             digits,
             period,
+            otp_type: self.otp_type.unwrap_or_default(),
+            counter: self.counter.unwrap_or(0),
         })
     }
 }
 
-struct FinalTotpConfig {
+pub(crate) struct FinalTotpConfig {
     secret: String,
     issuer: String,
     account: String,
     algorithm: String,
     digits: u8,
     period: u32,
+    otp_type: OtpType,
+    counter: u64,
 }
 
-fn parse_otpauth_uri(uri: &str) -> Result<TotpTemplate> {
-    let url = url::Url::parse(uri).context("Invalid otpauth URI")?;
-    if url.scheme() != "otpauth" {
-        bail!("URI must start with otpauth://");
-    }
-    if url.host_str() != Some("totp") {
-        bail!("Only otpauth TOTP URIs are supported");
-    }
-    let path = url.path().trim_start_matches('/');
-    let (issuer_label, account_label) = if let Some(pos) = path.find(':') {
-        (
-            path[..pos].trim().to_string(),
-            path[pos + 1..].trim().to_string(),
-        )
-    } else {
-        ("".into(), path.to_string())
-    };
-
-    let mut secret = None;
-    let mut issuer = if issuer_label.is_empty() {
-        None
-    } else {
-        Some(issuer_label)
-    };
-    let mut account = if account_label.is_empty() {
-        None
-    } else {
-        Some(account_label)
-    };
-    let mut algorithm = None;
-    let mut digits = None;
-    let mut period = None;
-
-    for (key, value) in url.query_pairs() {
-        match key.as_ref() {
-            "secret" => secret = Some(value.to_string()),
-            "issuer" => issuer = Some(value.to_string()),
-            "account" => account = Some(value.to_string()),
-            "algorithm" => algorithm = Some(value.to_string()),
-            "digits" => digits = value.parse().ok(),
-            "period" => period = value.parse().ok(),
-            _ => {}
+impl FinalTotpConfig {
+    pub(crate) fn to_data(&self) -> TwoFactorData {
+        TwoFactorData {
+            secret_key: self.secret.clone().into(),
+            issuer: self.issuer.clone(),
+            account_name: self.account.clone(),
+            algorithm: self.algorithm.clone(),
+            digits: self.digits,
+            period: self.period,
+            otp_type: self.otp_type,
+            counter: self.counter,
+            time_offset_seconds: 0,
         }
     }
+}
 
+pub(crate) fn parse_otpauth_uri(uri: &str) -> Result<TotpTemplate> {
+    let data = totp_import::parse_otpauth_uri(uri)
+        .into_anyhow()
+        .context("Invalid otpauth URI")?;
+    let non_empty = |value: String| (!value.is_empty()).then_some(value);
     Ok(TotpTemplate {
-        secret,
-        issuer,
-        account,
-        algorithm,
-        digits,
-        period,
+        secret: Some(data.secret_key.expose().to_string()),
+        issuer: non_empty(data.issuer),
+        account: non_empty(data.account_name),
+        algorithm: Some(data.algorithm),
+        digits: Some(data.digits),
+        period: Some(data.period),
+        otp_type: Some(data.otp_type),
+        counter: Some(data.counter),
     })
 }
 
@@ -408,82 +552,15 @@ fn decode_qr_file(path: &PathBuf) -> Result<String> {
     Ok(content)
 }
 
-fn generate_totp_code(template: &FinalTotpConfig) -> Result<(String, u32)> {
-    let data = TwoFactorData {
-        secret_key: template.secret.clone(),
-        issuer: template.issuer.clone(),
-        account_name: template.account.clone(),
-        algorithm: template.algorithm.clone(),
-        digits: template.digits,
-        period: template.period,
-    };
-    generate_totp_code_from_data(&data)
-}
-
-fn generate_totp_code_from_data(data: &TwoFactorData) -> Result<(String, u32)> {
-    let secret_bytes = decode_secret(&data.secret_key)?;
-    let now = chrono::Utc::now();
-    let period = data.period.max(1) as u64;
-    let timestamp = now.timestamp().max(0) as u64;
-    let counter = timestamp / period;
-    let digits = data.digits.clamp(4, 10) as u32;
-    let code_num = hotp(&secret_bytes, counter, &data.algorithm)?;
-    let modulo = 10_u32.pow(digits);
-    let value = code_num % modulo;
-    let code = format!("{:0width$}", value, width = digits as usize);
-    let remaining = (period - (timestamp % period)) as u32;
-    Ok((code, remaining))
-}
-
-fn hotp(secret: &[u8], counter: u64, algorithm: &str) -> Result<u32> {
-    let msg = counter.to_be_bytes();
-    let algo = algorithm.to_ascii_uppercase();
-    let hash = if algo == "SHA256" {
-        type HmacSha256 = Hmac<sha2::Sha256>;
-        let mut mac = HmacSha256::new_from_slice(secret).context("Invalid secret")?;
-        mac.update(&msg);
-        mac.finalize().into_bytes().to_vec()
-    } else if algo == "SHA512" {
-        type HmacSha512 = Hmac<sha2::Sha512>;
-        let mut mac = HmacSha512::new_from_slice(secret).context("Invalid secret")?;
-        mac.update(&msg);
-        mac.finalize().into_bytes().to_vec()
-    } else {
-        type HmacSha1 = Hmac<sha1::Sha1>;
-        let mut mac = HmacSha1::new_from_slice(secret).context("Invalid secret")?;
-        mac.update(&msg);
-        mac.finalize().into_bytes().to_vec()
-    };
-
-    let offset = (hash.last().copied().unwrap_or(0) & 0x0f) as usize;
-    if offset + 4 > hash.len() {
-        bail!("Invalid HMAC output");
-    }
-    let slice = &hash[offset..offset + 4];
-    let binary = ((slice[0] as u32 & 0x7f) << 24)
-        | ((slice[1] as u32) << 16)
-        | ((slice[2] as u32) << 8)
-        | slice[3] as u32;
-    Ok(binary)
-}
-
-fn decode_secret(secret: &str) -> Result<Vec<u8>> {
-    let normalized: String = secret
-        .chars()
-        .filter(|c| !c.is_whitespace())
-        .map(|c| c.to_ascii_uppercase())
-        .collect::<String>()
-        .trim_matches('=')
-        .to_string();
-    BASE32_NOPAD
-        .decode(normalized.as_bytes())
-        .or_else(|_| BASE32.decode(normalized.as_bytes()))
-        .map_err(|e| anyhow!("Invalid base32 secret: {}", e))
-}
-
 async fn init_service(config: &CliConfig) -> Result<PersonaService> {
+    if config.demo {
+        return PersonaService::demo()
+            .await
+            .into_anyhow()
+            .context("Failed to open demo vault");
+    }
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = open_database(&db_path)
         .await
         .into_anyhow()
         .with_context(|| format!("Failed to connect to database: {}", db_path.display()))?;
@@ -502,20 +579,13 @@ async fn init_service(config: &CliConfig) -> Result<PersonaService> {
         .into_anyhow()
         .context("Failed to check users")?
     {
-        let password = dialoguer::Password::new()
-            .with_prompt("Enter master password to unlock")
-            .interact()?;
-        match service
-            .authenticate_user(&password)
-            .await
-            .into_anyhow()
-            .context("Failed to authenticate user")?
-        {
-            persona_core::auth::authentication::AuthResult::Success => Ok(service),
-            other => anyhow::bail!("Authentication failed: {:?}", other),
-        }
+        auth::unlock(&mut service).await?;
+        Ok(service)
     } else {
-        anyhow::bail!("Workspace not initialized. Run `persona init` first");
+        Err(PersonaError::ConfigurationError(
+            "Workspace not initialized. Run `persona init` first".to_string(),
+        )
+        .into())
     }
 }
 
@@ -524,7 +594,7 @@ async fn resolve_identity(service: &mut PersonaService, name: &str) -> Result<Id
         .get_identity_by_name(name)
         .await
         .into_anyhow()?
-        .ok_or_else(|| anyhow!("Identity '{}' not found", name))
+        .ok_or_else(|| PersonaError::IdentityNotFound(name.to_string()).into())
 }
 
 type Identity = persona_core::models::Identity;
@@ -603,7 +673,7 @@ mod tests {
         #[test]
         fn base32_secret_roundtrip(bytes in collection::vec(any::<u8>(), 8..=64)) {
             let encoded = BASE32_NOPAD.encode(&bytes);
-            let decoded = decode_secret(&encoded).unwrap();
+            let decoded = otp::decode_secret(&encoded).unwrap();
             prop_assert_eq!(decoded, bytes);
         }
     }
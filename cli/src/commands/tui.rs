@@ -1,4 +1,5 @@
-use crate::{config::CliConfig, utils::core_ext::CoreResultExt};
+use crate::utils::open_database;
+use crate::{commands::auth, config::CliConfig, utils::core_ext::CoreResultExt};
 use anyhow::{anyhow, Context, Result};
 use clap::Args;
 use crossterm::{
@@ -6,12 +7,11 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use dialoguer::Password;
 use persona_core::{
-    auth::AuthResult,
     models::{Credential as CoreCredential, Identity as CoreIdentity},
+    names_match,
     storage::{CredentialRepository, IdentityRepository, Repository},
-    Database, PersonaService,
+    PersonaService,
 };
 use ratatui::{
     backend::CrosstermBackend,
@@ -53,8 +53,14 @@ pub async fn execute(args: TuiArgs, config: &CliConfig) -> Result<()> {
 }
 
 async fn init_data_provider(config: &CliConfig) -> Result<DataProvider> {
+    if config.demo {
+        let service = PersonaService::demo()
+            .await
+            .context("Failed to open demo vault")?;
+        return Ok(DataProvider::Service(service));
+    }
     let db_path = config.get_database_path();
-    let db: persona_core::Database = Database::from_file::<std::path::PathBuf>(db_path.to_owned())
+    let db: persona_core::Database = open_database(db_path.to_owned())
         .await
         .into_anyhow()
         .with_context(|| format!("Failed to open database at {}", db_path.display()))?;
@@ -72,20 +78,8 @@ async fn init_data_provider(config: &CliConfig) -> Result<DataProvider> {
         .await
         .context("Failed to check workspace users")?
     {
-        let password = Password::new()
-            .with_prompt("Enter master password to unlock")
-            .allow_empty_password(false)
-            .interact()
-            .context("Failed to read password")?;
-
-        match service
-            .authenticate_user(&password)
-            .await
-            .context("Authentication failed")?
-        {
-            AuthResult::Success => Ok(DataProvider::Service(service)),
-            other => Err(anyhow!("Authentication failed: {:?}", other)),
-        }
+        auth::unlock(&mut service).await?;
+        Ok(DataProvider::Service(service))
     } else {
         Ok(DataProvider::Direct {
             identity_repo: IdentityRepository::new(repo_db.clone()),
@@ -318,7 +312,7 @@ impl AppState {
             if let Some(pos) = self
                 .identities
                 .iter()
-                .position(|id| id.name.eq_ignore_ascii_case(name))
+                .position(|id| names_match(&id.name, name))
             {
                 self.selected = pos;
             } else if self.selected >= self.identities.len() {
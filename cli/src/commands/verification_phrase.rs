@@ -0,0 +1,62 @@
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use colored::*;
+
+use crate::utils::open_database;
+use crate::{commands::auth, config::CliConfig, utils::core_ext::CoreResultExt};
+use persona_core::PersonaService;
+
+#[derive(Args, Debug)]
+pub struct VerificationPhraseArgs {
+    #[command(subcommand)]
+    command: VerificationPhraseCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum VerificationPhraseCommand {
+    /// Show the phrase displayed before the master password prompt
+    Show,
+    /// Replace the phrase with a new random one
+    Reset,
+}
+
+pub async fn execute(args: VerificationPhraseArgs, config: &CliConfig) -> Result<()> {
+    let mut service = open_service(config).await?;
+
+    match args.command {
+        VerificationPhraseCommand::Show => {
+            match service.verification_phrase().await.into_anyhow()? {
+                Some(phrase) => println!("{}", phrase.cyan().bold()),
+                None => println!(
+                    "No verification phrase set. Create one with `persona verification-phrase reset`."
+                ),
+            }
+        }
+        VerificationPhraseCommand::Reset => {
+            if service.has_users().await.into_anyhow()? {
+                auth::unlock(&mut service).await?;
+            }
+            let phrase = service.reset_verification_phrase().await.into_anyhow()?;
+            println!(
+                "{} New verification phrase: {}",
+                "✓".green().bold(),
+                phrase.cyan().bold()
+            );
+            println!("  Expect to see it before every master password prompt from now on.");
+        }
+    }
+    Ok(())
+}
+
+async fn open_service(config: &CliConfig) -> Result<PersonaService> {
+    let db_path = config.get_database_path();
+    let db = open_database(&db_path)
+        .await
+        .into_anyhow()
+        .with_context(|| format!("Failed to connect to database: {}", db_path.display()))?;
+    db.migrate()
+        .await
+        .into_anyhow()
+        .context("Failed to run database migrations")?;
+    PersonaService::new(db).await.into_anyhow()
+}
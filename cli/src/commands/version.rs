@@ -0,0 +1,21 @@
+use anyhow::Result;
+use clap::Args;
+
+use persona_core::build_info;
+
+#[derive(Args, Debug)]
+pub struct VersionArgs {
+    /// Output format (text, json)
+    #[arg(short, long, default_value = "text")]
+    format: String,
+}
+
+pub async fn execute(args: VersionArgs) -> Result<()> {
+    let info = build_info();
+    match args.format.as_str() {
+        "text" => println!("persona {}", info),
+        "json" => println!("{}", serde_json::to_string_pretty(&info)?),
+        other => anyhow::bail!("Unsupported format '{}' (expected text or json)", other),
+    }
+    Ok(())
+}
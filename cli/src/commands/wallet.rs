@@ -1,13 +1,31 @@
-use crate::{config::CliConfig, utils::core_ext::CoreResultExt};
+use crate::{
+    config::CliConfig,
+    utils::{confirm_action, core_ext::CoreResultExt},
+};
 use anyhow::{anyhow, bail, Context, Result};
 use clap::{Args, Subcommand};
 use colored::*;
+#[cfg(feature = "balances")]
+use persona_core::service::balance::{BalanceProvider, CachedBalanceProvider, RpcBalanceProvider};
 use persona_core::{
+    crypto::{
+        address_generator::{bitcoin_script_pubkey, derive_address_from_xpub},
+        coin_selection::{
+            select_coins, select_manual, select_sweep, CoinSelection, CoinSelectionStrategy,
+            SpendPlan, StaticUtxoProvider, Utxo, UtxoProvider,
+        },
+        secret::SecretString,
+        wallet_crypto::{
+            build_psbt, sign_ethereum_transaction, sign_psbt, PsbtInput, PsbtOutput,
+            SignedEthereumTransaction, SignedPsbt,
+        },
+    },
     models::wallet::{
-        AddressType, BipVersion, BlockchainNetwork, CryptoWallet, TransactionRequest,
+        AddressType, BipVersion, BlockchainNetwork, CryptoWallet, NetworkKind, TransactionRequest,
         WalletAddress, WalletMetadata, WalletSecurityLevel, WalletType,
     },
-    storage::{CryptoWalletRepository, Database},
+    storage::{AddressBookRepository, CryptoWalletRepository, Database},
+    PersonaError,
 };
 use std::sync::Arc;
 use tabled::{settings::Style, Table, Tabled};
@@ -35,13 +53,21 @@ pub enum WalletCommand {
         watch_only: bool,
 
         /// Search wallets by name
-        #[arg(long, short)]
+        #[arg(long)]
         search: Option<String>,
+
+        /// Fetch live address balances before listing
+        #[arg(long)]
+        refresh_balances: bool,
     },
     /// Show details of a specific wallet
     Show {
         /// Wallet ID or name
         wallet_identifier: String,
+
+        /// Fetch live address balances before showing
+        #[arg(long)]
+        refresh_balances: bool,
     },
     /// Create a new crypto wallet
     Create {
@@ -54,7 +80,7 @@ pub enum WalletCommand {
         description: Option<String>,
 
         /// Blockchain network
-        #[arg(long, short)]
+        #[arg(long)]
         network: String,
 
         /// Wallet type (single, hd, multisig, hardware)
@@ -92,6 +118,9 @@ pub enum WalletCommand {
         /// Derivation path (for HD wallets)
         #[arg(long)]
         derivation_path: Option<String>,
+
+        #[command(flatten)]
+        network_kind: NetworkKindArgs,
     },
     /// Create a watch-only wallet
     CreateWatchOnly {
@@ -104,7 +133,7 @@ pub enum WalletCommand {
         description: Option<String>,
 
         /// Blockchain network
-        #[arg(long, short)]
+        #[arg(long)]
         network: String,
 
         /// Extended public key (xpub/ypub/zpub)
@@ -114,6 +143,9 @@ pub enum WalletCommand {
         /// Address count to derive
         #[arg(long, short)]
         address_count: Option<usize>,
+
+        #[command(flatten)]
+        network_kind: NetworkKindArgs,
     },
     /// Generate a new wallet with fresh keys
     Generate {
@@ -126,7 +158,7 @@ pub enum WalletCommand {
         description: Option<String>,
 
         /// Blockchain network
-        #[arg(long, short)]
+        #[arg(long)]
         network: String,
 
         /// Generate HD wallet
@@ -144,6 +176,13 @@ pub enum WalletCommand {
         /// Address count to derive (default: 20)
         #[arg(long, default_value = "20")]
         address_count: usize,
+
+        /// Prompt for a BIP-39 passphrase ("25th word"); it is never stored
+        #[arg(long)]
+        passphrase: bool,
+
+        #[command(flatten)]
+        network_kind: NetworkKindArgs,
     },
     /// Update wallet information
     Update {
@@ -179,7 +218,7 @@ pub enum WalletCommand {
         purpose: Option<String>,
 
         /// Add note
-        #[arg(long, short)]
+        #[arg(long)]
         note: Option<String>,
     },
     /// Delete a wallet
@@ -211,6 +250,15 @@ pub enum WalletCommand {
         #[arg(long)]
         derivation_path: Option<String>,
     },
+    /// Derive the next receive addresses of a watch-only wallet from its extended public key
+    DeriveAddress {
+        /// Wallet ID or name
+        wallet_identifier: String,
+
+        /// Number of addresses to derive
+        #[arg(long, short, default_value = "1")]
+        count: usize,
+    },
     /// List addresses in wallet
     ListAddresses {
         /// Wallet ID or name
@@ -241,7 +289,7 @@ pub enum WalletCommand {
         /// Wallet ID or name
         wallet_identifier: String,
 
-        /// To address
+        /// Recipient address or address-book label
         #[arg(long)]
         to: String,
 
@@ -250,8 +298,30 @@ pub enum WalletCommand {
         amount: String,
 
         /// Fee (in smallest unit)
+        #[arg(long, required_unless_present = "fee_rate")]
+        fee: Option<String>,
+
+        /// Bitcoin fee rate in sat/vB; sizes the fee from the selected inputs
         #[arg(long)]
-        fee: String,
+        fee_rate: Option<u64>,
+
+        /// Bitcoin output to spend (repeatable); all are spent unless --coin-selection is set.
+        /// Without it the wallet's confirmed outputs are fetched from the Bitcoin balance
+        /// endpoint. The address is needed to sign when the wallet has several
+        #[arg(
+            long = "utxo",
+            value_name = "TXID:VOUT:SATS[:ADDRESS]",
+            requires = "fee_rate"
+        )]
+        utxos: Vec<Utxo>,
+
+        /// Pick inputs from the available outputs instead of spending all of them
+        #[arg(long, value_name = "STRATEGY", requires = "fee_rate")]
+        coin_selection: Option<CoinSelectionStrategy>,
+
+        /// Address receiving Bitcoin change (defaults to an unused wallet address)
+        #[arg(long, requires = "fee_rate")]
+        change_address: Option<String>,
 
         /// Gas price (for EVM chains)
         #[arg(long)]
@@ -269,7 +339,8 @@ pub enum WalletCommand {
         #[arg(long)]
         memo: Option<String>,
 
-        /// Sign immediately (requires unlock)
+        /// Sign immediately, prompting for the wallet password (EIP-1559 for EVM wallets,
+        /// a finalized PSBT for Bitcoin)
         #[arg(long)]
         sign: bool,
 
@@ -281,6 +352,43 @@ pub enum WalletCommand {
         #[arg(long)]
         expires_in: Option<u64>,
     },
+    /// Sweep a wallet's funds into a single address
+    Consolidate {
+        /// Wallet ID or name
+        wallet_identifier: String,
+
+        /// Destination address or address-book label (defaults to a fresh receive address)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Bitcoin output to sweep (repeatable); fetched from the balance endpoint if omitted
+        #[arg(long = "utxo", value_name = "TXID:VOUT:SATS")]
+        utxos: Vec<Utxo>,
+
+        /// Bitcoin fee rate in sat/vB
+        #[arg(long)]
+        fee_rate: Option<u64>,
+
+        /// Address to sweep on EVM chains (defaults to the wallet's first address)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Balance to sweep in wei, overriding the stored address balance (EVM chains)
+        #[arg(long)]
+        balance: Option<String>,
+
+        /// Gas price in wei (EVM chains)
+        #[arg(long)]
+        gas_price: Option<String>,
+
+        /// Gas limit (EVM chains)
+        #[arg(long, default_value_t = 21_000)]
+        gas_limit: u64,
+
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
     /// List pending transactions
     ListTransactions {
         /// Wallet ID or name
@@ -298,6 +406,11 @@ pub enum WalletCommand {
         #[arg(long)]
         broadcast: bool,
     },
+    /// Show the status history of a wallet's transactions
+    History {
+        /// Wallet ID or name
+        wallet_identifier: String,
+    },
     /// Get wallet statistics
     Stats {
         /// Wallet ID or name (optional, shows overall stats if not provided)
@@ -332,9 +445,40 @@ pub enum WalletCommand {
         /// Wallet name (overrides imported name)
         #[arg(long)]
         name: Option<String>,
+
+        /// Prompt for the mnemonic's BIP-39 passphrase ("25th word"); it is never stored
+        #[arg(long)]
+        passphrase: bool,
+
+        #[command(flatten)]
+        network_kind: NetworkKindArgs,
     },
 }
 
+/// Chooses a test network instead of mainnet
+#[derive(Args, Clone, Copy)]
+pub struct NetworkKindArgs {
+    /// Use the public testnet (Bitcoin testnet, Sepolia-family EVM chains)
+    #[arg(long, conflicts_with = "regtest")]
+    testnet: bool,
+
+    /// Use a local regtest chain (Bitcoin regtest, a local EVM devnet)
+    #[arg(long)]
+    regtest: bool,
+}
+
+impl NetworkKindArgs {
+    fn kind(self) -> NetworkKind {
+        if self.regtest {
+            NetworkKind::Regtest
+        } else if self.testnet {
+            NetworkKind::Testnet
+        } else {
+            NetworkKind::Mainnet
+        }
+    }
+}
+
 /// Table display for CryptoWallet
 #[derive(Tabled)]
 struct WalletTable {
@@ -350,11 +494,27 @@ struct WalletTable {
     security: String,
     #[tabled(rename = "Addresses")]
     address_count: String,
+    #[tabled(rename = "Balance")]
+    balance: String,
     #[tabled(rename = "Watch-Only")]
     watch_only: String,
 }
 
 /// Table display for WalletAddress
+#[derive(Tabled)]
+struct TransactionHistoryTable {
+    #[tabled(rename = "Time")]
+    time: String,
+    #[tabled(rename = "Transaction")]
+    transaction: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Hash")]
+    hash: String,
+    #[tabled(rename = "Detail")]
+    detail: String,
+}
+
 #[derive(Tabled)]
 struct AddressTable {
     #[tabled(rename = "Index")]
@@ -372,7 +532,9 @@ struct AddressTable {
 }
 
 pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
-    let repo = init_wallet_repository(config).await?;
+    let db = open_database(config).await?;
+    let repo = CryptoWalletRepository::new(db.clone());
+    let address_book = AddressBookRepository::new(db);
     let formatter = OutputFormatter::default();
 
     match args.command {
@@ -381,6 +543,7 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             security_level,
             watch_only,
             search,
+            refresh_balances,
         } => {
             let mut wallets = if let Some(level_str) = security_level {
                 let level = parse_wallet_security_level(&level_str)?;
@@ -405,7 +568,7 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                 return Ok(());
             }
 
-            let filtered_wallets: Vec<_> = wallets
+            let mut filtered_wallets: Vec<_> = wallets
                 .into_iter()
                 .filter(|w| {
                     let include = true;
@@ -419,15 +582,20 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                 return Ok(());
             }
 
+            if refresh_balances {
+                refresh_wallet_balances(&repo, &mut filtered_wallets, config, &formatter).await?;
+            }
+
             let table_data: Vec<WalletTable> = filtered_wallets
                 .iter()
                 .map(|w| WalletTable {
                     id: w.id.to_string().chars().take(8).collect(),
                     name: w.name.clone(),
-                    network: format!("{}", w.network),
+                    network: format_network(w),
                     wallet_type: format_wallet_type(&w.wallet_type),
                     security: format!("{}", w.security_level),
                     address_count: w.addresses.len().to_string(),
+                    balance: format_wallet_balance(w),
                     watch_only: if w.watch_only { "✓" } else { "✗" }.to_string(),
                 })
                 .collect();
@@ -436,12 +604,24 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             formatter.print_output(&table);
         }
 
-        WalletCommand::Show { wallet_identifier } => {
-            let wallet = find_wallet_by_identifier(&repo, &wallet_identifier).await?;
+        WalletCommand::Show {
+            wallet_identifier,
+            refresh_balances,
+        } => {
+            let mut wallet = find_wallet_by_identifier(&repo, &wallet_identifier).await?;
+            if refresh_balances {
+                refresh_wallet_balances(
+                    &repo,
+                    std::slice::from_mut(&mut wallet),
+                    config,
+                    &formatter,
+                )
+                .await?;
+            }
 
             formatter.print_info(&format!("🔐 Crypto Wallet: {}", wallet.name));
             formatter.print_info(&format!("ID: {}", wallet.id));
-            formatter.print_info(&format!("Network: {}", wallet.network));
+            formatter.print_info(&format!("Network: {}", format_network(&wallet)));
             formatter.print_info(&format!("Type: {}", format_wallet_type(&wallet.wallet_type)));
             formatter.print_info(&format!("Security Level: {}", wallet.security_level));
             formatter.print_info(&format!(
@@ -465,6 +645,7 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             }
 
             formatter.print_info(&format!("Address Count: {}", wallet.addresses.len()));
+            formatter.print_info(&format!("Balance: {}", format_wallet_balance(&wallet)));
             formatter.print_info(&format!("Security Score: {}/100", wallet.security_score()));
 
             let unused_count = wallet.get_unused_addresses().len();
@@ -528,6 +709,7 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             mnemonic: _,
             private_key: _,
             derivation_path,
+            network_kind,
         } => {
             let network = parse_network(&network)?;
             let wallet_type = parse_wallet_type(&wallet_type, bip_version, address_count)?;
@@ -541,12 +723,13 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                     bail!("Watch-only wallets require an extended public key (--xpub)");
                 }
 
-                let wallet = CryptoWallet::new_watch_only(
+                let mut wallet = CryptoWallet::new_watch_only(
                     uuid::Uuid::new_v4(), // Would get from current identity
                     name,
                     network,
                     xpub.unwrap(),
                 );
+                wallet.network_kind = network_kind.kind();
 
                 let created = repo.create(&wallet).await.into_anyhow()?;
                 formatter.print_success(&format!(
@@ -565,6 +748,7 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                 );
 
                 wallet.description = description;
+                wallet.network_kind = network_kind.kind();
                 wallet.security_level = security_level;
                 wallet.derivation_path = derivation_path;
 
@@ -588,6 +772,7 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             network,
             xpub,
             address_count,
+            network_kind,
         } => {
             let network = parse_network(&network)?;
             let mut wallet = CryptoWallet::new_watch_only(
@@ -598,12 +783,19 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             );
 
             wallet.description = description;
+            wallet.network_kind = network_kind.kind();
+            if let Some(count) = address_count {
+                wallet.addresses = derive_watch_only_addresses(&wallet, count)?;
+            }
 
             let created = repo.create(&wallet).await.into_anyhow()?;
             formatter.print_success(&format!(
                 "👁️ Created watch-only wallet '{}' with ID: {}",
                 created.name, created.id
             ));
+            if !created.addresses.is_empty() {
+                formatter.print_info(&format!("Addresses: {}", created.addresses.len()));
+            }
         }
 
         WalletCommand::Generate {
@@ -614,13 +806,15 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             bip_version,
             account,
             address_count,
+            passphrase,
+            network_kind,
         } => {
             use persona_core::crypto::{
                 import_from_mnemonic, MasterKey, MnemonicWordCount, SecureMnemonic,
             };
 
             let network = parse_network(&network)?;
-            let network_str = network.to_string();
+            let network_kind = network_kind.kind();
 
             // Prompt for password
             formatter.print_info("🔐 Enter a password to encrypt your wallet:");
@@ -630,6 +824,8 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                 bail!("Password must be at least 8 characters long");
             }
 
+            let passphrase = read_bip39_passphrase(passphrase, true)?;
+
             // Generate mnemonic
             formatter.print_info("🎲 Generating new mnemonic phrase...");
             let mnemonic = SecureMnemonic::generate(MnemonicWordCount::Words24)
@@ -640,14 +836,18 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             formatter.print_warning("\n⚠️  IMPORTANT: Write down your recovery phrase!");
             formatter
                 .print_warning("This is the ONLY way to recover your wallet if you lose access.\n");
-            formatter.print_success(&format!("Recovery Phrase:\n{}\n", mnemonic_phrase));
+            formatter.print_success(&format!("Recovery Phrase:\n{}\n", mnemonic_phrase.expose()));
             formatter.print_warning("Press Enter after you've written it down securely...");
             let mut input = String::new();
             std::io::stdin().read_line(&mut input)?;
 
             // Create wallet using import function
             let derivation_path = if hd {
-                Some(CryptoWallet::recommended_derivation_path(&network, account))
+                Some(CryptoWallet::recommended_derivation_path(
+                    &network,
+                    network_kind,
+                    account,
+                ))
             } else {
                 None
             };
@@ -655,9 +855,10 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             let wallet = import_from_mnemonic(
                 uuid::Uuid::new_v4(), // Would get from current identity
                 name.clone(),
-                &mnemonic_phrase,
-                "", // No additional passphrase
+                mnemonic_phrase.expose(),
+                passphrase.expose(),
                 network,
+                network_kind,
                 derivation_path.clone(),
                 address_count,
                 &password,
@@ -670,7 +871,7 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                 "🔐 Generated new wallet '{}' with ID: {}",
                 created.name, created.id
             ));
-            formatter.print_info(&format!("Network: {}", network_str));
+            formatter.print_info(&format!("Network: {}", format_network(&created)));
             formatter.print_info(&format!("Addresses generated: {}", address_count));
 
             if let Some(path) = &created.derivation_path {
@@ -858,6 +1059,28 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             }
         }
 
+        WalletCommand::DeriveAddress {
+            wallet_identifier,
+            count,
+        } => {
+            let wallet = find_wallet_by_identifier(&repo, &wallet_identifier).await?;
+            if !wallet.watch_only {
+                bail!(
+                    "Wallet '{}' holds private keys; its addresses are derived when it is created",
+                    wallet.name
+                );
+            }
+
+            for address in derive_watch_only_addresses(&wallet, count)? {
+                repo.add_address(&wallet.id, &address).await.into_anyhow()?;
+                formatter.print_success(&format!(
+                    "Derived address #{}: {}",
+                    address.index, address.address
+                ));
+            }
+            repo.touch(&wallet.id).await.into_anyhow()?;
+        }
+
         WalletCommand::MarkUsed {
             wallet_identifier,
             address,
@@ -875,6 +1098,39 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             }
         }
 
+        WalletCommand::History { wallet_identifier } => {
+            let wallet = find_wallet_by_identifier(&repo, &wallet_identifier).await?;
+            let history = repo
+                .get_transaction_history(&wallet.id)
+                .await
+                .into_anyhow()?;
+
+            if history.is_empty() {
+                formatter.print_info("No transaction history found.");
+                return Ok(());
+            }
+
+            formatter.print_info(&format!(
+                "📜 Transaction history for wallet '{}':",
+                wallet.name
+            ));
+            let table_data: Vec<TransactionHistoryTable> = history
+                .iter()
+                .map(|event| TransactionHistoryTable {
+                    time: event.created_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+                    transaction: event.request_id.to_string().chars().take(8).collect(),
+                    status: event.status.to_string(),
+                    hash: event
+                        .transaction_hash
+                        .clone()
+                        .unwrap_or_else(|| "-".to_string()),
+                    detail: event.detail.clone().unwrap_or_default(),
+                })
+                .collect();
+            let table = Table::new(&table_data).with(Style::modern()).to_string();
+            formatter.print_output(&table);
+        }
+
         WalletCommand::Stats { wallet_identifier } => {
             if let Some(identifier) = wallet_identifier {
                 let wallet = find_wallet_by_identifier(&repo, &identifier).await?;
@@ -938,7 +1194,10 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                 ExportFormat::Mnemonic => {
                     let pwd =
                         password.ok_or_else(|| anyhow!("Password required for mnemonic export"))?;
-                    export_mnemonic(&wallet, &pwd).context("Failed to export mnemonic")?
+                    export_mnemonic(&wallet, &pwd)
+                        .context("Failed to export mnemonic")?
+                        .expose()
+                        .to_string()
                 }
                 ExportFormat::PrivateKey => {
                     let pwd = password
@@ -968,19 +1227,20 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             }
         }
 
-        WalletCommand::Import { format, data, name } => {
+        WalletCommand::Import {
+            format,
+            data,
+            name,
+            passphrase,
+            network_kind,
+        } => {
             use persona_core::crypto::{
-                import_from_mnemonic, import_from_private_key, parse_import_format, ImportFormat,
+                import_from_mnemonic, import_from_private_key, parse_import_format,
+                validate_mnemonic, ImportFormat,
             };
 
             let import_format = parse_import_format(&format)?;
-
-            formatter.print_info("Enter a password to encrypt the imported wallet:");
-            let password = rpassword::read_password().context("Failed to read password")?;
-
-            if password.len() < 8 {
-                bail!("Password must be at least 8 characters long");
-            }
+            let network_kind = network_kind.kind();
 
             // Read import data (from file or direct input)
             let import_data = if std::path::Path::new(&data).exists() {
@@ -989,6 +1249,20 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                 data.clone()
             };
 
+            // Catch typos before asking for anything else
+            if import_format == ImportFormat::Mnemonic {
+                if let Err(err) = validate_mnemonic(&import_data) {
+                    bail!("Invalid mnemonic: {}", err);
+                }
+            }
+
+            formatter.print_info("Enter a password to encrypt the imported wallet:");
+            let password = rpassword::read_password().context("Failed to read password")?;
+
+            if password.len() < 8 {
+                bail!("Password must be at least 8 characters long");
+            }
+
             let wallet = match import_format {
                 ImportFormat::Mnemonic => {
                     formatter.print_info("Enter network (bitcoin/ethereum/solana):");
@@ -1002,19 +1276,24 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                     let address_count = count_input.trim().parse().unwrap_or(20);
 
                     let wallet_name = name.unwrap_or_else(|| "Imported Wallet".to_string());
+                    let passphrase = read_bip39_passphrase(passphrase, false)?;
 
                     import_from_mnemonic(
                         uuid::Uuid::new_v4(),
                         wallet_name,
                         import_data.trim(),
-                        "",
+                        passphrase.expose(),
                         network,
+                        network_kind,
                         None,
                         address_count,
                         &password,
                     )
                     .context("Failed to import from mnemonic")?
                 }
+                _ if passphrase => {
+                    bail!("--passphrase only applies to mnemonic imports");
+                }
                 ImportFormat::PrivateKey => {
                     formatter.print_info("Enter network (bitcoin/ethereum/solana):");
                     let mut network_input = String::new();
@@ -1028,6 +1307,7 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                         wallet_name,
                         import_data.trim(),
                         network,
+                        network_kind,
                         &password,
                     )
                     .context("Failed to import from private key")?
@@ -1050,20 +1330,27 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             to,
             amount,
             fee,
+            fee_rate,
+            utxos,
+            coin_selection,
+            change_address,
             gas_price,
             gas_limit,
             nonce,
             memo,
-            sign: _,
+            sign,
             broadcast: _,
             expires_in,
         } => {
             let wallet = find_wallet_by_identifier(&repo, &wallet_identifier).await?;
+            let to =
+                resolve_recipient(&repo, &address_book, &to, &wallet.network, &formatter).await?;
 
-            let transaction = TransactionRequest {
+            let mut transaction = TransactionRequest {
                 id: uuid::Uuid::new_v4(),
                 wallet_id: wallet.id,
-                network: wallet.network,
+                network: wallet.network.clone(),
+                chain_id: wallet.network.chain_id(wallet.network_kind),
                 from_address: wallet
                     .addresses
                     .first()
@@ -1071,7 +1358,7 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                     .unwrap_or_default(),
                 to_address: to,
                 amount,
-                fee,
+                fee: fee.unwrap_or_default(),
                 gas_price,
                 gas_limit,
                 nonce,
@@ -1084,6 +1371,79 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
                 metadata: std::collections::HashMap::new(),
             };
 
+            if let Some(fee_rate) = fee_rate {
+                let selection = select_inputs(
+                    &wallet,
+                    &transaction,
+                    utxos,
+                    fee_rate,
+                    coin_selection,
+                    config,
+                )
+                .await?;
+                let change_address = match change_address {
+                    Some(address) => address,
+                    None => wallet
+                        .change_address()
+                        .map(|a| a.address.clone())
+                        .ok_or_else(|| anyhow!("Wallet has no address to receive change"))?,
+                };
+                selection
+                    .apply_to(&mut transaction, &change_address)
+                    .into_anyhow()?;
+
+                formatter.print_info(&format!(
+                    "Selected {} input(s) totalling {} sats:",
+                    selection.inputs.len(),
+                    selection.input_total()
+                ));
+                for input in &selection.inputs {
+                    formatter.print_info(&format!("  {} ({} sats)", input.outpoint(), input.value));
+                }
+                if selection.change > 0 {
+                    formatter.print_info(&format!(
+                        "Change: {} sats to {}",
+                        selection.change, change_address
+                    ));
+                }
+            }
+
+            let psbt = if transaction.raw_transaction_data.is_some() {
+                let psbt = build_plan_psbt(&wallet, &transaction)?;
+                transaction
+                    .metadata
+                    .insert("psbt".to_string(), psbt.clone());
+                Some(psbt)
+            } else {
+                None
+            };
+
+            // Sign before storing so a missing nonce or bad password leaves nothing behind
+            let signed = if sign {
+                if wallet.watch_only {
+                    bail!(
+                        "Wallet '{}' is watch-only; omit --sign to get an unsigned PSBT",
+                        wallet.name
+                    );
+                }
+                if wallet.network == BlockchainNetwork::Bitcoin && psbt.is_none() {
+                    bail!("Signing a Bitcoin transaction needs --fee-rate");
+                }
+                formatter.print_info("Enter wallet password:");
+                let password = rpassword::read_password().context("Failed to read password")?;
+                Some(match &psbt {
+                    Some(psbt) => {
+                        SignedSpend::Bitcoin(sign_psbt(&wallet, &password, psbt).into_anyhow()?)
+                    }
+                    None => SignedSpend::Ethereum(
+                        sign_ethereum_transaction(&wallet, &password, &transaction)
+                            .into_anyhow()?,
+                    ),
+                })
+            } else {
+                None
+            };
+
             let created = repo
                 .create_transaction_request(&transaction)
                 .await
@@ -1096,6 +1456,96 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
             formatter.print_info(&format!("To: {}", created.to_address));
             formatter.print_info(&format!("Amount: {} units", created.amount));
             formatter.print_info(&format!("Fee: {} units", created.fee));
+
+            match signed {
+                Some(SignedSpend::Ethereum(signed)) => {
+                    repo.create_signed_transaction(&signed.to_signed_transaction(&created))
+                        .await
+                        .into_anyhow()?;
+                    formatter
+                        .print_success(&format!("Signed transaction {}", signed.transaction_hash));
+                    formatter.print_info(&format!("v: {}", signed.v));
+                    formatter.print_info(&format!("r: {}", signed.r));
+                    formatter.print_info(&format!("s: {}", signed.s));
+                    formatter.print_info(&format!("Raw transaction: {}", signed.raw_transaction));
+                }
+                Some(SignedSpend::Bitcoin(signed)) => {
+                    repo.create_signed_transaction(&signed.to_signed_transaction(&created))
+                        .await
+                        .into_anyhow()?;
+                    formatter.print_success(&format!("Signed transaction {}", signed.txid));
+                    formatter.print_info(&format!("PSBT: {}", signed.psbt));
+                    formatter.print_info(&format!("Raw transaction: {}", signed.raw_transaction));
+                }
+                None => {
+                    if let Some(psbt) = psbt {
+                        formatter.print_info(&format!("Unsigned PSBT: {}", psbt));
+                    }
+                }
+            }
+        }
+
+        WalletCommand::Consolidate {
+            wallet_identifier,
+            to,
+            utxos,
+            fee_rate,
+            from,
+            balance,
+            gas_price,
+            gas_limit,
+            yes,
+        } => {
+            let wallet = find_wallet_by_identifier(&repo, &wallet_identifier).await?;
+            let to = match to {
+                Some(to) => {
+                    resolve_recipient(&repo, &address_book, &to, &wallet.network, &formatter)
+                        .await?
+                }
+                None => wallet
+                    .receive_address()
+                    .map(|a| a.address.clone())
+                    .ok_or_else(|| {
+                        anyhow!("Wallet has no unused receive address; pass --to or add one")
+                    })?,
+            };
+
+            let transaction = if wallet.network == BlockchainNetwork::Bitcoin {
+                let fee_rate =
+                    fee_rate.ok_or_else(|| anyhow!("--fee-rate is required for Bitcoin"))?;
+                plan_bitcoin_sweep(&wallet, to, utxos, fee_rate, config, &formatter).await?
+            } else if wallet.network.is_evm() {
+                let gas_price =
+                    gas_price.ok_or_else(|| anyhow!("--gas-price is required for EVM chains"))?;
+                plan_evm_sweep(&wallet, to, from, balance, gas_price, gas_limit, &formatter)?
+            } else {
+                bail!(
+                    "Consolidation is not supported for {} wallets",
+                    wallet.network
+                );
+            };
+
+            if !yes
+                && !confirm_action(
+                    &format!(
+                        "Send {} units to {}?",
+                        transaction.amount, transaction.to_address
+                    ),
+                    false,
+                )?
+            {
+                formatter.print_warning("Consolidation cancelled. Use --yes to skip confirmation.");
+                return Ok(());
+            }
+
+            let created = repo
+                .create_transaction_request(&transaction)
+                .await
+                .into_anyhow()?;
+            formatter.print_success(&format!(
+                "Created consolidation transaction request with ID: {}",
+                created.id
+            ));
         }
 
         WalletCommand::ListTransactions {
@@ -1138,9 +1588,9 @@ pub async fn handle_wallet(args: WalletArgs, config: &CliConfig) -> Result<()> {
 
 // Helper functions
 
-async fn init_wallet_repository(config: &CliConfig) -> Result<CryptoWalletRepository> {
+async fn open_database(config: &CliConfig) -> Result<Arc<Database>> {
     let db_path = config.get_database_path();
-    let db = Database::from_file(&db_path)
+    let db = crate::utils::open_database(&db_path)
         .await
         .into_anyhow()
         .with_context(|| format!("Failed to open database at {}", db_path.display()))?;
@@ -1148,7 +1598,446 @@ async fn init_wallet_repository(config: &CliConfig) -> Result<CryptoWalletReposi
         .await
         .into_anyhow()
         .context("Failed to run database migrations")?;
-    Ok(CryptoWalletRepository::new(Arc::new(db)))
+    Ok(Arc::new(db))
+}
+
+/// Resolve an address-book label and warn before paying an address for the first time
+async fn resolve_recipient(
+    repo: &CryptoWalletRepository,
+    address_book: &AddressBookRepository,
+    to: &str,
+    network: &BlockchainNetwork,
+    formatter: &OutputFormatter,
+) -> Result<String> {
+    let address = address_book
+        .resolve_recipient(to, network)
+        .await
+        .into_anyhow()?;
+    if address != to {
+        formatter.print_info(&format!("Recipient '{}' is {}", to, address));
+    }
+    if !repo.has_transactions_to(&address).await.into_anyhow()? {
+        formatter.print_warning(&format!(
+            "{} has never been sent to before; double-check it before signing",
+            address
+        ));
+    }
+    Ok(address)
+}
+
+/// Choose Bitcoin inputs for a spend from the wallet's unspent outputs
+async fn select_inputs(
+    wallet: &CryptoWallet,
+    transaction: &TransactionRequest,
+    utxos: Vec<Utxo>,
+    fee_rate: u64,
+    strategy: Option<CoinSelectionStrategy>,
+    config: &CliConfig,
+) -> Result<CoinSelection> {
+    if wallet.network != BlockchainNetwork::Bitcoin {
+        bail!("Coin selection is only supported for Bitcoin wallets");
+    }
+    let amount: u64 = transaction
+        .amount
+        .parse()
+        .with_context(|| format!("Invalid amount: {}", transaction.amount))?;
+
+    let unspent = list_wallet_unspent(wallet, utxos, config).await?;
+
+    match strategy {
+        Some(strategy) => select_coins(&unspent, amount, fee_rate, strategy),
+        None => select_manual(&unspent, amount, fee_rate),
+    }
+    .into_anyhow()
+}
+
+/// The next `count` receive addresses of a watch-only wallet, derived from its xpub
+fn derive_watch_only_addresses(wallet: &CryptoWallet, count: usize) -> Result<Vec<WalletAddress>> {
+    let xpub = wallet
+        .extended_public_key
+        .as_deref()
+        .ok_or_else(|| anyhow!("Wallet '{}' has no extended public key", wallet.name))?;
+    let start = wallet
+        .addresses
+        .iter()
+        .map(|address| address.index + 1)
+        .max()
+        .unwrap_or(0);
+
+    (start..)
+        .take(count)
+        .map(|index| {
+            let (address, address_type) =
+                derive_address_from_xpub(xpub, index, &wallet.network, wallet.network_kind)
+                    .into_anyhow()?;
+            Ok(WalletAddress {
+                address,
+                address_type,
+                derivation_path: None,
+                index,
+                used: false,
+                balance: None,
+                last_activity: None,
+                metadata: std::collections::HashMap::new(),
+                created_at: chrono::Utc::now(),
+            })
+        })
+        .collect()
+}
+
+/// Signature produced by `create-transaction --sign`
+enum SignedSpend {
+    Bitcoin(SignedPsbt),
+    Ethereum(SignedEthereumTransaction),
+}
+
+/// Unsigned PSBT for the spend plan that coin selection attached to `transaction`
+fn build_plan_psbt(wallet: &CryptoWallet, transaction: &TransactionRequest) -> Result<String> {
+    let plan: SpendPlan = serde_json::from_slice(
+        transaction
+            .raw_transaction_data
+            .as_deref()
+            .ok_or_else(|| anyhow!("Transaction has no spend plan"))?,
+    )
+    .context("Invalid spend plan")?;
+
+    let mut inputs = Vec::with_capacity(plan.inputs.len());
+    for utxo in &plan.inputs {
+        // Outputs given without an address belong to the wallet's only address
+        let address = match (&utxo.address, wallet.addresses.as_slice()) {
+            (Some(address), _) => address.as_str(),
+            (None, [only]) => only.address.as_str(),
+            (None, _) => bail!(
+                "Pass the address of {} as --utxo TXID:VOUT:SATS:ADDRESS; wallet '{}' has several",
+                utxo.outpoint(),
+                wallet.name
+            ),
+        };
+        inputs.push(PsbtInput {
+            txid: utxo.txid.clone(),
+            vout: utxo.vout,
+            amount: utxo.value,
+            script_pubkey: bitcoin_script_pubkey(address).into_anyhow()?,
+        });
+    }
+    let outputs = plan
+        .outputs
+        .iter()
+        .map(|output| {
+            Ok(PsbtOutput {
+                amount: output.value,
+                script_pubkey: bitcoin_script_pubkey(&output.address).into_anyhow()?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let fee: u64 = transaction
+        .fee
+        .parse()
+        .with_context(|| format!("Invalid fee: {}", transaction.fee))?;
+
+    build_psbt(&inputs, &outputs, fee).into_anyhow()
+}
+
+/// Build a changeless Bitcoin spend of every available output to `to`
+async fn plan_bitcoin_sweep(
+    wallet: &CryptoWallet,
+    to: String,
+    utxos: Vec<Utxo>,
+    fee_rate: u64,
+    config: &CliConfig,
+    formatter: &OutputFormatter,
+) -> Result<TransactionRequest> {
+    let unspent = list_wallet_unspent(wallet, utxos, config).await?;
+    if unspent.is_empty() {
+        bail!("No outputs to sweep; pass them with --utxo TXID:VOUT:SATS");
+    }
+
+    let selection = select_sweep(&unspent, fee_rate).into_anyhow()?;
+    let total = selection.input_total();
+    let amount = total - selection.fee;
+
+    formatter.print_info(&format!(
+        "Sweeping {} output(s) totalling {} sats to {}",
+        selection.inputs.len(),
+        total,
+        to
+    ));
+    let skipped = unspent.len() - selection.inputs.len();
+    if skipped > 0 {
+        formatter.print_warning(&format!(
+            "Skipping {} output(s) worth less than the fee to spend them",
+            skipped
+        ));
+    }
+    print_fee_impact(formatter, total as u128, selection.fee as u128, "sats");
+
+    let mut transaction = new_transaction_request(wallet, wallet_from_address(wallet), to);
+    transaction.amount = amount.to_string();
+    transaction.gas_price = Some(fee_rate.to_string());
+    // A sweep has no change output, so no change address is needed
+    selection.apply_to(&mut transaction, "").into_anyhow()?;
+    Ok(transaction)
+}
+
+/// Build an EVM transfer of an address's whole balance minus gas
+fn plan_evm_sweep(
+    wallet: &CryptoWallet,
+    to: String,
+    from: Option<String>,
+    balance: Option<String>,
+    gas_price: String,
+    gas_limit: u64,
+    formatter: &OutputFormatter,
+) -> Result<TransactionRequest> {
+    let from = from.unwrap_or_else(|| wallet_from_address(wallet));
+    let balance = match balance {
+        Some(balance) => balance,
+        None => wallet
+            .addresses
+            .iter()
+            .find(|a| a.address == from)
+            .and_then(|a| a.balance.clone())
+            .ok_or_else(|| anyhow!("No known balance for {}; pass --balance", from))?,
+    };
+    let balance: u128 = balance
+        .parse()
+        .with_context(|| format!("Invalid balance: {}", balance))?;
+    let price: u128 = gas_price
+        .parse()
+        .with_context(|| format!("Invalid gas price: {}", gas_price))?;
+    let fee = price
+        .checked_mul(gas_limit as u128)
+        .ok_or_else(|| anyhow!("Gas fee overflows"))?;
+    if balance <= fee {
+        bail!(
+            "Balance of {} wei does not cover the {} wei gas fee",
+            balance,
+            fee
+        );
+    }
+
+    formatter.print_info(&format!("Sweeping {} wei from {} to {}", balance, from, to));
+    print_fee_impact(formatter, balance, fee, "wei");
+
+    let mut transaction = new_transaction_request(wallet, from, to);
+    transaction.amount = (balance - fee).to_string();
+    transaction.fee = fee.to_string();
+    transaction.gas_price = Some(gas_price);
+    transaction.gas_limit = Some(gas_limit);
+    Ok(transaction)
+}
+
+/// Read the BIP-39 passphrase when `--passphrase` was given; empty otherwise.
+///
+/// New wallets ask twice, since a mistyped passphrase silently yields a different wallet.
+fn read_bip39_passphrase(enabled: bool, confirm: bool) -> Result<SecretString> {
+    if !enabled {
+        return Ok(SecretString::default());
+    }
+    let passphrase = SecretString::new(
+        rpassword::prompt_password("BIP-39 passphrase: ").context("Failed to read passphrase")?,
+    );
+    if confirm {
+        let repeated = SecretString::new(
+            rpassword::prompt_password("Confirm BIP-39 passphrase: ")
+                .context("Failed to read passphrase")?,
+        );
+        if repeated != passphrase {
+            bail!("Passphrases do not match");
+        }
+    }
+    Ok(passphrase)
+}
+
+/// Fetch live balances for every address of `wallets` and store them.
+///
+/// Outputs given with `--utxo`, or the wallet's confirmed outputs from the Bitcoin endpoint
+async fn list_wallet_unspent(
+    wallet: &CryptoWallet,
+    utxos: Vec<Utxo>,
+    config: &CliConfig,
+) -> Result<Vec<Utxo>> {
+    let addresses: Vec<String> = wallet.addresses.iter().map(|a| a.address.clone()).collect();
+    if utxos.is_empty() {
+        return fetch_unspent(wallet, &addresses, config).await;
+    }
+    StaticUtxoProvider::new(utxos)
+        .list_unspent(&addresses)
+        .await
+        .into_anyhow()
+}
+
+#[cfg(feature = "balances")]
+async fn fetch_unspent(
+    wallet: &CryptoWallet,
+    addresses: &[String],
+    config: &CliConfig,
+) -> Result<Vec<Utxo>> {
+    let (provider, configured) = configured_balance_provider(config)?;
+    if !wallet.network_kind.is_mainnet() && !configured.contains(&wallet.network) {
+        bail!(
+            "No {} endpoint configured for {}; pass the outputs with --utxo TXID:VOUT:SATS",
+            wallet.network,
+            wallet.network_kind
+        );
+    }
+    provider.list_unspent(addresses).await.into_anyhow()
+}
+
+#[cfg(not(feature = "balances"))]
+async fn fetch_unspent(
+    _wallet: &CryptoWallet,
+    _addresses: &[String],
+    _config: &CliConfig,
+) -> Result<Vec<Utxo>> {
+    Err(PersonaError::ConfigurationError(
+        "This build cannot look up unspent outputs (enable the `balances` feature); \
+         pass them with --utxo TXID:VOUT:SATS"
+            .to_string(),
+    )
+    .into())
+}
+
+/// Balance provider using the endpoints from the config, and the networks they were set for
+#[cfg(feature = "balances")]
+fn configured_balance_provider(
+    config: &CliConfig,
+) -> Result<(RpcBalanceProvider, Vec<BlockchainNetwork>)> {
+    let mut provider = RpcBalanceProvider::new(std::time::Duration::from_secs(10)).into_anyhow()?;
+    let mut configured = Vec::new();
+    for (network, endpoint) in &config.wallet.balance_endpoints {
+        let network = parse_network(network)?;
+        configured.push(network.clone());
+        provider = provider.with_endpoint(network, endpoint.clone());
+    }
+    Ok((provider, configured))
+}
+
+/// Public endpoints serve mainnet only, so other deployments are skipped unless the config
+/// names an endpoint for their network. An unreachable endpoint stops the refresh and leaves
+/// the stored balances in place.
+#[cfg(feature = "balances")]
+async fn refresh_wallet_balances(
+    repo: &CryptoWalletRepository,
+    wallets: &mut [CryptoWallet],
+    config: &CliConfig,
+    formatter: &OutputFormatter,
+) -> Result<()> {
+    let (provider, configured) = configured_balance_provider(config)?;
+    let provider = CachedBalanceProvider::new(
+        provider,
+        std::time::Duration::from_secs(config.wallet.balance_cache_ttl),
+    );
+
+    for wallet in wallets.iter_mut() {
+        if !wallet.network_kind.is_mainnet() && !configured.contains(&wallet.network) {
+            formatter.print_warning(&format!(
+                "Skipping balances for '{}': no {} endpoint configured for {}",
+                wallet.name, wallet.network, wallet.network_kind
+            ));
+            continue;
+        }
+        for address in wallet.addresses.iter_mut() {
+            let balance = match provider
+                .get_balance(&wallet.network, &address.address)
+                .await
+            {
+                Ok(balance) => balance,
+                Err(PersonaError::Io(err)) => {
+                    formatter.print_warning(&format!("{}; showing stored balances", err));
+                    return Ok(());
+                }
+                Err(err) => {
+                    formatter.print_warning(&format!(
+                        "Could not fetch balance of {}: {}",
+                        address.address, err
+                    ));
+                    continue;
+                }
+            };
+            repo.update_address_balance(&wallet.id, &address.address, &balance)
+                .await
+                .into_anyhow()?;
+            address.balance = Some(balance);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "balances"))]
+async fn refresh_wallet_balances(
+    _repo: &CryptoWalletRepository,
+    _wallets: &mut [CryptoWallet],
+    _config: &CliConfig,
+    _formatter: &OutputFormatter,
+) -> Result<()> {
+    Err(PersonaError::ConfigurationError(
+        "This build does not include live balances (enable the `balances` feature)".to_string(),
+    )
+    .into())
+}
+
+/// Sum of the known address balances in the network's smallest unit, or "-" if none is known
+fn format_wallet_balance(wallet: &CryptoWallet) -> String {
+    let known: Vec<u128> = wallet
+        .addresses
+        .iter()
+        .filter_map(|a| a.balance.as_deref()?.parse().ok())
+        .collect();
+    if known.is_empty() {
+        return "-".to_string();
+    }
+    let unit = match wallet.network {
+        BlockchainNetwork::Bitcoin
+        | BlockchainNetwork::BitcoinCash
+        | BlockchainNetwork::Litecoin
+        | BlockchainNetwork::Dogecoin => "sats",
+        BlockchainNetwork::Solana => "lamports",
+        BlockchainNetwork::Custom(_) => "units",
+        _ => "wei",
+    };
+    format!("{} {}", known.iter().sum::<u128>(), unit)
+}
+
+fn print_fee_impact(formatter: &OutputFormatter, total: u128, fee: u128, unit: &str) {
+    formatter.print_info(&format!(
+        "Fee: {} {} ({:.2}% of the swept balance); {} {} arrives",
+        fee,
+        unit,
+        fee as f64 / total as f64 * 100.0,
+        total - fee,
+        unit
+    ));
+}
+
+fn wallet_from_address(wallet: &CryptoWallet) -> String {
+    wallet
+        .addresses
+        .first()
+        .map(|a| a.address.clone())
+        .unwrap_or_default()
+}
+
+fn new_transaction_request(wallet: &CryptoWallet, from: String, to: String) -> TransactionRequest {
+    TransactionRequest {
+        id: uuid::Uuid::new_v4(),
+        wallet_id: wallet.id,
+        network: wallet.network.clone(),
+        chain_id: wallet.network.chain_id(wallet.network_kind),
+        from_address: from,
+        to_address: to,
+        amount: String::new(),
+        fee: String::new(),
+        gas_price: None,
+        gas_limit: None,
+        nonce: None,
+        memo: None,
+        raw_transaction_data: None,
+        required_signatures: 1,
+        created_at: chrono::Utc::now(),
+        expires_at: None,
+        metadata: std::collections::HashMap::new(),
+    }
 }
 
 async fn find_wallet_by_identifier(
@@ -1238,7 +2127,7 @@ async fn find_wallet_by_identifier(
     }
 }
 
-fn parse_network(network_str: &str) -> Result<BlockchainNetwork> {
+pub(crate) fn parse_network(network_str: &str) -> Result<BlockchainNetwork> {
     match network_str.to_lowercase().as_str() {
         "bitcoin" | "btc" => Ok(BlockchainNetwork::Bitcoin),
         "ethereum" | "eth" => Ok(BlockchainNetwork::Ethereum),
@@ -1302,6 +2191,15 @@ fn parse_address_type(type_str: &str) -> Result<AddressType> {
     }
 }
 
+/// Network name, qualified with the network kind unless it is mainnet
+fn format_network(wallet: &CryptoWallet) -> String {
+    if wallet.network_kind.is_mainnet() {
+        wallet.network.to_string()
+    } else {
+        format!("{} ({})", wallet.network, wallet.network_kind)
+    }
+}
+
 fn format_wallet_type(wallet_type: &WalletType) -> String {
     match wallet_type {
         WalletType::SingleAddress => "Single".to_string(),
@@ -1379,3 +2277,84 @@ impl OutputFormatter {
         println!("{} {}", "✗".red().bold(), message.red());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        wallet: WalletArgs,
+    }
+
+    fn parse_kind(args: &[&str]) -> NetworkKind {
+        let cli = TestCli::try_parse_from(args).unwrap();
+        match cli.wallet.command {
+            WalletCommand::CreateWatchOnly { network_kind, .. } => network_kind.kind(),
+            _ => panic!("unexpected subcommand"),
+        }
+    }
+
+    #[test]
+    fn wallet_commands_are_well_formed() {
+        TestCli::command().debug_assert();
+    }
+
+    #[test]
+    fn network_kind_flags() {
+        let base = [
+            "wallet",
+            "create-watch-only",
+            "--name",
+            "w",
+            "--network",
+            "bitcoin",
+            "--xpub",
+            "xpub",
+        ];
+        assert_eq!(parse_kind(&base), NetworkKind::Mainnet);
+        assert_eq!(
+            parse_kind(&[&base[..], &["--testnet"]].concat()),
+            NetworkKind::Testnet
+        );
+        assert_eq!(
+            parse_kind(&[&base[..], &["--regtest"]].concat()),
+            NetworkKind::Regtest
+        );
+        assert!(
+            TestCli::try_parse_from([&base[..], &["--testnet", "--regtest"]].concat()).is_err()
+        );
+    }
+
+    #[test]
+    fn watch_only_addresses_continue_after_the_last_index() {
+        // BIP-84 account key of the "abandon ... about" test mnemonic
+        let zpub = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+        let mut wallet = CryptoWallet::new_watch_only(
+            uuid::Uuid::new_v4(),
+            "watch".to_string(),
+            BlockchainNetwork::Bitcoin,
+            zpub.to_string(),
+        );
+
+        wallet.addresses = derive_watch_only_addresses(&wallet, 1).unwrap();
+        assert_eq!(
+            wallet.addresses[0].address,
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        );
+
+        let next = derive_watch_only_addresses(&wallet, 2).unwrap();
+        assert_eq!(next[0].index, 1);
+        assert_eq!(
+            next[0].address,
+            "bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g"
+        );
+        assert_eq!(next[1].index, 2);
+        assert_eq!(next[1].address_type, AddressType::P2WPKH);
+
+        wallet.extended_public_key = None;
+        assert!(derive_watch_only_addresses(&wallet, 1).is_err());
+    }
+}
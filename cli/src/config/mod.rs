@@ -1,5 +1,8 @@
 use anyhow::{Context, Result};
+use persona_core::service::balance::{BalanceEndpoint, DEFAULT_BALANCE_CACHE_TTL};
+use persona_core::CustomCredentialSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
@@ -11,6 +14,15 @@ pub struct CliConfig {
     pub sync: SyncConfig,
     pub ui: UiConfig,
     pub logging: LoggingConfig,
+    /// Schemas for custom credential types, rendered by `credential show`
+    #[serde(default)]
+    pub credential_types: Vec<CustomCredentialSchema>,
+    /// Live balance lookups for `wallet list/show --refresh-balances`
+    #[serde(default)]
+    pub wallet: WalletConfig,
+    /// Run against the in-memory demo vault (`--demo`); never persisted
+    #[serde(skip)]
+    pub demo: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +36,43 @@ pub struct SecurityConfig {
     pub encryption_enabled: bool,
     pub auto_lock_timeout: u64,
     pub require_biometric: bool,
+    /// Warn when credential notes look like they contain a plaintext secret
+    #[serde(default = "default_true")]
+    pub scan_notes_for_secrets: bool,
+    /// Offline breached-password database used by `audit passwords`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breach_db_path: Option<PathBuf>,
+    /// What to do when the database or workspace is readable by other users
+    #[serde(default)]
+    pub permission_check: PermissionCheck,
+    /// Previous passwords kept per credential when it is rotated (0 keeps none)
+    #[serde(default = "default_password_history")]
+    pub password_history: usize,
+    /// Largest file, in bytes, `credential attach` accepts
+    #[serde(default = "default_max_attachment_size")]
+    pub max_attachment_size: u64,
+}
+
+/// Response to vault files that other users can access
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionCheck {
+    Off,
+    #[default]
+    Warn,
+    Refuse,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_password_history() -> usize {
+    persona_core::DEFAULT_SECRET_HISTORY_RETENTION
+}
+
+fn default_max_attachment_size() -> u64 {
+    persona_core::DEFAULT_MAX_ATTACHMENT_SIZE
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +82,9 @@ pub struct BackupConfig {
     pub auto_backup: bool,
     pub backup_interval: u64,
     pub max_backups: u32,
+    /// Snapshot the vault before deletes, migrations and replacing imports
+    #[serde(default)]
+    pub backup_before_destructive: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +101,29 @@ pub struct UiConfig {
     pub default_output_format: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletConfig {
+    /// Balance endpoints by network name, replacing the public mainnet defaults
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub balance_endpoints: HashMap<String, BalanceEndpoint>,
+    /// Seconds a fetched balance is reused before asking the endpoint again
+    #[serde(default = "default_balance_cache_ttl")]
+    pub balance_cache_ttl: u64,
+}
+
+impl Default for WalletConfig {
+    fn default() -> Self {
+        Self {
+            balance_endpoints: HashMap::new(),
+            balance_cache_ttl: default_balance_cache_ttl(),
+        }
+    }
+}
+
+fn default_balance_cache_ttl() -> u64 {
+    DEFAULT_BALANCE_CACHE_TTL.as_secs()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoggingConfig {
     pub level: String,
@@ -71,6 +146,11 @@ impl Default for CliConfig {
                 encryption_enabled: true,
                 auto_lock_timeout: 300,
                 require_biometric: false,
+                scan_notes_for_secrets: true,
+                breach_db_path: None,
+                permission_check: PermissionCheck::default(),
+                password_history: default_password_history(),
+                max_attachment_size: default_max_attachment_size(),
             },
             backup: BackupConfig {
                 enabled: true,
@@ -78,6 +158,7 @@ impl Default for CliConfig {
                 auto_backup: true,
                 backup_interval: 86400,
                 max_backups: 30,
+                backup_before_destructive: false,
             },
             sync: SyncConfig {
                 enabled: false,
@@ -95,6 +176,9 @@ impl Default for CliConfig {
                 max_file_size: "10MB".to_string(),
                 max_files: 5,
             },
+            credential_types: Vec::new(),
+            wallet: WalletConfig::default(),
+            demo: false,
         }
     }
 }
@@ -183,6 +267,11 @@ impl CliConfig {
                 self.logging.level = level;
             }
         }
+
+        // Breached-password database
+        if let Ok(path) = std::env::var("PERSONA_BREACH_DB") {
+            self.security.breach_db_path = Some(PathBuf::from(path));
+        }
     }
 
     /// Save configuration to file
@@ -268,6 +357,14 @@ impl CliConfig {
         if workspace_config.sync.enabled != self.sync.enabled {
             self.sync = workspace_config.sync;
         }
+
+        if !workspace_config.credential_types.is_empty() {
+            self.credential_types = workspace_config.credential_types;
+        }
+
+        if !workspace_config.wallet.balance_endpoints.is_empty() {
+            self.wallet = workspace_config.wallet;
+        }
     }
 
     /// Validate configuration
@@ -318,6 +415,25 @@ impl CliConfig {
         self.workspace.path.join("identities.db")
     }
 
+    /// Directory holding encrypted credential attachments
+    pub fn get_attachments_path(&self) -> PathBuf {
+        self.workspace.path.join("attachments")
+    }
+
+    /// Get the breached-password database path (configured, or inside the workspace)
+    pub fn get_breach_db_path(&self) -> PathBuf {
+        self.security
+            .breach_db_path
+            .clone()
+            .unwrap_or_else(|| self.workspace.path.join("breach.bloom"))
+    }
+
+    /// Where destructive operations snapshot the vault first, if enabled
+    pub fn destructive_backup_dir(&self) -> Option<PathBuf> {
+        (self.backup.enabled && self.backup.backup_before_destructive)
+            .then(|| self.backup.directory.clone())
+    }
+
     /// Get logs directory
     pub fn get_logs_directory(&self) -> PathBuf {
         self.workspace.path.join("logs")
@@ -344,9 +460,4 @@ impl CliConfig {
     pub fn is_interactive(&self) -> bool {
         self.ui.interactive && atty::is(atty::Stream::Stdin)
     }
-
-    /// Check if color output is enabled
-    pub fn is_color_enabled(&self) -> bool {
-        self.ui.color_enabled && atty::is(atty::Stream::Stdout)
-    }
 }
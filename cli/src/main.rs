@@ -3,18 +3,23 @@ use clap::{Parser, Subcommand};
 use colored::*;
 use std::ffi::OsString;
 use std::path::Path;
+use std::process::ExitCode;
 
 mod commands;
 mod config;
 mod utils;
 
 use config::CliConfig;
-use persona_core::RedactedLoggerBuilder;
+use persona_core::{PersonaError, RedactedLoggerBuilder};
+use utils::exit_code::exit_code_for;
+
+/// Directory under the workspace that holds rotated log files
+const LOG_DIR: &str = "logs";
 
 #[derive(Parser)]
 #[command(name = "persona")]
 #[command(about = "Master your digital identity. Switch freely with one click.")]
-#[command(version = "0.1.0")]
+#[command(version = persona_core::VERSION, long_version = persona_core::LONG_VERSION)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
@@ -26,6 +31,14 @@ struct Cli {
     /// Configuration file path
     #[arg(short, long, global = true)]
     config: Option<std::path::PathBuf>,
+
+    /// Use an in-memory demo vault with fake data (nothing is saved)
+    #[arg(long, global = true)]
+    demo: bool,
+
+    /// Disable colored output (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    no_color: bool,
 }
 
 #[derive(Subcommand)]
@@ -66,12 +79,18 @@ enum Commands {
     /// SSH key operations (developer features)
     Ssh(commands::ssh::SshArgs),
 
+    /// SSH agent runtime status
+    Agent(commands::agent::AgentArgs),
+
     /// Credential management (password/api key/etc.)
     Credential(commands::credential::CredentialArgs),
 
     /// Password generator utilities
     Password(commands::password::PasswordArgs),
 
+    /// Security audits (e.g. offline breached-password check)
+    Audit(commands::audit::AuditArgs),
+
     /// Interactive terminal UI
     Tui(commands::tui::TuiArgs),
 
@@ -83,16 +102,49 @@ enum Commands {
 
     /// Crypto wallet management
     Wallet(commands::wallet::WalletArgs),
+
+    /// Labeled recipient addresses for wallet transactions
+    AddressBook(commands::address_book::AddressBookArgs),
+
+    /// Anti-phishing phrase shown before the master password prompt
+    VerificationPhrase(commands::verification_phrase::VerificationPhraseArgs),
+
+    /// Change the master password and re-encrypt the vault under it
+    Passwd(commands::passwd::PasswdArgs),
+
+    /// Recovery codes for a lost master password
+    Recovery(commands::recovery::RecoveryArgs),
+
+    /// Verified backups of the vault database, and restoring from them
+    Backup(commands::backup::BackupArgs),
+
+    /// Database upkeep: reclaim free space and check integrity
+    Maintenance(commands::maintenance::MaintenanceArgs),
+
+    /// Summarize the workspace, vault, SSH agent, bridge and backup state
+    #[command(alias = "whoami")]
+    Status(commands::status::StatusArgs),
+
+    /// Show version, git revision and enabled features
+    Version(commands::version::VersionArgs),
 }
 
+/// Runs the command and maps failures to the exit codes documented in `utils::exit_code`.
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            ExitCode::from(exit_code_for(&err))
+        }
+    }
+}
+
+async fn run() -> Result<()> {
     let args = maybe_inject_bridge_subcommand(std::env::args_os().collect());
     let cli = Cli::parse_from(args);
 
-    // Initialize logging
-    init_logging(cli.verbose)?;
-
     // Load configuration.
     //
     // Workspace commands are intentionally "local by default": they require a
@@ -100,23 +152,44 @@ async fn main() -> Result<()> {
     // so tests and automation don't accidentally operate on a user's global
     // `~/.persona` workspace.
     let requires_workspace = command_requires_workspace(&cli.command);
-    let config = if requires_workspace {
+    let config = if cli.demo {
+        if !command_supports_demo(&cli.command) {
+            anyhow::bail!("This command is not available in --demo mode");
+        }
+        let mut cfg = CliConfig {
+            demo: true,
+            ..CliConfig::default()
+        };
+        cfg.apply_env_overrides();
+        cfg
+    } else if requires_workspace {
         let config_path = match cli.config.as_deref() {
             Some(p) => p.to_path_buf(),
             None => std::env::current_dir()?.join("config.toml"),
         };
         if !config_path.exists() {
-            anyhow::bail!(
-                "Workspace not initialized in this directory. Run `persona init` first (or pass --config)."
-            );
+            return Err(PersonaError::ConfigurationError(
+                "Workspace not initialized in this directory. Run `persona init` first (or pass --config).".to_string(),
+            )
+            .into());
         }
         let mut cfg = CliConfig::load_file(&config_path)?;
         cfg.apply_env_overrides();
+        utils::check_vault_permissions(&cfg)?;
         cfg
     } else {
         CliConfig::load(cli.config.as_deref())?
     };
 
+    init_logging(cli.verbose, &config)?;
+    utils::output::init_colors(cli.no_color, &config);
+    if config.demo {
+        println!(
+            "{}",
+            "Demo mode: showing synthetic data, changes are not saved.".yellow()
+        );
+    }
+
     // Execute command
     match cli.command {
         Commands::Init(args) => commands::init::execute(args, &config).await,
@@ -131,12 +204,24 @@ async fn main() -> Result<()> {
         Commands::Import(args) => commands::import::execute(args, &config).await,
         Commands::Migrate(args) => commands::migrate::execute(args, &config).await,
         Commands::Ssh(args) => commands::ssh::execute(args, &config).await,
+        Commands::Agent(args) => commands::agent::execute(args).await,
         Commands::Credential(args) => commands::credential::execute(args, &config).await,
         Commands::Password(args) => commands::password::execute(args, &config).await,
+        Commands::Audit(args) => commands::audit::execute(args, &config).await,
         Commands::Tui(args) => commands::tui::execute(args, &config).await,
         Commands::Totp(args) => commands::totp::execute(args, &config).await,
         Commands::AutoLock(args) => commands::auto_lock::handle_auto_lock(args, &config).await,
         Commands::Wallet(args) => commands::wallet::handle_wallet(args, &config).await,
+        Commands::AddressBook(args) => commands::address_book::execute(args, &config).await,
+        Commands::VerificationPhrase(args) => {
+            commands::verification_phrase::execute(args, &config).await
+        }
+        Commands::Passwd(args) => commands::passwd::execute(args, &config).await,
+        Commands::Recovery(args) => commands::recovery::execute(args, &config).await,
+        Commands::Backup(args) => commands::backup::execute(args, &config).await,
+        Commands::Maintenance(args) => commands::maintenance::execute(args, &config).await,
+        Commands::Status(args) => commands::status::execute(args, &config).await,
+        Commands::Version(args) => commands::version::execute(args).await,
     }
 }
 
@@ -167,21 +252,49 @@ fn command_requires_workspace(cmd: &Commands) -> bool {
         Commands::Init(_) => false,
         Commands::Bridge(_) => false,
         Commands::Password(_) => false,
+        Commands::Version(_) => false,
+        Commands::Agent(_) => false,
         _ => true,
     }
 }
 
-/// Initialize logging based on verbosity level
-fn init_logging(verbose: bool) -> Result<()> {
+/// Commands that can run against the in-memory demo vault
+fn command_supports_demo(cmd: &Commands) -> bool {
+    matches!(
+        cmd,
+        Commands::List(_)
+            | Commands::Show(_)
+            | Commands::Credential(_)
+            | Commands::Totp(_)
+            | Commands::Tui(_)
+            | Commands::Password(_)
+    )
+}
+
+/// Initialize logging from `config.logging`; `--verbose` forces debug level.
+///
+/// Log files are written under the workspace's `logs` directory, but only once the workspace
+/// exists, and never in demo mode.
+fn init_logging(verbose: bool, config: &CliConfig) -> Result<()> {
     let level = if verbose {
         tracing::Level::DEBUG
     } else {
-        tracing::Level::INFO
+        config.logging.level.parse().unwrap_or(tracing::Level::INFO)
     };
 
-    RedactedLoggerBuilder::new(level)
-        .include_target(false)
-        .init()?;
+    let mut builder = RedactedLoggerBuilder::new(level).include_target(false);
+    if config.logging.file_enabled && !config.demo && config.workspace.path.is_dir() {
+        builder = builder
+            .with_file_rotation(
+                config.workspace.path.join(LOG_DIR),
+                &config.logging.max_file_size,
+                config.logging.max_files,
+            )
+            .map_err(|e| {
+                PersonaError::ConfigurationError(format!("logging.max_file_size: {}", e))
+            })?;
+    }
+    builder.init()?;
 
     Ok(())
 }
@@ -202,8 +315,9 @@ fn print_banner() {
     );
 
     println!(
-        "{}",
-        "Master your digital identity. Switch freely with one click.".italic()
+        "{}  {}",
+        "Master your digital identity. Switch freely with one click.".italic(),
+        format!("v{}", persona_core::VERSION).dimmed()
     );
     println!();
 }
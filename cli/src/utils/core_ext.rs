@@ -19,9 +19,14 @@ impl<T> CoreResultExt<T> for std::result::Result<T, Box<dyn std::error::Error +
     }
 }
 
-// For PersonaError (assuming it exists)
+// For PersonaError - keep the typed error so `main` can map it to an exit code
 impl<T> CoreResultExt<T> for std::result::Result<T, persona_core::PersonaError> {
     fn into_anyhow(self) -> Result<T> {
-        self.map_err(|e| anyhow!(e.to_string()))
+        self.map_err(anyhow::Error::from)
     }
 }
+
+/// Error for a non-successful `AuthResult`, classified as an authentication failure.
+pub fn auth_failed(result: impl std::fmt::Debug) -> anyhow::Error {
+    persona_core::PersonaError::AuthenticationFailed(format!("{:?}", result)).into()
+}
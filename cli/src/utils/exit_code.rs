@@ -0,0 +1,106 @@
+//! Process exit codes for failed commands.
+//!
+//! Scripts can tell failure classes apart by exit status instead of parsing stderr. The code is
+//! taken from the first [`PersonaError`] or [`std::io::Error`] in the error chain; errors that
+//! carry neither exit with [`GENERAL`]. Exit code 2 is left to clap for command-line usage errors. Keep the table in
+//! `docs/NON_INTERACTIVE_MODE.md` in sync.
+
+use persona_core::PersonaError;
+
+/// Unclassified failure
+pub const GENERAL: u8 = 1;
+/// Wrong master password or failed re-authentication
+pub const AUTH_FAILED: u8 = 3;
+/// Workspace not initialized, its configuration is invalid, or its permissions are refused
+pub const CONFIG: u8 = 4;
+/// Missing or rejected input
+pub const INVALID_INPUT: u8 = 5;
+/// The vault (or the session) is locked, including lockout after failed unlocks
+pub const LOCKED: u8 = 6;
+/// The requested identity, credential or other resource does not exist
+pub const NOT_FOUND: u8 = 7;
+/// The record changed since it was read; reload and retry
+pub const CONFLICT: u8 = 8;
+/// Database or storage failure
+pub const STORAGE: u8 = 9;
+/// Encryption or decryption failure
+pub const CRYPTO: u8 = 10;
+/// Filesystem or other I/O failure
+pub const IO: u8 = 11;
+
+/// Exit code for an error returned by a command
+pub fn exit_code_for(err: &anyhow::Error) -> u8 {
+    err.chain()
+        .find_map(|cause| {
+            if let Some(err) = cause.downcast_ref::<PersonaError>() {
+                Some(persona_error_code(err))
+            } else if cause.is::<std::io::Error>() {
+                Some(IO)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(GENERAL)
+}
+
+fn persona_error_code(err: &PersonaError) -> u8 {
+    match err {
+        PersonaError::AuthenticationFailed(_) => AUTH_FAILED,
+        PersonaError::Locked(_) => LOCKED,
+        PersonaError::IdentityNotFound(_) | PersonaError::NotFound(_) => NOT_FOUND,
+        PersonaError::InvalidInput(_) | PersonaError::Validation(_) => INVALID_INPUT,
+        PersonaError::Conflict(_) => CONFLICT,
+        PersonaError::ConfigurationError(_) => CONFIG,
+        PersonaError::StorageError(_) | PersonaError::Database(_) => STORAGE,
+        PersonaError::CryptographicError(_)
+        | PersonaError::Crypto(_)
+        | PersonaError::Cryptography(_) => CRYPTO,
+        PersonaError::Io(_) => IO,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn maps_each_persona_error_variant() {
+        let cases = [
+            (PersonaError::AuthenticationFailed("x".into()), AUTH_FAILED),
+            (PersonaError::Locked("x".into()), LOCKED),
+            (PersonaError::IdentityNotFound("x".into()), NOT_FOUND),
+            (PersonaError::NotFound("x".into()), NOT_FOUND),
+            (PersonaError::InvalidInput("x".into()), INVALID_INPUT),
+            (PersonaError::Validation("x".into()), INVALID_INPUT),
+            (PersonaError::Conflict("x".into()), CONFLICT),
+            (PersonaError::ConfigurationError("x".into()), CONFIG),
+            (PersonaError::StorageError("x".into()), STORAGE),
+            (PersonaError::Database("x".into()), STORAGE),
+            (PersonaError::CryptographicError("x".into()), CRYPTO),
+            (PersonaError::Crypto("x".into()), CRYPTO),
+            (PersonaError::Cryptography("x".into()), CRYPTO),
+            (PersonaError::Io("x".into()), IO),
+        ];
+        for (err, code) in cases {
+            // Commands usually add context on the way up
+            let wrapped = Err::<(), _>(err)
+                .context("Failed to run command")
+                .unwrap_err();
+            assert_eq!(exit_code_for(&wrapped), code, "{:?}", wrapped);
+        }
+    }
+
+    #[test]
+    fn file_errors_use_the_io_code() {
+        let missing = std::fs::read("/nonexistent/persona-exit-code")
+            .context("Failed to read attachment")
+            .unwrap_err();
+        assert_eq!(exit_code_for(&missing), IO);
+    }
+
+    #[test]
+    fn plain_errors_use_the_general_code() {
+        assert_eq!(exit_code_for(&anyhow::anyhow!("boom")), GENERAL);
+    }
+}
@@ -4,17 +4,73 @@ use aes_gcm::{
 };
 use anyhow::{Context, Result};
 use argon2::{Algorithm, Argon2, Params, Version};
+use clap::ValueEnum;
+use hmac::{Hmac, Mac};
+use persona_core::PersonaError;
 use rand::RngCore;
-use zeroize::Zeroize;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
 
 // Simple file encryption format:
-// [magic:8] "PERSENC1"
-// [salt_len:1][salt][nonce_len:1][nonce][kdf_params:4 bytes little endian mem_kib][enc_len:8][ciphertext...]
-// KDF: Argon2id with provided salt; key length 32 bytes; params memory_kib (default 64MB), iterations=3, parallelism=1
-// Cipher: AES-256-GCM with 12-byte nonce
+// [magic:8] "PERSENC3"
+// [cipher:1][salt_len:1][salt][nonce_len:1][nonce]
+// [kdf_params:12 bytes, little endian mem_kib, iterations, parallelism]
+// [manifest_len:4][manifest JSON][enc_len:8][ciphertext...][hmac:32]
+// KDF: Argon2id with provided salt and the stored params; key length 64 bytes (32 cipher key + 32 MAC key)
+// Cipher: AES-256-GCM (id 1) or ChaCha20-Poly1305 (id 2), both with a 12-byte nonce
+// MAC: HMAC-SHA256 over every byte before it, checked before anything is decrypted
+//
+// "PERSENC2" files have no cipher byte and store only mem_kib; they are AES-256-GCM with
+// iterations=3, parallelism=1. "PERSENC1" files additionally have no manifest/MAC, a 32-byte
+// key and end after the ciphertext.
 
-const MAGIC: &[u8; 8] = b"PERSENC1";
+const MAGIC: &[u8; 8] = b"PERSENC3";
+const V2_MAGIC: &[u8; 8] = b"PERSENC2";
+const LEGACY_MAGIC: &[u8; 8] = b"PERSENC1";
+const MAC_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
 
+/// Argon2 memory bounds in KiB. New exports need at least the minimum; files claiming more
+/// than the maximum are refused rather than allowed to exhaust memory on import.
+const MIN_MEM_KIB: u32 = 8 * 1024;
+const MAX_MEM_KIB: u32 = 4 * 1024 * 1024;
+const MAX_ITERATIONS: u32 = 64;
+const MAX_PARALLELISM: u32 = 16;
+
+/// Current [`ExportManifest`] layout; bump when fields change meaning
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// Describes an encrypted export so the receiver can check it is complete and unmodified
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub schema_version: u32,
+    /// Payload format (json, yaml, csv), used to parse it after decryption
+    pub format: String,
+    pub created: String,
+    pub identities_count: usize,
+    pub credentials_count: usize,
+    /// Hex SHA-256 of the plaintext payload, filled in on encryption
+    #[serde(default)]
+    pub content_sha256: String,
+}
+
+impl ExportManifest {
+    pub fn new(format: &str, identities_count: usize, credentials_count: usize) -> Self {
+        Self {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            format: format.to_string(),
+            created: chrono::Utc::now().to_rfc3339(),
+            identities_count,
+            credentials_count,
+            content_sha256: String::new(),
+        }
+    }
+}
+
+/// Argon2id cost parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct KdfParams {
     pub mem_kib: u32,
     pub iterations: u32,
@@ -23,138 +79,639 @@ pub struct KdfParams {
 
 impl Default for KdfParams {
     fn default() -> Self {
-        Self {
-            mem_kib: 64 * 1024,
-            iterations: 3,
+        KdfCost::Standard.params()
+    }
+}
+
+impl KdfParams {
+    /// Check parameters chosen for a new export
+    pub fn validate(&self) -> Result<()> {
+        if self.mem_kib < MIN_MEM_KIB {
+            return Err(PersonaError::Validation(format!(
+                "KDF memory must be at least {} MiB",
+                MIN_MEM_KIB / 1024
+            ))
+            .into());
+        }
+        if self.iterations == 0 || self.parallelism == 0 {
+            return Err(PersonaError::Validation(
+                "KDF iterations and parallelism must be at least 1".to_string(),
+            )
+            .into());
+        }
+        self.check_limits()
+    }
+
+    /// Upper bounds, also applied to parameters read from a file
+    fn check_limits(&self) -> Result<()> {
+        if self.mem_kib > MAX_MEM_KIB
+            || self.iterations > MAX_ITERATIONS
+            || self.parallelism > MAX_PARALLELISM
+        {
+            return Err(PersonaError::Validation(format!(
+                "KDF parameters exceed the supported maximum ({} MiB, {} iterations, {} lanes)",
+                MAX_MEM_KIB / 1024,
+                MAX_ITERATIONS,
+                MAX_PARALLELISM
+            ))
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Named KDF cost levels for `persona export --kdf-cost`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum KdfCost {
+    /// 64 MiB, 3 passes
+    #[default]
+    Standard,
+    /// 256 MiB, 4 passes; for long-term archives
+    High,
+    /// 1 GiB, 6 passes; slow to open even on fast machines
+    Max,
+}
+
+impl KdfCost {
+    pub fn params(self) -> KdfParams {
+        let (mem_mib, iterations) = match self {
+            KdfCost::Standard => (64, 3),
+            KdfCost::High => (256, 4),
+            KdfCost::Max => (1024, 6),
+        };
+        KdfParams {
+            mem_kib: mem_mib * 1024,
+            iterations,
             parallelism: 1,
         }
     }
 }
 
+/// AEAD protecting the export payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ExportCipher {
+    #[default]
+    #[value(name = "aes-256-gcm")]
+    Aes256Gcm,
+    #[value(name = "chacha20-poly1305")]
+    ChaCha20Poly1305,
+}
+
+impl ExportCipher {
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportCipher::Aes256Gcm => "aes-256-gcm",
+            ExportCipher::ChaCha20Poly1305 => "chacha20-poly1305",
+        }
+    }
+
+    fn id(self) -> u8 {
+        match self {
+            ExportCipher::Aes256Gcm => 1,
+            ExportCipher::ChaCha20Poly1305 => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self> {
+        match id {
+            1 => Ok(ExportCipher::Aes256Gcm),
+            2 => Ok(ExportCipher::ChaCha20Poly1305),
+            _ => Err(PersonaError::Validation(format!(
+                "Export uses an unknown cipher (id {}). Upgrade persona to import it",
+                id
+            ))
+            .into()),
+        }
+    }
+
+    fn encrypt(self, key: &[u8], nonce: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            ExportCipher::Aes256Gcm => Aes256Gcm::new(key.into())
+                .encrypt(nonce.into(), plaintext)
+                .map_err(|e| anyhow::anyhow!("Encryption failed: {:?}", e)),
+            ExportCipher::ChaCha20Poly1305 => {
+                let mut in_out = plaintext.to_vec();
+                chacha_key(key)?
+                    .seal_in_place_append_tag(chacha_nonce(nonce)?, Aad::empty(), &mut in_out)
+                    .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+                Ok(in_out)
+            }
+        }
+    }
+
+    fn decrypt(self, key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        match self {
+            ExportCipher::Aes256Gcm => Aes256Gcm::new(key.into())
+                .decrypt(nonce.into(), ciphertext)
+                .map(Zeroizing::new)
+                .map_err(|e| anyhow::anyhow!("Decryption failed: {:?}", e)),
+            ExportCipher::ChaCha20Poly1305 => {
+                let mut in_out = Zeroizing::new(ciphertext.to_vec());
+                let len = chacha_key(key)?
+                    .open_in_place(chacha_nonce(nonce)?, Aad::empty(), &mut in_out)
+                    .map_err(|_| anyhow::anyhow!("Decryption failed"))?
+                    .len();
+                in_out.truncate(len);
+                Ok(in_out)
+            }
+        }
+    }
+}
+
+/// Cipher and KDF cost of an encrypted export, stored in its header
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EncryptionParams {
+    pub kdf: KdfParams,
+    pub cipher: ExportCipher,
+}
+
+impl std::fmt::Display for EncryptionParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, Argon2id {} MiB × {}",
+            self.cipher.name(),
+            self.kdf.mem_kib / 1024,
+            self.kdf.iterations
+        )
+    }
+}
+
 pub fn encrypt_file_inplace(
     path: &std::path::Path,
     passphrase: &str,
-    kdf: Option<KdfParams>,
+    manifest: ExportManifest,
+    params: EncryptionParams,
 ) -> Result<()> {
-    let kdf_params = kdf.unwrap_or_default();
     let plaintext =
         std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let out = encrypt_bytes(&plaintext, passphrase, manifest, params)?;
+    std::fs::write(path, out).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Seal `plaintext` in memory, so callers never have to write it to disk
+pub fn encrypt_bytes(
+    plaintext: &[u8],
+    passphrase: &str,
+    mut manifest: ExportManifest,
+    params: EncryptionParams,
+) -> Result<Vec<u8>> {
+    params.kdf.check_limits()?;
+    manifest.content_sha256 = hex::encode(Sha256::digest(plaintext));
+    let manifest_json = serde_json::to_vec(&manifest)?;
 
     // Generate salt and nonce
     let mut salt = [0u8; 16];
     rand::thread_rng().fill_bytes(&mut salt);
-    let mut nonce = [0u8; 12];
+    let mut nonce = [0u8; NONCE_LEN];
     rand::thread_rng().fill_bytes(&mut nonce);
 
-    // Derive key
-    let argon = Argon2::new_with_secret(
-        &[],
-        Algorithm::Argon2id,
-        Version::V0x13,
-        Params::new(
-            kdf_params.mem_kib,
-            kdf_params.iterations,
-            kdf_params.parallelism,
-            Some(32),
-        )
-        .map_err(|e| anyhow::anyhow!("Argon2 params error: {:?}", e))?,
-    )
-    .map_err(|e| anyhow::anyhow!("Argon2 init error: {:?}", e))?;
-    let mut key = [0u8; 32];
-    argon
-        .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
-        .map_err(|e| anyhow::anyhow!("Argon2 derive error: {:?}", e))?;
-
-    let cipher = Aes256Gcm::new((&key).into());
-    let ciphertext = cipher
-        .encrypt((&nonce).into(), plaintext.as_ref())
-        .map_err(|e| anyhow::anyhow!("Encryption failed: {:?}", e))?;
+    // Derive cipher and MAC keys
+    let keys = derive_keys(passphrase, &salt, &params.kdf, 64)?;
+    let (key, mac_key) = keys.split_at(32);
+    let ciphertext = params.cipher.encrypt(key, &nonce, plaintext)?;
 
     // Build output
     let mut out: Vec<u8> = Vec::new();
     out.extend_from_slice(MAGIC);
+    out.push(params.cipher.id());
     out.push(salt.len() as u8);
     out.extend_from_slice(&salt);
     out.push(nonce.len() as u8);
     out.extend_from_slice(&nonce);
-    out.extend_from_slice(&kdf_params.mem_kib.to_le_bytes());
+    out.extend_from_slice(&params.kdf.mem_kib.to_le_bytes());
+    out.extend_from_slice(&params.kdf.iterations.to_le_bytes());
+    out.extend_from_slice(&params.kdf.parallelism.to_le_bytes());
+    out.extend_from_slice(&(manifest_json.len() as u32).to_le_bytes());
+    out.extend_from_slice(&manifest_json);
     out.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
     out.extend_from_slice(&ciphertext);
+    let tag = export_mac(mac_key, &out)?.finalize().into_bytes();
+    out.extend_from_slice(&tag);
+    Ok(out)
+}
 
-    // Zeroize key
-    key.zeroize();
+/// Header of an encrypted export, readable without the passphrase
+#[derive(Debug)]
+pub struct EncryptedHeader {
+    /// `None` for legacy files. Not authenticated until the file is decrypted, so only good
+    /// for early compatibility checks.
+    pub manifest: Option<ExportManifest>,
+}
 
-    // Write back
-    std::fs::write(path, out).with_context(|| format!("Failed to write {}", path.display()))?;
-    Ok(())
+/// A decrypted export, held in memory
+pub struct DecryptedExport {
+    pub plaintext: Zeroizing<Vec<u8>>,
+    /// `None` for legacy files written before manifests existed
+    pub manifest: Option<ExportManifest>,
+    pub params: EncryptionParams,
 }
 
-pub fn decrypt_file_to_temp(
-    path: &std::path::Path,
-    passphrase: &str,
-) -> Result<std::path::PathBuf> {
-    let data = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
-    let mut cursor = 0usize;
-    if data.len() < MAGIC.len() || &data[..MAGIC.len()] != MAGIC {
+/// Byte ranges of an encrypted export
+struct Layout<'a> {
+    legacy: bool,
+    params: EncryptionParams,
+    salt: &'a [u8],
+    nonce: &'a [u8],
+    manifest_json: Option<&'a [u8]>,
+    ciphertext: &'a [u8],
+    /// MAC and the bytes it covers (manifest-bearing files only)
+    tag: Option<(&'a [u8], &'a [u8])>,
+}
+
+/// Whether `data` starts like a Persona encrypted file
+pub fn is_encrypted(data: &[u8]) -> bool {
+    [MAGIC, V2_MAGIC, LEGACY_MAGIC]
+        .iter()
+        .any(|magic| data.starts_with(*magic))
+}
+
+/// Check the file structure and manifest version without deriving any keys.
+///
+/// Catches truncated files and exports from newer builds before the user is asked for a
+/// passphrase.
+pub fn inspect_encrypted(data: &[u8]) -> Result<EncryptedHeader> {
+    let layout = parse_layout(data)?;
+    let manifest = layout
+        .manifest_json
+        .map(|json| serde_json::from_slice(json).map_err(|_| integrity_error()))
+        .transpose()?;
+    if let Some(manifest) = &manifest {
+        check_manifest_version(manifest)?;
+    }
+    Ok(EncryptedHeader { manifest })
+}
+
+/// Decrypt an export in memory, with the cipher and KDF cost recorded in its header.
+///
+/// For manifest-bearing files the MAC is verified before decrypting and the payload hash after;
+/// any mismatch or truncation fails with `PersonaError::Validation`.
+pub fn decrypt_bytes(data: &[u8], passphrase: &str) -> Result<DecryptedExport> {
+    let layout = parse_layout(data)?;
+
+    // Derive key(s). The MAC key comes from the header's KDF params, so a modified header
+    // fails the MAC check below.
+    let keys = derive_keys(
+        passphrase,
+        layout.salt,
+        &layout.params.kdf,
+        if layout.legacy { 32 } else { 64 },
+    )?;
+    let (key, mac_key) = keys.split_at(32);
+
+    let manifest = match (layout.manifest_json, layout.tag) {
+        (Some(manifest_json), Some((tag, authenticated))) => {
+            export_mac(mac_key, authenticated)?
+                .verify_slice(tag)
+                .map_err(|_| integrity_error())?;
+            let manifest: ExportManifest =
+                serde_json::from_slice(manifest_json).map_err(|_| integrity_error())?;
+            check_manifest_version(&manifest)?;
+            Some(manifest)
+        }
+        _ => None,
+    };
+
+    let plaintext = layout
+        .params
+        .cipher
+        .decrypt(key, layout.nonce, layout.ciphertext)?;
+
+    if let Some(manifest) = &manifest {
+        if hex::encode(Sha256::digest(&*plaintext)) != manifest.content_sha256 {
+            return Err(integrity_error());
+        }
+    }
+
+    Ok(DecryptedExport {
+        plaintext,
+        manifest,
+        params: layout.params,
+    })
+}
+
+fn parse_layout(data: &[u8]) -> Result<Layout<'_>> {
+    let (legacy, full_params) = if data.starts_with(MAGIC) {
+        (false, true)
+    } else if data.starts_with(V2_MAGIC) {
+        (false, false)
+    } else if data.starts_with(LEGACY_MAGIC) {
+        (true, false)
+    } else {
         anyhow::bail!("Not a Persona encrypted file");
+    };
+
+    let mut cursor = MAGIC.len();
+    let cipher = if full_params {
+        ExportCipher::from_id(take(data, &mut cursor, 1)?[0])?
+    } else {
+        ExportCipher::Aes256Gcm
+    };
+    let salt_len = take(data, &mut cursor, 1)?[0] as usize;
+    let salt = take(data, &mut cursor, salt_len)?;
+    let nonce_len = take(data, &mut cursor, 1)?[0] as usize;
+    if nonce_len != NONCE_LEN {
+        return Err(integrity_error());
+    }
+    let nonce = take(data, &mut cursor, nonce_len)?;
+    let mut kdf = KdfParams {
+        mem_kib: read_u32(data, &mut cursor)?,
+        // Older formats only stored memory cost; the rest were fixed
+        iterations: 3,
+        parallelism: 1,
+    };
+    if full_params {
+        kdf.iterations = read_u32(data, &mut cursor)?;
+        kdf.parallelism = read_u32(data, &mut cursor)?;
+    }
+    kdf.check_limits()?;
+    let manifest_json = if legacy {
+        None
+    } else {
+        let len = read_u32(data, &mut cursor)? as usize;
+        Some(take(data, &mut cursor, len)?)
+    };
+    let enc_len = u64::from_le_bytes(take(data, &mut cursor, 8)?.try_into()?) as usize;
+    let ciphertext = take(data, &mut cursor, enc_len)?;
+
+    let tag = if legacy {
+        None
+    } else {
+        let authenticated = &data[..cursor];
+        let tag = take(data, &mut cursor, MAC_LEN)?;
+        if cursor != data.len() {
+            return Err(integrity_error());
+        }
+        Some((tag, authenticated))
+    };
+
+    Ok(Layout {
+        legacy,
+        params: EncryptionParams { kdf, cipher },
+        salt,
+        nonce,
+        manifest_json,
+        ciphertext,
+        tag,
+    })
+}
+
+fn check_manifest_version(manifest: &ExportManifest) -> Result<()> {
+    if manifest.schema_version > MANIFEST_SCHEMA_VERSION {
+        return Err(PersonaError::Validation(format!(
+            "Export manifest version {} is newer than this build supports. Upgrade persona to import it",
+            manifest.schema_version
+        ))
+        .into());
     }
-    cursor += MAGIC.len();
-    let salt_len = *data
-        .get(cursor)
-        .ok_or_else(|| anyhow::anyhow!("Bad header"))? as usize;
-    cursor += 1;
-    let salt = data
-        .get(cursor..cursor + salt_len)
-        .ok_or_else(|| anyhow::anyhow!("Bad header salt"))?;
-    cursor += salt_len;
-    let nonce_len = *data
-        .get(cursor)
-        .ok_or_else(|| anyhow::anyhow!("Bad header"))? as usize;
-    cursor += 1;
-    let nonce = data
-        .get(cursor..cursor + nonce_len)
-        .ok_or_else(|| anyhow::anyhow!("Bad header nonce"))?;
-    cursor += nonce_len;
-    let mut mem_kib_bytes = [0u8; 4];
-    mem_kib_bytes.copy_from_slice(
-        data.get(cursor..cursor + 4)
-            .ok_or_else(|| anyhow::anyhow!("Bad header kdf"))?,
-    );
-    cursor += 4;
-    let mut enc_len_bytes = [0u8; 8];
-    enc_len_bytes.copy_from_slice(
-        data.get(cursor..cursor + 8)
-            .ok_or_else(|| anyhow::anyhow!("Bad header len"))?,
-    );
-    cursor += 8;
-    let enc_len = u64::from_le_bytes(enc_len_bytes) as usize;
-    let ciphertext = data
-        .get(cursor..cursor + enc_len)
-        .ok_or_else(|| anyhow::anyhow!("Bad ciphertext len"))?;
-
-    // Derive key
+    Ok(())
+}
+
+/// Argon2id output of `len` bytes, wiped on drop
+fn derive_keys(
+    passphrase: &str,
+    salt: &[u8],
+    kdf: &KdfParams,
+    len: usize,
+) -> Result<Zeroizing<Vec<u8>>> {
     let argon = Argon2::new_with_secret(
         &[],
         Algorithm::Argon2id,
         Version::V0x13,
-        Params::new(u32::from_le_bytes(mem_kib_bytes), 3, 1, Some(32))
+        Params::new(kdf.mem_kib, kdf.iterations, kdf.parallelism, Some(len))
             .map_err(|e| anyhow::anyhow!("Argon2 params error: {:?}", e))?,
     )
     .map_err(|e| anyhow::anyhow!("Argon2 init error: {:?}", e))?;
-    let mut key = [0u8; 32];
+    let mut keys = Zeroizing::new(vec![0u8; len]);
     argon
-        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut keys)
         .map_err(|e| anyhow::anyhow!("Argon2 derive error: {:?}", e))?;
+    Ok(keys)
+}
+
+fn chacha_key(key: &[u8]) -> Result<LessSafeKey> {
+    let key = UnboundKey::new(&CHACHA20_POLY1305, key)
+        .map_err(|_| anyhow::anyhow!("Invalid ChaCha20-Poly1305 key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn chacha_nonce(nonce: &[u8]) -> Result<Nonce> {
+    Nonce::try_assume_unique_for_key(nonce).map_err(|_| integrity_error())
+}
+
+fn export_mac(mac_key: &[u8], authenticated: &[u8]) -> Result<Hmac<Sha256>> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(mac_key)
+        .map_err(|e| anyhow::anyhow!("Invalid MAC key: {}", e))?;
+    mac.update(authenticated);
+    Ok(mac)
+}
+
+fn read_u32(data: &[u8], cursor: &mut usize) -> Result<u32> {
+    Ok(u32::from_le_bytes(take(data, cursor, 4)?.try_into()?))
+}
+
+/// Next `len` bytes of the file; running out means the file was cut short
+fn take<'a>(data: &'a [u8], cursor: &mut usize, len: usize) -> Result<&'a [u8]> {
+    let bytes = cursor
+        .checked_add(len)
+        .and_then(|end| data.get(*cursor..end))
+        .ok_or_else(integrity_error)?;
+    *cursor += len;
+    Ok(bytes)
+}
 
-    let cipher = Aes256Gcm::new((&key).into());
-    let plaintext = cipher
-        .decrypt(nonce.into(), ciphertext.as_ref())
-        .map_err(|e| anyhow::anyhow!("Decryption failed: {:?}", e))?;
-    key.zeroize();
-
-    // Write to temp file
-    let mut out_path = path.to_path_buf();
-    out_path.set_extension("decrypted.tmp");
-    std::fs::write(&out_path, plaintext).with_context(|| "Failed to write decrypted temp file")?;
-    Ok(out_path)
+fn integrity_error() -> anyhow::Error {
+    PersonaError::Validation(
+        "Export failed its integrity check (wrong passphrase, or the file was truncated or modified)"
+            .to_string(),
+    )
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fast_kdf() -> EncryptionParams {
+        EncryptionParams {
+            kdf: KdfParams {
+                mem_kib: 1024,
+                iterations: 3,
+                parallelism: 1,
+            },
+            cipher: ExportCipher::Aes256Gcm,
+        }
+    }
+
+    fn encrypted(params: EncryptionParams) -> Vec<u8> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.json");
+        std::fs::write(&path, br#"{"identities":[]}"#).unwrap();
+        encrypt_file_inplace(&path, "pass", ExportManifest::new("json", 0, 0), params).unwrap();
+        std::fs::read(&path).unwrap()
+    }
+
+    fn assert_validation_error(result: Result<DecryptedExport>) {
+        let err = result.err().expect("decryption should fail");
+        assert!(
+            matches!(
+                err.downcast_ref::<PersonaError>(),
+                Some(PersonaError::Validation(_))
+            ),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn round_trips_with_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.json");
+        std::fs::write(&path, br#"{"identities":[]}"#).unwrap();
+
+        encrypt_file_inplace(&path, "pass", ExportManifest::new("json", 2, 5), fast_kdf()).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        let header = inspect_encrypted(&data).unwrap();
+        assert_eq!(header.manifest.unwrap().format, "json");
+
+        let decrypted = decrypt_bytes(&data, "pass").unwrap();
+        let manifest = decrypted.manifest.unwrap();
+        assert_eq!(manifest.identities_count, 2);
+        assert_eq!(manifest.credentials_count, 5);
+        assert_eq!(&decrypted.plaintext[..], br#"{"identities":[]}"#);
+    }
+
+    #[test]
+    fn flipped_or_truncated_bytes_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.json");
+        std::fs::write(&path, br#"{"identities":[]}"#).unwrap();
+        encrypt_file_inplace(&path, "pass", ExportManifest::new("json", 0, 0), fast_kdf()).unwrap();
+        let original = std::fs::read(&path).unwrap();
+
+        let salt_byte = MAGIC.len() + 2;
+        let manifest_byte = original.windows(7).position(|w| w == b"\"format").unwrap();
+        let ciphertext_byte = original.len() - MAC_LEN - 1;
+        for index in [
+            salt_byte,
+            manifest_byte,
+            ciphertext_byte,
+            original.len() - 1,
+        ] {
+            let mut tampered = original.clone();
+            tampered[index] ^= 0x01;
+            let err = decrypt_bytes(&tampered, "pass").err().unwrap();
+            assert!(
+                matches!(
+                    err.downcast_ref::<PersonaError>(),
+                    Some(PersonaError::Validation(_))
+                ),
+                "byte {}: {}",
+                index,
+                err
+            );
+        }
+
+        let truncated = &original[..original.len() - 10];
+        for err in [
+            inspect_encrypted(truncated).err().unwrap(),
+            decrypt_bytes(truncated, "pass").err().unwrap(),
+        ] {
+            assert!(matches!(
+                err.downcast_ref::<PersonaError>(),
+                Some(PersonaError::Validation(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn round_trips_across_parameter_sets() {
+        for cipher in [ExportCipher::Aes256Gcm, ExportCipher::ChaCha20Poly1305] {
+            for (mem_kib, iterations, parallelism) in [(1024, 1, 1), (2048, 2, 2), (1024, 5, 1)] {
+                let params = EncryptionParams {
+                    kdf: KdfParams {
+                        mem_kib,
+                        iterations,
+                        parallelism,
+                    },
+                    cipher,
+                };
+                let data = encrypted(params);
+
+                let decrypted = decrypt_bytes(&data, "pass").unwrap();
+                assert_eq!(decrypted.params, params);
+                assert_eq!(&decrypted.plaintext[..], br#"{"identities":[]}"#);
+            }
+        }
+    }
+
+    #[test]
+    fn header_params_drive_decryption() {
+        let params = EncryptionParams {
+            kdf: KdfParams {
+                mem_kib: 2048,
+                iterations: 2,
+                parallelism: 1,
+            },
+            cipher: ExportCipher::ChaCha20Poly1305,
+        };
+        let original = encrypted(params);
+        decrypt_bytes(&original, "pass").unwrap();
+
+        // Claiming the other cipher, or a different KDF cost, derives the wrong keys
+        let mut other_cipher = original.clone();
+        other_cipher[MAGIC.len()] = ExportCipher::Aes256Gcm.id();
+        assert_validation_error(decrypt_bytes(&other_cipher, "pass"));
+
+        let iterations_at = MAGIC.len() + 1 + 1 + 16 + 1 + NONCE_LEN + 4;
+        let mut other_cost = original.clone();
+        other_cost[iterations_at..iterations_at + 4].copy_from_slice(&3u32.to_le_bytes());
+        assert_validation_error(decrypt_bytes(&other_cost, "pass"));
+
+        let mut unknown_cipher = original.clone();
+        unknown_cipher[MAGIC.len()] = 0xff;
+        assert_validation_error(decrypt_bytes(&unknown_cipher, "pass"));
+
+        // A header demanding absurd memory is refused before any key derivation
+        let memory_at = iterations_at - 4;
+        let mut greedy = original;
+        greedy[memory_at..memory_at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(inspect_encrypted(&greedy).is_err());
+        assert_validation_error(decrypt_bytes(&greedy, "pass"));
+    }
+
+    #[test]
+    fn validate_rejects_unsafe_or_excessive_params() {
+        for cost in [KdfCost::Standard, KdfCost::High, KdfCost::Max] {
+            cost.params().validate().unwrap();
+        }
+        assert_eq!(KdfParams::default(), KdfCost::Standard.params());
+
+        let standard = KdfCost::Standard.params();
+        for params in [
+            KdfParams {
+                mem_kib: 1024,
+                ..standard
+            },
+            KdfParams {
+                iterations: 0,
+                ..standard
+            },
+            KdfParams {
+                iterations: MAX_ITERATIONS + 1,
+                ..standard
+            },
+            KdfParams {
+                mem_kib: MAX_MEM_KIB + 1,
+                ..standard
+            },
+            KdfParams {
+                parallelism: 0,
+                ..standard
+            },
+        ] {
+            assert!(params.validate().is_err(), "{:?}", params);
+        }
+    }
 }
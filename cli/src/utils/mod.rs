@@ -1,10 +1,21 @@
 use anyhow::{Context, Result};
+use persona_core::{
+    storage::{PermissionIssue, VaultLock},
+    Database, PersonaError, VaultEvent,
+};
 use std::path::Path;
+
+use crate::config::{CliConfig, PermissionCheck};
 use tracing::{debug, warn};
 
 pub mod core_ext;
+pub mod exit_code;
 pub mod file_crypto;
+pub mod output;
+pub mod password_manager;
 pub mod progress;
+pub mod ssh_config;
+pub mod vault_archive;
 /// Create directory if it doesn't exist
 pub fn create_directory<P: AsRef<Path>>(path: P) -> Result<()> {
     let path = path.as_ref();
@@ -217,6 +228,98 @@ pub fn confirm_action(message: &str, default: bool) -> Result<bool> {
         .interact()?)
 }
 
+/// Report a safety backup written before a destructive operation
+pub fn print_safety_backup(path: &Path) {
+    use colored::Colorize;
+    println!("💾 Backup created: {}", path.display().to_string().cyan());
+}
+
+/// Report safety backups the service wrote while `events` was subscribed
+pub fn report_safety_backups(events: &mut tokio::sync::broadcast::Receiver<VaultEvent>) {
+    while let Ok(event) = events.try_recv() {
+        if let VaultEvent::BackupCreated { path } = event {
+            print_safety_backup(&path);
+        }
+    }
+}
+
+/// Workspace paths (database, workspace directory) other users can access
+pub fn vault_permission_issues(config: &CliConfig) -> Vec<PermissionIssue> {
+    let database = config.get_database_path();
+    persona_core::storage::check_private(&[&config.workspace.path, &database])
+}
+
+/// Warn about, or refuse to open, a vault other users can read (`security.permission_check`)
+pub fn check_vault_permissions(config: &CliConfig) -> Result<()> {
+    if config.security.permission_check == PermissionCheck::Off {
+        return Ok(());
+    }
+    let issues = vault_permission_issues(config);
+    if issues.is_empty() {
+        return Ok(());
+    }
+
+    if config.security.permission_check == PermissionCheck::Refuse {
+        let details: Vec<String> = issues
+            .iter()
+            .map(|issue| format!("{} (fix with `{}`)", issue, issue.fix_command()))
+            .collect();
+        return Err(PersonaError::ConfigurationError(format!(
+            "Refusing to open the vault: {}. Set security.permission_check = \"warn\" to continue anyway.",
+            details.join("; ")
+        ))
+        .into());
+    }
+    use colored::Colorize;
+    for issue in &issues {
+        eprintln!(
+            "{} {} (fix with `{}`)",
+            "⚠️".yellow(),
+            issue,
+            issue.fix_command()
+        );
+    }
+    Ok(())
+}
+
+/// Hold the workspace lock for an exclusive operation until the guard is dropped.
+///
+/// Fails with a "vault is in use by PID X" conflict while another process holds it.
+pub fn lock_vault(config: &CliConfig, operation: &str) -> Result<VaultLock> {
+    VaultLock::acquire(&config.workspace.path, operation)
+}
+
+/// Open the vault database, asking for the master password first when the file is encrypted
+/// at rest.
+///
+/// The password is remembered, so unlocking the service afterwards does not ask again.
+pub async fn open_database<P: AsRef<Path>>(path: P) -> persona_core::Result<Database> {
+    let path = path.as_ref();
+    if !Database::is_encrypted_file(path)? {
+        return Database::from_file(path).await;
+    }
+    open_encrypted_database(path).await
+}
+
+#[cfg(feature = "sqlcipher")]
+async fn open_encrypted_database(path: &Path) -> persona_core::Result<Database> {
+    use crate::commands::auth;
+
+    let password = auth::read_master_password("Enter master password to decrypt the vault")?;
+    let db = Database::from_file_encrypted(path, &password).await?;
+    auth::remember_vault_passphrase(&password);
+    Ok(db)
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+async fn open_encrypted_database(path: &Path) -> persona_core::Result<Database> {
+    Err(PersonaError::ConfigurationError(format!(
+        "{} is encrypted at rest, but this build has no SQLCipher support; rebuild with `--features sqlcipher`",
+        path.display()
+    ))
+    .into())
+}
+
 /// File system utilities
 pub mod fs {
     use super::*;
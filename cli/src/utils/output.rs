@@ -0,0 +1,67 @@
+//! Process-wide control of terminal styling.
+//!
+//! Commands style text through `colored` (and `console`, which drives progress bars and
+//! prompts). Both read a global switch, so color is decided once here at startup instead of at
+//! every `.cyan()` call site.
+
+use crate::config::CliConfig;
+
+/// Decide whether output may be styled and apply that choice to every styling backend.
+///
+/// Color is off when `--no-color` is passed, `NO_COLOR` is set (<https://no-color.org>), the
+/// config disables it (`ui.color_enabled`, `PERSONA_NO_COLOR`), or stdout is not an interactive
+/// terminal. `CLICOLOR_FORCE` keeps color on for non-terminals unless one of the others disables it.
+pub fn init_colors(no_color_flag: bool, config: &CliConfig) {
+    let enabled = color_enabled(
+        no_color_flag,
+        config.ui.color_enabled,
+        env_flag("NO_COLOR"),
+        env_flag("CLICOLOR_FORCE"),
+        super::is_interactive_terminal(),
+    );
+    set_colors(enabled);
+}
+
+/// Turn styling on or off for `colored` and `console` alike.
+pub fn set_colors(enabled: bool) {
+    colored::control::set_override(enabled);
+    console::set_colors_enabled(enabled);
+    console::set_colors_enabled_stderr(enabled);
+}
+
+fn color_enabled(
+    no_color_flag: bool,
+    config_enabled: bool,
+    no_color_env: bool,
+    force_env: bool,
+    interactive: bool,
+) -> bool {
+    if no_color_flag || no_color_env || !config_enabled {
+        return false;
+    }
+    force_env || interactive
+}
+
+/// Whether an environment variable is set to something other than empty or `0`
+fn env_flag(name: &str) -> bool {
+    std::env::var_os(name).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_opt_out_wins_over_force() {
+        assert!(!color_enabled(true, true, false, true, true));
+        assert!(!color_enabled(false, true, true, true, true));
+        assert!(!color_enabled(false, false, false, true, true));
+    }
+
+    #[test]
+    fn non_terminals_are_plain_unless_forced() {
+        assert!(color_enabled(false, true, false, false, true));
+        assert!(!color_enabled(false, true, false, false, false));
+        assert!(color_enabled(false, true, false, true, false));
+    }
+}
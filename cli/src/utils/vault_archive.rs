@@ -0,0 +1,307 @@
+//! Portable, passphrase-sealed vault archives (`persona export --format vault`).
+//!
+//! An archive bundles identities, credentials with their attachments, and wallets so a vault
+//! can move between machines. It is a gzip-compressed JSON [`VaultArchive`] sealed in the same
+//! container as encrypted exports (see [`file_crypto`](super::file_crypto)) with manifest
+//! format `vault`: the HMAC over the whole file is checked before anything is decrypted, and
+//! the passphrase is independent of either vault's master password.
+//!
+//! Credential secrets are decrypted only in memory and re-encrypted under the destination
+//! vault's master key on import. Wallet keys stay encrypted under their own wallet passwords.
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use persona_core::{
+    models::{Credential, CryptoWallet, Identity},
+    PersonaError,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use zeroize::Zeroizing;
+
+use super::file_crypto::{
+    decrypt_bytes, encrypt_bytes, inspect_encrypted, is_encrypted, EncryptionParams,
+    ExportManifest,
+};
+
+/// Manifest format marking a vault archive
+pub const VAULT_FORMAT: &str = "vault";
+
+/// File extension written by `persona export --format vault`
+pub const VAULT_ARCHIVE_EXTENSION: &str = "personavault";
+
+/// Current [`VaultArchive`] layout; archives with a higher version are refused
+pub const VAULT_ARCHIVE_VERSION: u32 = 1;
+
+/// Everything a vault archive carries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultArchive {
+    pub version: u32,
+    pub created: String,
+    pub identities: Vec<ArchivedIdentity>,
+    #[serde(default)]
+    pub wallets: Vec<CryptoWallet>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedIdentity {
+    pub identity: Identity,
+    pub credentials: Vec<ArchivedCredential>,
+}
+
+/// A credential's metadata and its canonical data, decrypted from the source vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedCredential {
+    /// `encrypted_data` and `wrapped_item_key` are cleared; they only open in the source vault
+    pub credential: Credential,
+    pub data: serde_json::Value,
+    #[serde(default)]
+    pub attachments: Vec<ArchivedAttachment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedAttachment {
+    pub filename: String,
+    /// Base64 file content
+    pub content: String,
+}
+
+impl ArchivedAttachment {
+    pub fn new(filename: String, content: &[u8]) -> Self {
+        Self {
+            filename,
+            content: BASE64.encode(content),
+        }
+    }
+
+    pub fn content(&self) -> Result<Vec<u8>> {
+        BASE64
+            .decode(&self.content)
+            .with_context(|| format!("Attachment '{}' is not valid base64", self.filename))
+    }
+}
+
+impl VaultArchive {
+    pub fn new() -> Self {
+        Self {
+            version: VAULT_ARCHIVE_VERSION,
+            created: chrono::Utc::now().to_rfc3339(),
+            identities: Vec::new(),
+            wallets: Vec::new(),
+        }
+    }
+
+    pub fn credentials_count(&self) -> usize {
+        self.identities.iter().map(|i| i.credentials.len()).sum()
+    }
+
+    pub fn attachments_count(&self) -> usize {
+        self.identities
+            .iter()
+            .flat_map(|i| &i.credentials)
+            .map(|c| c.attachments.len())
+            .sum()
+    }
+
+    /// Compress and encrypt the archive under `passphrase`
+    pub fn seal(&self, passphrase: &str, params: EncryptionParams) -> Result<Vec<u8>> {
+        let json = Zeroizing::new(serde_json::to_vec(self)?);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&json)?;
+        let compressed = Zeroizing::new(encoder.finish()?);
+        let manifest = ExportManifest::new(
+            VAULT_FORMAT,
+            self.identities.len(),
+            self.credentials_count(),
+        );
+        encrypt_bytes(&compressed, passphrase, manifest, params)
+    }
+
+    /// Verify and decrypt an archive.
+    ///
+    /// Structure and manifest version are checked before `passphrase` is asked for; the MAC is
+    /// verified before decryption, and the archive version and counts after.
+    pub fn open(data: &[u8], passphrase: impl FnOnce() -> Result<String>) -> Result<Self> {
+        if !is_encrypted(data) {
+            return Err(not_an_archive());
+        }
+        match inspect_encrypted(data)?.manifest {
+            Some(manifest) if manifest.format == VAULT_FORMAT => {}
+            _ => return Err(not_an_archive()),
+        }
+        let decrypted = decrypt_bytes(data, &passphrase()?)?;
+        let manifest = decrypted.manifest.ok_or_else(not_an_archive)?;
+
+        let mut json = Zeroizing::new(Vec::new());
+        GzDecoder::new(&decrypted.plaintext[..])
+            .read_to_end(&mut json)
+            .map_err(|_| corrupt_archive())?;
+        let value: serde_json::Value =
+            serde_json::from_slice(&json).map_err(|_| corrupt_archive())?;
+        check_archive_version(&value)?;
+        let archive: VaultArchive = serde_json::from_value(value).map_err(|_| corrupt_archive())?;
+
+        if archive.identities.len() != manifest.identities_count
+            || archive.credentials_count() != manifest.credentials_count
+        {
+            return Err(corrupt_archive());
+        }
+        Ok(archive)
+    }
+}
+
+impl Default for VaultArchive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `data` is a sealed vault archive, judged from its unauthenticated header
+pub fn is_vault_archive(data: &[u8]) -> bool {
+    is_encrypted(data)
+        && inspect_encrypted(data)
+            .ok()
+            .and_then(|header| header.manifest)
+            .is_some_and(|manifest| manifest.format == VAULT_FORMAT)
+}
+
+fn check_archive_version(value: &serde_json::Value) -> Result<()> {
+    let version = value
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .ok_or_else(corrupt_archive)?;
+    if version > VAULT_ARCHIVE_VERSION as u64 {
+        return Err(PersonaError::Validation(format!(
+            "Vault archive version {} is newer than this build supports ({}). Upgrade persona to import it",
+            version, VAULT_ARCHIVE_VERSION
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+fn not_an_archive() -> anyhow::Error {
+    PersonaError::Validation(format!(
+        "Not a Persona vault archive. Create one with `persona export --format {}`",
+        VAULT_FORMAT
+    ))
+    .into()
+}
+
+fn corrupt_archive() -> anyhow::Error {
+    PersonaError::Validation("The vault archive is corrupt or incomplete".to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::file_crypto::KdfParams;
+    use persona_core::models::{CredentialType, IdentityType, SecurityLevel};
+
+    fn fast_kdf() -> EncryptionParams {
+        EncryptionParams {
+            kdf: KdfParams {
+                mem_kib: 1024,
+                iterations: 3,
+                parallelism: 1,
+            },
+            ..EncryptionParams::default()
+        }
+    }
+
+    fn sample() -> VaultArchive {
+        let identity = Identity::new("alice".to_string(), IdentityType::Personal);
+        let credential = Credential::new(
+            identity.id,
+            "bank".to_string(),
+            CredentialType::Password,
+            SecurityLevel::High,
+            Vec::new(),
+            None,
+        );
+        let mut archive = VaultArchive::new();
+        archive.identities.push(ArchivedIdentity {
+            identity,
+            credentials: vec![ArchivedCredential {
+                credential,
+                data: serde_json::json!({"type": "password"}),
+                attachments: vec![ArchivedAttachment::new("sheet.txt".to_string(), b"hi")],
+            }],
+        });
+        archive
+    }
+
+    fn no_passphrase() -> Result<String> {
+        panic!("passphrase requested for an archive that should have been rejected first")
+    }
+
+    fn assert_validation(result: Result<VaultArchive>, needle: &str) {
+        let err = result.expect_err("archive should be rejected");
+        assert!(
+            matches!(
+                err.downcast_ref::<PersonaError>(),
+                Some(PersonaError::Validation(_))
+            ),
+            "{:#}",
+            err
+        );
+        assert!(err.to_string().contains(needle), "{}", err);
+    }
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let sealed = sample().seal("archive pass", fast_kdf()).unwrap();
+        assert!(is_vault_archive(&sealed));
+
+        let opened = VaultArchive::open(&sealed, || Ok("archive pass".to_string())).unwrap();
+        assert_eq!(opened.identities[0].identity.name, "alice");
+        assert_eq!(opened.credentials_count(), 1);
+        let attachment = &opened.identities[0].credentials[0].attachments[0];
+        assert_eq!(attachment.content().unwrap(), b"hi");
+
+        assert_validation(
+            VaultArchive::open(&sealed, || Ok("wrong".to_string())),
+            "integrity check",
+        );
+    }
+
+    #[test]
+    fn rejects_tampered_and_foreign_files() {
+        let mut sealed = sample().seal("archive pass", fast_kdf()).unwrap();
+        let last = sealed.len() - 40;
+        sealed[last] ^= 1;
+        assert_validation(
+            VaultArchive::open(&sealed, || Ok("archive pass".to_string())),
+            "integrity check",
+        );
+
+        assert_validation(
+            VaultArchive::open(b"{\"identities\": []}", no_passphrase),
+            "Not a Persona vault archive",
+        );
+        let export = encrypt_bytes(
+            b"{}",
+            "archive pass",
+            ExportManifest::new("json", 0, 0),
+            fast_kdf(),
+        )
+        .unwrap();
+        assert!(!is_vault_archive(&export));
+        assert_validation(
+            VaultArchive::open(&export, no_passphrase),
+            "Not a Persona vault archive",
+        );
+    }
+
+    #[test]
+    fn rejects_newer_archive_versions() {
+        let mut archive = sample();
+        archive.version = VAULT_ARCHIVE_VERSION + 1;
+        let sealed = archive.seal("archive pass", fast_kdf()).unwrap();
+        assert_validation(
+            VaultArchive::open(&sealed, || Ok("archive pass".to_string())),
+            "newer than this build supports",
+        );
+    }
+}
@@ -26,7 +26,29 @@ fn test_cli_version() -> Result<()> {
     cmd.arg("--version")
         .assert()
         .success()
-        .stdout(predicate::str::contains("0.1.0"));
+        .stdout(predicate::str::contains(format!(
+            "persona {}",
+            env!("CARGO_PKG_VERSION")
+        )));
+
+    Ok(())
+}
+
+#[test]
+fn test_version_command_reports_build_info() -> Result<()> {
+    let output = Command::cargo_bin("persona")?
+        .args(["version", "--format", "json"])
+        .output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let json: serde_json::Value = serde_json::from_str(&stdout[stdout.find('{').unwrap()..])?;
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    assert!(!json["git_hash"].as_str().unwrap().is_empty());
+    assert!(json["features"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!("sqlite")));
 
     Ok(())
 }
@@ -229,3 +251,1439 @@ fn test_missing_config() -> Result<()> {
 
     Ok(())
 }
+
+/// Demo mode runs without a workspace and shows only fixture data
+#[test]
+fn test_demo_mode_lists_fixtures_without_workspace() -> Result<()> {
+    let temp_dir = tempdir()?;
+
+    let mut cmd = Command::cargo_bin("persona")?;
+    cmd.arg("--demo")
+        .arg("list")
+        .arg("--format")
+        .arg("json")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Alex Example"));
+
+    assert!(!temp_dir.path().join("config.toml").exists());
+
+    Ok(())
+}
+
+/// `show --history` prints a credential's timeline and needs a credential ID
+#[test]
+fn test_show_history_prints_credential_timeline() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let demo_totp_id = "de300000-0000-0000-0000-000100000004";
+
+    Command::cargo_bin("persona")?
+        .args(["--demo", "show", demo_totp_id, "--history", "--format", "json"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"change\": \"created\""));
+    Command::cargo_bin("persona")?
+        .args(["--demo", "show", "Alex Example", "--history"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expects a credential ID"));
+
+    Ok(())
+}
+
+/// Failures exit with a code that identifies the underlying error class
+#[test]
+fn test_exit_codes_identify_error_class() -> Result<()> {
+    let temp_dir = tempdir()?;
+    // TOTP (not HOTP) entry in the demo vault
+    let demo_totp_id = "de300000-0000-0000-0000-000100000004";
+    let missing_id = "00000000-0000-0000-0000-00000000beef";
+
+    let cases: [(&[&str], i32); 5] = [
+        (&["nonexistent-command"], 2),
+        (&["list"], 4),
+        (&["--demo", "totp", "next", demo_totp_id], 5),
+        (&["--demo", "totp", "verify", demo_totp_id, "12345"], 5),
+        (&["--demo", "totp", "next", missing_id], 7),
+    ];
+    for (args, code) in cases {
+        Command::cargo_bin("persona")?
+            .args(args)
+            .current_dir(temp_dir.path())
+            .assert()
+            .code(code)
+            .stderr(predicate::str::is_empty().not());
+    }
+
+    Ok(())
+}
+
+/// The exit codes from docs/NON_INTERACTIVE_MODE.md that need a real workspace; usage (2)
+/// and invalid input (5) are covered by `test_exit_codes_identify_error_class`
+#[test]
+fn test_exit_codes_cover_every_documented_class() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    let persona_as = |password: &str, args: &[&str]| -> Result<Command> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.args(args)
+            .env("PERSONA_NON_INTERACTIVE", "1")
+            .env("PERSONA_MASTER_PASSWORD", password)
+            .env("NO_COLOR", "1")
+            .current_dir(workspace_path);
+        Ok(cmd)
+    };
+    let persona = |args: &[&str]| persona_as("hunter22", args);
+    let expect_code = |args: &[&str], code: i32| -> Result<()> {
+        persona(args)?
+            .assert()
+            .code(code)
+            .stderr(predicate::str::is_empty().not());
+        Ok(())
+    };
+    let find_uuid = |stdout: &[u8]| -> String {
+        String::from_utf8_lossy(stdout)
+            .split(|c: char| !(c.is_ascii_hexdigit() || c == '-'))
+            .find(|word| word.len() == 36 && word.matches('-').count() == 4)
+            .expect("uuid in output")
+            .to_string()
+    };
+    let only_file_in = |dir: std::path::PathBuf, extension: &str| -> Result<std::path::PathBuf> {
+        let mut pending = vec![dir];
+        while let Some(dir) = pending.pop() {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else if path
+                    .extension()
+                    .map_or(extension.is_empty(), |e| e == extension)
+                {
+                    return Ok(path);
+                }
+            }
+        }
+        anyhow::bail!("no .{} file found", extension)
+    };
+    let flip_last_byte = |path: &std::path::Path| -> Result<()> {
+        let mut bytes = fs::read(path)?;
+        *bytes.last_mut().unwrap() ^= 1;
+        fs::write(path, bytes)?;
+        Ok(())
+    };
+
+    persona(&["add", "alice", "--yes"])?.assert().success();
+    persona(&[
+        "credential",
+        "add",
+        "--identity",
+        "alice",
+        "--name",
+        "bank",
+        "--secret",
+        "pw",
+    ])?
+    .assert()
+    .success();
+    let listed = persona(&["credential", "list", "--format", "json"])?
+        .assert()
+        .success();
+    let credential_id = find_uuid(&listed.get_output().stdout);
+    fs::write(workspace_path.join("sheet.txt"), "recovery sheet contents")?;
+    persona(&["credential", "attach", "--id", &credential_id, "sheet.txt"])?
+        .assert()
+        .success();
+    let listed = persona(&["credential", "list-attachments", "--id", &credential_id])?
+        .assert()
+        .success();
+    let attachment_id = find_uuid(&listed.get_output().stdout);
+    let extract = ["credential", "extract", "--attachment", &attachment_id];
+
+    // 1: a refusal that carries no error class
+    fs::write(workspace_path.join("taken.txt"), "")?;
+    expect_code(&[&extract[..], &["--output", "taken.txt"]].concat(), 1)?;
+    // 7: missing identity, credential and attachment
+    expect_code(&["show", "nobody"], 7)?;
+    expect_code(
+        &[
+            "credential",
+            "show",
+            "--id",
+            "00000000-0000-0000-0000-00000000beef",
+        ],
+        7,
+    )?;
+    expect_code(
+        &[
+            "credential",
+            "extract",
+            "--attachment",
+            "00000000-0000-0000-0000-00000000beef",
+        ],
+        7,
+    )?;
+    // 11: unreadable file
+    expect_code(
+        &[
+            "credential",
+            "attach",
+            "--id",
+            &credential_id,
+            "missing.txt",
+        ],
+        11,
+    )?;
+
+    // 10: attachment ciphertext modified on disk
+    flip_last_byte(&only_file_in(workspace_path.join("attachments"), "")?)?;
+    expect_code(&[&extract[..], &["--output", "out.txt"]].concat(), 10)?;
+
+    // 9: backup that no longer matches its recorded hash
+    persona(&["backup", "create"])?.assert().success();
+    let backup = only_file_in(workspace_path.join("backups"), "db")?;
+    flip_last_byte(&backup)?;
+    expect_code(&["backup", "restore", backup.to_str().unwrap(), "--yes"], 9)?;
+
+    // 8: another live process holds the vault lock
+    let lock = serde_json::json!({
+        "pid": std::process::id(),
+        "operation": "import",
+        "acquired_at": "2026-01-01T00:00:00Z",
+    });
+    fs::write(workspace_path.join(".persona.lock"), lock.to_string())?;
+    expect_code(&["maintenance", "vacuum"], 8)?;
+    fs::remove_file(workspace_path.join(".persona.lock"))?;
+
+    // 4: permission denied: the vault is readable by others and the config refuses that
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let config_path = workspace_path.join("config.toml");
+        let config = fs::read_to_string(&config_path)?;
+        fs::write(
+            &config_path,
+            config.replace(
+                "permission_check = \"warn\"",
+                "permission_check = \"refuse\"",
+            ),
+        )?;
+        let database = workspace_path.join("identities.db");
+        fs::set_permissions(&database, fs::Permissions::from_mode(0o644))?;
+        expect_code(&["list"], 4)?;
+        fs::set_permissions(&database, fs::Permissions::from_mode(0o600))?;
+        fs::write(&config_path, config)?;
+    }
+
+    // 3: wrong master password; 6: the account is locked after repeated failures
+    persona_as("wrong", &["list"])?.assert().code(3);
+    for _ in 0..4 {
+        persona_as("wrong", &["list"])?.assert().failure();
+    }
+    expect_code(&["list"], 6)?;
+
+    Ok(())
+}
+
+/// `--no-color` and `NO_COLOR` strip styling even when color is forced
+#[test]
+fn test_no_color_disables_styling() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let escape = predicate::str::contains("\u{1b}[");
+
+    let run = |extra_env: Option<(&str, &str)>, flag: bool| -> Result<_> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.env("CLICOLOR_FORCE", "1")
+            .env_remove("NO_COLOR")
+            .current_dir(temp_dir.path())
+            .arg("--demo");
+        if let Some((key, value)) = extra_env {
+            cmd.env(key, value);
+        }
+        if flag {
+            cmd.arg("--no-color");
+        }
+        Ok(cmd.arg("list").assert().success())
+    };
+
+    run(None, false)?.stdout(escape.clone());
+    run(None, true)?.stdout(escape.clone().not());
+    run(Some(("NO_COLOR", "1")), false)?.stdout(escape.not());
+
+    Ok(())
+}
+
+/// `credential generate --deterministic` reproduces the same password for the same inputs
+#[test]
+fn test_credential_generate_deterministic() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace = temp_dir.path().join("vault");
+    Command::cargo_bin("persona")?
+        .args(["init", "--path"])
+        .arg(&workspace)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    let generate = |password: &str, extra: &[&str]| -> Result<_> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.env("PERSONA_NON_INTERACTIVE", "1")
+            .env("PERSONA_MASTER_PASSWORD", password)
+            .env("NO_COLOR", "1")
+            .current_dir(&workspace)
+            .args([
+                "credential",
+                "generate",
+                "--deterministic",
+                "--site",
+                "example.com",
+            ])
+            .args(extra);
+        Ok(cmd.assert())
+    };
+    let stdout = |assert: assert_cmd::assert::Assert| {
+        let output = String::from_utf8(assert.success().get_output().stdout.clone()).unwrap();
+        output
+            .lines()
+            .find(|line| line.contains("Password for"))
+            .map(str::to_string)
+            .unwrap_or(output)
+    };
+
+    let first = stdout(generate("hunter22", &[])?);
+    assert!(first.contains("Password for example.com (counter 1)"));
+    assert_eq!(first, stdout(generate("hunter22", &[])?));
+    let rotated = stdout(generate("hunter22", &["--counter", "2"])?);
+    assert_ne!(first.rsplit(": ").next(), rotated.rsplit(": ").next());
+
+    // A mistyped master password is rejected rather than deriving a different password
+    generate("hunter23", &[])?.failure();
+
+    Ok(())
+}
+
+/// `password check` scores with the shared core estimator and lists its warnings
+#[test]
+fn test_password_check_reports_score_and_warnings() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let check = |password: &str| -> Result<_> {
+        Ok(Command::cargo_bin("persona")?
+            .env("NO_COLOR", "1")
+            .current_dir(temp_dir.path())
+            .args(["password", "check", password])
+            .assert()
+            .success())
+    };
+
+    check("password123")?
+        .stdout(predicate::str::contains("Strength: 1/4 (weak)"))
+        .stdout(predicate::str::contains("Too short"))
+        .stdout(predicate::str::contains("common password"));
+    check("v9#Lq2!xR7@mWz4p")?
+        .stdout(predicate::str::contains("Strength: 4/4 (very strong)"))
+        .stdout(predicate::str::contains("⚠").not());
+
+    Ok(())
+}
+
+/// `import --from-ssh-config --dry-run` previews resolved hosts without touching the vault
+#[test]
+fn test_import_ssh_config_dry_run() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .arg("--yes")
+        .assert()
+        .success();
+
+    let ssh_config = workspace_path.join("ssh_config");
+    std::fs::write(
+        &ssh_config,
+        "Host build\n  HostName build.example.com\n  User deploy\n\nHost *\n  Port 2222\n",
+    )?;
+
+    Command::cargo_bin("persona")?
+        .arg("import")
+        .arg("--from-ssh-config")
+        .arg(&ssh_config)
+        .arg("--dry-run")
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("deploy@build.example.com:2222"))
+        .stdout(predicate::str::contains("the vault was not changed"));
+
+    Command::cargo_bin("persona")?
+        .arg("list")
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No identities found"));
+
+    Ok(())
+}
+
+/// `import --format bitwarden` creates an identity per folder, skips malformed records and
+/// deduplicates on re-import
+#[test]
+fn test_import_bitwarden_export() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    let export = workspace_path.join("bitwarden.json");
+    fs::write(
+        &export,
+        r#"{"encrypted": false,
+            "folders": [{"id": "f1", "name": "Work"}],
+            "items": [
+                {"type": 1, "name": "GitHub", "folderId": "f1",
+                 "login": {"username": "octo", "password": "pw",
+                           "totp": "JBSWY3DPEHPK3PXP", "uris": [{"uri": "https://github.com"}]}},
+                {"type": 1, "name": "Forum", "login": {"username": "me", "password": "pw2"}},
+                {"type": 1, "name": "Broken", "login": {"totp": "not base32!"}},
+                {"type": 1}
+            ]}"#,
+    )?;
+    let import = || -> Result<Command> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.args(["import", "--format", "bitwarden", "--force"])
+            .arg(&export)
+            .env("PERSONA_NON_INTERACTIVE", "1")
+            .env("PERSONA_MASTER_PASSWORD", "hunter22")
+            .env("NO_COLOR", "1")
+            .current_dir(workspace_path);
+        Ok(cmd)
+    };
+
+    import()?.assert().success().stdout(predicate::str::contains(
+        "Imported 3 credentials into 2 identities; skipped 0 duplicates and 2 malformed records",
+    ));
+    import()?.assert().success().stdout(predicate::str::contains(
+        "Imported 0 credentials into 2 identities; skipped 3 duplicates and 2 malformed records",
+    ));
+
+    Command::cargo_bin("persona")?
+        .arg("list")
+        .env("PERSONA_NON_INTERACTIVE", "1")
+        .env("PERSONA_MASTER_PASSWORD", "hunter22")
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Work"))
+        .stdout(predicate::str::contains("Bitwarden"));
+
+    Ok(())
+}
+
+#[test]
+fn test_audit_build_breach_db() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .arg("--yes")
+        .assert()
+        .success();
+
+    let corpus = workspace_path.join("pwned.txt");
+    std::fs::write(
+        &corpus,
+        "F3BBBD66A63D4BF1747940578EC3D0103530E21D:2254650\npassword123\n",
+    )?;
+
+    Command::cargo_bin("persona")?
+        .args(["audit", "passwords", "--breach-db"])
+        .arg(workspace_path.join("missing.bloom"))
+        .current_dir(workspace_path)
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("Breach database not found"));
+
+    Command::cargo_bin("persona")?
+        .args(["audit", "build-breach-db"])
+        .arg(&corpus)
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("with 2 entries"));
+    assert!(workspace_path.join("breach.bloom").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_address_book_add_list_remove() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .arg("--yes")
+        .assert()
+        .success();
+
+    let address = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+    Command::cargo_bin("persona")?
+        .args(["address-book", "add", "cold", address, "--network", "btc"])
+        .current_dir(workspace_path)
+        .assert()
+        .success();
+
+    // Same address with a typo in the last character fails the bech32 checksum
+    Command::cargo_bin("persona")?
+        .args([
+            "address-book",
+            "add",
+            "typo",
+            &address.replace("mdq", "mdp"),
+        ])
+        .args(["--network", "btc"])
+        .current_dir(workspace_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("checksum"));
+
+    Command::cargo_bin("persona")?
+        .args(["address-book", "list"])
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cold").and(predicate::str::contains(address)));
+
+    Command::cargo_bin("persona")?
+        .args(["address-book", "rm", "cold"])
+        .current_dir(workspace_path)
+        .assert()
+        .success();
+    Command::cargo_bin("persona")?
+        .args(["address-book", "rm", "cold"])
+        .current_dir(workspace_path)
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+#[test]
+fn test_wallet_import_names_the_mistyped_mnemonic_word() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .arg("--yes")
+        .assert()
+        .success();
+
+    // Rejected before any password prompt, so no stdin is needed
+    let phrase = "abandon abandon abandon abandon abandon abandon abandom abandon abandon abandon abandon about";
+    Command::cargo_bin("persona")?
+        .args(["wallet", "import", "--format", "mnemonic", phrase])
+        .current_dir(workspace_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "word 7 'abandom' is not in the wordlist",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn test_verification_phrase_set_at_init_and_reset() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    let init = Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .arg("--yes")
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Verification phrase:"));
+    let init_output = String::from_utf8(init.get_output().stdout.clone())?;
+    let phrase = init_output
+        .lines()
+        .find_map(|line| line.split("Verification phrase: ").nth(1))
+        .unwrap()
+        .trim()
+        .to_string();
+    assert_eq!(phrase.split(' ').count(), 4);
+
+    Command::cargo_bin("persona")?
+        .args(["verification-phrase", "show"])
+        .env("NO_COLOR", "1")
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(phrase.as_str()));
+
+    Command::cargo_bin("persona")?
+        .args(["verification-phrase", "reset"])
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("New verification phrase"));
+
+    Ok(())
+}
+
+#[test]
+fn test_recovery_codes_generate_and_regenerate() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    let recovery = |args: &[&str]| -> Result<Command> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.args(["recovery", "codes"])
+            .args(args)
+            .env("PERSONA_NON_INTERACTIVE", "1")
+            .env("PERSONA_MASTER_PASSWORD", "hunter22")
+            .env("NO_COLOR", "1")
+            .current_dir(workspace_path);
+        Ok(cmd)
+    };
+    let codes_in = |stdout: &[u8]| -> Vec<String> {
+        String::from_utf8_lossy(stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.len() == 23 && line.matches('-').count() == 3)
+            .map(str::to_string)
+            .collect()
+    };
+
+    let first = recovery(&["generate"])?.assert().success();
+    let first = codes_in(&first.get_output().stdout);
+    assert_eq!(first.len(), 10);
+
+    recovery(&["generate"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("regenerate"));
+
+    let second = recovery(&["regenerate", "--yes"])?.assert().success();
+    let second = codes_in(&second.get_output().stdout);
+    assert_eq!(second.len(), 10);
+    assert!(first.iter().all(|code| !second.contains(code)));
+
+    Ok(())
+}
+
+#[test]
+fn test_credential_attachment_roundtrip() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    let persona = |args: &[&str]| -> Result<Command> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.args(args)
+            .env("PERSONA_NON_INTERACTIVE", "1")
+            .env("PERSONA_MASTER_PASSWORD", "hunter22")
+            .env("NO_COLOR", "1")
+            .current_dir(workspace_path);
+        Ok(cmd)
+    };
+    let find_uuid = |stdout: &[u8]| -> String {
+        String::from_utf8_lossy(stdout)
+            .split(|c: char| !(c.is_ascii_hexdigit() || c == '-'))
+            .find(|word| word.len() == 36 && word.matches('-').count() == 4)
+            .expect("uuid in output")
+            .to_string()
+    };
+
+    persona(&["add", "alice", "--yes"])?.assert().success();
+    persona(&[
+        "credential",
+        "add",
+        "--identity",
+        "alice",
+        "--name",
+        "bank",
+        "--secret",
+        "pw",
+    ])?
+    .assert()
+    .success();
+    let listed = persona(&["credential", "list", "--format", "json"])?
+        .assert()
+        .success();
+    let credential_id = find_uuid(&listed.get_output().stdout);
+
+    fs::write(workspace_path.join("sheet.txt"), "recovery sheet contents")?;
+    persona(&["credential", "attach", "--id", &credential_id, "sheet.txt"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Attached 'sheet.txt'"));
+
+    let listed = persona(&["credential", "list-attachments", "--id", &credential_id])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("sheet.txt"));
+    let attachment_id = find_uuid(&listed.get_output().stdout);
+
+    persona(&[
+        "credential",
+        "extract",
+        "--attachment",
+        &attachment_id,
+        "--output",
+        "out.txt",
+    ])?
+    .assert()
+    .success();
+    assert_eq!(
+        fs::read_to_string(workspace_path.join("out.txt"))?,
+        "recovery sheet contents"
+    );
+
+    // Existing files are not overwritten without --force
+    persona(&[
+        "credential",
+        "extract",
+        "--attachment",
+        &attachment_id,
+        "--output",
+        "out.txt",
+    ])?
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("--force"));
+
+    Ok(())
+}
+
+#[test]
+fn test_passwd_reencrypts_under_the_new_password() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    let persona = |password: &str, args: &[&str]| -> Result<Command> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.args(args)
+            .env("PERSONA_NON_INTERACTIVE", "1")
+            .env("PERSONA_MASTER_PASSWORD", password)
+            .env("NO_COLOR", "1")
+            .current_dir(workspace_path);
+        Ok(cmd)
+    };
+
+    persona("hunter22", &["add", "alice", "--yes"])?
+        .assert()
+        .success();
+    persona(
+        "hunter22",
+        &[
+            "credential",
+            "add",
+            "--identity",
+            "alice",
+            "--name",
+            "bank",
+            "--secret",
+            "pw",
+        ],
+    )?
+    .assert()
+    .success();
+    let listed = persona("hunter22", &["credential", "list", "--format", "json"])?
+        .assert()
+        .success();
+    let credential_id = String::from_utf8_lossy(&listed.get_output().stdout)
+        .split(|c: char| !(c.is_ascii_hexdigit() || c == '-'))
+        .find(|word| word.len() == 36 && word.matches('-').count() == 4)
+        .expect("uuid in output")
+        .to_string();
+    fs::write(workspace_path.join("sheet.txt"), "recovery sheet contents")?;
+    persona(
+        "hunter22",
+        &["credential", "attach", "--id", &credential_id, "sheet.txt"],
+    )?
+    .assert()
+    .success();
+
+    // The new password must be supplied when there is no terminal
+    persona("hunter22", &["passwd"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("PERSONA_NEW_MASTER_PASSWORD"));
+    persona("hunter22", &["passwd"])?
+        .env("PERSONA_NEW_MASTER_PASSWORD", "correct horse")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Master password changed"))
+        .stdout(predicate::str::contains("Credentials re-encrypted: 1"));
+
+    persona("hunter22", &["credential", "list"])?
+        .assert()
+        .failure();
+    let listed = persona(
+        "correct horse",
+        &["credential", "list-attachments", "--id", &credential_id],
+    )?
+    .assert()
+    .success();
+    let attachment_id = String::from_utf8_lossy(&listed.get_output().stdout)
+        .split(|c: char| !(c.is_ascii_hexdigit() || c == '-'))
+        .find(|word| word.len() == 36 && word.matches('-').count() == 4)
+        .expect("uuid in output")
+        .to_string();
+    persona(
+        "correct horse",
+        &[
+            "credential",
+            "extract",
+            "--attachment",
+            &attachment_id,
+            "--output",
+            "out.txt",
+        ],
+    )?
+    .assert()
+    .success();
+    assert_eq!(
+        fs::read_to_string(workspace_path.join("out.txt"))?,
+        "recovery sheet contents"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_backup_create_and_restore() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    let persona = |args: &[&str]| -> Result<Command> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.args(args)
+            .env("PERSONA_NON_INTERACTIVE", "1")
+            .env("PERSONA_MASTER_PASSWORD", "hunter22")
+            .env("NO_COLOR", "1")
+            .current_dir(workspace_path);
+        Ok(cmd)
+    };
+
+    persona(&["add", "alice", "--yes"])?.assert().success();
+    persona(&["backup", "create", "--output", "before.db"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("SHA-256"));
+    assert!(workspace_path.join("before.db.sha256").exists());
+    persona(&["add", "bob", "--yes"])?.assert().success();
+
+    // Restoring asks first; without a terminal that means no
+    persona(&["backup", "restore", "before.db"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cancelled"));
+    persona(&["list"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bob"));
+
+    persona(&["backup", "restore", "before.db", "--yes"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored"));
+    persona(&["list"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("alice"))
+        .stdout(predicate::str::contains("bob").not());
+
+    // A modified backup is refused
+    let mut bytes = fs::read(workspace_path.join("before.db"))?;
+    bytes[100] ^= 0xff;
+    fs::write(workspace_path.join("before.db"), bytes)?;
+    persona(&["backup", "restore", "before.db", "--yes"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("recorded SHA-256"));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+#[test]
+fn test_encrypt_db_needs_sqlcipher() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("persona")?
+        .args(["migrate", "encrypt-db", "--yes"])
+        .env("PERSONA_NON_INTERACTIVE", "1")
+        .env("PERSONA_MASTER_PASSWORD", "hunter22")
+        .current_dir(workspace_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--features sqlcipher"));
+    Ok(())
+}
+
+#[cfg(feature = "sqlcipher")]
+#[test]
+fn test_encrypt_db_hides_metadata_and_follows_passwd() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    let persona = |password: &str, args: &[&str]| -> Result<Command> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.args(args)
+            .env("PERSONA_NON_INTERACTIVE", "1")
+            .env("PERSONA_MASTER_PASSWORD", password)
+            .env("NO_COLOR", "1")
+            .current_dir(workspace_path);
+        Ok(cmd)
+    };
+
+    persona("hunter22", &["add", "plaintext-marker", "--yes"])?
+        .assert()
+        .success();
+    persona("hunter22", &["migrate", "encrypt-db", "--yes"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("encrypted at rest"));
+    let file = fs::read(workspace_path.join("identities.db"))?;
+    assert!(!file.starts_with(b"SQLite format 3"));
+    assert!(!file
+        .windows(b"plaintext-marker".len())
+        .any(|window| window == b"plaintext-marker"));
+
+    persona("hunter22", &["list"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("plaintext-marker"));
+    persona("wrong", &["list"])?.assert().failure();
+
+    // Changing the master password rekeys the file too
+    persona("hunter22", &["passwd"])?
+        .env("PERSONA_NEW_MASTER_PASSWORD", "correct horse")
+        .assert()
+        .success();
+    persona("hunter22", &["list"])?.assert().failure();
+    persona("correct horse", &["list"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("plaintext-marker"));
+    Ok(())
+}
+
+#[test]
+fn test_maintenance_vacuum_checks_integrity() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .arg("--yes")
+        .assert()
+        .success();
+
+    // Incremental mode is only available after a full vacuum
+    Command::cargo_bin("persona")?
+        .args(["maintenance", "vacuum", "--incremental"])
+        .current_dir(workspace_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("full vacuum"));
+
+    Command::cargo_bin("persona")?
+        .args(["maintenance", "vacuum"])
+        .env("NO_COLOR", "1")
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Vacuum complete"))
+        .stdout(predicate::str::contains("Integrity check passed"));
+
+    let output = Command::cargo_bin("persona")?
+        .args(["maintenance", "vacuum", "--incremental", "--format", "json"])
+        .current_dir(workspace_path)
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    let report: serde_json::Value = serde_json::from_str(&stdout[stdout.find('{').unwrap()..])?;
+    assert_eq!(report["incremental"], true);
+    assert_eq!(report["integrity_errors"], serde_json::json!([]));
+
+    Ok(())
+}
+
+#[test]
+fn test_maintenance_reindex_rebuilds_the_search_index() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    let persona = |args: &[&str]| -> Result<Command> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.args(args)
+            .env("PERSONA_NON_INTERACTIVE", "1")
+            .env("PERSONA_MASTER_PASSWORD", "hunter22")
+            .env("NO_COLOR", "1")
+            .current_dir(workspace_path);
+        Ok(cmd)
+    };
+    persona(&["add", "alice", "--yes"])?.assert().success();
+    for name in ["GitHub", "GitLab", "Gitea"] {
+        persona(&[
+            "credential",
+            "add",
+            "--identity",
+            "alice",
+            "--name",
+            name,
+            "--secret",
+            "pw",
+        ])?
+        .assert()
+        .success();
+    }
+
+    persona(&["maintenance", "reindex", "--batch-size", "2"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 of 3 credentials indexed"));
+
+    let output = persona(&["maintenance", "reindex", "--format", "json"])?
+        .assert()
+        .success();
+    let report: serde_json::Value = serde_json::from_slice(&output.get_output().stdout)?;
+    assert_eq!(report["credentials"], 3);
+    assert_eq!(report["indexed"], 3);
+
+    persona(&["maintenance", "reindex", "--batch-size", "0"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--batch-size"));
+
+    Ok(())
+}
+
+#[test]
+fn test_exclusive_operations_refuse_a_held_vault_lock() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .arg("--yes")
+        .assert()
+        .success();
+
+    // This test process stands in for another persona process mid-import
+    let lock = serde_json::json!({
+        "pid": std::process::id(),
+        "operation": "import",
+        "acquired_at": "2026-01-01T00:00:00Z",
+    });
+    std::fs::write(workspace_path.join(".persona.lock"), lock.to_string())?;
+
+    Command::cargo_bin("persona")?
+        .args(["migrate", "--apply"])
+        .current_dir(workspace_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "Vault is in use by PID {}",
+            std::process::id()
+        )));
+
+    std::fs::remove_file(workspace_path.join(".persona.lock"))?;
+    Command::cargo_bin("persona")?
+        .args(["migrate", "--apply"])
+        .current_dir(workspace_path)
+        .assert()
+        .success();
+    assert!(!workspace_path.join(".persona.lock").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_migrate_lists_pending_and_applies_with_flag() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .arg("--yes")
+        .assert()
+        .success();
+
+    Command::cargo_bin("persona")?
+        .arg("migrate")
+        .env("NO_COLOR", "1")
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Schema up to date"))
+        .stdout(predicate::str::contains("Pending migrations").not());
+
+    Command::cargo_bin("persona")?
+        .args(["migrate", "--apply"])
+        .env("NO_COLOR", "1")
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Schema already up to date"));
+
+    Ok(())
+}
+
+#[test]
+fn test_status_reports_workspace_state() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    let state_dir = temp_dir.path().join("state");
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .arg("--yes")
+        .assert()
+        .success();
+
+    Command::cargo_bin("persona")?
+        .arg("status")
+        .env("NO_COLOR", "1")
+        .env("PERSONA_AGENT_STATE_DIR", &state_dir)
+        .env("PERSONA_BRIDGE_STATE_DIR", &state_dir)
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Vault:"))
+        .stdout(predicate::str::contains("identities.db is accessible").not())
+        .stdout(predicate::str::contains("SSH agent:       not running"))
+        .stdout(predicate::str::contains("no paired extensions"));
+
+    let output = Command::cargo_bin("persona")?
+        .args(["whoami", "--format", "json"])
+        .env("PERSONA_AGENT_STATE_DIR", &state_dir)
+        .env("PERSONA_BRIDGE_STATE_DIR", &state_dir)
+        .env_remove("SSH_AUTH_SOCK")
+        .current_dir(workspace_path)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    // Skip the log line printed before the report
+    let output = String::from_utf8(output)?;
+    let report: serde_json::Value = serde_json::from_str(&output[output.find('{').unwrap()..])?;
+    assert_eq!(report["schema_version"], report["latest_schema_version"]);
+    assert_eq!(report["ssh_agent"]["running"], false);
+    assert_eq!(report["bridge_pairings"].as_array().map(Vec::len), Some(0));
+
+    Ok(())
+}
+
+#[test]
+fn test_agent_status_json_reads_state_files() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let state_dir = temp_dir.path().join("agent");
+    fs::create_dir_all(&state_dir)?;
+    let socket = state_dir.join("missing.sock");
+    fs::write(
+        state_dir.join("ssh-agent.sock"),
+        socket.to_string_lossy().as_bytes(),
+    )?;
+    fs::write(state_dir.join("ssh-agent.pid"), "4242\n")?;
+
+    // No workspace needed: scripts call this from anywhere
+    let output = Command::cargo_bin("persona")?
+        .args(["agent", "status", "--json"])
+        .env("PERSONA_AGENT_STATE_DIR", &state_dir)
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output)?;
+    let status: serde_json::Value = serde_json::from_str(&output[output.find('{').unwrap()..])?;
+    assert_eq!(status["running"], true);
+    assert_eq!(status["pid"], 4242);
+    assert_eq!(status["socket_path"], socket.to_string_lossy().as_ref());
+    assert!(status["key_count"].is_null());
+    assert_eq!(status["state_dir"], state_dir.to_string_lossy().as_ref());
+
+    fs::remove_file(state_dir.join("ssh-agent.sock"))?;
+    fs::remove_file(state_dir.join("ssh-agent.pid"))?;
+    Command::cargo_bin("persona")?
+        .args(["agent", "status"])
+        .env("NO_COLOR", "1")
+        .env("PERSONA_AGENT_STATE_DIR", &state_dir)
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "persona-ssh-agent is not running.",
+        ));
+
+    Ok(())
+}
+
+/// `list --recent` shows credentials in the order they were last opened
+#[test]
+fn test_list_recent_orders_by_last_use() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    let persona = |args: &[&str]| -> Result<Command> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.args(args)
+            .env("PERSONA_NON_INTERACTIVE", "1")
+            .env("PERSONA_MASTER_PASSWORD", "hunter22")
+            .env("NO_COLOR", "1")
+            .current_dir(workspace_path);
+        Ok(cmd)
+    };
+    let hotp_id = |name: &str| -> Result<String> {
+        let setup = persona(&[
+            "totp",
+            "setup",
+            "--identity",
+            "alice",
+            "--name",
+            name,
+            "--secret",
+            "JBSWY3DPEHPK3PXP",
+            "--hotp",
+        ])?
+        .assert()
+        .success();
+        let stdout = String::from_utf8_lossy(&setup.get_output().stdout).to_string();
+        Ok(stdout
+            .split("persona totp next ")
+            .nth(1)
+            .and_then(|rest| rest.split('`').next())
+            .expect("credential id in output")
+            .to_string())
+    };
+
+    persona(&["add", "alice", "--yes"])?.assert().success();
+    let mail = hotp_id("Mail")?;
+    let bank = hotp_id("Bank")?;
+    hotp_id("Forum")?;
+    persona(&["list", "--recent"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No credentials have been used yet"));
+
+    persona(&["totp", "next", &bank])?.assert().success();
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    persona(&["totp", "next", &mail])?.assert().success();
+
+    let listed = persona(&["list", "--recent", "--format", "json"])?
+        .assert()
+        .success();
+    let recent: serde_json::Value = serde_json::from_slice(&listed.get_output().stdout)?;
+    let names: Vec<&str> = recent
+        .as_array()
+        .expect("json array")
+        .iter()
+        .filter_map(|credential| credential["name"].as_str())
+        .collect();
+    assert_eq!(names, ["Mail", "Bank"]);
+    assert_eq!(recent[0]["identity"], "alice");
+
+    persona(&["list", "--recent", "1"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Mail"))
+        .stdout(predicate::str::contains("Bank").not());
+
+    Ok(())
+}
+
+/// `credential set-origins` stores extra fill origins as hosts in the credential's metadata
+#[test]
+fn test_credential_set_origins() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let workspace_path = temp_dir.path();
+    Command::cargo_bin("persona")?
+        .arg("init")
+        .arg("--path")
+        .arg(workspace_path)
+        .args(["--yes", "--encrypted", "--master-password", "hunter22"])
+        .assert()
+        .success();
+
+    let persona = |args: &[&str]| -> Result<Command> {
+        let mut cmd = Command::cargo_bin("persona")?;
+        cmd.args(args)
+            .env("PERSONA_NON_INTERACTIVE", "1")
+            .env("PERSONA_MASTER_PASSWORD", "hunter22")
+            .env("NO_COLOR", "1")
+            .current_dir(workspace_path);
+        Ok(cmd)
+    };
+    let allowed_origins = || -> Result<(String, serde_json::Value)> {
+        let listed = persona(&["credential", "list", "--format", "json"])?
+            .assert()
+            .success();
+        let credentials: serde_json::Value = serde_json::from_slice(&listed.get_output().stdout)?;
+        let credential = &credentials[0];
+        let origins = credential["metadata"]["allowed_origins"]
+            .as_str()
+            .map(serde_json::from_str)
+            .transpose()?
+            .unwrap_or(serde_json::Value::Null);
+        Ok((credential["id"].as_str().unwrap().to_string(), origins))
+    };
+
+    persona(&["add", "alice", "--yes"])?.assert().success();
+    persona(&[
+        "credential",
+        "add",
+        "--identity",
+        "alice",
+        "--name",
+        "GitHub",
+        "--url",
+        "https://github.com",
+        "--secret",
+        "pw",
+    ])?
+    .assert()
+    .success();
+    let (id, origins) = allowed_origins()?;
+    assert!(origins.is_null());
+
+    persona(&[
+        "credential",
+        "set-origins",
+        &id,
+        "--add",
+        "https://GitHub.community/login",
+        "--add",
+        "github.community",
+        "--add",
+        "gist.github.com",
+    ])?
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("https://github.com (URL)"))
+    .stdout(predicate::str::contains("github.community"));
+    assert_eq!(
+        allowed_origins()?.1,
+        serde_json::json!(["github.community", "gist.github.com"])
+    );
+
+    persona(&[
+        "credential",
+        "set-origins",
+        &id,
+        "--remove",
+        "gist.github.com",
+    ])?
+    .assert()
+    .success();
+    assert_eq!(
+        allowed_origins()?.1,
+        serde_json::json!(["github.community"])
+    );
+
+    // A public suffix would match every site under it
+    for suffix in ["com", "https://co.uk", "github.io"] {
+        persona(&["credential", "set-origins", &id, "--add", suffix])?
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("public suffix"));
+    }
+    assert_eq!(
+        allowed_origins()?.1,
+        serde_json::json!(["github.community"])
+    );
+
+    persona(&["credential", "set-origins", &id, "--clear"])?
+        .assert()
+        .success();
+    assert!(allowed_origins()?.1.is_null());
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! Embeds the git revision and enabled features for `persona_core::build_info()`.
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=PERSONA_GIT_HASH");
+    // Packagers building from a source tarball can supply the revision themselves
+    let git_hash = std::env::var("PERSONA_GIT_HASH")
+        .ok()
+        .filter(|hash| !hash.is_empty())
+        .or_else(git_revision)
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=PERSONA_GIT_HASH={}", git_hash);
+
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(str::to_string))
+        .map(|feature| feature.to_lowercase().replace('_', "-"))
+        .filter(|feature| feature != "default")
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=PERSONA_FEATURES={}", features.join(","));
+}
+
+/// Short hash of HEAD
+fn git_revision() -> Option<String> {
+    let git_dir = git(&["rev-parse", "--git-dir"])?;
+    let git_dir = Path::new(&git_dir);
+    // Rebuild when HEAD moves, whether by checkout or by commit
+    for file in ["HEAD", "packed-refs"] {
+        let path = git_dir.join(file);
+        if path.exists() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+    }
+    if let Some(head_ref) = git(&["symbolic-ref", "-q", "HEAD"]) {
+        let path = git_dir.join(head_ref);
+        if path.exists() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+    }
+
+    git(&["rev-parse", "--short=12", "HEAD"])
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
@@ -0,0 +1,56 @@
+//! Registering a custom credential type and formatting it through the registry.
+//!
+//! Run with `cargo run -p persona-core --example custom_credential_type`.
+
+use persona_core::*;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let db = Database::in_memory().await?;
+    db.migrate().await?;
+
+    let mut service = PersonaService::new(db).await?;
+    let salt = service.generate_salt();
+    service.unlock(&SecretString::from("example_master_password"), &salt)?;
+
+    // A Wi-Fi network: the SSID and security mode are safe to show, the passphrase is not.
+    service.register_custom_credential_type(
+        CustomCredentialSchema::new("WifiNetwork")
+            .field("ssid", "Network name")
+            .field("security", "Security")
+            .secret_field("passphrase", "Passphrase")
+            .display_template("{ssid} ({security})"),
+    )?;
+
+    let identity = service
+        .create_identity("Home".to_string(), IdentityType::Personal)
+        .await?;
+    let fields = serde_json::json!({
+        "ssid": "HomeNet",
+        "security": "WPA2",
+        "passphrase": "correct horse battery staple",
+    });
+    let credential = service
+        .create_credential(
+            identity.id,
+            "Home Wi-Fi".to_string(),
+            CredentialType::Custom("WifiNetwork".to_string()),
+            SecurityLevel::Medium,
+            &CredentialData::Raw(serde_json::to_vec(&fields)?),
+        )
+        .await?;
+
+    for reveal in [false, true] {
+        if let Some(view) = service
+            .format_custom_credential(&credential.id, reveal)
+            .await?
+        {
+            println!("{}", view.summary.unwrap_or_default());
+            for field in view.fields {
+                println!("  {}: {}", field.label, field.value);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,3 @@
+-- Row versions for optimistic concurrency: updates must match the version they read
+ALTER TABLE identities ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE credentials ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
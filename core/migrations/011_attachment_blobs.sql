@@ -0,0 +1,11 @@
+-- Content-addressed attachment blobs
+-- Identical attachments share one stored blob, keyed by the SHA-256 of the plaintext.
+-- Each blob is encrypted under its own content key, stored wrapped by the attachment key.
+CREATE TABLE IF NOT EXISTS attachment_blobs (
+    content_hash TEXT PRIMARY KEY,
+    size INTEGER NOT NULL,
+    storage_path TEXT NOT NULL,
+    wrapped_key BLOB,
+    ref_count INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL
+);
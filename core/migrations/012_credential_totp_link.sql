@@ -0,0 +1,4 @@
+-- Optional link from a password credential to the TOTP credential protecting the same login.
+-- Deleting the TOTP credential clears the link.
+ALTER TABLE credentials ADD COLUMN linked_totp_id TEXT REFERENCES credentials(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_credentials_linked_totp ON credentials(linked_totp_id);
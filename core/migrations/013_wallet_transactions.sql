@@ -0,0 +1,16 @@
+-- Migration: Wallet transaction history
+-- Description: Record each status transition of a wallet transaction (created -> signed -> broadcast -> confirmed/failed)
+
+CREATE TABLE IF NOT EXISTS wallet_transactions (
+    id TEXT PRIMARY KEY NOT NULL,
+    wallet_id TEXT NOT NULL,
+    request_id TEXT NOT NULL,
+    status TEXT NOT NULL CHECK(status IN ('created', 'signed', 'broadcast', 'confirmed', 'failed')),
+    transaction_hash TEXT,
+    detail TEXT,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (wallet_id) REFERENCES crypto_wallets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_transactions_wallet_id ON wallet_transactions(wallet_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_wallet_transactions_request_id ON wallet_transactions(request_id);
@@ -0,0 +1,15 @@
+-- Migration: Address book
+-- Description: Labeled recipient addresses for wallet transactions
+
+CREATE TABLE IF NOT EXISTS address_book (
+    id TEXT PRIMARY KEY NOT NULL,
+    label TEXT NOT NULL UNIQUE COLLATE NOCASE CHECK(length(trim(label)) > 0),
+    address TEXT NOT NULL CHECK(length(trim(address)) > 0),
+    network TEXT NOT NULL,
+    notes TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_address_book_address ON address_book(address);
+CREATE INDEX IF NOT EXISTS idx_address_book_network ON address_book(network);
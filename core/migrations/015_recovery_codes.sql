@@ -0,0 +1,9 @@
+-- Migration: Recovery codes
+-- Description: One-time recovery codes and a vault key that survives master password resets
+
+-- JSON list of code hashes, each with a copy of the vault key wrapped under that code
+ALTER TABLE user_auth ADD COLUMN recovery_codes TEXT NOT NULL DEFAULT '[]';
+
+-- Vault key wrapped under the current master password. NULL means the vault key is derived
+-- directly from the master password, as it is until the password is first reset.
+ALTER TABLE user_auth ADD COLUMN wrapped_vault_key TEXT;
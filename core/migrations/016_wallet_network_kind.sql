@@ -0,0 +1,8 @@
+-- Migration: Wallet network kind
+-- Description: Track whether a wallet lives on mainnet, a testnet or regtest
+
+ALTER TABLE crypto_wallets ADD COLUMN network_kind TEXT NOT NULL DEFAULT 'mainnet'
+    CHECK(network_kind IN ('mainnet', 'testnet', 'regtest'));
+
+-- EIP-155 chain id an EVM transaction request is bound to
+ALTER TABLE transaction_requests ADD COLUMN chain_id INTEGER;
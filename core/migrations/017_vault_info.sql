@@ -0,0 +1,12 @@
+-- Migration: Vault info
+-- Description: Per-vault installation id, from which the non-secret vault fingerprint is derived
+
+-- Single row; the id is random and never changes for the life of the vault
+CREATE TABLE IF NOT EXISTS vault_info (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    installation_id TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+INSERT OR IGNORE INTO vault_info (id, installation_id, created_at)
+VALUES (1, lower(hex(randomblob(16))), strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
@@ -0,0 +1,6 @@
+-- Migration: Credential trash
+-- Description: Deleting a credential moves it to the trash; purging the trash removes it for good
+
+-- Set (with is_active = 0) when the credential is trashed, cleared on restore
+ALTER TABLE credentials ADD COLUMN deleted_at TEXT;
+CREATE INDEX IF NOT EXISTS idx_credentials_deleted_at ON credentials(deleted_at);
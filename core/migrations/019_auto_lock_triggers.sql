@@ -0,0 +1,5 @@
+-- Migration: Auto-lock system triggers
+-- Description: Per-policy switches for locking on system sleep/resume and OS screen lock
+
+ALTER TABLE auto_lock_policies ADD COLUMN lock_on_sleep INTEGER NOT NULL DEFAULT 1 CHECK (lock_on_sleep IN (0, 1));
+ALTER TABLE auto_lock_policies ADD COLUMN lock_on_screen_lock INTEGER NOT NULL DEFAULT 1 CHECK (lock_on_screen_lock IN (0, 1));
@@ -0,0 +1,4 @@
+-- Migration: User roles
+-- Description: Per-user role for shared vaults; existing users own their vault
+
+ALTER TABLE user_auth ADD COLUMN role TEXT NOT NULL DEFAULT 'owner' CHECK (role IN ('owner', 'editor', 'viewer'));
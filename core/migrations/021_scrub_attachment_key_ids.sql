@@ -0,0 +1,6 @@
+-- Migration: Scrub attachment key ids
+-- Description: Early builds recorded the first 16 bytes of the vault key, hex-encoded, as an
+-- encrypted attachment's key id. Clear those ids; the hashed id is filled in on next unlock.
+
+UPDATE attachments SET encryption_key_id = NULL
+WHERE is_encrypted = 1 AND length(encryption_key_id) = 32;
@@ -1,9 +1,14 @@
-use crate::crypto::{EncryptionService, PasswordHasher};
+use crate::auth::permissions::Role;
+use crate::auth::recovery_codes::RecoveryCode;
+use crate::crypto::{EncryptionService, PasswordHashParams, PasswordHasher};
 use crate::{PersonaError, Result};
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
+/// Failed master password attempts allowed before the account is locked
+pub const MAX_FAILED_ATTEMPTS: u32 = 5;
+
 /// Authentication factor types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum AuthFactor {
@@ -55,6 +60,18 @@ pub struct UserAuth {
     /// Password change required
     pub password_change_required: bool,
 
+    /// One-time recovery codes (hashes only)
+    #[serde(default)]
+    pub recovery_codes: Vec<RecoveryCode>,
+
+    /// Hex vault key wrapped under the master password key, once the password has been reset
+    #[serde(default)]
+    pub wrapped_vault_key: Option<String>,
+
+    /// Role in a shared vault
+    #[serde(default)]
+    pub role: Role,
+
     /// Creation timestamp
     pub created_at: SystemTime,
 
@@ -75,6 +92,9 @@ impl UserAuth {
             locked_until: None,
             last_auth: None,
             password_change_required: false,
+            recovery_codes: Vec::new(),
+            wrapped_vault_key: None,
+            role: Role::Owner,
             created_at: now,
             updated_at: now,
         }
@@ -82,7 +102,16 @@ impl UserAuth {
 
     /// Set master password and generate salt if needed
     pub fn set_master_password(&mut self, password: &str) -> Result<()> {
-        let hasher = PasswordHasher::new();
+        self.set_master_password_with_params(password, PasswordHashParams::default())
+    }
+
+    /// Set master password hashed with the given Argon2 parameters
+    pub fn set_master_password_with_params(
+        &mut self,
+        password: &str,
+        params: PasswordHashParams,
+    ) -> Result<()> {
+        let hasher = PasswordHasher::from_params(params)?;
         let hash = hasher.hash_password(password)?;
         self.master_password_hash = Some(hash);
 
@@ -93,7 +122,7 @@ impl UserAuth {
             self.master_key_salt = Some(hex::encode(salt));
         }
 
-        self.enabled_factors.push(AuthFactor::MasterPassword);
+        self.enable_factor(AuthFactor::MasterPassword);
         self.password_change_required = false;
         self.updated_at = SystemTime::now();
         Ok(())
@@ -144,13 +173,24 @@ impl UserAuth {
         self.failed_attempts += 1;
         self.updated_at = SystemTime::now();
 
-        // Lock account after 5 failed attempts
-        if self.failed_attempts >= 5 {
+        // Lock account after too many failed attempts
+        if self.failed_attempts >= MAX_FAILED_ATTEMPTS {
             self.locked_until = Some(SystemTime::now() + Duration::from_secs(300));
             // 5 minutes
         }
     }
 
+    /// Failed attempts left before the account is locked
+    pub fn remaining_attempts(&self) -> u32 {
+        if self.is_locked() {
+            return 0;
+        }
+        // Once a lockout has expired the next failure locks again
+        MAX_FAILED_ATTEMPTS
+            .saturating_sub(self.failed_attempts)
+            .max(1)
+    }
+
     /// Reset failed attempts (called on successful auth)
     pub fn reset_failed_attempts(&mut self) {
         self.failed_attempts = 0;
@@ -177,6 +217,21 @@ impl UserAuth {
     pub fn has_factor(&self, factor: &AuthFactor) -> bool {
         self.enabled_factors.contains(factor)
     }
+
+    /// Index of the unused recovery code matching `code`
+    pub fn find_recovery_code(&self, code: &str) -> Option<usize> {
+        self.recovery_codes
+            .iter()
+            .position(|stored| stored.matches(code))
+    }
+
+    /// Recovery codes that have not been used yet
+    pub fn unused_recovery_codes(&self) -> usize {
+        self.recovery_codes
+            .iter()
+            .filter(|code| code.used_at.is_none())
+            .count()
+    }
 }
 
 /// Master key derivation service
@@ -251,16 +306,15 @@ impl AuthService {
             return Ok(AuthResult::AccountLocked);
         }
 
-        // Check if password change is required
-        if user_auth.password_change_required {
-            return Ok(AuthResult::PasswordChangeRequired);
-        }
-
         // Verify password
         let valid = user_auth.verify_master_password(password)?;
 
         if valid {
             user_auth.reset_failed_attempts();
+            // A correct password does not unlock while a reset is pending
+            if user_auth.password_change_required {
+                return Ok(AuthResult::PasswordChangeRequired);
+            }
             Ok(AuthResult::Success)
         } else {
             user_auth.add_failed_attempt();
@@ -1,8 +1,23 @@
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{PersonaError, Result};
 
+#[cfg(all(feature = "platform-biometrics", target_os = "linux"))]
+mod polkit;
+#[cfg(all(feature = "platform-biometrics", target_os = "macos"))]
+mod touch_id;
+#[cfg(all(feature = "platform-biometrics", windows))]
+mod windows_hello;
+
+#[cfg(all(feature = "platform-biometrics", target_os = "linux"))]
+pub use polkit::PolkitProvider;
+#[cfg(all(feature = "platform-biometrics", target_os = "macos"))]
+pub use touch_id::TouchIdProvider;
+#[cfg(all(feature = "platform-biometrics", windows))]
+pub use windows_hello::WindowsHelloProvider;
+
 /// Supported biometric platforms (abstracted to keep the core crate cross-platform).
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum BiometricPlatform {
@@ -10,9 +25,26 @@ pub enum BiometricPlatform {
     FaceId,
     WindowsHello,
     LinuxSecretService,
+    /// polkit authentication dialog; biometric when PAM is set up for fprintd
+    Polkit,
     Unknown,
 }
 
+impl BiometricPlatform {
+    /// The platform a real provider would use on this OS, if any
+    pub fn current() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            Some(BiometricPlatform::TouchId)
+        } else if cfg!(windows) {
+            Some(BiometricPlatform::WindowsHello)
+        } else if cfg!(target_os = "linux") {
+            Some(BiometricPlatform::Polkit)
+        } else {
+            None
+        }
+    }
+}
+
 /// Client-provided hint for biometric unlock.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BiometricPrompt {
@@ -27,9 +59,35 @@ pub struct BiometricAuthResult {
     pub user_id: Uuid,
     pub verified: bool,
     pub platform: BiometricPlatform,
+    /// Why verification failed (cancelled, not recognised, locked out, ...); `None` when verified
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl BiometricAuthResult {
+    pub fn verified(user_id: Uuid, platform: BiometricPlatform) -> Self {
+        Self {
+            user_id,
+            verified: true,
+            platform,
+            error: None,
+        }
+    }
+
+    pub fn rejected(user_id: Uuid, platform: BiometricPlatform, reason: impl Into<String>) -> Self {
+        Self {
+            user_id,
+            verified: false,
+            platform,
+            error: Some(reason.into()),
+        }
+    }
 }
 
 /// Abstraction for platform-specific biometric unlock hooks.
+///
+/// `authenticate` returns `Ok` with `verified: false` when the user fails or cancels the
+/// ceremony, and `Err` when it could not be run at all.
 pub trait BiometricProvider: Send + Sync {
     /// Whether biometric hardware/OS APIs are available.
     fn is_available(&self, hint: Option<BiometricPlatform>) -> bool;
@@ -38,6 +96,24 @@ pub trait BiometricProvider: Send + Sync {
     fn authenticate(&self, prompt: &BiometricPrompt) -> Result<BiometricAuthResult>;
 }
 
+/// The real provider for this OS when it was built with `platform-biometrics` and the
+/// hardware/OS service is usable right now; `None` otherwise, so callers can fall back.
+pub fn platform_biometric_provider() -> Option<Arc<dyn BiometricProvider>> {
+    #[cfg(all(feature = "platform-biometrics", target_os = "macos"))]
+    let provider: Option<Arc<dyn BiometricProvider>> = Some(Arc::new(TouchIdProvider::new()));
+    #[cfg(all(feature = "platform-biometrics", windows))]
+    let provider: Option<Arc<dyn BiometricProvider>> = Some(Arc::new(WindowsHelloProvider::new()));
+    #[cfg(all(feature = "platform-biometrics", target_os = "linux"))]
+    let provider: Option<Arc<dyn BiometricProvider>> = Some(Arc::new(PolkitProvider::default()));
+    #[cfg(not(all(
+        feature = "platform-biometrics",
+        any(target_os = "macos", windows, target_os = "linux")
+    )))]
+    let provider: Option<Arc<dyn BiometricProvider>> = None;
+
+    provider.filter(|provider| provider.is_available(None))
+}
+
 /// In-memory mock that simulates biometric success/failure.
 #[derive(Debug, Clone)]
 pub struct MockBiometricProvider {
@@ -78,11 +154,10 @@ impl BiometricProvider for MockBiometricProvider {
             .into());
         }
 
-        Ok(BiometricAuthResult {
-            user_id: prompt.user_id,
-            verified: true,
-            platform: prompt.platform.unwrap_or(self.platform),
-        })
+        Ok(BiometricAuthResult::verified(
+            prompt.user_id,
+            prompt.platform.unwrap_or(self.platform),
+        ))
     }
 }
 
@@ -132,4 +207,22 @@ mod tests {
         let err = provider.authenticate(&prompt).unwrap_err();
         assert!(err.to_string().contains("Biometric verification failed"));
     }
+
+    #[test]
+    fn rejected_result_carries_reason() {
+        let result =
+            BiometricAuthResult::rejected(Uuid::new_v4(), BiometricPlatform::Polkit, "cancelled");
+        assert!(!result.verified);
+        assert_eq!(result.error.as_deref(), Some("cancelled"));
+
+        // Results serialized before `error` existed still deserialize
+        let json = serde_json::json!({
+            "user_id": Uuid::new_v4(),
+            "verified": true,
+            "platform": "TouchId",
+        });
+        let result: BiometricAuthResult = serde_json::from_value(json).unwrap();
+        assert!(result.verified);
+        assert!(result.error.is_none());
+    }
 }
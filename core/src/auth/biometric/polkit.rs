@@ -0,0 +1,90 @@
+//! polkit authentication through `pkcheck`.
+//!
+//! polkit shows the desktop's authentication agent, which asks for a fingerprint when PAM is
+//! set up for fprintd and for the user's password otherwise. The action has to be installed
+//! (see `docs/polkit/com.persona.authenticate.policy`) or polkit rejects every check.
+
+use std::path::Path;
+use std::process::Command;
+
+use super::{BiometricAuthResult, BiometricPlatform, BiometricPrompt, BiometricProvider};
+use crate::{PersonaError, Result};
+
+/// Authenticates the current process against a polkit action
+#[derive(Debug, Clone)]
+pub struct PolkitProvider {
+    action_id: String,
+}
+
+impl PolkitProvider {
+    /// Action shipped in `docs/polkit/com.persona.authenticate.policy`
+    pub const DEFAULT_ACTION_ID: &'static str = "com.persona.authenticate";
+
+    pub fn new(action_id: impl Into<String>) -> Self {
+        Self {
+            action_id: action_id.into(),
+        }
+    }
+}
+
+impl Default for PolkitProvider {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_ACTION_ID)
+    }
+}
+
+impl BiometricProvider for PolkitProvider {
+    fn is_available(&self, hint: Option<BiometricPlatform>) -> bool {
+        let platform_matches = matches!(
+            hint,
+            None | Some(BiometricPlatform::Polkit | BiometricPlatform::LinuxSecretService)
+        );
+        platform_matches
+            && Path::new("/usr/share/polkit-1/actions")
+                .join(format!("{}.policy", self.action_id))
+                .exists()
+            && find_pkcheck()
+    }
+
+    fn authenticate(&self, prompt: &BiometricPrompt) -> Result<BiometricAuthResult> {
+        if !self.is_available(prompt.platform) {
+            return Err(
+                PersonaError::AuthenticationFailed("Biometric unavailable".to_string()).into(),
+            );
+        }
+
+        let output = Command::new("pkcheck")
+            .arg("--action-id")
+            .arg(&self.action_id)
+            .arg("--process")
+            .arg(std::process::id().to_string())
+            .arg("--allow-user-interaction")
+            .output()
+            .map_err(|e| PersonaError::AuthenticationFailed(format!("pkcheck failed: {}", e)))?;
+
+        let platform = BiometricPlatform::Polkit;
+        match output.status.code() {
+            Some(0) => Ok(BiometricAuthResult::verified(prompt.user_id, platform)),
+            Some(1) => Ok(BiometricAuthResult::rejected(
+                prompt.user_id,
+                platform,
+                "Not authorized",
+            )),
+            Some(2) => Ok(BiometricAuthResult::rejected(
+                prompt.user_id,
+                platform,
+                "Authentication dialog dismissed",
+            )),
+            _ => Err(PersonaError::AuthenticationFailed(format!(
+                "pkcheck failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+            .into()),
+        }
+    }
+}
+
+fn find_pkcheck() -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join("pkcheck").is_file()))
+}
@@ -0,0 +1,215 @@
+//! Touch ID through LocalAuthentication's `LAContext`, driven with raw Objective-C messages so
+//! the core needs no Objective-C bindings crate.
+
+use std::ffi::{c_char, c_int, c_long, c_ulong, c_void, CStr};
+use std::sync::mpsc;
+
+use core_foundation_sys::base::CFRelease;
+use core_foundation_sys::string::{
+    kCFStringEncodingUTF8, CFStringCreateWithCString, CFStringGetCString, CFStringRef,
+};
+
+use super::{BiometricAuthResult, BiometricPlatform, BiometricPrompt, BiometricProvider};
+use crate::{PersonaError, Result};
+
+type Id = *mut c_void;
+type Sel = *const c_void;
+
+#[repr(C)]
+struct Opaque {
+    _private: [u8; 0],
+}
+
+#[link(name = "LocalAuthentication", kind = "framework")]
+extern "C" {}
+
+#[link(name = "objc")]
+extern "C" {
+    fn objc_getClass(name: *const c_char) -> Id;
+    fn sel_registerName(name: *const c_char) -> Sel;
+    fn objc_msgSend();
+}
+
+extern "C" {
+    static _NSConcreteStackBlock: Opaque;
+}
+
+/// `LAPolicyDeviceOwnerAuthenticationWithBiometrics`
+const BIOMETRICS_POLICY: c_long = 1;
+
+/// `(success, (code, description) on failure)` sent from the reply block
+type Reply = (bool, Option<(c_long, String)>);
+
+/// Block literal layout for `void (^)(BOOL success, NSError *error)`, capturing the reply sender
+#[repr(C)]
+struct ReplyBlock {
+    isa: *const Opaque,
+    flags: c_int,
+    reserved: c_int,
+    invoke: unsafe extern "C" fn(*mut ReplyBlock, bool, Id),
+    descriptor: *const BlockDescriptor,
+    reply: *const mpsc::Sender<Reply>,
+}
+
+#[repr(C)]
+struct BlockDescriptor {
+    reserved: c_ulong,
+    size: c_ulong,
+}
+
+static REPLY_BLOCK_DESCRIPTOR: BlockDescriptor = BlockDescriptor {
+    reserved: 0,
+    size: std::mem::size_of::<ReplyBlock>() as c_ulong,
+};
+
+/// Verifies the user with Touch ID
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TouchIdProvider;
+
+impl TouchIdProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl BiometricProvider for TouchIdProvider {
+    fn is_available(&self, hint: Option<BiometricPlatform>) -> bool {
+        matches!(hint, None | Some(BiometricPlatform::TouchId))
+            // SAFETY: a fresh LAContext, released before returning
+            && unsafe {
+                let context = new_context();
+                if context.is_null() {
+                    return false;
+                }
+                let available = can_evaluate(context);
+                release(context);
+                available
+            }
+    }
+
+    fn authenticate(&self, prompt: &BiometricPrompt) -> Result<BiometricAuthResult> {
+        let platform = BiometricPlatform::TouchId;
+        let (sender, replies) = mpsc::channel::<Reply>();
+
+        // SAFETY: LAContext copies the block; the copy keeps a pointer to `sender`, which outlives
+        // the single reply because we block on `replies` below.
+        unsafe {
+            let context = new_context();
+            if context.is_null() || !can_evaluate(context) {
+                if !context.is_null() {
+                    release(context);
+                }
+                return Err(PersonaError::AuthenticationFailed(
+                    "Biometric unavailable".to_string(),
+                )
+                .into());
+            }
+
+            let reason = cf_string(&prompt.reason);
+            let mut block = ReplyBlock {
+                isa: &_NSConcreteStackBlock,
+                flags: 0,
+                reserved: 0,
+                invoke: on_reply,
+                descriptor: &REPLY_BLOCK_DESCRIPTOR,
+                reply: &sender,
+            };
+            let evaluate: unsafe extern "C" fn(Id, Sel, c_long, Id, *mut ReplyBlock) =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            evaluate(
+                context,
+                selector(c"evaluatePolicy:localizedReason:reply:"),
+                BIOMETRICS_POLICY,
+                reason as Id,
+                &mut block,
+            );
+            CFRelease(reason as *const c_void);
+
+            let reply = replies.recv();
+            release(context);
+            match reply {
+                Ok((true, _)) => Ok(BiometricAuthResult::verified(prompt.user_id, platform)),
+                Ok((false, error)) => Ok(BiometricAuthResult::rejected(
+                    prompt.user_id,
+                    platform,
+                    error
+                        .map(|(code, description)| format!("{} ({})", description, code))
+                        .unwrap_or_else(|| "Verification failed".to_string()),
+                )),
+                Err(_) => Err(PersonaError::AuthenticationFailed(
+                    "Touch ID did not reply".to_string(),
+                )
+                .into()),
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn on_reply(block: *mut ReplyBlock, success: bool, error: Id) {
+    let error = (!success && !error.is_null()).then(|| {
+        let code: unsafe extern "C" fn(Id, Sel) -> c_long =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+        let description = send(error, c"localizedDescription") as CFStringRef;
+        (code(error, selector(c"code")), rust_string(description))
+    });
+    let _ = (*(*block).reply).send((success, error));
+}
+
+unsafe fn selector(name: &CStr) -> Sel {
+    sel_registerName(name.as_ptr())
+}
+
+/// Send a message that takes no arguments and returns an object
+unsafe fn send(receiver: Id, name: &CStr) -> Id {
+    let send: unsafe extern "C" fn(Id, Sel) -> Id =
+        std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+    send(receiver, selector(name))
+}
+
+/// `[[LAContext alloc] init]`, or null when the framework is missing
+unsafe fn new_context() -> Id {
+    let class = objc_getClass(c"LAContext".as_ptr());
+    if class.is_null() {
+        return std::ptr::null_mut();
+    }
+    send(send(class, c"alloc"), c"init")
+}
+
+unsafe fn release(object: Id) {
+    send(object, c"release");
+}
+
+unsafe fn can_evaluate(context: Id) -> bool {
+    let can_evaluate: unsafe extern "C" fn(Id, Sel, c_long, *mut Id) -> bool =
+        std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+    can_evaluate(
+        context,
+        selector(c"canEvaluatePolicy:error:"),
+        BIOMETRICS_POLICY,
+        std::ptr::null_mut(),
+    )
+}
+
+unsafe fn cf_string(value: &str) -> CFStringRef {
+    let value = std::ffi::CString::new(value.replace('\0', "")).unwrap_or_default();
+    CFStringCreateWithCString(std::ptr::null(), value.as_ptr(), kCFStringEncodingUTF8)
+}
+
+unsafe fn rust_string(value: CFStringRef) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+    let mut buffer = [0 as c_char; 512];
+    if CFStringGetCString(
+        value,
+        buffer.as_mut_ptr(),
+        buffer.len() as _,
+        kCFStringEncodingUTF8,
+    ) == 0
+    {
+        return String::new();
+    }
+    CStr::from_ptr(buffer.as_ptr())
+        .to_string_lossy()
+        .into_owned()
+}
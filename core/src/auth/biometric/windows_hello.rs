@@ -0,0 +1,61 @@
+//! Windows Hello through the WinRT `UserConsentVerifier`.
+
+use windows::core::HSTRING;
+use windows::Security::Credentials::UI::{
+    UserConsentVerificationResult, UserConsentVerifier, UserConsentVerifierAvailability,
+};
+
+use super::{BiometricAuthResult, BiometricPlatform, BiometricPrompt, BiometricProvider};
+use crate::{PersonaError, Result};
+
+/// Verifies the user with Windows Hello (fingerprint, face or PIN)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WindowsHelloProvider;
+
+impl WindowsHelloProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl BiometricProvider for WindowsHelloProvider {
+    fn is_available(&self, hint: Option<BiometricPlatform>) -> bool {
+        matches!(hint, None | Some(BiometricPlatform::WindowsHello))
+            && UserConsentVerifier::CheckAvailabilityAsync()
+                .and_then(|operation| operation.get())
+                .is_ok_and(|availability| {
+                    availability == UserConsentVerifierAvailability::Available
+                })
+    }
+
+    fn authenticate(&self, prompt: &BiometricPrompt) -> Result<BiometricAuthResult> {
+        let platform = BiometricPlatform::WindowsHello;
+        let result = UserConsentVerifier::RequestVerificationAsync(&HSTRING::from(&prompt.reason))
+            .and_then(|operation| operation.get())
+            .map_err(|e| {
+                PersonaError::AuthenticationFailed(format!("Windows Hello failed: {}", e))
+            })?;
+
+        let reason = match result {
+            UserConsentVerificationResult::Verified => {
+                return Ok(BiometricAuthResult::verified(prompt.user_id, platform))
+            }
+            UserConsentVerificationResult::Canceled => "Verification cancelled",
+            UserConsentVerificationResult::RetriesExhausted => "Too many failed attempts",
+            UserConsentVerificationResult::DeviceBusy => "Biometric device is busy",
+            UserConsentVerificationResult::DeviceNotPresent
+            | UserConsentVerificationResult::NotConfiguredForUser
+            | UserConsentVerificationResult::DisabledByPolicy => {
+                return Err(
+                    PersonaError::AuthenticationFailed("Biometric unavailable".to_string()).into(),
+                )
+            }
+            _ => "Verification failed",
+        };
+        Ok(BiometricAuthResult::rejected(
+            prompt.user_id,
+            platform,
+            reason,
+        ))
+    }
+}
@@ -0,0 +1,189 @@
+//! System events that lock the vault regardless of idle time.
+//!
+//! A [`LockTrigger`] reports sleep, resume and screen-lock events, and the auto-lock policy
+//! decides which of them lock the vault (see [`LockTriggerSettings`]). [`PlatformLockTrigger`]
+//! listens to logind on Linux, IOKit and the distributed notification center on macOS, and
+//! session/power broadcasts on Windows when the `system-lock-triggers` feature is enabled;
+//! everywhere else it reports nothing.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+
+use crate::models::auto_lock_policy::AutoLockPolicy;
+
+#[cfg(all(feature = "system-lock-triggers", target_os = "linux"))]
+mod linux;
+#[cfg(all(feature = "system-lock-triggers", target_os = "macos"))]
+mod macos;
+#[cfg(all(feature = "system-lock-triggers", windows))]
+mod windows;
+
+/// A system event that may lock the vault
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemLockEvent {
+    /// The system is about to sleep
+    Sleep,
+    /// The system woke up; covers sleeps whose notice arrived too late to act on
+    Resume,
+    /// The OS locked the screen
+    ScreenLocked,
+}
+
+impl SystemLockEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SystemLockEvent::Sleep => "sleep",
+            SystemLockEvent::Resume => "resume",
+            SystemLockEvent::ScreenLocked => "screen_locked",
+        }
+    }
+}
+
+impl std::fmt::Display for SystemLockEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Receiving end of a trigger subscription
+pub type LockEventReceiver = mpsc::UnboundedReceiver<SystemLockEvent>;
+
+/// A source of system lock events
+pub trait LockTrigger: Send + Sync {
+    /// Start listening. The receiver closes when the source stops; a source that can't run here
+    /// returns a receiver that is already closed.
+    fn subscribe(&self) -> LockEventReceiver;
+}
+
+/// Which system events lock the vault, taken from the auto-lock policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockTriggerSettings {
+    pub on_sleep: bool,
+    pub on_screen_lock: bool,
+}
+
+impl Default for LockTriggerSettings {
+    fn default() -> Self {
+        Self {
+            on_sleep: true,
+            on_screen_lock: true,
+        }
+    }
+}
+
+impl From<&AutoLockPolicy> for LockTriggerSettings {
+    fn from(policy: &AutoLockPolicy) -> Self {
+        Self {
+            on_sleep: policy.lock_on_sleep,
+            on_screen_lock: policy.lock_on_screen_lock,
+        }
+    }
+}
+
+impl LockTriggerSettings {
+    /// Whether `event` should lock the vault
+    pub fn locks_on(&self, event: SystemLockEvent) -> bool {
+        match event {
+            SystemLockEvent::Sleep | SystemLockEvent::Resume => self.on_sleep,
+            SystemLockEvent::ScreenLocked => self.on_screen_lock,
+        }
+    }
+}
+
+/// Sleep and screen-lock notifications from the current platform
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PlatformLockTrigger;
+
+impl PlatformLockTrigger {
+    /// Whether this build can observe system events on this platform
+    pub fn is_supported() -> bool {
+        cfg!(all(
+            feature = "system-lock-triggers",
+            any(target_os = "linux", target_os = "macos", windows)
+        ))
+    }
+}
+
+impl LockTrigger for PlatformLockTrigger {
+    fn subscribe(&self) -> LockEventReceiver {
+        #[allow(unused_variables)]
+        let (events, receiver) = mpsc::unbounded_channel();
+        #[cfg(all(feature = "system-lock-triggers", target_os = "linux"))]
+        linux::watch(events);
+        #[cfg(all(feature = "system-lock-triggers", target_os = "macos"))]
+        macos::watch(events);
+        #[cfg(all(feature = "system-lock-triggers", windows))]
+        windows::watch(events);
+        receiver
+    }
+}
+
+/// A trigger fired by hand, for exercising lock handling in tests
+#[derive(Debug, Default)]
+pub struct MockLockTrigger {
+    subscribers: Mutex<Vec<mpsc::UnboundedSender<SystemLockEvent>>>,
+}
+
+impl MockLockTrigger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deliver `event` to every live subscription
+    pub fn fire(&self, event: SystemLockEvent) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.send(event).is_ok());
+    }
+}
+
+impl LockTrigger for MockLockTrigger {
+    fn subscribe(&self) -> LockEventReceiver {
+        let (events, receiver) = mpsc::unbounded_channel();
+        self.subscribers.lock().unwrap().push(events);
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::auto_lock_policy::AutoLockSecurityLevel;
+
+    #[test]
+    fn settings_follow_policy_switches() {
+        let mut policy = AutoLockPolicy::new("Laptop".to_string(), AutoLockSecurityLevel::Low, 600);
+        policy.lock_on_sleep = true;
+        policy.lock_on_screen_lock = false;
+        let triggers = LockTriggerSettings::from(&policy);
+
+        assert!(triggers.locks_on(SystemLockEvent::Sleep));
+        assert!(triggers.locks_on(SystemLockEvent::Resume));
+        assert!(!triggers.locks_on(SystemLockEvent::ScreenLocked));
+    }
+
+    #[tokio::test]
+    async fn mock_trigger_reaches_every_subscriber() {
+        let trigger = MockLockTrigger::new();
+        let mut first = trigger.subscribe();
+        let mut second = trigger.subscribe();
+
+        trigger.fire(SystemLockEvent::ScreenLocked);
+        assert_eq!(first.recv().await, Some(SystemLockEvent::ScreenLocked));
+        assert_eq!(second.recv().await, Some(SystemLockEvent::ScreenLocked));
+
+        drop(first);
+        trigger.fire(SystemLockEvent::Sleep);
+        assert_eq!(second.recv().await, Some(SystemLockEvent::Sleep));
+    }
+
+    #[cfg(not(feature = "system-lock-triggers"))]
+    #[tokio::test]
+    async fn platform_trigger_is_silent_without_the_feature() {
+        assert!(!PlatformLockTrigger::is_supported());
+        assert_eq!(PlatformLockTrigger.subscribe().recv().await, None);
+    }
+}
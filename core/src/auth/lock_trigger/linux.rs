@@ -0,0 +1,60 @@
+//! logind signals on the system bus: `Manager.PrepareForSleep` and the session's `Lock`.
+
+use futures_util::StreamExt;
+use tokio::sync::mpsc;
+use zbus::zvariant::OwnedObjectPath;
+use zbus::{Connection, Proxy};
+
+use super::SystemLockEvent;
+
+const LOGIND: &str = "org.freedesktop.login1";
+const MANAGER_PATH: &str = "/org/freedesktop/login1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+const SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+
+pub(super) fn watch(events: mpsc::UnboundedSender<SystemLockEvent>) {
+    tokio::spawn(async move {
+        if let Err(e) = forward_signals(&events).await {
+            tracing::warn!("logind lock triggers unavailable: {}", e);
+        }
+    });
+}
+
+async fn forward_signals(events: &mpsc::UnboundedSender<SystemLockEvent>) -> zbus::Result<()> {
+    let connection = Connection::system().await?;
+    let manager = Proxy::new(&connection, LOGIND, MANAGER_PATH, MANAGER_INTERFACE).await?;
+    let mut sleep = manager.receive_signal("PrepareForSleep").await?;
+
+    // Signals are emitted on the session's real path, so resolve "auto" instead of subscribing
+    // to the alias. Without a session (e.g. a system service) only sleep is watched.
+    let session_path: Option<OwnedObjectPath> = match manager.call("GetSession", &("auto",)).await {
+        Ok(path) => Some(path),
+        Err(e) => {
+            tracing::debug!("No logind session for screen-lock events: {}", e);
+            None
+        }
+    };
+    let mut lock = match session_path {
+        Some(path) => {
+            let session = Proxy::new(&connection, LOGIND, path, SESSION_INTERFACE).await?;
+            Some(session.receive_signal("Lock").await?)
+        }
+        None => None,
+    };
+
+    loop {
+        let event = tokio::select! {
+            message = sleep.next() => match message {
+                Some(message) => match message.body().deserialize::<bool>()? {
+                    true => SystemLockEvent::Sleep,
+                    false => SystemLockEvent::Resume,
+                },
+                None => return Ok(()),
+            },
+            Some(_) = async { lock.as_mut()?.next().await } => SystemLockEvent::ScreenLocked,
+        };
+        if events.send(event).is_err() {
+            return Ok(());
+        }
+    }
+}
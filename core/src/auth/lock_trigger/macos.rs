@@ -0,0 +1,137 @@
+//! IOKit system power notifications and the `com.apple.screenIsLocked` distributed
+//! notification, both delivered on a dedicated run-loop thread.
+
+use std::ffi::{c_void, CStr};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use core_foundation_sys::base::CFRelease;
+use core_foundation_sys::dictionary::CFDictionaryRef;
+use core_foundation_sys::notification_center::{
+    CFNotificationCenterAddObserver, CFNotificationCenterGetDistributedCenter,
+    CFNotificationCenterRef, CFNotificationName,
+    CFNotificationSuspensionBehaviorDeliverImmediately,
+};
+use core_foundation_sys::runloop::{
+    kCFRunLoopDefaultMode, CFRunLoopAddSource, CFRunLoopGetCurrent, CFRunLoopRun,
+    CFRunLoopSourceRef,
+};
+use core_foundation_sys::string::{kCFStringEncodingUTF8, CFStringCreateWithCString};
+use tokio::sync::mpsc;
+
+use super::SystemLockEvent;
+
+type IoConnect = u32;
+type IoObject = u32;
+type IoNotificationPortRef = *mut c_void;
+type IoServiceInterestCallback =
+    extern "C" fn(refcon: *mut c_void, service: IoObject, message_type: u32, argument: *mut c_void);
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IORegisterForSystemPower(
+        refcon: *mut c_void,
+        port: *mut IoNotificationPortRef,
+        callback: IoServiceInterestCallback,
+        notifier: *mut IoObject,
+    ) -> IoConnect;
+    fn IONotificationPortGetRunLoopSource(port: IoNotificationPortRef) -> CFRunLoopSourceRef;
+    fn IOAllowPowerChange(kernel_port: IoConnect, notification_id: isize) -> i32;
+}
+
+// iokit_common_msg(0x270), (0x280) and (0x300) from IOMessage.h
+const IO_MESSAGE_CAN_SYSTEM_SLEEP: u32 = 0xE000_0270;
+const IO_MESSAGE_SYSTEM_WILL_SLEEP: u32 = 0xE000_0280;
+const IO_MESSAGE_SYSTEM_HAS_POWERED_ON: u32 = 0xE000_0300;
+
+const SCREEN_LOCKED_NOTIFICATION: &CStr = c"com.apple.screenIsLocked";
+
+/// Root power domain connection, needed to acknowledge sleep requests from the callback
+static ROOT_PORT: AtomicU32 = AtomicU32::new(0);
+
+type Sender = mpsc::UnboundedSender<SystemLockEvent>;
+
+pub(super) fn watch(events: Sender) {
+    let spawned = std::thread::Builder::new()
+        .name("persona-lock-triggers".to_string())
+        .spawn(move || {
+            // Leaked on purpose: both callbacks use it for as long as the run loop runs
+            let refcon = Box::into_raw(Box::new(events)) as *mut c_void;
+            // SAFETY: plain IOKit/CoreFoundation registration on this thread's run loop; the
+            // callbacks only read `refcon`, which is never freed.
+            unsafe {
+                let run_loop = CFRunLoopGetCurrent();
+
+                let mut port: IoNotificationPortRef = std::ptr::null_mut();
+                let mut notifier: IoObject = 0;
+                let root =
+                    IORegisterForSystemPower(refcon, &mut port, on_power_message, &mut notifier);
+                if root == 0 {
+                    tracing::warn!(
+                        "IORegisterForSystemPower failed; sleep will not lock the vault"
+                    );
+                } else {
+                    ROOT_PORT.store(root, Ordering::SeqCst);
+                    let source = IONotificationPortGetRunLoopSource(port);
+                    CFRunLoopAddSource(run_loop, source, kCFRunLoopDefaultMode);
+                }
+
+                let name = CFStringCreateWithCString(
+                    std::ptr::null(),
+                    SCREEN_LOCKED_NOTIFICATION.as_ptr(),
+                    kCFStringEncodingUTF8,
+                );
+                CFNotificationCenterAddObserver(
+                    CFNotificationCenterGetDistributedCenter(),
+                    refcon,
+                    on_screen_locked,
+                    name,
+                    std::ptr::null(),
+                    CFNotificationSuspensionBehaviorDeliverImmediately,
+                );
+                CFRelease(name as *const c_void);
+
+                CFRunLoopRun();
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::warn!("Failed to start lock trigger thread: {}", e);
+    }
+}
+
+fn send(refcon: *const c_void, event: SystemLockEvent) {
+    // SAFETY: `refcon` is the leaked `Sender` from `watch`
+    let events = unsafe { &*(refcon as *const Sender) };
+    let _ = events.send(event);
+}
+
+extern "C" fn on_power_message(
+    refcon: *mut c_void,
+    _service: IoObject,
+    message_type: u32,
+    argument: *mut c_void,
+) {
+    match message_type {
+        // Sleep must be acknowledged or the system waits out a timeout before sleeping
+        IO_MESSAGE_CAN_SYSTEM_SLEEP => unsafe {
+            IOAllowPowerChange(ROOT_PORT.load(Ordering::SeqCst), argument as isize);
+        },
+        IO_MESSAGE_SYSTEM_WILL_SLEEP => {
+            send(refcon, SystemLockEvent::Sleep);
+            unsafe {
+                IOAllowPowerChange(ROOT_PORT.load(Ordering::SeqCst), argument as isize);
+            }
+        }
+        IO_MESSAGE_SYSTEM_HAS_POWERED_ON => send(refcon, SystemLockEvent::Resume),
+        _ => {}
+    }
+}
+
+extern "C" fn on_screen_locked(
+    _center: CFNotificationCenterRef,
+    observer: *mut c_void,
+    _name: CFNotificationName,
+    _object: *const c_void,
+    _user_info: CFDictionaryRef,
+) {
+    send(observer, SystemLockEvent::ScreenLocked);
+}
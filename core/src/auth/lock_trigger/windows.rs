@@ -0,0 +1,109 @@
+//! Session-change and power broadcasts, received by a hidden window on a dedicated thread.
+//!
+//! `WM_POWERBROADCAST` only reaches top-level windows, so this is a real (never shown) window
+//! rather than a message-only one.
+
+use std::cell::RefCell;
+
+use tokio::sync::mpsc;
+use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows_sys::Win32::System::RemoteDesktop::{
+    WTSRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW,
+    TranslateMessage, MSG, PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND, WM_POWERBROADCAST,
+    WM_WTSSESSION_CHANGE, WNDCLASSW, WTS_SESSION_LOCK,
+};
+
+use super::SystemLockEvent;
+
+thread_local! {
+    /// Where the window procedure sends events; set before the window exists
+    static EVENTS: RefCell<Option<mpsc::UnboundedSender<SystemLockEvent>>> =
+        const { RefCell::new(None) };
+}
+
+pub(super) fn watch(events: mpsc::UnboundedSender<SystemLockEvent>) {
+    let spawned = std::thread::Builder::new()
+        .name("persona-lock-triggers".to_string())
+        .spawn(move || {
+            EVENTS.with(|slot| *slot.borrow_mut() = Some(events));
+            if let Err(e) = run_message_loop() {
+                tracing::warn!("Windows lock triggers unavailable: {}", e);
+            }
+            EVENTS.with(|slot| slot.borrow_mut().take());
+        });
+    if let Err(e) = spawned {
+        tracing::warn!("Failed to start lock trigger thread: {}", e);
+    }
+}
+
+fn run_message_loop() -> Result<(), String> {
+    let class_name: Vec<u16> = "PersonaLockTriggers\0".encode_utf16().collect();
+    // SAFETY: standard window class registration and message pump on this thread; the window
+    // and class live until the thread exits.
+    unsafe {
+        let instance = GetModuleHandleW(std::ptr::null());
+        let mut class: WNDCLASSW = std::mem::zeroed();
+        class.lpfnWndProc = Some(window_proc);
+        class.hInstance = instance;
+        class.lpszClassName = class_name.as_ptr();
+        if RegisterClassW(&class) == 0 {
+            return Err("RegisterClassW failed".to_string());
+        }
+
+        let window = CreateWindowExW(
+            0,
+            class_name.as_ptr(),
+            class_name.as_ptr(),
+            0,
+            0,
+            0,
+            0,
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            instance,
+            std::ptr::null(),
+        );
+        if window.is_null() {
+            return Err("CreateWindowExW failed".to_string());
+        }
+        if WTSRegisterSessionNotification(window, NOTIFY_FOR_THIS_SESSION) == 0 {
+            tracing::warn!(
+                "WTSRegisterSessionNotification failed; screen lock will not lock the vault"
+            );
+        }
+
+        let mut message: MSG = std::mem::zeroed();
+        while GetMessageW(&mut message, std::ptr::null_mut(), 0, 0) > 0 {
+            TranslateMessage(&message);
+            DispatchMessageW(&message);
+        }
+    }
+    Ok(())
+}
+
+unsafe extern "system" fn window_proc(
+    window: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let event = match (message, wparam as u32) {
+        (WM_WTSSESSION_CHANGE, WTS_SESSION_LOCK) => Some(SystemLockEvent::ScreenLocked),
+        (WM_POWERBROADCAST, PBT_APMSUSPEND) => Some(SystemLockEvent::Sleep),
+        (WM_POWERBROADCAST, PBT_APMRESUMEAUTOMATIC) => Some(SystemLockEvent::Resume),
+        _ => None,
+    };
+    if let Some(event) = event {
+        EVENTS.with(|slot| {
+            if let Some(events) = slot.borrow().as_ref() {
+                let _ = events.send(event);
+            }
+        });
+    }
+    DefWindowProcW(window, message, wparam, lparam)
+}
@@ -1,13 +1,21 @@
 pub mod authentication;
 pub mod auto_lock;
 pub mod biometric;
+pub mod lock_trigger;
 pub mod permissions;
+pub mod recovery_codes;
+#[cfg(feature = "sync")]
 pub mod remote;
 pub mod session;
+pub mod verification_phrase;
 
 pub use authentication::*;
 pub use auto_lock::*;
 pub use biometric::*;
+pub use lock_trigger::*;
 pub use permissions::*;
+pub use recovery_codes::*;
+#[cfg(feature = "sync")]
 pub use remote::*;
 pub use session::*;
+pub use verification_phrase::*;
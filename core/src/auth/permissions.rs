@@ -102,6 +102,60 @@ impl Default for PermissionChecker {
     }
 }
 
+/// Role of a user in a shared vault
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Full access, including managing other users
+    #[default]
+    Owner,
+
+    /// Can create, update and delete items but not manage users
+    Editor,
+
+    /// Read-only access
+    Viewer,
+}
+
+impl Role {
+    /// Permissions granted to this role
+    pub fn permissions(&self) -> PermissionChecker {
+        match self {
+            Role::Owner => PermissionChecker::new(vec![Permission::Admin]),
+            Role::Editor => PermissionChecker::new(vec![
+                Permission::Read,
+                Permission::Create,
+                Permission::Update,
+                Permission::Delete,
+            ]),
+            Role::Viewer => PermissionChecker::new(vec![Permission::Read]),
+        }
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Owner => write!(f, "owner"),
+            Role::Editor => write!(f, "editor"),
+            Role::Viewer => write!(f, "viewer"),
+        }
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "owner" => Ok(Role::Owner),
+            "editor" => Ok(Role::Editor),
+            "viewer" => Ok(Role::Viewer),
+            _ => Err(format!("Invalid role: {}", s)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +184,20 @@ mod tests {
         let admin_checker = PermissionChecker::new(vec![Permission::Admin]);
         assert!(admin_checker.has_permission(&Permission::Delete));
     }
+
+    #[test]
+    fn test_role_permissions() {
+        assert!(Role::Owner.permissions().has_permission(&Permission::Admin));
+
+        let editor = Role::Editor.permissions();
+        assert!(editor.has_all_permissions(&[Permission::Create, Permission::Delete]));
+        assert!(!editor.has_permission(&Permission::Admin));
+
+        let viewer = Role::Viewer.permissions();
+        assert!(viewer.has_permission(&Permission::Read));
+        assert!(!viewer.has_any_permission(&[Permission::Create, Permission::Update]));
+
+        assert_eq!("Viewer".parse::<Role>().unwrap(), Role::Viewer);
+        assert_eq!(Role::Editor.to_string(), "editor");
+    }
 }
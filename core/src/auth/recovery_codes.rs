@@ -0,0 +1,192 @@
+//! One-time recovery codes.
+//!
+//! Each code carries a copy of the vault key wrapped under a key derived from the code, so a
+//! code can unlock the vault once without the master password. Only a hash of each code is
+//! stored. Codes are 100 bits from the OS CSPRNG, so a fast hash is enough here; a slow KDF
+//! only matters for secrets people choose themselves.
+
+use crate::crypto::{EncryptionService, HmacSha256, Sha256Hasher};
+use crate::{PersonaError, Result};
+use chrono::{DateTime, Utc};
+use rand::{rngs::OsRng, Rng};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Codes issued per set
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Characters per code, excluding separators (5 bits each)
+const CODE_CHARS: usize = 20;
+
+/// Characters between `-` separators when displaying a code
+const GROUP_SIZE: usize = 5;
+
+/// Crockford base32: no I, L, O or U, so codes survive being written down
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Stored half of a recovery code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryCode {
+    /// Hex SHA-256 of the normalized code
+    pub hash: String,
+    /// Hex vault key encrypted under the code's wrapping key
+    pub wrapped_key: String,
+    /// When the code was redeemed; used codes are never accepted again
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+impl RecoveryCode {
+    /// Store `vault_key` behind `code`
+    pub fn seal(code: &str, vault_key: &[u8; 32]) -> Result<Self> {
+        let code = normalize_recovery_code(code)
+            .ok_or_else(|| PersonaError::InvalidInput("Malformed recovery code".to_string()))?;
+        let wrapped_key = wrapping_cipher(&code).encrypt(vault_key).map_err(|e| {
+            PersonaError::CryptographicError(format!("Failed to wrap vault key: {}", e))
+        })?;
+        Ok(Self {
+            hash: hash_code(&code),
+            wrapped_key: hex::encode(wrapped_key),
+            used_at: None,
+        })
+    }
+
+    /// Whether `code` is this (unused) recovery code
+    pub fn matches(&self, code: &str) -> bool {
+        self.used_at.is_none()
+            && normalize_recovery_code(code).is_some_and(|code| hash_code(&code) == self.hash)
+    }
+
+    /// Recover the vault key with `code`
+    pub fn open(&self, code: &str) -> Result<Zeroizing<[u8; 32]>> {
+        let code = normalize_recovery_code(code)
+            .ok_or_else(|| PersonaError::InvalidInput("Malformed recovery code".to_string()))?;
+        let wrapped = hex::decode(&self.wrapped_key).map_err(|e| {
+            PersonaError::CryptographicError(format!("Invalid wrapped recovery key: {}", e))
+        })?;
+        let mut key_bytes = wrapping_cipher(&code).decrypt(&wrapped).map_err(|_| {
+            PersonaError::CryptographicError("Failed to unwrap vault key".to_string())
+        })?;
+        if key_bytes.len() != 32 {
+            key_bytes.zeroize();
+            return Err(PersonaError::CryptographicError(
+                "Unwrapped key has invalid length".to_string(),
+            )
+            .into());
+        }
+        let mut key = Zeroizing::new([0u8; 32]);
+        key.copy_from_slice(&key_bytes);
+        key_bytes.zeroize();
+        Ok(key)
+    }
+}
+
+/// Generate a fresh set of codes for `vault_key`.
+///
+/// Returns the codes to show the user once, and the records to store in their place.
+pub fn generate_recovery_codes(vault_key: &[u8; 32]) -> Result<(Vec<String>, Vec<RecoveryCode>)> {
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| generate_recovery_code())
+        .collect();
+    let sealed = codes
+        .iter()
+        .map(|code| RecoveryCode::seal(code, vault_key))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((codes, sealed))
+}
+
+/// A single random code, formatted as `XXXXX-XXXXX-XXXXX-XXXXX`
+pub fn generate_recovery_code() -> String {
+    let chars: Vec<char> = (0..CODE_CHARS)
+        .map(|_| ALPHABET[OsRng.gen_range(0..ALPHABET.len())] as char)
+        .collect();
+    chars
+        .chunks(GROUP_SIZE)
+        .map(|group| group.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Canonical form of user input, or `None` if it cannot be a recovery code.
+///
+/// Case, spaces and dashes are ignored, and the Crockford look-alikes O, I and L are read as
+/// 0, 1 and 1.
+pub fn normalize_recovery_code(input: &str) -> Option<String> {
+    let code: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        })
+        .collect();
+    (code.len() == CODE_CHARS && code.bytes().all(|b| ALPHABET.contains(&b))).then_some(code)
+}
+
+fn hash_code(code: &str) -> String {
+    Sha256Hasher::hash_string_hex(&format!("persona-recovery-code:{}", code))
+}
+
+fn wrapping_cipher(code: &str) -> EncryptionService {
+    let mut key = HmacSha256::compute(code.as_bytes(), b"persona-recovery-wrapping-key");
+    let cipher = EncryptionService::new(&key);
+    key.zeroize();
+    cipher
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_well_formed_and_distinct() {
+        let key = EncryptionService::generate_key();
+        let (codes, sealed) = generate_recovery_codes(&key).unwrap();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(sealed.len(), RECOVERY_CODE_COUNT);
+
+        let mut unique = codes.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), RECOVERY_CODE_COUNT);
+        for code in &codes {
+            assert_eq!(code.len(), 23);
+            assert!(normalize_recovery_code(code).is_some());
+        }
+    }
+
+    #[test]
+    fn code_unwraps_the_vault_key() {
+        let key = EncryptionService::generate_key();
+        let code = generate_recovery_code();
+        let sealed = RecoveryCode::seal(&code, &key).unwrap();
+
+        assert!(!sealed.hash.contains(&code.replace('-', "")));
+        let sloppy = code.to_lowercase().replace('-', " ");
+        assert!(sealed.matches(&sloppy));
+        assert_eq!(*sealed.open(&sloppy).unwrap(), key);
+
+        let other = generate_recovery_code();
+        assert!(!sealed.matches(&other));
+        assert!(sealed.open(&other).is_err());
+    }
+
+    #[test]
+    fn used_code_no_longer_matches() {
+        let code = generate_recovery_code();
+        let mut sealed = RecoveryCode::seal(&code, &EncryptionService::generate_key()).unwrap();
+        sealed.used_at = Some(Utc::now());
+        assert!(!sealed.matches(&code));
+    }
+
+    #[test]
+    fn rejects_input_that_is_not_a_code() {
+        assert!(normalize_recovery_code("correct horse battery staple").is_none());
+        assert!(normalize_recovery_code("ABCDE-FGHJK").is_none());
+        assert!(normalize_recovery_code("ABCDE-FGHJK-MNPQR-STVWU").is_none());
+        assert_eq!(
+            normalize_recovery_code("abcde-fghjk-mnpqr-stvwo").as_deref(),
+            Some("ABCDEFGHJKMNPQRSTVW0")
+        );
+    }
+}
@@ -1,6 +1,14 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use uuid::Uuid;
 
+use crate::crypto::SecretString;
+use crate::models::{AuditAction, AuditLog, ResourceType};
 use crate::{PersonaError, Result};
 
 /// Minimal SRP-like parameters used to negotiate a remote authentication.
@@ -104,6 +112,291 @@ impl RemoteAuthProvider for MockRemoteAuthProvider {
     }
 }
 
+/// Sensitive operation waiting for approval on another device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalContext {
+    /// Unique per request, so approvers can match replies and drop duplicates
+    pub request_id: Uuid,
+    /// Machine-readable operation, e.g. `ssh_sign` or `credential_reveal`
+    pub operation: String,
+    /// Human-readable summary shown to the approver
+    pub description: String,
+    pub identity_id: Option<Uuid>,
+    pub credential_id: Option<Uuid>,
+    pub requested_at: DateTime<Utc>,
+}
+
+impl ApprovalContext {
+    pub fn new(operation: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            request_id: Uuid::new_v4(),
+            operation: operation.into(),
+            description: description.into(),
+            identity_id: None,
+            credential_id: None,
+            requested_at: Utc::now(),
+        }
+    }
+
+    /// Name the credential the operation uses
+    pub fn with_credential(mut self, identity_id: Uuid, credential_id: Uuid) -> Self {
+        self.identity_id = Some(identity_id);
+        self.credential_id = Some(credential_id);
+        self
+    }
+}
+
+/// Answer to an approval request. Also the JSON a webhook replies with, e.g.
+/// `{"status": "approved"}` or `{"status": "denied", "reason": "not me"}`, alongside the
+/// `request_id` and `signature` that `WebhookApprover` checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ApprovalOutcome {
+    Approved,
+    Denied {
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    /// Nobody answered within the configured timeout
+    TimedOut,
+    /// The approver could not be reached or gave an unusable reply
+    Unavailable {
+        reason: String,
+    },
+}
+
+impl ApprovalOutcome {
+    pub fn is_approved(&self) -> bool {
+        matches!(self, ApprovalOutcome::Approved)
+    }
+
+    /// Audit entry recording this outcome for `context`
+    pub fn audit_log(&self, context: &ApprovalContext) -> AuditLog {
+        let resource_type = if context.credential_id.is_some() {
+            ResourceType::Credential
+        } else {
+            ResourceType::System
+        };
+        let error = match self {
+            ApprovalOutcome::Approved => None,
+            ApprovalOutcome::Denied { reason } => {
+                Some(reason.clone().unwrap_or_else(|| "denied".to_string()))
+            }
+            ApprovalOutcome::TimedOut => Some("timed out".to_string()),
+            ApprovalOutcome::Unavailable { reason } => Some(reason.clone()),
+        };
+        AuditLog::new(
+            AuditAction::RemoteApproval,
+            resource_type,
+            self.is_approved(),
+        )
+        .with_identity_id(context.identity_id)
+        .with_credential_id(context.credential_id)
+        .with_error_message(error)
+        .with_metadata("operation".to_string(), context.operation.clone())
+        .with_metadata("request_id".to_string(), context.request_id.to_string())
+    }
+}
+
+/// Push-style second factor: asks a registered device to approve a sensitive operation and
+/// waits for the answer.
+#[async_trait]
+pub trait RemoteApprover: Send + Sync {
+    /// Block until the request is approved, denied or times out.
+    async fn request_approval(&self, context: &ApprovalContext) -> ApprovalOutcome;
+}
+
+/// Where approval requests go and how long to wait for an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalConfig {
+    /// Webhook URL that receives each [`ApprovalContext`] as a JSON POST; https, or http on a
+    /// loopback address
+    pub endpoint: String,
+    /// How long to wait for the approver before giving up
+    pub timeout: Duration,
+    /// Key shared with the webhook, which signs its replies with it (see [`sign_approval_reply`])
+    pub secret: Option<SecretString>,
+}
+
+impl ApprovalConfig {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            timeout: Self::DEFAULT_TIMEOUT,
+            secret: None,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_secret(mut self, secret: impl Into<SecretString>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    /// `PERSONA_APPROVAL_WEBHOOK`, `PERSONA_APPROVAL_SECRET` and `PERSONA_APPROVAL_TIMEOUT_SECS`;
+    /// `None` without a webhook.
+    pub fn from_env() -> Option<Self> {
+        let endpoint = std::env::var("PERSONA_APPROVAL_WEBHOOK")
+            .ok()
+            .filter(|endpoint| !endpoint.trim().is_empty())?;
+        let timeout = std::env::var("PERSONA_APPROVAL_TIMEOUT_SECS")
+            .ok()
+            .and_then(|secs| secs.trim().parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Self::DEFAULT_TIMEOUT);
+        let mut config = Self::new(endpoint).with_timeout(timeout);
+        config.secret = std::env::var("PERSONA_APPROVAL_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .map(SecretString::from);
+        Some(config)
+    }
+}
+
+/// Signature a webhook puts in the `signature` field of its reply: hex HMAC-SHA256 under the
+/// shared secret of `"<request_id>:<status>"`, so a reply only answers the request it names.
+pub fn sign_approval_reply(secret: &[u8], request_id: &Uuid, status: &str) -> String {
+    hex::encode(
+        reply_mac(secret, request_id, status)
+            .finalize()
+            .into_bytes(),
+    )
+}
+
+fn reply_mac(secret: &[u8], request_id: &Uuid, status: &str) -> Hmac<Sha256> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret).expect("HMAC accepts any key");
+    mac.update(format!("{}:{}", request_id, status).as_bytes());
+    mac
+}
+
+/// Check a webhook reply against the request it must answer.
+///
+/// Anything that does not name `request_id` or is not signed with `secret` is `Unavailable`,
+/// never an approval.
+#[cfg(feature = "remote-approval")]
+fn verify_approval_reply(reply: &[u8], request_id: &Uuid, secret: &[u8]) -> ApprovalOutcome {
+    let unavailable = |reason: String| ApprovalOutcome::Unavailable { reason };
+    let reply: serde_json::Value = match serde_json::from_slice(reply) {
+        Ok(reply) => reply,
+        Err(e) => return unavailable(format!("Invalid approval reply: {}", e)),
+    };
+    if reply.get("request_id").and_then(|id| id.as_str()) != Some(&request_id.to_string()) {
+        return unavailable("Approval reply does not answer this request".to_string());
+    }
+    let status = reply.get("status").and_then(|status| status.as_str());
+    let signature = reply
+        .get("signature")
+        .and_then(|signature| signature.as_str())
+        .and_then(|signature| hex::decode(signature).ok());
+    let (Some(status), Some(signature)) = (status, signature) else {
+        return unavailable("Approval reply is not signed".to_string());
+    };
+    if reply_mac(secret, request_id, status)
+        .verify_slice(&signature)
+        .is_err()
+    {
+        return unavailable("Approval reply has an invalid signature".to_string());
+    }
+    serde_json::from_value(reply)
+        .unwrap_or_else(|e| unavailable(format!("Invalid approval reply: {}", e)))
+}
+
+/// Sends approval requests to a webhook that holds the connection open until the user decides,
+/// then replies with an [`ApprovalOutcome`].
+#[cfg(feature = "remote-approval")]
+#[derive(Debug, Clone)]
+pub struct WebhookApprover {
+    http: reqwest::Client,
+    endpoint: String,
+    secret: SecretString,
+}
+
+#[cfg(feature = "remote-approval")]
+impl WebhookApprover {
+    /// Fails unless the endpoint is https (or http on a loopback address) and a shared secret
+    /// is configured: whoever can answer or alter the reply decides the approval.
+    pub fn new(config: ApprovalConfig) -> Result<Self> {
+        let endpoint = reqwest::Url::parse(&config.endpoint).map_err(|e| {
+            PersonaError::ConfigurationError(format!("Invalid approval webhook URL: {}", e))
+        })?;
+        let host = endpoint.host_str().unwrap_or_default();
+        let loopback = host.eq_ignore_ascii_case("localhost")
+            || host
+                .trim_matches(['[', ']'])
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback());
+        if endpoint.scheme() != "https" && !(endpoint.scheme() == "http" && loopback) {
+            return Err(PersonaError::ConfigurationError(
+                "Approval webhook must use https (plain http only on a loopback address)"
+                    .to_string(),
+            )
+            .into());
+        }
+        let secret = config
+            .secret
+            .filter(|secret| !secret.expose().is_empty())
+            .ok_or_else(|| {
+                PersonaError::ConfigurationError(
+                    "Approval webhook needs a shared secret to verify replies".to_string(),
+                )
+            })?;
+
+        let http = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .user_agent(concat!("persona/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| {
+                PersonaError::ConfigurationError(format!("Failed to create HTTP client: {}", e))
+            })?;
+        Ok(Self {
+            http,
+            endpoint: config.endpoint,
+            secret,
+        })
+    }
+}
+
+#[cfg(feature = "remote-approval")]
+#[async_trait]
+impl RemoteApprover for WebhookApprover {
+    async fn request_approval(&self, context: &ApprovalContext) -> ApprovalOutcome {
+        let body = match serde_json::to_vec(context) {
+            Ok(body) => body,
+            Err(e) => {
+                return ApprovalOutcome::Unavailable {
+                    reason: format!("Failed to encode approval request: {}", e),
+                }
+            }
+        };
+        let reply = async {
+            self.http
+                .post(&self.endpoint)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body)
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await
+        };
+        match reply.await {
+            Ok(reply) => {
+                verify_approval_reply(&reply, &context.request_id, self.secret.expose().as_bytes())
+            }
+            Err(e) if e.is_timeout() => ApprovalOutcome::TimedOut,
+            Err(e) => ApprovalOutcome::Unavailable {
+                reason: format!("Approval webhook unreachable: {}", e),
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,4 +419,171 @@ mod tests {
         let err = provider.finalize(&challenge, "").unwrap_err();
         assert!(err.to_string().contains("empty client proof"));
     }
+
+    #[test]
+    fn approval_outcome_parses_webhook_replies() {
+        let approved: ApprovalOutcome = serde_json::from_str(r#"{"status":"approved"}"#).unwrap();
+        assert!(approved.is_approved());
+        let denied: ApprovalOutcome =
+            serde_json::from_str(r#"{"status":"denied","reason":"not me"}"#).unwrap();
+        assert_eq!(
+            denied,
+            ApprovalOutcome::Denied {
+                reason: Some("not me".to_string())
+            }
+        );
+        assert!(serde_json::from_str::<ApprovalOutcome>(r#"{"status":"maybe"}"#).is_err());
+    }
+
+    #[test]
+    fn approval_outcome_audit_log() {
+        let credential_id = Uuid::new_v4();
+        let context = ApprovalContext::new("ssh_sign", "Sign for github.com")
+            .with_credential(Uuid::new_v4(), credential_id);
+
+        let log = ApprovalOutcome::TimedOut.audit_log(&context);
+        assert_eq!(log.action, AuditAction::RemoteApproval);
+        assert!(!log.success);
+        assert_eq!(log.credential_id, Some(credential_id));
+        assert_eq!(log.error_message.as_deref(), Some("timed out"));
+        assert_eq!(
+            log.metadata.get("operation").map(String::as_str),
+            Some("ssh_sign")
+        );
+
+        assert!(ApprovalOutcome::Approved.audit_log(&context).success);
+    }
+
+    #[cfg(feature = "remote-approval")]
+    #[test]
+    fn webhook_replies_must_name_the_request_and_be_signed() {
+        let secret = b"shared secret";
+        let request_id = Uuid::new_v4();
+        let reply = |id: &Uuid, status: &str, signature: &str| {
+            serde_json::json!({ "status": status, "request_id": id, "signature": signature })
+                .to_string()
+                .into_bytes()
+        };
+        let verify = |reply: &[u8]| verify_approval_reply(reply, &request_id, secret);
+
+        let signed = sign_approval_reply(secret, &request_id, "approved");
+        assert_eq!(
+            verify(&reply(&request_id, "approved", &signed)),
+            ApprovalOutcome::Approved
+        );
+        let denied = serde_json::json!({
+            "status": "denied",
+            "reason": "not me",
+            "request_id": request_id,
+            "signature": sign_approval_reply(secret, &request_id, "denied"),
+        });
+        assert_eq!(
+            verify(denied.to_string().as_bytes()),
+            ApprovalOutcome::Denied {
+                reason: Some("not me".to_string())
+            }
+        );
+
+        let unavailable =
+            |outcome: ApprovalOutcome| matches!(outcome, ApprovalOutcome::Unavailable { .. });
+        // A bare approval, or one meant for another request, approves nothing
+        assert!(unavailable(verify(br#"{"status":"approved"}"#)));
+        let other = Uuid::new_v4();
+        let for_other = sign_approval_reply(secret, &other, "approved");
+        assert!(unavailable(verify(&reply(&other, "approved", &for_other))));
+        assert!(unavailable(verify(&reply(
+            &request_id,
+            "approved",
+            &for_other
+        ))));
+        // A denial's signature cannot be replayed as an approval
+        let denial = sign_approval_reply(secret, &request_id, "denied");
+        assert!(unavailable(verify(&reply(
+            &request_id,
+            "approved",
+            &denial
+        ))));
+        let forged = sign_approval_reply(b"guessed", &request_id, "approved");
+        assert!(unavailable(verify(&reply(
+            &request_id,
+            "approved",
+            &forged
+        ))));
+        assert!(unavailable(verify(&reply(&request_id, "approved", "zz"))));
+    }
+
+    #[cfg(feature = "remote-approval")]
+    #[test]
+    fn webhook_approver_requires_https_and_a_secret() {
+        let approver = |endpoint: &str| {
+            WebhookApprover::new(ApprovalConfig::new(endpoint).with_secret("shared secret"))
+        };
+        assert!(approver("https://approve.example.com/hook").is_ok());
+        assert!(approver("http://127.0.0.1:8080/hook").is_ok());
+        assert!(approver("http://[::1]:8080/hook").is_ok());
+        assert!(approver("http://localhost/hook").is_ok());
+        assert!(approver("http://approve.example.com/hook").is_err());
+        assert!(approver("http://10.0.0.5/hook").is_err());
+        assert!(approver("not a url").is_err());
+
+        assert!(WebhookApprover::new(ApprovalConfig::new("https://approve.example.com")).is_err());
+        assert!(WebhookApprover::new(
+            ApprovalConfig::new("https://approve.example.com").with_secret("")
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "remote-approval")]
+    #[tokio::test]
+    async fn webhook_approver_posts_context_and_waits() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let context = ApprovalContext::new("credential_reveal", "Reveal Wallet");
+        let body = serde_json::json!({
+            "status": "approved",
+            "request_id": context.request_id,
+            "signature": sign_approval_reply(b"shared secret", &context.request_id, "approved"),
+        })
+        .to_string();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (request_tx, request_rx) = tokio::sync::oneshot::channel();
+        let server = tokio::spawn(async move {
+            // First request is approved; the second never gets an answer
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 4096];
+            let n = socket.read(&mut request).await.unwrap();
+            let _ = request_tx.send(String::from_utf8_lossy(&request[..n]).to_string());
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+
+            let (_silent, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let config = ApprovalConfig::new(format!("http://{}/approve", addr))
+            .with_timeout(Duration::from_millis(500))
+            .with_secret("shared secret");
+        let approver = WebhookApprover::new(config).unwrap();
+        assert_eq!(
+            approver.request_approval(&context).await,
+            ApprovalOutcome::Approved
+        );
+        let request = request_rx.await.unwrap();
+        assert!(request.starts_with("POST /approve "));
+        assert!(request.contains("application/json"));
+        assert_eq!(
+            approver.request_approval(&context).await,
+            ApprovalOutcome::TimedOut
+        );
+
+        server.abort();
+        let _ = server.await;
+        let offline = approver.request_approval(&context).await;
+        assert!(matches!(offline, ApprovalOutcome::Unavailable { .. }));
+    }
 }
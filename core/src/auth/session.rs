@@ -201,6 +201,17 @@ impl Default for AutoLockConfig {
     }
 }
 
+impl From<&crate::models::auto_lock_policy::AutoLockPolicy> for AutoLockConfig {
+    fn from(policy: &crate::models::auto_lock_policy::AutoLockPolicy) -> Self {
+        Self {
+            inactivity_timeout_secs: policy.inactivity_timeout_secs,
+            absolute_timeout_secs: policy.absolute_timeout_secs,
+            require_reauth_sensitive: policy.force_lock_sensitive,
+            sensitive_operation_timeout_secs: policy.sensitive_operation_timeout_secs,
+        }
+    }
+}
+
 /// Session manager for tracking and managing sessions with auto-lock
 pub struct SessionManager {
     sessions: Arc<RwLock<std::collections::HashMap<String, Session>>>,
@@ -457,4 +468,16 @@ mod tests {
         manager.unlock_session(&session.id).await.ok();
         assert!(manager.is_valid(&session.id).await);
     }
+
+    #[test]
+    fn test_auto_lock_config_from_policy() {
+        use crate::models::auto_lock_policy::{AutoLockPolicy, AutoLockSecurityLevel};
+
+        let policy = AutoLockPolicy::new("Strict".to_string(), AutoLockSecurityLevel::High, 120);
+        let config = AutoLockConfig::from(&policy);
+        assert_eq!(config.inactivity_timeout_secs, 120);
+        assert_eq!(config.absolute_timeout_secs, 1800);
+        assert!(config.require_reauth_sensitive);
+        assert_eq!(config.sensitive_operation_timeout_secs, 180);
+    }
 }
@@ -0,0 +1,202 @@
+//! Anti-phishing verification phrase.
+//!
+//! Each vault gets a random seed at init; the phrase shown at unlock is derived from it so
+//! users learn to recognise their own prompt. The seed is unrelated to the master password
+//! and keys, so it can be stored unencrypted and read while the vault is locked.
+
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Random bytes behind a verification phrase
+const SEED_BYTES: usize = 16;
+
+/// Words in a phrase: adjective, noun, adjective, noun
+const PHRASE_WORDS: usize = 4;
+
+// Deliberately not BIP-39 words, so the phrase is never mistaken for a recovery phrase
+const ADJECTIVES: [&str; 64] = [
+    "amber",
+    "ancient",
+    "azure",
+    "bold",
+    "brave",
+    "bright",
+    "calm",
+    "clever",
+    "copper",
+    "coral",
+    "crimson",
+    "curious",
+    "dancing",
+    "dapper",
+    "distant",
+    "dusty",
+    "eager",
+    "electric",
+    "emerald",
+    "fancy",
+    "fearless",
+    "fuzzy",
+    "gentle",
+    "gilded",
+    "glowing",
+    "golden",
+    "happy",
+    "hidden",
+    "humble",
+    "icy",
+    "jolly",
+    "lively",
+    "lucky",
+    "lunar",
+    "marble",
+    "mellow",
+    "misty",
+    "noble",
+    "olive",
+    "patient",
+    "plucky",
+    "polished",
+    "proud",
+    "quiet",
+    "rapid",
+    "rustic",
+    "scarlet",
+    "silent",
+    "silver",
+    "sleepy",
+    "smoky",
+    "snowy",
+    "solar",
+    "spotted",
+    "steady",
+    "stormy",
+    "sunny",
+    "swift",
+    "tidy",
+    "velvet",
+    "violet",
+    "wandering",
+    "witty",
+    "zesty",
+];
+
+const NOUNS: [&str; 64] = [
+    "anchor",
+    "badger",
+    "beacon",
+    "bison",
+    "canyon",
+    "castle",
+    "comet",
+    "cricket",
+    "dolphin",
+    "dragon",
+    "falcon",
+    "fern",
+    "ferret",
+    "fjord",
+    "gecko",
+    "glacier",
+    "harbor",
+    "hedgehog",
+    "heron",
+    "island",
+    "jaguar",
+    "kettle",
+    "koala",
+    "lantern",
+    "lemur",
+    "lighthouse",
+    "lynx",
+    "maple",
+    "meadow",
+    "meteor",
+    "otter",
+    "owl",
+    "panda",
+    "parrot",
+    "pebble",
+    "pelican",
+    "penguin",
+    "pine",
+    "planet",
+    "puffin",
+    "quartz",
+    "raccoon",
+    "raven",
+    "reef",
+    "river",
+    "rocket",
+    "saddle",
+    "salmon",
+    "sparrow",
+    "squirrel",
+    "summit",
+    "teapot",
+    "thistle",
+    "tiger",
+    "tortoise",
+    "trumpet",
+    "tulip",
+    "turtle",
+    "valley",
+    "walrus",
+    "willow",
+    "wombat",
+    "yak",
+    "zebra",
+];
+
+/// Generate a new random seed, hex-encoded for storage in workspace settings
+pub fn generate_verification_seed() -> String {
+    let mut seed = [0u8; SEED_BYTES];
+    rand::thread_rng().fill_bytes(&mut seed);
+    hex::encode(seed)
+}
+
+/// Derive the phrase for a stored seed; `None` if the seed is not valid hex
+pub fn verification_phrase_from_seed(seed: &str) -> Option<String> {
+    let seed = hex::decode(seed).ok().filter(|bytes| !bytes.is_empty())?;
+    let digest = Sha256::digest(&seed);
+
+    let words: Vec<&str> = digest
+        .iter()
+        .take(PHRASE_WORDS)
+        .enumerate()
+        .map(|(i, byte)| {
+            let list = if i % 2 == 0 { &ADJECTIVES } else { &NOUNS };
+            list[*byte as usize % list.len()]
+        })
+        .collect();
+    Some(words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phrase_is_deterministic_per_seed() {
+        let seed = generate_verification_seed();
+        let phrase = verification_phrase_from_seed(&seed).unwrap();
+        assert_eq!(verification_phrase_from_seed(&seed).unwrap(), phrase);
+        assert_eq!(phrase.split(' ').count(), PHRASE_WORDS);
+
+        let words: Vec<&str> = phrase.split(' ').collect();
+        assert!(ADJECTIVES.contains(&words[0]) && NOUNS.contains(&words[1]));
+    }
+
+    #[test]
+    fn different_seeds_give_different_phrases() {
+        let a = verification_phrase_from_seed(&"00".repeat(SEED_BYTES)).unwrap();
+        let b = verification_phrase_from_seed(&"01".repeat(SEED_BYTES)).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn invalid_seed_has_no_phrase() {
+        assert!(verification_phrase_from_seed("not hex").is_none());
+        assert!(verification_phrase_from_seed("").is_none());
+    }
+}
@@ -0,0 +1,478 @@
+//! Offline breached-password lookups backed by an on-disk bloom filter.
+//!
+//! A downloaded breach corpus (e.g. the Have I Been Pwned SHA-1 list) is compiled once into a
+//! compact bloom filter; checking a password afterwards needs no network access and never
+//! reveals the password to anyone.
+//!
+//! A bloom filter never misses a password that was in the corpus, but it may report a password
+//! as breached when it was not. The false-positive rate is chosen at build time: each halving of
+//! the rate costs roughly 1.44 extra bits per entry, so 1% needs ~9.6 bits per password and 0.1%
+//! ~14.4 bits. A false positive only ever makes the audit more cautious.
+//!
+//! With the `hibp` feature, [`HibpClient`] offers an opt-in online alternative using the Have I
+//! Been Pwned range API. Only the first five hex characters of the password's SHA-1 are sent;
+//! the returned suffixes are compared locally, so the full hash never leaves the machine.
+
+use crate::{PersonaError, Result};
+use sha1::{Digest, Sha1};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+#[cfg(feature = "hibp")]
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// File signature for serialized filters.
+const MAGIC: &[u8; 8] = b"PBLOOM1\0";
+/// Default false-positive rate for newly built filters.
+pub const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.001;
+/// Upper bound on hash functions; more buys nothing at realistic rates.
+const MAX_HASHES: u32 = 32;
+/// Bytes before the bit array: magic, hash count, bit count, item count.
+const HEADER_LEN: u64 = 8 + 4 + 8 + 8;
+/// Largest bit array `load` allocates; the full HIBP corpus at 0.01% needs about 2 GiB.
+const MAX_FILTER_BYTES: u64 = 4 << 30;
+/// Have I Been Pwned range API endpoint; the 5-character hash prefix is appended.
+pub const HIBP_RANGE_URL: &str = "https://api.pwnedpasswords.com/range/";
+/// Length of the SHA-1 prefix sent to the range API.
+const HIBP_PREFIX_LEN: usize = 5;
+
+/// Space-efficient probabilistic set of breached passwords, keyed by SHA-1.
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    bits: Vec<u8>,
+    num_bits: u64,
+    num_hashes: u32,
+    item_count: u64,
+}
+
+impl BloomFilter {
+    /// Create an empty filter sized for `expected_items` at the given false-positive rate.
+    pub fn new(expected_items: u64, false_positive_rate: f64) -> Result<Self> {
+        if !(false_positive_rate > 0.0 && false_positive_rate < 1.0) {
+            return Err(PersonaError::InvalidInput(
+                "False-positive rate must be between 0 and 1".to_string(),
+            )
+            .into());
+        }
+        let n = expected_items.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = ((-n * false_positive_rate.ln()) / (ln2 * ln2))
+            .ceil()
+            .max(8.0) as u64;
+        let num_hashes = ((num_bits as f64 / n) * ln2)
+            .round()
+            .clamp(1.0, MAX_HASHES as f64) as u32;
+
+        Ok(Self {
+            bits: vec![0u8; num_bits.div_ceil(8) as usize],
+            num_bits,
+            num_hashes,
+            item_count: 0,
+        })
+    }
+
+    /// Compile a breach corpus into a filter.
+    ///
+    /// Each non-empty line is either an HIBP-style `SHA1HEX[:count]` entry or a plaintext password.
+    pub fn build_from_file<P: AsRef<Path>>(source: P, false_positive_rate: f64) -> Result<Self> {
+        let source = source.as_ref();
+        // First pass only counts entries so the filter can be sized up front
+        let expected = Self::corpus_lines(source)?.try_fold(0u64, |count, line| {
+            line.map(|l| count + u64::from(!l.trim().is_empty()))
+        })?;
+
+        let mut filter = Self::new(expected, false_positive_rate)?;
+        for line in Self::corpus_lines(source)? {
+            let line = line?;
+            if let Some(digest) = parse_corpus_line(&line) {
+                filter.insert_digest(&digest);
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Add a plaintext password.
+    pub fn insert_password(&mut self, password: &str) {
+        self.insert_digest(&sha1_digest(password));
+    }
+
+    /// Add a SHA-1 digest.
+    pub fn insert_digest(&mut self, digest: &[u8; 20]) {
+        for index in self.bit_indexes(digest) {
+            self.bits[(index / 8) as usize] |= 1 << (index % 8);
+        }
+        self.item_count += 1;
+    }
+
+    /// Whether the password is (probably) in the corpus.
+    pub fn contains_password(&self, password: &str) -> bool {
+        self.contains_digest(&sha1_digest(password))
+    }
+
+    /// Whether the SHA-1 digest is (probably) in the corpus.
+    pub fn contains_digest(&self, digest: &[u8; 20]) -> bool {
+        self.bit_indexes(digest)
+            .all(|index| self.bits[(index / 8) as usize] & (1 << (index % 8)) != 0)
+    }
+
+    /// Number of entries inserted.
+    pub fn len(&self) -> u64 {
+        self.item_count
+    }
+
+    /// Whether no entries have been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.item_count == 0
+    }
+
+    /// Expected false-positive rate given the entries inserted so far.
+    pub fn false_positive_rate(&self) -> f64 {
+        let k = self.num_hashes as f64;
+        let fill = 1.0 - (-k * self.item_count as f64 / self.num_bits as f64).exp();
+        fill.powf(k)
+    }
+
+    /// Size of the bit array in bytes.
+    pub fn size_bytes(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Write the filter to disk.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path.as_ref())?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&self.num_hashes.to_le_bytes())?;
+        writer.write_all(&self.num_bits.to_le_bytes())?;
+        writer.write_all(&self.item_count.to_le_bytes())?;
+        writer.write_all(&self.bits)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a filter previously written by [`BloomFilter::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+        let invalid = || {
+            PersonaError::InvalidInput(format!(
+                "{} is not a breached-password database",
+                path.display()
+            ))
+        };
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).map_err(|_| invalid())?;
+        if &magic != MAGIC {
+            return Err(invalid().into());
+        }
+        let mut word = [0u8; 4];
+        let mut long = [0u8; 8];
+        reader.read_exact(&mut word).map_err(|_| invalid())?;
+        let num_hashes = u32::from_le_bytes(word);
+        reader.read_exact(&mut long).map_err(|_| invalid())?;
+        let num_bits = u64::from_le_bytes(long);
+        reader.read_exact(&mut long).map_err(|_| invalid())?;
+        let item_count = u64::from_le_bytes(long);
+        if num_hashes == 0 || num_hashes > MAX_HASHES || num_bits == 0 {
+            return Err(invalid().into());
+        }
+        // Check the header against the file before trusting it with an allocation
+        let num_bytes = num_bits.div_ceil(8);
+        if num_bytes != file_len.saturating_sub(HEADER_LEN) || num_bytes > MAX_FILTER_BYTES {
+            return Err(PersonaError::Validation(format!(
+                "{} is truncated or corrupt: its header declares {} bytes of filter data, the file holds {}",
+                path.display(),
+                num_bytes,
+                file_len.saturating_sub(HEADER_LEN)
+            ))
+            .into());
+        }
+
+        let mut bits = vec![0u8; num_bytes as usize];
+        reader.read_exact(&mut bits).map_err(|_| invalid())?;
+        Ok(Self {
+            bits,
+            num_bits,
+            num_hashes,
+            item_count,
+        })
+    }
+
+    fn corpus_lines(source: &Path) -> Result<impl Iterator<Item = std::io::Result<String>>> {
+        Ok(BufReader::new(File::open(source)?).lines())
+    }
+
+    /// Double hashing (Kirsch–Mitzenmacher) over the already uniform SHA-1 digest.
+    fn bit_indexes(&self, digest: &[u8; 20]) -> impl Iterator<Item = u64> {
+        let h1 = u64::from_le_bytes(digest[0..8].try_into().expect("8-byte slice"));
+        let h2 = u64::from_le_bytes(digest[8..16].try_into().expect("8-byte slice")) | 1;
+        let num_bits = self.num_bits;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+}
+
+/// SHA-1 digest of a password, the key format used by breach corpora.
+fn sha1_digest(password: &str) -> [u8; 20] {
+    Sha1::digest(password.as_bytes()).into()
+}
+
+/// Parse one corpus line into a digest; `None` for blank lines.
+fn parse_corpus_line(line: &str) -> Option<[u8; 20]> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let candidate = line.split(':').next().unwrap_or(line);
+    if candidate.len() == 40 {
+        if let Ok(bytes) = hex::decode(candidate) {
+            let mut digest = [0u8; 20];
+            digest.copy_from_slice(&bytes);
+            return Some(digest);
+        }
+    }
+    Some(sha1_digest(line))
+}
+
+/// Split a password's SHA-1 into the prefix sent to the range API and the suffix kept local.
+pub fn hibp_range_key(password: &str) -> (String, String) {
+    let hash = hex::encode_upper(sha1_digest(password));
+    let (prefix, suffix) = hash.split_at(HIBP_PREFIX_LEN);
+    (prefix.to_string(), suffix.to_string())
+}
+
+/// Breach count for `suffix` in a range API response (`SUFFIX:COUNT` lines); 0 if absent.
+///
+/// Padding entries added by the API carry a count of 0 and therefore never match.
+pub fn range_response_count(body: &str, suffix: &str) -> u64 {
+    body.lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Opt-in online breached-password lookups against the HIBP range API.
+///
+/// Requests honour the standard `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` environment variables.
+/// Network failures surface as [`PersonaError::Io`] so callers can fall back to the local filter.
+/// Range responses are cached per prefix for the client's lifetime (shared by its clones), so
+/// scanning a vault asks for each prefix once.
+#[cfg(feature = "hibp")]
+#[derive(Debug, Clone)]
+pub struct HibpClient {
+    http: reqwest::Client,
+    base_url: String,
+    ranges: Arc<Mutex<HashMap<String, Arc<str>>>>,
+}
+
+#[cfg(feature = "hibp")]
+impl HibpClient {
+    /// Create a client whose requests give up after `timeout`.
+    pub fn new(timeout: std::time::Duration) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(timeout)
+            .user_agent(concat!("persona/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| {
+                PersonaError::ConfigurationError(format!("Failed to create HTTP client: {}", e))
+            })?;
+        Ok(Self {
+            http,
+            base_url: HIBP_RANGE_URL.to_string(),
+            ranges: Default::default(),
+        })
+    }
+
+    /// Point the client at a different range endpoint (mirrors, tests).
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Number of times the password appears in HIBP; 0 if it was never seen.
+    pub async fn breach_count(&self, password: &str) -> Result<u64> {
+        let (prefix, suffix) = hibp_range_key(password);
+        let body = self.range(&prefix).await?;
+        Ok(range_response_count(&body, &suffix))
+    }
+
+    /// Range response for `prefix`, fetched once per client
+    async fn range(&self, prefix: &str) -> Result<Arc<str>> {
+        let cached = self
+            .ranges
+            .lock()
+            .expect("HIBP range cache poisoned")
+            .get(prefix)
+            .cloned();
+        if let Some(body) = cached {
+            return Ok(body);
+        }
+
+        let unreachable =
+            |e: reqwest::Error| PersonaError::Io(format!("HIBP range API unreachable: {}", e));
+        let body: Arc<str> = self
+            .http
+            .get(format!("{}{}", self.base_url, prefix))
+            .header("Add-Padding", "true")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(unreachable)?
+            .text()
+            .await
+            .map_err(unreachable)?
+            .into();
+        self.ranges
+            .lock()
+            .expect("HIBP range cache poisoned")
+            .insert(prefix.to_string(), body.clone());
+        Ok(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_build_from_corpus_and_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let corpus = dir.path().join("corpus.txt");
+        let hibp_entry = hex::encode_upper(sha1_digest("hunter2"));
+        std::fs::write(&corpus, format!("{}:17\npassword123\n\n", hibp_entry)).unwrap();
+
+        let filter = BloomFilter::build_from_file(&corpus, 0.01).unwrap();
+        assert_eq!(filter.len(), 2);
+        assert!(filter.contains_password("hunter2"));
+        assert!(filter.contains_password("password123"));
+
+        let db = dir.path().join("breach.bloom");
+        filter.save(&db).unwrap();
+        let loaded = BloomFilter::load(&db).unwrap();
+        assert!(loaded.contains_password("hunter2"));
+        assert!(loaded.contains_password("password123"));
+        assert_eq!(loaded.len(), 2);
+
+        std::fs::write(&corpus, b"not a filter").unwrap();
+        assert!(BloomFilter::load(&corpus).is_err());
+    }
+
+    #[test]
+    fn test_load_rejects_a_header_that_disagrees_with_the_file() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("breach.bloom");
+        let mut filter = BloomFilter::new(100, 0.01).unwrap();
+        filter.insert_password("hunter2");
+        filter.save(&db).unwrap();
+        let saved = std::fs::read(&db).unwrap();
+        let is_validation_error = |path: &Path| {
+            matches!(
+                BloomFilter::load(path)
+                    .unwrap_err()
+                    .downcast_ref::<PersonaError>(),
+                Some(PersonaError::Validation(_))
+            )
+        };
+
+        // Truncated bit array
+        std::fs::write(&db, &saved[..saved.len() - 1]).unwrap();
+        assert!(is_validation_error(&db));
+
+        // Header claiming more bits than any filter may have, with no data behind it
+        let mut oversized = saved[..HEADER_LEN as usize].to_vec();
+        oversized[12..20].copy_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&db, &oversized).unwrap();
+        assert!(is_validation_error(&db));
+
+        // Trailing bytes after the declared bit array
+        let mut padded = saved.clone();
+        padded.push(0);
+        std::fs::write(&db, &padded).unwrap();
+        assert!(is_validation_error(&db));
+
+        std::fs::write(&db, &saved).unwrap();
+        assert!(BloomFilter::load(&db).unwrap().contains_password("hunter2"));
+    }
+
+    #[test]
+    fn test_no_false_negatives_and_bounded_false_positives() {
+        let mut filter = BloomFilter::new(5_000, 0.01).unwrap();
+        for i in 0..5_000 {
+            filter.insert_password(&format!("breached-{}", i));
+        }
+        assert!((0..5_000).all(|i| filter.contains_password(&format!("breached-{}", i))));
+
+        let false_positives = (0..20_000)
+            .filter(|i| filter.contains_password(&format!("clean-{}", i)))
+            .count();
+        let observed = false_positives as f64 / 20_000.0;
+        assert!(observed < 0.02, "false-positive rate {} too high", observed);
+        assert!(filter.false_positive_rate() < 0.02);
+    }
+
+    #[test]
+    fn test_range_key_and_response_matching() {
+        let (prefix, suffix) = hibp_range_key("password");
+        assert_eq!(prefix, "5BAA6");
+        assert_eq!(suffix, "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+
+        let body = "003D68EB55068C33ACE09247EE4C639306B:3\r\n\
+                    1E4C9B93F3F0682250B6CF8331B7EE68FD8:9659365\r\n\
+                    01330C689E5D64F660D6947A93AD634EF8F:0\r\n";
+        assert_eq!(range_response_count(body, &suffix), 9_659_365);
+        assert_eq!(
+            range_response_count(body, "01330C689E5D64F660D6947A93AD634EF8F"),
+            0
+        );
+        assert_eq!(range_response_count(body, "FFFFF"), 0);
+    }
+
+    #[cfg(feature = "hibp")]
+    #[tokio::test]
+    async fn test_hibp_client_sends_only_prefix() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 4096];
+            let n = socket.read(&mut request).await.unwrap();
+            let body = "1E4C9B93F3F0682250B6CF8331B7EE68FD8:42\r\n";
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request[..n]).to_string()
+        });
+
+        let client = HibpClient::new(std::time::Duration::from_secs(5))
+            .unwrap()
+            .with_base_url(format!("http://{}/range/", addr));
+        assert_eq!(client.breach_count("password").await.unwrap(), 42);
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("GET /range/5BAA6 "));
+        assert!(!request.contains("1E4C9B93"));
+        // The server is gone; the same prefix is answered from the cache
+        assert_eq!(client.clone().breach_count("password").await.unwrap(), 42);
+
+        // Nothing listening: reported as an I/O error rather than a panic or a false "clean"
+        let offline = HibpClient::new(std::time::Duration::from_secs(1))
+            .unwrap()
+            .with_base_url(format!("http://{}/range/", addr));
+        let err = offline.breach_count("password").await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PersonaError>(),
+            Some(PersonaError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,60 @@
+//! Version and build details, reported the same way by every Persona binary.
+
+use serde::Serialize;
+
+/// Release version of the Persona workspace
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Short git revision the core was built from, or `unknown` outside a checkout
+pub const GIT_HASH: &str = env!("PERSONA_GIT_HASH");
+
+/// Version line for `--version` output, e.g. `0.1.0 (1a2b3c4d5e6f; features: sqlite)`
+pub const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("PERSONA_GIT_HASH"),
+    "; features: ",
+    env!("PERSONA_FEATURES"),
+    ")"
+);
+
+/// Machine-readable build description
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_hash: &'static str,
+    /// Cargo features persona-core was compiled with
+    pub features: Vec<&'static str>,
+}
+
+impl std::fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(LONG_VERSION)
+    }
+}
+
+/// Describe this build
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: VERSION,
+        git_hash: GIT_HASH,
+        features: env!("PERSONA_FEATURES")
+            .split(',')
+            .filter(|feature| !feature.is_empty())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_version_and_features() {
+        let info = build_info();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.git_hash.is_empty());
+        assert_eq!(info.features.contains(&"sqlite"), cfg!(feature = "sqlite"));
+        assert!(info.to_string().starts_with(VERSION));
+    }
+}
@@ -1,12 +1,18 @@
 // Multi-chain address generation from public keys
 
-use crate::crypto::wallet_crypto::DerivedKey;
+use crate::crypto::wallet_crypto::{DerivedKey, MasterKey};
+use crate::models::wallet::{AddressType, BlockchainNetwork, NetworkKind};
 use crate::{PersonaError, PersonaResult};
+use bip32::{ChildNumber, ExtendedKey, XPub};
 use k256::elliptic_curve::sec1::ToEncodedPoint;
 use ripemd::Ripemd160;
 use sha2::{Digest, Sha256};
 use sha3::Keccak256;
 
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
 /// Bitcoin address types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BitcoinAddressType {
@@ -24,40 +30,50 @@ pub enum BitcoinAddressType {
 pub fn generate_bitcoin_address(
     key: &DerivedKey,
     address_type: BitcoinAddressType,
-    testnet: bool,
+    network_kind: NetworkKind,
 ) -> PersonaResult<String> {
-    let pubkey_bytes = key.public_key_bytes();
-
-    match address_type {
-        BitcoinAddressType::P2PKH => generate_p2pkh_address(&pubkey_bytes, testnet),
-        BitcoinAddressType::P2SH => generate_p2sh_address(&pubkey_bytes, testnet),
-        BitcoinAddressType::P2WPKH => generate_p2wpkh_address(&pubkey_bytes, testnet),
-        BitcoinAddressType::P2TR => generate_p2tr_address(&pubkey_bytes, testnet),
-    }
+    generate_bitcoin_address_from_compressed_pubkey(
+        &key.public_key_bytes(),
+        address_type,
+        network_kind,
+    )
 }
 
 /// Generate Bitcoin address directly from a compressed secp256k1 public key.
 pub fn generate_bitcoin_address_from_compressed_pubkey(
     pubkey: &[u8; 33],
     address_type: BitcoinAddressType,
-    testnet: bool,
+    network_kind: NetworkKind,
 ) -> PersonaResult<String> {
     match address_type {
-        BitcoinAddressType::P2PKH => generate_p2pkh_address(pubkey, testnet),
-        BitcoinAddressType::P2SH => generate_p2sh_address(pubkey, testnet),
-        BitcoinAddressType::P2WPKH => generate_p2wpkh_address(pubkey, testnet),
-        BitcoinAddressType::P2TR => generate_p2tr_address(pubkey, testnet),
+        BitcoinAddressType::P2PKH => generate_p2pkh_address(pubkey, network_kind),
+        BitcoinAddressType::P2SH => generate_p2sh_address(pubkey, network_kind),
+        BitcoinAddressType::P2WPKH => generate_p2wpkh_address(pubkey, network_kind),
+        BitcoinAddressType::P2TR => generate_p2tr_address(pubkey, network_kind),
+    }
+}
+
+/// Human-readable part of Bitcoin segwit addresses
+fn bitcoin_hrp(network_kind: NetworkKind) -> &'static str {
+    match network_kind {
+        NetworkKind::Mainnet => "bc",
+        NetworkKind::Testnet => "tb",
+        NetworkKind::Regtest => "bcrt",
     }
 }
 
 /// Generate P2PKH (Pay-to-Public-Key-Hash) address
-fn generate_p2pkh_address(pubkey: &[u8; 33], testnet: bool) -> PersonaResult<String> {
+fn generate_p2pkh_address(pubkey: &[u8; 33], network_kind: NetworkKind) -> PersonaResult<String> {
     // SHA256 then RIPEMD160
     let sha256_hash = Sha256::digest(pubkey);
     let ripemd_hash = Ripemd160::digest(&sha256_hash);
 
-    // Add version byte (0x00 for mainnet, 0x6f for testnet)
-    let version = if testnet { 0x6f } else { 0x00 };
+    // Add version byte (0x00 for mainnet, 0x6f for testnet and regtest)
+    let version = if network_kind.is_mainnet() {
+        0x00
+    } else {
+        0x6f
+    };
     let mut payload = Vec::with_capacity(21);
     payload.push(version);
     payload.extend_from_slice(&ripemd_hash);
@@ -66,41 +82,103 @@ fn generate_p2pkh_address(pubkey: &[u8; 33], testnet: bool) -> PersonaResult<Str
     Ok(base58_check_encode(&payload))
 }
 
-/// Generate P2SH address (simplified - actual P2SH requires redeem script)
-fn generate_p2sh_address(pubkey: &[u8; 33], testnet: bool) -> PersonaResult<String> {
-    // For demonstration: wrap in a simple P2SH script
-    // Real implementation would need actual redeem script
-    let sha256_hash = Sha256::digest(pubkey);
-    let ripemd_hash = Ripemd160::digest(&sha256_hash);
+/// Generate nested SegWit P2SH-P2WPKH address (BIP-49)
+fn generate_p2sh_address(pubkey: &[u8; 33], network_kind: NetworkKind) -> PersonaResult<String> {
+    // Redeem script: OP_0 <20-byte pubkey hash>
+    let mut redeem_script = vec![0x00, 0x14];
+    redeem_script.extend_from_slice(&Ripemd160::digest(Sha256::digest(pubkey)));
+    let script_hash = Ripemd160::digest(Sha256::digest(&redeem_script));
 
-    let version = if testnet { 0xc4 } else { 0x05 };
+    let version = if network_kind.is_mainnet() {
+        0x05
+    } else {
+        0xc4
+    };
     let mut payload = Vec::with_capacity(21);
     payload.push(version);
-    payload.extend_from_slice(&ripemd_hash);
+    payload.extend_from_slice(&script_hash);
 
     Ok(base58_check_encode(&payload))
 }
 
 /// Generate Native SegWit (Bech32) address
-fn generate_p2wpkh_address(pubkey: &[u8; 33], testnet: bool) -> PersonaResult<String> {
+fn generate_p2wpkh_address(pubkey: &[u8; 33], network_kind: NetworkKind) -> PersonaResult<String> {
     let sha256_hash = Sha256::digest(pubkey);
     let ripemd_hash = Ripemd160::digest(&sha256_hash);
 
-    let hrp = if testnet { "tb" } else { "bc" };
-
     // Bech32 encoding (witness version 0)
-    bech32_encode(hrp, 0, &ripemd_hash)
+    bech32_encode(bitcoin_hrp(network_kind), 0, &ripemd_hash)
 }
 
 /// Generate Taproot address (simplified)
-fn generate_p2tr_address(pubkey: &[u8; 33], testnet: bool) -> PersonaResult<String> {
+fn generate_p2tr_address(pubkey: &[u8; 33], network_kind: NetworkKind) -> PersonaResult<String> {
     // Taproot uses x-only pubkey (32 bytes)
     let x_only_pubkey = &pubkey[1..]; // Remove compression prefix
 
-    let hrp = if testnet { "tb" } else { "bc" };
-
     // Bech32m encoding (witness version 1)
-    bech32_encode(hrp, 1, x_only_pubkey)
+    bech32_encode(bitcoin_hrp(network_kind), 1, x_only_pubkey)
+}
+
+/// Derive external (receive) address `index` from an account-level extended public key.
+///
+/// The address is `<key>/0/<index>`, found by public (non-hardened) derivation only. For Bitcoin
+/// the key's SLIP-132 prefix picks the script type: xpub/tpub P2PKH, ypub/upub P2SH-P2WPKH and
+/// zpub/vpub P2WPKH. EVM networks accept any public prefix.
+pub fn derive_address_from_xpub(
+    xpub: &str,
+    index: u32,
+    network: &BlockchainNetwork,
+    network_kind: NetworkKind,
+) -> PersonaResult<(String, AddressType)> {
+    let invalid = |reason: String| {
+        PersonaError::InvalidInput(format!("Invalid extended public key: {}", reason))
+    };
+    let extended_key = xpub
+        .trim()
+        .parse::<ExtendedKey>()
+        .map_err(|e| invalid(e.to_string()))?;
+    let prefix = extended_key.prefix;
+    if !prefix.is_public() {
+        return Err(invalid(format!(
+            "expected a public key, got {}",
+            prefix.as_str()
+        )));
+    }
+    let account = XPub::try_from(extended_key).map_err(|e| invalid(e.to_string()))?;
+
+    let child = |key: &XPub, index: u32| {
+        ChildNumber::new(index, false)
+            .and_then(|number| key.derive_child(number))
+            .map_err(|e| PersonaError::Cryptography(format!("Public derivation failed: {}", e)))
+    };
+    let pubkey = child(&child(&account, 0)?, index)?.to_bytes();
+
+    match network {
+        BlockchainNetwork::Bitcoin => {
+            let (address_type, bitcoin_type) = match prefix.as_str() {
+                "xpub" | "tpub" => (AddressType::P2PKH, BitcoinAddressType::P2PKH),
+                "ypub" | "upub" => (AddressType::P2SH, BitcoinAddressType::P2SH),
+                "zpub" | "vpub" => (AddressType::P2WPKH, BitcoinAddressType::P2WPKH),
+                other => {
+                    return Err(invalid(format!("unsupported Bitcoin prefix {}", other)));
+                }
+            };
+            let address = generate_bitcoin_address_from_compressed_pubkey(
+                &pubkey,
+                bitcoin_type,
+                network_kind,
+            )?;
+            Ok((address, address_type))
+        }
+        network if network.is_evm() => Ok((
+            generate_ethereum_address_checksummed_from_compressed_pubkey(&pubkey)?,
+            AddressType::Ethereum,
+        )),
+        other => Err(PersonaError::InvalidInput(format!(
+            "Address derivation from an extended public key is not supported for {}",
+            other
+        ))),
+    }
 }
 
 /// Generate Ethereum address from public key
@@ -167,8 +245,22 @@ fn apply_eip55_checksum(address: &str) -> String {
     checksummed
 }
 
+/// Generate Solana address `m/44'/501'/<index>'/0'` (the Phantom/Solflare layout)
+pub fn generate_solana_address(master_key: &MasterKey, index: u32) -> PersonaResult<String> {
+    let signing_key = master_key.derive_ed25519_path(&solana_derivation_path(index))?;
+    generate_solana_address_from_pubkey(signing_key.verifying_key().as_bytes())
+}
+
+/// Coin-level node that Solana accounts derive from
+pub const SOLANA_BASE_PATH: &str = "m/44'/501'";
+
+/// SLIP-0010 path of Solana account `index`
+pub fn solana_derivation_path(index: u32) -> String {
+    format!("{}/{}'/0'", SOLANA_BASE_PATH, index)
+}
+
 /// Generate Solana address (base58-encoded Ed25519 public key)
-pub fn generate_solana_address(pubkey_bytes: &[u8]) -> PersonaResult<String> {
+pub fn generate_solana_address_from_pubkey(pubkey_bytes: &[u8]) -> PersonaResult<String> {
     if pubkey_bytes.len() != 32 {
         return Err(PersonaError::Cryptography(
             "Solana requires 32-byte Ed25519 public key".to_string(),
@@ -194,16 +286,43 @@ fn base58_check_encode(payload: &[u8]) -> String {
     bs58::encode(data).into_string()
 }
 
-/// Bech32/Bech32m encoding (simplified)
+/// Segwit address encoding: bech32 for witness v0, bech32m for later versions (BIP-173/BIP-350)
 fn bech32_encode(hrp: &str, witness_version: u8, witness_program: &[u8]) -> PersonaResult<String> {
-    // This is a simplified version - production code should use proper bech32 library
-    // For now, return a placeholder format
-    Ok(format!(
-        "{}1q{}{}",
-        hrp,
-        witness_version,
-        bs58::encode(witness_program).into_string().to_lowercase()
-    ))
+    if witness_version > 16 || !(2..=40).contains(&witness_program.len()) {
+        return Err(PersonaError::Cryptography(
+            "Invalid segwit witness program".to_string(),
+        ));
+    }
+
+    // Regroup the program's 8-bit bytes into 5-bit values, zero-padding the last one
+    let mut data = vec![witness_version];
+    let (mut acc, mut bits) = (0u32, 0u32);
+    for byte in witness_program {
+        acc = (acc << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            data.push(((acc >> bits) & 31) as u8);
+        }
+    }
+    if bits > 0 {
+        data.push(((acc << (5 - bits)) & 31) as u8);
+    }
+
+    let mut values = bech32_hrp_expand(hrp);
+    values.extend(&data);
+    values.extend([0u8; 6]);
+    let constant = if witness_version == 0 {
+        BECH32_CONST
+    } else {
+        BECH32M_CONST
+    };
+    let checksum = bech32_polymod(&values) ^ constant;
+    data.extend((0..6).map(|i| ((checksum >> (5 * (5 - i))) & 31) as u8));
+
+    let mut address = format!("{}1", hrp);
+    address.extend(data.iter().map(|v| BECH32_CHARSET[*v as usize] as char));
+    Ok(address)
 }
 
 /// Uncompress secp256k1 public key (simplified)
@@ -256,13 +375,302 @@ pub fn validate_solana_address(address: &str) -> bool {
     bs58::decode(address).into_vec().is_ok()
 }
 
+/// Validate an address for a network, including its checksum where the format has one.
+///
+/// Base58Check and bech32/bech32m checksums are verified for Bitcoin-style addresses;
+/// mixed-case EVM addresses must match their EIP-55 checksum.
+pub fn verify_address(network: &BlockchainNetwork, address: &str) -> PersonaResult<()> {
+    let invalid = |reason: &str| {
+        Err(PersonaError::InvalidInput(format!(
+            "Invalid {} address '{}': {}",
+            network, address, reason
+        )))
+    };
+
+    match network {
+        BlockchainNetwork::Bitcoin | BlockchainNetwork::Litecoin => {
+            let lower = address.to_lowercase();
+            let segwit_hrp = match network {
+                BlockchainNetwork::Litecoin => ["ltc1", "tltc1", "rltc1"],
+                _ => ["bc1", "tb1", "bcrt1"],
+            };
+            if segwit_hrp.iter().any(|hrp| lower.starts_with(hrp)) {
+                if !verify_bech32_checksum(address) {
+                    return invalid("bech32 checksum mismatch");
+                }
+            } else if !verify_base58check(address, 21) {
+                return invalid("base58 checksum mismatch");
+            }
+        }
+        BlockchainNetwork::BitcoinCash | BlockchainNetwork::Dogecoin => {
+            if !verify_base58check(address, 21) {
+                return invalid("base58 checksum mismatch");
+            }
+        }
+        BlockchainNetwork::Ethereum
+        | BlockchainNetwork::Polygon
+        | BlockchainNetwork::Arbitrum
+        | BlockchainNetwork::Optimism
+        | BlockchainNetwork::BinanceSmartChain => {
+            if !validate_ethereum_address(address) {
+                return invalid("expected 0x followed by 40 hex characters");
+            }
+            let hex = &address[2..];
+            let mixed_case = hex.chars().any(|c| c.is_ascii_uppercase())
+                && hex.chars().any(|c| c.is_ascii_lowercase());
+            if mixed_case && apply_eip55_checksum(address) != address {
+                return invalid("EIP-55 checksum mismatch");
+            }
+        }
+        BlockchainNetwork::Solana => {
+            let decoded_len = bs58::decode(address).into_vec().map(|bytes| bytes.len());
+            if !validate_solana_address(address) || decoded_len.ok() != Some(32) {
+                return invalid("expected a base58-encoded 32-byte public key");
+            }
+        }
+        BlockchainNetwork::Custom(_) => {
+            if address.trim().is_empty() {
+                return invalid("address is empty");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Output script (scriptPubKey) paying to a Bitcoin address.
+///
+/// Segwit addresses of any witness version and Base58Check P2PKH/P2SH addresses are supported;
+/// checksums are verified.
+pub fn bitcoin_script_pubkey(address: &str) -> PersonaResult<Vec<u8>> {
+    let invalid = || PersonaError::InvalidInput(format!("Invalid Bitcoin address: {}", address));
+    let lower = address.to_lowercase();
+
+    if ["bc1", "tb1", "bcrt1"]
+        .iter()
+        .any(|hrp| lower.starts_with(hrp))
+    {
+        if !verify_bech32_checksum(address) {
+            return Err(invalid());
+        }
+        let separator = lower.rfind('1').ok_or_else(invalid)?;
+        let data = lower[separator + 1..lower.len() - 6]
+            .chars()
+            .map(|c| {
+                BECH32_CHARSET
+                    .iter()
+                    .position(|v| *v as char == c)
+                    .map(|v| v as u8)
+            })
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid)?;
+        let (version, values) = data.split_first().ok_or_else(invalid)?;
+
+        // Regroup 5-bit values into bytes; the padding left over must be zero
+        let mut program = Vec::new();
+        let (mut acc, mut bits) = (0u32, 0u32);
+        for value in values {
+            acc = ((acc << 5) | u32::from(*value)) & 0xfff;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                program.push((acc >> bits) as u8);
+            }
+        }
+        if bits >= 5 || acc & ((1 << bits) - 1) != 0 {
+            return Err(invalid());
+        }
+        if *version > 16
+            || !(2..=40).contains(&program.len())
+            || (*version == 0 && program.len() != 20 && program.len() != 32)
+        {
+            return Err(invalid());
+        }
+
+        let opcode = if *version == 0 { 0x00 } else { 0x50 + version };
+        let mut script = vec![opcode, program.len() as u8];
+        script.extend(program);
+        return Ok(script);
+    }
+
+    if !verify_base58check(address, 21) {
+        return Err(invalid());
+    }
+    let payload = bs58::decode(address).into_vec().map_err(|_| invalid())?;
+    let hash = &payload[1..21];
+    match payload[0] {
+        // OP_DUP OP_HASH160 <hash> OP_EQUALVERIFY OP_CHECKSIG
+        0x00 | 0x6f => Ok([&[0x76, 0xa9, 0x14], hash, &[0x88, 0xac]].concat()),
+        // OP_HASH160 <hash> OP_EQUAL
+        0x05 | 0xc4 => Ok([&[0xa9, 0x14], hash, &[0x87]].concat()),
+        _ => Err(invalid()),
+    }
+}
+
+/// Base58Check: payload followed by the first 4 bytes of its double SHA-256
+fn verify_base58check(address: &str, payload_len: usize) -> bool {
+    let Ok(bytes) = bs58::decode(address).into_vec() else {
+        return false;
+    };
+    if bytes.len() != payload_len + 4 {
+        return false;
+    }
+    let (payload, checksum) = bytes.split_at(payload_len);
+    Sha256::digest(Sha256::digest(payload))[..4] == *checksum
+}
+
+/// BIP-173/BIP-350 checksum: bech32 for witness v0, bech32m for later versions
+fn verify_bech32_checksum(address: &str) -> bool {
+    if address.chars().any(|c| c.is_ascii_uppercase())
+        && address.chars().any(|c| c.is_ascii_lowercase())
+    {
+        return false;
+    }
+    let address = address.to_lowercase();
+    let Some(separator) = address.rfind('1') else {
+        return false;
+    };
+    let (hrp, data) = (&address[..separator], &address[separator + 1..]);
+    if hrp.is_empty() || data.len() < 7 {
+        return false;
+    }
+    let Some(data) = data
+        .chars()
+        .map(|c| {
+            BECH32_CHARSET
+                .iter()
+                .position(|v| *v as char == c)
+                .map(|v| v as u8)
+        })
+        .collect::<Option<Vec<u8>>>()
+    else {
+        return false;
+    };
+
+    let mut values = bech32_hrp_expand(hrp);
+    values.extend(&data);
+
+    let expected = if data[0] == 0 {
+        BECH32_CONST
+    } else {
+        BECH32M_CONST
+    };
+    bech32_polymod(&values) == expected
+}
+
+fn bech32_hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut values: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    values.push(0);
+    values.extend(hrp.bytes().map(|b| b & 31));
+    values
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+    let mut checksum: u32 = 1;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ff_ffff) << 5) ^ u32::from(*value);
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::crypto::wallet_crypto::{
-        Bip44PathBuilder, CoinType, MasterKey, MnemonicWordCount, SecureMnemonic,
+        Bip44PathBuilder, CoinType, MnemonicWordCount, SecureMnemonic,
     };
 
+    /// Account-level extended public key of the BIP-39 "abandon ... about" test mnemonic
+    fn account_xpub(path: &str, prefix: bip32::Prefix) -> String {
+        let mnemonic = SecureMnemonic::from_phrase(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+        .unwrap();
+        let seed = mnemonic.to_seed("");
+        bip32::XPrv::derive_from_path(&seed, &path.parse().unwrap())
+            .unwrap()
+            .public_key()
+            .to_string(prefix)
+    }
+
+    #[test]
+    fn test_derive_address_from_xpub() {
+        let btc = BlockchainNetwork::Bitcoin;
+        let mainnet = NetworkKind::Mainnet;
+
+        // First receive addresses from the BIP-44, BIP-49 and BIP-84 test vectors
+        let xpub = account_xpub("m/44'/0'/0'", bip32::Prefix::XPUB);
+        assert_eq!(
+            derive_address_from_xpub(&xpub, 0, &btc, mainnet).unwrap(),
+            (
+                "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA".to_string(),
+                AddressType::P2PKH
+            )
+        );
+        let ypub = account_xpub("m/49'/0'/0'", bip32::Prefix::YPUB);
+        assert_eq!(
+            derive_address_from_xpub(&ypub, 0, &btc, mainnet).unwrap(),
+            (
+                "37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf".to_string(),
+                AddressType::P2SH
+            )
+        );
+        let zpub = account_xpub("m/84'/0'/0'", bip32::Prefix::ZPUB);
+        assert_eq!(
+            zpub,
+            "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs"
+        );
+        assert_eq!(
+            derive_address_from_xpub(&zpub, 0, &btc, mainnet).unwrap(),
+            (
+                "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu".to_string(),
+                AddressType::P2WPKH
+            )
+        );
+        let (second, _) = derive_address_from_xpub(&zpub, 1, &btc, mainnet).unwrap();
+        assert_eq!(second, "bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g");
+
+        let eth_xpub = account_xpub("m/44'/60'/0'", bip32::Prefix::XPUB);
+        assert_eq!(
+            derive_address_from_xpub(&eth_xpub, 0, &BlockchainNetwork::Ethereum, mainnet).unwrap(),
+            (
+                "0x9858EfFD232B4033E47d90003D41EC34EcaEda94".to_string(),
+                AddressType::Ethereum
+            )
+        );
+    }
+
+    #[test]
+    fn test_derive_address_from_xpub_rejects_private_keys() {
+        let xprv = bip32::XPrv::new([7u8; 64])
+            .unwrap()
+            .to_string(bip32::Prefix::XPRV);
+        let err =
+            derive_address_from_xpub(&xprv, 0, &BlockchainNetwork::Bitcoin, NetworkKind::Mainnet)
+                .unwrap_err();
+        assert!(err.to_string().contains("expected a public key"));
+
+        assert!(derive_address_from_xpub(
+            "xpub-not-base58",
+            0,
+            &BlockchainNetwork::Bitcoin,
+            NetworkKind::Mainnet
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_bitcoin_address_generation() {
         let mnemonic = SecureMnemonic::generate(MnemonicWordCount::Words12).unwrap();
@@ -271,11 +679,55 @@ mod tests {
         let path = Bip44PathBuilder::new(CoinType::Bitcoin).build();
         let key = master.derive_path(&path).unwrap();
 
-        let address = generate_bitcoin_address(&key, BitcoinAddressType::P2PKH, false).unwrap();
+        let address =
+            generate_bitcoin_address(&key, BitcoinAddressType::P2PKH, NetworkKind::Mainnet)
+                .unwrap();
         assert!(validate_bitcoin_address(&address));
         assert!(address.starts_with('1'));
     }
 
+    #[test]
+    fn test_testnet_addresses_differ_from_mainnet() {
+        // Compressed public key of private key 1 (the BIP-173 test vector key)
+        let pubkey: [u8; 33] =
+            hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let address = |address_type, kind| {
+            generate_bitcoin_address_from_compressed_pubkey(&pubkey, address_type, kind).unwrap()
+        };
+
+        assert_eq!(
+            address(BitcoinAddressType::P2WPKH, NetworkKind::Mainnet),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        assert_eq!(
+            address(BitcoinAddressType::P2WPKH, NetworkKind::Testnet),
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+        );
+        let regtest = address(BitcoinAddressType::P2WPKH, NetworkKind::Regtest);
+        assert!(regtest.starts_with("bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7k"));
+        assert!(verify_address(&BlockchainNetwork::Bitcoin, &regtest).is_ok());
+
+        assert_eq!(
+            address(BitcoinAddressType::P2PKH, NetworkKind::Mainnet),
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"
+        );
+        assert_eq!(
+            address(BitcoinAddressType::P2PKH, NetworkKind::Testnet),
+            "mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r"
+        );
+        assert_eq!(
+            address(BitcoinAddressType::P2PKH, NetworkKind::Regtest),
+            address(BitcoinAddressType::P2PKH, NetworkKind::Testnet)
+        );
+
+        let taproot = address(BitcoinAddressType::P2TR, NetworkKind::Testnet);
+        assert!(taproot.starts_with("tb1p"));
+        assert!(verify_address(&BlockchainNetwork::Bitcoin, &taproot).is_ok());
+    }
+
     #[test]
     fn test_ethereum_address_generation() {
         let mnemonic = SecureMnemonic::generate(MnemonicWordCount::Words12).unwrap();
@@ -290,6 +742,27 @@ mod tests {
         assert_eq!(address.len(), 42); // 0x + 40 hex chars
     }
 
+    #[test]
+    fn test_solana_address_generation() {
+        let mnemonic = SecureMnemonic::from_phrase(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+        .unwrap();
+        let master = MasterKey::from_mnemonic(&mnemonic, "").unwrap();
+
+        // First account shown by Phantom and Solflare for this mnemonic
+        let address = generate_solana_address(&master, 0).unwrap();
+        assert_eq!(address, "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+        assert!(verify_address(&BlockchainNetwork::Solana, &address).is_ok());
+        assert_ne!(generate_solana_address(&master, 1).unwrap(), address);
+
+        // A key restored from its xprv has no seed to run SLIP-0010 from
+        let xprv = bip32::XPrv::new(mnemonic.to_seed("")).unwrap();
+        let restored =
+            MasterKey::from_bytes(xprv.to_string(bip32::Prefix::XPRV).as_bytes()).unwrap();
+        assert!(generate_solana_address(&restored, 0).is_err());
+    }
+
     #[test]
     fn test_address_validation() {
         assert!(validate_bitcoin_address(
@@ -308,4 +781,49 @@ mod tests {
         ));
         assert!(!validate_ethereum_address("0xInvalid"));
     }
+
+    #[test]
+    fn test_bitcoin_script_pubkey() {
+        let script = |address: &str| hex::encode(bitcoin_script_pubkey(address).unwrap());
+        assert_eq!(
+            script("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"),
+            "0014751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+        assert_eq!(
+            script("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0"),
+            "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        );
+        assert_eq!(
+            script("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+            "76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac"
+        );
+        assert!(bitcoin_script_pubkey("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5").is_err());
+        assert!(bitcoin_script_pubkey("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+    }
+
+    #[test]
+    fn test_verify_address_checksums() {
+        let btc = BlockchainNetwork::Bitcoin;
+        assert!(verify_address(&btc, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa").is_ok());
+        assert!(verify_address(&btc, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb").is_err());
+        assert!(verify_address(&btc, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq").is_ok());
+        assert!(verify_address(&btc, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdp").is_err());
+        assert!(verify_address(
+            &btc,
+            "bc1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusxg3297"
+        )
+        .is_ok());
+
+        let eth = BlockchainNetwork::Ethereum;
+        assert!(verify_address(&eth, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_ok());
+        assert!(verify_address(&eth, "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").is_ok());
+        assert!(verify_address(&eth, "0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+        assert!(verify_address(&eth, "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+
+        assert!(verify_address(
+            &BlockchainNetwork::Solana,
+            "11111111111111111111111111111111"
+        )
+        .is_ok());
+    }
 }
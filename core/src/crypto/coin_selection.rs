@@ -0,0 +1,707 @@
+// Bitcoin coin control
+// Chooses which UTXOs fund a spend and sizes the fee and change output
+
+use crate::models::wallet::{BlockchainNetwork, TransactionRequest};
+use crate::{PersonaError, PersonaResult};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Virtual size of a P2WPKH input
+pub const INPUT_VBYTES: u64 = 68;
+
+/// Virtual size of a P2WPKH output
+pub const OUTPUT_VBYTES: u64 = 31;
+
+/// Version, locktime, counts and segwit marker
+pub const TX_OVERHEAD_VBYTES: u64 = 11;
+
+/// Change below this value is added to the fee instead of creating an output
+pub const DUST_LIMIT: u64 = 546;
+
+/// Upper bound on branch-and-bound search steps before falling back
+const BNB_MAX_TRIES: usize = 100_000;
+
+/// Unspent transaction output available to a wallet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Utxo {
+    /// Funding transaction ID (hex)
+    pub txid: String,
+
+    /// Output index in the funding transaction
+    pub vout: u32,
+
+    /// Value in satoshis
+    pub value: u64,
+
+    /// Address holding the output, when known
+    pub address: Option<String>,
+}
+
+impl Utxo {
+    /// `txid:vout` identifier of this output
+    pub fn outpoint(&self) -> String {
+        format!("{}:{}", self.txid, self.vout)
+    }
+}
+
+impl FromStr for Utxo {
+    type Err = PersonaError;
+
+    /// Parse `txid:vout:sats`, optionally followed by `:address`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            PersonaError::InvalidInput(format!("Expected TXID:VOUT:SATS[:ADDRESS], got '{}'", s))
+        };
+        let mut parts = s.split(':');
+        let (Some(txid), Some(vout), Some(value), address, None) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            return Err(invalid());
+        };
+        if txid.len() != 64 || !txid.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            txid: txid.to_ascii_lowercase(),
+            vout: vout.parse().map_err(|_| invalid())?,
+            value: value.parse().map_err(|_| invalid())?,
+            address: address.filter(|a| !a.is_empty()).map(str::to_string),
+        })
+    }
+}
+
+/// Source of spendable outputs for a set of addresses
+#[async_trait]
+pub trait UtxoProvider: Send + Sync {
+    async fn list_unspent(&self, addresses: &[String]) -> PersonaResult<Vec<Utxo>>;
+}
+
+/// Fixed set of outputs supplied by the caller (manual coin control, offline use)
+#[derive(Debug, Clone, Default)]
+pub struct StaticUtxoProvider {
+    utxos: Vec<Utxo>,
+}
+
+impl StaticUtxoProvider {
+    pub fn new(utxos: Vec<Utxo>) -> Self {
+        Self { utxos }
+    }
+}
+
+#[async_trait]
+impl UtxoProvider for StaticUtxoProvider {
+    /// Outputs without a known address are assumed to belong to the wallet
+    async fn list_unspent(&self, addresses: &[String]) -> PersonaResult<Vec<Utxo>> {
+        Ok(self
+            .utxos
+            .iter()
+            .filter(|utxo| match &utxo.address {
+                Some(address) => addresses.contains(address),
+                None => true,
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+/// Coin selection algorithm
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CoinSelectionStrategy {
+    /// Spend the largest outputs first; simple and keeps the input count low
+    LargestFirst,
+    /// Search for a changeless input set, falling back to largest-first
+    #[default]
+    BranchAndBound,
+}
+
+impl CoinSelectionStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CoinSelectionStrategy::LargestFirst => "largest-first",
+            CoinSelectionStrategy::BranchAndBound => "branch-and-bound",
+        }
+    }
+}
+
+impl fmt::Display for CoinSelectionStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CoinSelectionStrategy {
+    type Err = PersonaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "largest-first" | "largest" => Ok(CoinSelectionStrategy::LargestFirst),
+            "branch-and-bound" | "bnb" => Ok(CoinSelectionStrategy::BranchAndBound),
+            other => Err(PersonaError::InvalidInput(format!(
+                "Unknown coin selection strategy: {} (expected largest-first or branch-and-bound)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Inputs chosen for a spend, with the resulting fee and change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoinSelection {
+    pub inputs: Vec<Utxo>,
+
+    /// Fee in satoshis, including any dust absorbed from change
+    pub fee: u64,
+
+    /// Change returned to the wallet; zero means no change output
+    pub change: u64,
+}
+
+impl CoinSelection {
+    /// Total value of the selected inputs
+    pub fn input_total(&self) -> u64 {
+        self.inputs.iter().map(|utxo| utxo.value).sum()
+    }
+
+    /// Attach the selected inputs and outputs to a Bitcoin transaction request
+    ///
+    /// The plan is stored as `raw_transaction_data` for the transaction builder and the
+    /// request fee is replaced by the computed one.
+    pub fn apply_to(
+        &self,
+        request: &mut TransactionRequest,
+        change_address: &str,
+    ) -> PersonaResult<()> {
+        if request.network != BlockchainNetwork::Bitcoin {
+            return Err(PersonaError::InvalidInput(
+                "Coin selection only applies to Bitcoin transactions".to_string(),
+            ));
+        }
+        let amount: u64 = request.amount.parse().map_err(|_| {
+            PersonaError::InvalidInput(format!("Invalid amount: {}", request.amount))
+        })?;
+
+        let mut outputs = vec![SpendOutput {
+            address: request.to_address.clone(),
+            value: amount,
+        }];
+        if self.change > 0 {
+            outputs.push(SpendOutput {
+                address: change_address.to_string(),
+                value: self.change,
+            });
+            request
+                .metadata
+                .insert("change_address".to_string(), change_address.to_string());
+        }
+        let plan = SpendPlan {
+            inputs: self.inputs.clone(),
+            outputs,
+        };
+
+        request.fee = self.fee.to_string();
+        request.raw_transaction_data = Some(serde_json::to_vec(&plan)?);
+        Ok(())
+    }
+}
+
+/// Unsigned Bitcoin spend handed to the transaction builder
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpendPlan {
+    pub inputs: Vec<Utxo>,
+    pub outputs: Vec<SpendOutput>,
+}
+
+/// Output of a [`SpendPlan`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpendOutput {
+    pub address: String,
+    pub value: u64,
+}
+
+/// Estimated fee for a P2WPKH transaction of the given shape
+///
+/// Fails when the fee does not fit in a `u64` of satoshis.
+pub fn estimate_fee(inputs: usize, outputs: usize, fee_rate: u64) -> PersonaResult<u64> {
+    (inputs as u64)
+        .checked_mul(INPUT_VBYTES)
+        .and_then(|vbytes| vbytes.checked_add(TX_OVERHEAD_VBYTES))
+        .and_then(|vbytes| {
+            (outputs as u64)
+                .checked_mul(OUTPUT_VBYTES)
+                .and_then(|output_vbytes| vbytes.checked_add(output_vbytes))
+        })
+        .and_then(|vbytes| vbytes.checked_mul(fee_rate))
+        .ok_or_else(|| overflow("Fee"))
+}
+
+/// Choose inputs paying `target` satoshis at `fee_rate` sat/vB
+pub fn select_coins(
+    utxos: &[Utxo],
+    target: u64,
+    fee_rate: u64,
+    strategy: CoinSelectionStrategy,
+) -> PersonaResult<CoinSelection> {
+    if target == 0 {
+        return Err(PersonaError::InvalidInput(
+            "Amount must be greater than zero".to_string(),
+        ));
+    }
+    // Bounds every partial sum and fee the strategies compute
+    total_value(utxos)?;
+    estimate_fee(utxos.len(), 2, fee_rate)?;
+
+    let inputs = match strategy {
+        CoinSelectionStrategy::LargestFirst => largest_first(utxos, target, fee_rate),
+        CoinSelectionStrategy::BranchAndBound => branch_and_bound(utxos, target, fee_rate)
+            .or_else(|| largest_first(utxos, target, fee_rate)),
+    };
+    match inputs {
+        Some(inputs) => finalize(inputs, target, fee_rate),
+        None => Err(insufficient_funds(utxos, target)),
+    }
+}
+
+/// Spend exactly the given outputs (manual coin control)
+pub fn select_manual(utxos: &[Utxo], target: u64, fee_rate: u64) -> PersonaResult<CoinSelection> {
+    if utxos.is_empty() {
+        return Err(PersonaError::InvalidInput("No inputs selected".to_string()));
+    }
+    for (i, utxo) in utxos.iter().enumerate() {
+        if utxos[..i]
+            .iter()
+            .any(|other| other.outpoint() == utxo.outpoint())
+        {
+            return Err(PersonaError::InvalidInput(format!(
+                "Input {} selected more than once",
+                utxo.outpoint()
+            )));
+        }
+    }
+    finalize(utxos.to_vec(), target, fee_rate)
+}
+
+/// Spend every economical output to a single destination with no change
+///
+/// Outputs worth less than the fee to spend them are left out. The swept amount is
+/// `input_total() - fee`.
+pub fn select_sweep(utxos: &[Utxo], fee_rate: u64) -> PersonaResult<CoinSelection> {
+    let input_fee = INPUT_VBYTES
+        .checked_mul(fee_rate)
+        .ok_or_else(|| overflow("Fee"))?;
+    let inputs: Vec<Utxo> = utxos
+        .iter()
+        .filter(|utxo| utxo.value > input_fee)
+        .cloned()
+        .collect();
+
+    let total = total_value(&inputs)?;
+    let fee = estimate_fee(inputs.len(), 1, fee_rate)?;
+    if inputs.is_empty()
+        || total
+            .checked_sub(fee)
+            .is_none_or(|swept| swept < DUST_LIMIT)
+    {
+        return Err(PersonaError::InvalidInput(format!(
+            "Nothing to sweep: {} output(s) worth {} sats do not cover the fee at {} sat/vB",
+            utxos.len(),
+            utxos
+                .iter()
+                .fold(0u64, |total, utxo| total.saturating_add(utxo.value)),
+            fee_rate
+        )));
+    }
+    Ok(CoinSelection {
+        inputs,
+        fee,
+        change: 0,
+    })
+}
+
+fn finalize(inputs: Vec<Utxo>, target: u64, fee_rate: u64) -> PersonaResult<CoinSelection> {
+    let total = total_value(&inputs)?;
+    let fee_without_change = estimate_fee(inputs.len(), 1, fee_rate)?;
+    let fee_with_change = estimate_fee(inputs.len(), 2, fee_rate)?;
+
+    // What the inputs hold beyond the target; the fee and any change come out of it
+    let Some(excess) = total
+        .checked_sub(target)
+        .filter(|excess| *excess >= fee_without_change)
+    else {
+        return Err(PersonaError::InvalidInput(format!(
+            "Insufficient funds: inputs total {} sats, need {} sats plus {} sats fee",
+            total, target, fee_without_change
+        )));
+    };
+
+    let (fee, change) = match excess.checked_sub(fee_with_change) {
+        Some(change) if change >= DUST_LIMIT => (fee_with_change, change),
+        _ => (excess, 0),
+    };
+    Ok(CoinSelection {
+        inputs,
+        fee,
+        change,
+    })
+}
+
+/// Sum of the outputs' values, failing instead of wrapping
+fn total_value(utxos: &[Utxo]) -> PersonaResult<u64> {
+    utxos
+        .iter()
+        .try_fold(0u64, |total, utxo| total.checked_add(utxo.value))
+        .ok_or_else(|| overflow("Input total"))
+}
+
+fn overflow(what: &str) -> PersonaError {
+    PersonaError::InvalidInput(format!("{} exceeds the largest representable amount", what))
+}
+
+fn largest_first(utxos: &[Utxo], target: u64, fee_rate: u64) -> Option<Vec<Utxo>> {
+    let mut sorted: Vec<&Utxo> = utxos.iter().collect();
+    sorted.sort_by_key(|utxo| std::cmp::Reverse(utxo.value));
+
+    let mut selected = Vec::new();
+    let mut total = 0u64;
+    for utxo in sorted {
+        selected.push(utxo.clone());
+        total = total.checked_add(utxo.value)?;
+        let needed = estimate_fee(selected.len(), 1, fee_rate)
+            .ok()
+            .and_then(|fee| target.checked_add(fee))?;
+        if total >= needed {
+            return Some(selected);
+        }
+    }
+    None
+}
+
+/// Depth-first search for an input set whose effective value lands between the target
+/// and the cost of adding a change output, so the spend needs no change
+fn branch_and_bound(utxos: &[Utxo], target: u64, fee_rate: u64) -> Option<Vec<Utxo>> {
+    let input_fee = INPUT_VBYTES.checked_mul(fee_rate)?;
+    let mut candidates: Vec<(&Utxo, u64)> = utxos
+        .iter()
+        .filter(|utxo| utxo.value > input_fee)
+        .map(|utxo| (utxo, utxo.value - input_fee))
+        .collect();
+    candidates.sort_by_key(|(_, value)| std::cmp::Reverse(*value));
+
+    let values: Vec<u64> = candidates.iter().map(|(_, value)| *value).collect();
+    let remaining = values
+        .iter()
+        .try_fold(0u64, |total, value| total.checked_add(*value))?;
+    let target = (TX_OVERHEAD_VBYTES + OUTPUT_VBYTES)
+        .checked_mul(fee_rate)
+        .and_then(|fee| target.checked_add(fee))?;
+    let cost_of_change = OUTPUT_VBYTES
+        .checked_mul(fee_rate)
+        .and_then(|fee| fee.checked_add(DUST_LIMIT))?;
+    let mut search = BnbSearch {
+        values: &values,
+        target,
+        upper: target.saturating_add(cost_of_change),
+        tries: 0,
+        selected: Vec::new(),
+        best: None,
+    };
+    // Every partial sum below is at most `remaining`, so the search cannot overflow
+    search.run(0, 0, remaining);
+
+    search.best.map(|(_, indices)| {
+        indices
+            .into_iter()
+            .map(|i| candidates[i].0.clone())
+            .collect()
+    })
+}
+
+struct BnbSearch<'a> {
+    values: &'a [u64],
+    target: u64,
+    upper: u64,
+    tries: usize,
+    selected: Vec<usize>,
+    /// Lowest excess found so far and the indices that produced it
+    best: Option<(u64, Vec<usize>)>,
+}
+
+impl BnbSearch<'_> {
+    fn run(&mut self, index: usize, current: u64, remaining: u64) {
+        if self.tries >= BNB_MAX_TRIES || matches!(self.best, Some((0, _))) {
+            return;
+        }
+        self.tries += 1;
+
+        if current > self.upper || current + remaining < self.target {
+            return;
+        }
+        if current >= self.target {
+            // Adding more inputs only increases the excess
+            let waste = current - self.target;
+            if self.best.as_ref().is_none_or(|(best, _)| waste < *best) {
+                self.best = Some((waste, self.selected.clone()));
+            }
+            return;
+        }
+        let Some(&value) = self.values.get(index) else {
+            return;
+        };
+
+        self.selected.push(index);
+        self.run(index + 1, current + value, remaining - value);
+        self.selected.pop();
+        self.run(index + 1, current, remaining - value);
+    }
+}
+
+fn insufficient_funds(utxos: &[Utxo], target: u64) -> PersonaError {
+    let total = utxos
+        .iter()
+        .fold(0u64, |total, utxo| total.saturating_add(utxo.value));
+    PersonaError::InvalidInput(format!(
+        "Insufficient funds: {} sats available across {} output(s), need {} sats plus fees",
+        total,
+        utxos.len(),
+        target
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utxo(n: u8, value: u64) -> Utxo {
+        Utxo {
+            txid: format!("{:02x}", n).repeat(32),
+            vout: 0,
+            value,
+            address: None,
+        }
+    }
+
+    fn fee(inputs: usize, outputs: usize, fee_rate: u64) -> u64 {
+        estimate_fee(inputs, outputs, fee_rate).unwrap()
+    }
+
+    fn values(selection: &CoinSelection) -> Vec<u64> {
+        let mut values: Vec<u64> = selection.inputs.iter().map(|u| u.value).collect();
+        values.sort_unstable();
+        values
+    }
+
+    #[test]
+    fn largest_first_spends_biggest_outputs_and_returns_change() {
+        let utxos = vec![utxo(1, 10_000), utxo(2, 50_000), utxo(3, 30_000)];
+        let selection =
+            select_coins(&utxos, 60_000, 2, CoinSelectionStrategy::LargestFirst).unwrap();
+
+        assert_eq!(values(&selection), vec![30_000, 50_000]);
+        assert_eq!(selection.fee, fee(2, 2, 2));
+        assert_eq!(
+            selection.input_total(),
+            60_000 + selection.fee + selection.change
+        );
+        assert!(selection.change > DUST_LIMIT);
+    }
+
+    #[test]
+    fn branch_and_bound_finds_changeless_match() {
+        let fee_rate = 1;
+        // Exactly covers 25_000 plus the fee for two inputs and one output
+        let exact = 25_000 + fee(2, 1, fee_rate);
+        let utxos = vec![
+            utxo(1, 40_000),
+            utxo(2, exact - 5_000),
+            utxo(3, 5_000),
+            utxo(4, 12_000),
+        ];
+
+        let selection = select_coins(
+            &utxos,
+            25_000,
+            fee_rate,
+            CoinSelectionStrategy::BranchAndBound,
+        )
+        .unwrap();
+        assert_eq!(values(&selection), vec![5_000, exact - 5_000]);
+        assert_eq!(selection.change, 0);
+        assert_eq!(selection.fee, fee(2, 1, fee_rate));
+
+        // Largest-first would have spent the 40k output and produced change
+        let largest = select_coins(
+            &utxos,
+            25_000,
+            fee_rate,
+            CoinSelectionStrategy::LargestFirst,
+        )
+        .unwrap();
+        assert_eq!(values(&largest), vec![40_000]);
+        assert!(largest.change > 0);
+    }
+
+    #[test]
+    fn branch_and_bound_falls_back_when_no_exact_match() {
+        let utxos = vec![utxo(1, 100_000), utxo(2, 200_000)];
+        let selection =
+            select_coins(&utxos, 50_000, 1, CoinSelectionStrategy::BranchAndBound).unwrap();
+
+        assert_eq!(values(&selection), vec![200_000]);
+        assert!(selection.change > 0);
+    }
+
+    #[test]
+    fn dust_change_is_added_to_the_fee() {
+        let fee_rate = 1;
+        let utxos = vec![utxo(1, 10_000 + fee(1, 1, fee_rate) + 300)];
+        let selection = select_coins(
+            &utxos,
+            10_000,
+            fee_rate,
+            CoinSelectionStrategy::LargestFirst,
+        )
+        .unwrap();
+
+        assert_eq!(selection.change, 0);
+        assert_eq!(selection.fee, fee(1, 1, fee_rate) + 300);
+    }
+
+    #[test]
+    fn insufficient_funds_is_an_error() {
+        let utxos = vec![utxo(1, 1_000), utxo(2, 2_000)];
+        for strategy in [
+            CoinSelectionStrategy::LargestFirst,
+            CoinSelectionStrategy::BranchAndBound,
+        ] {
+            assert!(select_coins(&utxos, 3_000, 1, strategy).is_err());
+        }
+        assert!(select_coins(&[], 1, 1, CoinSelectionStrategy::BranchAndBound).is_err());
+    }
+
+    #[test]
+    fn manual_selection_spends_every_input() {
+        let utxos = vec![utxo(1, 20_000), utxo(2, 20_000)];
+        let selection = select_manual(&utxos, 10_000, 1).unwrap();
+        assert_eq!(selection.inputs.len(), 2);
+        assert_eq!(selection.change, 40_000 - 10_000 - fee(2, 2, 1));
+
+        let duplicate = vec![utxo(1, 20_000), utxo(1, 20_000)];
+        assert!(select_manual(&duplicate, 10_000, 1).is_err());
+        assert!(select_manual(&utxos, 50_000, 1).is_err());
+    }
+
+    #[test]
+    fn amounts_near_u64_max_are_errors_not_overflows() {
+        let utxos = vec![utxo(1, 20_000), utxo(2, 20_000)];
+        for strategy in [
+            CoinSelectionStrategy::LargestFirst,
+            CoinSelectionStrategy::BranchAndBound,
+        ] {
+            assert!(select_coins(&utxos, u64::MAX, 1, strategy).is_err());
+            assert!(select_coins(&utxos, 10_000, u64::MAX, strategy).is_err());
+        }
+        assert!(select_manual(&utxos, u64::MAX, 1).is_err());
+        assert!(select_manual(&utxos, 10_000, u64::MAX).is_err());
+        assert!(estimate_fee(usize::MAX, 1, 1).is_err());
+
+        let whales = vec![utxo(1, u64::MAX), utxo(2, u64::MAX)];
+        let err = select_manual(&whales, 10_000, 1).unwrap_err();
+        assert!(err.to_string().contains("Input total"), "{}", err);
+        assert!(select_coins(&whales, 10_000, 1, CoinSelectionStrategy::LargestFirst).is_err());
+        assert!(select_sweep(&whales, 1).is_err());
+        assert!(select_sweep(&utxos, u64::MAX).is_err());
+
+        // A single u64::MAX output still funds a spend
+        let selection = select_manual(&[utxo(1, u64::MAX)], 10_000, 1).unwrap();
+        assert_eq!(selection.change, u64::MAX - 10_000 - fee(1, 2, 1));
+    }
+
+    #[test]
+    fn sweep_spends_everything_except_uneconomical_dust() {
+        let fee_rate = 5;
+        let utxos = vec![utxo(1, 20_000), utxo(2, 300), utxo(3, 8_000)];
+        let selection = select_sweep(&utxos, fee_rate).unwrap();
+
+        assert_eq!(values(&selection), vec![8_000, 20_000]);
+        assert_eq!(selection.change, 0);
+        assert_eq!(selection.fee, fee(2, 1, fee_rate));
+        assert_eq!(selection.input_total() - selection.fee, 28_000 - 5 * 178);
+
+        assert!(select_sweep(&[utxo(1, 300)], fee_rate).is_err());
+        assert!(select_sweep(&[], fee_rate).is_err());
+    }
+
+    #[test]
+    fn parses_outpoints() {
+        let txid = "ab".repeat(32);
+        let parsed: Utxo = format!("{}:1:5000", txid).parse().unwrap();
+        assert_eq!(parsed.outpoint(), format!("{}:1", txid));
+        assert_eq!(parsed.value, 5000);
+        assert_eq!(parsed.address, None);
+
+        let parsed: Utxo = format!("{}:1:5000:bc1qexample", txid).parse().unwrap();
+        assert_eq!(parsed.address.as_deref(), Some("bc1qexample"));
+
+        assert!("deadbeef:0:1".parse::<Utxo>().is_err());
+        assert!(format!("{}:x:1", txid).parse::<Utxo>().is_err());
+        assert!(format!("{}:0", txid).parse::<Utxo>().is_err());
+    }
+
+    #[tokio::test]
+    async fn static_provider_filters_by_address() {
+        let mut owned = utxo(1, 1_000);
+        owned.address = Some("bc1qowned".to_string());
+        let mut other = utxo(2, 2_000);
+        other.address = Some("bc1qother".to_string());
+        let provider = StaticUtxoProvider::new(vec![owned.clone(), other, utxo(3, 3_000)]);
+
+        let unspent = provider
+            .list_unspent(&["bc1qowned".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(unspent.len(), 2);
+        assert!(unspent.contains(&owned));
+    }
+
+    #[test]
+    fn plan_is_attached_to_bitcoin_requests() {
+        let selection = select_manual(&[utxo(1, 100_000)], 40_000, 1).unwrap();
+        let mut request = TransactionRequest {
+            id: uuid::Uuid::new_v4(),
+            wallet_id: uuid::Uuid::new_v4(),
+            network: BlockchainNetwork::Bitcoin,
+            chain_id: None,
+            from_address: "bc1qfrom".to_string(),
+            to_address: "bc1qto".to_string(),
+            amount: "40000".to_string(),
+            fee: "0".to_string(),
+            gas_price: None,
+            gas_limit: None,
+            nonce: None,
+            memo: None,
+            raw_transaction_data: None,
+            required_signatures: 1,
+            created_at: chrono::Utc::now(),
+            expires_at: None,
+            metadata: Default::default(),
+        };
+        selection.apply_to(&mut request, "bc1qchange").unwrap();
+
+        let plan: SpendPlan =
+            serde_json::from_slice(request.raw_transaction_data.as_ref().unwrap()).unwrap();
+        assert_eq!(plan.inputs, selection.inputs);
+        assert_eq!(plan.outputs.len(), 2);
+        assert_eq!(plan.outputs[1].address, "bc1qchange");
+        assert_eq!(plan.outputs[1].value, selection.change);
+        assert_eq!(request.fee, selection.fee.to_string());
+
+        request.network = BlockchainNetwork::Ethereum;
+        assert!(selection.apply_to(&mut request, "bc1qchange").is_err());
+    }
+}
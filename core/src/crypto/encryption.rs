@@ -1,9 +1,35 @@
-use aes_gcm::{aead::Aead, Aes256Gcm, Key, KeyInit, Nonce};
+use crate::{PersonaError, PersonaResult};
+use aes_gcm::{
+    aead::{Aead, Payload},
+    Aes256Gcm, Key, KeyInit, Nonce,
+};
 use argon2::password_hash::{PasswordHash, PasswordVerifier, SaltString};
 use argon2::{Argon2, PasswordHasher};
 use rand::{rngs::OsRng, RngCore};
+use std::io::{Read, Write};
 use zeroize::Zeroize;
 
+// Streaming encryption format:
+// [magic:4] "PSTM" [version:1] [chunk_size:4, little endian] [nonce_prefix:7]
+// then chunks of [ciphertext][tag:16], each holding chunk_size plaintext bytes except
+// the last, which holds fewer (possibly zero) and is marked final.
+// Chunk nonce: nonce_prefix || counter (u32, big endian) || final flag (1 byte).
+// The header is authenticated as associated data of every chunk, so reordering,
+// truncating, extending or editing any chunk fails decryption at that chunk.
+
+const STREAM_MAGIC: &[u8; 4] = b"PSTM";
+const STREAM_VERSION: u8 = 1;
+const STREAM_HEADER_LEN: usize = 16;
+const STREAM_NONCE_PREFIX_LEN: usize = 7;
+const STREAM_TAG_LEN: usize = 16;
+
+/// Plaintext bytes per chunk written by [`encrypt_stream`]
+pub const STREAM_CHUNK_SIZE: u32 = 64 * 1024;
+
+/// Largest chunk size [`decrypt_stream`] accepts, so a forged header cannot
+/// make it allocate unbounded memory
+const MAX_STREAM_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+
 /// Encrypted data with metadata
 #[derive(Debug, Clone)]
 pub struct EncryptedData {
@@ -77,6 +103,155 @@ fn derive_key_from_password(password: &[u8], salt: &[u8], output: &mut [u8; 32])
         .expect("Failed to derive key from password");
 }
 
+/// Encrypt everything from `reader` into `writer` in authenticated chunks
+///
+/// Memory use is bounded by [`STREAM_CHUNK_SIZE`], so arbitrarily large files can
+/// be encrypted. Returns the number of plaintext bytes processed.
+pub fn encrypt_stream<R: Read, W: Write>(
+    key: &[u8; 32],
+    mut reader: R,
+    mut writer: W,
+) -> PersonaResult<u64> {
+    let mut header = [0u8; STREAM_HEADER_LEN];
+    header[..4].copy_from_slice(STREAM_MAGIC);
+    header[4] = STREAM_VERSION;
+    header[5..9].copy_from_slice(&STREAM_CHUNK_SIZE.to_le_bytes());
+    OsRng.fill_bytes(&mut header[9..]);
+    writer.write_all(&header)?;
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let mut buffer = vec![0u8; STREAM_CHUNK_SIZE as usize];
+    let mut counter: u32 = 0;
+    let mut total: u64 = 0;
+
+    loop {
+        let filled = read_full(&mut reader, &mut buffer)?;
+        let last = filled < buffer.len();
+        let nonce = stream_nonce(&header, counter, last);
+        let chunk = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &buffer[..filled],
+                    aad: &header,
+                },
+            )
+            .map_err(|_| PersonaError::Crypto("Chunk encryption failed".to_string()))?;
+        writer.write_all(&chunk)?;
+        total += filled as u64;
+
+        if last {
+            break;
+        }
+        counter = next_counter(counter)?;
+    }
+
+    buffer.zeroize();
+    writer.flush()?;
+    Ok(total)
+}
+
+/// Decrypt a stream written by [`encrypt_stream`]
+///
+/// Plaintext is written chunk by chunk as each one authenticates, so on error
+/// `writer` may already hold a verified prefix of the data; callers should
+/// discard the output unless this returns `Ok`. Returns the number of
+/// plaintext bytes written.
+pub fn decrypt_stream<R: Read, W: Write>(
+    key: &[u8; 32],
+    mut reader: R,
+    mut writer: W,
+) -> PersonaResult<u64> {
+    let mut header = [0u8; STREAM_HEADER_LEN];
+    reader
+        .read_exact(&mut header)
+        .map_err(|_| PersonaError::Crypto("Stream header is truncated".to_string()))?;
+    if &header[..4] != STREAM_MAGIC {
+        return Err(PersonaError::Crypto("Not an encrypted stream".to_string()));
+    }
+    if header[4] != STREAM_VERSION {
+        return Err(PersonaError::Crypto(format!(
+            "Unsupported stream version {}",
+            header[4]
+        )));
+    }
+    let chunk_size = u32::from_le_bytes(header[5..9].try_into().expect("4-byte slice"));
+    if chunk_size == 0 || chunk_size > MAX_STREAM_CHUNK_SIZE {
+        return Err(PersonaError::Crypto(format!(
+            "Invalid stream chunk size {}",
+            chunk_size
+        )));
+    }
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let mut buffer = vec![0u8; chunk_size as usize + STREAM_TAG_LEN];
+    let mut counter: u32 = 0;
+    let mut total: u64 = 0;
+
+    loop {
+        let filled = read_full(&mut reader, &mut buffer)?;
+        // Only the final chunk is short, so a full buffer always has more after it
+        let last = filled < buffer.len();
+        if filled < STREAM_TAG_LEN {
+            return Err(PersonaError::Crypto(format!(
+                "Stream is truncated at chunk {}",
+                counter
+            )));
+        }
+        let nonce = stream_nonce(&header, counter, last);
+        let mut plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &buffer[..filled],
+                    aad: &header,
+                },
+            )
+            .map_err(|_| {
+                PersonaError::Crypto(format!("Stream chunk {} failed authentication", counter))
+            })?;
+        writer.write_all(&plaintext)?;
+        total += plaintext.len() as u64;
+        plaintext.zeroize();
+
+        if last {
+            break;
+        }
+        counter = next_counter(counter)?;
+    }
+
+    writer.flush()?;
+    Ok(total)
+}
+
+fn stream_nonce(header: &[u8; STREAM_HEADER_LEN], counter: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..STREAM_NONCE_PREFIX_LEN].copy_from_slice(&header[9..]);
+    nonce[7..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+fn next_counter(counter: u32) -> PersonaResult<u32> {
+    counter
+        .checked_add(1)
+        .ok_or_else(|| PersonaError::Crypto("Stream is too long to encrypt".to_string()))
+}
+
+/// Fill `buf` from `reader`, returning fewer bytes only at end of input
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> PersonaResult<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(filled)
+}
+
 /// AES-256-GCM encryption service
 pub struct EncryptionService {
     cipher: Aes256Gcm,
@@ -193,6 +368,102 @@ mod tests {
         assert_eq!(plaintext, decrypted.as_slice());
     }
 
+    /// Deterministic, non-repeating-per-chunk test data
+    fn pattern_byte(i: u64) -> u8 {
+        (i.wrapping_mul(2654435761) >> 7) as u8
+    }
+
+    #[test]
+    fn test_stream_roundtrip_large_file() {
+        use std::io::{BufReader, BufWriter, Seek};
+
+        const SIZE: u64 = 50 * 1024 * 1024;
+        let key = EncryptionService::generate_key();
+
+        let mut source = tempfile::tempfile().unwrap();
+        {
+            let mut writer = BufWriter::new(&mut source);
+            let block: Vec<u8> = (0..SIZE).take(1 << 20).map(pattern_byte).collect();
+            let mut written = 0;
+            while written < SIZE {
+                // Shift each block by its offset so chunks differ
+                let offset = (written >> 20) as u8;
+                let shifted: Vec<u8> = block.iter().map(|b| b ^ offset).collect();
+                writer.write_all(&shifted).unwrap();
+                written += block.len() as u64;
+            }
+            writer.flush().unwrap();
+        }
+        source.rewind().unwrap();
+
+        let mut encrypted = tempfile::tempfile().unwrap();
+        let n = encrypt_stream(&key, BufReader::new(&source), BufWriter::new(&encrypted)).unwrap();
+        assert_eq!(n, SIZE);
+        assert!(encrypted.metadata().unwrap().len() > SIZE);
+        encrypted.rewind().unwrap();
+
+        let mut decrypted = tempfile::tempfile().unwrap();
+        let n =
+            decrypt_stream(&key, BufReader::new(&encrypted), BufWriter::new(&decrypted)).unwrap();
+        assert_eq!(n, SIZE);
+
+        source.rewind().unwrap();
+        decrypted.rewind().unwrap();
+        let mut expected = vec![0u8; 1 << 20];
+        let mut actual = vec![0u8; 1 << 20];
+        for _ in 0..(SIZE >> 20) {
+            source.read_exact(&mut expected).unwrap();
+            decrypted.read_exact(&mut actual).unwrap();
+            assert_eq!(expected, actual);
+        }
+        assert_eq!(decrypted.read(&mut actual).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_stream_detects_tampering() {
+        let key = EncryptionService::generate_key();
+        let chunk = STREAM_CHUNK_SIZE as usize;
+        let plaintext: Vec<u8> = (0..(chunk * 3) as u64).map(pattern_byte).collect();
+
+        let mut encrypted = Vec::new();
+        encrypt_stream(&key, plaintext.as_slice(), &mut encrypted).unwrap();
+        let mut decrypted = Vec::new();
+        decrypt_stream(&key, encrypted.as_slice(), &mut decrypted).unwrap();
+        assert_eq!(decrypted, plaintext);
+
+        // A flipped bit in the second chunk is caught there
+        let mut tampered = encrypted.clone();
+        tampered[STREAM_HEADER_LEN + chunk + STREAM_TAG_LEN + 10] ^= 1;
+        let err = decrypt_stream(&key, tampered.as_slice(), Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("chunk 1"));
+
+        // Dropping the final chunk, or cutting one short, is detected
+        let full_chunk = chunk + STREAM_TAG_LEN;
+        let truncated = &encrypted[..STREAM_HEADER_LEN + 3 * full_chunk];
+        assert!(decrypt_stream(&key, truncated, Vec::new()).is_err());
+        let truncated = &encrypted[..STREAM_HEADER_LEN + full_chunk + 100];
+        assert!(decrypt_stream(&key, truncated, Vec::new()).is_err());
+
+        // Swapping two chunks is detected
+        let mut swapped = encrypted.clone();
+        let (first, rest) = swapped[STREAM_HEADER_LEN..].split_at_mut(full_chunk);
+        first.swap_with_slice(&mut rest[..full_chunk]);
+        assert!(decrypt_stream(&key, swapped.as_slice(), Vec::new()).is_err());
+
+        // The wrong key fails on the first chunk
+        let other = EncryptionService::generate_key();
+        assert!(decrypt_stream(&other, encrypted.as_slice(), Vec::new()).is_err());
+
+        // Empty input still produces an authenticated final chunk
+        let mut empty = Vec::new();
+        encrypt_stream(&key, &[][..], &mut empty).unwrap();
+        assert_eq!(empty.len(), STREAM_HEADER_LEN + STREAM_TAG_LEN);
+        assert_eq!(
+            decrypt_stream(&key, empty.as_slice(), Vec::new()).unwrap(),
+            0
+        );
+    }
+
     #[test]
     fn test_secure_string() {
         let secure = SecureString::from_string("secret".to_string());
@@ -1,24 +1,156 @@
 use crate::{PersonaError, PersonaResult};
 use argon2::{
     password_hash::{PasswordHash, SaltString},
-    Argon2, PasswordHasher as Argon2PasswordHasher, PasswordVerifier,
+    Algorithm, Argon2, Params, PasswordHasher as Argon2PasswordHasher, PasswordVerifier, Version,
 };
 use rand::rngs::OsRng;
 use ring::digest::{Context, SHA256};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Highest memory cost [`calibrate_argon2`] will pick (256 MiB)
+pub const CALIBRATION_MAX_MEMORY_KIB: u32 = 256 * 1024;
+
+/// Highest iteration count [`calibrate_argon2`] will pick
+pub const CALIBRATION_MAX_ITERATIONS: u32 = 10;
+
+/// Argon2id cost parameters for master password hashing
+///
+/// The parameters are recorded in the PHC string produced by
+/// [`PasswordHasher::hash_password`], so a hash always verifies with the
+/// parameters it was created with, regardless of the current defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasswordHashParams {
+    /// Memory cost in KiB
+    pub memory_kib: u32,
+    /// Number of passes over memory
+    pub iterations: u32,
+    /// Degree of parallelism (lanes)
+    pub parallelism: u32,
+}
+
+impl PasswordHashParams {
+    /// Read the parameters a PHC hash string was created with
+    pub fn from_hash(hash: &str) -> PersonaResult<Self> {
+        let parsed_hash = PasswordHash::new(hash)
+            .map_err(|e| PersonaError::Crypto(format!("Invalid hash format: {}", e)))?;
+        let params = Params::try_from(&parsed_hash)
+            .map_err(|e| PersonaError::Crypto(format!("Invalid hash parameters: {}", e)))?;
+        Ok(Self {
+            memory_kib: params.m_cost(),
+            iterations: params.t_cost(),
+            parallelism: params.p_cost(),
+        })
+    }
+
+    fn to_argon2_params(self) -> PersonaResult<Params> {
+        Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| PersonaError::Crypto(format!("Invalid Argon2 parameters: {}", e)))
+    }
+}
+
+impl Default for PasswordHashParams {
+    /// The argon2 crate defaults (19 MiB, 2 iterations, 1 lane), which match
+    /// the parameters used before they became configurable.
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// Pick Argon2 parameters that take roughly `target_ms` to hash on this machine
+///
+/// Starts from [`PasswordHashParams::default`] and never returns anything
+/// weaker. Memory is doubled first, up to [`CALIBRATION_MAX_MEMORY_KIB`], then
+/// iterations are raised, up to [`CALIBRATION_MAX_ITERATIONS`]. Each step runs
+/// one trial hash, so the loop is bounded by those two ceilings; on slow
+/// hardware the result may overshoot the target by one step.
+pub fn calibrate_argon2(target_ms: u64) -> PersonaResult<PasswordHashParams> {
+    calibrate_with(Duration::from_millis(target_ms), |params| {
+        let hasher = PasswordHasher::from_params(params)?;
+        let start = Instant::now();
+        hasher.hash_password("persona-calibration")?;
+        Ok(start.elapsed())
+    })
+}
+
+fn calibrate_with<F>(target: Duration, mut measure: F) -> PersonaResult<PasswordHashParams>
+where
+    F: FnMut(PasswordHashParams) -> PersonaResult<Duration>,
+{
+    let mut params = PasswordHashParams::default();
+    let mut elapsed = measure(params)?;
+
+    while elapsed < target {
+        let mut next = params;
+        if params.memory_kib < CALIBRATION_MAX_MEMORY_KIB {
+            next.memory_kib = (params.memory_kib * 2).min(CALIBRATION_MAX_MEMORY_KIB);
+        } else if params.iterations < CALIBRATION_MAX_ITERATIONS {
+            next.iterations += 1;
+        } else {
+            break;
+        }
+
+        let next_elapsed = measure(next)?;
+        // Keep whichever of the last two steps lands closer to the target
+        if next_elapsed >= target && next_elapsed - target > target - elapsed {
+            break;
+        }
+        params = next;
+        elapsed = next_elapsed;
+    }
+
+    Ok(params)
+}
 
 /// Password hashing service using Argon2
 pub struct PasswordHasher {
     argon2: Argon2<'static>,
+    params: PasswordHashParams,
 }
 
 impl PasswordHasher {
-    /// Create a new password hasher
+    /// Create a new password hasher with the default parameters
     pub fn new() -> Self {
         Self {
             argon2: Argon2::default(),
+            params: PasswordHashParams::default(),
         }
     }
 
+    /// Create a password hasher with custom Argon2id cost parameters
+    pub fn with_params(memory_kib: u32, iterations: u32, parallelism: u32) -> PersonaResult<Self> {
+        Self::from_params(PasswordHashParams {
+            memory_kib,
+            iterations,
+            parallelism,
+        })
+    }
+
+    /// Create a password hasher from a parameter set
+    pub fn from_params(params: PasswordHashParams) -> PersonaResult<Self> {
+        let argon2 = Argon2::new(
+            Algorithm::Argon2id,
+            Version::V0x13,
+            params.to_argon2_params()?,
+        );
+        Ok(Self { argon2, params })
+    }
+
+    /// Parameters new hashes are created with
+    pub fn params(&self) -> PasswordHashParams {
+        self.params
+    }
+
+    /// Whether a stored hash was created with weaker or different parameters
+    /// than this hasher uses, and should be re-hashed after a successful login
+    pub fn needs_rehash(&self, hash: &str) -> PersonaResult<bool> {
+        Ok(PasswordHashParams::from_hash(hash)? != self.params)
+    }
+
     /// Hash a password with a random salt
     pub fn hash_password(&self, password: &str) -> PersonaResult<String> {
         let salt = SaltString::generate(&mut OsRng);
@@ -28,6 +160,9 @@ impl PasswordHasher {
     }
 
     /// Verify a password against a hash
+    ///
+    /// Uses the parameters embedded in `hash`, not the ones this hasher was
+    /// created with, so hashes from older parameter sets keep verifying.
     pub fn verify_password(&self, password: &str, hash: &str) -> PersonaResult<bool> {
         let parsed_hash = PasswordHash::new(hash)
             .map_err(|e| PersonaError::Crypto(format!("Invalid hash format: {}", e)))?;
@@ -115,6 +250,77 @@ mod tests {
         assert!(!hasher.verify_password("wrong_password", &hash).unwrap());
     }
 
+    #[test]
+    fn test_password_hashing_with_params() {
+        let hasher = PasswordHasher::with_params(8 * 1024, 1, 1).unwrap();
+        let hash = hasher.hash_password("test_password").unwrap();
+        assert_eq!(
+            PasswordHashParams::from_hash(&hash).unwrap(),
+            PasswordHashParams {
+                memory_kib: 8 * 1024,
+                iterations: 1,
+                parallelism: 1,
+            }
+        );
+        assert!(!hasher.needs_rehash(&hash).unwrap());
+
+        // Verification follows the stored parameters, not the hasher's own
+        let default_hasher = PasswordHasher::new();
+        assert!(default_hasher
+            .verify_password("test_password", &hash)
+            .unwrap());
+        assert!(!default_hasher.verify_password("wrong", &hash).unwrap());
+        assert!(default_hasher.needs_rehash(&hash).unwrap());
+
+        assert!(PasswordHasher::with_params(0, 1, 1).is_err());
+    }
+
+    #[test]
+    fn test_legacy_hash_still_verifies() {
+        // Hashes created before parameters were configurable used Argon2::default()
+        let salt = SaltString::generate(&mut OsRng);
+        let legacy = Argon2PasswordHasher::hash_password(&Argon2::default(), b"legacy", &salt)
+            .unwrap()
+            .to_string();
+
+        let hasher = PasswordHasher::with_params(8 * 1024, 3, 2).unwrap();
+        assert!(hasher.verify_password("legacy", &legacy).unwrap());
+        assert_eq!(
+            PasswordHashParams::from_hash(&legacy).unwrap(),
+            PasswordHashParams::default()
+        );
+        assert!(!PasswordHasher::new().needs_rehash(&legacy).unwrap());
+    }
+
+    #[test]
+    fn test_calibration_is_bounded() {
+        // A machine where hashing is free never reaches the target, so the
+        // loop must stop at the ceilings
+        let mut trials = 0;
+        let params = calibrate_with(Duration::from_secs(1), |_| {
+            trials += 1;
+            Ok(Duration::ZERO)
+        })
+        .unwrap();
+        assert_eq!(params.memory_kib, CALIBRATION_MAX_MEMORY_KIB);
+        assert_eq!(params.iterations, CALIBRATION_MAX_ITERATIONS);
+        assert!(trials < 20);
+
+        // Cost proportional to memory * iterations, 1ms per default unit
+        let unit = PasswordHashParams::default();
+        let params = calibrate_with(Duration::from_millis(8), |p| {
+            Ok(Duration::from_millis(
+                (p.memory_kib / unit.memory_kib * p.iterations / unit.iterations) as u64,
+            ))
+        })
+        .unwrap();
+        assert_eq!(params.memory_kib, unit.memory_kib * 8);
+        assert_eq!(params.iterations, unit.iterations);
+
+        // Already slower than the target: keep the defaults
+        assert_eq!(calibrate_argon2(0).unwrap(), PasswordHashParams::default());
+    }
+
     #[test]
     fn test_sha256_hashing() {
         let data = b"Hello, World!";
@@ -1,12 +1,16 @@
+#[cfg(feature = "ssh")]
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey as Ed25519VerifyingKey};
 use rand::{rngs::OsRng, RngCore};
+#[cfg(feature = "ssh")]
 use zeroize::Zeroize;
 
 /// Ed25519 key pair for digital signatures
+#[cfg(feature = "ssh")]
 pub struct SigningKeyPair {
     signing_key: SigningKey,
 }
 
+#[cfg(feature = "ssh")]
 impl SigningKeyPair {
     /// Generate a new random key pair
     pub fn generate() -> Self {
@@ -57,6 +61,7 @@ impl SigningKeyPair {
     }
 }
 
+#[cfg(feature = "ssh")]
 impl Drop for SigningKeyPair {
     fn drop(&mut self) {
         // Zeroize the signing key bytes
@@ -65,10 +70,12 @@ impl Drop for SigningKeyPair {
 }
 
 /// Wrapper for public key verification
+#[cfg(feature = "ssh")]
 pub struct VerifyingKey {
     public_key: Ed25519VerifyingKey,
 }
 
+#[cfg(feature = "ssh")]
 impl VerifyingKey {
     /// Create from public key bytes
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, ed25519_dalek::SignatureError> {
@@ -137,6 +144,7 @@ impl KeyDerivation {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "ssh")]
     #[test]
     fn test_signing_keypair() {
         let keypair = SigningKeyPair::generate();
@@ -150,6 +158,7 @@ mod tests {
         assert!(keypair.verify(wrong_message, &signature).is_err());
     }
 
+    #[cfg(feature = "ssh")]
     #[test]
     fn test_verifying_key() {
         let keypair = SigningKeyPair::generate();
@@ -1,19 +1,37 @@
+#[cfg(feature = "wallet")]
 pub mod address_generator;
+#[cfg(feature = "wallet")]
+pub mod coin_selection;
 pub mod encryption;
 pub mod hashing;
 pub mod key_hierarchy;
 pub mod keys;
+pub mod secret;
+#[cfg(feature = "wallet")]
 pub mod transaction_signing;
+pub mod vault_fingerprint;
+#[cfg(feature = "wallet")]
 pub mod wallet_crypto;
+#[cfg(feature = "wallet")]
 pub mod wallet_encryption;
+#[cfg(feature = "wallet")]
 pub mod wallet_import_export;
 
+#[cfg(feature = "wallet")]
 pub use address_generator::*;
+#[cfg(feature = "wallet")]
+pub use coin_selection::*;
 pub use encryption::*;
 pub use hashing::*;
 pub use key_hierarchy::*;
 pub use keys::*;
+pub use secret::*;
+#[cfg(feature = "wallet")]
 pub use transaction_signing::*;
+pub use vault_fingerprint::*;
+#[cfg(feature = "wallet")]
 pub use wallet_crypto::*;
+#[cfg(feature = "wallet")]
 pub use wallet_encryption::*;
+#[cfg(feature = "wallet")]
 pub use wallet_import_export::*;
@@ -0,0 +1,116 @@
+//! String wrapper for secrets that must never end up in logs.
+//!
+//! `SecretString` prints as `[REDACTED]` through both `Debug` and `Display`, wipes its buffer on
+//! drop, and only hands out the plaintext through an explicit [`SecretString::expose`] call.
+//! It serializes as a plain string so stored and exported formats are unchanged.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use zeroize::Zeroize;
+
+/// Text shown in place of the secret value
+pub const REDACTED: &str = "[REDACTED]";
+
+/// A UTF-8 secret that redacts itself when formatted and zeroizes on drop
+#[derive(Clone, Default)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Wrap a secret value
+    pub fn new(value: String) -> Self {
+        Self(value)
+    }
+
+    /// Access the plaintext. Keep the borrow as short as possible.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Length of the secret in bytes
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check if the secret is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretString({})", REDACTED)
+    }
+}
+
+impl fmt::Display for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl PartialEq for SecretString {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for SecretString {}
+
+impl Zeroize for SecretString {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting_never_reveals_the_value() {
+        let secret = SecretString::from("hunter2-correct-horse");
+        assert!(!format!("{:?}", secret).contains("hunter2"));
+        assert!(!format!("{}", secret).contains("hunter2"));
+        assert_eq!(secret.expose(), "hunter2-correct-horse");
+    }
+
+    #[test]
+    fn serializes_as_plain_string() {
+        let secret = SecretString::from("s3cret");
+        let json = serde_json::to_string(&secret).unwrap();
+        assert_eq!(json, "\"s3cret\"");
+        let back: SecretString = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, secret);
+    }
+}
@@ -132,6 +132,11 @@ fn create_bitcoin_sighash(request: &TransactionRequest) -> PersonaResult<[u8; 32
         hasher.update(&fee_u64.to_le_bytes());
     }
 
+    // Commit to the selected inputs and change output when coin selection ran
+    if let Some(plan) = &request.raw_transaction_data {
+        hasher.update(plan);
+    }
+
     let hash = hasher.finalize();
     let mut sighash = [0u8; 32];
     sighash.copy_from_slice(&hash);
@@ -154,6 +159,10 @@ fn create_ethereum_transaction_hash(request: &TransactionRequest) -> PersonaResu
     if let Some(nonce) = request.nonce {
         hasher.update(&nonce.to_be_bytes());
     }
+    // EIP-155: bind the signature to one chain so it cannot be replayed on another
+    if let Some(chain_id) = request.chain_id {
+        hasher.update(chain_id.to_be_bytes());
+    }
 
     let hash = hasher.finalize();
     let mut eth_hash = [0u8; 32];
@@ -265,6 +274,7 @@ mod tests {
             id: uuid::Uuid::new_v4(),
             wallet_id: uuid::Uuid::new_v4(),
             network: BlockchainNetwork::Bitcoin,
+            chain_id: None,
             from_address: "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".to_string(),
             to_address: "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2".to_string(),
             amount: "100000".to_string(), // 0.001 BTC in satoshis
@@ -294,6 +304,7 @@ mod tests {
             id: uuid::Uuid::new_v4(),
             wallet_id: uuid::Uuid::new_v4(),
             network: BlockchainNetwork::Ethereum,
+            chain_id: Some(1),
             from_address: "0x742d35Cc6634C0532925a3b8D4E7E0E0e9e0dF6D".to_string(),
             to_address: "0x8ba1f109551bD432803012645Hac136c".to_string(),
             amount: "1000000000000000000".to_string(), // 1 ETH in wei
@@ -314,5 +325,15 @@ mod tests {
         let (signature, scheme) = result.unwrap();
         assert_eq!(scheme, SignatureScheme::ECDSA);
         assert_eq!(signature.len(), 65); // r(32) + s(32) + v(1)
+
+        // The same transfer on Sepolia must not hash like the mainnet one
+        let sepolia = TransactionRequest {
+            chain_id: Some(11_155_111),
+            ..request.clone()
+        };
+        assert_ne!(
+            create_ethereum_transaction_hash(&request).unwrap(),
+            create_ethereum_transaction_hash(&sepolia).unwrap()
+        );
     }
 }
\ No newline at end of file
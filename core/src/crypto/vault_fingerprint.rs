@@ -0,0 +1,162 @@
+//! Short, non-secret fingerprint that identifies a vault.
+//!
+//! Devices paired with the same vault (desktop, mobile, browser extension) show the same
+//! fingerprint, so a user can confirm the match at a glance. It is derived from the random
+//! installation id stored in the vault, never from key material, and says nothing about the
+//! vault's contents.
+
+use crate::crypto::Sha256Hasher;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Symbols shown per fingerprint (6 bits each)
+pub const FINGERPRINT_SYMBOLS: usize = 7;
+
+/// Bytes of the digest shown as hex
+const HEX_BYTES: usize = 8;
+
+/// 64 emoji with their names, chosen to be easy to tell apart and to read aloud
+const SYMBOLS: [(&str, &str); 64] = [
+    ("🐶", "Dog"),
+    ("🐱", "Cat"),
+    ("🦁", "Lion"),
+    ("🐎", "Horse"),
+    ("🦄", "Unicorn"),
+    ("🐷", "Pig"),
+    ("🐘", "Elephant"),
+    ("🐰", "Rabbit"),
+    ("🐼", "Panda"),
+    ("🐓", "Rooster"),
+    ("🐧", "Penguin"),
+    ("🐢", "Turtle"),
+    ("🐟", "Fish"),
+    ("🐙", "Octopus"),
+    ("🦋", "Butterfly"),
+    ("🌷", "Flower"),
+    ("🌳", "Tree"),
+    ("🌵", "Cactus"),
+    ("🍄", "Mushroom"),
+    ("🌏", "Globe"),
+    ("🌙", "Moon"),
+    ("☁️", "Cloud"),
+    ("🔥", "Fire"),
+    ("🍌", "Banana"),
+    ("🍎", "Apple"),
+    ("🍓", "Strawberry"),
+    ("🌽", "Corn"),
+    ("🍕", "Pizza"),
+    ("🎂", "Cake"),
+    ("❤️", "Heart"),
+    ("😀", "Smiley"),
+    ("🤖", "Robot"),
+    ("🎩", "Hat"),
+    ("👓", "Glasses"),
+    ("🔧", "Spanner"),
+    ("🎅", "Santa"),
+    ("👍", "Thumbs Up"),
+    ("☂️", "Umbrella"),
+    ("⌛", "Hourglass"),
+    ("⏰", "Clock"),
+    ("🎁", "Gift"),
+    ("💡", "Light Bulb"),
+    ("📕", "Book"),
+    ("✏️", "Pencil"),
+    ("📎", "Paperclip"),
+    ("✂️", "Scissors"),
+    ("🔒", "Lock"),
+    ("🔑", "Key"),
+    ("🔨", "Hammer"),
+    ("☎️", "Telephone"),
+    ("🏁", "Flag"),
+    ("🚂", "Train"),
+    ("🚲", "Bicycle"),
+    ("✈️", "Aeroplane"),
+    ("🚀", "Rocket"),
+    ("🏆", "Trophy"),
+    ("⚽", "Ball"),
+    ("🎸", "Guitar"),
+    ("🎺", "Trumpet"),
+    ("🔔", "Bell"),
+    ("⚓", "Anchor"),
+    ("🎧", "Headphones"),
+    ("📁", "Folder"),
+    ("📌", "Pin"),
+];
+
+/// Fingerprint of one vault, in the forms a UI may show
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultFingerprint {
+    /// Emoji separated by spaces, e.g. `🐶 🔑 🚀 …`
+    pub emoji: String,
+    /// Names of the same emoji, for reading aloud or screen readers
+    pub words: Vec<String>,
+    /// Leading digest bytes as lowercase hex, for logs and support requests
+    pub hex: String,
+}
+
+impl VaultFingerprint {
+    /// Fingerprint for the vault with `installation_id`
+    pub fn from_installation_id(installation_id: &str) -> Self {
+        let digest =
+            Sha256Hasher::hash_string(&format!("persona-vault-fingerprint:{}", installation_id));
+        let symbols: Vec<(&str, &str)> = (0..FINGERPRINT_SYMBOLS)
+            .map(|i| SYMBOLS[six_bits(&digest, i * 6)])
+            .collect();
+        Self {
+            emoji: symbols
+                .iter()
+                .map(|(emoji, _)| *emoji)
+                .collect::<Vec<_>>()
+                .join(" "),
+            words: symbols
+                .iter()
+                .map(|(_, word)| word.to_string())
+                .collect(),
+            hex: hex::encode(&digest[..HEX_BYTES]),
+        }
+    }
+}
+
+impl fmt::Display for VaultFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.emoji)
+    }
+}
+
+/// Six bits of `bytes` starting at bit `offset`, most significant bit first
+fn six_bits(bytes: &[u8], offset: usize) -> usize {
+    let pair = u16::from_be_bytes([bytes[offset / 8], bytes[offset / 8 + 1]]);
+    ((pair >> (10 - offset % 8)) & 0x3f) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_is_deterministic() {
+        let a = VaultFingerprint::from_installation_id("0123456789abcdef0123456789abcdef");
+        let b = VaultFingerprint::from_installation_id("0123456789abcdef0123456789abcdef");
+        assert_eq!(a, b);
+        assert_eq!(a.words.len(), FINGERPRINT_SYMBOLS);
+        assert_eq!(a.emoji.split(' ').count(), FINGERPRINT_SYMBOLS);
+        assert_eq!(a.hex.len(), HEX_BYTES * 2);
+        assert_eq!(a.to_string(), a.emoji);
+    }
+
+    #[test]
+    fn different_vaults_have_different_fingerprints() {
+        let a = VaultFingerprint::from_installation_id("0123456789abcdef0123456789abcdef");
+        let b = VaultFingerprint::from_installation_id("fedcba9876543210fedcba9876543210");
+        assert_ne!(a.hex, b.hex);
+        assert_ne!(a.words, b.words);
+    }
+
+    #[test]
+    fn six_bits_reads_across_byte_boundaries() {
+        let bytes = [0b1010_1111, 0b0000_0011, 0b1100_0000];
+        assert_eq!(six_bits(&bytes, 0), 0b101011);
+        assert_eq!(six_bits(&bytes, 6), 0b110000);
+        assert_eq!(six_bits(&bytes, 12), 0b001111);
+    }
+}
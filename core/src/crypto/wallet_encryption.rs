@@ -1,6 +1,7 @@
 // Wallet-specific encryption for private keys and mnemonics
 
 use crate::crypto::encryption::{decrypt_data, encrypt_data};
+use crate::crypto::secret::SecretString;
 use crate::crypto::wallet_crypto::{MasterKey, SecureMnemonic};
 use crate::{PersonaError, PersonaResult};
 use serde::{Deserialize, Serialize};
@@ -124,7 +125,7 @@ pub fn encrypt_mnemonic(mnemonic: &str, password: &str) -> PersonaResult<Encrypt
 pub fn decrypt_mnemonic(
     encrypted_mnemonic: &EncryptedMnemonic,
     password: &str,
-) -> PersonaResult<String> {
+) -> PersonaResult<SecretString> {
     if encrypted_mnemonic.version != 1 {
         return Err(PersonaError::Cryptography(format!(
             "Unsupported encryption version: {}",
@@ -141,6 +142,7 @@ pub fn decrypt_mnemonic(
     .map_err(|e| PersonaError::Cryptography(format!("Failed to decrypt mnemonic: {}", e)))?;
 
     String::from_utf8(decrypted)
+        .map(SecretString::new)
         .map_err(|e| PersonaError::Cryptography(format!("Invalid UTF-8 in mnemonic: {}", e)))
 }
 
@@ -276,7 +278,7 @@ mod tests {
         let encrypted = encrypt_mnemonic(mnemonic, password).unwrap();
         let decrypted = decrypt_mnemonic(&encrypted, password).unwrap();
 
-        assert_eq!(decrypted, mnemonic);
+        assert_eq!(decrypted.expose(), mnemonic);
     }
 
     #[test]
@@ -2,9 +2,11 @@
 
 use crate::crypto::address_generator::{
     generate_bitcoin_address, generate_bitcoin_address_from_compressed_pubkey,
-    generate_ethereum_address_checksummed, generate_ethereum_address_checksummed_from_compressed_pubkey,
-    BitcoinAddressType,
+    generate_ethereum_address_checksummed,
+    generate_ethereum_address_checksummed_from_compressed_pubkey, generate_solana_address,
+    solana_derivation_path, BitcoinAddressType, SOLANA_BASE_PATH,
 };
+use crate::crypto::secret::SecretString;
 use crate::crypto::wallet_crypto::{
     Bip44PathBuilder, CoinType, DerivedKey, MasterKey, MnemonicWordCount, SecureMnemonic,
 };
@@ -12,7 +14,7 @@ use crate::crypto::wallet_encryption::{
     decrypt_mnemonic, encrypt_master_key, encrypt_mnemonic, EncryptedMnemonic, EncryptedWalletKey,
     WalletKeyMaterial,
 };
-use crate::models::wallet::{BlockchainNetwork, CryptoWallet, WalletType};
+use crate::models::wallet::{BlockchainNetwork, CryptoWallet, NetworkKind, WalletType};
 use crate::{PersonaError, PersonaResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -51,21 +53,27 @@ pub struct WalletExport {
     pub wallet_id: Uuid,
     pub name: String,
     pub network: String,
+    #[serde(default)]
+    pub network_kind: NetworkKind,
     pub wallet_type: String,
     pub derivation_path: Option<String>,
-    pub mnemonic: Option<String>,
+    pub mnemonic: Option<SecretString>,
     pub private_keys: Option<HashMap<String, String>>,
     pub addresses: Vec<String>,
     pub created_at: String,
 }
 
-/// Import wallet from mnemonic phrase
+/// Import wallet from mnemonic phrase.
+///
+/// `passphrase` is the optional BIP-39 passphrase ("25th word"; "" for none). It only feeds
+/// the seed and is never stored, so exporting the mnemonic alone does not restore the wallet.
 pub fn import_from_mnemonic(
     identity_id: Uuid,
     name: String,
     mnemonic_phrase: &str,
     passphrase: &str,
     network: BlockchainNetwork,
+    network_kind: NetworkKind,
     derivation_path: Option<String>,
     address_count: usize,
     password: &str,
@@ -77,10 +85,21 @@ pub fn import_from_mnemonic(
     let master_key = MasterKey::from_mnemonic(&mnemonic, passphrase)?;
 
     // Determine derivation path
-    let path = derivation_path.unwrap_or_else(|| {
-        let coin_type = network_to_coin_type(&network);
-        Bip44PathBuilder::new(coin_type).build()
-    });
+    let path = if network == BlockchainNetwork::Solana {
+        // Solana accounts are hardened siblings under the coin node (see `solana_derivation_path`)
+        if derivation_path.is_some_and(|path| path != SOLANA_BASE_PATH) {
+            return Err(PersonaError::InvalidInput(format!(
+                "Solana wallets use the {}/<index>'/0' layout",
+                SOLANA_BASE_PATH
+            )));
+        }
+        SOLANA_BASE_PATH.to_string()
+    } else {
+        derivation_path.unwrap_or_else(|| {
+            let coin_type = network_to_coin_type(&network, network_kind);
+            Bip44PathBuilder::new(coin_type).build()
+        })
+    };
 
     // Encrypt master key
     let encrypted_key = encrypt_master_key(&master_key, password)?;
@@ -102,6 +121,7 @@ pub fn import_from_mnemonic(
             .map_err(|e| PersonaError::Cryptography(format!("Serialization error: {}", e)))?,
     );
 
+    wallet.network_kind = network_kind;
     wallet.derivation_path = Some(path.clone());
     wallet.extended_public_key = Some(master_key.to_xpub());
     wallet.encrypted_mnemonic = Some(
@@ -110,7 +130,7 @@ pub fn import_from_mnemonic(
     );
 
     // Derive addresses
-    let addresses = derive_addresses(&master_key, &path, &network, address_count)?;
+    let addresses = derive_addresses(&master_key, &path, &network, network_kind, address_count)?;
     wallet.addresses = addresses;
 
     Ok(wallet)
@@ -122,6 +142,7 @@ pub fn import_from_private_key(
     name: String,
     private_key_hex: &str,
     network: BlockchainNetwork,
+    network_kind: NetworkKind,
     password: &str,
 ) -> PersonaResult<CryptoWallet> {
     // Parse private key
@@ -147,6 +168,7 @@ pub fn import_from_private_key(
         serde_json::to_vec(&encrypted_key)
             .map_err(|e| PersonaError::Cryptography(format!("Serialization error: {}", e)))?,
     );
+    wallet.network_kind = network_kind;
 
     // Derive address from private key (secp256k1)
     let signing_key = k256::ecdsa::SigningKey::from_bytes(private_key_bytes.as_slice().into())
@@ -163,7 +185,7 @@ pub fn import_from_private_key(
             generate_bitcoin_address_from_compressed_pubkey(
                 &compressed,
                 BitcoinAddressType::P2WPKH,
-                false,
+                network_kind,
             )?,
             crate::models::wallet::AddressType::P2WPKH,
         ),
@@ -199,7 +221,7 @@ pub fn import_from_private_key(
 }
 
 /// Export wallet mnemonic (requires password)
-pub fn export_mnemonic(wallet: &CryptoWallet, password: &str) -> PersonaResult<String> {
+pub fn export_mnemonic(wallet: &CryptoWallet, password: &str) -> PersonaResult<SecretString> {
     let encrypted_mnemonic_bytes = wallet
         .encrypted_mnemonic
         .as_ref()
@@ -243,6 +265,7 @@ pub fn export_to_json(
         wallet_id: wallet.id,
         name: wallet.name.clone(),
         network: format!("{:?}", wallet.network),
+        network_kind: wallet.network_kind,
         wallet_type: format!("{:?}", wallet.wallet_type),
         derivation_path: wallet.derivation_path.clone(),
         mnemonic: None,
@@ -299,7 +322,15 @@ pub fn parse_export_format(format_str: &str) -> PersonaResult<ExportFormat> {
 
 // Helper functions
 
-fn network_to_coin_type(network: &BlockchainNetwork) -> CoinType {
+fn network_to_coin_type(network: &BlockchainNetwork, network_kind: NetworkKind) -> CoinType {
+    // Test networks of UTXO chains share coin type 1'
+    let utxo = matches!(
+        network,
+        BlockchainNetwork::Bitcoin | BlockchainNetwork::Litecoin | BlockchainNetwork::Dogecoin
+    );
+    if utxo && !network_kind.is_mainnet() {
+        return CoinType::Testnet;
+    }
     match network {
         BlockchainNetwork::Bitcoin => CoinType::Bitcoin,
         BlockchainNetwork::Ethereum => CoinType::Ethereum,
@@ -318,26 +349,42 @@ fn derive_addresses(
     master_key: &MasterKey,
     base_path: &str,
     network: &BlockchainNetwork,
+    network_kind: NetworkKind,
     count: usize,
 ) -> PersonaResult<Vec<crate::models::wallet::WalletAddress>> {
     let mut addresses = Vec::new();
 
-    // Parse base path and derive parent
-    let parent_key = master_key.derive_path(base_path)?;
+    // Parse base path and derive parent (Solana derives ed25519 keys from the seed instead)
+    let parent_key = match network {
+        BlockchainNetwork::Solana => None,
+        _ => Some(master_key.derive_path(base_path)?),
+    };
 
     for i in 0..count {
-        let child_key = parent_key.derive_child(i as u32, false)?;
-        let address_string = match network {
-            BlockchainNetwork::Bitcoin => {
-                generate_bitcoin_address(&child_key, BitcoinAddressType::P2WPKH, false)?
-            }
-            BlockchainNetwork::Ethereum
-            | BlockchainNetwork::Polygon
-            | BlockchainNetwork::Arbitrum
-            | BlockchainNetwork::Optimism
-            | BlockchainNetwork::BinanceSmartChain => {
-                generate_ethereum_address_checksummed(&child_key)?
-            }
+        let (address_string, path) = match (network, &parent_key) {
+            (BlockchainNetwork::Solana, _) => (
+                generate_solana_address(master_key, i as u32)?,
+                solana_derivation_path(i as u32),
+            ),
+            (BlockchainNetwork::Bitcoin, Some(parent_key)) => (
+                generate_bitcoin_address(
+                    &parent_key.derive_child(i as u32, false)?,
+                    BitcoinAddressType::P2WPKH,
+                    network_kind,
+                )?,
+                format!("{}/{}", base_path, i),
+            ),
+            (
+                BlockchainNetwork::Ethereum
+                | BlockchainNetwork::Polygon
+                | BlockchainNetwork::Arbitrum
+                | BlockchainNetwork::Optimism
+                | BlockchainNetwork::BinanceSmartChain,
+                Some(parent_key),
+            ) => (
+                generate_ethereum_address_checksummed(&parent_key.derive_child(i as u32, false)?)?,
+                format!("{}/{}", base_path, i),
+            ),
             _ => {
                 return Err(PersonaError::Cryptography(format!(
                     "Address generation not implemented for {:?}",
@@ -350,9 +397,10 @@ fn derive_addresses(
             address: address_string,
             address_type: match network {
                 BlockchainNetwork::Bitcoin => crate::models::wallet::AddressType::P2WPKH,
+                BlockchainNetwork::Solana => crate::models::wallet::AddressType::Solana,
                 _ => crate::models::wallet::AddressType::Ethereum,
             },
-            derivation_path: Some(format!("{}/{}", base_path, i)),
+            derivation_path: Some(path),
             index: i as u32,
             used: false,
             balance: None,
@@ -383,6 +431,7 @@ mod tests {
             test_mnemonic,
             "",
             BlockchainNetwork::Bitcoin,
+            NetworkKind::Mainnet,
             None,
             5,
             password,
@@ -395,6 +444,122 @@ mod tests {
         assert!(wallet.encrypted_mnemonic.is_some());
     }
 
+    #[test]
+    fn test_import_testnet_wallet() {
+        let test_mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let import = |network, kind| {
+            import_from_mnemonic(
+                Uuid::new_v4(),
+                "Test Wallet".to_string(),
+                test_mnemonic,
+                "",
+                network,
+                kind,
+                None,
+                2,
+                "test_password",
+            )
+            .unwrap()
+        };
+
+        let mainnet = import(BlockchainNetwork::Bitcoin, NetworkKind::Mainnet);
+        let testnet = import(BlockchainNetwork::Bitcoin, NetworkKind::Testnet);
+        assert_eq!(testnet.network_kind, NetworkKind::Testnet);
+        assert_eq!(mainnet.derivation_path.as_deref(), Some("m/44'/0'/0'/0/0"));
+        assert_eq!(testnet.derivation_path.as_deref(), Some("m/44'/1'/0'/0/0"));
+        assert!(mainnet
+            .addresses
+            .iter()
+            .all(|a| a.address.starts_with("bc1q")));
+        assert!(testnet
+            .addresses
+            .iter()
+            .all(|a| a.address.starts_with("tb1q")));
+
+        // EVM testnets reuse the mainnet account
+        let eth_mainnet = import(BlockchainNetwork::Ethereum, NetworkKind::Mainnet);
+        let eth_testnet = import(BlockchainNetwork::Ethereum, NetworkKind::Testnet);
+        let addresses = |wallet: &CryptoWallet| {
+            wallet
+                .addresses
+                .iter()
+                .map(|a| a.address.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(addresses(&eth_mainnet), addresses(&eth_testnet));
+    }
+
+    #[test]
+    fn test_import_with_passphrase() {
+        let test_mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let import = |passphrase| {
+            import_from_mnemonic(
+                Uuid::new_v4(),
+                "Test Wallet".to_string(),
+                test_mnemonic,
+                passphrase,
+                BlockchainNetwork::Ethereum,
+                NetworkKind::Mainnet,
+                Some("m/44'/60'/0'/0".to_string()),
+                1,
+                "test_password",
+            )
+            .unwrap()
+        };
+
+        let plain = import("");
+        let protected = import("TREZOR");
+        assert_eq!(
+            plain.addresses[0].address,
+            "0x9858EfFD232B4033E47d90003D41EC34EcaEda94"
+        );
+        assert_ne!(protected.addresses[0].address, plain.addresses[0].address);
+        assert_ne!(
+            import("trezor").addresses[0].address,
+            protected.addresses[0].address
+        );
+    }
+
+    #[test]
+    fn test_import_solana_wallet() {
+        let test_mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let password = "test_password";
+        let wallet = import_from_mnemonic(
+            Uuid::new_v4(),
+            "Solana Wallet".to_string(),
+            test_mnemonic,
+            "",
+            BlockchainNetwork::Solana,
+            NetworkKind::Mainnet,
+            None,
+            2,
+            password,
+        )
+        .unwrap();
+
+        assert_eq!(wallet.derivation_path.as_deref(), Some("m/44'/501'"));
+        let first = &wallet.addresses[0];
+        assert_eq!(
+            first.address,
+            "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk"
+        );
+        assert_eq!(first.derivation_path.as_deref(), Some("m/44'/501'/0'/0'"));
+        assert_eq!(
+            first.address_type,
+            crate::models::wallet::AddressType::Solana
+        );
+
+        // The stored key keeps the seed, so later addresses can still be derived
+        let encrypted: EncryptedWalletKey =
+            serde_json::from_slice(&wallet.encrypted_private_key).unwrap();
+        let master_key =
+            crate::crypto::wallet_encryption::decrypt_master_key(&encrypted, password).unwrap();
+        assert_eq!(
+            generate_solana_address(&master_key, 1).unwrap(),
+            wallet.addresses[1].address
+        );
+    }
+
     #[test]
     fn test_export_mnemonic() {
         let test_mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
@@ -407,6 +572,7 @@ mod tests {
             test_mnemonic,
             "",
             BlockchainNetwork::Ethereum,
+            NetworkKind::Mainnet,
             None,
             1,
             password,
@@ -414,7 +580,7 @@ mod tests {
         .unwrap();
 
         let exported = export_mnemonic(&wallet, password).unwrap();
-        assert_eq!(exported, test_mnemonic);
+        assert_eq!(exported.expose(), test_mnemonic);
     }
 
     #[test]
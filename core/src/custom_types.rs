@@ -0,0 +1,299 @@
+//! Registry for host-defined credential types.
+//!
+//! `CredentialType::Custom(name)` credentials store their fields as a JSON object inside
+//! `CredentialData::Raw`. A host app registers a [`CustomCredentialSchema`] describing those
+//! fields so front-ends can render and reveal them instead of dumping raw bytes.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::{CredentialData, CredentialType};
+use crate::{PersonaError, PersonaResult};
+
+/// Placeholder shown in place of secret values that were not revealed
+pub const MASKED_VALUE: &str = "••••••••";
+
+/// A single field of a custom credential type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomFieldSpec {
+    /// Key in the stored JSON object
+    pub name: String,
+    /// Human-readable label
+    pub label: String,
+    /// Whether the value is hidden unless explicitly revealed
+    #[serde(default)]
+    pub secret: bool,
+}
+
+/// Schema describing a custom credential type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomCredentialSchema {
+    /// Name matching `CredentialType::Custom(name)`
+    pub type_name: String,
+    /// Fields in display order
+    pub fields: Vec<CustomFieldSpec>,
+    /// One-line summary with `{field}` placeholders, e.g. `"{ssid} ({security})"`
+    #[serde(default)]
+    pub display_template: Option<String>,
+}
+
+impl CustomCredentialSchema {
+    /// Create an empty schema for the given type name
+    pub fn new(type_name: impl Into<String>) -> Self {
+        Self {
+            type_name: type_name.into(),
+            fields: Vec::new(),
+            display_template: None,
+        }
+    }
+
+    /// Add a plain field
+    pub fn field(mut self, name: impl Into<String>, label: impl Into<String>) -> Self {
+        self.fields.push(CustomFieldSpec {
+            name: name.into(),
+            label: label.into(),
+            secret: false,
+        });
+        self
+    }
+
+    /// Add a field that is masked unless revealed
+    pub fn secret_field(mut self, name: impl Into<String>, label: impl Into<String>) -> Self {
+        self.fields.push(CustomFieldSpec {
+            name: name.into(),
+            label: label.into(),
+            secret: true,
+        });
+        self
+    }
+
+    /// Set the summary template
+    pub fn display_template(mut self, template: impl Into<String>) -> Self {
+        self.display_template = Some(template.into());
+        self
+    }
+
+    fn validate(&self) -> PersonaResult<()> {
+        if self.type_name.trim().is_empty() {
+            return Err(PersonaError::Validation(
+                "Custom credential type name cannot be empty".to_string(),
+            ));
+        }
+        if self.fields.is_empty() {
+            return Err(PersonaError::Validation(format!(
+                "Custom credential type '{}' must define at least one field",
+                self.type_name
+            )));
+        }
+        if let Some(template) = &self.display_template {
+            for placeholder in template_placeholders(template) {
+                if !self.fields.iter().any(|f| f.name == placeholder) {
+                    return Err(PersonaError::Validation(format!(
+                        "Display template for '{}' references unknown field '{}'",
+                        self.type_name, placeholder
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A field value prepared for display
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RenderedField {
+    pub name: String,
+    pub label: String,
+    pub value: String,
+    pub secret: bool,
+}
+
+/// A custom credential formatted through its registered schema
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CustomCredentialView {
+    pub type_name: String,
+    pub summary: Option<String>,
+    pub fields: Vec<RenderedField>,
+}
+
+/// Registered custom credential schemas, keyed by type name
+#[derive(Debug, Clone, Default)]
+pub struct CustomTypeRegistry {
+    schemas: HashMap<String, CustomCredentialSchema>,
+}
+
+impl CustomTypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a schema. Re-registering a type name replaces the previous schema.
+    pub fn register(&mut self, schema: CustomCredentialSchema) -> PersonaResult<()> {
+        schema.validate()?;
+        self.schemas.insert(schema.type_name.clone(), schema);
+        Ok(())
+    }
+
+    /// Look up a schema by type name
+    pub fn get(&self, type_name: &str) -> Option<&CustomCredentialSchema> {
+        self.schemas.get(type_name)
+    }
+
+    /// Registered type names
+    pub fn type_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.schemas.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Format credential data for a registered custom type.
+    ///
+    /// Returns `Ok(None)` when the type is not custom, not registered, or the data is not raw.
+    /// Secret fields are masked unless `reveal` is set.
+    pub fn format(
+        &self,
+        credential_type: &CredentialType,
+        data: &CredentialData,
+        reveal: bool,
+    ) -> PersonaResult<Option<CustomCredentialView>> {
+        let (schema, bytes) = match (credential_type, data) {
+            (CredentialType::Custom(name), CredentialData::Raw(bytes)) => match self.get(name) {
+                Some(schema) => (schema, bytes),
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+
+        let values: serde_json::Map<String, serde_json::Value> = if bytes.is_empty() {
+            serde_json::Map::new()
+        } else {
+            serde_json::from_slice(bytes).map_err(|e| {
+                PersonaError::Validation(format!(
+                    "Custom credential '{}' does not contain a JSON object: {}",
+                    schema.type_name, e
+                ))
+            })?
+        };
+
+        let fields: Vec<RenderedField> = schema
+            .fields
+            .iter()
+            .map(|spec| {
+                let value = values
+                    .get(&spec.name)
+                    .map(json_to_display)
+                    .unwrap_or_default();
+                RenderedField {
+                    name: spec.name.clone(),
+                    label: spec.label.clone(),
+                    value: if spec.secret && !reveal && !value.is_empty() {
+                        MASKED_VALUE.to_string()
+                    } else {
+                        value
+                    },
+                    secret: spec.secret,
+                }
+            })
+            .collect();
+
+        let summary = schema.display_template.as_ref().map(|template| {
+            fields.iter().fold(template.clone(), |acc, field| {
+                acc.replace(&format!("{{{}}}", field.name), &field.value)
+            })
+        });
+
+        Ok(Some(CustomCredentialView {
+            type_name: schema.type_name.clone(),
+            summary,
+            fields,
+        }))
+    }
+}
+
+fn json_to_display(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn template_placeholders(template: &str) -> Vec<&str> {
+    let mut placeholders = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) => {
+                placeholders.push(&after[..end]);
+                rest = &after[end + 1..];
+            }
+            None => break,
+        }
+    }
+    placeholders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi_schema() -> CustomCredentialSchema {
+        CustomCredentialSchema::new("WifiNetwork")
+            .field("ssid", "Network name")
+            .field("security", "Security")
+            .secret_field("passphrase", "Passphrase")
+            .display_template("{ssid} ({security})")
+    }
+
+    fn wifi_data() -> CredentialData {
+        CredentialData::Raw(
+            serde_json::to_vec(&serde_json::json!({
+                "ssid": "HomeNet",
+                "security": "WPA2",
+                "passphrase": "correct horse",
+            }))
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn masks_secret_fields_unless_revealed() {
+        let mut registry = CustomTypeRegistry::new();
+        registry.register(wifi_schema()).unwrap();
+        let kind = CredentialType::Custom("WifiNetwork".to_string());
+
+        let view = registry
+            .format(&kind, &wifi_data(), false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(view.summary.as_deref(), Some("HomeNet (WPA2)"));
+        assert_eq!(view.fields[2].value, MASKED_VALUE);
+
+        let revealed = registry.format(&kind, &wifi_data(), true).unwrap().unwrap();
+        assert_eq!(revealed.fields[2].value, "correct horse");
+    }
+
+    #[test]
+    fn unregistered_or_builtin_types_are_skipped() {
+        let registry = CustomTypeRegistry::new();
+        let kind = CredentialType::Custom("WifiNetwork".to_string());
+        assert!(registry
+            .format(&kind, &wifi_data(), true)
+            .unwrap()
+            .is_none());
+        assert!(registry
+            .format(&CredentialType::Password, &wifi_data(), true)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rejects_template_with_unknown_field() {
+        let mut registry = CustomTypeRegistry::new();
+        let schema = CustomCredentialSchema::new("Broken")
+            .field("a", "A")
+            .display_template("{b}");
+        assert!(registry.register(schema).is_err());
+    }
+}
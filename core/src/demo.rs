@@ -0,0 +1,258 @@
+//! Synthetic vault used by demo mode (documentation screenshots, support sessions).
+//!
+//! Fixtures use fixed IDs so `persona --demo credential show <id>` works across runs, and every
+//! secret that leaves the service in demo mode is replaced by an obviously fake value.
+
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::crypto::SecretString;
+use crate::models::{
+    ApiKeyData, BankCardData, Credential, CredentialData, CredentialType, CryptoWalletData,
+    Identity, IdentityType, OtpType, PasswordCredentialData, SecurityLevel, ServerConfigData,
+    SshKeyData, TwoFactorData,
+};
+
+/// Master password of the demo vault (the vault only ever lives in memory)
+pub const DEMO_MASTER_PASSWORD: &str = "persona-demo";
+
+/// Value returned in place of any revealed secret
+pub const DEMO_SECRET_PLACEHOLDER: &str = "demo-not-a-real-secret";
+
+/// Base32 TOTP seed returned in demo mode, so codes still render
+pub const DEMO_TOTP_SECRET: &str = "DEMODEMODEMODEMO";
+
+/// Identities seeded into the demo vault
+pub fn demo_identities() -> Vec<Identity> {
+    let mut personal = Identity::new("Alex Example".to_string(), IdentityType::Personal);
+    personal.id = Uuid::from_u128(0xde30_0000_0000_0000_0000_0000_0000_0001);
+    personal.email = Some("alex@example.com".to_string());
+    personal.description = Some("Everyday accounts".to_string());
+    personal.tags = vec!["demo".to_string()];
+
+    let mut work = Identity::new("Alex @ Example Corp".to_string(), IdentityType::Work);
+    work.id = Uuid::from_u128(0xde30_0000_0000_0000_0000_0000_0000_0002);
+    work.email = Some("alex@corp.example".to_string());
+    work.description = Some("Work tooling and servers".to_string());
+    work.tags = vec!["demo".to_string()];
+
+    vec![personal, work]
+}
+
+/// Credentials (with their plaintext data) seeded into the demo vault
+pub fn demo_credentials() -> Vec<(Credential, CredentialData)> {
+    let identities = demo_identities();
+    let personal = identities[0].id;
+    let work = identities[1].id;
+
+    let fixture = |n: u128,
+                   identity_id: Uuid,
+                   name: &str,
+                   credential_type: CredentialType,
+                   security_level: SecurityLevel,
+                   username: Option<&str>,
+                   url: Option<&str>| {
+        let mut credential = Credential::new(
+            identity_id,
+            name.to_string(),
+            credential_type,
+            security_level,
+            Vec::new(),
+            None,
+        );
+        credential.id = Uuid::from_u128(0xde30_0000_0000_0000_0000_0001_0000_0000 + n);
+        credential.username = username.map(str::to_string);
+        credential.url = url.map(str::to_string);
+        credential.tags = vec!["demo".to_string()];
+        credential
+    };
+
+    let mut server_config = HashMap::new();
+    server_config.insert("region".to_string(), "eu-example-1".to_string());
+
+    vec![
+        (
+            fixture(
+                1,
+                personal,
+                "Example Mail",
+                CredentialType::Password,
+                SecurityLevel::High,
+                Some("alex@example.com"),
+                Some("https://mail.example.com"),
+            ),
+            CredentialData::Password(PasswordCredentialData {
+                password: DEMO_SECRET_PLACEHOLDER.into(),
+                email: Some("alex@example.com".to_string()),
+                security_questions: Vec::new(),
+            }),
+        ),
+        (
+            fixture(
+                2,
+                personal,
+                "Example Bank Card",
+                CredentialType::BankCard,
+                SecurityLevel::Critical,
+                None,
+                None,
+            ),
+            CredentialData::BankCard(BankCardData {
+                card_number: "4000 0000 0000 0002".into(),
+                cardholder_name: "ALEX EXAMPLE".to_string(),
+                expiry_date: "12/99".to_string(),
+                cvv: "000".into(),
+                bank_name: "Example Bank".to_string(),
+                card_type: "visa".to_string(),
+            }),
+        ),
+        (
+            fixture(
+                3,
+                personal,
+                "Example Wallet",
+                CredentialType::CryptoWallet,
+                SecurityLevel::Critical,
+                None,
+                None,
+            ),
+            CredentialData::CryptoWallet(CryptoWalletData {
+                wallet_type: "bitcoin".to_string(),
+                mnemonic_phrase: Some(DEMO_SECRET_PLACEHOLDER.into()),
+                private_key: None,
+                public_key: "02demo".to_string(),
+                address: "bc1qdemoaddressnotreal".to_string(),
+                network: "testnet".to_string(),
+            }),
+        ),
+        (
+            fixture(
+                4,
+                work,
+                "Example Git Hosting",
+                CredentialType::TwoFactor,
+                SecurityLevel::High,
+                Some("alex"),
+                Some("https://git.example.com"),
+            ),
+            CredentialData::TwoFactor(TwoFactorData {
+                secret_key: DEMO_TOTP_SECRET.into(),
+                issuer: "Example Git".to_string(),
+                account_name: "alex@corp.example".to_string(),
+                algorithm: "SHA1".to_string(),
+                digits: 6,
+                period: 30,
+                otp_type: OtpType::Totp,
+                counter: 0,
+                time_offset_seconds: 0,
+            }),
+        ),
+        (
+            fixture(
+                5,
+                work,
+                "Deploy API",
+                CredentialType::ApiKey,
+                SecurityLevel::High,
+                None,
+                Some("https://api.example.com"),
+            ),
+            CredentialData::ApiKey(ApiKeyData {
+                api_key: DEMO_SECRET_PLACEHOLDER.into(),
+                api_secret: None,
+                token: None,
+                permissions: vec!["deploy".to_string(), "read".to_string()],
+                expires_at: None,
+            }),
+        ),
+        (
+            fixture(
+                6,
+                work,
+                "Build Server",
+                CredentialType::ServerConfig,
+                SecurityLevel::High,
+                Some("deploy"),
+                None,
+            ),
+            CredentialData::ServerConfig(ServerConfigData {
+                hostname: "build.example.com".to_string(),
+                ip_address: Some("192.0.2.10".to_string()),
+                port: 22,
+                protocol: "ssh".to_string(),
+                username: "deploy".to_string(),
+                password: None,
+                ssh_key_id: Some(Uuid::from_u128(0xde30_0000_0000_0000_0000_0001_0000_0007)),
+                additional_config: server_config,
+            }),
+        ),
+        (
+            fixture(
+                7,
+                work,
+                "Deploy SSH Key",
+                CredentialType::SshKey,
+                SecurityLevel::High,
+                None,
+                None,
+            ),
+            CredentialData::SshKey(SshKeyData {
+                private_key: DEMO_SECRET_PLACEHOLDER.into(),
+                public_key: "ssh-ed25519 AAAAdemo alex@corp.example".to_string(),
+                key_type: "ed25519".to_string(),
+                passphrase: None,
+            }),
+        ),
+    ]
+}
+
+/// Replace every sensitive field with an obviously fake value.
+pub fn redact_for_demo(data: &CredentialData) -> CredentialData {
+    let fake = || SecretString::from(DEMO_SECRET_PLACEHOLDER);
+    let fake_opt = |value: &Option<SecretString>| value.as_ref().map(|_| fake());
+
+    match data {
+        CredentialData::Password(d) => CredentialData::Password(PasswordCredentialData {
+            password: fake(),
+            email: d.email.clone(),
+            security_questions: d
+                .security_questions
+                .iter()
+                .map(|q| crate::models::SecurityQuestion {
+                    question: q.question.clone(),
+                    answer: fake(),
+                })
+                .collect(),
+        }),
+        CredentialData::CryptoWallet(d) => CredentialData::CryptoWallet(CryptoWalletData {
+            mnemonic_phrase: fake_opt(&d.mnemonic_phrase),
+            private_key: fake_opt(&d.private_key),
+            ..d.clone()
+        }),
+        CredentialData::SshKey(d) => CredentialData::SshKey(SshKeyData {
+            private_key: fake(),
+            passphrase: fake_opt(&d.passphrase),
+            ..d.clone()
+        }),
+        CredentialData::ApiKey(d) => CredentialData::ApiKey(ApiKeyData {
+            api_key: fake(),
+            api_secret: fake_opt(&d.api_secret),
+            token: fake_opt(&d.token),
+            ..d.clone()
+        }),
+        CredentialData::BankCard(d) => CredentialData::BankCard(BankCardData {
+            card_number: "4000 0000 0000 0002".into(),
+            cvv: "000".into(),
+            ..d.clone()
+        }),
+        CredentialData::ServerConfig(d) => CredentialData::ServerConfig(ServerConfigData {
+            password: fake_opt(&d.password),
+            ..d.clone()
+        }),
+        CredentialData::TwoFactor(d) => CredentialData::TwoFactor(TwoFactorData {
+            secret_key: DEMO_TOTP_SECRET.into(),
+            ..d.clone()
+        }),
+        CredentialData::Raw(_) => CredentialData::Raw(Vec::new()),
+    }
+}
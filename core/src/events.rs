@@ -0,0 +1,85 @@
+//! Vault change notifications.
+//!
+//! `PersonaService` publishes a [`VaultEvent`] after every successful mutation so UI layers can
+//! refresh just what changed instead of re-querying everything. Publishing never blocks and is
+//! a no-op when nobody is subscribed; slow subscribers see `RecvError::Lagged` and should
+//! fall back to a full reload.
+
+use serde::Serialize;
+use std::path::PathBuf;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Number of events buffered per subscriber before it starts lagging
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// A change to the vault
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VaultEvent {
+    IdentityCreated {
+        identity_id: Uuid,
+    },
+    IdentityUpdated {
+        identity_id: Uuid,
+    },
+    IdentityDeleted {
+        identity_id: Uuid,
+    },
+    CredentialCreated {
+        credential_id: Uuid,
+        identity_id: Uuid,
+    },
+    CredentialUpdated {
+        credential_id: Uuid,
+        identity_id: Uuid,
+    },
+    CredentialDeleted {
+        credential_id: Uuid,
+        identity_id: Uuid,
+    },
+    Locked,
+    Unlocked,
+    /// A backup was written, before a destructive operation or on schedule
+    BackupCreated {
+        path: PathBuf,
+    },
+    /// A scheduled backup could not be written
+    BackupFailed {
+        error: String,
+    },
+    /// The SSH agent reloaded its key list from the vault
+    AgentKeysReloaded {
+        key_count: usize,
+    },
+}
+
+/// Broadcast channel carrying [`VaultEvent`]s
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<VaultEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Subscribe to events published after this call
+    pub fn subscribe(&self) -> broadcast::Receiver<VaultEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Publish an event to all current subscribers
+    pub fn publish(&self, event: VaultEvent) {
+        // An error only means there are no subscribers right now.
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(EVENT_CHANNEL_CAPACITY)
+    }
+}
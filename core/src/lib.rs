@@ -2,18 +2,34 @@
 //!
 //! This crate provides the core functionality for the Persona digital identity management system,
 //! including cryptographic operations, secure storage, and identity management.
+//!
+//! Optional components sit behind Cargo features, all enabled by default:
+//! `wallet` (crypto wallets), `ssh` (Ed25519 signing keys), `totp` (one-time codes) and
+//! `sync` (remote authentication). Embedded and mobile builds can use
+//! `default-features = false` to leave them out.
 
 pub mod auth;
+pub mod breach;
+pub mod build_info;
 pub mod crypto;
+pub mod custom_types;
+pub mod demo;
+pub mod events;
 pub mod logging;
 pub mod models;
+#[cfg(feature = "totp")]
+pub mod otp;
 pub mod password;
+pub mod secret_scan;
 pub mod service;
 pub mod storage;
 
 // Re-export commonly used types
 pub use auth::*;
+pub use build_info::*;
 pub use crypto::*;
+pub use custom_types::*;
+pub use events::*;
 pub use logging::*;
 
 // Selective re-exports from models to avoid conflicts
@@ -28,10 +44,12 @@ pub use models::workspace::*;
 pub use storage::blob::*;
 pub use storage::database::*;
 pub use storage::filesystem::*;
+pub use storage::name_lookup::*;
 pub use storage::repository::*;
 pub use storage::user_auth::*;
 
 pub use password::*;
+pub use secret_scan::*;
 pub use service::*;
 
 /// Core result type used throughout the library
@@ -46,6 +64,9 @@ pub enum PersonaError {
     #[error("Authentication failed: {0}")]
     AuthenticationFailed(String),
 
+    #[error("Vault is locked: {0}")]
+    Locked(String),
+
     #[error("Cryptographic operation failed: {0}")]
     CryptographicError(String),
 
@@ -78,6 +99,9 @@ pub enum PersonaError {
 
     #[error("Validation error: {0}")]
     Validation(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
 }
 
 // Implement From conversions for common error types
@@ -0,0 +1,223 @@
+//! Size-based log file rotation.
+//!
+//! Lines go to `<dir>/persona.log`. When the next line would push it past the size limit, the
+//! file becomes `persona.log.1`, older files shift up by one (`.1` to `.2`, ...), and files
+//! past the configured count are deleted. The directory and file are created on first write, so
+//! an installed logger costs nothing until something is logged.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::{PersonaError, PersonaResult};
+
+/// Name of the live log file inside the log directory
+pub const LOG_FILE_NAME: &str = "persona.log";
+
+/// Parse a size such as `10MB`, `512 KiB` or `1048576` into bytes.
+///
+/// Units are binary (`1KB` is 1024 bytes), case-insensitive, and the `i` is optional.
+pub fn parse_size(size: &str) -> PersonaResult<u64> {
+    let trimmed = size.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let invalid = || {
+        PersonaError::InvalidInput(format!(
+            "Invalid size '{}'; expected a number with an optional B, KB, MB or GB suffix",
+            size
+        ))
+    };
+
+    let value: u64 = number.parse().map_err(|_| invalid())?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        _ => return Err(invalid()),
+    };
+    match value.checked_mul(multiplier) {
+        Some(0) | None => Err(invalid()),
+        Some(bytes) => Ok(bytes),
+    }
+}
+
+/// A [`MakeWriter`] appending to a rotating log file; clones share the same file
+#[derive(Clone)]
+pub struct RotatingFileWriter {
+    inner: Arc<Mutex<RotatingFile>>,
+}
+
+struct RotatingFile {
+    dir: PathBuf,
+    max_size: u64,
+    max_files: u32,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RotatingFileWriter {
+    /// Write to `dir/persona.log`, rotating at `max_size` bytes and keeping `max_files`
+    /// rotated files besides the live one
+    pub fn new(dir: impl Into<PathBuf>, max_size: u64, max_files: u32) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(RotatingFile {
+                dir: dir.into(),
+                max_size: max_size.max(1),
+                max_files,
+                file: None,
+                size: 0,
+            })),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for RotatingFileWriter {
+    type Writer = RotatingFileWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut file = self
+            .inner
+            .lock()
+            .map_err(|_| io::Error::other("log file lock poisoned"))?;
+        file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut file = self
+            .inner
+            .lock()
+            .map_err(|_| io::Error::other("log file lock poisoned"))?;
+        match file.file.as_mut() {
+            Some(handle) => handle.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.file.is_none() {
+            self.open()?;
+        }
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        let handle = self.file.as_mut().expect("log file opened above");
+        handle.write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn path(&self, index: u32) -> PathBuf {
+        match index {
+            0 => self.dir.join(LOG_FILE_NAME),
+            n => self.dir.join(format!("{}.{}", LOG_FILE_NAME, n)),
+        }
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(0))?;
+        self.size = file.metadata()?.len();
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+
+        // The oldest kept file is about to be shifted out; drop it along with any leftovers
+        // from a larger limit
+        let rotated_prefix = format!("{}.", LOG_FILE_NAME);
+        for entry in fs::read_dir(&self.dir)? {
+            let name = entry?.file_name();
+            let index = name
+                .to_str()
+                .and_then(|name| name.strip_prefix(&rotated_prefix))
+                .and_then(|index| index.parse::<u32>().ok());
+            if index.is_some_and(|index| index >= self.max_files.max(1)) {
+                remove_if_exists(&self.dir.join(name))?;
+            }
+        }
+        for index in (1..self.max_files).rev() {
+            rename_if_exists(&self.path(index), &self.path(index + 1))?;
+        }
+        if self.max_files == 0 {
+            remove_if_exists(&self.path(0))?;
+        } else {
+            rename_if_exists(&self.path(0), &self.path(1))?;
+        }
+
+        self.open()
+    }
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+fn rename_if_exists(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("10MB").unwrap(), 10 * 1024 * 1024);
+        assert_eq!(parse_size(" 512 kib ").unwrap(), 512 * 1024);
+        assert_eq!(parse_size("1g").unwrap(), 1 << 30);
+        assert_eq!(parse_size("4096").unwrap(), 4096);
+        for bad in ["", "MB", "10TB", "-1MB", "0", "1.5MB"] {
+            assert!(parse_size(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn rotates_and_prunes_old_files() {
+        let dir = tempfile::tempdir().unwrap();
+        // Leftover from an earlier, larger max_files setting
+        fs::write(dir.path().join("persona.log.5"), "stale").unwrap();
+
+        let mut writer = RotatingFileWriter::new(dir.path(), 10, 2);
+        for line in ["line-0\n", "line-1\n", "line-2\n", "line-3\n"] {
+            writer.write_all(line.as_bytes()).unwrap();
+        }
+        writer.flush().unwrap();
+
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("persona.log"), "line-3\n");
+        assert_eq!(read("persona.log.1"), "line-2\n");
+        assert_eq!(read("persona.log.2"), "line-1\n");
+        assert!(!dir.path().join("persona.log.3").exists());
+        assert!(!dir.path().join("persona.log.5").exists());
+
+        // Reopening appends to the live file instead of truncating it
+        let mut writer = RotatingFileWriter::new(dir.path(), 100, 2);
+        writer.write_all(b"line-4\n").unwrap();
+        assert_eq!(read("persona.log"), "line-3\nline-4\n");
+    }
+}
@@ -0,0 +1,49 @@
+use crate::models::wallet::BlockchainNetwork;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Labeled recipient address
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AddressBookEntry {
+    /// Entry ID
+    pub id: Uuid,
+
+    /// Unique label used in place of the address
+    pub label: String,
+
+    /// Recipient address
+    pub address: String,
+
+    /// Network the address belongs to
+    pub network: BlockchainNetwork,
+
+    /// Free-form notes
+    pub notes: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AddressBookEntry {
+    pub fn new(label: String, address: String, network: BlockchainNetwork) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            label,
+            address,
+            network,
+            notes: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn with_notes(mut self, notes: Option<String>) -> Self {
+        self.notes = notes;
+        self
+    }
+}
@@ -177,6 +177,29 @@ impl AttachmentChunk {
     }
 }
 
+/// Content-addressed blob shared by every attachment with the same plaintext
+/// 按内容寻址的共享附件数据块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentBlob {
+    /// SHA-256 of the plaintext; also the blob's address
+    pub content_hash: String,
+
+    /// Plaintext size in bytes
+    pub size: u64,
+
+    /// Storage path relative to the blob store root
+    pub storage_path: String,
+
+    /// Blob content key, encrypted under the attachment key (`None` for plaintext blobs)
+    pub wrapped_key: Option<Vec<u8>>,
+
+    /// Number of attachments referencing this blob
+    pub ref_count: u32,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
 /// Attachment statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AttachmentStats {
@@ -64,6 +64,8 @@ pub enum AuditAction {
     PasswordChange,
     MfaEnabled,
     MfaDisabled,
+    BiometricAuth,
+    RemoteApproval,
 
     // 身份管理
     IdentityCreated,
@@ -76,6 +78,8 @@ pub enum AuditAction {
     CredentialCreated,
     CredentialUpdated,
     CredentialDeleted,
+    CredentialTrashed,
+    CredentialRestored,
     CredentialViewed,
     CredentialDecrypted,
     CredentialExported,
@@ -115,6 +119,8 @@ impl std::fmt::Display for AuditAction {
             AuditAction::PasswordChange => "password_change",
             AuditAction::MfaEnabled => "mfa_enabled",
             AuditAction::MfaDisabled => "mfa_disabled",
+            AuditAction::BiometricAuth => "biometric_auth",
+            AuditAction::RemoteApproval => "remote_approval",
             AuditAction::IdentityCreated => "identity_created",
             AuditAction::IdentityUpdated => "identity_updated",
             AuditAction::IdentityDeleted => "identity_deleted",
@@ -123,6 +129,8 @@ impl std::fmt::Display for AuditAction {
             AuditAction::CredentialCreated => "credential_created",
             AuditAction::CredentialUpdated => "credential_updated",
             AuditAction::CredentialDeleted => "credential_deleted",
+            AuditAction::CredentialTrashed => "credential_trashed",
+            AuditAction::CredentialRestored => "credential_restored",
             AuditAction::CredentialViewed => "credential_viewed",
             AuditAction::CredentialDecrypted => "credential_decrypted",
             AuditAction::CredentialExported => "credential_exported",
@@ -157,6 +165,8 @@ impl std::str::FromStr for AuditAction {
             "password_change" => Ok(AuditAction::PasswordChange),
             "mfa_enabled" => Ok(AuditAction::MfaEnabled),
             "mfa_disabled" => Ok(AuditAction::MfaDisabled),
+            "biometric_auth" => Ok(AuditAction::BiometricAuth),
+            "remote_approval" => Ok(AuditAction::RemoteApproval),
             "identity_created" => Ok(AuditAction::IdentityCreated),
             "identity_updated" => Ok(AuditAction::IdentityUpdated),
             "identity_deleted" => Ok(AuditAction::IdentityDeleted),
@@ -165,6 +175,8 @@ impl std::str::FromStr for AuditAction {
             "credential_created" => Ok(AuditAction::CredentialCreated),
             "credential_updated" => Ok(AuditAction::CredentialUpdated),
             "credential_deleted" => Ok(AuditAction::CredentialDeleted),
+            "credential_trashed" => Ok(AuditAction::CredentialTrashed),
+            "credential_restored" => Ok(AuditAction::CredentialRestored),
             "credential_viewed" => Ok(AuditAction::CredentialViewed),
             "credential_decrypted" => Ok(AuditAction::CredentialDecrypted),
             "credential_exported" => Ok(AuditAction::CredentialExported),
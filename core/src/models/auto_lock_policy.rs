@@ -44,6 +44,14 @@ pub struct AutoLockPolicy {
     /// Background check interval in seconds
     pub background_check_interval_secs: u64,
 
+    /// Lock when the system goes to sleep or resumes from it
+    #[serde(default = "default_true")]
+    pub lock_on_sleep: bool,
+
+    /// Lock when the OS screen locks
+    #[serde(default = "default_true")]
+    pub lock_on_screen_lock: bool,
+
     /// Policy metadata
     pub metadata: PolicyMetadata,
 
@@ -60,6 +68,10 @@ pub struct AutoLockPolicy {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+fn default_true() -> bool {
+    true
+}
+
 /// Security levels for auto-lock policies
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(rename_all = "snake_case")]
@@ -176,6 +188,8 @@ impl AutoLockPolicy {
             force_lock_sensitive: security_level != AutoLockSecurityLevel::Low,
             activity_grace_period_secs: 5,
             background_check_interval_secs: 30,
+            lock_on_sleep: true,
+            lock_on_screen_lock: security_level != AutoLockSecurityLevel::Low,
             metadata: PolicyMetadata::default(),
             is_active: true,
             is_default: false,
@@ -202,6 +216,8 @@ impl AutoLockPolicy {
             force_lock_sensitive: config.force_lock_sensitive,
             activity_grace_period_secs: config.activity_grace_period_secs,
             background_check_interval_secs: config.background_check_interval_secs,
+            lock_on_sleep: config.lock_on_sleep,
+            lock_on_screen_lock: config.lock_on_screen_lock,
             metadata: PolicyMetadata::default(),
             is_active: config.is_active,
             is_default: false,
@@ -224,6 +240,8 @@ impl AutoLockPolicy {
         self.force_lock_sensitive = config.force_lock_sensitive;
         self.activity_grace_period_secs = config.activity_grace_period_secs;
         self.background_check_interval_secs = config.background_check_interval_secs;
+        self.lock_on_sleep = config.lock_on_sleep;
+        self.lock_on_screen_lock = config.lock_on_screen_lock;
         self.is_active = config.is_active;
         self.updated_at = chrono::Utc::now();
     }
@@ -364,6 +382,10 @@ pub struct PolicyConfiguration {
     pub force_lock_sensitive: bool,
     pub activity_grace_period_secs: u64,
     pub background_check_interval_secs: u64,
+    #[serde(default = "default_true")]
+    pub lock_on_sleep: bool,
+    #[serde(default = "default_true")]
+    pub lock_on_screen_lock: bool,
     pub is_active: bool,
 }
 
@@ -43,6 +43,10 @@ pub struct Identity {
 
     /// Whether this identity is currently active
     pub is_active: bool,
+
+    /// Row version, bumped on every update (optimistic concurrency)
+    #[serde(default = "crate::models::initial_version")]
+    pub version: i64,
 }
 
 /// Types of digital identities
@@ -102,6 +106,7 @@ impl Identity {
             created_at: now,
             updated_at: now,
             is_active: true,
+            version: crate::models::initial_version(),
         }
     }
 
@@ -1,17 +1,28 @@
+#[cfg(feature = "wallet")]
+pub mod address_book;
 pub mod attachment;
 pub mod audit_log;
 pub mod auto_lock_policy;
 pub mod change_history;
 pub mod credential;
 pub mod identity;
+#[cfg(feature = "wallet")]
 pub mod wallet;
 pub mod workspace;
 
+#[cfg(feature = "wallet")]
+pub use address_book::*;
 pub use attachment::*;
 pub use audit_log::*;
 pub use auto_lock_policy::*;
 pub use change_history::*;
 pub use credential::*;
 pub use identity::*;
+#[cfg(feature = "wallet")]
 pub use wallet::*;
 pub use workspace::*;
+
+/// Version assigned to newly created identities and credentials
+pub fn initial_version() -> i64 {
+    1
+}
@@ -21,6 +21,10 @@ pub struct CryptoWallet {
     /// Blockchain network
     pub network: BlockchainNetwork,
 
+    /// Mainnet, or a test network of `network`
+    #[serde(default)]
+    pub network_kind: NetworkKind,
+
     /// Wallet type
     pub wallet_type: WalletType,
 
@@ -82,6 +86,43 @@ pub enum BlockchainNetwork {
     Custom(String),
 }
 
+impl BlockchainNetwork {
+    /// Whether the network uses Ethereum-style accounts and gas
+    pub fn is_evm(&self) -> bool {
+        matches!(
+            self,
+            BlockchainNetwork::Ethereum
+                | BlockchainNetwork::Polygon
+                | BlockchainNetwork::Arbitrum
+                | BlockchainNetwork::Optimism
+                | BlockchainNetwork::BinanceSmartChain
+        )
+    }
+
+    /// EIP-155 chain id of an EVM network; testnets are the Sepolia deployments and regtest
+    /// is the id local devnets (Hardhat, Anvil) use
+    pub fn chain_id(&self, kind: NetworkKind) -> Option<u64> {
+        if !self.is_evm() {
+            return None;
+        }
+        let id = match (self, kind) {
+            (_, NetworkKind::Regtest) => 31_337,
+            (BlockchainNetwork::Ethereum, NetworkKind::Mainnet) => 1,
+            (BlockchainNetwork::Ethereum, NetworkKind::Testnet) => 11_155_111,
+            (BlockchainNetwork::Polygon, NetworkKind::Mainnet) => 137,
+            (BlockchainNetwork::Polygon, NetworkKind::Testnet) => 80_002,
+            (BlockchainNetwork::Arbitrum, NetworkKind::Mainnet) => 42_161,
+            (BlockchainNetwork::Arbitrum, NetworkKind::Testnet) => 421_614,
+            (BlockchainNetwork::Optimism, NetworkKind::Mainnet) => 10,
+            (BlockchainNetwork::Optimism, NetworkKind::Testnet) => 11_155_420,
+            (BlockchainNetwork::BinanceSmartChain, NetworkKind::Mainnet) => 56,
+            (BlockchainNetwork::BinanceSmartChain, NetworkKind::Testnet) => 97,
+            _ => return None,
+        };
+        Some(id)
+    }
+}
+
 impl std::fmt::Display for BlockchainNetwork {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -100,6 +141,51 @@ impl std::fmt::Display for BlockchainNetwork {
     }
 }
 
+/// Which deployment of a blockchain network a wallet uses
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkKind {
+    /// Production network holding real funds
+    #[default]
+    Mainnet,
+    /// Public test network (Bitcoin testnet/signet, EVM Sepolia-family testnets)
+    Testnet,
+    /// Local regression-test chain (Bitcoin regtest, a local EVM devnet)
+    Regtest,
+}
+
+impl NetworkKind {
+    /// Whether funds on this network are real
+    pub fn is_mainnet(self) -> bool {
+        self == NetworkKind::Mainnet
+    }
+
+    /// Lowercase name, as stored in the database
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NetworkKind::Mainnet => "mainnet",
+            NetworkKind::Testnet => "testnet",
+            NetworkKind::Regtest => "regtest",
+        }
+    }
+
+    /// Parse the stored or user-supplied name of a network kind
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "mainnet" | "main" => Some(NetworkKind::Mainnet),
+            "testnet" | "test" => Some(NetworkKind::Testnet),
+            "regtest" => Some(NetworkKind::Regtest),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for NetworkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Wallet types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum WalletType {
@@ -193,6 +279,16 @@ pub struct WalletAddress {
     pub created_at: DateTime<Utc>,
 }
 
+impl WalletAddress {
+    /// Whether the address is on the BIP44 internal (change) chain, `.../1/index`
+    pub fn is_change(&self) -> bool {
+        self.derivation_path
+            .as_deref()
+            .and_then(|path| path.rsplit('/').nth(1))
+            == Some("1")
+    }
+}
+
 /// Address types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum AddressType {
@@ -371,6 +467,10 @@ pub struct TransactionRequest {
     /// Network
     pub network: BlockchainNetwork,
 
+    /// EIP-155 chain id the transaction is bound to (EVM chains)
+    #[serde(default)]
+    pub chain_id: Option<u64>,
+
     /// From address
     pub from_address: String,
 
@@ -495,6 +595,107 @@ pub enum BroadcastStatus {
     },
 }
 
+/// Lifecycle status of a wallet transaction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletTransactionStatus {
+    /// Request created, not yet signed
+    Created,
+    /// Signed locally
+    Signed,
+    /// Submitted to the network
+    Broadcast,
+    /// Included in a block
+    Confirmed,
+    /// Rejected or abandoned
+    Failed,
+}
+
+impl WalletTransactionStatus {
+    /// Stable identifier stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WalletTransactionStatus::Created => "created",
+            WalletTransactionStatus::Signed => "signed",
+            WalletTransactionStatus::Broadcast => "broadcast",
+            WalletTransactionStatus::Confirmed => "confirmed",
+            WalletTransactionStatus::Failed => "failed",
+        }
+    }
+
+    /// Whether a transaction in this status may move to `next`
+    pub fn can_transition_to(self, next: WalletTransactionStatus) -> bool {
+        use WalletTransactionStatus::*;
+        matches!(
+            (self, next),
+            (Created, Signed)
+                | (Signed, Broadcast)
+                | (Broadcast, Confirmed)
+                | (Created | Signed | Broadcast, Failed)
+        )
+    }
+
+    /// No further transitions are possible
+    pub fn is_final(self) -> bool {
+        matches!(
+            self,
+            WalletTransactionStatus::Confirmed | WalletTransactionStatus::Failed
+        )
+    }
+}
+
+impl std::fmt::Display for WalletTransactionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WalletTransactionStatus::Created => write!(f, "Created"),
+            WalletTransactionStatus::Signed => write!(f, "Signed"),
+            WalletTransactionStatus::Broadcast => write!(f, "Broadcast"),
+            WalletTransactionStatus::Confirmed => write!(f, "Confirmed"),
+            WalletTransactionStatus::Failed => write!(f, "Failed"),
+        }
+    }
+}
+
+impl std::str::FromStr for WalletTransactionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "created" => Ok(WalletTransactionStatus::Created),
+            "signed" => Ok(WalletTransactionStatus::Signed),
+            "broadcast" => Ok(WalletTransactionStatus::Broadcast),
+            "confirmed" => Ok(WalletTransactionStatus::Confirmed),
+            "failed" => Ok(WalletTransactionStatus::Failed),
+            other => Err(format!("Unknown transaction status: {}", other)),
+        }
+    }
+}
+
+/// One status transition in a wallet transaction's history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WalletTransactionEvent {
+    /// Event ID
+    pub id: Uuid,
+
+    /// Wallet ID
+    pub wallet_id: Uuid,
+
+    /// Transaction request the event belongs to
+    pub request_id: Uuid,
+
+    /// Status entered by this transition
+    pub status: WalletTransactionStatus,
+
+    /// Transaction hash, once known
+    pub transaction_hash: Option<String>,
+
+    /// Error message or other context
+    pub detail: Option<String>,
+
+    /// When the transition happened
+    pub created_at: DateTime<Utc>,
+}
+
 impl CryptoWallet {
     /// Create a new crypto wallet
     pub fn new(
@@ -511,6 +712,7 @@ impl CryptoWallet {
             name,
             description: None,
             network,
+            network_kind: NetworkKind::Mainnet,
             wallet_type,
             derivation_path: None,
             extended_public_key: None,
@@ -539,6 +741,7 @@ impl CryptoWallet {
             name,
             description: Some("Watch-only wallet".to_string()),
             network,
+            network_kind: NetworkKind::Mainnet,
             wallet_type: WalletType::HierarchicalDeterministic {
                 bip_version: BipVersion::Bip44,
                 address_count: 20,
@@ -573,6 +776,25 @@ impl CryptoWallet {
         self.addresses.iter().filter(|addr| !addr.used).collect()
     }
 
+    /// Address to receive change: an unused internal-chain address, then any unused
+    /// address, then the first address
+    pub fn change_address(&self) -> Option<&WalletAddress> {
+        let unused = self.get_unused_addresses();
+        unused
+            .iter()
+            .find(|addr| addr.is_change())
+            .or_else(|| unused.first())
+            .copied()
+            .or_else(|| self.addresses.first())
+    }
+
+    /// Fresh address on the external chain for receiving funds
+    pub fn receive_address(&self) -> Option<&WalletAddress> {
+        self.addresses
+            .iter()
+            .find(|addr| !addr.used && !addr.is_change())
+    }
+
     /// Update address usage status
     pub fn mark_address_used(&mut self, address: &str) -> bool {
         if let Some(addr) = self.addresses.iter_mut().find(|a| a.address == address) {
@@ -665,8 +887,27 @@ impl CryptoWallet {
         Ok(())
     }
 
-    /// Get recommended derivation path for network
-    pub fn recommended_derivation_path(network: &BlockchainNetwork, account: u32) -> String {
+    /// Get recommended derivation path for network.
+    ///
+    /// Test networks of UTXO chains use coin type 1' (BIP-44 "testnet, all coins"). EVM and
+    /// Solana wallets keep their mainnet coin type, as their wallets do, so one seed yields the
+    /// same account on mainnet and testnets.
+    pub fn recommended_derivation_path(
+        network: &BlockchainNetwork,
+        network_kind: NetworkKind,
+        account: u32,
+    ) -> String {
+        let utxo_testnet = !network_kind.is_mainnet()
+            && matches!(
+                network,
+                BlockchainNetwork::Bitcoin
+                    | BlockchainNetwork::BitcoinCash
+                    | BlockchainNetwork::Litecoin
+                    | BlockchainNetwork::Dogecoin
+            );
+        if utxo_testnet {
+            return format!("m/44'/1'/{}'/0", account);
+        }
         match network {
             BlockchainNetwork::Bitcoin => format!("m/44'/0'/{}'/0", account),
             BlockchainNetwork::BitcoinCash => format!("m/44'/145'/{}'/0", account),
@@ -742,16 +983,45 @@ mod tests {
 
     #[test]
     fn test_recommended_derivation_path() {
-        let btc_path = CryptoWallet::recommended_derivation_path(&BlockchainNetwork::Bitcoin, 0);
+        let mainnet = NetworkKind::Mainnet;
+        let btc_path =
+            CryptoWallet::recommended_derivation_path(&BlockchainNetwork::Bitcoin, mainnet, 0);
         assert_eq!(btc_path, "m/44'/0'/0'/0");
 
-        let eth_path = CryptoWallet::recommended_derivation_path(&BlockchainNetwork::Ethereum, 1);
+        let eth_path =
+            CryptoWallet::recommended_derivation_path(&BlockchainNetwork::Ethereum, mainnet, 1);
         assert_eq!(eth_path, "m/44'/60'/1'/0");
 
-        let sol_path = CryptoWallet::recommended_derivation_path(&BlockchainNetwork::Solana, 0);
+        let sol_path =
+            CryptoWallet::recommended_derivation_path(&BlockchainNetwork::Solana, mainnet, 0);
         assert_eq!(sol_path, "m/44'/501'/0'/0'");
     }
 
+    #[test]
+    fn test_testnet_derivation_and_chain_ids() {
+        for kind in [NetworkKind::Testnet, NetworkKind::Regtest] {
+            let btc_path =
+                CryptoWallet::recommended_derivation_path(&BlockchainNetwork::Bitcoin, kind, 2);
+            assert_eq!(btc_path, "m/44'/1'/2'/0");
+            let eth_path =
+                CryptoWallet::recommended_derivation_path(&BlockchainNetwork::Ethereum, kind, 0);
+            assert_eq!(eth_path, "m/44'/60'/0'/0");
+        }
+
+        let eth = BlockchainNetwork::Ethereum;
+        assert_eq!(eth.chain_id(NetworkKind::Mainnet), Some(1));
+        assert_eq!(eth.chain_id(NetworkKind::Testnet), Some(11_155_111));
+        assert_eq!(eth.chain_id(NetworkKind::Regtest), Some(31_337));
+        assert_eq!(
+            BlockchainNetwork::Bitcoin.chain_id(NetworkKind::Mainnet),
+            None
+        );
+
+        assert_eq!(NetworkKind::parse("TestNet"), Some(NetworkKind::Testnet));
+        assert_eq!(NetworkKind::parse("signet"), None);
+        assert_eq!(NetworkKind::default(), NetworkKind::Mainnet);
+    }
+
     #[test]
     fn test_address_management() {
         let mut wallet = CryptoWallet::new(
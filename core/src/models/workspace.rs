@@ -47,6 +47,10 @@ pub struct WorkspaceSettings {
 
     /// Default identity type for new identities
     pub default_identity_type: String,
+
+    /// Seed of the anti-phishing phrase shown at unlock (hex, not secret)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_seed: Option<String>,
 }
 
 impl Default for WorkspaceSettings {
@@ -58,10 +62,29 @@ impl Default for WorkspaceSettings {
             session_timeout_seconds: 3600,
             require_confirmation: true,
             default_identity_type: "personal".to_string(),
+            verification_seed: None,
         }
     }
 }
 
+impl WorkspaceSettings {
+    /// Anti-phishing phrase for this workspace, if one has been set
+    pub fn verification_phrase(&self) -> Option<String> {
+        self.verification_seed
+            .as_deref()
+            .and_then(crate::auth::verification_phrase_from_seed)
+    }
+
+    /// Replace the verification seed and return the new phrase
+    pub fn reset_verification_phrase(&mut self) -> String {
+        let seed = crate::auth::generate_verification_seed();
+        let phrase =
+            crate::auth::verification_phrase_from_seed(&seed).expect("generated seed is valid hex");
+        self.verification_seed = Some(seed);
+        phrase
+    }
+}
+
 impl Workspace {
     /// Create a new workspace
     pub fn new<P: Into<PathBuf>>(path: P, name: String) -> Self {
@@ -0,0 +1,327 @@
+//! One-time password generation: HOTP (RFC 4226) and TOTP (RFC 6238).
+//!
+//! Front-ends used to carry their own copies of this code; new callers should go through
+//! these helpers (or `PersonaService::next_hotp_code` for counter-based entries, which also
+//! persists the advanced counter).
+
+use data_encoding::{BASE32, BASE32_NOPAD};
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+
+use crate::models::{OtpType, TwoFactorData};
+use crate::{PersonaError, PersonaResult};
+
+/// Smallest and largest code lengths we generate
+const MIN_DIGITS: u8 = 4;
+const MAX_DIGITS: u8 = 10;
+
+/// How far from the local clock calibration looks for a matching code (either direction)
+pub const CALIBRATION_WINDOW_SECONDS: i64 = 12 * 60 * 60;
+
+/// Time steps on either side of the current one that verification accepts by default
+pub const DEFAULT_VERIFY_SKEW_STEPS: u32 = 1;
+
+/// Widest drift verification accepts; every extra step is another code that passes
+pub const MAX_VERIFY_SKEW_STEPS: u32 = 10;
+
+/// A generated code and how long it stays valid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TotpCode {
+    pub code: String,
+    /// Seconds until the current time step ends
+    pub remaining: u32,
+}
+
+/// Decode a base32 shared secret, tolerating whitespace, lowercase and missing padding
+pub fn decode_secret(secret: &str) -> PersonaResult<Vec<u8>> {
+    let normalized: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect::<String>()
+        .trim_matches('=')
+        .to_string();
+    BASE32_NOPAD
+        .decode(normalized.as_bytes())
+        .or_else(|_| BASE32.decode(normalized.as_bytes()))
+        .map_err(|e| PersonaError::InvalidInput(format!("Invalid base32 secret: {}", e)))
+}
+
+/// RFC 4226 dynamic truncation of `HMAC(secret, counter)`; returns the 31-bit value
+pub fn hotp(secret: &[u8], counter: u64, algorithm: &str) -> PersonaResult<u32> {
+    let msg = counter.to_be_bytes();
+    let hash = match algorithm.to_ascii_uppercase().as_str() {
+        "SHA256" => hmac_digest::<Hmac<sha2::Sha256>>(secret, &msg)?,
+        "SHA512" => hmac_digest::<Hmac<sha2::Sha512>>(secret, &msg)?,
+        _ => hmac_digest::<Hmac<sha1::Sha1>>(secret, &msg)?,
+    };
+
+    let offset = (hash.last().copied().unwrap_or(0) & 0x0f) as usize;
+    if offset + 4 > hash.len() {
+        return Err(PersonaError::CryptographicError(
+            "Invalid HMAC output".to_string(),
+        ));
+    }
+    let slice = &hash[offset..offset + 4];
+    Ok(((slice[0] as u32 & 0x7f) << 24)
+        | ((slice[1] as u32) << 16)
+        | ((slice[2] as u32) << 8)
+        | slice[3] as u32)
+}
+
+/// Zero-padded code of `digits` length for a truncated HOTP value
+pub fn format_code(value: u32, digits: u8) -> String {
+    let digits = digits.clamp(MIN_DIGITS, MAX_DIGITS) as u32;
+    let value = value as u64 % 10_u64.pow(digits);
+    format!("{:0width$}", value, width = digits as usize)
+}
+
+/// HOTP code for an entry at an explicit counter value
+pub fn hotp_code(data: &TwoFactorData, counter: u64) -> PersonaResult<String> {
+    let secret = decode_secret(data.secret_key.expose())?;
+    Ok(format_code(
+        hotp(&secret, counter, &data.algorithm)?,
+        data.digits,
+    ))
+}
+
+/// TOTP code for an entry at the given Unix time (before the entry's clock correction)
+pub fn totp_code_at(data: &TwoFactorData, unix_time: i64) -> PersonaResult<TotpCode> {
+    if data.otp_type == OtpType::Hotp {
+        return Err(PersonaError::InvalidInput(
+            "Counter-based (HOTP) entries have no time-based code".to_string(),
+        ));
+    }
+    let period = data.period.max(1) as u64;
+    let timestamp = unix_time.saturating_add(data.time_offset_seconds).max(0) as u64;
+    Ok(TotpCode {
+        code: hotp_code(data, timestamp / period)?,
+        remaining: (period - (timestamp % period)) as u32,
+    })
+}
+
+/// TOTP code for an entry right now
+pub fn totp_code(data: &TwoFactorData) -> PersonaResult<TotpCode> {
+    totp_code_at(data, chrono::Utc::now().timestamp())
+}
+
+/// Find the clock correction under which `current_code` is the entry's code at `unix_time`
+/// and `previous_code` the one from the step before.
+///
+/// One code alone is not enough: the window holds thousands of steps, so a six-digit code
+/// (or a typo of one) matches some wrong step about 0.3% of the time. Two consecutive codes
+/// make a false match practically impossible.
+///
+/// Searches whole time steps within [`CALIBRATION_WINDOW_SECONDS`], nearest first, ignoring
+/// any correction already stored on the entry. Returns `None` if no step matches both codes.
+pub fn find_time_offset(
+    data: &TwoFactorData,
+    previous_code: &str,
+    current_code: &str,
+    unix_time: i64,
+) -> PersonaResult<Option<i64>> {
+    let uncorrected = TwoFactorData {
+        time_offset_seconds: 0,
+        ..data.clone()
+    };
+    let previous = previous_code.trim();
+    let current = current_code.trim();
+    let period = data.period.max(1) as i64;
+    let max_steps = CALIBRATION_WINDOW_SECONDS / period;
+
+    let candidates =
+        std::iter::once(0).chain((1..=max_steps).flat_map(|step| [step * period, -step * period]));
+    for offset in candidates {
+        let time = unix_time + offset;
+        if totp_code_at(&uncorrected, time)?.code == current
+            && totp_code_at(&uncorrected, time - period)?.code == previous
+        {
+            return Ok(Some(offset));
+        }
+    }
+    Ok(None)
+}
+
+/// Check a user-entered code against a TOTP entry at `unix_time`.
+///
+/// The current time step is tried first, then up to `skew_steps` steps on either side, nearest
+/// first, to tolerate clock drift. Returns the matching step (for replay checks), or `None`.
+/// More than [`MAX_VERIFY_SKEW_STEPS`] is rejected as `InvalidInput`.
+pub fn verify_totp_at(
+    data: &TwoFactorData,
+    code: &str,
+    skew_steps: u32,
+    unix_time: i64,
+) -> PersonaResult<Option<u64>> {
+    if data.otp_type == OtpType::Hotp {
+        return Err(PersonaError::InvalidInput(
+            "Counter-based (HOTP) entries cannot be verified by time".to_string(),
+        ));
+    }
+    if skew_steps > MAX_VERIFY_SKEW_STEPS {
+        return Err(PersonaError::InvalidInput(format!(
+            "Clock skew of {} steps is above the maximum of {}",
+            skew_steps, MAX_VERIFY_SKEW_STEPS
+        )));
+    }
+    let entered: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    let period = data.period.max(1) as u64;
+    let current = unix_time.saturating_add(data.time_offset_seconds).max(0) as u64 / period;
+
+    let skew = skew_steps as u64;
+    let candidates = std::iter::once(current).chain((1..=skew).flat_map(|distance| {
+        [current.checked_sub(distance), current.checked_add(distance)]
+            .into_iter()
+            .flatten()
+    }));
+    for step in candidates {
+        if hotp_code(data, step)? == entered {
+            return Ok(Some(step));
+        }
+    }
+    Ok(None)
+}
+
+/// Check a user-entered code against a TOTP entry right now; see [`verify_totp_at`]
+pub fn verify_totp(
+    data: &TwoFactorData,
+    code: &str,
+    skew_steps: u32,
+) -> PersonaResult<Option<u64>> {
+    verify_totp_at(data, code, skew_steps, chrono::Utc::now().timestamp())
+}
+
+fn hmac_digest<M: Mac + KeyInit>(key: &[u8], msg: &[u8]) -> PersonaResult<Vec<u8>> {
+    let mut mac = <M as Mac>::new_from_slice(key)
+        .map_err(|e| PersonaError::CryptographicError(format!("Invalid secret: {}", e)))?;
+    mac.update(msg);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ASCII "12345678901234567890", the RFC 4226 appendix D test key
+    const RFC4226_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    fn hotp_entry() -> TwoFactorData {
+        TwoFactorData {
+            secret_key: RFC4226_SECRET.into(),
+            issuer: "RFC".to_string(),
+            account_name: "4226".to_string(),
+            algorithm: "SHA1".to_string(),
+            digits: 6,
+            period: 30,
+            otp_type: OtpType::Hotp,
+            counter: 0,
+            time_offset_seconds: 0,
+        }
+    }
+
+    #[test]
+    fn matches_rfc4226_vectors() {
+        let expected = [
+            "755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583",
+            "399871", "520489",
+        ];
+        let data = hotp_entry();
+        for (counter, code) in expected.iter().enumerate() {
+            assert_eq!(hotp_code(&data, counter as u64).unwrap(), *code);
+        }
+    }
+
+    #[test]
+    fn matches_rfc6238_sha1_vector() {
+        let data = TwoFactorData {
+            otp_type: OtpType::Totp,
+            digits: 8,
+            ..hotp_entry()
+        };
+        let code = totp_code_at(&data, 59).unwrap();
+        assert_eq!(code.code, "94287082");
+        assert_eq!(code.remaining, 1);
+    }
+
+    #[test]
+    fn calibration_finds_known_clock_offset() {
+        let data = TwoFactorData {
+            otp_type: OtpType::Totp,
+            ..hotp_entry()
+        };
+        // The device displaying the code runs 37 minutes ahead of us
+        let now = 1_700_000_000;
+        let previous = totp_code_at(&data, now + 37 * 60 - 30).unwrap().code;
+        let observed = totp_code_at(&data, now + 37 * 60).unwrap().code;
+
+        let offset = find_time_offset(&data, &previous, &observed, now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(offset, 37 * 60);
+
+        // Codes out of order, or not from consecutive steps, match nothing
+        assert_eq!(
+            find_time_offset(&data, &observed, &previous, now).unwrap(),
+            None
+        );
+        let earlier = totp_code_at(&data, now + 37 * 60 - 60).unwrap().code;
+        assert_eq!(
+            find_time_offset(&data, &earlier, &observed, now).unwrap(),
+            None
+        );
+
+        let corrected = TwoFactorData {
+            time_offset_seconds: offset,
+            ..data
+        };
+        assert_eq!(totp_code_at(&corrected, now).unwrap().code, observed);
+    }
+
+    #[test]
+    fn verification_tolerates_skew_and_reports_step() {
+        let data = TwoFactorData {
+            otp_type: OtpType::Totp,
+            ..hotp_entry()
+        };
+        let now = 1_700_000_000;
+        let step = (now / 30) as u64;
+        let code_at = |offset: i64| totp_code_at(&data, now + offset).unwrap().code;
+
+        assert_eq!(
+            verify_totp_at(&data, &code_at(0), 0, now).unwrap(),
+            Some(step)
+        );
+        let spaced = format!("{} {}", &code_at(0)[..3], &code_at(0)[3..]);
+        assert_eq!(verify_totp_at(&data, &spaced, 0, now).unwrap(), Some(step));
+
+        // One step behind: only accepted with skew
+        let previous = code_at(-30);
+        assert_eq!(verify_totp_at(&data, &previous, 0, now).unwrap(), None);
+        assert_eq!(
+            verify_totp_at(&data, &previous, 1, now).unwrap(),
+            Some(step - 1)
+        );
+        assert_eq!(
+            verify_totp_at(&data, &code_at(30), 1, now).unwrap(),
+            Some(step + 1)
+        );
+        assert_eq!(verify_totp_at(&data, &code_at(90), 1, now).unwrap(), None);
+        assert!(verify_totp_at(&hotp_entry(), "755224", 1, now).is_err());
+
+        let far = code_at(MAX_VERIFY_SKEW_STEPS as i64 * 30);
+        assert_eq!(
+            verify_totp_at(&data, &far, MAX_VERIFY_SKEW_STEPS, now).unwrap(),
+            Some(step + MAX_VERIFY_SKEW_STEPS as u64)
+        );
+        assert!(matches!(
+            verify_totp_at(&data, &far, MAX_VERIFY_SKEW_STEPS + 1, now),
+            Err(PersonaError::InvalidInput(_))
+        ));
+        assert!(verify_totp_at(&data, &far, 4_000_000_000, now).is_err());
+    }
+
+    #[test]
+    fn hotp_entries_have_no_time_based_code() {
+        assert!(totp_code_at(&hotp_entry(), 59).is_err());
+    }
+}
@@ -1,5 +1,9 @@
 use crate::{PersonaError, Result};
 use rand::{rngs::OsRng, seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+pub use persona_password::deterministic::*;
+pub use persona_password::strength::*;
 
 const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
 const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
@@ -41,6 +45,53 @@ impl Default for PasswordGeneratorOptions {
     }
 }
 
+impl PasswordGeneratorOptions {
+    /// Estimated entropy, in bits, of a password generated with these options.
+    ///
+    /// Random passwords draw every character from the combined pool. Pronounceable passwords
+    /// alternate consonant and vowel pools; the digits and symbols injected afterwards only
+    /// replace letters and are not counted, which keeps the estimate conservative.
+    pub fn entropy_bits(&self) -> f64 {
+        if self.pronounceable {
+            let consonants = self.letter_pool_size(LOWER_CONSONANTS, UPPER_CONSONANTS);
+            let vowels = self.letter_pool_size(LOWER_VOWELS, UPPER_VOWELS);
+            let consonant_positions = self.length.div_ceil(2);
+            entropy_bits(consonants, consonant_positions)
+                + entropy_bits(vowels, self.length - consonant_positions)
+        } else {
+            let pool = [
+                (self.include_lowercase, LOWERCASE),
+                (self.include_uppercase, UPPERCASE),
+                (self.include_numbers, DIGITS),
+                (self.include_symbols, SYMBOLS),
+            ]
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, set)| set.len())
+            .sum();
+            entropy_bits(pool, self.length)
+        }
+    }
+
+    fn letter_pool_size(&self, lower: &str, upper: &str) -> usize {
+        let mut size = 0;
+        if self.include_lowercase {
+            size += lower.len();
+        }
+        if self.include_uppercase {
+            size += upper.len();
+        }
+        size
+    }
+}
+
+/// A generated password and its estimated entropy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneratedPassword {
+    pub password: String,
+    pub entropy_bits: f64,
+}
+
 /// Password generation helper shared by CLI/Desktop/Server.
 pub struct PasswordGenerator;
 
@@ -56,6 +107,14 @@ impl PasswordGenerator {
         }
     }
 
+    /// Generate a password and report its estimated entropy.
+    pub fn generate_with_entropy(options: &PasswordGeneratorOptions) -> Result<GeneratedPassword> {
+        Ok(GeneratedPassword {
+            password: Self::generate(options)?,
+            entropy_bits: options.entropy_bits(),
+        })
+    }
+
     fn validate_options(options: &PasswordGeneratorOptions) -> Result<()> {
         if options.length < 4 {
             return Err(PersonaError::InvalidInput(
@@ -260,4 +319,41 @@ mod tests {
             .to_string()
             .contains("At least one character set must be enabled"));
     }
+
+    #[test]
+    fn entropy_scales_with_length_and_charset() {
+        let lowercase = PasswordGeneratorOptions {
+            length: 10,
+            include_lowercase: true,
+            include_uppercase: false,
+            include_numbers: false,
+            include_symbols: false,
+            pronounceable: false,
+        };
+        let bits = lowercase.entropy_bits();
+        assert!((bits - 10.0 * 26f64.log2()).abs() < 1e-9);
+
+        let longer = PasswordGeneratorOptions {
+            length: 20,
+            ..lowercase.clone()
+        };
+        assert!((longer.entropy_bits() - 2.0 * bits).abs() < 1e-9);
+
+        let all_sets = PasswordGeneratorOptions {
+            length: 10,
+            ..PasswordGeneratorOptions::default()
+        };
+        assert!(all_sets.entropy_bits() > bits);
+
+        // Alternating consonants and vowels carries less entropy than free choice
+        let pronounceable = PasswordGeneratorOptions {
+            pronounceable: true,
+            ..lowercase.clone()
+        };
+        assert!(pronounceable.entropy_bits() < bits);
+
+        let generated = PasswordGenerator::generate_with_entropy(&all_sets).unwrap();
+        assert_eq!(generated.password.len(), 10);
+        assert_eq!(generated.entropy_bits, all_sets.entropy_bits());
+    }
 }
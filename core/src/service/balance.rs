@@ -0,0 +1,535 @@
+//! Live address balances from chain endpoints.
+//!
+//! [`BalanceProvider`] looks up the balance of a single address, in the network's smallest unit
+//! (satoshis, wei, lamports) so it can be stored in `WalletAddress::balance` and used by
+//! sweeps as-is. [`CachedBalanceProvider`] wraps any provider with a short TTL so repeated
+//! listings don't hammer public endpoints.
+//!
+//! With the `balances` feature, [`RpcBalanceProvider`] queries JSON-RPC nodes, Etherscan-style
+//! explorer APIs, and Esplora REST APIs. Without it nothing here touches the network.
+//! The same provider lists Bitcoin unspent outputs from the Esplora endpoint, so coin
+//! selection can run without the caller typing in every UTXO.
+
+#[cfg(feature = "wallet")]
+use crate::crypto::coin_selection::Utxo;
+#[cfg(feature = "balances")]
+use crate::crypto::coin_selection::UtxoProvider;
+use crate::models::wallet::BlockchainNetwork;
+use crate::{PersonaError, PersonaResult};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a fetched balance is reused before asking the endpoint again
+pub const DEFAULT_BALANCE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Source of address balances
+#[async_trait]
+pub trait BalanceProvider: Send + Sync {
+    /// Balance of `address` in the network's smallest unit, as a decimal string
+    async fn get_balance(
+        &self,
+        network: &BlockchainNetwork,
+        address: &str,
+    ) -> PersonaResult<String>;
+}
+
+/// Where and how to ask for a network's balances
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BalanceEndpoint {
+    /// Ethereum JSON-RPC node (`eth_getBalance`)
+    EvmJsonRpc { url: String },
+    /// Etherscan-compatible explorer API (`module=account&action=balance`)
+    Etherscan {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        api_key: Option<String>,
+    },
+    /// Esplora REST API (Blockstream, mempool.space); confirmed balance only
+    Esplora { url: String },
+    /// Solana JSON-RPC node (`getBalance`)
+    SolanaJsonRpc { url: String },
+}
+
+impl BalanceEndpoint {
+    /// Public mainnet endpoint for `network`, if there is a well-known one
+    pub fn default_for(network: &BlockchainNetwork) -> Option<Self> {
+        let evm = |url: &str| {
+            Some(BalanceEndpoint::EvmJsonRpc {
+                url: url.to_string(),
+            })
+        };
+        match network {
+            BlockchainNetwork::Bitcoin => Some(BalanceEndpoint::Esplora {
+                url: "https://blockstream.info/api".to_string(),
+            }),
+            BlockchainNetwork::Ethereum => evm("https://cloudflare-eth.com"),
+            BlockchainNetwork::Polygon => evm("https://polygon-rpc.com"),
+            BlockchainNetwork::Arbitrum => evm("https://arb1.arbitrum.io/rpc"),
+            BlockchainNetwork::Optimism => evm("https://mainnet.optimism.io"),
+            BlockchainNetwork::BinanceSmartChain => evm("https://bsc-dataseed.binance.org"),
+            BlockchainNetwork::Solana => Some(BalanceEndpoint::SolanaJsonRpc {
+                url: "https://api.mainnet-beta.solana.com".to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Extract the balance from the endpoint's JSON response
+    pub fn parse_response(&self, response: &Value) -> PersonaResult<String> {
+        let malformed = |what: &str| {
+            PersonaError::InvalidInput(format!("Unexpected balance response: {}", what))
+        };
+        if let Some(error) = response.get("error").filter(|error| !error.is_null()) {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string());
+            return Err(PersonaError::InvalidInput(format!(
+                "Balance endpoint returned an error: {}",
+                message
+            )));
+        }
+
+        match self {
+            BalanceEndpoint::EvmJsonRpc { .. } => {
+                let quantity = response
+                    .get("result")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| malformed("missing result"))?;
+                let digits = quantity
+                    .strip_prefix("0x")
+                    .ok_or_else(|| malformed("result is not a hex quantity"))?;
+                if digits.is_empty() {
+                    return Ok("0".to_string());
+                }
+                u128::from_str_radix(digits, 16)
+                    .map(|wei| wei.to_string())
+                    .map_err(|_| malformed("result is not a hex quantity"))
+            }
+            BalanceEndpoint::Etherscan { .. } => {
+                let result = response
+                    .get("result")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| malformed("missing result"))?;
+                if response.get("status").and_then(Value::as_str) != Some("1") {
+                    return Err(PersonaError::InvalidInput(format!(
+                        "Balance endpoint returned an error: {}",
+                        result
+                    )));
+                }
+                result
+                    .parse::<u128>()
+                    .map(|wei| wei.to_string())
+                    .map_err(|_| malformed("result is not a number"))
+            }
+            BalanceEndpoint::Esplora { .. } => {
+                let stats = response
+                    .get("chain_stats")
+                    .ok_or_else(|| malformed("missing chain_stats"))?;
+                let sum = |field: &str| {
+                    stats
+                        .get(field)
+                        .and_then(Value::as_u64)
+                        .ok_or_else(|| malformed(field))
+                };
+                let funded = sum("funded_txo_sum")?;
+                let spent = sum("spent_txo_sum")?;
+                Ok(funded.saturating_sub(spent).to_string())
+            }
+            BalanceEndpoint::SolanaJsonRpc { .. } => response
+                .pointer("/result/value")
+                .and_then(Value::as_u64)
+                .map(|lamports| lamports.to_string())
+                .ok_or_else(|| malformed("missing result.value")),
+        }
+    }
+
+    /// Extract the confirmed unspent outputs of `address` from an Esplora `/utxo` response
+    #[cfg(feature = "wallet")]
+    pub fn parse_utxos(&self, address: &str, response: &Value) -> PersonaResult<Vec<Utxo>> {
+        if !matches!(self, BalanceEndpoint::Esplora { .. }) {
+            return Err(PersonaError::ConfigurationError(
+                "Only Esplora endpoints can list unspent outputs".to_string(),
+            ));
+        }
+        let malformed =
+            |what: &str| PersonaError::InvalidInput(format!("Unexpected UTXO response: {}", what));
+        let outputs = response
+            .as_array()
+            .ok_or_else(|| malformed("expected an array"))?;
+
+        let mut utxos = Vec::new();
+        for output in outputs {
+            let confirmed = output
+                .pointer("/status/confirmed")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            if !confirmed {
+                continue;
+            }
+            let txid = output
+                .get("txid")
+                .and_then(Value::as_str)
+                .filter(|txid| txid.len() == 64 && txid.chars().all(|c| c.is_ascii_hexdigit()))
+                .ok_or_else(|| malformed("missing txid"))?;
+            let vout = output
+                .get("vout")
+                .and_then(Value::as_u64)
+                .and_then(|vout| u32::try_from(vout).ok())
+                .ok_or_else(|| malformed("missing vout"))?;
+            let value = output
+                .get("value")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| malformed("missing value"))?;
+            utxos.push(Utxo {
+                txid: txid.to_ascii_lowercase(),
+                vout,
+                value,
+                address: Some(address.to_string()),
+            });
+        }
+        Ok(utxos)
+    }
+}
+
+/// Reuses balances from an inner provider for `ttl`
+pub struct CachedBalanceProvider<P> {
+    inner: P,
+    ttl: Duration,
+    entries: Mutex<HashMap<(BlockchainNetwork, String), (String, Instant)>>,
+}
+
+impl<P: BalanceProvider> CachedBalanceProvider<P> {
+    pub fn new(inner: P, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn cached(&self, key: &(BlockchainNetwork, String)) -> Option<String> {
+        let entries = self.entries.lock().ok()?;
+        entries
+            .get(key)
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < self.ttl)
+            .map(|(balance, _)| balance.clone())
+    }
+}
+
+#[async_trait]
+impl<P: BalanceProvider> BalanceProvider for CachedBalanceProvider<P> {
+    async fn get_balance(
+        &self,
+        network: &BlockchainNetwork,
+        address: &str,
+    ) -> PersonaResult<String> {
+        let key = (network.clone(), address.to_string());
+        if let Some(balance) = self.cached(&key) {
+            return Ok(balance);
+        }
+
+        let balance = self.inner.get_balance(network, address).await?;
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, (balance.clone(), Instant::now()));
+        }
+        Ok(balance)
+    }
+}
+
+/// Balances from the configured per-network endpoints.
+///
+/// Requests honour the standard `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` environment variables.
+/// Network failures surface as [`PersonaError::Io`].
+#[cfg(feature = "balances")]
+#[derive(Debug, Clone)]
+pub struct RpcBalanceProvider {
+    http: reqwest::Client,
+    endpoints: HashMap<BlockchainNetwork, BalanceEndpoint>,
+}
+
+#[cfg(feature = "balances")]
+impl RpcBalanceProvider {
+    /// Create a provider with the public mainnet endpoints whose requests give up after `timeout`.
+    pub fn new(timeout: Duration) -> PersonaResult<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(timeout)
+            .user_agent(concat!("persona/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| {
+                PersonaError::ConfigurationError(format!("Failed to create HTTP client: {}", e))
+            })?;
+        let endpoints = [
+            BlockchainNetwork::Bitcoin,
+            BlockchainNetwork::Ethereum,
+            BlockchainNetwork::Polygon,
+            BlockchainNetwork::Arbitrum,
+            BlockchainNetwork::Optimism,
+            BlockchainNetwork::BinanceSmartChain,
+            BlockchainNetwork::Solana,
+        ]
+        .into_iter()
+        .filter_map(|network| BalanceEndpoint::default_for(&network).map(|e| (network, e)))
+        .collect();
+        Ok(Self { http, endpoints })
+    }
+
+    /// Use `endpoint` for `network` instead of the default
+    pub fn with_endpoint(mut self, network: BlockchainNetwork, endpoint: BalanceEndpoint) -> Self {
+        self.endpoints.insert(network, endpoint);
+        self
+    }
+
+    async fn fetch(&self, endpoint: &BalanceEndpoint, address: &str) -> PersonaResult<Value> {
+        let json_rpc = |url: &str, method: &str, params: Value| {
+            self.http
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": 1,
+                        "method": method,
+                        "params": params,
+                    })
+                    .to_string(),
+                )
+        };
+        let request = match endpoint {
+            BalanceEndpoint::EvmJsonRpc { url } => json_rpc(
+                url,
+                "eth_getBalance",
+                serde_json::json!([address, "latest"]),
+            ),
+            BalanceEndpoint::SolanaJsonRpc { url } => {
+                json_rpc(url, "getBalance", serde_json::json!([address]))
+            }
+            BalanceEndpoint::Etherscan { url, api_key } => {
+                let mut query = vec![
+                    ("module", "account"),
+                    ("action", "balance"),
+                    ("address", address),
+                    ("tag", "latest"),
+                ];
+                if let Some(api_key) = api_key {
+                    query.push(("apikey", api_key));
+                }
+                self.http.get(url).query(&query)
+            }
+            BalanceEndpoint::Esplora { url } => {
+                self.http
+                    .get(format!("{}/address/{}", url.trim_end_matches('/'), address))
+            }
+        };
+
+        self.send(request).await
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> PersonaResult<Value> {
+        let unreachable =
+            |e: reqwest::Error| PersonaError::Io(format!("Balance endpoint unreachable: {}", e));
+        let body = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(unreachable)?
+            .text()
+            .await
+            .map_err(unreachable)?;
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+#[cfg(feature = "balances")]
+#[async_trait]
+impl BalanceProvider for RpcBalanceProvider {
+    async fn get_balance(
+        &self,
+        network: &BlockchainNetwork,
+        address: &str,
+    ) -> PersonaResult<String> {
+        let endpoint = self.endpoints.get(network).ok_or_else(|| {
+            PersonaError::ConfigurationError(format!(
+                "No balance endpoint configured for {}",
+                network
+            ))
+        })?;
+        let response = self.fetch(endpoint, address).await?;
+        endpoint.parse_response(&response)
+    }
+}
+
+/// Lists confirmed outputs from the Bitcoin endpoint, which must be an Esplora API
+#[cfg(feature = "balances")]
+#[async_trait]
+impl UtxoProvider for RpcBalanceProvider {
+    async fn list_unspent(&self, addresses: &[String]) -> PersonaResult<Vec<Utxo>> {
+        let endpoint = self
+            .endpoints
+            .get(&BlockchainNetwork::Bitcoin)
+            .ok_or_else(|| {
+                PersonaError::ConfigurationError(
+                    "No balance endpoint configured for bitcoin".to_string(),
+                )
+            })?;
+        let BalanceEndpoint::Esplora { url } = endpoint else {
+            return Err(PersonaError::ConfigurationError(
+                "Only Esplora endpoints can list unspent outputs".to_string(),
+            ));
+        };
+
+        let mut utxos = Vec::new();
+        for address in addresses {
+            let request = self.http.get(format!(
+                "{}/address/{}/utxo",
+                url.trim_end_matches('/'),
+                address
+            ));
+            let response = self.send(request).await?;
+            utxos.extend(endpoint.parse_utxos(address, &response)?);
+        }
+        Ok(utxos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl BalanceProvider for CountingProvider {
+        async fn get_balance(
+            &self,
+            _network: &BlockchainNetwork,
+            address: &str,
+        ) -> PersonaResult<String> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("{}-{}", address, call))
+        }
+    }
+
+    #[test]
+    fn test_parse_balance_responses() {
+        let url = String::new();
+        let evm = BalanceEndpoint::EvmJsonRpc { url: url.clone() };
+        assert_eq!(
+            evm.parse_response(&json!({"jsonrpc": "2.0", "id": 1, "result": "0xde0b6b3a7640000"}))
+                .unwrap(),
+            "1000000000000000000"
+        );
+        assert!(evm
+            .parse_response(&json!({"error": {"code": -32602, "message": "invalid address"}}))
+            .unwrap_err()
+            .to_string()
+            .contains("invalid address"));
+
+        let etherscan = BalanceEndpoint::Etherscan {
+            url: url.clone(),
+            api_key: None,
+        };
+        assert_eq!(
+            etherscan
+                .parse_response(&json!({"status": "1", "message": "OK", "result": "42"}))
+                .unwrap(),
+            "42"
+        );
+        assert!(etherscan
+            .parse_response(
+                &json!({"status": "0", "message": "NOTOK", "result": "Invalid API Key"})
+            )
+            .is_err());
+
+        let esplora = BalanceEndpoint::Esplora { url: url.clone() };
+        assert_eq!(
+            esplora
+                .parse_response(&json!({
+                    "chain_stats": {"funded_txo_sum": 150_000, "spent_txo_sum": 50_000},
+                    "mempool_stats": {"funded_txo_sum": 7, "spent_txo_sum": 0}
+                }))
+                .unwrap(),
+            "100000"
+        );
+
+        let solana = BalanceEndpoint::SolanaJsonRpc { url };
+        assert_eq!(
+            solana
+                .parse_response(&json!({"result": {"context": {"slot": 1}, "value": 2_039_280}}))
+                .unwrap(),
+            "2039280"
+        );
+    }
+
+    #[cfg(feature = "wallet")]
+    #[test]
+    fn test_parse_esplora_utxos() {
+        let esplora = BalanceEndpoint::Esplora { url: String::new() };
+        let txid = "AB".repeat(32);
+        let utxos = esplora
+            .parse_utxos(
+                "bc1qexample",
+                &json!([
+                    {"txid": txid, "vout": 1, "value": 50_000, "status": {"confirmed": true}},
+                    {"txid": "cd".repeat(32), "vout": 0, "value": 7, "status": {"confirmed": false}}
+                ]),
+            )
+            .unwrap();
+        assert_eq!(
+            utxos,
+            vec![Utxo {
+                txid: "ab".repeat(32),
+                vout: 1,
+                value: 50_000,
+                address: Some("bc1qexample".to_string()),
+            }]
+        );
+
+        assert!(esplora
+            .parse_utxos("bc1qexample", &json!({"chain_stats": {}}))
+            .is_err());
+        assert!(esplora
+            .parse_utxos(
+                "bc1qexample",
+                &json!([{"txid": "zz", "vout": 0, "value": 1, "status": {"confirmed": true}}])
+            )
+            .is_err());
+        assert!(BalanceEndpoint::EvmJsonRpc { url: String::new() }
+            .parse_utxos("0xabc", &json!([]))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cached_provider_reuses_fresh_balances() {
+        let counting = CountingProvider {
+            calls: AtomicUsize::new(0),
+        };
+        let cached = CachedBalanceProvider::new(counting, Duration::from_secs(60));
+        let network = BlockchainNetwork::Ethereum;
+
+        let first = cached.get_balance(&network, "0xabc").await.unwrap();
+        assert_eq!(cached.get_balance(&network, "0xabc").await.unwrap(), first);
+        assert_ne!(cached.get_balance(&network, "0xdef").await.unwrap(), first);
+        assert_eq!(cached.inner.calls.load(Ordering::SeqCst), 2);
+
+        let expiring = CachedBalanceProvider::new(
+            CountingProvider {
+                calls: AtomicUsize::new(0),
+            },
+            Duration::ZERO,
+        );
+        expiring.get_balance(&network, "0xabc").await.unwrap();
+        expiring.get_balance(&network, "0xabc").await.unwrap();
+        assert_eq!(expiring.inner.calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,585 @@
+//! Importing one-time password accounts from authenticator apps.
+//!
+//! Two formats are understood:
+//!
+//! * `otpauth://totp/...` and `otpauth://hotp/...` key URIs, as shown in setup QR codes
+//! * `otpauth-migration://offline?data=...` exports from Google Authenticator, whose `data`
+//!   is a base64 protobuf `MigrationPayload` that may carry several accounts
+//!
+//! Both decode to [`TwoFactorData`]; [`PersonaService::import_otp_uri`] stores one
+//! `CredentialType::TwoFactor` credential per account.
+
+use data_encoding::{BASE32_NOPAD, BASE64_NOPAD};
+use uuid::Uuid;
+
+use super::PersonaService;
+use crate::{
+    crypto::SecretString,
+    models::{Credential, CredentialData, CredentialType, OtpType, SecurityLevel, TwoFactorData},
+    PersonaError, Result,
+};
+
+/// Scheme of single-account key URIs
+pub const OTPAUTH_SCHEME: &str = "otpauth";
+
+/// Scheme of Google Authenticator export URIs
+pub const MIGRATION_SCHEME: &str = "otpauth-migration";
+
+/// Parse an `otpauth://` key URI.
+///
+/// The label is percent-decoded and split into `Issuer:account`; an `issuer` parameter wins
+/// over the label prefix. Missing parameters get the usual defaults (SHA1, 6 digits, 30 s).
+pub fn parse_otpauth_uri(uri: &str) -> Result<TwoFactorData> {
+    let (scheme, rest) = split_scheme(uri)?;
+    if scheme != OTPAUTH_SCHEME {
+        return Err(invalid(format!("Expected an {}:// URI", OTPAUTH_SCHEME)));
+    }
+    let (kind, rest) = rest.split_once('/').unwrap_or((rest, ""));
+    let otp_type = match kind.to_ascii_lowercase().as_str() {
+        "totp" => OtpType::Totp,
+        "hotp" => OtpType::Hotp,
+        other => return Err(invalid(format!("Unsupported OTP type '{}'", other))),
+    };
+    let (label, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let label = percent_decode(label)?;
+    let (label_issuer, label_account) = match label.split_once(':') {
+        Some((issuer, account)) => (issuer.trim(), account.trim()),
+        None => ("", label.trim()),
+    };
+
+    let mut data = TwoFactorData {
+        secret_key: SecretString::new(String::new()),
+        issuer: label_issuer.to_string(),
+        account_name: label_account.to_string(),
+        algorithm: "SHA1".to_string(),
+        digits: 6,
+        period: 30,
+        otp_type,
+        counter: 0,
+        time_offset_seconds: 0,
+    };
+    let mut secret = None;
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value)?;
+        match key.to_ascii_lowercase().as_str() {
+            "secret" => secret = Some(value),
+            "issuer" if !value.trim().is_empty() => data.issuer = value.trim().to_string(),
+            "account" if !value.trim().is_empty() => data.account_name = value.trim().to_string(),
+            "algorithm" => data.algorithm = normalize_algorithm(&value)?,
+            "digits" => data.digits = parse_digits(&value)?,
+            "period" => {
+                data.period = value
+                    .parse()
+                    .ok()
+                    .filter(|period| *period > 0)
+                    .ok_or_else(|| invalid(format!("Invalid period '{}'", value)))?
+            }
+            "counter" => {
+                data.counter = value
+                    .parse()
+                    .map_err(|_| invalid(format!("Invalid counter '{}'", value)))?
+            }
+            _ => {}
+        }
+    }
+
+    let secret = secret
+        .filter(|secret| !secret.trim().is_empty())
+        .ok_or_else(|| invalid("The URI has no secret".to_string()))?;
+    // Only the alphabet is checked here; some issuers hand out secrets whose length is not a
+    // whole number of base32 blocks, and those are accepted as they were before
+    if !secret.chars().all(is_base32_char) {
+        return Err(invalid("The secret is not base32".to_string()));
+    }
+    data.secret_key = SecretString::new(secret);
+    Ok(data)
+}
+
+fn is_base32_char(c: char) -> bool {
+    matches!(c.to_ascii_uppercase(), 'A'..='Z' | '2'..='7' | '=') || c.is_whitespace()
+}
+
+/// Decode every account in a Google Authenticator `otpauth-migration://offline?data=...` URI.
+///
+/// Exports spread over several QR codes are separate URIs; decode each one.
+pub fn decode_migration_uri(uri: &str) -> Result<Vec<TwoFactorData>> {
+    let (scheme, rest) = split_scheme(uri)?;
+    if scheme != MIGRATION_SCHEME {
+        return Err(invalid(format!("Expected an {}:// URI", MIGRATION_SCHEME)));
+    }
+    let query = rest.split_once('?').map_or("", |(_, query)| query);
+    let data = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("data="))
+        .ok_or_else(|| invalid("The migration URI has no data parameter".to_string()))?;
+    // Exports percent-encode the base64; some tools use the URL-safe alphabet
+    let base64: String = percent_decode(data)?
+        .trim()
+        .trim_end_matches('=')
+        .chars()
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+    let payload = BASE64_NOPAD
+        .decode(base64.as_bytes())
+        .map_err(|e| invalid(format!("The migration data is not valid base64: {}", e)))?;
+
+    let mut accounts = Vec::new();
+    let mut reader = ProtoReader::new(&payload);
+    while let Some((field, value)) = reader.next_field()? {
+        if let (1, ProtoValue::Bytes(parameters)) = (field, value) {
+            accounts.push(decode_otp_parameters(parameters)?);
+        }
+    }
+    if accounts.is_empty() {
+        return Err(invalid(
+            "The migration data contains no accounts".to_string(),
+        ));
+    }
+    Ok(accounts)
+}
+
+/// Decode either URI kind into the accounts it holds
+pub fn decode_otp_uri(uri: &str) -> Result<Vec<TwoFactorData>> {
+    match split_scheme(uri)?.0.as_str() {
+        MIGRATION_SCHEME => decode_migration_uri(uri),
+        _ => Ok(vec![parse_otpauth_uri(uri)?]),
+    }
+}
+
+/// Outcome of [`PersonaService::import_otp_uri`]
+#[derive(Debug, Default)]
+pub struct OtpImportResult {
+    pub imported: Vec<Credential>,
+    /// Names of accounts skipped because the identity already has them
+    pub skipped: Vec<String>,
+}
+
+impl PersonaService {
+    /// Store every account in an `otpauth://` or `otpauth-migration://` URI as a
+    /// `CredentialType::TwoFactor` credential of `identity_id`.
+    ///
+    /// Accounts whose name and account match an existing two-factor credential of the identity
+    /// are skipped, so importing the same export twice adds nothing.
+    pub async fn import_otp_uri(&self, identity_id: Uuid, uri: &str) -> Result<OtpImportResult> {
+        let accounts = decode_otp_uri(uri)?;
+        let existing: Vec<Credential> = self
+            .get_credentials_for_identity(&identity_id)
+            .await?
+            .into_iter()
+            .filter(|c| c.credential_type == CredentialType::TwoFactor)
+            .collect();
+
+        let mut result = OtpImportResult::default();
+        for data in accounts {
+            let name = otp_credential_name(&data);
+            let duplicate = existing.iter().chain(&result.imported).any(|c| {
+                c.name == name && c.username.as_deref() == Some(data.account_name.as_str())
+            });
+            if duplicate {
+                result.skipped.push(name);
+                continue;
+            }
+
+            let mut credential = Credential::new(
+                identity_id,
+                name,
+                CredentialType::TwoFactor,
+                SecurityLevel::High,
+                Vec::new(),
+                None,
+            );
+            credential.username = Some(data.account_name.clone());
+            credential
+                .metadata
+                .insert("issuer".into(), data.issuer.clone());
+            credential
+                .metadata
+                .insert("algorithm".into(), data.algorithm.clone());
+            credential
+                .metadata
+                .insert("digits".into(), data.digits.to_string());
+            let created = self
+                .create_credential_full(credential, &CredentialData::TwoFactor(data))
+                .await?;
+            result.imported.push(created);
+        }
+        Ok(result)
+    }
+}
+
+/// "Issuer (account)", or just the account when there is no issuer
+pub fn otp_credential_name(data: &TwoFactorData) -> String {
+    match (data.issuer.is_empty(), data.account_name.is_empty()) {
+        (true, _) => data.account_name.clone(),
+        (false, true) => data.issuer.clone(),
+        (false, false) => format!("{} ({})", data.issuer, data.account_name),
+    }
+}
+
+fn decode_otp_parameters(bytes: &[u8]) -> Result<TwoFactorData> {
+    let mut secret = Vec::new();
+    let mut name = String::new();
+    let mut issuer = String::new();
+    let mut algorithm = "SHA1";
+    let mut digits = 6;
+    let mut otp_type = OtpType::Totp;
+    let mut counter = 0;
+
+    let mut reader = ProtoReader::new(bytes);
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (1, ProtoValue::Bytes(value)) => secret = value.to_vec(),
+            (2, ProtoValue::Bytes(value)) => name = utf8(value)?,
+            (3, ProtoValue::Bytes(value)) => issuer = utf8(value)?,
+            (4, ProtoValue::Varint(value)) => {
+                algorithm = match value {
+                    0 | 1 => "SHA1",
+                    2 => "SHA256",
+                    3 => "SHA512",
+                    _ => return Err(invalid("Unsupported algorithm (MD5) in export".to_string())),
+                }
+            }
+            (5, ProtoValue::Varint(value)) => digits = if value == 2 { 8 } else { 6 },
+            (6, ProtoValue::Varint(value)) => {
+                otp_type = if value == 1 {
+                    OtpType::Hotp
+                } else {
+                    OtpType::Totp
+                }
+            }
+            (7, ProtoValue::Varint(value)) => counter = value,
+            _ => {}
+        }
+    }
+    if secret.is_empty() {
+        return Err(invalid(format!("Account '{}' has no secret", name)));
+    }
+
+    // Names are usually "Issuer:account"; keep only the account when the issuer is known
+    let account = match name.split_once(':') {
+        Some((prefix, account)) if issuer.is_empty() || prefix.trim() == issuer => {
+            if issuer.is_empty() {
+                issuer = prefix.trim().to_string();
+            }
+            account.trim().to_string()
+        }
+        _ => name.trim().to_string(),
+    };
+    Ok(TwoFactorData {
+        secret_key: SecretString::new(BASE32_NOPAD.encode(&secret)),
+        issuer,
+        account_name: account,
+        algorithm: algorithm.to_string(),
+        digits,
+        period: 30,
+        otp_type,
+        counter,
+        time_offset_seconds: 0,
+    })
+}
+
+fn split_scheme(uri: &str) -> Result<(String, &str)> {
+    let (scheme, rest) = uri
+        .trim()
+        .split_once("://")
+        .ok_or_else(|| invalid("Not an otpauth URI".to_string()))?;
+    Ok((scheme.to_ascii_lowercase(), rest))
+}
+
+fn normalize_algorithm(value: &str) -> Result<String> {
+    let algorithm = value.trim().to_ascii_uppercase().replace('-', "");
+    match algorithm.as_str() {
+        "SHA1" | "SHA256" | "SHA512" => Ok(algorithm),
+        _ => Err(invalid(format!("Unsupported algorithm '{}'", value))),
+    }
+}
+
+fn parse_digits(value: &str) -> Result<u8> {
+    value
+        .parse()
+        .ok()
+        .filter(|digits| (6..=8).contains(digits))
+        .ok_or_else(|| invalid(format!("Invalid digits '{}' (expected 6 to 8)", value)))
+}
+
+/// Decode `%XX` escapes; `+` is kept as is since base64 data uses it
+fn percent_decode(value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = value
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| invalid(format!("Invalid percent-encoding in '{}'", value)))?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    utf8(&decoded)
+}
+
+fn utf8(bytes: &[u8]) -> Result<String> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| invalid("Text is not valid UTF-8".to_string()))
+}
+
+fn invalid(message: String) -> anyhow::Error {
+    PersonaError::InvalidInput(message).into()
+}
+
+/// Protobuf field value; only the wire types the migration payload uses are kept
+enum ProtoValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Other,
+}
+
+/// Minimal protobuf wire-format reader
+struct ProtoReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ProtoReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn next_field(&mut self) -> Result<Option<(u64, ProtoValue<'a>)>> {
+        if self.bytes.is_empty() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let value = match key & 0x7 {
+            0 => ProtoValue::Varint(self.varint()?),
+            1 => {
+                self.take(8)?;
+                ProtoValue::Other
+            }
+            2 => {
+                let len = usize::try_from(self.varint()?).map_err(|_| truncated())?;
+                ProtoValue::Bytes(self.take(len)?)
+            }
+            5 => {
+                self.take(4)?;
+                ProtoValue::Other
+            }
+            _ => return Err(truncated()),
+        };
+        Ok(Some((key >> 3, value)))
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self.bytes.split_first().ok_or_else(truncated)?;
+            self.bytes = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(truncated())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.bytes.len() {
+            return Err(truncated());
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+}
+
+fn truncated() -> anyhow::Error {
+    invalid("The migration data is corrupt".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Database;
+    use crate::IdentityType;
+
+    fn varint(mut value: u64, out: &mut Vec<u8>) {
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn bytes_field(field: u64, value: &[u8], out: &mut Vec<u8>) {
+        varint(field << 3 | 2, out);
+        varint(value.len() as u64, out);
+        out.extend_from_slice(value);
+    }
+
+    fn varint_field(field: u64, value: u64, out: &mut Vec<u8>) {
+        varint(field << 3, out);
+        varint(value, out);
+    }
+
+    type MigrationAccount<'a> = (&'a [u8], &'a str, &'a str, u64, u64, u64);
+
+    /// `otpauth-migration` URI for `(secret, name, issuer, algorithm, digits, type)` accounts
+    fn migration_uri(accounts: &[MigrationAccount]) -> String {
+        let mut payload = Vec::new();
+        for &(secret, name, issuer, algorithm, digits, otp_type) in accounts {
+            let mut parameters = Vec::new();
+            bytes_field(1, secret, &mut parameters);
+            bytes_field(2, name.as_bytes(), &mut parameters);
+            bytes_field(3, issuer.as_bytes(), &mut parameters);
+            varint_field(4, algorithm, &mut parameters);
+            varint_field(5, digits, &mut parameters);
+            varint_field(6, otp_type, &mut parameters);
+            bytes_field(1, &parameters, &mut payload);
+        }
+        varint_field(2, 1, &mut payload);
+        let data = data_encoding::BASE64.encode(&payload);
+        format!(
+            "otpauth-migration://offline?data={}",
+            data.replace('+', "%2B")
+                .replace('/', "%2F")
+                .replace('=', "%3D")
+        )
+    }
+
+    #[test]
+    fn parses_key_uris() {
+        let data = parse_otpauth_uri(
+            "otpauth://totp/ACME%20Co:john.doe%40email.com?secret=HXDMVJECJJWSRB3HWIZR4IFUGFTMXBOZ&issuer=ACME%20Co&algorithm=SHA256&digits=8&period=60",
+        )
+        .unwrap();
+        assert_eq!(data.issuer, "ACME Co");
+        assert_eq!(data.account_name, "john.doe@email.com");
+        assert_eq!(data.algorithm, "SHA256");
+        assert_eq!((data.digits, data.period), (8, 60));
+        assert_eq!(data.otp_type, OtpType::Totp);
+
+        // The issuer parameter wins over the label prefix
+        let data = parse_otpauth_uri("otpauth://totp/Old:alice?secret=JBSWY3DPEHPK3PXP&issuer=New")
+            .unwrap();
+        assert_eq!(
+            (data.issuer.as_str(), data.account_name.as_str()),
+            ("New", "alice")
+        );
+        assert_eq!((data.algorithm.as_str(), data.digits), ("SHA1", 6));
+
+        let hotp = parse_otpauth_uri(
+            "otpauth://hotp/bob?secret=JBSWY3DPEHPK3PXP&counter=7&algorithm=sha512",
+        )
+        .unwrap();
+        assert_eq!((hotp.otp_type, hotp.counter), (OtpType::Hotp, 7));
+        assert_eq!(
+            (hotp.issuer.as_str(), hotp.algorithm.as_str()),
+            ("", "SHA512")
+        );
+
+        for bad in [
+            "otpauth://totp/alice",
+            "otpauth://totp/alice?secret=not*base32",
+            "otpauth://totp/alice?secret=JBSWY3DPEHPK3PXP&algorithm=MD5",
+            "otpauth://sms/alice?secret=JBSWY3DPEHPK3PXP",
+            "https://example.com",
+        ] {
+            assert!(parse_otpauth_uri(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn decodes_migration_payloads() {
+        let uri = migration_uri(&[
+            (
+                b"Hello!\xde\xad\xbe\xef",
+                "GitHub:octocat",
+                "GitHub",
+                1,
+                1,
+                2,
+            ),
+            (b"0123456789", "ops@example.com", "", 3, 2, 1),
+        ]);
+        let accounts = decode_migration_uri(&uri).unwrap();
+        assert_eq!(accounts.len(), 2);
+
+        let github = &accounts[0];
+        assert_eq!(github.secret_key.expose(), "JBSWY3DPEHPK3PXP");
+        assert_eq!(
+            (github.issuer.as_str(), github.account_name.as_str()),
+            ("GitHub", "octocat")
+        );
+        assert_eq!((github.digits, github.otp_type), (6, OtpType::Totp));
+
+        let ops = &accounts[1];
+        assert_eq!(
+            (ops.issuer.as_str(), ops.account_name.as_str()),
+            ("", "ops@example.com")
+        );
+        assert_eq!((ops.algorithm.as_str(), ops.digits), ("SHA512", 8));
+        assert_eq!(ops.otp_type, OtpType::Hotp);
+
+        assert!(decode_migration_uri("otpauth-migration://offline?data=AAAA%").is_err());
+        assert!(decode_migration_uri("otpauth-migration://offline?data=CgU%3D").is_err());
+        assert!(decode_migration_uri("otpauth-migration://offline").is_err());
+    }
+
+    #[tokio::test]
+    async fn import_creates_one_credential_per_account() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let mut service = PersonaService::new(db).await.unwrap();
+        let salt = service.generate_salt();
+        service
+            .unlock(&SecretString::from("test_password"), &salt)
+            .unwrap();
+        let identity = service
+            .create_identity("Phone".to_string(), IdentityType::Personal)
+            .await
+            .unwrap();
+
+        let uri = migration_uri(&[
+            (
+                b"Hello!\xde\xad\xbe\xef",
+                "GitHub:octocat",
+                "GitHub",
+                1,
+                1,
+                2,
+            ),
+            (b"0123456789", "ops@example.com", "", 1, 1, 2),
+        ]);
+        let result = service.import_otp_uri(identity.id, &uri).await.unwrap();
+        assert_eq!(result.imported.len(), 2);
+        assert_eq!(result.imported[0].name, "GitHub (octocat)");
+        assert_eq!(result.imported[0].username.as_deref(), Some("octocat"));
+        assert!(result
+            .imported
+            .iter()
+            .all(|c| c.credential_type == CredentialType::TwoFactor));
+        match service
+            .get_credential_data(&result.imported[0].id)
+            .await
+            .unwrap()
+        {
+            Some(CredentialData::TwoFactor(data)) => {
+                assert_eq!(data.secret_key.expose(), "JBSWY3DPEHPK3PXP")
+            }
+            other => panic!("unexpected data: {:?}", other.is_some()),
+        }
+
+        // Re-importing the same export adds nothing
+        let again = service.import_otp_uri(identity.id, &uri).await.unwrap();
+        assert!(again.imported.is_empty());
+        assert_eq!(again.skipped, ["GitHub (octocat)", "ops@example.com"]);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::crypto::address_generator::verify_address;
+use crate::models::address_book::AddressBookEntry;
+use crate::models::wallet::BlockchainNetwork;
+use crate::storage::Database;
+use crate::{PersonaError, PersonaResult};
+use chrono::{TimeZone, Utc};
+use sqlx::Row;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Repository for labeled recipient addresses
+pub struct AddressBookRepository {
+    db: Arc<Database>,
+}
+
+impl AddressBookRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Add an entry after validating the address (and its checksum) for the network
+    pub async fn create(&self, entry: &AddressBookEntry) -> PersonaResult<AddressBookEntry> {
+        if entry.label.trim().is_empty() {
+            return Err(PersonaError::InvalidInput(
+                "Label must not be empty".to_string(),
+            ));
+        }
+        verify_address(&entry.network, &entry.address)?;
+        if self.find_by_label(&entry.label).await?.is_some() {
+            return Err(PersonaError::InvalidInput(format!(
+                "Address book already has an entry labeled '{}'",
+                entry.label
+            )));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO address_book (id, label, address, network, notes, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(entry.id.to_string())
+        .bind(&entry.label)
+        .bind(&entry.address)
+        .bind(serde_json::to_string(&entry.network)?)
+        .bind(&entry.notes)
+        .bind(entry.created_at.timestamp())
+        .bind(entry.updated_at.timestamp())
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(entry.clone())
+    }
+
+    /// Find an entry by its label (case-insensitive)
+    pub async fn find_by_label(&self, label: &str) -> PersonaResult<Option<AddressBookEntry>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, label, address, network, notes, created_at, updated_at
+            FROM address_book
+            WHERE label = $1 COLLATE NOCASE
+            "#,
+        )
+        .bind(label)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.map(|row| self.entry_from_row(&row)).transpose()
+    }
+
+    /// Find entries for an address
+    pub async fn find_by_address(&self, address: &str) -> PersonaResult<Vec<AddressBookEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, label, address, network, notes, created_at, updated_at
+            FROM address_book
+            WHERE address = $1
+            ORDER BY label
+            "#,
+        )
+        .bind(address)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(|row| self.entry_from_row(row)).collect()
+    }
+
+    /// List entries, optionally restricted to one network
+    pub async fn list(
+        &self,
+        network: Option<&BlockchainNetwork>,
+    ) -> PersonaResult<Vec<AddressBookEntry>> {
+        let network = network.map(serde_json::to_string).transpose()?;
+        let rows = sqlx::query(
+            r#"
+            SELECT id, label, address, network, notes, created_at, updated_at
+            FROM address_book
+            WHERE $1 IS NULL OR network = $1
+            ORDER BY label
+            "#,
+        )
+        .bind(network)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter().map(|row| self.entry_from_row(row)).collect()
+    }
+
+    /// Remove an entry by label
+    pub async fn delete_by_label(&self, label: &str) -> PersonaResult<bool> {
+        let result = sqlx::query("DELETE FROM address_book WHERE label = $1 COLLATE NOCASE")
+            .bind(label)
+            .execute(self.db.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Resolve a transaction recipient given as a label or a literal address
+    ///
+    /// Labels must belong to `network`; anything that is not a label is returned as-is.
+    pub async fn resolve_recipient(
+        &self,
+        label_or_address: &str,
+        network: &BlockchainNetwork,
+    ) -> PersonaResult<String> {
+        match self.find_by_label(label_or_address).await? {
+            Some(entry) if &entry.network == network => Ok(entry.address),
+            Some(entry) => Err(PersonaError::InvalidInput(format!(
+                "Address book entry '{}' is a {} address, not {}",
+                entry.label, entry.network, network
+            ))),
+            None => Ok(label_or_address.to_string()),
+        }
+    }
+
+    fn entry_from_row(&self, row: &sqlx::sqlite::SqliteRow) -> PersonaResult<AddressBookEntry> {
+        let id_str: String = row.get("id");
+        let network_str: String = row.get("network");
+        let created_at_ts: i64 = row.get("created_at");
+        let updated_at_ts: i64 = row.get("updated_at");
+
+        Ok(AddressBookEntry {
+            id: Uuid::parse_str(&id_str).map_err(|e| PersonaError::InvalidInput(e.to_string()))?,
+            label: row.get("label"),
+            address: row.get("address"),
+            network: serde_json::from_str(&network_str)?,
+            notes: row.get("notes"),
+            created_at: Utc.timestamp_opt(created_at_ts, 0).unwrap(),
+            updated_at: Utc.timestamp_opt(updated_at_ts, 0).unwrap(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BTC_ADDRESS: &str = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+    const ETH_ADDRESS: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    async fn repository() -> AddressBookRepository {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        AddressBookRepository::new(Arc::new(db))
+    }
+
+    #[tokio::test]
+    async fn test_address_book_crud_and_resolution() {
+        let repo = repository().await;
+        repo.create(
+            &AddressBookEntry::new(
+                "Cold Storage".to_string(),
+                BTC_ADDRESS.to_string(),
+                BlockchainNetwork::Bitcoin,
+            )
+            .with_notes(Some("hardware wallet".to_string())),
+        )
+        .await
+        .unwrap();
+        repo.create(&AddressBookEntry::new(
+            "exchange".to_string(),
+            ETH_ADDRESS.to_string(),
+            BlockchainNetwork::Ethereum,
+        ))
+        .await
+        .unwrap();
+
+        let entry = repo.find_by_label("cold storage").await.unwrap().unwrap();
+        assert_eq!(entry.address, BTC_ADDRESS);
+        assert_eq!(entry.notes.as_deref(), Some("hardware wallet"));
+        assert_eq!(repo.list(None).await.unwrap().len(), 2);
+        assert_eq!(
+            repo.list(Some(&BlockchainNetwork::Ethereum))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(repo.find_by_address(ETH_ADDRESS).await.unwrap().len(), 1);
+
+        let btc = BlockchainNetwork::Bitcoin;
+        assert_eq!(
+            repo.resolve_recipient("Cold Storage", &btc).await.unwrap(),
+            BTC_ADDRESS
+        );
+        assert_eq!(
+            repo.resolve_recipient("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", &btc)
+                .await
+                .unwrap(),
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"
+        );
+        assert!(repo.resolve_recipient("exchange", &btc).await.is_err());
+
+        assert!(repo.delete_by_label("EXCHANGE").await.unwrap());
+        assert!(!repo.delete_by_label("exchange").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_address_book_rejects_invalid_entries() {
+        let repo = repository().await;
+        let entry = |label: &str, address: &str| {
+            AddressBookEntry::new(
+                label.to_string(),
+                address.to_string(),
+                BlockchainNetwork::Bitcoin,
+            )
+        };
+
+        // Last character altered: checksum no longer matches
+        let typo = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdp";
+        assert!(repo.create(&entry("typo", typo)).await.is_err());
+        assert!(repo.create(&entry(" ", BTC_ADDRESS)).await.is_err());
+
+        repo.create(&entry("savings", BTC_ADDRESS)).await.unwrap();
+        assert!(repo.create(&entry("Savings", BTC_ADDRESS)).await.is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::models::{Attachment, AttachmentChunk, AttachmentStats};
+use crate::models::{Attachment, AttachmentBlob, AttachmentChunk, AttachmentStats};
 use crate::storage::Database;
 use crate::{PersonaError, Result};
 use sqlx::Row;
@@ -96,6 +96,73 @@ impl AttachmentRepository {
             .collect()
     }
 
+    /// Find every attachment of a credential, including deactivated ones, so it can be removed
+    /// along with the credential
+    pub async fn find_all_by_credential(&self, credential_id: &Uuid) -> Result<Vec<Attachment>> {
+        let query = r#"
+            SELECT id, credential_id, filename, mime_type, size,
+                   storage_path, content_hash, is_encrypted, encryption_key_id,
+                   chunk_count, chunk_size, tags, metadata,
+                   created_at, updated_at, last_accessed, is_active
+            FROM attachments
+            WHERE credential_id = ?
+        "#;
+
+        let rows = sqlx::query(query)
+            .bind(credential_id.to_string())
+            .fetch_all(self.db.pool())
+            .await
+            .map_err(|e| PersonaError::Database(format!("Failed to find attachments: {}", e)))?;
+
+        rows.into_iter()
+            .map(|row| self.row_to_attachment(row))
+            .collect()
+    }
+
+    /// Find every attachment of every credential (trashed or not) of an identity
+    pub async fn find_all_by_identity(&self, identity_id: &Uuid) -> Result<Vec<Attachment>> {
+        let query = r#"
+            SELECT a.id, a.credential_id, a.filename, a.mime_type, a.size,
+                   a.storage_path, a.content_hash, a.is_encrypted, a.encryption_key_id,
+                   a.chunk_count, a.chunk_size, a.tags, a.metadata,
+                   a.created_at, a.updated_at, a.last_accessed, a.is_active
+            FROM attachments a
+            JOIN credentials c ON c.id = a.credential_id
+            WHERE c.identity_id = ?
+        "#;
+
+        let rows = sqlx::query(query)
+            .bind(identity_id.to_string())
+            .fetch_all(self.db.pool())
+            .await
+            .map_err(|e| PersonaError::Database(format!("Failed to find attachments: {}", e)))?;
+
+        rows.into_iter()
+            .map(|row| self.row_to_attachment(row))
+            .collect()
+    }
+
+    /// Find every active encrypted attachment
+    pub async fn find_encrypted(&self) -> Result<Vec<Attachment>> {
+        let query = r#"
+            SELECT id, credential_id, filename, mime_type, size,
+                   storage_path, content_hash, is_encrypted, encryption_key_id,
+                   chunk_count, chunk_size, tags, metadata,
+                   created_at, updated_at, last_accessed, is_active
+            FROM attachments
+            WHERE is_encrypted = 1 AND is_active = 1
+        "#;
+
+        let rows = sqlx::query(query)
+            .fetch_all(self.db.pool())
+            .await
+            .map_err(|e| PersonaError::Database(format!("Failed to find attachments: {}", e)))?;
+
+        rows.into_iter()
+            .map(|row| self.row_to_attachment(row))
+            .collect()
+    }
+
     /// Update attachment
     pub async fn update(&self, attachment: &Attachment) -> Result<()> {
         let query = r#"
@@ -159,6 +226,19 @@ impl AttachmentRepository {
         Ok(())
     }
 
+    /// Record `key_id` on encrypted attachments that have no key id; returns how many changed
+    pub async fn fill_missing_key_ids(&self, key_id: &str) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE attachments SET encryption_key_id = ? WHERE is_encrypted = 1 AND encryption_key_id IS NULL",
+        )
+        .bind(key_id)
+        .execute(self.db.pool())
+        .await
+        .map_err(|e| PersonaError::Database(format!("Failed to update key ids: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Get attachment statistics
     pub async fn get_stats(&self) -> Result<AttachmentStats> {
         let query = r#"
@@ -262,6 +342,119 @@ impl AttachmentRepository {
         Ok(())
     }
 
+    /// Delete a single chunk of an attachment
+    pub async fn delete_chunk(&self, attachment_id: &Uuid, chunk_index: u32) -> Result<()> {
+        let query = "DELETE FROM attachment_chunks WHERE attachment_id = ? AND chunk_index = ?";
+
+        sqlx::query(query)
+            .bind(attachment_id.to_string())
+            .bind(chunk_index as i32)
+            .execute(self.db.pool())
+            .await
+            .map_err(|e| PersonaError::Database(format!("Failed to delete chunk: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Find a content-addressed blob by the hash of its plaintext
+    pub async fn find_blob(&self, content_hash: &str) -> Result<Option<AttachmentBlob>> {
+        let query = r#"
+            SELECT content_hash, size, storage_path, wrapped_key, ref_count, created_at
+            FROM attachment_blobs
+            WHERE content_hash = ?
+        "#;
+
+        let row = sqlx::query(query)
+            .bind(content_hash)
+            .fetch_optional(self.db.pool())
+            .await
+            .map_err(|e| PersonaError::Database(format!("Failed to find blob: {}", e)))?;
+
+        row.map(|r| self.row_to_blob(r)).transpose()
+    }
+
+    /// Record a newly stored blob with a single reference, or add a reference to the row a
+    /// concurrent store already recorded for the same content.
+    ///
+    /// Returns the row as stored; a `ref_count` of 1 means this call created it.
+    pub async fn create_or_acquire_blob(&self, blob: &AttachmentBlob) -> Result<AttachmentBlob> {
+        let query = r#"
+            INSERT INTO attachment_blobs (
+                content_hash, size, storage_path, wrapped_key, ref_count, created_at
+            ) VALUES (?, ?, ?, ?, 1, ?)
+            ON CONFLICT(content_hash) DO UPDATE SET ref_count = ref_count + 1
+            RETURNING content_hash, size, storage_path, wrapped_key, ref_count, created_at
+        "#;
+
+        let row = sqlx::query(query)
+            .bind(&blob.content_hash)
+            .bind(blob.size as i64)
+            .bind(&blob.storage_path)
+            .bind(&blob.wrapped_key)
+            .bind(blob.created_at.to_rfc3339())
+            .fetch_one(self.db.pool())
+            .await
+            .map_err(|e| PersonaError::Database(format!("Failed to create blob: {}", e)))?;
+
+        self.row_to_blob(row)
+    }
+
+    /// Add a reference to an existing blob
+    pub async fn acquire_blob(&self, content_hash: &str) -> Result<()> {
+        let query = "UPDATE attachment_blobs SET ref_count = ref_count + 1 WHERE content_hash = ?";
+
+        let result = sqlx::query(query)
+            .bind(content_hash)
+            .execute(self.db.pool())
+            .await
+            .map_err(|e| PersonaError::Database(format!("Failed to reference blob: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(PersonaError::NotFound(format!("Blob {} not found", content_hash)).into());
+        }
+        Ok(())
+    }
+
+    /// Drop a reference to a blob, removing its row once nothing references it.
+    ///
+    /// Returns the number of references left; at zero the caller deletes the stored data.
+    pub async fn release_blob(&self, content_hash: &str) -> Result<u32> {
+        let mut tx =
+            self.db.pool().begin().await.map_err(|e| {
+                PersonaError::Database(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        sqlx::query(
+            "UPDATE attachment_blobs SET ref_count = ref_count - 1 \
+             WHERE content_hash = ? AND ref_count > 0",
+        )
+        .bind(content_hash)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| PersonaError::Database(format!("Failed to release blob: {}", e)))?;
+
+        let remaining: Option<i64> =
+            sqlx::query_scalar("SELECT ref_count FROM attachment_blobs WHERE content_hash = ?")
+                .bind(content_hash)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| PersonaError::Database(format!("Failed to release blob: {}", e)))?;
+        let remaining = remaining.unwrap_or(0).max(0) as u32;
+
+        if remaining == 0 {
+            sqlx::query("DELETE FROM attachment_blobs WHERE content_hash = ?")
+                .bind(content_hash)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| PersonaError::Database(format!("Failed to delete blob: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| PersonaError::Database(format!("Failed to commit transaction: {}", e)))?;
+        Ok(remaining)
+    }
+
     /// Convert database row to Attachment
     fn row_to_attachment(&self, row: sqlx::sqlite::SqliteRow) -> Result<Attachment> {
         let tags_str: String = row.get("tags");
@@ -314,6 +507,20 @@ impl AttachmentRepository {
                 .with_timezone(&chrono::Utc),
         })
     }
+
+    /// Convert database row to AttachmentBlob
+    fn row_to_blob(&self, row: sqlx::sqlite::SqliteRow) -> Result<AttachmentBlob> {
+        Ok(AttachmentBlob {
+            content_hash: row.get("content_hash"),
+            size: row.get::<i64, _>("size") as u64,
+            storage_path: row.get("storage_path"),
+            wrapped_key: row.get("wrapped_key"),
+            ref_count: row.get::<i64, _>("ref_count") as u32,
+            created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+                .map_err(|e| PersonaError::Database(format!("Invalid datetime: {}", e)))?
+                .with_timezone(&chrono::Utc),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -28,14 +28,15 @@ impl AutoLockPolicyRepository {
                 sensitive_operation_timeout_secs, max_concurrent_sessions,
                 enable_warnings, warning_time_secs, force_lock_sensitive,
                 activity_grace_period_secs, background_check_interval_secs,
+                lock_on_sleep, lock_on_screen_lock,
                 metadata, is_active, is_default, created_at, updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(policy.id.to_string())
         .bind(&policy.name)
         .bind(&policy.description)
-        .bind(policy.security_level.to_string())
+        .bind(policy.security_level.to_string().to_lowercase())
         .bind(policy.inactivity_timeout_secs as i64)
         .bind(policy.absolute_timeout_secs as i64)
         .bind(policy.sensitive_operation_timeout_secs as i64)
@@ -45,6 +46,8 @@ impl AutoLockPolicyRepository {
         .bind(policy.force_lock_sensitive)
         .bind(policy.activity_grace_period_secs as i64)
         .bind(policy.background_check_interval_secs as i64)
+        .bind(policy.lock_on_sleep)
+        .bind(policy.lock_on_screen_lock)
         .bind(serde_json::to_string(&policy.metadata)?)
         .bind(policy.is_active)
         .bind(policy.is_default)
@@ -74,14 +77,15 @@ impl AutoLockPolicyRepository {
                 absolute_timeout_secs = ?, sensitive_operation_timeout_secs = ?,
                 max_concurrent_sessions = ?, enable_warnings = ?, warning_time_secs = ?,
                 force_lock_sensitive = ?, activity_grace_period_secs = ?,
-                background_check_interval_secs = ?, metadata = ?, is_active = ?,
+                background_check_interval_secs = ?, lock_on_sleep = ?,
+                lock_on_screen_lock = ?, metadata = ?, is_active = ?,
                 is_default = ?, updated_at = ?
             WHERE id = ?
             "#,
         )
         .bind(&policy.name)
         .bind(&policy.description)
-        .bind(policy.security_level.to_string())
+        .bind(policy.security_level.to_string().to_lowercase())
         .bind(policy.inactivity_timeout_secs as i64)
         .bind(policy.absolute_timeout_secs as i64)
         .bind(policy.sensitive_operation_timeout_secs as i64)
@@ -91,6 +95,8 @@ impl AutoLockPolicyRepository {
         .bind(policy.force_lock_sensitive)
         .bind(policy.activity_grace_period_secs as i64)
         .bind(policy.background_check_interval_secs as i64)
+        .bind(policy.lock_on_sleep)
+        .bind(policy.lock_on_screen_lock)
         .bind(serde_json::to_string(&policy.metadata)?)
         .bind(policy.is_active)
         .bind(policy.is_default)
@@ -155,7 +161,7 @@ impl AutoLockPolicyRepository {
         let rows = sqlx::query(
             "SELECT * FROM auto_lock_policies WHERE security_level = ? AND is_active = 1 ORDER BY name",
         )
-        .bind(level.to_string())
+        .bind(level.to_string().to_lowercase())
         .fetch_all(self.db.pool())
         .await
         .map_err(|e| PersonaError::Database(format!("Failed to fetch policies: {}", e)))?;
@@ -295,6 +301,8 @@ impl AutoLockPolicyRepository {
             activity_grace_period_secs: row.get::<i64, _>("activity_grace_period_secs") as u64,
             background_check_interval_secs: row.get::<i64, _>("background_check_interval_secs")
                 as u64,
+            lock_on_sleep: row.get("lock_on_sleep"),
+            lock_on_screen_lock: row.get("lock_on_screen_lock"),
             metadata,
             is_active: row.get("is_active"),
             is_default: row.get("is_default"),
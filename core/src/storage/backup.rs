@@ -0,0 +1,478 @@
+//! Verified copies of the vault database.
+//!
+//! A backup is written with `VACUUM INTO` from a read transaction, so it is a consistent
+//! snapshot even while another process has the vault open and is writing to it. Its SHA-256 is
+//! recorded beside it in `<backup>.sha256` (`sha256sum` format), and a restore refuses any file
+//! whose contents no longer match. Backups are database copies only: secrets in them stay
+//! encrypted under the master key, and attachment files are not included.
+//!
+//! Backups of a vault encrypted at rest are encrypted under the same passphrase. They cannot be
+//! opened without it, so for them only the recorded hash is checked; SQLCipher's per-page HMAC
+//! catches damage once the restored vault is unlocked.
+
+use crate::storage::permissions::{create_private_dir_all, create_private_file};
+use crate::storage::Database;
+use crate::{PersonaError, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// File name prefix shared by manual backups and pre-destructive snapshots
+pub const BACKUP_FILE_PREFIX: &str = "persona_backup_";
+
+/// Timestamp at the start of a backup's name, after the prefix
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S_%3f";
+
+/// Length of a formatted [`BACKUP_TIMESTAMP_FORMAT`]
+const BACKUP_TIMESTAMP_FORMAT_LEN: usize = 19;
+
+/// Extension appended to a backup's path for its recorded hash
+pub const BACKUP_HASH_EXTENSION: &str = "sha256";
+
+/// A backup file and the hash it was recorded with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    /// Hex SHA-256 of the file
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// File name for a backup labelled `label` (e.g. `manual`, `purge_trash`).
+///
+/// Names start with a fixed-width UTC timestamp, so sorting them by name sorts them by age.
+pub fn backup_file_name(label: &str) -> String {
+    backup_file_name_at(label, Utc::now())
+}
+
+/// [`backup_file_name`] for a backup taken at `at`
+pub fn backup_file_name_at(label: &str, at: DateTime<Utc>) -> String {
+    format!(
+        "{}{}_{}.db",
+        BACKUP_FILE_PREFIX,
+        at.format(BACKUP_TIMESTAMP_FORMAT),
+        label
+    )
+}
+
+/// When the backup at `path` was taken, read back from its file name
+pub fn backup_timestamp(path: &Path) -> Option<DateTime<Utc>> {
+    let name = path.file_name()?.to_str()?;
+    let timestamp = name
+        .strip_prefix(BACKUP_FILE_PREFIX)?
+        .get(..BACKUP_TIMESTAMP_FORMAT_LEN)?;
+    NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// Where the hash of the backup at `path` is recorded
+pub fn backup_hash_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(BACKUP_HASH_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Write a consistent, integrity-checked copy of `db` to `path` and record its hash.
+///
+/// `path` must not exist yet. The copy is written under a temporary name and only moved into
+/// place once it has passed `PRAGMA integrity_check`, so an interrupted backup never leaves a
+/// file that looks complete.
+pub async fn create_backup(db: &Database, path: &Path) -> Result<BackupInfo> {
+    if path.exists() {
+        return Err(PersonaError::InvalidInput(format!(
+            "{} already exists; backups never overwrite a file",
+            path.display()
+        ))
+        .into());
+    }
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        create_private_dir_all(parent)?;
+    }
+
+    let partial = sibling(path, "partial");
+    remove_if_exists(&partial)?;
+    // VACUUM INTO accepts an existing empty file, which keeps the copy owner-only
+    create_private_file(&partial)?;
+    let written = async {
+        db.backup_to(&partial).await?;
+        if Database::is_encrypted_file(&partial)? {
+            return Ok(());
+        }
+        let errors =
+            open_existing(&partial, |copy| async move { copy.integrity_check().await }).await?;
+        if !errors.is_empty() {
+            return Err(PersonaError::StorageError(format!(
+                "The backup copy failed its integrity check: {}",
+                errors.join("; ")
+            ))
+            .into());
+        }
+        Ok(())
+    }
+    .await;
+    if let Err(e) = written {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    let (sha256, size) = hash_file(&partial)?;
+    fs::rename(&partial, path)
+        .map_err(|e| PersonaError::Io(format!("{}: {}", path.display(), e)))?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    fs::write(
+        backup_hash_path(path),
+        format!("{}  {}\n", sha256, file_name),
+    )
+    .map_err(|e| PersonaError::Io(format!("{}: {}", path.display(), e)))?;
+
+    Ok(BackupInfo {
+        path: path.to_path_buf(),
+        sha256,
+        size,
+    })
+}
+
+/// Check a backup against its recorded hash and make sure it is a sound Persona vault.
+///
+/// Fails if the hash file is missing, the contents have changed since the backup was made, the
+/// database is corrupt, or it was written by a newer schema than this build understands. An
+/// encrypted backup is only checked against its hash.
+pub async fn verify_backup(path: &Path) -> Result<BackupInfo> {
+    let recorded = recorded_hash(path)?;
+    let (sha256, size) = hash_file(path)?;
+    if sha256 != recorded {
+        return Err(PersonaError::StorageError(format!(
+            "{} does not match its recorded SHA-256; it is corrupt or has been modified",
+            path.display()
+        ))
+        .into());
+    }
+    let info = BackupInfo {
+        path: path.to_path_buf(),
+        sha256,
+        size,
+    };
+    if Database::is_encrypted_file(path)? {
+        return Ok(info);
+    }
+
+    let (errors, version) = open_existing(path, |backup| async move {
+        Ok((
+            backup.integrity_check().await?,
+            backup.schema_version().await?,
+        ))
+    })
+    .await?;
+    if !errors.is_empty() {
+        return Err(PersonaError::StorageError(format!(
+            "{} failed its integrity check: {}",
+            path.display(),
+            errors.join("; ")
+        ))
+        .into());
+    }
+    match version {
+        None => {
+            return Err(PersonaError::InvalidInput(format!(
+                "{} is not a Persona vault",
+                path.display()
+            ))
+            .into())
+        }
+        Some(version) if version > Database::latest_schema_version() => {
+            return Err(PersonaError::ConfigurationError(format!(
+                "{} was written by a newer version of Persona (schema {})",
+                path.display(),
+                version
+            ))
+            .into())
+        }
+        Some(_) => {}
+    }
+
+    Ok(info)
+}
+
+/// The SHA-256 recorded for the backup at `path` when it was made, in lowercase hex
+pub fn recorded_hash(path: &Path) -> Result<String> {
+    let hash_path = backup_hash_path(path);
+    let recorded = fs::read_to_string(&hash_path).map_err(|_| {
+        PersonaError::NotFound(format!(
+            "No recorded hash for {} (expected {})",
+            path.display(),
+            hash_path.display()
+        ))
+    })?;
+    let recorded = recorded
+        .split_whitespace()
+        .next()
+        .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| {
+            PersonaError::StorageError(format!("Malformed hash file {}", hash_path.display()))
+        })?
+        .to_ascii_lowercase();
+    Ok(recorded)
+}
+
+/// Verify `backup` and atomically replace the database at `target` with it.
+///
+/// The backup is copied beside `target` and the copy is hashed again before it is renamed over
+/// the live file, so a reader sees either the old vault or the restored one, never a mix.
+/// Stale `-wal`/`-shm` files of the old database are removed first so SQLite cannot replay
+/// them onto the restored file. Processes that still hold the old database open keep reading
+/// the replaced file until they reopen it.
+pub async fn restore_backup(backup: &Path, target: &Path) -> Result<BackupInfo> {
+    let info = verify_backup(backup).await?;
+
+    let staged = sibling(target, "restore");
+    remove_if_exists(&staged)?;
+    create_private_file(&staged)?;
+    let copied = copy_synced(backup, &staged).and_then(|()| {
+        let (sha256, _) = hash_file(&staged)?;
+        if sha256 != info.sha256 {
+            return Err(PersonaError::StorageError(
+                "The restored copy does not match the backup".to_string(),
+            )
+            .into());
+        }
+        Ok(())
+    });
+    if let Err(e) = copied {
+        let _ = fs::remove_file(&staged);
+        return Err(e);
+    }
+
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let mut journal = target.as_os_str().to_owned();
+        journal.push(suffix);
+        remove_if_exists(Path::new(&journal))?;
+    }
+    fs::rename(&staged, target)
+        .map_err(|e| PersonaError::Io(format!("{}: {}", target.display(), e)))?;
+    Ok(info)
+}
+
+/// Backups in `directory`, oldest first
+pub fn list_backups(directory: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(PersonaError::Io(format!("{}: {}", directory.display(), e)).into()),
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path.file_name().is_some_and(|name| {
+                    let name = name.to_string_lossy();
+                    name.starts_with(BACKUP_FILE_PREFIX) && name.ends_with(".db")
+                })
+        })
+        .collect();
+    backups.sort();
+    Ok(backups)
+}
+
+/// Delete the oldest backups in `directory` beyond the newest `keep`, with their hash files.
+/// Returns the removed backups.
+pub fn prune_backups(directory: &Path, keep: usize) -> Result<Vec<PathBuf>> {
+    let backups = list_backups(directory)?;
+    let excess = backups.len().saturating_sub(keep);
+    let removed: Vec<PathBuf> = backups.into_iter().take(excess).collect();
+    for path in &removed {
+        remove_backup(path)?;
+    }
+    Ok(removed)
+}
+
+/// Delete a backup and its hash file
+pub fn remove_backup(path: &Path) -> Result<()> {
+    remove_if_exists(path)?;
+    remove_if_exists(&backup_hash_path(path))
+}
+
+/// Open the database at `path` for the duration of `f`, without creating it.
+///
+/// Read-write because FTS5 cannot validate its index on a read-only connection; the checks
+/// only read, so the file is left byte-for-byte unchanged.
+async fn open_existing<T, F, Fut>(path: &Path, f: F) -> Result<T>
+where
+    F: FnOnce(Database) -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let db = Database::new(&format!("sqlite:{}?mode=rw", path.display())).await?;
+    let pool = db.pool().clone();
+    let result = f(db).await;
+    pool.close().await;
+    result
+}
+
+/// Hex SHA-256 and length of a file, read in blocks
+fn hash_file(path: &Path) -> Result<(String, u64)> {
+    let mut file =
+        fs::File::open(path).map_err(|e| PersonaError::Io(format!("{}: {}", path.display(), e)))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let read = file.read(&mut buffer).map_err(PersonaError::from)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+    Ok((hex::encode(hasher.finalize()), size))
+}
+
+/// Copy `from` into the existing file `to` and flush it to disk
+fn copy_synced(from: &Path, to: &Path) -> Result<()> {
+    let mut source =
+        fs::File::open(from).map_err(|e| PersonaError::Io(format!("{}: {}", from.display(), e)))?;
+    let mut destination = fs::OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(to)
+        .map_err(|e| PersonaError::Io(format!("{}: {}", to.display(), e)))?;
+    std::io::copy(&mut source, &mut destination).map_err(PersonaError::from)?;
+    destination.flush().map_err(PersonaError::from)?;
+    destination.sync_all().map_err(PersonaError::from)?;
+    Ok(())
+}
+
+/// Hidden temporary file next to `path`
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}", name, suffix))
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(PersonaError::Io(format!("{}: {}", path.display(), e)).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn vault(dir: &Path) -> Database {
+        let db = Database::from_file(dir.join("identities.db"))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn backup_is_consistent_while_another_connection_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = vault(dir.path()).await;
+        let installation = db.installation_id().await.unwrap();
+
+        // A second process holding an uncommitted write
+        let other = Database::from_file(dir.path().join("identities.db"))
+            .await
+            .unwrap();
+        let mut tx = other.pool().begin().await.unwrap();
+        sqlx::query("UPDATE vault_info SET installation_id = 'uncommitted' WHERE id = 1")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+
+        let path = dir.path().join("backups").join(backup_file_name("manual"));
+        let info = create_backup(&db, &path).await.unwrap();
+        tx.rollback().await.unwrap();
+
+        assert_eq!(info.size, fs::metadata(&path).unwrap().len());
+        let recorded = fs::read_to_string(backup_hash_path(&path)).unwrap();
+        assert!(recorded.starts_with(&info.sha256));
+        assert_eq!(verify_backup(&path).await.unwrap(), info);
+
+        let copy = Database::from_file(&path).await.unwrap();
+        assert_eq!(copy.installation_id().await.unwrap(), installation);
+        assert!(create_backup(&db, &path).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn tampered_backups_are_not_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = vault(dir.path()).await;
+        let path = dir.path().join(backup_file_name("manual"));
+        create_backup(&db, &path).await.unwrap();
+
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&path, bytes).unwrap();
+
+        let target = dir.path().join("restored.db");
+        let err = restore_backup(&path, &target).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PersonaError>(),
+            Some(PersonaError::StorageError(_))
+        ));
+        assert!(!target.exists());
+
+        fs::remove_file(backup_hash_path(&path)).unwrap();
+        assert!(verify_backup(&path).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn restore_replaces_the_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = vault(dir.path()).await;
+        let before = db.installation_id().await.unwrap();
+        let path = dir.path().join(backup_file_name("manual"));
+        create_backup(&db, &path).await.unwrap();
+
+        sqlx::query("UPDATE vault_info SET installation_id = 'changed' WHERE id = 1")
+            .execute(db.pool())
+            .await
+            .unwrap();
+        db.pool().close().await;
+
+        let target = dir.path().join("identities.db");
+        restore_backup(&path, &target).await.unwrap();
+        let restored = Database::from_file(&target).await.unwrap();
+        assert_eq!(restored.installation_id().await.unwrap(), before);
+        assert!(!sibling(&target, "restore").exists());
+    }
+
+    #[tokio::test]
+    async fn prune_keeps_the_newest_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = vault(dir.path()).await;
+        let backups = dir.path().join("backups");
+        let mut created = Vec::new();
+        for label in ["first", "second", "third"] {
+            let path = backups.join(backup_file_name(label));
+            create_backup(&db, &path).await.unwrap();
+            created.push(path);
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        fs::write(backups.join("notes.txt"), b"kept").unwrap();
+
+        assert_eq!(prune_backups(&backups, 2).unwrap(), created[..1]);
+        assert!(!backup_hash_path(&created[0]).exists());
+        assert_eq!(list_backups(&backups).unwrap(), created[1..]);
+        assert!(backups.join("notes.txt").exists());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::storage::{backup_file_name, create_backup, Database};
+use crate::Result;
+use std::path::{Path, PathBuf};
+
+/// Snapshots the vault before destructive operations when enabled.
+///
+/// Destructive paths (service deletes, migrations, replacing imports) all ask the guard
+/// rather than each deciding whether to back up. Snapshots are ordinary verified backups (see
+/// [`create_backup`]), so they can be restored like any other.
+#[derive(Debug, Clone, Default)]
+pub struct BackupGuard {
+    directory: Option<PathBuf>,
+}
+
+impl BackupGuard {
+    /// Guard that writes snapshots into `directory`
+    pub fn new(directory: PathBuf) -> Self {
+        Self {
+            directory: Some(directory),
+        }
+    }
+
+    /// Guard that never snapshots
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Where snapshots go, or `None` when disabled
+    pub fn directory(&self) -> Option<&Path> {
+        self.directory.as_deref()
+    }
+
+    /// Snapshot `db` ahead of `operation`, returning the backup path when one was written
+    pub async fn snapshot(&self, db: &Database, operation: &str) -> Result<Option<PathBuf>> {
+        let Some(directory) = &self.directory else {
+            return Ok(None);
+        };
+        let path = directory.join(backup_file_name(operation));
+        create_backup(db, &path).await?;
+        Ok(Some(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn disabled_guard_writes_nothing() {
+        let db = Database::in_memory().await.unwrap();
+        assert!(BackupGuard::disabled()
+            .snapshot(&db, "delete_identity")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn snapshot_copies_the_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::from_file(dir.path().join("src.db"))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+
+        let guard = BackupGuard::new(dir.path().join("backups"));
+        let path = guard.snapshot(&db, "migrate").await.unwrap().unwrap();
+        assert!(path.starts_with(guard.directory().unwrap()));
+        assert!(path.to_string_lossy().ends_with("_migrate.db"));
+
+        let copy = Database::from_file(&path).await.unwrap();
+        assert_eq!(
+            copy.schema_version().await.unwrap(),
+            db.schema_version().await.unwrap()
+        );
+    }
+}
@@ -0,0 +1,367 @@
+//! Automatic backups for long-running processes (desktop app, SSH agent).
+//!
+//! The schedule is the `[backup]` table of the `config.toml` beside the database, the same
+//! settings `persona backup create` honours. A backup is due once `backup_interval` seconds have
+//! passed since the newest backup in the directory (manual ones count too). When the vault has
+//! not changed since that backup the fresh copy hashes the same and is discarded, so an idle
+//! vault does not fill the directory with identical files.
+
+use crate::events::{EventBus, VaultEvent};
+use crate::storage::{
+    backup_file_name_at, backup_timestamp, create_backup, list_backups, prune_backups,
+    recorded_hash, remove_backup, BackupInfo, Database,
+};
+use crate::{PersonaError, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Label of backups written by the scheduler
+pub const SCHEDULED_BACKUP_LABEL: &str = "scheduled";
+
+/// Longest the background task sleeps between checks whether a backup is due
+const BACKUP_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Source of the current time, injectable so tests can move it forward
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// When and where automatic backups are written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupSchedule {
+    pub directory: PathBuf,
+    pub interval: Duration,
+    /// Backups kept in `directory`; 0 keeps all of them
+    pub max_backups: u32,
+}
+
+/// The `[backup]` table as the CLI writes it
+#[derive(Debug, Deserialize)]
+struct BackupSection {
+    enabled: bool,
+    directory: PathBuf,
+    auto_backup: bool,
+    backup_interval: u64,
+    max_backups: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkspaceConfigFile {
+    backup: Option<BackupSection>,
+}
+
+impl BackupSchedule {
+    /// Read the schedule for the database at `db_path` from `config.toml` in the same directory.
+    ///
+    /// Returns `None` when there is no config file or backup table, or when backups, automatic
+    /// backups or the interval are switched off.
+    pub fn for_database(db_path: &Path) -> Result<Option<Self>> {
+        let config_path = db_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("config.toml");
+        let content = match std::fs::read_to_string(&config_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(PersonaError::Io(format!("{}: {}", config_path.display(), e)).into())
+            }
+        };
+        let config: WorkspaceConfigFile = toml::from_str(&content).map_err(|e| {
+            PersonaError::ConfigurationError(format!("{}: {}", config_path.display(), e))
+        })?;
+        Ok(config.backup.and_then(|backup| {
+            (backup.enabled && backup.auto_backup && backup.backup_interval > 0).then(|| Self {
+                directory: backup.directory,
+                interval: Duration::from_secs(backup.backup_interval),
+                max_backups: backup.max_backups,
+            })
+        }))
+    }
+}
+
+/// What a scheduler check did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduledBackup {
+    /// The interval has not elapsed since the last backup
+    NotDue { next_due: DateTime<Utc> },
+    /// The vault matches the newest backup, so nothing was kept
+    Unchanged,
+    /// A backup was written, and older ones beyond `max_backups` removed
+    Created {
+        info: BackupInfo,
+        pruned: Vec<PathBuf>,
+    },
+}
+
+/// Writes a backup whenever the schedule's interval has elapsed
+pub struct BackupScheduler {
+    schedule: BackupSchedule,
+    clock: Arc<dyn Clock>,
+    /// Last time a due check found the vault unchanged; counts as a backup for the interval
+    last_checked: Option<DateTime<Utc>>,
+}
+
+impl BackupScheduler {
+    pub fn new(schedule: BackupSchedule) -> Self {
+        Self::with_clock(schedule, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(schedule: BackupSchedule, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            schedule,
+            clock,
+            last_checked: None,
+        }
+    }
+
+    pub fn schedule(&self) -> &BackupSchedule {
+        &self.schedule
+    }
+
+    /// When the next backup is due; `None` if there has never been one
+    pub fn next_due(&self) -> Result<Option<DateTime<Utc>>> {
+        let newest = list_backups(&self.schedule.directory)?
+            .last()
+            .and_then(|path| backup_timestamp(path));
+        let last = newest.max(self.last_checked);
+        let interval =
+            ChronoDuration::from_std(self.schedule.interval).unwrap_or(ChronoDuration::MAX);
+        Ok(last.map(|last| {
+            last.checked_add_signed(interval)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC)
+        }))
+    }
+
+    /// Back up `db` if the interval has elapsed since the last backup.
+    pub async fn run_due(&mut self, db: &Database) -> Result<ScheduledBackup> {
+        let now = self.clock.now();
+        if let Some(next_due) = self.next_due()? {
+            if now < next_due {
+                return Ok(ScheduledBackup::NotDue { next_due });
+            }
+        }
+
+        let previous = list_backups(&self.schedule.directory)?.pop();
+        let path = self
+            .schedule
+            .directory
+            .join(backup_file_name_at(SCHEDULED_BACKUP_LABEL, now));
+        let info = create_backup(db, &path).await?;
+
+        // An identical copy means nothing changed; keep the older file and its timestamp
+        let unchanged = previous
+            .filter(|previous| previous != &path)
+            .and_then(|previous| recorded_hash(&previous).ok())
+            .is_some_and(|hash| hash == info.sha256);
+        if unchanged {
+            remove_backup(&path)?;
+            self.last_checked = Some(now);
+            return Ok(ScheduledBackup::Unchanged);
+        }
+
+        let pruned = if self.schedule.max_backups > 0 {
+            prune_backups(&self.schedule.directory, self.schedule.max_backups as usize)?
+        } else {
+            Vec::new()
+        };
+        Ok(ScheduledBackup::Created { info, pruned })
+    }
+
+    /// Check in the background until the task is aborted, logging each backup and publishing
+    /// [`VaultEvent::BackupCreated`] or [`VaultEvent::BackupFailed`] on `events`.
+    pub fn spawn(mut self, db: Database, events: Option<EventBus>) -> tokio::task::JoinHandle<()> {
+        let poll = self.schedule.interval.min(BACKUP_POLL_INTERVAL);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(poll);
+            loop {
+                ticker.tick().await;
+                match self.run_due(&db).await {
+                    Ok(ScheduledBackup::Created { info, pruned }) => {
+                        info!(
+                            "Scheduled backup written to {} ({} old backup(s) pruned)",
+                            info.path.display(),
+                            pruned.len()
+                        );
+                        if let Some(events) = &events {
+                            events.publish(VaultEvent::BackupCreated { path: info.path });
+                        }
+                    }
+                    Ok(ScheduledBackup::Unchanged) => {
+                        info!("Vault unchanged since the last backup; scheduled backup skipped");
+                    }
+                    Ok(ScheduledBackup::NotDue { .. }) => {}
+                    Err(e) => {
+                        warn!("Scheduled backup failed: {}", e);
+                        if let Some(events) = &events {
+                            events.publish(VaultEvent::BackupFailed {
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct TestClock(Mutex<DateTime<Utc>>);
+
+    impl TestClock {
+        fn advance(&self, by: Duration) {
+            let mut now = self.0.lock().unwrap();
+            *now += ChronoDuration::from_std(by).unwrap();
+        }
+    }
+
+    impl Clock for TestClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    async fn vault(dir: &Path) -> Database {
+        let db = Database::from_file(dir.join("identities.db"))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    fn scheduler(dir: &Path, max_backups: u32) -> (BackupScheduler, Arc<TestClock>) {
+        let clock = Arc::new(TestClock(Mutex::new(Utc::now())));
+        let schedule = BackupSchedule {
+            directory: dir.join("backups"),
+            interval: Duration::from_secs(3600),
+            max_backups,
+        };
+        (BackupScheduler::with_clock(schedule, clock.clone()), clock)
+    }
+
+    async fn touch(db: &Database, value: &str) {
+        sqlx::query("UPDATE vault_info SET installation_id = ? WHERE id = 1")
+            .bind(value)
+            .execute(db.pool())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn backs_up_once_the_interval_elapses() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = vault(dir.path()).await;
+        let (mut scheduler, clock) = scheduler(dir.path(), 0);
+
+        let first = scheduler.run_due(&db).await.unwrap();
+        assert!(matches!(first, ScheduledBackup::Created { .. }));
+
+        touch(&db, "changed").await;
+        clock.advance(Duration::from_secs(1800));
+        assert!(matches!(
+            scheduler.run_due(&db).await.unwrap(),
+            ScheduledBackup::NotDue { .. }
+        ));
+
+        clock.advance(Duration::from_secs(1800));
+        assert!(matches!(
+            scheduler.run_due(&db).await.unwrap(),
+            ScheduledBackup::Created { .. }
+        ));
+        assert_eq!(
+            list_backups(&scheduler.schedule().directory).unwrap().len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn unchanged_vault_is_not_backed_up_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = vault(dir.path()).await;
+        let (mut scheduler, clock) = scheduler(dir.path(), 0);
+        scheduler.run_due(&db).await.unwrap();
+
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(
+            scheduler.run_due(&db).await.unwrap(),
+            ScheduledBackup::Unchanged
+        );
+        let directory = scheduler.schedule().directory.clone();
+        assert_eq!(list_backups(&directory).unwrap().len(), 1);
+
+        // The skipped check restarts the interval instead of retrying every poll
+        clock.advance(Duration::from_secs(60));
+        assert!(matches!(
+            scheduler.run_due(&db).await.unwrap(),
+            ScheduledBackup::NotDue { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn prunes_beyond_max_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = vault(dir.path()).await;
+        let (mut scheduler, clock) = scheduler(dir.path(), 2);
+
+        let mut pruned_total = 0;
+        for i in 0..3 {
+            touch(&db, &format!("change-{}", i)).await;
+            match scheduler.run_due(&db).await.unwrap() {
+                ScheduledBackup::Created { pruned, .. } => pruned_total += pruned.len(),
+                other => panic!("expected a backup, got {:?}", other),
+            }
+            clock.advance(Duration::from_secs(3600));
+        }
+        assert_eq!(pruned_total, 1);
+        assert_eq!(
+            list_backups(&scheduler.schedule().directory).unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn schedule_is_read_from_the_workspace_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("identities.db");
+        assert_eq!(BackupSchedule::for_database(&db_path).unwrap(), None);
+
+        let config = |auto_backup: bool| {
+            format!(
+                "[backup]\nenabled = true\ndirectory = \"{}\"\nauto_backup = {}\n\
+                 backup_interval = 86400\nmax_backups = 30\n",
+                dir.path().join("backups").display(),
+                auto_backup
+            )
+        };
+        std::fs::write(dir.path().join("config.toml"), config(true)).unwrap();
+        assert_eq!(
+            BackupSchedule::for_database(&db_path).unwrap(),
+            Some(BackupSchedule {
+                directory: dir.path().join("backups"),
+                interval: Duration::from_secs(86400),
+                max_backups: 30,
+            })
+        );
+
+        std::fs::write(dir.path().join("config.toml"), config(false)).unwrap();
+        assert_eq!(BackupSchedule::for_database(&db_path).unwrap(), None);
+    }
+}
@@ -1,11 +1,13 @@
 use crate::crypto::EncryptionService;
-use crate::models::{Attachment, AttachmentChunk};
+use crate::models::{Attachment, AttachmentBlob, AttachmentChunk};
 use crate::storage::{AttachmentRepository, FileSystem};
 use crate::{PersonaError, Result};
 use anyhow::anyhow;
 use ring::digest::{Context, SHA256};
 use std::path::{Path, PathBuf};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use uuid::Uuid;
+use zeroize::Zeroizing;
 
 /// Default chunk size: 1MB
 const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024;
@@ -13,6 +15,12 @@ const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024;
 /// Maximum file size for chunking: 100MB
 const MAX_SINGLE_FILE_SIZE: u64 = 100 * 1024 * 1024;
 
+/// Attachment metadata key that marks a streamed blob and tracks whether it is complete
+const STREAM_METADATA_KEY: &str = "stream";
+
+/// Attachment metadata key that marks an attachment backed by a shared content-addressed blob
+const BLOB_METADATA_KEY: &str = "content_addressed";
+
 /// Blob store for managing attachment file storage
 pub struct BlobStore {
     storage_root: PathBuf,
@@ -80,7 +88,7 @@ impl BlobStore {
                     .encrypt(&content)
                     .map_err(|e| anyhow::anyhow!("Encryption failed: {:?}", e))?;
                 content = encrypted;
-                (true, Some(hex::encode(&key[..16]))) // Use first 16 bytes as key ID
+                (true, Some(key_id(key)))
             } else {
                 return Err(anyhow::anyhow!("Encryption key required").into());
             }
@@ -220,7 +228,7 @@ impl BlobStore {
         attachment: &Attachment,
         chunks: &[AttachmentChunk],
     ) -> Result<()> {
-        if attachment.chunk_count > 1 {
+        if uses_chunks(attachment) {
             // Delete all chunks
             for chunk in chunks {
                 let chunk_path = self.storage_root.join(&chunk.storage_path);
@@ -246,6 +254,243 @@ impl BlobStore {
         Ok(())
     }
 
+    /// Stream a chunked blob written by [`AttachmentManager::put_stream`] into `writer`.
+    ///
+    /// Each chunk is checked against its manifest row before it is decrypted, so a missing,
+    /// truncated or altered chunk fails the read instead of producing corrupt output. Returns
+    /// the number of plaintext bytes written.
+    pub async fn get_stream<W: AsyncWrite + Unpin>(
+        &self,
+        attachment: &Attachment,
+        chunks: &[AttachmentChunk],
+        writer: &mut W,
+        decryption_key: Option<&[u8]>,
+    ) -> Result<u64> {
+        if !is_complete_stream(attachment) {
+            return Err(PersonaError::StorageError(format!(
+                "Attachment {} has not finished uploading",
+                attachment.id
+            ))
+            .into());
+        }
+
+        let mut context = Context::new(&SHA256);
+        let mut written = 0u64;
+        for index in 0..attachment.chunk_count {
+            let chunk = chunks
+                .iter()
+                .find(|c| c.chunk_index == index)
+                .ok_or_else(|| missing_chunk(attachment, index))?;
+            let plaintext = self.read_chunk(attachment, chunk, decryption_key).await?;
+
+            context.update(&plaintext);
+            writer.write_all(&plaintext).await?;
+            written += plaintext.len() as u64;
+        }
+        writer.flush().await?;
+
+        if hex::encode(context.finish().as_ref()) != attachment.content_hash {
+            return Err(anyhow!("Content hash mismatch"));
+        }
+        Ok(written)
+    }
+
+    /// Encrypt (if a key is given) and write one chunk of a streamed blob, returning its
+    /// manifest row
+    async fn write_chunk(
+        &self,
+        attachment: &Attachment,
+        chunk_index: u32,
+        plaintext: &[u8],
+        encryption_key: Option<&[u8]>,
+    ) -> Result<AttachmentChunk> {
+        let stored = match encryption_key {
+            Some(key) => cipher_for(key)?
+                .encrypt(plaintext)
+                .map_err(|e| anyhow!("Encryption failed: {:?}", e))?,
+            None => plaintext.to_vec(),
+        };
+
+        let chunk_path = self.get_chunk_path(
+            &attachment.credential_id,
+            &attachment.id,
+            chunk_index as usize,
+        );
+        if let Some(parent) = chunk_path.parent() {
+            FileSystem::create_dir_all(parent).await?;
+        }
+        FileSystem::write(&chunk_path, &stored).await?;
+
+        let mut chunk = AttachmentChunk::new(
+            attachment.id,
+            chunk_index,
+            stored.len() as u32,
+            self.calculate_hash(&stored),
+            self.relative_path(&chunk_path),
+        );
+        chunk.is_encrypted = encryption_key.is_some();
+        Ok(chunk)
+    }
+
+    /// Read one chunk, verify it against its manifest row and decrypt it
+    async fn read_chunk(
+        &self,
+        attachment: &Attachment,
+        chunk: &AttachmentChunk,
+        decryption_key: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        let chunk_path = self.storage_root.join(&chunk.storage_path);
+        if !FileSystem::exists(&chunk_path).await {
+            return Err(missing_chunk(attachment, chunk.chunk_index));
+        }
+
+        let stored = FileSystem::read(&chunk_path).await?;
+        if self.calculate_hash(&stored) != chunk.content_hash {
+            return Err(PersonaError::StorageError(format!(
+                "Chunk {} of attachment {} is corrupted",
+                chunk.chunk_index, attachment.id
+            ))
+            .into());
+        }
+
+        if !chunk.is_encrypted {
+            return Ok(stored);
+        }
+        let key = decryption_key.ok_or_else(|| anyhow!("Decryption key required"))?;
+        cipher_for(key)?
+            .decrypt(&stored)
+            .map_err(|e| anyhow!("Decryption failed: {:?}", e))
+    }
+
+    /// Write the content-addressed blob for `plaintext` beside its final path.
+    ///
+    /// With a key, the blob is encrypted under a fresh content key and only the content key,
+    /// wrapped by `encryption_key`, is kept in the returned row. The data only takes the blob's
+    /// address once [`Self::promote_blob`] moves it there, so a concurrent store of the same
+    /// content never overwrites a blob whose row holds a different content key.
+    pub async fn stage_blob(
+        &self,
+        content_hash: &str,
+        plaintext: &[u8],
+        encryption_key: Option<&[u8]>,
+    ) -> Result<(AttachmentBlob, PathBuf)> {
+        let (stored, wrapped_key) = match encryption_key {
+            Some(key) => {
+                let content_key = Zeroizing::new(EncryptionService::generate_key());
+                let stored = EncryptionService::new(&content_key)
+                    .encrypt(plaintext)
+                    .map_err(|e| anyhow!("Encryption failed: {:?}", e))?;
+                let wrapped = cipher_for(key)?
+                    .encrypt(content_key.as_slice())
+                    .map_err(|e| anyhow!("Encryption failed: {:?}", e))?;
+                (stored, Some(wrapped))
+            }
+            None => (plaintext.to_vec(), None),
+        };
+
+        let blob_path = self.get_blob_path(content_hash);
+        let staged = blob_path.with_file_name(format!("{}.{}.tmp", content_hash, Uuid::new_v4()));
+        if let Some(parent) = staged.parent() {
+            FileSystem::create_dir_all(parent).await?;
+        }
+        FileSystem::write(&staged, &stored).await?;
+
+        let blob = AttachmentBlob {
+            content_hash: content_hash.to_string(),
+            size: plaintext.len() as u64,
+            storage_path: self.relative_path(&blob_path),
+            wrapped_key,
+            ref_count: 1,
+            created_at: chrono::Utc::now(),
+        };
+        Ok((blob, staged))
+    }
+
+    /// Move a blob written by [`Self::stage_blob`] to its content address
+    pub async fn promote_blob(&self, staged: &Path, blob: &AttachmentBlob) -> Result<()> {
+        FileSystem::rename(staged, self.storage_root.join(&blob.storage_path)).await
+    }
+
+    /// Read a content-addressed blob.
+    ///
+    /// Encrypted blobs are returned as stored when no key is given; otherwise the content is
+    /// decrypted and checked against the blob's address.
+    pub async fn read_blob(
+        &self,
+        blob: &AttachmentBlob,
+        decryption_key: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        let blob_path = self.storage_root.join(&blob.storage_path);
+        if !FileSystem::exists(&blob_path).await {
+            return Err(PersonaError::StorageError(format!(
+                "Blob {} is missing",
+                blob.content_hash
+            ))
+            .into());
+        }
+        let stored = FileSystem::read(&blob_path).await?;
+
+        let content = match (&blob.wrapped_key, decryption_key) {
+            (Some(_), None) => return Ok(stored),
+            (Some(_), Some(key)) => {
+                let content_key = self.unwrap_content_key(blob, key)?;
+                EncryptionService::new(&content_key)
+                    .decrypt(&stored)
+                    .map_err(|e| anyhow!("Decryption failed: {:?}", e))?
+            }
+            (None, _) => stored,
+        };
+
+        if self.calculate_hash(&content) != blob.content_hash {
+            return Err(anyhow!("Content hash mismatch"));
+        }
+        Ok(content)
+    }
+
+    /// Whether an attachment stored with `encryption_key` may reference `blob`: both must be
+    /// plaintext, or the key must unwrap the blob's content key.
+    pub fn can_share_blob(&self, blob: &AttachmentBlob, encryption_key: Option<&[u8]>) -> bool {
+        match (&blob.wrapped_key, encryption_key) {
+            (None, None) => true,
+            (Some(_), Some(key)) => self.unwrap_content_key(blob, key).is_ok(),
+            _ => false,
+        }
+    }
+
+    /// Delete the stored data of a blob nothing references any more
+    pub async fn delete_blob(&self, blob_path: &str) -> Result<()> {
+        let blob_path = self.storage_root.join(blob_path);
+        if FileSystem::exists(&blob_path).await {
+            FileSystem::remove_file(&blob_path).await?;
+        }
+        Ok(())
+    }
+
+    fn unwrap_content_key(&self, blob: &AttachmentBlob, key: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+        let wrapped = blob
+            .wrapped_key
+            .as_deref()
+            .ok_or_else(|| anyhow!("Blob {} is not encrypted", blob.content_hash))?;
+        let content_key = Zeroizing::new(
+            cipher_for(key)?
+                .decrypt(wrapped)
+                .map_err(|e| anyhow!("Failed to unwrap blob key: {:?}", e))?,
+        );
+        let content_key: [u8; 32] = content_key
+            .as_slice()
+            .try_into()
+            .map_err(|_| anyhow!("Invalid blob key length"))?;
+        Ok(Zeroizing::new(content_key))
+    }
+
+    /// Path relative to the storage root, as recorded in the database
+    fn relative_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.storage_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    }
+
     /// Calculate SHA-256 hash of data using ring
     fn calculate_hash(&self, data: &[u8]) -> String {
         let mut context = Context::new(&SHA256);
@@ -291,6 +536,14 @@ impl BlobStore {
             .join(filename)
     }
 
+    /// Get content-addressed blob path, fanned out by the first byte of the hash
+    fn get_blob_path(&self, content_hash: &str) -> PathBuf {
+        self.storage_root
+            .join("blobs")
+            .join(&content_hash[..2.min(content_hash.len())])
+            .join(content_hash)
+    }
+
     /// Get chunk directory
     fn get_chunk_dir(&self, credential_id: &Uuid, attachment_id: &Uuid) -> PathBuf {
         self.storage_root
@@ -311,12 +564,122 @@ impl BlobStore {
     }
 }
 
+/// Whether the attachment was written by `put_stream` (one file per chunk, each encrypted
+/// on its own) rather than `store_file`
+fn is_streamed(attachment: &Attachment) -> bool {
+    attachment.metadata.get(STREAM_METADATA_KEY).is_some()
+}
+
+/// Whether every chunk of a streamed attachment has been written
+fn is_complete_stream(attachment: &Attachment) -> bool {
+    attachment
+        .metadata
+        .get(STREAM_METADATA_KEY)
+        .and_then(|stream| stream.get("complete"))
+        .and_then(|complete| complete.as_bool())
+        .unwrap_or(false)
+}
+
+/// Whether the attachment references a shared content-addressed blob
+fn is_content_addressed(attachment: &Attachment) -> bool {
+    attachment
+        .metadata
+        .get(BLOB_METADATA_KEY)
+        .and_then(|flag| flag.as_bool())
+        .unwrap_or(false)
+}
+
+/// Whether the attachment is encrypted in files of its own, rather than in a shared blob whose
+/// content key is wrapped by the attachment key. Unfinished uploads are left out.
+pub(crate) fn has_own_encrypted_files(attachment: &Attachment) -> bool {
+    attachment.is_encrypted
+        && !is_content_addressed(attachment)
+        && (!is_streamed(attachment) || is_complete_stream(attachment))
+}
+
+/// Whether the attachment's content lives in chunk rows rather than a single file
+fn uses_chunks(attachment: &Attachment) -> bool {
+    attachment.chunk_count > 1 || is_streamed(attachment)
+}
+
+fn missing_chunk(attachment: &Attachment, chunk_index: u32) -> anyhow::Error {
+    PersonaError::StorageError(format!(
+        "Chunk {} of attachment {} is missing",
+        chunk_index, attachment.id
+    ))
+    .into()
+}
+
+/// Identifier recorded for the key an attachment is encrypted under.
+///
+/// A truncated hash, so the stored id reveals nothing about the key itself.
+pub(crate) fn key_id(key: &[u8]) -> String {
+    let digest = ring::digest::digest(&SHA256, key);
+    hex::encode(&digest.as_ref()[..8])
+}
+
+fn cipher_for(key: &[u8]) -> Result<EncryptionService> {
+    Ok(EncryptionService::new(
+        key.try_into()
+            .map_err(|_| anyhow!("Invalid encryption key length"))?,
+    ))
+}
+
+/// Fill `buf` from `reader`, stopping early only at end of input
+async fn read_full<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let n = reader.read(&mut buf[filled..]).await?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+    Ok(filled)
+}
+
 /// Attachment manager combining repository and blob store
 pub struct AttachmentManager {
     repository: AttachmentRepository,
     blob_store: BlobStore,
 }
 
+/// Attachment files re-encrypted under a new key by [`AttachmentManager::stage_rekey`].
+///
+/// The new files sit beside the originals until the rewritten rows are committed. Then
+/// [`Self::finish`] removes the originals, or [`Self::discard`] removes the new files if the
+/// rows were not committed.
+#[derive(Debug, Default)]
+pub struct AttachmentRekey {
+    /// Rewritten attachment rows, each with its new chunk rows (empty for single files)
+    pub attachments: Vec<(Attachment, Vec<AttachmentChunk>)>,
+    staged: Vec<PathBuf>,
+    superseded: Vec<PathBuf>,
+}
+
+impl AttachmentRekey {
+    /// Remove the files the committed rows no longer reference
+    pub async fn finish(self) {
+        remove_files(&self.superseded).await;
+    }
+
+    /// Remove the new files after the rewritten rows were abandoned
+    pub async fn discard(self) {
+        remove_files(&self.staged).await;
+    }
+}
+
+/// Best-effort removal: a file left behind is unreferenced, not unreadable
+async fn remove_files(paths: &[PathBuf]) {
+    for path in paths {
+        if FileSystem::exists(path).await {
+            if let Err(e) = FileSystem::remove_file(path).await {
+                tracing::warn!("Failed to remove {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
 impl AttachmentManager {
     /// Create a new attachment manager
     pub fn new(repository: AttachmentRepository, blob_store: BlobStore) -> Self {
@@ -331,13 +694,202 @@ impl AttachmentManager {
         self.blob_store.init().await
     }
 
-    /// Store an attachment
+    /// Record the id of `key` on encrypted attachments whose key id was scrubbed
+    pub async fn fill_missing_key_ids(&self, key: &[u8]) -> Result<u64> {
+        self.repository.fill_missing_key_ids(&key_id(key)).await
+    }
+
+    /// Store an attachment.
+    ///
+    /// Content is stored once per distinct plaintext: attaching a file whose content is
+    /// already in the store only adds a reference to the existing blob. Files too large to
+    /// hold in memory, and content already stored under a different key, get their own copy.
     pub async fn store<P: AsRef<Path>>(
         &self,
         file_path: P,
         credential_id: Uuid,
         encrypt: bool,
         encryption_key: Option<&[u8]>,
+    ) -> Result<Uuid> {
+        let file_path = file_path.as_ref();
+        if !FileSystem::exists(file_path).await {
+            return Err(anyhow!("File does not exist"));
+        }
+        if FileSystem::file_size(file_path).await? > MAX_SINGLE_FILE_SIZE {
+            return self
+                .store_unshared(file_path, credential_id, encrypt, encryption_key)
+                .await;
+        }
+
+        let key = if encrypt {
+            Some(encryption_key.ok_or_else(|| anyhow!("Encryption key required"))?)
+        } else {
+            None
+        };
+        let filename = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow!("Invalid filename"))?
+            .to_string();
+        let content = Zeroizing::new(FileSystem::read(file_path).await?);
+
+        match self
+            .store_shared(credential_id, &filename, &content, key)
+            .await?
+        {
+            Some(attachment_id) => Ok(attachment_id),
+            None => {
+                self.store_unshared(file_path, credential_id, encrypt, encryption_key)
+                    .await
+            }
+        }
+    }
+
+    /// Store in-memory content as an attachment, sharing its blob with identical content.
+    ///
+    /// Content already stored under a different key gets its own copy.
+    pub async fn store_bytes(
+        &self,
+        credential_id: Uuid,
+        filename: &str,
+        content: &[u8],
+        encryption_key: Option<&[u8]>,
+    ) -> Result<Uuid> {
+        if let Some(attachment_id) = self
+            .store_shared(credential_id, filename, content, encryption_key)
+            .await?
+        {
+            return Ok(attachment_id);
+        }
+
+        let mut attachment = Attachment::new(
+            credential_id,
+            filename.to_string(),
+            self.blob_store.detect_mime_type(filename),
+            content.len() as u64,
+            String::new(),
+            String::new(),
+        );
+        let stored = match encryption_key {
+            Some(key) => {
+                attachment.enable_encryption(key_id(key));
+                cipher_for(key)?
+                    .encrypt(content)
+                    .map_err(|e| anyhow!("Encryption failed: {:?}", e))?
+            }
+            None => content.to_vec(),
+        };
+        attachment.content_hash = self.blob_store.calculate_hash(&stored);
+        let path = self
+            .blob_store
+            .get_file_path(&credential_id, &attachment.id, filename);
+        if let Some(parent) = path.parent() {
+            FileSystem::create_dir_all(parent).await?;
+        }
+        FileSystem::write(&path, &stored).await?;
+        attachment.storage_path = self.blob_store.relative_path(&path);
+
+        self.repository.create(&attachment).await?;
+        Ok(attachment.id)
+    }
+
+    /// Store content as a reference to its content-addressed blob.
+    ///
+    /// Returns `None` when the blob exists under a different key and cannot be shared.
+    async fn store_shared(
+        &self,
+        credential_id: Uuid,
+        filename: &str,
+        content: &[u8],
+        key: Option<&[u8]>,
+    ) -> Result<Option<Uuid>> {
+        let content_hash = self.blob_store.calculate_hash(content);
+
+        let blob = match self.repository.find_blob(&content_hash).await? {
+            Some(blob) if self.blob_store.can_share_blob(&blob, key) => {
+                self.repository.acquire_blob(&content_hash).await?;
+                blob
+            }
+            Some(_) => return Ok(None),
+            None => match self.create_blob(&content_hash, content, key).await? {
+                Some(blob) => blob,
+                None => return Ok(None),
+            },
+        };
+
+        let mut attachment = Attachment::new(
+            credential_id,
+            filename.to_string(),
+            self.blob_store.detect_mime_type(filename),
+            content.len() as u64,
+            blob.storage_path.clone(),
+            content_hash.clone(),
+        );
+        attachment.metadata[BLOB_METADATA_KEY] = serde_json::json!(true);
+        if let Some(key) = key {
+            attachment.enable_encryption(key_id(key));
+        }
+
+        if let Err(e) = self.repository.create(&attachment).await {
+            self.release_blob(&content_hash).await?;
+            return Err(e);
+        }
+        Ok(Some(attachment.id))
+    }
+
+    /// Write a new blob for `content`, or take a reference to the one a concurrent store
+    /// recorded first.
+    ///
+    /// The row is inserted (or its count incremented) in one statement, and the staged data is
+    /// only moved into place when this call created the row. Returns `None` when the other
+    /// store's blob is under a different key and cannot be shared.
+    async fn create_blob(
+        &self,
+        content_hash: &str,
+        content: &[u8],
+        key: Option<&[u8]>,
+    ) -> Result<Option<AttachmentBlob>> {
+        let (candidate, staged) = self
+            .blob_store
+            .stage_blob(content_hash, content, key)
+            .await?;
+        let stored = match self.repository.create_or_acquire_blob(&candidate).await {
+            Ok(stored) => stored,
+            Err(e) => {
+                remove_files(&[staged]).await;
+                return Err(e);
+            }
+        };
+
+        if stored.ref_count == 1 {
+            if let Err(e) = self.blob_store.promote_blob(&staged, &stored).await {
+                remove_files(&[staged]).await;
+                self.repository.release_blob(content_hash).await?;
+                return Err(e);
+            }
+            return Ok(Some(stored));
+        }
+
+        remove_files(&[staged]).await;
+        if !self.blob_store.can_share_blob(&stored, key) {
+            self.release_blob(content_hash).await?;
+            return Ok(None);
+        }
+        Ok(Some(stored))
+    }
+
+    /// Look up an attachment's metadata
+    pub async fn get(&self, attachment_id: &Uuid) -> Result<Option<Attachment>> {
+        self.repository.find_by_id(attachment_id).await
+    }
+
+    /// Store an attachment in its own files, without sharing content with other attachments
+    async fn store_unshared(
+        &self,
+        file_path: &Path,
+        credential_id: Uuid,
+        encrypt: bool,
+        encryption_key: Option<&[u8]>,
     ) -> Result<Uuid> {
         // Store file in blob store
         let attachment = self
@@ -377,6 +929,152 @@ impl AttachmentManager {
         Ok(attachment.id)
     }
 
+    /// Store an attachment from a stream, one chunk at a time.
+    ///
+    /// The attachment row is written first and acts as the manifest: every chunk gets its own
+    /// row (with the hash of the stored bytes) as soon as it is on disk, and the attachment is
+    /// only marked complete once the whole stream has been written. Memory use is bounded by
+    /// the chunk size. If the upload is interrupted, pass the returned id to
+    /// [`Self::resume_stream`] with the same source.
+    pub async fn put_stream<R: AsyncRead + Unpin>(
+        &self,
+        reader: &mut R,
+        credential_id: Uuid,
+        filename: &str,
+        encryption_key: Option<&[u8]>,
+    ) -> Result<Uuid> {
+        let mut attachment = Attachment::new(
+            credential_id,
+            filename.to_string(),
+            self.blob_store.detect_mime_type(filename),
+            0,
+            String::new(),
+            String::new(),
+        );
+        attachment.storage_path = self.blob_store.relative_path(
+            &self
+                .blob_store
+                .get_chunk_dir(&credential_id, &attachment.id),
+        );
+        attachment.set_chunks(0, self.blob_store.chunk_size as u32);
+        attachment.metadata[STREAM_METADATA_KEY] = serde_json::json!({ "complete": false });
+        if let Some(key) = encryption_key {
+            attachment.enable_encryption(key_id(key));
+        }
+        self.repository.create(&attachment).await?;
+
+        self.resume_stream(&attachment.id, reader, encryption_key)
+            .await?;
+        Ok(attachment.id)
+    }
+
+    /// Continue (or repair) a streamed attachment from the start of its source.
+    ///
+    /// Chunks that are already stored intact and match the source are kept as they are; missing
+    /// or damaged ones are rewritten. The attachment is marked complete at the end.
+    pub async fn resume_stream<R: AsyncRead + Unpin>(
+        &self,
+        attachment_id: &Uuid,
+        reader: &mut R,
+        encryption_key: Option<&[u8]>,
+    ) -> Result<()> {
+        let mut attachment = self
+            .repository
+            .find_by_id(attachment_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Attachment not found"))?;
+        if !is_streamed(&attachment) {
+            return Err(PersonaError::InvalidInput(format!(
+                "Attachment {} was not stored as a stream",
+                attachment_id
+            ))
+            .into());
+        }
+        if attachment.is_encrypted != encryption_key.is_some() {
+            return Err(PersonaError::InvalidInput(
+                "Resume must use the same encryption setting as the original upload".to_string(),
+            )
+            .into());
+        }
+        let existing = self.repository.get_chunks(attachment_id).await?;
+
+        let mut context = Context::new(&SHA256);
+        let mut buffer = vec![0u8; attachment.chunk_size.max(1) as usize];
+        let mut size = 0u64;
+        let mut index = 0u32;
+        loop {
+            let n = read_full(reader, &mut buffer).await?;
+            if n == 0 {
+                break;
+            }
+            let piece = &buffer[..n];
+            context.update(piece);
+            size += n as u64;
+
+            let stored = existing.iter().find(|c| c.chunk_index == index);
+            let intact = match stored {
+                Some(chunk) => matches!(
+                    self.blob_store
+                        .read_chunk(&attachment, chunk, encryption_key)
+                        .await,
+                    Ok(ref plaintext) if plaintext.as_slice() == piece
+                ),
+                None => false,
+            };
+            if !intact {
+                if stored.is_some() {
+                    self.repository.delete_chunk(attachment_id, index).await?;
+                }
+                let chunk = self
+                    .blob_store
+                    .write_chunk(&attachment, index, piece, encryption_key)
+                    .await?;
+                self.repository.create_chunk(&chunk).await?;
+            }
+
+            index += 1;
+            if n < buffer.len() {
+                break;
+            }
+        }
+
+        // Drop chunks left over from a longer source
+        for chunk in existing.iter().filter(|c| c.chunk_index >= index) {
+            let chunk_path = self.blob_store.storage_root.join(&chunk.storage_path);
+            if FileSystem::exists(&chunk_path).await {
+                FileSystem::remove_file(&chunk_path).await?;
+            }
+            self.repository
+                .delete_chunk(attachment_id, chunk.chunk_index)
+                .await?;
+        }
+
+        attachment.size = size;
+        attachment.content_hash = hex::encode(context.finish().as_ref());
+        attachment.set_chunks(index, attachment.chunk_size);
+        attachment.metadata[STREAM_METADATA_KEY] = serde_json::json!({ "complete": true });
+        self.repository.update(&attachment).await
+    }
+
+    /// Stream a chunked attachment into `writer`, verifying every chunk on the way
+    pub async fn get_stream<W: AsyncWrite + Unpin>(
+        &self,
+        attachment_id: &Uuid,
+        writer: &mut W,
+        decryption_key: Option<&[u8]>,
+    ) -> Result<u64> {
+        let attachment = self
+            .repository
+            .find_by_id(attachment_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Attachment not found"))?;
+        let chunks = self.repository.get_chunks(attachment_id).await?;
+
+        self.blob_store
+            .get_stream(&attachment, &chunks, writer, decryption_key)
+            .await
+    }
+
     /// Retrieve an attachment
     pub async fn retrieve(
         &self,
@@ -392,12 +1090,36 @@ impl AttachmentManager {
             .ok_or_else(|| anyhow::anyhow!("Attachment not found"))?;
 
         // Load chunks if needed
-        let chunks = if attachment.chunk_count > 1 {
+        let chunks = if uses_chunks(&attachment) {
             self.repository.get_chunks(attachment_id).await?
         } else {
             Vec::new()
         };
 
+        if is_content_addressed(&attachment) {
+            let blob = self
+                .repository
+                .find_blob(&attachment.content_hash)
+                .await?
+                .ok_or_else(|| {
+                    PersonaError::StorageError(format!(
+                        "Blob {} is missing",
+                        attachment.content_hash
+                    ))
+                })?;
+            let key = if decrypt { decryption_key } else { None };
+            return self.blob_store.read_blob(&blob, key).await;
+        }
+
+        if is_streamed(&attachment) {
+            let mut content = Vec::with_capacity(attachment.size as usize);
+            let key = if decrypt { decryption_key } else { None };
+            self.blob_store
+                .get_stream(&attachment, &chunks, &mut content, key)
+                .await?;
+            return Ok(content);
+        }
+
         // Retrieve file from blob store
         self.blob_store
             .retrieve_file(&attachment, &chunks, decrypt, decryption_key)
@@ -413,8 +1135,14 @@ impl AttachmentManager {
             .await?
             .ok_or_else(|| anyhow::anyhow!("Attachment not found"))?;
 
+        if is_content_addressed(&attachment) {
+            // The blob goes away with its last reference
+            self.repository.permanent_delete(attachment_id).await?;
+            return self.release_blob(&attachment.content_hash).await;
+        }
+
         // Load chunks if needed
-        let chunks = if attachment.chunk_count > 1 {
+        let chunks = if uses_chunks(&attachment) {
             self.repository.get_chunks(attachment_id).await?
         } else {
             Vec::new()
@@ -424,7 +1152,7 @@ impl AttachmentManager {
         self.blob_store.delete_file(&attachment, &chunks).await?;
 
         // Delete chunks metadata
-        if attachment.chunk_count > 1 {
+        if uses_chunks(&attachment) {
             self.repository.delete_chunks(attachment_id).await?;
         }
 
@@ -434,6 +1162,155 @@ impl AttachmentManager {
         Ok(())
     }
 
+    /// Drop one reference to a blob, deleting its data when it was the last
+    async fn release_blob(&self, content_hash: &str) -> Result<()> {
+        let blob = self.repository.find_blob(content_hash).await?;
+        if self.repository.release_blob(content_hash).await? == 0 {
+            if let Some(blob) = blob {
+                self.blob_store.delete_blob(&blob.storage_path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-encrypt the attachments kept in files of their own under `new_key`.
+    ///
+    /// Shared blobs are not rewritten, since only their content keys are wrapped by the
+    /// attachment key. Every attachment is read and verified under `old_key` first, so a missing
+    /// or damaged file fails the whole operation and nothing staged is kept.
+    pub async fn stage_rekey(&self, old_key: &[u8], new_key: &[u8]) -> Result<AttachmentRekey> {
+        let mut rekey = AttachmentRekey::default();
+        for attachment in self.repository.find_encrypted().await? {
+            if !has_own_encrypted_files(&attachment) {
+                continue;
+            }
+            if let Err(e) = self
+                .stage_attachment(&attachment, old_key, new_key, &mut rekey)
+                .await
+            {
+                rekey.discard().await;
+                return Err(e.context(format!(
+                    "Failed to re-encrypt attachment '{}'",
+                    attachment.filename
+                )));
+            }
+        }
+        Ok(rekey)
+    }
+
+    async fn stage_attachment(
+        &self,
+        attachment: &Attachment,
+        old_key: &[u8],
+        new_key: &[u8],
+        rekey: &mut AttachmentRekey,
+    ) -> Result<()> {
+        let plaintext = Zeroizing::new(self.retrieve(&attachment.id, true, Some(old_key)).await?);
+        let old_chunks = if uses_chunks(attachment) {
+            self.repository.get_chunks(&attachment.id).await?
+        } else {
+            Vec::new()
+        };
+        let cipher = cipher_for(new_key)?;
+        let encrypt = |data: &[u8]| {
+            cipher
+                .encrypt(data)
+                .map_err(|e| anyhow!("Encryption failed: {:?}", e))
+        };
+        let new_key_id = key_id(new_key);
+
+        let mut updated = attachment.clone();
+        updated.enable_encryption(new_key_id.clone());
+        let mut chunks = Vec::new();
+        if is_streamed(attachment) {
+            // Streamed chunks are encrypted one by one and the content hash is of the plaintext
+            let pieces = plaintext.chunks(attachment.chunk_size.max(1) as usize);
+            for (index, piece) in pieces.enumerate() {
+                let stored = encrypt(piece)?;
+                let path = self.blob_store.get_chunk_path(
+                    &attachment.credential_id,
+                    &attachment.id,
+                    index,
+                );
+                let staged_path = self
+                    .write_staged(&path, &stored, &new_key_id, rekey)
+                    .await?;
+                let mut chunk = AttachmentChunk::new(
+                    attachment.id,
+                    index as u32,
+                    stored.len() as u32,
+                    self.blob_store.calculate_hash(&stored),
+                    staged_path,
+                );
+                chunk.is_encrypted = true;
+                chunks.push(chunk);
+            }
+        } else {
+            let stored = encrypt(&plaintext)?;
+            updated.content_hash = self.blob_store.calculate_hash(&stored);
+            if uses_chunks(attachment) {
+                for (index, piece) in self.blob_store.chunk_data(&stored).iter().enumerate() {
+                    let path = self.blob_store.get_chunk_path(
+                        &attachment.credential_id,
+                        &attachment.id,
+                        index,
+                    );
+                    let staged_path = self.write_staged(&path, piece, &new_key_id, rekey).await?;
+                    updated.storage_path = staged_path.clone();
+                    chunks.push(AttachmentChunk::new(
+                        attachment.id,
+                        index as u32,
+                        piece.len() as u32,
+                        self.blob_store.calculate_hash(piece),
+                        staged_path,
+                    ));
+                }
+            } else {
+                let path = self.blob_store.get_file_path(
+                    &attachment.credential_id,
+                    &attachment.id,
+                    &attachment.filename,
+                );
+                updated.storage_path = self
+                    .write_staged(&path, &stored, &new_key_id, rekey)
+                    .await?;
+            }
+        }
+
+        if old_chunks.is_empty() {
+            rekey
+                .superseded
+                .push(self.blob_store.storage_root.join(&attachment.storage_path));
+        }
+        for chunk in &old_chunks {
+            rekey
+                .superseded
+                .push(self.blob_store.storage_root.join(&chunk.storage_path));
+        }
+        rekey.attachments.push((updated, chunks));
+        Ok(())
+    }
+
+    /// Write re-encrypted bytes beside `path`, tagged with the id of their key, and return the
+    /// new file's path relative to the storage root
+    async fn write_staged(
+        &self,
+        path: &Path,
+        stored: &[u8],
+        key_id: &str,
+        rekey: &mut AttachmentRekey,
+    ) -> Result<String> {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".{}", key_id));
+        let staged = path.with_file_name(name);
+        if let Some(parent) = staged.parent() {
+            FileSystem::create_dir_all(parent).await?;
+        }
+        rekey.staged.push(staged.clone());
+        FileSystem::write(&staged, stored).await?;
+        Ok(self.blob_store.relative_path(&staged))
+    }
+
     /// List attachments for a credential
     pub async fn list_for_credential(&self, credential_id: &Uuid) -> Result<Vec<Attachment>> {
         self.repository.find_by_credential(credential_id).await
@@ -622,4 +1499,259 @@ mod tests {
         let result = manager.retrieve(&attachment_id, false, None).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_stream_multi_chunk_blob_and_detect_missing_chunk() {
+        let temp_dir = tempdir().unwrap();
+        let db = create_test_db().await;
+        let credential_id = seed_identity_and_credential(&db).await;
+        let repo = AttachmentRepository::new(db);
+        let blob_store = BlobStore::with_chunk_size(temp_dir.path().join("storage"), 16);
+        let manager = AttachmentManager::new(repo, blob_store);
+        manager.init().await.unwrap();
+        let key = b"0123456789abcdef0123456789abcdef";
+        let content: Vec<u8> = (0..100u8).collect();
+
+        let attachment_id = manager
+            .put_stream(
+                &mut content.as_slice(),
+                credential_id,
+                "backup.bin",
+                Some(key),
+            )
+            .await
+            .unwrap();
+
+        let chunks = manager.repository.get_chunks(&attachment_id).await.unwrap();
+        assert_eq!(chunks.len(), 7);
+        assert!(chunks.iter().all(|c| c.is_encrypted));
+
+        // The recorded key id is derived from the key and holds none of its bytes
+        let attachment = manager
+            .repository
+            .find_by_id(&attachment_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let recorded = attachment.encryption_key_id.unwrap();
+        assert_eq!(recorded, key_id(key));
+        assert!(!hex::encode(key).contains(&recorded));
+
+        let mut restored = Vec::new();
+        let written = manager
+            .get_stream(&attachment_id, &mut restored, Some(key))
+            .await
+            .unwrap();
+        assert_eq!(written, 100);
+        assert_eq!(restored, content);
+
+        // Lose one chunk on disk: reading must fail rather than return short output
+        let lost = temp_dir
+            .path()
+            .join("storage")
+            .join(&chunks[3].storage_path);
+        FileSystem::remove_file(&lost).await.unwrap();
+        let err = manager
+            .get_stream(&attachment_id, &mut Vec::new(), Some(key))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Chunk 3"), "{}", err);
+
+        // Resuming from the same source rewrites only the missing chunk
+        manager
+            .resume_stream(&attachment_id, &mut content.as_slice(), Some(key))
+            .await
+            .unwrap();
+        let repaired = manager.repository.get_chunks(&attachment_id).await.unwrap();
+        assert_eq!(repaired[0].content_hash, chunks[0].content_hash);
+        assert_ne!(repaired[3].content_hash, chunks[3].content_hash);
+        let content_back = manager
+            .retrieve(&attachment_id, true, Some(key))
+            .await
+            .unwrap();
+        assert_eq!(content_back, content);
+
+        // A missing manifest row is detected as well
+        manager
+            .repository
+            .delete_chunk(&attachment_id, 5)
+            .await
+            .unwrap();
+        assert!(manager
+            .get_stream(&attachment_id, &mut Vec::new(), Some(key))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_identical_attachments_share_one_blob() {
+        let temp_dir = tempdir().unwrap();
+        let storage_dir = temp_dir.path().join("storage");
+        let first = temp_dir.path().join("recovery.pdf");
+        let second = temp_dir.path().join("recovery-copy.pdf");
+        FileSystem::write(&first, b"recovery codes").await.unwrap();
+        FileSystem::write(&second, b"recovery codes").await.unwrap();
+
+        let db = create_test_db().await;
+        let credential_a = seed_identity_and_credential(&db).await;
+        let credential_b = seed_identity_and_credential(&db).await;
+        let manager =
+            AttachmentManager::new(AttachmentRepository::new(db), BlobStore::new(&storage_dir));
+        manager.init().await.unwrap();
+        let key = b"0123456789abcdef0123456789abcdef";
+
+        let id_a = manager
+            .store(&first, credential_a, true, Some(key))
+            .await
+            .unwrap();
+        let id_b = manager
+            .store(&second, credential_b, true, Some(key))
+            .await
+            .unwrap();
+
+        let a = manager.repository.find_by_id(&id_a).await.unwrap().unwrap();
+        let b = manager.repository.find_by_id(&id_b).await.unwrap().unwrap();
+        assert_eq!(a.storage_path, b.storage_path);
+        assert_eq!(b.filename, "recovery-copy.pdf");
+        // Both the new blob and the shared reference record the derived key id
+        for attachment in [&a, &b] {
+            let recorded = attachment.encryption_key_id.as_deref().unwrap();
+            assert_eq!(recorded, key_id(key));
+            assert!(!hex::encode(key).contains(recorded));
+        }
+        let blob = manager
+            .repository
+            .find_blob(&a.content_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(blob.ref_count, 2);
+        let blob_files =
+            FileSystem::read_dir(storage_dir.join("blobs").join(&blob.content_hash[..2]))
+                .await
+                .unwrap();
+        assert_eq!(blob_files.len(), 1);
+
+        // The shared blob is encrypted at rest
+        let stored = FileSystem::read(storage_dir.join(&blob.storage_path))
+            .await
+            .unwrap();
+        assert_ne!(stored, b"recovery codes");
+        let content = manager.retrieve(&id_b, true, Some(key)).await.unwrap();
+        assert_eq!(content, b"recovery codes");
+    }
+
+    #[tokio::test]
+    async fn test_racing_stores_of_one_blob_keep_it_readable() {
+        let temp_dir = tempdir().unwrap();
+        let storage_dir = temp_dir.path().join("storage");
+        let db = create_test_db().await;
+        let manager =
+            AttachmentManager::new(AttachmentRepository::new(db), BlobStore::new(&storage_dir));
+        manager.init().await.unwrap();
+        let key = b"0123456789abcdef0123456789abcdef";
+        let content = b"recovery codes";
+        let content_hash = manager.blob_store.calculate_hash(content);
+
+        // Both stores missed the lookup and each staged the content under its own content key
+        let (first, second) = tokio::join!(
+            manager.create_blob(&content_hash, content, Some(key)),
+            manager.create_blob(&content_hash, content, Some(key)),
+        );
+        let (first, second) = (first.unwrap().unwrap(), second.unwrap().unwrap());
+        assert_eq!(first.wrapped_key, second.wrapped_key);
+
+        let blob = manager
+            .repository
+            .find_blob(&content_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(blob.ref_count, 2);
+        assert_eq!(
+            manager
+                .blob_store
+                .read_blob(&blob, Some(key))
+                .await
+                .unwrap(),
+            content
+        );
+        // Only the promoted blob is left; the loser's staged copy is gone
+        let blob_files = FileSystem::read_dir(storage_dir.join("blobs").join(&content_hash[..2]))
+            .await
+            .unwrap();
+        assert_eq!(blob_files, vec![storage_dir.join(&blob.storage_path)]);
+
+        // A racing store under another key takes no reference to the blob it cannot open
+        let other_key = b"fedcba9876543210fedcba9876543210";
+        assert!(manager
+            .create_blob(&content_hash, content, Some(other_key))
+            .await
+            .unwrap()
+            .is_none());
+        let blob = manager
+            .repository
+            .find_blob(&content_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(blob.ref_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_shared_blob_removed_with_last_reference() {
+        let temp_dir = tempdir().unwrap();
+        let storage_dir = temp_dir.path().join("storage");
+        let test_file = temp_dir.path().join("shared.txt");
+        FileSystem::write(&test_file, b"shared content")
+            .await
+            .unwrap();
+
+        let db = create_test_db().await;
+        let credential_id = seed_identity_and_credential(&db).await;
+        let manager =
+            AttachmentManager::new(AttachmentRepository::new(db), BlobStore::new(&storage_dir));
+        manager.init().await.unwrap();
+
+        let id_a = manager
+            .store(&test_file, credential_id, false, None)
+            .await
+            .unwrap();
+        let id_b = manager
+            .store(&test_file, credential_id, false, None)
+            .await
+            .unwrap();
+        let content_hash = manager
+            .repository
+            .find_by_id(&id_a)
+            .await
+            .unwrap()
+            .unwrap()
+            .content_hash;
+        let blob = manager
+            .repository
+            .find_blob(&content_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        let blob_path = storage_dir.join(&blob.storage_path);
+
+        // Deleting one reference keeps the data for the other
+        manager.delete(&id_a).await.unwrap();
+        let blob = manager.repository.find_blob(&content_hash).await.unwrap();
+        assert_eq!(blob.unwrap().ref_count, 1);
+        assert!(FileSystem::exists(&blob_path).await);
+        let content = manager.retrieve(&id_b, false, None).await.unwrap();
+        assert_eq!(content, b"shared content");
+
+        // Deleting the last reference removes the blob
+        manager.delete(&id_b).await.unwrap();
+        assert!(manager
+            .repository
+            .find_blob(&content_hash)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(!FileSystem::exists(&blob_path).await);
+    }
 }
@@ -283,6 +283,44 @@ impl ChangeHistoryRepository {
         Ok(stats)
     }
 
+    /// Drop the stored previous state from all but the newest `keep` entries of an entity
+    /// recorded with `reason`. The entries themselves stay in the history.
+    pub async fn redact_previous_states(
+        &self,
+        entity_type: EntityType,
+        entity_id: &Uuid,
+        reason: &str,
+        keep: usize,
+    ) -> Result<usize> {
+        let query = r#"
+            UPDATE change_history SET previous_state = NULL
+            WHERE entity_type = ? AND entity_id = ? AND reason = ? AND previous_state IS NOT NULL
+              AND id NOT IN (
+                  SELECT id FROM change_history
+                  WHERE entity_type = ? AND entity_id = ? AND reason = ?
+                    AND previous_state IS NOT NULL
+                  ORDER BY version DESC
+                  LIMIT ?
+              )
+        "#;
+
+        let result = sqlx::query(query)
+            .bind(entity_type.to_string())
+            .bind(entity_id.to_string())
+            .bind(reason)
+            .bind(entity_type.to_string())
+            .bind(entity_id.to_string())
+            .bind(reason)
+            .bind(keep as i64)
+            .execute(self.db.pool())
+            .await
+            .map_err(|e| {
+                PersonaError::Database(format!("Failed to redact change history: {}", e))
+            })?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
     /// Delete old history entries (for cleanup/GDPR)
     pub async fn delete_before_date(&self, before: chrono::DateTime<chrono::Utc>) -> Result<usize> {
         let query = "DELETE FROM change_history WHERE timestamp < ?";
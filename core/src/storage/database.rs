@@ -1,6 +1,175 @@
+use crate::storage::migrations::{self, MigrationStatus};
+use crate::storage::permissions::create_private_file;
 use crate::{PersonaError, Result};
+use serde::Serialize;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions};
 use sqlx::{Pool, Sqlite, SqlitePool};
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// `PRAGMA auto_vacuum` value that allows `PRAGMA incremental_vacuum`
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+
+/// FTS5 table mirroring the searchable credential columns
+const CREDENTIAL_SEARCH_TABLE: &str = "credentials_fts";
+
+/// Created outside the versioned migrations because FTS5 is optional in SQLite builds.
+/// The trigram tokenizer keeps substring matching, as the LIKE fallback does.
+const CREDENTIAL_SEARCH_SCHEMA: [&str; 4] = [
+    r#"
+    CREATE VIRTUAL TABLE credentials_fts USING fts5(
+        id UNINDEXED, name, username, url, notes, tags, tokenize = 'trigram'
+    )
+    "#,
+    r#"
+    CREATE TRIGGER credentials_fts_insert AFTER INSERT ON credentials BEGIN
+        INSERT INTO credentials_fts (id, name, username, url, notes, tags)
+        VALUES (new.id, new.name, new.username, new.url, new.notes, new.tags);
+    END
+    "#,
+    r#"
+    CREATE TRIGGER credentials_fts_delete AFTER DELETE ON credentials BEGIN
+        DELETE FROM credentials_fts WHERE id = old.id;
+    END
+    "#,
+    r#"
+    CREATE TRIGGER credentials_fts_update
+    AFTER UPDATE OF id, name, username, url, notes, tags ON credentials BEGIN
+        DELETE FROM credentials_fts WHERE id = old.id;
+        INSERT INTO credentials_fts (id, name, username, url, notes, tags)
+        VALUES (new.id, new.name, new.username, new.url, new.notes, new.tags);
+    END
+    "#,
+];
+
+/// Drops the FTS5 credential index and its triggers, so it can be rebuilt from scratch
+const CREDENTIAL_SEARCH_TEARDOWN: [&str; 4] = [
+    "DROP TRIGGER IF EXISTS credentials_fts_insert",
+    "DROP TRIGGER IF EXISTS credentials_fts_delete",
+    "DROP TRIGGER IF EXISTS credentials_fts_update",
+    "DROP TABLE IF EXISTS credentials_fts",
+];
+
+/// Credentials copied into the search index per statement by [`Database::rebuild_search_index`]
+pub const SEARCH_INDEX_BATCH_SIZE: u64 = 500;
+
+/// First bytes of every plaintext SQLite database; SQLCipher files start with a random salt
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Connection pool settings for a database file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolOptions {
+    /// Most connections the pool keeps open at once
+    pub max_connections: u32,
+    /// How long a statement waits for another connection's lock before failing as busy
+    pub busy_timeout: Duration,
+    /// Write-ahead logging, so reads keep going while another connection writes
+    pub wal: bool,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self {
+            max_connections: 8,
+            busy_timeout: Duration::from_secs(5),
+            wal: true,
+        }
+    }
+}
+
+impl PoolOptions {
+    fn connect_options(&self, options: SqliteConnectOptions) -> SqliteConnectOptions {
+        let options = options.busy_timeout(self.busy_timeout);
+        if self.wal {
+            options.journal_mode(SqliteJournalMode::Wal)
+        } else {
+            options
+        }
+    }
+
+    fn pool_options(&self) -> SqlitePoolOptions {
+        SqlitePoolOptions::new().max_connections(self.max_connections.max(1))
+    }
+}
+
+/// Page usage of a database file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SpaceUsage {
+    pub page_size: i64,
+    pub page_count: i64,
+    /// Pages on the freelist, left behind by deletes
+    pub free_pages: i64,
+}
+
+impl SpaceUsage {
+    /// Size of the database in bytes
+    pub fn total_bytes(&self) -> i64 {
+        self.page_size * self.page_count
+    }
+
+    /// Bytes held by free pages
+    pub fn free_bytes(&self) -> i64 {
+        self.page_size * self.free_pages
+    }
+}
+
+/// Outcome of [`Database::compact`]
+#[derive(Debug, Clone, Serialize)]
+pub struct VacuumReport {
+    pub incremental: bool,
+    pub before: SpaceUsage,
+    pub after: SpaceUsage,
+    /// Problems reported by `PRAGMA integrity_check`; empty when the database is sound
+    pub integrity_errors: Vec<String>,
+}
+
+impl VacuumReport {
+    /// Bytes the database file shrank by
+    pub fn reclaimed_bytes(&self) -> i64 {
+        (self.before.total_bytes() - self.after.total_bytes()).max(0)
+    }
+}
+
+/// Outcome of [`Database::rebuild_search_index`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ReindexReport {
+    /// Credentials in the vault
+    pub credentials: u64,
+    /// Rows in the rebuilt index; always equal to `credentials`
+    pub indexed: u64,
+}
+
+/// `value` as a quoted SQL string literal, for pragmas that cannot take bound parameters
+#[cfg(feature = "sqlcipher")]
+fn sql_string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// SQLCipher reports a wrong key as a corrupt or non-database file
+#[cfg(feature = "sqlcipher")]
+fn key_error(path: &Path, error: sqlx::Error) -> anyhow::Error {
+    match &error {
+        sqlx::Error::Database(db) if db.message().contains("not a database") => {
+            PersonaError::AuthenticationFailed(format!(
+                "Cannot decrypt {}: wrong master password, or not an encrypted vault",
+                path.display()
+            ))
+            .into()
+        }
+        _ => PersonaError::Database(error.to_string()).into(),
+    }
+}
+
+/// Connect options for the file at `path`, creating it when missing.
+///
+/// Without `mode=rwc`, sqlx/sqlite will default to read-write and fail with "unable to open
+/// database file" if the DB file is missing.
+fn file_connect_options(path: &Path) -> Result<SqliteConnectOptions> {
+    SqliteConnectOptions::from_str(&format!("sqlite:{}?mode=rwc", path.display()))
+        .map_err(|e| PersonaError::Database(e.to_string()).into())
+}
 
 /// Database wrapper for SQLite operations
 #[derive(Clone)]
@@ -18,15 +187,146 @@ impl Database {
         Ok(Self { pool })
     }
 
-    /// Create a database from file path
+    /// Create a database from file path, with the default [`PoolOptions`]
+    ///
+    /// A missing file is created owner-only (`0600` on Unix) before SQLite opens it; SQLite
+    /// gives its journal and WAL files the same permissions.
     pub async fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::with_pool_options(path, PoolOptions::default()).await
+    }
+
+    /// Create a database from file path with a connection pool configured by `options`.
+    ///
+    /// Every connection gets the busy timeout, and the file is switched to WAL mode when
+    /// `options.wal` is set. WAL is a property of the file, so it stays on for later opens.
+    pub async fn with_pool_options<P: AsRef<Path>>(path: P, options: PoolOptions) -> Result<Self> {
         let path = path.as_ref();
-        // Ensure SQLite creates the DB file when it does not exist.
-        //
-        // Without `mode=rwc`, sqlx/sqlite will default to read-write and fail
-        // with "unable to open database file" if the DB file is missing.
-        let database_url = format!("sqlite:{}?mode=rwc", path.display());
-        Self::new(&database_url).await
+        create_private_file(path)?;
+        let pool = options
+            .pool_options()
+            .connect_with(options.connect_options(file_connect_options(path)?))
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(Self { pool })
+    }
+
+    /// Open a database encrypted at rest with SQLCipher, creating it if missing.
+    ///
+    /// `passphrase` is the master password. SQLCipher derives the page key from it with
+    /// PBKDF2-HMAC-SHA512 (256,000 iterations) and a random salt kept in the file's first 16
+    /// bytes, so no key material is stored beside the database. A wrong passphrase, or a file
+    /// that is not an encrypted vault, fails with `AuthenticationFailed`. The pool uses the
+    /// default [`PoolOptions`].
+    #[cfg(feature = "sqlcipher")]
+    pub async fn from_file_encrypted<P: AsRef<Path>>(
+        path: P,
+        passphrase: &crate::SecretString,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        create_private_file(path)?;
+        let options = PoolOptions::default();
+        // sqlx sends `key` before any other pragma, so journal_mode is set on the decrypted file
+        let connect = options.connect_options(
+            file_connect_options(path)?.pragma("key", sql_string_literal(passphrase.expose())),
+        );
+        let pool = options
+            .pool_options()
+            .connect_with(connect)
+            .await
+            .map_err(|e| key_error(path, e))?;
+        // The key is only checked when the first page is read
+        sqlx::query("SELECT count(*) FROM sqlite_master")
+            .execute(&pool)
+            .await
+            .map_err(|e| key_error(path, e))?;
+        Ok(Self { pool })
+    }
+
+    /// Whether the file at `path` is not a plaintext SQLite database, i.e. encrypted at rest.
+    ///
+    /// Missing and empty files are not encrypted; they become plaintext vaults when opened with
+    /// [`Self::from_file`].
+    pub fn is_encrypted_file<P: AsRef<Path>>(path: P) -> Result<bool> {
+        use std::io::Read;
+
+        let path = path.as_ref();
+        let mut file = match std::fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(PersonaError::Io(format!("{}: {}", path.display(), e)).into()),
+        };
+        let mut header = Vec::with_capacity(SQLITE_HEADER.len());
+        file.by_ref()
+            .take(SQLITE_HEADER.len() as u64)
+            .read_to_end(&mut header)
+            .map_err(PersonaError::from)?;
+        Ok(!header.is_empty() && header != SQLITE_HEADER)
+    }
+
+    /// Write an encrypted copy of this (plaintext) database to `path` with `sqlcipher_export`.
+    ///
+    /// `path` must not exist yet. The copy holds every table, index, trigger and the schema
+    /// version, so it opens with [`Self::from_file_encrypted`] without re-running migrations.
+    #[cfg(feature = "sqlcipher")]
+    pub async fn export_encrypted(
+        &self,
+        path: &Path,
+        passphrase: &crate::SecretString,
+    ) -> Result<()> {
+        if path.exists() {
+            return Err(
+                PersonaError::InvalidInput(format!("{} already exists", path.display())).into(),
+            );
+        }
+        // ATTACH and the export must run on the same connection
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        sqlx::query("ATTACH DATABASE ? AS encrypted KEY ?")
+            .bind(path.to_string_lossy().into_owned())
+            .bind(passphrase.expose())
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        let exported = sqlx::query("SELECT sqlcipher_export('encrypted')")
+            .execute(&mut *conn)
+            .await;
+        let detached = sqlx::query("DETACH DATABASE encrypted")
+            .execute(&mut *conn)
+            .await;
+        exported.map_err(|e| PersonaError::Database(e.to_string()))?;
+        detached.map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Re-encrypt the SQLCipher database at `path` under `new_passphrase`.
+    ///
+    /// Runs `PRAGMA rekey` on a dedicated connection, which rewrites every page in one
+    /// transaction. Close other handles to the file first: their connections keep the old key.
+    #[cfg(feature = "sqlcipher")]
+    pub async fn rekey_file(
+        path: &Path,
+        passphrase: &crate::SecretString,
+        new_passphrase: &crate::SecretString,
+    ) -> Result<()> {
+        let db = Self::from_file_encrypted(path, passphrase).await?;
+        let mut conn = db
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        let rekeyed = sqlx::query(&format!(
+            "PRAGMA rekey = {}",
+            sql_string_literal(new_passphrase.expose())
+        ))
+        .execute(&mut *conn)
+        .await;
+        drop(conn);
+        db.pool.close().await;
+        rekeyed.map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(())
     }
 
     /// Create an in-memory database
@@ -34,15 +334,281 @@ impl Database {
         Self::new("sqlite::memory:").await
     }
 
-    /// Run database migrations
+    /// Apply pending schema migrations, oldest first, each in its own transaction.
+    ///
+    /// Safe to call on every open: applied migrations are recorded in `schema_migrations` and
+    /// never run again. Refuses to touch a vault migrated by a newer build.
     pub async fn migrate(&self) -> Result<()> {
-        sqlx::migrate!("./migrations")
-            .run(&self.pool)
+        migrations::run(self).await?;
+        if let Err(e) = self.ensure_search_index().await {
+            tracing::warn!("Full-text search unavailable, falling back to LIKE: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Build the FTS5 credential index if this SQLite supports it, rebuilding it when it is
+    /// missing or its row count has drifted from the credentials table
+    async fn ensure_search_index(&self) -> Result<()> {
+        if self.has_search_index().await? {
+            let in_sync: bool = sqlx::query_scalar(
+                "SELECT (SELECT COUNT(*) FROM credentials_fts) = (SELECT COUNT(*) FROM credentials)",
+            )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+            if in_sync {
+                return Ok(());
+            }
+            tracing::warn!("Search index is out of date; rebuilding it");
+        }
+        self.rebuild_search_index(SEARCH_INDEX_BATCH_SIZE, &AtomicBool::new(false), |_, _| {})
+            .await?;
+        Ok(())
+    }
+
+    /// Drop and rebuild the FTS5 credential index, `batch_size` credentials at a time.
+    ///
+    /// Runs in one transaction, so readers keep the old index until the new one is complete.
+    /// `progress` is called with (indexed, total) after every batch. Once `cancel` is set the
+    /// rebuild stops before the next batch and rolls back, returning `None`. Fails without
+    /// committing unless the new index holds exactly one row per credential.
+    pub async fn rebuild_search_index(
+        &self,
+        batch_size: u64,
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(u64, u64),
+    ) -> Result<Option<ReindexReport>> {
+        let batch_size = batch_size.max(1);
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        for statement in CREDENTIAL_SEARCH_TEARDOWN
+            .iter()
+            .chain(CREDENTIAL_SEARCH_SCHEMA.iter())
+        {
+            sqlx::query(statement)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| PersonaError::Database(e.to_string()))?;
+        }
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM credentials")
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        let total = total as u64;
+        let mut indexed = 0u64;
+        while indexed < total {
+            if cancel.load(Ordering::Relaxed) {
+                tx.rollback()
+                    .await
+                    .map_err(|e| PersonaError::Database(e.to_string()))?;
+                return Ok(None);
+            }
+            let inserted = sqlx::query(
+                "INSERT INTO credentials_fts (id, name, username, url, notes, tags) SELECT id, name, username, url, notes, tags FROM credentials ORDER BY rowid LIMIT ? OFFSET ?",
+            )
+            .bind(batch_size as i64)
+            .bind(indexed as i64)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?
+            .rows_affected();
+            if inserted == 0 {
+                break;
+            }
+            indexed += inserted;
+            progress(indexed, total);
+        }
+
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM credentials_fts")
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        if rows as u64 != total {
+            tx.rollback()
+                .await
+                .map_err(|e| PersonaError::Database(e.to_string()))?;
+            return Err(PersonaError::Database(format!(
+                "Rebuilt search index has {} rows for {} credentials",
+                rows, total
+            ))
+            .into());
+        }
+        tx.commit()
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(Some(ReindexReport {
+            credentials: total,
+            indexed: rows as u64,
+        }))
+    }
+
+    /// Whether the FTS5 credential index exists
+    pub async fn has_search_index(&self) -> Result<bool> {
+        let found: Option<i64> =
+            sqlx::query_scalar("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?")
+                .bind(CREDENTIAL_SEARCH_TABLE)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(found.is_some())
+    }
+
+    /// Applied migrations compared with the ones bundled with this build
+    pub async fn migration_status(&self) -> Result<MigrationStatus> {
+        migrations::status(self).await
+    }
+
+    /// Newest migration applied to this database, or `None` before the first migration
+    pub async fn schema_version(&self) -> Result<Option<i64>> {
+        Ok(self.migration_status().await?.current)
+    }
+
+    /// Newest migration bundled with this build
+    pub fn latest_schema_version() -> i64 {
+        migrations::latest_version()
+    }
+
+    /// Random id assigned to this vault when it was created; stable across reopens and copies
+    pub async fn installation_id(&self) -> Result<String> {
+        sqlx::query_scalar("SELECT installation_id FROM vault_info WHERE id = 1")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()).into())
+    }
+
+    /// Write a consistent copy of the database to `path`, which must not exist yet
+    pub async fn backup_to(&self, path: &Path) -> Result<()> {
+        sqlx::query("VACUUM INTO ?")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Current page usage
+    pub async fn space_usage(&self) -> Result<SpaceUsage> {
+        Ok(SpaceUsage {
+            page_size: self.pragma_i64("page_size").await?,
+            page_count: self.pragma_i64("page_count").await?,
+            free_pages: self.pragma_i64("freelist_count").await?,
+        })
+    }
+
+    /// Problems found by `PRAGMA integrity_check`; empty when the database is sound
+    pub async fn integrity_check(&self) -> Result<Vec<String>> {
+        let rows: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(rows.into_iter().filter(|row| row != "ok").collect())
+    }
+
+    /// Whether free pages can be released with `PRAGMA incremental_vacuum`
+    pub async fn incremental_vacuum_enabled(&self) -> Result<bool> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        // A connection only rereads the header (and so the mode) when it reads the database;
+        // without this, pooled connections can report the mode from before a VACUUM
+        sqlx::query("SELECT COUNT(*) FROM sqlite_master")
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        let mode: i64 = sqlx::query_scalar("PRAGMA auto_vacuum")
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(mode == AUTO_VACUUM_INCREMENTAL)
+    }
+
+    /// Reclaim free pages and integrity-check the result.
+    ///
+    /// A full vacuum rebuilds the file and switches it to incremental auto-vacuum, so later
+    /// incremental runs (and [`Self::reclaim_free_pages`]) can release pages without a rebuild.
+    /// An incremental vacuum requires that mode and fails until one full vacuum has run.
+    pub async fn compact(&self, incremental: bool) -> Result<VacuumReport> {
+        let before = self.space_usage().await?;
+        if incremental {
+            if !self.incremental_vacuum_enabled().await? {
+                return Err(PersonaError::InvalidInput(
+                    "Incremental vacuum is not enabled for this database; run a full vacuum once to enable it"
+                        .to_string(),
+                )
+                .into());
+            }
+            self.incremental_vacuum().await?;
+        } else {
+            // auto_vacuum only changes during a VACUUM on the same connection
+            let mut conn = self
+                .pool
+                .acquire()
+                .await
+                .map_err(|e| PersonaError::Database(e.to_string()))?;
+            for statement in ["PRAGMA auto_vacuum = INCREMENTAL", "VACUUM"] {
+                sqlx::query(statement)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| PersonaError::Database(e.to_string()))?;
+            }
+            drop(conn);
+            self.checkpoint().await?;
+        }
+
+        Ok(VacuumReport {
+            incremental,
+            before,
+            after: self.space_usage().await?,
+            integrity_errors: self.integrity_check().await?,
+        })
+    }
+
+    /// Release free pages once at least `threshold` have built up.
+    ///
+    /// Cheap enough to call after large deletes; does nothing unless incremental auto-vacuum
+    /// is enabled. Returns the number of pages released.
+    pub async fn reclaim_free_pages(&self, threshold: i64) -> Result<i64> {
+        let free_pages = self.pragma_i64("freelist_count").await?;
+        if free_pages < threshold || !self.incremental_vacuum_enabled().await? {
+            return Ok(0);
+        }
+        self.incremental_vacuum().await?;
+        Ok(free_pages - self.pragma_i64("freelist_count").await?)
+    }
+
+    async fn incremental_vacuum(&self) -> Result<()> {
+        // The pragma frees pages as its result rows are stepped through
+        sqlx::query("PRAGMA incremental_vacuum")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        self.checkpoint().await
+    }
+
+    /// Copy committed pages from the write-ahead log into the database file and truncate the
+    /// log, so the file on disk is complete and sized to its contents. Does nothing outside
+    /// WAL mode.
+    pub async fn checkpoint(&self) -> Result<()> {
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .fetch_all(&self.pool)
             .await
             .map_err(|e| PersonaError::Database(e.to_string()))?;
         Ok(())
     }
 
+    async fn pragma_i64(&self, name: &str) -> Result<i64> {
+        sqlx::query_scalar(&format!("PRAGMA {}", name))
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()).into())
+    }
+
     /// Get a reference to the connection pool
     pub fn pool(&self) -> &Pool<Sqlite> {
         &self.pool
@@ -88,6 +654,20 @@ impl Database {
         Ok(row)
     }
 
+    /// Begin a transaction guard.
+    ///
+    /// Run statements on it through `&mut *tx` and make them permanent with
+    /// [`Transaction::commit`]. Dropping the guard uncommitted, as an early `?` return does,
+    /// rolls every statement back.
+    pub async fn begin(&self) -> Result<Transaction<'static>> {
+        let tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(Transaction::new(tx))
+    }
+
     /// Begin a database transaction
     pub async fn begin_transaction(&self) -> Result<sqlx::Transaction<'_, Sqlite>> {
         Ok(self
@@ -103,11 +683,25 @@ impl Database {
     }
 }
 
-/// Database transaction helper
+/// Database transaction helper; rolled back when dropped without [`Self::commit`]
 pub struct Transaction<'a> {
     tx: sqlx::Transaction<'a, Sqlite>,
 }
 
+impl std::ops::Deref for Transaction<'_> {
+    type Target = SqliteConnection;
+
+    fn deref(&self) -> &SqliteConnection {
+        &self.tx
+    }
+}
+
+impl std::ops::DerefMut for Transaction<'_> {
+    fn deref_mut(&mut self) -> &mut SqliteConnection {
+        &mut self.tx
+    }
+}
+
 impl<'a> Transaction<'a> {
     /// Create a new transaction wrapper
     pub fn new(tx: sqlx::Transaction<'a, Sqlite>) -> Self {
@@ -173,4 +767,384 @@ mod tests {
         let retrieved_name: String = row.get("name");
         assert_eq!(retrieved_name, "test_name");
     }
+
+    #[tokio::test]
+    async fn test_transaction_rolls_back_unless_committed() {
+        let db = Database::in_memory().await.unwrap();
+        db.execute("CREATE TABLE test (id INTEGER PRIMARY KEY, name TEXT)")
+            .await
+            .unwrap();
+
+        {
+            let mut tx = db.begin().await.unwrap();
+            sqlx::query("INSERT INTO test (name) VALUES ('dropped')")
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+        }
+        let mut tx = db.begin().await.unwrap();
+        tx.execute("INSERT INTO test (name) VALUES ('kept')")
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        let names: Vec<String> = sqlx::query_scalar("SELECT name FROM test")
+            .fetch_all(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(names, vec!["kept".to_string()]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_from_file_creates_owner_only_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("identities.db");
+        let db = Database::from_file(&path).await.unwrap();
+        db.migrate().await.unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+    }
+
+    #[tokio::test]
+    async fn test_schema_version_tracks_migrations() {
+        let db = Database::in_memory().await.unwrap();
+        assert_eq!(db.schema_version().await.unwrap(), None);
+
+        db.migrate().await.unwrap();
+        assert_eq!(
+            db.schema_version().await.unwrap(),
+            Some(Database::latest_schema_version())
+        );
+        assert!(Database::latest_schema_version() >= 14);
+    }
+
+    #[tokio::test]
+    async fn test_vacuum_shrinks_file_after_bulk_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("identities.db");
+        let db = Database::from_file(&path).await.unwrap();
+        db.execute("CREATE TABLE blobs (id INTEGER PRIMARY KEY, data BLOB)")
+            .await
+            .unwrap();
+        for _ in 0..200 {
+            sqlx::query("INSERT INTO blobs (data) VALUES (zeroblob(8192))")
+                .execute(db.pool())
+                .await
+                .unwrap();
+        }
+        db.execute("DELETE FROM blobs").await.unwrap();
+        db.checkpoint().await.unwrap();
+
+        let bloated = std::fs::metadata(&path).unwrap().len();
+        assert!(db.space_usage().await.unwrap().free_pages > 0);
+        assert!(db.compact(true).await.is_err());
+
+        let report = db.compact(false).await.unwrap();
+        assert!(report.integrity_errors.is_empty());
+        assert!(report.reclaimed_bytes() > 1_000_000);
+        assert_eq!(report.after.free_pages, 0);
+        assert!(std::fs::metadata(&path).unwrap().len() < bloated);
+
+        // The full vacuum switched the file to incremental mode
+        assert!(db.incremental_vacuum_enabled().await.unwrap());
+        for _ in 0..50 {
+            sqlx::query("INSERT INTO blobs (data) VALUES (zeroblob(8192))")
+                .execute(db.pool())
+                .await
+                .unwrap();
+        }
+        db.execute("DELETE FROM blobs").await.unwrap();
+        assert_eq!(db.reclaim_free_pages(i64::MAX).await.unwrap(), 0);
+        assert!(db.reclaim_free_pages(1).await.unwrap() > 0);
+        assert_eq!(db.space_usage().await.unwrap().free_pages, 0);
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_restores_an_emptied_or_corrupted_search_index() {
+        use crate::models::{Credential, CredentialType, Identity, IdentityType, SecurityLevel};
+        use crate::storage::{CredentialRepository, IdentityRepository, Repository};
+
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        if !db.has_search_index().await.unwrap() {
+            return; // SQLite built without FTS5
+        }
+        let identity = IdentityRepository::new(db.clone())
+            .create(&Identity::new("Search".to_string(), IdentityType::Personal))
+            .await
+            .unwrap();
+        let credentials = CredentialRepository::new(db.clone());
+        for i in 0..7 {
+            let credential = Credential::new(
+                identity.id,
+                format!("Site{i:03}"),
+                CredentialType::Password,
+                SecurityLevel::Medium,
+                vec![0u8; 16],
+                None,
+            );
+            credentials.create(&credential).await.unwrap();
+        }
+        let index_rows = || async {
+            let row = db
+                .fetch_one("SELECT COUNT(*) AS n FROM credentials_fts")
+                .await
+                .unwrap();
+            row.get::<i64, _>("n")
+        };
+
+        // Emptied index
+        db.execute("DELETE FROM credentials_fts").await.unwrap();
+        let mut batches = Vec::new();
+        let report = db
+            .rebuild_search_index(3, &AtomicBool::new(false), |done, total| {
+                batches.push((done, total))
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((report.credentials, report.indexed), (7, 7));
+        assert_eq!(batches, vec![(3, 7), (6, 7), (7, 7)]);
+        assert_eq!(index_rows().await, 7);
+
+        // Stale rows for credentials that no longer exist
+        db.execute("INSERT INTO credentials_fts (id, name) VALUES ('gone', 'Deleted site')")
+            .await
+            .unwrap();
+        db.rebuild_search_index(SEARCH_INDEX_BATCH_SIZE, &AtomicBool::new(false), |_, _| {})
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(index_rows().await, 7);
+
+        // Index table gone while its triggers remain
+        db.execute("DROP TABLE credentials_fts").await.unwrap();
+        db.rebuild_search_index(SEARCH_INDEX_BATCH_SIZE, &AtomicBool::new(false), |_, _| {})
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(index_rows().await, 7);
+        let found = credentials.search_full_text("site004").await.unwrap();
+        assert_eq!(found.len(), 1);
+
+        // A cancelled rebuild rolls back and leaves the previous index in place
+        db.execute("DELETE FROM credentials_fts WHERE rowid > 2")
+            .await
+            .unwrap();
+        assert!(db
+            .rebuild_search_index(3, &AtomicBool::new(true), |_, _| {})
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(index_rows().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_opening_rebuilds_a_partial_search_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("identities.db");
+        let db = Database::from_file(&path).await.unwrap();
+        db.migrate().await.unwrap();
+        if !db.has_search_index().await.unwrap() {
+            return; // SQLite built without FTS5
+        }
+        let identity_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO identities (id, name, identity_type, tags, attributes, created_at, updated_at, is_active) VALUES (?, 'Search', 'personal', '[]', '{}', datetime(), datetime(), 1)",
+        )
+        .bind(&identity_id)
+        .execute(db.pool())
+        .await
+        .unwrap();
+        for name in ["GitHub", "GitLab"] {
+            sqlx::query(
+                "INSERT INTO credentials (id, identity_id, name, credential_type, security_level, encrypted_data, tags, metadata, created_at, updated_at, is_active, is_favorite) VALUES (?, ?, ?, 'password', 'medium', x'00', '[]', '{}', datetime(), datetime(), 1, 0)",
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&identity_id)
+            .bind(name)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+        db.execute("DELETE FROM credentials_fts WHERE name = 'GitLab'")
+            .await
+            .unwrap();
+        db.pool.close().await;
+
+        let reopened = Database::from_file(&path).await.unwrap();
+        reopened.migrate().await.unwrap();
+        let indexed = reopened
+            .fetch_one("SELECT COUNT(*) AS n FROM credentials_fts WHERE name = 'GitLab'")
+            .await
+            .unwrap();
+        assert_eq!(indexed.get::<i64, _>("n"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_file_databases_use_wal_and_the_configured_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("identities.db");
+        let options = PoolOptions {
+            max_connections: 3,
+            ..PoolOptions::default()
+        };
+        let db = Database::with_pool_options(&path, options).await.unwrap();
+        let mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(mode, "wal");
+        assert_eq!(db.pool().options().get_max_connections(), 3);
+        db.close().await;
+
+        let rollback = PoolOptions {
+            wal: false,
+            ..PoolOptions::default()
+        };
+        let other = dir.path().join("rollback.db");
+        let db = Database::with_pool_options(&other, rollback).await.unwrap();
+        let mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(mode, "delete");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_reads_and_writes_share_one_pool() {
+        const WRITERS: usize = 8;
+        const READERS: usize = 8;
+        const ROWS: usize = 50;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::from_file(dir.path().join("identities.db"))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db.execute("CREATE TABLE stress (id INTEGER PRIMARY KEY, writer INTEGER, data TEXT)")
+            .await
+            .unwrap();
+
+        let mut tasks = Vec::new();
+        for writer in 0..WRITERS {
+            let db = db.clone();
+            tasks.push(tokio::spawn(async move {
+                for row in 0..ROWS {
+                    let mut tx = db.pool().begin().await?;
+                    sqlx::query("INSERT INTO stress (writer, data) VALUES (?, ?)")
+                        .bind(writer as i64)
+                        .bind(format!("row {}", row))
+                        .execute(&mut *tx)
+                        .await?;
+                    tx.commit().await?;
+                }
+                Ok::<(), sqlx::Error>(())
+            }));
+        }
+        for _ in 0..READERS {
+            let db = db.clone();
+            tasks.push(tokio::spawn(async move {
+                for _ in 0..ROWS {
+                    let _: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM stress")
+                        .fetch_one(db.pool())
+                        .await?;
+                    let _: Option<String> =
+                        sqlx::query_scalar("SELECT installation_id FROM vault_info")
+                            .fetch_optional(db.pool())
+                            .await?;
+                }
+                Ok::<(), sqlx::Error>(())
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM stress")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(rows, (WRITERS * ROWS) as i64);
+        assert!(db.integrity_check().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_files_are_not_reported_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("identities.db");
+        assert!(!Database::is_encrypted_file(&path).unwrap());
+
+        let db = Database::from_file(&path).await.unwrap();
+        db.migrate().await.unwrap();
+        assert!(!Database::is_encrypted_file(&path).unwrap());
+
+        std::fs::write(&path, [0x5a; 64]).unwrap();
+        assert!(Database::is_encrypted_file(&path).unwrap());
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[tokio::test]
+    async fn test_encrypted_export_hides_metadata_and_needs_the_passphrase() {
+        use crate::SecretString;
+
+        let dir = tempfile::tempdir().unwrap();
+        let plain_path = dir.path().join("plain.db");
+        let db = Database::from_file(&plain_path).await.unwrap();
+        db.migrate().await.unwrap();
+        sqlx::query("UPDATE vault_info SET installation_id = 'findable-marker' WHERE id = 1")
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        let passphrase = SecretString::from("it's secret");
+        let encrypted_path = dir.path().join("encrypted.db");
+        db.export_encrypted(&encrypted_path, &passphrase)
+            .await
+            .unwrap();
+        assert!(Database::is_encrypted_file(&encrypted_path).unwrap());
+        let bytes = std::fs::read(&encrypted_path).unwrap();
+        assert!(!bytes
+            .windows(b"findable-marker".len())
+            .any(|window| window == b"findable-marker"));
+
+        let wrong = Database::from_file_encrypted(&encrypted_path, &SecretString::from("nope"))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            wrong.downcast_ref::<PersonaError>(),
+            Some(PersonaError::AuthenticationFailed(_))
+        ));
+
+        let encrypted = Database::from_file_encrypted(&encrypted_path, &passphrase)
+            .await
+            .unwrap();
+        // Migrations still run (as no-ops) once the file is unlocked
+        encrypted.migrate().await.unwrap();
+        assert_eq!(
+            encrypted.installation_id().await.unwrap(),
+            "findable-marker"
+        );
+        encrypted.pool.close().await;
+
+        let renewed = SecretString::from("renewed");
+        Database::rekey_file(&encrypted_path, &passphrase, &renewed)
+            .await
+            .unwrap();
+        assert!(Database::from_file_encrypted(&encrypted_path, &passphrase)
+            .await
+            .is_err());
+        let reopened = Database::from_file_encrypted(&encrypted_path, &renewed)
+            .await
+            .unwrap();
+        assert_eq!(
+            reopened.schema_version().await.unwrap(),
+            Some(Database::latest_schema_version())
+        );
+    }
 }
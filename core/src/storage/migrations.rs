@@ -0,0 +1,294 @@
+//! Versioned schema migrations.
+//!
+//! The SQL files in `core/migrations` are embedded at build time. Every applied migration is
+//! recorded in `schema_migrations` with its checksum, so opening a vault only runs the ones it
+//! has not seen, oldest first, each in its own transaction. A vault that records a migration
+//! this build does not know was written by a newer Persona and is left untouched.
+//!
+//! Vaults migrated by earlier builds kept their history in sqlx's `_sqlx_migrations`; it is
+//! copied into `schema_migrations` the first time such a vault is migrated, so nothing runs
+//! twice.
+
+use crate::storage::Database;
+use crate::{PersonaError, Result};
+use serde::Serialize;
+use sqlx::migrate::{Migration, Migrator};
+use sqlx::{Executor, Row};
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Table recording applied migrations
+const LEDGER_TABLE: &str = "schema_migrations";
+
+/// Table sqlx's migrator kept before `schema_migrations` existed
+const LEGACY_LEDGER_TABLE: &str = "_sqlx_migrations";
+
+const LEDGER_SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version INTEGER PRIMARY KEY,
+    description TEXT NOT NULL,
+    checksum BLOB NOT NULL,
+    applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+)
+"#;
+
+/// A migration bundled with this build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub description: String,
+}
+
+/// Where a database stands against the migrations bundled with this build
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    /// Newest migration applied, or `None` before the first one
+    pub current: Option<i64>,
+    /// Newest migration bundled with this build
+    pub latest: i64,
+    /// Bundled migrations not applied yet, oldest first
+    pub pending: Vec<MigrationInfo>,
+}
+
+impl MigrationStatus {
+    /// Whether every bundled migration has been applied
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Whether the database was migrated by a newer build than this one
+    pub fn is_newer_than_build(&self) -> bool {
+        self.current.is_some_and(|current| current > self.latest)
+    }
+}
+
+/// Newest migration bundled with this build
+pub(crate) fn latest_version() -> i64 {
+    bundled()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Compare the database's applied migrations with the bundled ones
+pub(crate) async fn status(db: &Database) -> Result<MigrationStatus> {
+    let applied = applied(db).await?;
+    let pending = bundled()
+        .filter(|migration| {
+            !applied
+                .iter()
+                .any(|(version, _)| *version == migration.version)
+        })
+        .map(|migration| MigrationInfo {
+            version: migration.version,
+            description: migration.description.to_string(),
+        })
+        .collect();
+    Ok(MigrationStatus {
+        current: applied.iter().map(|(version, _)| *version).max(),
+        latest: latest_version(),
+        pending,
+    })
+}
+
+/// Apply every pending migration, oldest first, each in its own transaction.
+///
+/// Fails without changing anything when the database records a migration this build does not
+/// bundle (it was written by a newer Persona) or one whose SQL has changed since it ran.
+pub(crate) async fn run(db: &Database) -> Result<()> {
+    adopt_legacy_ledger(db).await?;
+    let applied = applied(db).await?;
+    for (version, checksum) in &applied {
+        match bundled().find(|migration| migration.version == *version) {
+            None => {
+                return Err(PersonaError::Database(format!(
+                    "This vault has schema migration {} applied, but this build only knows migrations up to {}; it was written by a newer Persona and will not be downgraded",
+                    version,
+                    latest_version()
+                ))
+                .into())
+            }
+            Some(migration) if migration.checksum.as_ref() != checksum.as_slice() => {
+                return Err(PersonaError::Database(format!(
+                    "Schema migration {} ({}) differs from the one applied to this vault",
+                    version, migration.description
+                ))
+                .into())
+            }
+            Some(_) => {}
+        }
+    }
+
+    for migration in bundled() {
+        if applied
+            .iter()
+            .any(|(version, _)| *version == migration.version)
+        {
+            continue;
+        }
+        apply(db, migration).await?;
+    }
+    Ok(())
+}
+
+/// Run one migration and record it in the same transaction
+async fn apply(db: &Database, migration: &Migration) -> Result<()> {
+    let mut tx = db.begin().await?;
+    // Recording first takes the write lock before anything is read, so a second process
+    // migrating the same vault waits here and then finds the version already claimed
+    let claimed = sqlx::query(
+        "INSERT OR IGNORE INTO schema_migrations (version, description, checksum) VALUES (?, ?, ?)",
+    )
+    .bind(migration.version)
+    .bind(migration.description.as_ref())
+    .bind(migration.checksum.as_ref())
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| PersonaError::Database(e.to_string()))?;
+    if claimed.rows_affected() == 0 {
+        return Ok(());
+    }
+    (&mut *tx)
+        .execute(migration.sql.as_ref())
+        .await
+        .map_err(|e| {
+            PersonaError::Database(format!(
+                "Schema migration {} ({}) failed: {}",
+                migration.version, migration.description, e
+            ))
+        })?;
+    tx.commit().await?;
+    tracing::info!(
+        "Applied schema migration {} ({})",
+        migration.version,
+        migration.description
+    );
+    Ok(())
+}
+
+/// Create `schema_migrations`, seeding it from `_sqlx_migrations` for vaults migrated by
+/// earlier builds
+async fn adopt_legacy_ledger(db: &Database) -> Result<()> {
+    if has_table(db, LEDGER_TABLE).await? {
+        return Ok(());
+    }
+    let legacy = has_table(db, LEGACY_LEDGER_TABLE).await?;
+    let mut tx = db.begin().await?;
+    tx.execute(LEDGER_SCHEMA).await?;
+    if legacy {
+        let dirty: Option<i64> =
+            sqlx::query_scalar("SELECT MIN(version) FROM _sqlx_migrations WHERE success = 0")
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| PersonaError::Database(e.to_string()))?;
+        if let Some(version) = dirty {
+            return Err(PersonaError::Database(format!(
+                "Schema migration {} was left partly applied by an earlier version; restore the vault from a backup",
+                version
+            ))
+            .into());
+        }
+        tx.execute(
+            "INSERT OR IGNORE INTO schema_migrations (version, description, checksum, applied_at) SELECT version, description, checksum, installed_on FROM _sqlx_migrations WHERE success = 1",
+        )
+        .await?;
+    }
+    tx.commit().await
+}
+
+/// Versions and checksums of the applied migrations, oldest first
+async fn applied(db: &Database) -> Result<Vec<(i64, Vec<u8>)>> {
+    let query = if has_table(db, LEDGER_TABLE).await? {
+        "SELECT version, checksum FROM schema_migrations ORDER BY version"
+    } else if has_table(db, LEGACY_LEDGER_TABLE).await? {
+        "SELECT version, checksum FROM _sqlx_migrations WHERE success = 1 ORDER BY version"
+    } else {
+        return Ok(Vec::new());
+    };
+    let rows = sqlx::query(query)
+        .fetch_all(db.pool())
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("version"), row.get("checksum")))
+        .collect())
+}
+
+async fn has_table(db: &Database, name: &str) -> Result<bool> {
+    let found: Option<i64> =
+        sqlx::query_scalar("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(name)
+            .fetch_optional(db.pool())
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+    Ok(found.is_some())
+}
+
+/// Up migrations in version order
+fn bundled() -> impl Iterator<Item = &'static Migration> {
+    MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn pending_migrations_are_applied_once() {
+        let db = Database::in_memory().await.unwrap();
+        let fresh = db.migration_status().await.unwrap();
+        assert_eq!(fresh.current, None);
+        assert_eq!(fresh.pending.len(), bundled().count());
+        assert_eq!(fresh.pending[0].version, 1);
+
+        db.migrate().await.unwrap();
+        let migrated = db.migration_status().await.unwrap();
+        assert!(migrated.is_up_to_date());
+        assert_eq!(migrated.current, Some(migrated.latest));
+
+        // A second run finds nothing to do; re-running 003 would fail on its ALTER TABLEs
+        db.migrate().await.unwrap();
+        let recorded: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM schema_migrations")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(recorded, bundled().count() as i64);
+    }
+
+    #[tokio::test]
+    async fn vaults_migrated_by_sqlx_keep_their_history() {
+        let db = Database::in_memory().await.unwrap();
+        MIGRATOR.run(db.pool()).await.unwrap();
+        assert!(db.migration_status().await.unwrap().is_up_to_date());
+
+        db.migrate().await.unwrap();
+        let workspace_v2: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM schema_migrations WHERE version = 3")
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(workspace_v2, 1);
+        assert_eq!(db.schema_version().await.unwrap(), Some(latest_version()));
+    }
+
+    #[tokio::test]
+    async fn newer_vaults_are_not_downgraded() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        sqlx::query(
+            "INSERT INTO schema_migrations (version, description, checksum) VALUES (?, 'future', x'00')",
+        )
+        .bind(latest_version() + 1)
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        let status = db.migration_status().await.unwrap();
+        assert!(status.is_newer_than_build());
+        let err = db.migrate().await.unwrap_err();
+        assert!(err.to_string().contains("newer Persona"));
+    }
+}
@@ -1,19 +1,41 @@
+#[cfg(feature = "wallet")]
+pub mod address_book_repository;
 pub mod attachment;
 pub mod auto_lock_policy_repository;
+pub mod backup;
+pub mod backup_guard;
+pub mod backup_schedule;
 pub mod blob;
 pub mod change_history;
 pub mod database;
 pub mod filesystem;
+pub mod migrations;
+pub mod name_lookup;
+pub mod permissions;
+pub mod rekey;
 pub mod repository;
 pub mod user_auth;
+pub mod vault_lock;
+#[cfg(feature = "wallet")]
 pub mod wallet_repository;
 
+#[cfg(feature = "wallet")]
+pub use address_book_repository::*;
 pub use attachment::*;
 pub use auto_lock_policy_repository::*;
+pub use backup::*;
+pub use backup_guard::*;
+pub use backup_schedule::*;
 pub use blob::*;
 pub use change_history::*;
 pub use database::*;
 pub use filesystem::*;
+pub use migrations::{MigrationInfo, MigrationStatus};
+pub use name_lookup::*;
+pub use permissions::*;
+pub use rekey::*;
 pub use repository::*;
 pub use user_auth::*;
+pub use vault_lock::*;
+#[cfg(feature = "wallet")]
 pub use wallet_repository::*;
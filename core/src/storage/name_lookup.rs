@@ -0,0 +1,95 @@
+//! Forgiving name lookups for identities, credentials and wallets.
+//!
+//! SQL does the cheap part (`COLLATE NOCASE`, which only folds ASCII); when that finds nothing,
+//! names are compared after [`fold_name`], so `persona switch jose` finds "José" and
+//! `persona switch ÉQUIPE` finds "équipe".
+
+use crate::{PersonaError, Result};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Lowercased, accent-stripped form of a name used for comparisons.
+///
+/// Applies compatibility decomposition (NFKD), drops combining marks and lowercases, so
+/// "José", "JOSE" and "jose\u{301}" all fold to "jose".
+pub fn fold_name(name: &str) -> String {
+    name.trim()
+        .nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Whether two names are equal once folded
+pub fn names_match(a: &str, b: &str) -> bool {
+    fold_name(a) == fold_name(b)
+}
+
+/// Choose the single entry named `name` among `candidates`.
+///
+/// An exact (case-sensitive) name wins over the others; several candidates without one is
+/// an [`PersonaError::InvalidInput`] listing them, so callers never act on a guess.
+pub(crate) fn pick_by_name<T>(
+    mut candidates: Vec<T>,
+    name: &str,
+    kind: &str,
+    name_of: impl Fn(&T) -> &str,
+) -> Result<Option<T>> {
+    if candidates.len() <= 1 {
+        return Ok(candidates.pop());
+    }
+    let exact: Vec<usize> = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, candidate)| name_of(candidate) == name.trim())
+        .map(|(index, _)| index)
+        .collect();
+    if let [index] = exact[..] {
+        return Ok(Some(candidates.swap_remove(index)));
+    }
+
+    let mut names: Vec<&str> = candidates.iter().map(&name_of).collect();
+    names.sort_unstable();
+    Err(PersonaError::InvalidInput(format!(
+        "'{}' matches several {}: {}. Use the exact name",
+        name,
+        kind,
+        names.join(", ")
+    ))
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_case_and_accents() {
+        assert_eq!(fold_name("José"), "jose");
+        assert_eq!(fold_name("  ÉQUIPE Rouge "), "equipe rouge");
+        // Precomposed and decomposed forms fold alike
+        assert!(names_match("Jos\u{e9}", "jose\u{301}"));
+        assert!(names_match("Ｗｏｒｋ", "work"));
+        assert!(!names_match("work", "worker"));
+    }
+
+    #[test]
+    fn pick_prefers_exact_and_rejects_ambiguity() {
+        let pick = |names: &[&str], name: &str| {
+            pick_by_name(
+                names.iter().map(|n| n.to_string()).collect(),
+                name,
+                "identities",
+                |n| n.as_str(),
+            )
+        };
+
+        assert_eq!(pick(&[], "work").unwrap(), None);
+        assert_eq!(pick(&["Work"], "work").unwrap().as_deref(), Some("Work"));
+        assert_eq!(
+            pick(&["Work", "work"], "work").unwrap().as_deref(),
+            Some("work")
+        );
+        let err = pick(&["Work", "WORK"], "work").unwrap_err();
+        assert!(err.to_string().contains("WORK, Work"), "{}", err);
+    }
+}
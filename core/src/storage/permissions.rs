@@ -0,0 +1,134 @@
+//! Owner-only permissions for vault files.
+//!
+//! On Unix the database is created `0600` and new vault directories `0700`, and the self-check
+//! flags anything group- or world-accessible. On Windows, files under the user profile inherit
+//! an owner-only ACL, so nothing is changed there and the check reports no issues.
+
+use crate::{PersonaError, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Permission bits granting group or world access
+#[cfg(unix)]
+const SHARED_BITS: u32 = 0o077;
+
+/// A vault path that other users can access
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PermissionIssue {
+    pub path: PathBuf,
+    /// Unix permission bits, e.g. `0o644`
+    pub mode: u32,
+    pub directory: bool,
+}
+
+impl PermissionIssue {
+    /// Shell command that makes the path owner-only
+    pub fn fix_command(&self) -> String {
+        let mode = if self.directory { "700" } else { "600" };
+        format!("chmod {} {}", mode, self.path.display())
+    }
+}
+
+impl std::fmt::Display for PermissionIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is accessible by other users (mode {:o})",
+            self.path.display(),
+            self.mode
+        )
+    }
+}
+
+/// Create an empty owner-only file unless `path` already exists
+pub fn create_private_file(path: &Path) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    match options.open(path) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(()),
+        Err(e) => Err(PersonaError::Io(format!("{}: {}", path.display(), e)).into()),
+    }
+}
+
+/// Create a directory (and parents); a newly created leaf is made owner-only
+pub fn create_private_dir_all(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Ok(());
+    }
+    fs::create_dir_all(path).map_err(PersonaError::from)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o700)).map_err(PersonaError::from)?;
+    }
+    Ok(())
+}
+
+/// Paths among `paths` that other users can access; missing paths are skipped
+pub fn check_private(paths: &[&Path]) -> Vec<PermissionIssue> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let metadata = fs::metadata(path).ok()?;
+            let mode = shared_mode(&metadata)?;
+            Some(PermissionIssue {
+                path: path.to_path_buf(),
+                mode,
+                directory: metadata.is_dir(),
+            })
+        })
+        .collect()
+}
+
+/// Permission bits when they grant access beyond the owner
+#[cfg(unix)]
+fn shared_mode(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = metadata.permissions().mode() & 0o777;
+    (mode & SHARED_BITS != 0).then_some(mode)
+}
+
+#[cfg(not(unix))]
+fn shared_mode(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn flags_group_and_world_access() {
+        let dir = tempfile::tempdir().unwrap();
+        let private = dir.path().join("private");
+        let shared = dir.path().join("shared");
+        create_private_file(&private).unwrap();
+        fs::write(&shared, b"").unwrap();
+        fs::set_permissions(&shared, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let issues = check_private(&[&private, &shared, &dir.path().join("missing")]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, shared);
+        assert_eq!(issues[0].mode, 0o644);
+        assert!(issues[0].fix_command().starts_with("chmod 600 "));
+    }
+
+    #[test]
+    fn new_directories_are_owner_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("a").join("vault");
+        create_private_dir_all(&vault).unwrap();
+
+        let mode = fs::metadata(&vault).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o700);
+        assert!(check_private(&[&vault]).is_empty());
+    }
+}
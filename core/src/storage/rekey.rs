@@ -0,0 +1,265 @@
+//! One transaction over every row encrypted under the vault key.
+//!
+//! Changing the master password re-encrypts credentials, retained previous secrets, attachment
+//! keys and wallet keys. [`VaultRekey`] holds a single transaction across those tables, so the
+//! vault is never left partly under the old key and partly under the new one: either every
+//! rewrite is committed together, or the transaction is dropped and SQLite rolls it back.
+
+use crate::auth::authentication::UserAuth;
+use crate::models::{Attachment, AttachmentChunk};
+use crate::storage::{Database, Transaction};
+use crate::{PersonaError, Result};
+use sqlx::Row;
+use uuid::Uuid;
+
+/// A credential's stored payload
+#[derive(Debug, Clone)]
+pub struct StoredPayload {
+    pub id: Uuid,
+    pub encrypted_data: Vec<u8>,
+    /// Item key wrapped by the vault key (`None` for legacy rows under the vault key itself)
+    pub wrapped_item_key: Option<Vec<u8>>,
+}
+
+/// A wallet's sealed key material
+#[cfg(feature = "wallet")]
+#[derive(Debug, Clone)]
+pub struct StoredWalletKeys {
+    pub id: String,
+    pub encrypted_private_key: Vec<u8>,
+    pub encrypted_mnemonic: Option<Vec<u8>>,
+}
+
+/// Open transaction for re-encrypting the vault; dropping it without [`Self::commit`] rolls
+/// every change back
+pub struct VaultRekey {
+    tx: Transaction<'static>,
+}
+
+impl VaultRekey {
+    /// Begin the transaction
+    pub async fn begin(db: &Database) -> Result<Self> {
+        Ok(Self {
+            tx: db.begin().await?,
+        })
+    }
+
+    /// Payloads of every credential, trashed ones included
+    pub async fn credential_payloads(&mut self) -> Result<Vec<StoredPayload>> {
+        let rows =
+            sqlx::query("SELECT id, encrypted_data, wrapped_item_key FROM credentials ORDER BY id")
+                .fetch_all(&mut *self.tx)
+                .await
+                .map_err(|e| PersonaError::Database(e.to_string()))?;
+        rows.into_iter().map(row_to_payload).collect()
+    }
+
+    /// Payload of one credential as written so far in this transaction
+    pub async fn credential_payload(&mut self, id: &Uuid) -> Result<Option<StoredPayload>> {
+        let row = sqlx::query(
+            "SELECT id, encrypted_data, wrapped_item_key FROM credentials WHERE id = ?",
+        )
+        .bind(id.to_string())
+        .fetch_optional(&mut *self.tx)
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+        row.map(row_to_payload).transpose()
+    }
+
+    /// Replace a credential's payload.
+    ///
+    /// The row version is bumped so an edit based on the old ciphertext fails with a conflict
+    /// instead of writing it back.
+    pub async fn replace_credential_payload(
+        &mut self,
+        id: &Uuid,
+        encrypted_data: &[u8],
+        wrapped_item_key: &[u8],
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE credentials SET encrypted_data = ?, wrapped_item_key = ?, version = version + 1 WHERE id = ?",
+        )
+        .bind(encrypted_data)
+        .bind(wrapped_item_key)
+        .bind(id.to_string())
+        .execute(&mut *self.tx)
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Previous states of the change-history entries recorded with `reason`
+    pub async fn history_states(
+        &mut self,
+        reason: &str,
+    ) -> Result<Vec<(String, serde_json::Value)>> {
+        let rows = sqlx::query(
+            "SELECT id, previous_state FROM change_history WHERE reason = ? AND previous_state IS NOT NULL",
+        )
+        .bind(reason)
+        .fetch_all(&mut *self.tx)
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+        rows.into_iter()
+            .map(|row| {
+                let state: String = row.get("previous_state");
+                let state = serde_json::from_str(&state).map_err(|e| {
+                    PersonaError::Database(format!("Invalid change history state: {}", e))
+                })?;
+                Ok((row.get("id"), state))
+            })
+            .collect()
+    }
+
+    /// Replace the previous state of a change-history entry
+    pub async fn replace_history_state(
+        &mut self,
+        id: &str,
+        state: &serde_json::Value,
+    ) -> Result<()> {
+        sqlx::query("UPDATE change_history SET previous_state = ? WHERE id = ?")
+            .bind(state.to_string())
+            .bind(id)
+            .execute(&mut *self.tx)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Wrapped content keys of the encrypted attachment blobs, by content hash
+    pub async fn blob_keys(&mut self) -> Result<Vec<(String, Vec<u8>)>> {
+        let rows = sqlx::query(
+            "SELECT content_hash, wrapped_key FROM attachment_blobs WHERE wrapped_key IS NOT NULL",
+        )
+        .fetch_all(&mut *self.tx)
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("content_hash"), row.get("wrapped_key")))
+            .collect())
+    }
+
+    /// Replace a blob's wrapped content key
+    pub async fn replace_blob_key(&mut self, content_hash: &str, wrapped_key: &[u8]) -> Result<()> {
+        sqlx::query("UPDATE attachment_blobs SET wrapped_key = ? WHERE content_hash = ?")
+            .bind(wrapped_key)
+            .bind(content_hash)
+            .execute(&mut *self.tx)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Record `key_id` as the key of every encrypted attachment; returns how many there are
+    pub async fn set_attachment_key_id(&mut self, key_id: &str) -> Result<u64> {
+        let result =
+            sqlx::query("UPDATE attachments SET encryption_key_id = ? WHERE is_encrypted = 1")
+                .bind(key_id)
+                .execute(&mut *self.tx)
+                .await
+                .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Point an attachment at its re-encrypted files, replacing its chunk rows
+    pub async fn replace_attachment_files(
+        &mut self,
+        attachment: &Attachment,
+        chunks: &[AttachmentChunk],
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE attachments SET storage_path = ?, content_hash = ?, encryption_key_id = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(&attachment.storage_path)
+        .bind(&attachment.content_hash)
+        .bind(&attachment.encryption_key_id)
+        .bind(attachment.updated_at.to_rfc3339())
+        .bind(attachment.id.to_string())
+        .execute(&mut *self.tx)
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+
+        sqlx::query("DELETE FROM attachment_chunks WHERE attachment_id = ?")
+            .bind(attachment.id.to_string())
+            .execute(&mut *self.tx)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        for chunk in chunks {
+            sqlx::query(
+                r#"
+                INSERT INTO attachment_chunks (
+                    id, attachment_id, chunk_index, size, content_hash,
+                    storage_path, is_encrypted, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(chunk.id.to_string())
+            .bind(chunk.attachment_id.to_string())
+            .bind(chunk.chunk_index as i32)
+            .bind(chunk.size as i32)
+            .bind(&chunk.content_hash)
+            .bind(&chunk.storage_path)
+            .bind(chunk.is_encrypted)
+            .bind(chunk.created_at.to_rfc3339())
+            .execute(&mut *self.tx)
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Sealed key material of every wallet that has any
+    #[cfg(feature = "wallet")]
+    pub async fn wallet_keys(&mut self) -> Result<Vec<StoredWalletKeys>> {
+        let rows = sqlx::query(
+            "SELECT id, encrypted_private_key, encrypted_mnemonic FROM crypto_wallets WHERE length(encrypted_private_key) > 0",
+        )
+        .fetch_all(&mut *self.tx)
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| StoredWalletKeys {
+                id: row.get("id"),
+                encrypted_private_key: row.get("encrypted_private_key"),
+                encrypted_mnemonic: row.get("encrypted_mnemonic"),
+            })
+            .collect())
+    }
+
+    /// Replace a wallet's sealed key material
+    #[cfg(feature = "wallet")]
+    pub async fn replace_wallet_keys(&mut self, keys: &StoredWalletKeys) -> Result<()> {
+        sqlx::query(
+            "UPDATE crypto_wallets SET encrypted_private_key = ?, encrypted_mnemonic = ? WHERE id = ?",
+        )
+        .bind(&keys.encrypted_private_key)
+        .bind(&keys.encrypted_mnemonic)
+        .bind(&keys.id)
+        .execute(&mut *self.tx)
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Write the user's new password hash, salt and recovery state
+    pub async fn update_user_auth(&mut self, auth: &UserAuth) -> Result<()> {
+        crate::storage::user_auth::write_update(&mut *self.tx, auth).await
+    }
+
+    /// Make every change permanent
+    pub async fn commit(self) -> Result<()> {
+        self.tx.commit().await
+    }
+}
+
+fn row_to_payload(row: sqlx::sqlite::SqliteRow) -> Result<StoredPayload> {
+    let id: String = row.get("id");
+    Ok(StoredPayload {
+        id: Uuid::parse_str(&id)
+            .map_err(|e| PersonaError::Database(format!("Invalid UUID: {}", e)))?,
+        encrypted_data: row.get("encrypted_data"),
+        wrapped_item_key: row.get("wrapped_item_key"),
+    })
+}
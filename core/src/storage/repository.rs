@@ -2,7 +2,8 @@ use crate::models::{
     AuditAction, AuditLog, Credential, CredentialType, Identity, IdentityType, ResourceType,
     SecurityLevel, Workspace,
 };
-use crate::storage::Database;
+use crate::storage::name_lookup::{fold_name, pick_by_name};
+use crate::storage::{Database, Transaction};
 use crate::{PersonaError, Result};
 use async_trait::async_trait;
 use sqlx::Row;
@@ -33,7 +34,7 @@ impl IdentityRepository {
     pub async fn find_by_type(&self, identity_type: &IdentityType) -> Result<Vec<Identity>> {
         let type_str = identity_type.to_string();
         let rows = sqlx::query(
-            "SELECT id, name, identity_type, description, email, phone, ssh_key, gpg_key, tags, attributes, created_at, updated_at, is_active FROM identities WHERE identity_type = ?"
+            "SELECT id, name, identity_type, description, email, phone, ssh_key, gpg_key, tags, attributes, created_at, updated_at, is_active, version FROM identities WHERE identity_type = ?"
         )
         .bind(&type_str)
         .fetch_all(self.db.pool())
@@ -47,19 +48,32 @@ impl IdentityRepository {
         Ok(identities)
     }
 
+    /// Find an identity by name, ignoring case and accents ("jose" finds "José").
+    ///
+    /// An exact name wins when several identities fold to the same name; otherwise that is
+    /// an [`PersonaError::InvalidInput`], see [`fold_name`].
     pub async fn find_by_name(&self, name: &str) -> Result<Option<Identity>> {
-        let row = sqlx::query(
-            "SELECT id, name, identity_type, description, email, phone, ssh_key, gpg_key, tags, attributes, created_at, updated_at, is_active FROM identities WHERE name = ?"
+        let rows = sqlx::query(
+            "SELECT id, name, identity_type, description, email, phone, ssh_key, gpg_key, tags, attributes, created_at, updated_at, is_active, version FROM identities WHERE name = ? COLLATE NOCASE"
         )
-        .bind(name)
-        .fetch_optional(self.db.pool())
+        .bind(name.trim())
+        .fetch_all(self.db.pool())
         .await
         .map_err(|e| PersonaError::Database(e.to_string()))?;
 
-        match row {
-            Some(row) => Ok(Some(self.row_to_identity(row)?)),
-            None => Ok(None),
-        }
+        let candidates = if rows.is_empty() {
+            let folded = fold_name(name);
+            self.find_all()
+                .await?
+                .into_iter()
+                .filter(|identity| fold_name(&identity.name) == folded)
+                .collect()
+        } else {
+            rows.into_iter()
+                .map(|row| self.row_to_identity(row))
+                .collect::<Result<Vec<_>>>()?
+        };
+        pick_by_name(candidates, name, "identities", |identity| &identity.name)
     }
 
     fn row_to_identity(&self, row: sqlx::sqlite::SqliteRow) -> Result<Identity> {
@@ -104,6 +118,7 @@ impl IdentityRepository {
             created_at,
             updated_at,
             is_active: row.get("is_active"),
+            version: row.get("version"),
         })
     }
 }
@@ -122,8 +137,8 @@ impl Repository<Identity> for IdentityRepository {
             r#"
             INSERT INTO identities (
                 id, name, identity_type, description, email, phone, ssh_key, gpg_key,
-                tags, attributes, created_at, updated_at, is_active
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                tags, attributes, created_at, updated_at, is_active, version
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(identity.id.to_string())
@@ -139,6 +154,7 @@ impl Repository<Identity> for IdentityRepository {
         .bind(identity.created_at.to_rfc3339())
         .bind(identity.updated_at.to_rfc3339())
         .bind(identity.is_active)
+        .bind(identity.version)
         .execute(self.db.pool())
         .await
         .map_err(|e| PersonaError::Database(e.to_string()))?;
@@ -148,7 +164,7 @@ impl Repository<Identity> for IdentityRepository {
 
     async fn find_by_id(&self, id: &Uuid) -> Result<Option<Identity>> {
         let row = sqlx::query(
-            "SELECT id, name, identity_type, description, email, phone, ssh_key, gpg_key, tags, attributes, created_at, updated_at, is_active FROM identities WHERE id = ?"
+            "SELECT id, name, identity_type, description, email, phone, ssh_key, gpg_key, tags, attributes, created_at, updated_at, is_active, version FROM identities WHERE id = ?"
         )
         .bind(id.to_string())
         .fetch_optional(self.db.pool())
@@ -163,7 +179,7 @@ impl Repository<Identity> for IdentityRepository {
 
     async fn find_all(&self) -> Result<Vec<Identity>> {
         let rows = sqlx::query(
-            "SELECT id, name, identity_type, description, email, phone, ssh_key, gpg_key, tags, attributes, created_at, updated_at, is_active FROM identities ORDER BY created_at DESC"
+            "SELECT id, name, identity_type, description, email, phone, ssh_key, gpg_key, tags, attributes, created_at, updated_at, is_active, version FROM identities ORDER BY created_at DESC"
         )
         .fetch_all(self.db.pool())
         .await
@@ -184,12 +200,13 @@ impl Repository<Identity> for IdentityRepository {
             PersonaError::Database(format!("Failed to serialize attributes: {}", e))
         })?;
 
-        sqlx::query(
+        let result = sqlx::query(
             r#"
             UPDATE identities SET
                 name = ?, identity_type = ?, description = ?, email = ?, phone = ?,
-                ssh_key = ?, gpg_key = ?, tags = ?, attributes = ?, updated_at = ?, is_active = ?
-            WHERE id = ?
+                ssh_key = ?, gpg_key = ?, tags = ?, attributes = ?, updated_at = ?, is_active = ?,
+                version = version + 1
+            WHERE id = ? AND version = ?
             "#,
         )
         .bind(&identity.name)
@@ -204,11 +221,22 @@ impl Repository<Identity> for IdentityRepository {
         .bind(identity.updated_at.to_rfc3339())
         .bind(identity.is_active)
         .bind(identity.id.to_string())
+        .bind(identity.version)
         .execute(self.db.pool())
         .await
         .map_err(|e| PersonaError::Database(e.to_string()))?;
 
-        Ok(identity.clone())
+        if result.rows_affected() == 0 {
+            return Err(PersonaError::Conflict(format!(
+                "Identity {} was modified or removed since version {}; reload and retry",
+                identity.id, identity.version
+            ))
+            .into());
+        }
+
+        let mut updated = identity.clone();
+        updated.version += 1;
+        Ok(updated)
     }
 
     async fn delete(&self, id: &Uuid) -> Result<bool> {
@@ -232,14 +260,24 @@ impl CredentialRepository {
         Self { db }
     }
 
+    /// Insert a credential inside `tx`; it only becomes visible when `tx` commits
+    pub async fn create_in(
+        &self,
+        tx: &mut Transaction<'_>,
+        credential: &Credential,
+    ) -> Result<Credential> {
+        insert_credential(&mut **tx, credential).await?;
+        Ok(credential.clone())
+    }
+
     /// Find credentials by identity
     pub async fn find_by_identity(&self, identity_id: &Uuid) -> Result<Vec<Credential>> {
         let rows = sqlx::query(
             r#"
             SELECT id, identity_id, name, credential_type, security_level, url, username,
                    encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
-                   last_accessed, is_active, is_favorite
-            FROM credentials WHERE identity_id = ? ORDER BY created_at DESC
+                   last_accessed, is_active, is_favorite, linked_totp_id, version, deleted_at
+            FROM credentials WHERE identity_id = ? AND deleted_at IS NULL ORDER BY created_at DESC
             "#,
         )
         .bind(identity_id.to_string())
@@ -260,8 +298,9 @@ impl CredentialRepository {
             r#"
             SELECT id, identity_id, name, credential_type, security_level, url, username,
                    encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
-                   last_accessed, is_active, is_favorite
-            FROM credentials WHERE credential_type = ? ORDER BY created_at DESC
+                   last_accessed, is_active, is_favorite, linked_totp_id, version, deleted_at
+            FROM credentials WHERE credential_type = ? AND deleted_at IS NULL
+            ORDER BY created_at DESC
             "#,
         )
         .bind(credential_type.to_string())
@@ -276,6 +315,42 @@ impl CredentialRepository {
         Ok(credentials)
     }
 
+    /// Find an identity's credential by name, ignoring case and accents.
+    ///
+    /// Same rules as [`IdentityRepository::find_by_name`].
+    pub async fn find_by_name(&self, identity_id: &Uuid, name: &str) -> Result<Option<Credential>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, identity_id, name, credential_type, security_level, url, username,
+                   encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
+                   last_accessed, is_active, is_favorite, linked_totp_id, version, deleted_at
+            FROM credentials
+            WHERE identity_id = ? AND name = ? COLLATE NOCASE AND deleted_at IS NULL
+            "#,
+        )
+        .bind(identity_id.to_string())
+        .bind(name.trim())
+        .fetch_all(self.db.pool())
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+
+        let candidates = if rows.is_empty() {
+            let folded = fold_name(name);
+            self.find_by_identity(identity_id)
+                .await?
+                .into_iter()
+                .filter(|credential| fold_name(&credential.name) == folded)
+                .collect()
+        } else {
+            rows.into_iter()
+                .map(|row| self.row_to_credential(row))
+                .collect::<Result<Vec<_>>>()?
+        };
+        pick_by_name(candidates, name, "credentials", |credential| {
+            &credential.name
+        })
+    }
+
     /// Search credentials by name
     pub async fn search_by_name(&self, query: &str) -> Result<Vec<Credential>> {
         let search_query = format!("%{}%", query);
@@ -283,8 +358,9 @@ impl CredentialRepository {
             r#"
             SELECT id, identity_id, name, credential_type, security_level, url, username,
                    encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
-                   last_accessed, is_active, is_favorite
-            FROM credentials WHERE name LIKE ? AND is_active = 1 ORDER BY created_at DESC
+                   last_accessed, is_active, is_favorite, linked_totp_id, version, deleted_at
+            FROM credentials WHERE name LIKE ? AND is_active = 1 AND deleted_at IS NULL
+            ORDER BY created_at DESC
             "#,
         )
         .bind(&search_query)
@@ -299,16 +375,113 @@ impl CredentialRepository {
         Ok(credentials)
     }
 
+    /// Search name, username, URL, notes and tags, best match first.
+    ///
+    /// Every whitespace-separated term must appear (case-insensitively) in at least one field.
+    /// Candidates come from the FTS5 index when it exists and every term is at least three
+    /// characters (the trigram minimum); otherwise from LIKE clauses. Both paths are scored
+    /// the same way, see [`CredentialSearchHit::score`]. An empty query returns every active
+    /// credential with a score of zero.
+    pub async fn search_full_text(&self, query: &str) -> Result<Vec<CredentialSearchHit>> {
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let columns = r#"
+            SELECT id, identity_id, name, credential_type, security_level, url, username,
+                   encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
+                   last_accessed, is_active, is_favorite, linked_totp_id, version, deleted_at
+            FROM credentials WHERE is_active = 1 AND deleted_at IS NULL"#;
+
+        let rows = if terms.is_empty() {
+            sqlx::query(&format!("{} ORDER BY created_at DESC", columns))
+                .fetch_all(self.db.pool())
+                .await
+        } else if terms.iter().all(|t| t.chars().count() >= 3) && self.db.has_search_index().await?
+        {
+            let fts_query = terms
+                .iter()
+                .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+                .collect::<Vec<_>>()
+                .join(" ");
+            sqlx::query(&format!(
+                "{} AND id IN (SELECT id FROM credentials_fts WHERE credentials_fts MATCH ?)",
+                columns
+            ))
+            .bind(fts_query)
+            .fetch_all(self.db.pool())
+            .await
+        } else {
+            let clause = "(name LIKE ? ESCAPE '\\' OR username LIKE ? ESCAPE '\\' OR url LIKE ? ESCAPE '\\' OR notes LIKE ? ESCAPE '\\' OR tags LIKE ? ESCAPE '\\')";
+            let sql = format!("{} AND {}", columns, vec![clause; terms.len()].join(" AND "));
+            let mut q = sqlx::query(&sql);
+            for term in &terms {
+                let pattern = format!("%{}%", escape_like(term));
+                for _ in 0..5 {
+                    q = q.bind(pattern.clone());
+                }
+            }
+            q.fetch_all(self.db.pool()).await
+        }
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+
+        let mut hits = Vec::new();
+        for row in rows {
+            let credential = self.row_to_credential(row)?;
+            if terms.is_empty() {
+                hits.push(CredentialSearchHit {
+                    credential,
+                    score: 0,
+                    matched_field: None,
+                });
+            } else if let Some((score, field)) = score_credential(&credential, query, &terms) {
+                hits.push(CredentialSearchHit {
+                    credential,
+                    score,
+                    matched_field: Some(field),
+                });
+            }
+        }
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| b.credential.last_accessed.cmp(&a.credential.last_accessed))
+                .then_with(|| a.credential.name.cmp(&b.credential.name))
+        });
+        Ok(hits)
+    }
+
     /// Get favorite credentials
     pub async fn find_favorites(&self) -> Result<Vec<Credential>> {
         let rows = sqlx::query(
             r#"
             SELECT id, identity_id, name, credential_type, security_level, url, username,
                    encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
-                   last_accessed, is_active, is_favorite
-            FROM credentials WHERE is_favorite = 1 AND is_active = 1 ORDER BY created_at DESC
+                   last_accessed, is_active, is_favorite, linked_totp_id, version, deleted_at
+            FROM credentials WHERE is_favorite = 1 AND is_active = 1 AND deleted_at IS NULL
+            ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(self.db.pool())
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+
+        let mut credentials = Vec::new();
+        for row in rows {
+            credentials.push(self.row_to_credential(row)?);
+        }
+        Ok(credentials)
+    }
+
+    /// Up to `limit` credentials that have been opened, most recently accessed first
+    pub async fn find_recently_used(&self, limit: u32) -> Result<Vec<Credential>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, identity_id, name, credential_type, security_level, url, username,
+                   encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
+                   last_accessed, is_active, is_favorite, linked_totp_id, version, deleted_at
+            FROM credentials WHERE last_accessed IS NOT NULL AND deleted_at IS NULL
+            ORDER BY last_accessed DESC LIMIT ?
             "#,
         )
+        .bind(limit)
         .fetch_all(self.db.pool())
         .await
         .map_err(|e| PersonaError::Database(e.to_string()))?;
@@ -320,6 +493,102 @@ impl CredentialRepository {
         Ok(credentials)
     }
 
+    /// Credentials in the trash, most recently trashed first
+    pub async fn find_trashed(&self) -> Result<Vec<Credential>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, identity_id, name, credential_type, security_level, url, username,
+                   encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
+                   last_accessed, is_active, is_favorite, linked_totp_id, version, deleted_at
+            FROM credentials WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC
+            "#,
+        )
+        .fetch_all(self.db.pool())
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+
+        let mut credentials = Vec::new();
+        for row in rows {
+            credentials.push(self.row_to_credential(row)?);
+        }
+        Ok(credentials)
+    }
+
+    /// Move a credential to the trash; `false` if it does not exist or is already trashed
+    pub async fn move_to_trash(
+        &self,
+        id: &Uuid,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE credentials SET is_active = 0, deleted_at = ?, version = version + 1 WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(at.to_rfc3339())
+        .bind(id.to_string())
+        .execute(self.db.pool())
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Take a credential out of the trash; `false` if it is not in the trash
+    pub async fn restore_from_trash(&self, id: &Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE credentials SET is_active = 1, deleted_at = NULL, version = version + 1 WHERE id = ? AND deleted_at IS NOT NULL",
+        )
+        .bind(id.to_string())
+        .execute(self.db.pool())
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record an access time without bumping the row version
+    pub async fn touch_last_accessed(
+        &self,
+        id: &Uuid,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE credentials SET last_accessed = ? WHERE id = ?")
+            .bind(at.to_rfc3339())
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Replace the encrypted payload without bumping the row version.
+    /// Used when re-encoding data whose plaintext is unchanged.
+    pub async fn replace_encrypted_payload(
+        &self,
+        id: &Uuid,
+        encrypted_data: &[u8],
+        wrapped_item_key: Option<&[u8]>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE credentials SET encrypted_data = ?, wrapped_item_key = ? WHERE id = ?")
+            .bind(encrypted_data)
+            .bind(wrapped_item_key)
+            .bind(id.to_string())
+            .execute(self.db.pool())
+            .await
+            .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Clear every link to a TOTP credential that is about to be removed.
+    /// Returns the number of password credentials that were unlinked.
+    pub async fn clear_totp_links(&self, totp_id: &Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE credentials SET linked_totp_id = NULL, version = version + 1 WHERE linked_totp_id = ?",
+        )
+        .bind(totp_id.to_string())
+        .execute(self.db.pool())
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+
     fn row_to_credential(&self, row: sqlx::sqlite::SqliteRow) -> Result<Credential> {
         let id_str: String = row.get("id");
         let id = Uuid::parse_str(&id_str)
@@ -379,6 +648,19 @@ impl CredentialRepository {
 
         let wrapped_item_key: Option<Vec<u8>> = row.get("wrapped_item_key");
 
+        let linked_totp_id = row
+            .get::<Option<String>, _>("linked_totp_id")
+            .map(|id| Uuid::parse_str(&id))
+            .transpose()
+            .map_err(|e| PersonaError::Database(format!("Invalid linked_totp_id: {}", e)))?;
+
+        let deleted_at = row
+            .get::<Option<String>, _>("deleted_at")
+            .map(|s| chrono::DateTime::parse_from_rfc3339(&s))
+            .transpose()
+            .map_err(|e| PersonaError::Database(format!("Invalid deleted_at: {}", e)))?
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+
         Ok(Credential {
             id,
             identity_id,
@@ -397,6 +679,9 @@ impl CredentialRepository {
             last_accessed,
             is_active: row.get("is_active"),
             is_favorite: row.get("is_favorite"),
+            linked_totp_id,
+            version: row.get("version"),
+            deleted_at,
         })
     }
 }
@@ -404,42 +689,7 @@ impl CredentialRepository {
 #[async_trait]
 impl Repository<Credential> for CredentialRepository {
     async fn create(&self, credential: &Credential) -> Result<Credential> {
-        let tags_json = serde_json::to_string(&credential.tags)
-            .map_err(|e| PersonaError::Database(format!("Failed to serialize tags: {}", e)))?;
-
-        let metadata_json = serde_json::to_string(&credential.metadata)
-            .map_err(|e| PersonaError::Database(format!("Failed to serialize metadata: {}", e)))?;
-
-        sqlx::query(
-            r#"
-            INSERT INTO credentials (
-                id, identity_id, name, credential_type, security_level, url, username,
-                encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
-                last_accessed, is_active, is_favorite
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(credential.id.to_string())
-        .bind(credential.identity_id.to_string())
-        .bind(&credential.name)
-        .bind(credential.credential_type.to_string())
-        .bind(credential.security_level.to_string())
-        .bind(&credential.url)
-        .bind(&credential.username)
-        .bind(&credential.encrypted_data)
-        .bind(&credential.wrapped_item_key)
-        .bind(&credential.notes)
-        .bind(&tags_json)
-        .bind(&metadata_json)
-        .bind(credential.created_at.to_rfc3339())
-        .bind(credential.updated_at.to_rfc3339())
-        .bind(credential.last_accessed.map(|dt| dt.to_rfc3339()))
-        .bind(credential.is_active)
-        .bind(credential.is_favorite)
-        .execute(self.db.pool())
-        .await
-        .map_err(|e| PersonaError::Database(e.to_string()))?;
-
+        insert_credential(self.db.pool(), credential).await?;
         Ok(credential.clone())
     }
 
@@ -448,7 +698,7 @@ impl Repository<Credential> for CredentialRepository {
             r#"
             SELECT id, identity_id, name, credential_type, security_level, url, username,
                    encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
-                   last_accessed, is_active, is_favorite
+                   last_accessed, is_active, is_favorite, linked_totp_id, version, deleted_at
             FROM credentials WHERE id = ?
             "#,
         )
@@ -468,8 +718,8 @@ impl Repository<Credential> for CredentialRepository {
             r#"
             SELECT id, identity_id, name, credential_type, security_level, url, username,
                    encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
-                   last_accessed, is_active, is_favorite
-            FROM credentials ORDER BY created_at DESC
+                   last_accessed, is_active, is_favorite, linked_totp_id, version, deleted_at
+            FROM credentials WHERE deleted_at IS NULL ORDER BY created_at DESC
             "#,
         )
         .fetch_all(self.db.pool())
@@ -490,13 +740,17 @@ impl Repository<Credential> for CredentialRepository {
         let metadata_json = serde_json::to_string(&credential.metadata)
             .map_err(|e| PersonaError::Database(format!("Failed to serialize metadata: {}", e)))?;
 
-        sqlx::query(
+        let last_accessed = credential.last_accessed.map(|dt| dt.to_rfc3339());
+        // An access recorded since `credential` was read is kept: the later time wins
+        let result = sqlx::query(
             r#"
             UPDATE credentials SET
                 identity_id = ?, name = ?, credential_type = ?, security_level = ?, url = ?,
                 username = ?, encrypted_data = ?, wrapped_item_key = ?, notes = ?, tags = ?, metadata = ?,
-                updated_at = ?, last_accessed = ?, is_active = ?, is_favorite = ?
-            WHERE id = ?
+                updated_at = ?, last_accessed = COALESCE(MAX(?, last_accessed), ?, last_accessed),
+                is_active = ?, is_favorite = ?,
+                linked_totp_id = ?, version = version + 1
+            WHERE id = ? AND version = ?
             "#
         )
         .bind(credential.identity_id.to_string())
@@ -511,15 +765,28 @@ impl Repository<Credential> for CredentialRepository {
         .bind(&tags_json)
         .bind(&metadata_json)
         .bind(credential.updated_at.to_rfc3339())
-        .bind(credential.last_accessed.map(|dt| dt.to_rfc3339()))
+        .bind(&last_accessed)
+        .bind(&last_accessed)
         .bind(credential.is_active)
         .bind(credential.is_favorite)
+        .bind(credential.linked_totp_id.map(|id| id.to_string()))
         .bind(credential.id.to_string())
+        .bind(credential.version)
         .execute(self.db.pool())
         .await
         .map_err(|e| PersonaError::Database(e.to_string()))?;
 
-        Ok(credential.clone())
+        if result.rows_affected() == 0 {
+            return Err(PersonaError::Conflict(format!(
+                "Credential {} was modified or removed since version {}; reload and retry",
+                credential.id, credential.version
+            ))
+            .into());
+        }
+
+        let mut updated = credential.clone();
+        updated.version += 1;
+        Ok(updated)
     }
 
     async fn delete(&self, id: &Uuid) -> Result<bool> {
@@ -533,6 +800,53 @@ impl Repository<Credential> for CredentialRepository {
     }
 }
 
+/// Insert every column of `credential`, through the pool or an open transaction
+async fn insert_credential<'e, E>(executor: E, credential: &Credential) -> Result<()>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let tags_json = serde_json::to_string(&credential.tags)
+        .map_err(|e| PersonaError::Database(format!("Failed to serialize tags: {}", e)))?;
+
+    let metadata_json = serde_json::to_string(&credential.metadata)
+        .map_err(|e| PersonaError::Database(format!("Failed to serialize metadata: {}", e)))?;
+
+    sqlx::query(
+        r#"
+            INSERT INTO credentials (
+                id, identity_id, name, credential_type, security_level, url, username,
+                encrypted_data, wrapped_item_key, notes, tags, metadata, created_at, updated_at,
+                last_accessed, is_active, is_favorite, linked_totp_id, version, deleted_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+    )
+    .bind(credential.id.to_string())
+    .bind(credential.identity_id.to_string())
+    .bind(&credential.name)
+    .bind(credential.credential_type.to_string())
+    .bind(credential.security_level.to_string())
+    .bind(&credential.url)
+    .bind(&credential.username)
+    .bind(&credential.encrypted_data)
+    .bind(&credential.wrapped_item_key)
+    .bind(&credential.notes)
+    .bind(&tags_json)
+    .bind(&metadata_json)
+    .bind(credential.created_at.to_rfc3339())
+    .bind(credential.updated_at.to_rfc3339())
+    .bind(credential.last_accessed.map(|dt| dt.to_rfc3339()))
+    .bind(credential.is_active)
+    .bind(credential.is_favorite)
+    .bind(credential.linked_totp_id.map(|id| id.to_string()))
+    .bind(credential.version)
+    .bind(credential.deleted_at.map(|dt| dt.to_rfc3339()))
+    .execute(executor)
+    .await
+    .map_err(|e| PersonaError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
 /// Workspace repository (aligns with initial schema for MVP; supports v2 if available)
 pub struct WorkspaceRepository {
     db: Database,
@@ -810,6 +1124,20 @@ impl AuditLogRepository {
         Ok(res.rows_affected())
     }
 
+    /// Detach audit logs from every credential of an identity before the identity (and with it
+    /// its credentials) is deleted.
+    pub async fn clear_identity_credential_references(&self, identity_id: &Uuid) -> Result<u64> {
+        let res = sqlx::query(
+            "UPDATE audit_logs SET credential_id = NULL \
+             WHERE credential_id IN (SELECT id FROM credentials WHERE identity_id = ?)",
+        )
+        .bind(identity_id.to_string())
+        .execute(self.db.pool())
+        .await
+        .map_err(|e| PersonaError::Database(e.to_string()))?;
+        Ok(res.rows_affected())
+    }
+
     /// Detach audit logs from a credential before deleting the credential.
     pub async fn clear_credential_reference(&self, credential_id: &Uuid) -> Result<u64> {
         let res = sqlx::query("UPDATE audit_logs SET credential_id = NULL WHERE credential_id = ?")
@@ -826,7 +1154,7 @@ impl AuditLogRepository {
             r#"
             SELECT id, user_id, identity_id, credential_id, action, resource_type,
                    resource_id, ip_address, user_agent, success, error_message,
-                   metadata, timestamp
+                   metadata, timestamp, session_id
             FROM audit_logs WHERE user_id = ? ORDER BY timestamp DESC
             "#,
         )
@@ -848,7 +1176,7 @@ impl AuditLogRepository {
             r#"
             SELECT id, user_id, identity_id, credential_id, action, resource_type,
                    resource_id, ip_address, user_agent, success, error_message,
-                   metadata, timestamp
+                   metadata, timestamp, session_id
             FROM audit_logs WHERE identity_id = ? ORDER BY timestamp DESC
             "#,
         )
@@ -870,7 +1198,7 @@ impl AuditLogRepository {
             r#"
             SELECT id, user_id, identity_id, credential_id, action, resource_type,
                    resource_id, ip_address, user_agent, success, error_message,
-                   metadata, timestamp
+                   metadata, timestamp, session_id
             FROM audit_logs WHERE action = ? ORDER BY timestamp DESC
             "#,
         )
@@ -892,7 +1220,7 @@ impl AuditLogRepository {
             r#"
             SELECT id, user_id, identity_id, credential_id, action, resource_type,
                    resource_id, ip_address, user_agent, success, error_message,
-                   metadata, timestamp
+                   metadata, timestamp, session_id
             FROM audit_logs WHERE success = 0 ORDER BY timestamp DESC
             "#,
         )
@@ -930,7 +1258,7 @@ impl AuditLogRepository {
             r#"
             SELECT id, user_id, identity_id, credential_id, action, resource_type,
                    resource_id, ip_address, user_agent, success, error_message,
-                   metadata, timestamp
+                   metadata, timestamp, session_id
             FROM audit_logs WHERE action IN ({}) ORDER BY timestamp DESC
             "#,
             placeholders
@@ -963,7 +1291,7 @@ impl AuditLogRepository {
             r#"
             SELECT id, user_id, identity_id, credential_id, action, resource_type,
                    resource_id, ip_address, user_agent, success, error_message,
-                   metadata, timestamp
+                   metadata, timestamp, session_id
             FROM audit_logs WHERE timestamp BETWEEN ? AND ? ORDER BY timestamp DESC
             "#,
         )
@@ -986,7 +1314,7 @@ impl AuditLogRepository {
             r#"
             SELECT id, user_id, identity_id, credential_id, action, resource_type,
                    resource_id, ip_address, user_agent, success, error_message,
-                   metadata, timestamp
+                   metadata, timestamp, session_id
             FROM audit_logs WHERE ip_address = ? ORDER BY timestamp DESC
             "#,
         )
@@ -1127,8 +1455,8 @@ impl Repository<AuditLog> for AuditLogRepository {
             INSERT INTO audit_logs (
                 id, user_id, identity_id, credential_id, action, resource_type,
                 resource_id, ip_address, user_agent, success, error_message,
-                metadata, timestamp
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                metadata, timestamp, session_id
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(log.id.to_string())
@@ -1144,6 +1472,7 @@ impl Repository<AuditLog> for AuditLogRepository {
         .bind(&log.error_message)
         .bind(&metadata_json)
         .bind(log.timestamp.to_rfc3339())
+        .bind(&log.session_id)
         .execute(self.db.pool())
         .await
         .map_err(|e| PersonaError::Database(e.to_string()))?;
@@ -1156,7 +1485,7 @@ impl Repository<AuditLog> for AuditLogRepository {
             r#"
             SELECT id, user_id, identity_id, credential_id, action, resource_type,
                    resource_id, ip_address, user_agent, success, error_message,
-                   metadata, timestamp
+                   metadata, timestamp, session_id
             FROM audit_logs WHERE id = ?
             "#,
         )
@@ -1176,7 +1505,7 @@ impl Repository<AuditLog> for AuditLogRepository {
             r#"
             SELECT id, user_id, identity_id, credential_id, action, resource_type,
                    resource_id, ip_address, user_agent, success, error_message,
-                   metadata, timestamp
+                   metadata, timestamp, session_id
             FROM audit_logs ORDER BY timestamp DESC LIMIT 1000
             "#,
         )
@@ -1239,6 +1568,99 @@ impl Repository<AuditLog> for AuditLogRepository {
     }
 }
 
+/// Credential field a search term matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialSearchField {
+    Notes,
+    Tags,
+    Url,
+    Username,
+    Name,
+}
+
+impl CredentialSearchField {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Username => "username",
+            Self::Url => "url",
+            Self::Tags => "tags",
+            Self::Notes => "notes",
+        }
+    }
+
+    /// Score contributed by one term matching this field
+    pub fn weight(self) -> u32 {
+        match self {
+            Self::Name => 50,
+            Self::Username => 40,
+            Self::Url => 30,
+            Self::Tags => 20,
+            Self::Notes => 10,
+        }
+    }
+}
+
+/// Bonus for a name equal to the whole query, so exact names outrank everything else
+pub const EXACT_NAME_SCORE: u32 = 100;
+
+/// A credential returned by [`CredentialRepository::search_full_text`]
+#[derive(Debug, Clone)]
+pub struct CredentialSearchHit {
+    pub credential: Credential,
+    /// Sum over terms of the best [`CredentialSearchField::weight`] each matched, plus
+    /// [`EXACT_NAME_SCORE`] for an exact name; higher is more relevant
+    pub score: u32,
+    /// Highest-weighted field any term matched; `None` for an empty query
+    pub matched_field: Option<CredentialSearchField>,
+}
+
+fn score_credential(
+    credential: &Credential,
+    query: &str,
+    terms: &[String],
+) -> Option<(u32, CredentialSearchField)> {
+    let lower = |value: Option<&str>| value.map(str::to_lowercase).unwrap_or_default();
+    let fields = [
+        (CredentialSearchField::Name, credential.name.to_lowercase()),
+        (
+            CredentialSearchField::Username,
+            lower(credential.username.as_deref()),
+        ),
+        (CredentialSearchField::Url, lower(credential.url.as_deref())),
+        (
+            CredentialSearchField::Tags,
+            credential.tags.join(" ").to_lowercase(),
+        ),
+        (
+            CredentialSearchField::Notes,
+            lower(credential.notes.as_deref()),
+        ),
+    ];
+
+    let mut score = 0;
+    let mut best = CredentialSearchField::Notes;
+    for term in terms {
+        let field = fields
+            .iter()
+            .find(|(_, value)| value.contains(term.as_str()))
+            .map(|(field, _)| *field)?;
+        score += field.weight();
+        best = best.max(field);
+    }
+    if credential.name.trim().to_lowercase() == query.trim().to_lowercase() {
+        score += EXACT_NAME_SCORE;
+    }
+    Some((score, best))
+}
+
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 /// Audit log statistics
 #[derive(Debug, Clone)]
 pub struct AuditLogStatistics {
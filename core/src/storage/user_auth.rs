@@ -31,7 +31,7 @@ impl UserAuthRepository {
             r#"
             SELECT user_id, master_password_hash, master_key_salt, enabled_factors,
                    failed_attempts, locked_until, last_auth, password_change_required,
-                   created_at, updated_at
+                   recovery_codes, wrapped_vault_key, role, created_at, updated_at
             FROM user_auth LIMIT 1
             "#,
         )
@@ -51,7 +51,7 @@ impl UserAuthRepository {
             r#"
             SELECT user_id, master_password_hash, master_key_salt, enabled_factors,
                    failed_attempts, locked_until, last_auth, password_change_required,
-                   created_at, updated_at
+                   recovery_codes, wrapped_vault_key, role, created_at, updated_at
             FROM user_auth WHERE user_id = ?
             "#,
         )
@@ -70,14 +70,17 @@ impl UserAuthRepository {
     pub async fn create(&self, auth: &UserAuth) -> Result<()> {
         let enabled_factors = serde_json::to_string(&auth.enabled_factors)
             .map_err(|e| PersonaError::Database(format!("Failed to serialize factors: {}", e)))?;
+        let recovery_codes = serde_json::to_string(&auth.recovery_codes).map_err(|e| {
+            PersonaError::Database(format!("Failed to serialize recovery codes: {}", e))
+        })?;
 
         sqlx::query(
             r#"
             INSERT INTO user_auth (
                 user_id, master_password_hash, master_key_salt, enabled_factors,
                 failed_attempts, locked_until, last_auth, password_change_required,
-                created_at, updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                recovery_codes, wrapped_vault_key, role, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(auth.user_id.to_string())
@@ -88,6 +91,9 @@ impl UserAuthRepository {
         .bind(system_time_to_rfc3339(auth.locked_until))
         .bind(system_time_to_rfc3339(auth.last_auth))
         .bind(auth.password_change_required)
+        .bind(recovery_codes)
+        .bind(&auth.wrapped_vault_key)
+        .bind(auth.role.to_string())
         .bind(system_time_to_rfc3339(Some(auth.created_at)).unwrap())
         .bind(system_time_to_rfc3339(Some(auth.updated_at)).unwrap())
         .execute(self.db.pool())
@@ -99,37 +105,7 @@ impl UserAuthRepository {
 
     /// Update an existing user auth record
     pub async fn update(&self, auth: &UserAuth) -> Result<()> {
-        let enabled_factors = serde_json::to_string(&auth.enabled_factors)
-            .map_err(|e| PersonaError::Database(format!("Failed to serialize factors: {}", e)))?;
-
-        sqlx::query(
-            r#"
-            UPDATE user_auth SET
-                master_password_hash = ?,
-                master_key_salt = ?,
-                enabled_factors = ?,
-                failed_attempts = ?,
-                locked_until = ?,
-                last_auth = ?,
-                password_change_required = ?,
-                updated_at = ?
-            WHERE user_id = ?
-            "#,
-        )
-        .bind(&auth.master_password_hash)
-        .bind(&auth.master_key_salt)
-        .bind(enabled_factors)
-        .bind(auth.failed_attempts as i64)
-        .bind(system_time_to_rfc3339(auth.locked_until))
-        .bind(system_time_to_rfc3339(auth.last_auth))
-        .bind(auth.password_change_required)
-        .bind(system_time_to_rfc3339(Some(auth.updated_at)).unwrap())
-        .bind(auth.user_id.to_string())
-        .execute(self.db.pool())
-        .await
-        .map_err(|e| PersonaError::Database(e.to_string()))?;
-
-        Ok(())
+        write_update(self.db.pool(), auth).await
     }
 
     fn row_to_user_auth(&self, row: sqlx::sqlite::SqliteRow) -> Result<UserAuth> {
@@ -152,11 +128,65 @@ impl UserAuthRepository {
         user.locked_until = rfc3339_to_system_time(row.get("locked_until"));
         user.last_auth = rfc3339_to_system_time(row.get("last_auth"));
         user.password_change_required = row.get("password_change_required");
+
+        let codes_json: String = row.get("recovery_codes");
+        user.recovery_codes = serde_json::from_str(&codes_json)
+            .map_err(|e| PersonaError::Database(format!("Invalid recovery codes: {}", e)))?;
+        user.wrapped_vault_key = row.get("wrapped_vault_key");
+        let role: String = row.get("role");
+        user.role = role.parse().map_err(PersonaError::Database)?;
         // created_at/updated_at are informational; keep defaults
         Ok(user)
     }
 }
 
+/// Write every mutable column of `auth`, through the pool or an open transaction
+pub(crate) async fn write_update<'e, E>(executor: E, auth: &UserAuth) -> Result<()>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let enabled_factors = serde_json::to_string(&auth.enabled_factors)
+        .map_err(|e| PersonaError::Database(format!("Failed to serialize factors: {}", e)))?;
+    let recovery_codes = serde_json::to_string(&auth.recovery_codes).map_err(|e| {
+        PersonaError::Database(format!("Failed to serialize recovery codes: {}", e))
+    })?;
+
+    sqlx::query(
+        r#"
+        UPDATE user_auth SET
+            master_password_hash = ?,
+            master_key_salt = ?,
+            enabled_factors = ?,
+            failed_attempts = ?,
+            locked_until = ?,
+            last_auth = ?,
+            password_change_required = ?,
+            recovery_codes = ?,
+            wrapped_vault_key = ?,
+            role = ?,
+            updated_at = ?
+        WHERE user_id = ?
+        "#,
+    )
+    .bind(&auth.master_password_hash)
+    .bind(&auth.master_key_salt)
+    .bind(enabled_factors)
+    .bind(auth.failed_attempts as i64)
+    .bind(system_time_to_rfc3339(auth.locked_until))
+    .bind(system_time_to_rfc3339(auth.last_auth))
+    .bind(auth.password_change_required)
+    .bind(recovery_codes)
+    .bind(&auth.wrapped_vault_key)
+    .bind(auth.role.to_string())
+    .bind(system_time_to_rfc3339(Some(auth.updated_at)).unwrap())
+    .bind(auth.user_id.to_string())
+    .execute(executor)
+    .await
+    .map_err(|e| PersonaError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
 fn system_time_to_rfc3339(time: Option<SystemTime>) -> Option<String> {
     time.map(|t| {
         let datetime: chrono::DateTime<chrono::Utc> = t.into();
@@ -0,0 +1,219 @@
+//! Advisory lock for exclusive vault operations.
+//!
+//! SQLite serializes individual writes, but operations such as migrations and imports make
+//! many writes that must not interleave with another process doing the same. Those operations
+//! hold a lock file in the workspace directory recording the owning PID. A lock left behind by a
+//! process that no longer runs is treated as stale and taken over.
+
+use crate::storage::permissions::create_private_file;
+use crate::{PersonaError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Lock file name inside the workspace directory
+pub const VAULT_LOCK_FILE: &str = ".persona.lock";
+
+/// Who holds a vault lock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    /// Operation the lock was taken for, e.g. `migrate`
+    pub operation: String,
+    pub acquired_at: DateTime<Utc>,
+}
+
+/// Held exclusive lock; released when dropped
+#[derive(Debug)]
+pub struct VaultLock {
+    path: PathBuf,
+    holder: LockHolder,
+}
+
+impl VaultLock {
+    /// Take the lock in `directory` for `operation`.
+    ///
+    /// Fails with `PersonaError::Conflict` while a running process holds it.
+    pub fn acquire(directory: &Path, operation: &str) -> Result<Self> {
+        let path = directory.join(VAULT_LOCK_FILE);
+        let holder = LockHolder {
+            pid: std::process::id(),
+            operation: operation.to_string(),
+            acquired_at: Utc::now(),
+        };
+
+        // One retry: the first attempt may find a stale lock and remove it
+        for _ in 0..2 {
+            if try_create(&path, &holder)? {
+                return Ok(Self { path, holder });
+            }
+            match read_holder(&path) {
+                Some(current) if process_alive(current.pid) => {
+                    return Err(PersonaError::Conflict(format!(
+                        "Vault is in use by PID {} ({} since {}); try again when it finishes",
+                        current.pid,
+                        current.operation,
+                        current.acquired_at.format("%H:%M:%S UTC")
+                    ))
+                    .into());
+                }
+                stale => {
+                    // Only remove what was judged stale, not a lock another process just took
+                    if read_holder(&path) == stale {
+                        let _ = fs::remove_file(&path);
+                    }
+                }
+            }
+        }
+        Err(PersonaError::Conflict(format!(
+            "Could not take the vault lock at {}",
+            path.display()
+        ))
+        .into())
+    }
+
+    /// The live process holding the lock in `directory`, if any
+    pub fn holder(directory: &Path) -> Option<LockHolder> {
+        read_holder(&directory.join(VAULT_LOCK_FILE)).filter(|holder| process_alive(holder.pid))
+    }
+
+    /// Path of the lock file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for VaultLock {
+    fn drop(&mut self) {
+        // Leave a lock that was taken over (after being judged stale) to its new owner
+        if read_holder(&self.path).as_ref() == Some(&self.holder) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Atomically create the lock file with `holder` in it; `false` if it already exists
+fn try_create(path: &Path, holder: &LockHolder) -> Result<bool> {
+    // Write a private temp file first and hard-link it into place, so no reader ever sees a
+    // lock file without its owner
+    let temp = path.with_extension(format!("{}.tmp", holder.pid));
+    let contents = serde_json::to_vec(holder)?;
+    let _ = fs::remove_file(&temp);
+    create_private_file(&temp)?;
+    fs::write(&temp, contents).map_err(PersonaError::from)?;
+
+    let linked = fs::hard_link(&temp, path);
+    let _ = fs::remove_file(&temp);
+    match linked {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(PersonaError::Io(format!("{}: {}", path.display(), e)).into()),
+    }
+}
+
+fn read_holder(path: &Path) -> Option<LockHolder> {
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        // Out of range for this system, so no such process
+        return false;
+    };
+    // Signal 0 only checks that the process exists and could be signalled.
+    // SAFETY: kill(2) with signal 0 sends nothing and does not touch our memory.
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    // EPERM means the process exists but belongs to another user; only ESRCH means it is gone,
+    // and anything else errs on the side of the lock being held
+    std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+#[cfg(windows)]
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH", "/FO", "CSV"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains(&format!("\"{}\"", pid)))
+        .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_acquirer_is_refused_while_held() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock = VaultLock::acquire(dir.path(), "migrate").unwrap();
+        assert!(lock.path().exists());
+
+        let err = VaultLock::acquire(dir.path(), "import").unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains(&format!("PID {}", std::process::id())));
+        assert!(message.contains("migrate"));
+        assert_eq!(VaultLock::holder(dir.path()).unwrap().operation, "migrate");
+
+        drop(lock);
+        assert!(VaultLock::holder(dir.path()).is_none());
+        VaultLock::acquire(dir.path(), "import").unwrap();
+    }
+
+    #[test]
+    fn stale_lock_from_dead_process_is_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let stale = LockHolder {
+            pid: dead_pid(),
+            operation: "import".to_string(),
+            acquired_at: Utc::now(),
+        };
+        fs::write(
+            dir.path().join(VAULT_LOCK_FILE),
+            serde_json::to_vec(&stale).unwrap(),
+        )
+        .unwrap();
+        assert!(VaultLock::holder(dir.path()).is_none());
+
+        let lock = VaultLock::acquire(dir.path(), "migrate").unwrap();
+        assert_eq!(
+            VaultLock::holder(dir.path()).unwrap().pid,
+            std::process::id()
+        );
+        drop(lock);
+        assert!(!dir.path().join(VAULT_LOCK_FILE).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn processes_of_other_users_count_as_alive() {
+        assert!(process_alive(std::process::id()));
+        // init is owned by root: signalling it fails with EPERM unless we are root
+        assert!(process_alive(1));
+        assert!(!process_alive(dead_pid()));
+    }
+
+    #[test]
+    fn unreadable_lock_is_treated_as_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(VAULT_LOCK_FILE), b"garbage").unwrap();
+        VaultLock::acquire(dir.path(), "migrate").unwrap();
+    }
+
+    /// PID of a process that has exited
+    fn dead_pid() -> u32 {
+        let mut child = std::process::Command::new(if cfg!(windows) { "cmd" } else { "true" })
+            .args(if cfg!(windows) {
+                &["/C", "exit"][..]
+            } else {
+                &[][..]
+            })
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+}
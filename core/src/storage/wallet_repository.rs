@@ -1,8 +1,9 @@
 use crate::models::wallet::{
-    BlockchainNetwork, CryptoWallet, SignedTransaction, TransactionRequest, WalletAddress,
-    WalletMetadata, WalletSecurityLevel,
+    BlockchainNetwork, BroadcastStatus, CryptoWallet, NetworkKind, SignedTransaction,
+    TransactionRequest, WalletAddress, WalletMetadata, WalletSecurityLevel, WalletTransactionEvent,
+    WalletTransactionStatus,
 };
-use crate::storage::Database;
+use crate::storage::{fold_name, Database};
 use crate::{PersonaError, PersonaResult};
 use chrono::{DateTime, TimeZone, Utc};
 use serde_json;
@@ -30,7 +31,7 @@ impl CryptoWalletRepository {
     pub async fn find_all(&self) -> PersonaResult<Vec<CryptoWallet>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, identity_id, name, description, network, wallet_type,
+            SELECT id, identity_id, name, description, network, network_kind, wallet_type,
                    derivation_path, extended_public_key, encrypted_private_key,
                    encrypted_mnemonic, watch_only, security_level,
                    created_at, updated_at
@@ -72,11 +73,11 @@ impl CryptoWalletRepository {
         sqlx::query(
             r#"
             INSERT INTO crypto_wallets (
-                id, identity_id, name, description, network, wallet_type,
+                id, identity_id, name, description, network, network_kind, wallet_type,
                 derivation_path, extended_public_key, encrypted_private_key,
                 encrypted_mnemonic, watch_only, security_level,
                 created_at, updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
             "#,
         )
         .bind(wallet.id.to_string())
@@ -84,6 +85,7 @@ impl CryptoWalletRepository {
         .bind(&wallet.name)
         .bind(&wallet.description)
         .bind(serde_json::to_string(&wallet.network)?)
+        .bind(wallet.network_kind.as_str())
         .bind(serde_json::to_string(&wallet.wallet_type)?)
         .bind(&wallet.derivation_path)
         .bind(&wallet.extended_public_key)
@@ -117,7 +119,7 @@ impl CryptoWalletRepository {
     pub async fn find_by_id(&self, id: &Uuid) -> PersonaResult<Option<CryptoWallet>> {
         let row = sqlx::query(
             r#"
-            SELECT id, identity_id, name, description, network, wallet_type,
+            SELECT id, identity_id, name, description, network, network_kind, wallet_type,
                    derivation_path, extended_public_key, encrypted_private_key,
                    encrypted_mnemonic, watch_only, security_level,
                    created_at, updated_at
@@ -149,7 +151,7 @@ impl CryptoWalletRepository {
     pub async fn find_by_identity(&self, identity_id: &Uuid) -> PersonaResult<Vec<CryptoWallet>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, identity_id, name, description, network, wallet_type,
+            SELECT id, identity_id, name, description, network, network_kind, wallet_type,
                    derivation_path, extended_public_key, encrypted_private_key,
                    encrypted_mnemonic, watch_only, security_level,
                    created_at, updated_at
@@ -186,7 +188,7 @@ impl CryptoWalletRepository {
         let network_str = serde_json::to_string(network)?;
         let rows = sqlx::query(
             r#"
-            SELECT id, identity_id, name, description, network, wallet_type,
+            SELECT id, identity_id, name, description, network, network_kind, wallet_type,
                    derivation_path, extended_public_key, encrypted_private_key,
                    encrypted_mnemonic, watch_only, security_level,
                    created_at, updated_at
@@ -223,7 +225,7 @@ impl CryptoWalletRepository {
         let level_str = serde_json::to_string(security_level)?;
         let rows = sqlx::query(
             r#"
-            SELECT id, identity_id, name, description, network, wallet_type,
+            SELECT id, identity_id, name, description, network, network_kind, wallet_type,
                    derivation_path, extended_public_key, encrypted_private_key,
                    encrypted_mnemonic, watch_only, security_level,
                    created_at, updated_at
@@ -252,23 +254,40 @@ impl CryptoWalletRepository {
         Ok(wallets)
     }
 
-    /// Find wallets by name (case-insensitive exact match).
+    /// Find wallets by name, ignoring case and accents (see [`fold_name`]).
     pub async fn find_by_name(&self, name: &str) -> PersonaResult<Vec<CryptoWallet>> {
-        let rows = sqlx::query(
+        let mut rows = sqlx::query(
             r#"
-            SELECT id, identity_id, name, description, network, wallet_type,
+            SELECT id, identity_id, name, description, network, network_kind, wallet_type,
                    derivation_path, extended_public_key, encrypted_private_key,
                    encrypted_mnemonic, watch_only, security_level,
                    created_at, updated_at
             FROM crypto_wallets
-            WHERE LOWER(name) = LOWER($1)
+            WHERE name = $1 COLLATE NOCASE
             ORDER BY created_at DESC
             "#,
         )
-        .bind(name)
+        .bind(name.trim())
         .fetch_all(self.db.pool())
         .await?;
 
+        if rows.is_empty() {
+            let folded = fold_name(name);
+            rows = sqlx::query(
+                r#"
+                SELECT id, identity_id, name, description, network, network_kind, wallet_type,
+                       derivation_path, extended_public_key, encrypted_private_key,
+                       encrypted_mnemonic, watch_only, security_level,
+                       created_at, updated_at
+                FROM crypto_wallets
+                ORDER BY created_at DESC
+                "#,
+            )
+            .fetch_all(self.db.pool())
+            .await?;
+            rows.retain(|row| fold_name(row.get::<&str, _>("name")) == folded);
+        }
+
         let mut wallets = Vec::new();
         for row in rows {
             let mut wallet = self.wallet_from_row(&row)?;
@@ -285,7 +304,7 @@ impl CryptoWalletRepository {
         let like = format!("%{}%", name_pattern.to_lowercase());
         let rows = sqlx::query(
             r#"
-            SELECT id, identity_id, name, description, network, wallet_type,
+            SELECT id, identity_id, name, description, network, network_kind, wallet_type,
                    derivation_path, extended_public_key, encrypted_private_key,
                    encrypted_mnemonic, watch_only, security_level,
                    created_at, updated_at
@@ -314,7 +333,7 @@ impl CryptoWalletRepository {
         let like = format!("{}%", id_prefix);
         let rows = sqlx::query(
             r#"
-            SELECT id, identity_id, name, description, network, wallet_type,
+            SELECT id, identity_id, name, description, network, network_kind, wallet_type,
                    derivation_path, extended_public_key, encrypted_private_key,
                    encrypted_mnemonic, watch_only, security_level,
                    created_at, updated_at
@@ -346,7 +365,7 @@ impl CryptoWalletRepository {
                 name = $2, description = $3, network = $4, wallet_type = $5,
                 derivation_path = $6, extended_public_key = $7, encrypted_private_key = $8,
                 encrypted_mnemonic = $9, watch_only = $10, security_level = $11,
-                updated_at = $12
+                updated_at = $12, network_kind = $13
             WHERE id = $1
             "#,
         )
@@ -362,6 +381,7 @@ impl CryptoWalletRepository {
         .bind(wallet.watch_only)
         .bind(serde_json::to_string(&wallet.security_level)?)
         .bind(wallet.updated_at.timestamp())
+        .bind(wallet.network_kind.as_str())
         .execute(self.db.pool())
         .await?;
 
@@ -472,6 +492,29 @@ impl CryptoWalletRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Record a freshly fetched address balance (smallest unit of the network)
+    pub async fn update_address_balance(
+        &self,
+        wallet_id: &Uuid,
+        address: &str,
+        balance: &str,
+    ) -> PersonaResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE wallet_addresses SET
+                balance = $2
+            WHERE wallet_id = $1 AND address = $3
+            "#,
+        )
+        .bind(wallet_id.to_string())
+        .bind(balance)
+        .bind(address)
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Create transaction request
     pub async fn create_transaction_request(
         &self,
@@ -482,8 +525,8 @@ impl CryptoWalletRepository {
             INSERT INTO transaction_requests (
                 id, wallet_id, network, from_address, to_address, amount, fee,
                 gas_price, gas_limit, nonce, memo, raw_transaction_data,
-                required_signatures, created_at, expires_at, metadata, status
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, 'pending')
+                required_signatures, created_at, expires_at, metadata, chain_id, status
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, 'pending')
             "#,
         )
         .bind(request.id.to_string())
@@ -502,9 +545,19 @@ impl CryptoWalletRepository {
         .bind(request.created_at.timestamp())
         .bind(request.expires_at.map(|d| d.timestamp()))
         .bind(serde_json::to_string(&request.metadata)?)
+        .bind(request.chain_id.map(|id| id as i64))
         .execute(self.db.pool())
         .await?;
 
+        self.record_transaction_status(
+            &request.wallet_id,
+            &request.id,
+            WalletTransactionStatus::Created,
+            None,
+            None,
+        )
+        .await?;
+
         // Return the request as-is since we just inserted it
         Ok(request.clone())
     }
@@ -534,9 +587,240 @@ impl CryptoWalletRepository {
         .execute(self.db.pool())
         .await?;
 
+        sqlx::query(
+            "UPDATE transaction_requests SET signed_at = $1, status = 'signed' WHERE id = $2",
+        )
+        .bind(signed_tx.signed_at.timestamp())
+        .bind(signed_tx.request.id.to_string())
+        .execute(self.db.pool())
+        .await?;
+
+        let request = &signed_tx.request;
+        // Requests stored before history tracking have no `created` event yet
+        if self.latest_transaction_event(&request.id).await?.is_none() {
+            self.record_transaction_status(
+                &request.wallet_id,
+                &request.id,
+                WalletTransactionStatus::Created,
+                None,
+                None,
+            )
+            .await?;
+        }
+        self.record_transaction_status(
+            &request.wallet_id,
+            &request.id,
+            WalletTransactionStatus::Signed,
+            Some(&signed_tx.transaction_hash),
+            None,
+        )
+        .await?;
+
         Ok(signed_tx.clone())
     }
 
+    /// Store the broadcast outcome of a signed transaction and record it in the history
+    pub async fn record_broadcast(
+        &self,
+        signed_tx_id: &Uuid,
+        status: &BroadcastStatus,
+    ) -> PersonaResult<()> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, request, signatures, raw_signed_transaction, transaction_hash,
+                   signed_at, broadcast_status
+            FROM signed_transactions
+            WHERE id = $1
+            "#,
+        )
+        .bind(signed_tx_id.to_string())
+        .fetch_optional(self.db.pool())
+        .await?
+        .ok_or_else(|| {
+            PersonaError::NotFound(format!("Signed transaction {} not found", signed_tx_id))
+        })?;
+        let signed_tx = self.signed_transaction_from_row(&row)?;
+
+        let (confirmations, block_height, confirmed_at) = match status {
+            BroadcastStatus::BroadcastSuccess {
+                confirmations,
+                block_height,
+                confirmed_at,
+                ..
+            } => (
+                *confirmations as i64,
+                block_height.map(|h| h as i64),
+                confirmed_at.map(|d| d.timestamp()),
+            ),
+            _ => (0, None, None),
+        };
+        sqlx::query(
+            r#"
+            UPDATE signed_transactions
+            SET broadcast_status = $1, confirmations = $2, block_height = $3, confirmed_at = $4
+            WHERE id = $5
+            "#,
+        )
+        .bind(serde_json::to_string(status)?)
+        .bind(confirmations)
+        .bind(block_height)
+        .bind(confirmed_at)
+        .bind(signed_tx_id.to_string())
+        .execute(self.db.pool())
+        .await?;
+
+        let request = &signed_tx.request;
+        let current = self
+            .latest_transaction_event(&request.id)
+            .await?
+            .map(|event| event.status);
+        let mut transitions = Vec::new();
+        match status {
+            BroadcastStatus::NotBroadcast | BroadcastStatus::Broadcasting => {}
+            BroadcastStatus::BroadcastSuccess {
+                hash,
+                block_height,
+                confirmed_at,
+                ..
+            } => {
+                if current == Some(WalletTransactionStatus::Signed) {
+                    transitions.push((
+                        WalletTransactionStatus::Broadcast,
+                        Some(hash.as_str()),
+                        None,
+                    ));
+                }
+                let confirmed = block_height.is_some() || confirmed_at.is_some();
+                if confirmed && current != Some(WalletTransactionStatus::Confirmed) {
+                    transitions.push((
+                        WalletTransactionStatus::Confirmed,
+                        Some(hash.as_str()),
+                        None,
+                    ));
+                }
+            }
+            BroadcastStatus::BroadcastFailed { error, .. } => {
+                if !current.is_some_and(|status| status.is_final()) {
+                    transitions.push((WalletTransactionStatus::Failed, None, Some(error.as_str())));
+                }
+            }
+        }
+
+        for (next, hash, detail) in transitions {
+            self.record_transaction_status(&request.wallet_id, &request.id, next, hash, detail)
+                .await?;
+            sqlx::query("UPDATE transaction_requests SET status = $1 WHERE id = $2")
+                .bind(next.as_str())
+                .bind(request.id.to_string())
+                .execute(self.db.pool())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Append a status transition to a transaction's history.
+    ///
+    /// The first event of a transaction must be `Created`, and later events must follow
+    /// Created → Signed → Broadcast → Confirmed, with Failed allowed from any open state.
+    pub async fn record_transaction_status(
+        &self,
+        wallet_id: &Uuid,
+        request_id: &Uuid,
+        status: WalletTransactionStatus,
+        transaction_hash: Option<&str>,
+        detail: Option<&str>,
+    ) -> PersonaResult<WalletTransactionEvent> {
+        let previous = self.latest_transaction_event(request_id).await?;
+        let allowed = match &previous {
+            Some(prev) => prev.status.can_transition_to(status),
+            None => status == WalletTransactionStatus::Created,
+        };
+        if !allowed {
+            let from = previous
+                .as_ref()
+                .map(|prev| prev.status.to_string())
+                .unwrap_or_else(|| "none".to_string());
+            return Err(PersonaError::InvalidInput(format!(
+                "Invalid transaction status transition for {}: {} -> {}",
+                request_id, from, status
+            )));
+        }
+
+        let event = WalletTransactionEvent {
+            id: Uuid::new_v4(),
+            wallet_id: *wallet_id,
+            request_id: *request_id,
+            status,
+            // Keep the hash on later events once it is known
+            transaction_hash: transaction_hash
+                .map(str::to_string)
+                .or_else(|| previous.and_then(|prev| prev.transaction_hash)),
+            detail: detail.map(str::to_string),
+            created_at: Utc::now(),
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO wallet_transactions (
+                id, wallet_id, request_id, status, transaction_hash, detail, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(event.id.to_string())
+        .bind(event.wallet_id.to_string())
+        .bind(event.request_id.to_string())
+        .bind(event.status.as_str())
+        .bind(&event.transaction_hash)
+        .bind(&event.detail)
+        .bind(event.created_at.timestamp())
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Transaction history of a wallet, oldest event first
+    pub async fn get_transaction_history(
+        &self,
+        wallet_id: &Uuid,
+    ) -> PersonaResult<Vec<WalletTransactionEvent>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, wallet_id, request_id, status, transaction_hash, detail, created_at
+            FROM wallet_transactions
+            WHERE wallet_id = $1
+            ORDER BY created_at ASC, rowid ASC
+            "#,
+        )
+        .bind(wallet_id.to_string())
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.iter()
+            .map(|row| self.transaction_event_from_row(row))
+            .collect()
+    }
+
+    async fn latest_transaction_event(
+        &self,
+        request_id: &Uuid,
+    ) -> PersonaResult<Option<WalletTransactionEvent>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, wallet_id, request_id, status, transaction_hash, detail, created_at
+            FROM wallet_transactions
+            WHERE request_id = $1
+            ORDER BY created_at DESC, rowid DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(request_id.to_string())
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.map(|row| self.transaction_event_from_row(&row))
+            .transpose()
+    }
+
     /// Get pending transaction requests for a wallet
     pub async fn get_pending_requests(
         &self,
@@ -546,7 +830,7 @@ impl CryptoWalletRepository {
             r#"
             SELECT id, wallet_id, network, from_address, to_address, amount, fee,
                    gas_price, gas_limit, nonce, memo, raw_transaction_data,
-                   required_signatures, created_at, signed_at, expires_at, metadata, status
+                   required_signatures, created_at, signed_at, expires_at, metadata, chain_id, status
             FROM transaction_requests
             WHERE wallet_id = $1 AND signed_at IS NULL
             ORDER BY created_at DESC
@@ -564,6 +848,16 @@ impl CryptoWalletRepository {
         Ok(requests)
     }
 
+    /// Whether any wallet has previously created a transaction paying `address`
+    pub async fn has_transactions_to(&self, address: &str) -> PersonaResult<bool> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM transaction_requests WHERE to_address = $1")
+                .bind(address)
+                .fetch_one(self.db.pool())
+                .await?;
+        Ok(count > 0)
+    }
+
     /// Get transaction statistics for a wallet
     pub async fn get_transaction_stats(
         &self,
@@ -597,6 +891,7 @@ impl CryptoWalletRepository {
         let id_str: String = row.get("id");
         let identity_id_str: String = row.get("identity_id");
         let network_str: String = row.get("network");
+        let network_kind_str: String = row.get("network_kind");
         let wallet_type_str: String = row.get("wallet_type");
         let security_level_str: String = row.get("security_level");
         let created_at_ts: i64 = row.get("created_at");
@@ -608,6 +903,9 @@ impl CryptoWalletRepository {
             name: row.get("name"),
             description: row.get("description"),
             network: serde_json::from_str(&network_str)?,
+            network_kind: NetworkKind::parse(&network_kind_str).ok_or_else(|| {
+                PersonaError::Database(format!("Invalid network kind: {}", network_kind_str))
+            })?,
             wallet_type: serde_json::from_str(&wallet_type_str)?,
             derivation_path: row.get("derivation_path"),
             extended_public_key: row.get("extended_public_key"),
@@ -805,6 +1103,7 @@ impl CryptoWalletRepository {
             id: Uuid::parse_str(&id_str).map_err(|e| PersonaError::InvalidInput(e.to_string()))?,
             wallet_id: Uuid::parse_str(&wallet_id_str).map_err(|e| PersonaError::InvalidInput(e.to_string()))?,
             network: serde_json::from_str(&network_str)?,
+            chain_id: row.get::<Option<i64>, _>("chain_id").map(|id| id as u64),
             from_address: row.get("from_address"),
             to_address: row.get("to_address"),
             amount: row.get("amount"),
@@ -821,7 +1120,28 @@ impl CryptoWalletRepository {
         })
     }
 
-    #[allow(dead_code)]
+    fn transaction_event_from_row(
+        &self,
+        row: &sqlx::sqlite::SqliteRow,
+    ) -> PersonaResult<WalletTransactionEvent> {
+        let parse_uuid = |column: &str| {
+            let value: String = row.get(column);
+            Uuid::parse_str(&value).map_err(|e| PersonaError::InvalidInput(e.to_string()))
+        };
+        let status_str: String = row.get("status");
+        let created_at_ts: i64 = row.get("created_at");
+
+        Ok(WalletTransactionEvent {
+            id: parse_uuid("id")?,
+            wallet_id: parse_uuid("wallet_id")?,
+            request_id: parse_uuid("request_id")?,
+            status: status_str.parse().map_err(PersonaError::InvalidInput)?,
+            transaction_hash: row.get("transaction_hash"),
+            detail: row.get("detail"),
+            created_at: Utc.timestamp_opt(created_at_ts, 0).unwrap(),
+        })
+    }
+
     fn signed_transaction_from_row(
         &self,
         row: &sqlx::sqlite::SqliteRow,
@@ -919,12 +1239,16 @@ mod tests {
         let by_identity = repo.find_by_identity(&identity_id).await.unwrap();
         assert_eq!(by_identity.len(), 1);
 
+        assert_eq!(found.network_kind, NetworkKind::Mainnet);
+
         // Update
         let mut updated_wallet = found.clone();
         updated_wallet.name = "Updated Wallet".to_string();
+        updated_wallet.network_kind = NetworkKind::Testnet;
         updated_wallet.updated_at = chrono::Utc::now();
         let updated = repo.update(&updated_wallet).await.unwrap();
         assert_eq!(updated.name, "Updated Wallet");
+        assert_eq!(updated.network_kind, NetworkKind::Testnet);
 
         // Delete
         let deleted = repo.delete(&updated.id).await.unwrap();
@@ -935,6 +1259,30 @@ mod tests {
         assert!(found_deleted.is_none());
     }
 
+    #[tokio::test]
+    async fn test_find_by_name_ignores_case_and_accents() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let identity_id = seed_identity(&db).await;
+        let repo = CryptoWalletRepository::new(Arc::new(db));
+
+        let wallet = CryptoWallet::new(
+            identity_id,
+            "Épargne".to_string(),
+            BlockchainNetwork::Bitcoin,
+            WalletType::SingleAddress,
+            vec![1, 2, 3, 4],
+        );
+        repo.create(&wallet).await.unwrap();
+
+        for query in ["Épargne", "épargne", "EPARGNE", " epargne "] {
+            let found = repo.find_by_name(query).await.unwrap();
+            assert_eq!(found.len(), 1, "{}", query);
+            assert_eq!(found[0].id, wallet.id);
+        }
+        assert!(repo.find_by_name("epargn").await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_address_management() {
         let db = Database::in_memory().await.unwrap();
@@ -975,5 +1323,113 @@ mod tests {
             .await
             .unwrap();
         assert!(updated);
+
+        // Record a refreshed balance
+        assert!(repo
+            .update_address_balance(&created.id, &address.address, "5000")
+            .await
+            .unwrap());
+        let reloaded = repo.find_by_id(&created.id).await.unwrap().unwrap();
+        assert_eq!(reloaded.addresses[0].balance.as_deref(), Some("5000"));
+    }
+
+    #[tokio::test]
+    async fn test_transaction_history_tracks_status_transitions() {
+        let db = Database::in_memory().await.unwrap();
+        db.migrate().await.unwrap();
+        let identity_id = seed_identity(&db).await;
+        let repo = CryptoWalletRepository::new(Arc::new(db));
+
+        let wallet = repo
+            .create(&CryptoWallet::new(
+                identity_id,
+                "Spending".to_string(),
+                BlockchainNetwork::Ethereum,
+                WalletType::SingleAddress,
+                vec![1, 2, 3, 4],
+            ))
+            .await
+            .unwrap();
+        let request = TransactionRequest {
+            id: Uuid::new_v4(),
+            wallet_id: wallet.id,
+            network: BlockchainNetwork::Ethereum,
+            chain_id: Some(11_155_111),
+            from_address: "0xfrom".to_string(),
+            to_address: "0xto".to_string(),
+            amount: "1000".to_string(),
+            fee: "21".to_string(),
+            gas_price: None,
+            gas_limit: None,
+            nonce: Some(0),
+            memo: None,
+            raw_transaction_data: None,
+            required_signatures: 1,
+            created_at: chrono::Utc::now(),
+            expires_at: None,
+            metadata: std::collections::HashMap::new(),
+        };
+        assert!(!repo.has_transactions_to("0xto").await.unwrap());
+        repo.create_transaction_request(&request).await.unwrap();
+        assert!(repo.has_transactions_to("0xto").await.unwrap());
+        let pending = repo.get_pending_requests(&wallet.id).await.unwrap();
+        assert_eq!(pending[0].chain_id, Some(11_155_111));
+
+        let signed = SignedTransaction {
+            id: Uuid::new_v4(),
+            request: request.clone(),
+            signatures: Vec::new(),
+            raw_signed_transaction: vec![0xde, 0xad],
+            transaction_hash: "0xhash".to_string(),
+            signed_at: chrono::Utc::now(),
+            broadcast_status: BroadcastStatus::NotBroadcast,
+        };
+        repo.create_signed_transaction(&signed).await.unwrap();
+        assert!(repo
+            .get_pending_requests(&wallet.id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let success = |block_height| BroadcastStatus::BroadcastSuccess {
+            hash: "0xhash".to_string(),
+            block_height,
+            confirmations: 0,
+            confirmed_at: None,
+        };
+        repo.record_broadcast(&signed.id, &success(None))
+            .await
+            .unwrap();
+        repo.record_broadcast(&signed.id, &success(Some(42)))
+            .await
+            .unwrap();
+
+        let history = repo.get_transaction_history(&wallet.id).await.unwrap();
+        let statuses: Vec<_> = history.iter().map(|event| event.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                WalletTransactionStatus::Created,
+                WalletTransactionStatus::Signed,
+                WalletTransactionStatus::Broadcast,
+                WalletTransactionStatus::Confirmed,
+            ]
+        );
+        assert!(history[0].transaction_hash.is_none());
+        assert!(history[1..]
+            .iter()
+            .all(|event| event.transaction_hash.as_deref() == Some("0xhash")));
+
+        // Confirmed is final
+        let err = repo
+            .record_transaction_status(
+                &wallet.id,
+                &request.id,
+                WalletTransactionStatus::Failed,
+                None,
+                Some("late failure"),
+            )
+            .await;
+        assert!(err.is_err());
     }
 }
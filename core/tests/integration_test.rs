@@ -14,8 +14,8 @@ async fn test_complete_identity_workflow() -> Result<()> {
     let mut service = PersonaService::new(db).await?;
 
     // Step 2: Initialize first-time user
-    let master_password = "test_master_password_123";
-    let user_id = service.initialize_user(master_password).await?;
+    let master_password = SecretString::from("test_master_password_123");
+    let user_id = service.initialize_user(&master_password, None).await?;
 
     assert!(service.is_unlocked());
     println!("✓ User initialized with ID: {}", user_id);
@@ -32,7 +32,7 @@ async fn test_complete_identity_workflow() -> Result<()> {
 
     // Step 4: Create a password credential
     let password_data = CredentialData::Password(PasswordCredentialData {
-        password: "test_password_123".to_string(),
+        password: "test_password_123".into(),
         email: Some("test@example.com".to_string()),
         security_questions: vec![],
     });
@@ -57,7 +57,7 @@ async fn test_complete_identity_workflow() -> Result<()> {
     assert!(retrieved_data.is_some());
 
     if let Some(CredentialData::Password(pwd_data)) = retrieved_data {
-        assert_eq!(pwd_data.password, "test_password_123");
+        assert_eq!(pwd_data.password.expose(), "test_password_123");
         assert_eq!(pwd_data.email, Some("test@example.com".to_string()));
         println!("✓ Credential decrypted successfully");
     } else {
@@ -102,7 +102,9 @@ async fn test_credential_encryption() -> Result<()> {
     db.migrate().await?;
 
     let mut service = PersonaService::new(db).await?;
-    let _user_id = service.initialize_user("encryption_test_password").await?;
+    let _user_id = service
+        .initialize_user(&SecretString::from("encryption_test_password"), None)
+        .await?;
 
     // Create identity
     let identity = service
@@ -114,7 +116,7 @@ async fn test_credential_encryption() -> Result<()> {
         (
             "Password Test",
             CredentialData::Password(PasswordCredentialData {
-                password: "super_secret_password".to_string(),
+                password: "super_secret_password".into(),
                 email: Some("work@company.com".to_string()),
                 security_questions: vec![],
             }),
@@ -123,8 +125,8 @@ async fn test_credential_encryption() -> Result<()> {
         (
             "API Key Test",
             CredentialData::ApiKey(ApiKeyData {
-                api_key: "sk-1234567890abcdef".to_string(),
-                api_secret: Some("secret_key_here".to_string()),
+                api_key: "sk-1234567890abcdef".into(),
+                api_secret: Some("secret_key_here".into()),
                 token: None,
                 permissions: vec!["read".to_string(), "write".to_string()],
                 expires_at: None,
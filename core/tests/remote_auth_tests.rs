@@ -1,3 +1,5 @@
+#![cfg(feature = "sync")]
+
 use persona_core::auth::{MockRemoteAuthProvider, RemoteAuthProvider};
 
 #[test]
@@ -0,0 +1,230 @@
+//! Inactivity auto-lock for the desktop backend.
+//!
+//! Every command invocation except status polls counts as activity. A background task locks the
+//! service once the default auto-lock policy's inactivity timeout passes without any, or when
+//! the system sleeps or locks the screen and the policy enables that trigger. It also stops the
+//! SSH agent when the policy forces locking of sensitive operations, and emits `service-locked`
+//! so the UI can return to the unlock screen.
+
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+
+use persona_core::storage::{AutoLockPolicyRepository, Database};
+use persona_core::{
+    AutoLockPolicy, LockEventReceiver, LockTrigger, LockTriggerSettings, PlatformLockTrigger,
+    SystemLockEvent,
+};
+use tauri::{AppHandle, Manager};
+use tokio::sync::watch;
+
+use crate::types::{AppState, AutoLockSettings, ServiceLockedEvent};
+
+/// Commands the UI calls on a timer; they must not keep an idle vault unlocked
+const PASSIVE_COMMANDS: &[&str] = &["is_service_unlocked", "get_ssh_agent_status"];
+
+/// Timeout used when the vault has no default policy, matching `security.auto_lock_timeout`
+const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 300;
+
+/// Last-activity tracker plus the current settings; the lock task sleeps on both
+pub struct AutoLockTimer {
+    activity: StdMutex<Activity>,
+    settings: watch::Sender<AutoLockSettings>,
+}
+
+struct Activity {
+    last: Instant,
+    /// Set once the task has locked, so an idle vault is locked (and announced) only once
+    locked: bool,
+}
+
+impl Default for AutoLockSettings {
+    fn default() -> Self {
+        Self {
+            inactivity_timeout_secs: DEFAULT_IDLE_TIMEOUT_SECS,
+            stop_ssh_agent: false,
+            lock_triggers: LockTriggerSettings::default(),
+        }
+    }
+}
+
+impl From<&AutoLockPolicy> for AutoLockSettings {
+    fn from(policy: &AutoLockPolicy) -> Self {
+        Self {
+            inactivity_timeout_secs: policy.inactivity_timeout_secs,
+            stop_ssh_agent: policy.force_lock_sensitive,
+            lock_triggers: LockTriggerSettings::from(policy),
+        }
+    }
+}
+
+impl Default for AutoLockTimer {
+    fn default() -> Self {
+        Self {
+            activity: StdMutex::new(Activity {
+                last: Instant::now(),
+                locked: false,
+            }),
+            settings: watch::channel(AutoLockSettings::default()).0,
+        }
+    }
+}
+
+impl AutoLockTimer {
+    /// Record activity for `command`, ignoring passive status polls
+    pub fn record_command(&self, command: &str) {
+        if !PASSIVE_COMMANDS.contains(&command) {
+            self.touch();
+        }
+    }
+
+    /// Restart the inactivity countdown
+    pub fn touch(&self) {
+        let mut activity = self.activity.lock().unwrap();
+        activity.last = Instant::now();
+        activity.locked = false;
+    }
+
+    /// The vault is locked; don't lock and announce it again until there is new activity
+    pub fn mark_locked(&self) {
+        self.activity.lock().unwrap().locked = true;
+    }
+
+    /// Swap in new settings; the running task picks them up without a restart
+    pub fn apply(&self, settings: AutoLockSettings) {
+        self.settings.send_replace(settings);
+    }
+
+    /// Time left before the vault should lock, or `None` when it is already due (or locked)
+    fn remaining(&self, timeout: Duration) -> Option<Duration> {
+        let activity = self.activity.lock().unwrap();
+        if activity.locked {
+            return Some(timeout);
+        }
+        timeout.checked_sub(activity.last.elapsed()).filter(|left| !left.is_zero())
+    }
+}
+
+/// Settings from the vault's default auto-lock policy, or the defaults when there is no vault
+/// database (demo mode), no default policy or it can't be read
+pub async fn load_settings(db: Option<Database>) -> AutoLockSettings {
+    let Some(db) = db else {
+        return AutoLockSettings::default();
+    };
+    let policy = AutoLockPolicyRepository::new(Arc::new(db))
+        .get_default_policy()
+        .await;
+    match policy {
+        Ok(Some(policy)) => AutoLockSettings::from(&policy),
+        Ok(None) => AutoLockSettings::default(),
+        Err(e) => {
+            tracing::warn!("Failed to load default auto-lock policy: {}", e);
+            AutoLockSettings::default()
+        }
+    }
+}
+
+/// Run the lock task for the lifetime of the app
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut settings_rx = state.auto_lock.settings.subscribe();
+        let mut system_events = Some(PlatformLockTrigger.subscribe());
+        loop {
+            let settings = *settings_rx.borrow_and_update();
+            // A zero timeout disables idle locking until the policy changes
+            let wait = match settings.inactivity_timeout_secs {
+                0 => None,
+                secs => {
+                    let timeout = Duration::from_secs(secs);
+                    match state.auto_lock.remaining(timeout) {
+                        Some(left) => Some(left),
+                        None => {
+                            state.auto_lock.mark_locked();
+                            lock_service(&app, &state, settings, None).await;
+                            Some(timeout)
+                        }
+                    }
+                }
+            };
+
+            tokio::select! {
+                _ = idle_deadline(wait) => {}
+                changed = settings_rx.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                }
+                event = next_system_event(&mut system_events) => {
+                    lock_service(&app, &state, settings, Some(event)).await;
+                }
+            }
+        }
+    });
+}
+
+async fn idle_deadline(wait: Option<Duration>) {
+    match wait {
+        Some(wait) => tokio::time::sleep(wait).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Next sleep or screen-lock event; waits forever once the platform trigger has stopped
+async fn next_system_event(events: &mut Option<LockEventReceiver>) -> SystemLockEvent {
+    if let Some(receiver) = events {
+        if let Some(event) = receiver.recv().await {
+            return event;
+        }
+        *events = None;
+    }
+    std::future::pending().await
+}
+
+/// Lock for idle time (`event` is `None`) or for a system event the policy enables, then stop
+/// the SSH agent if the policy asks and tell the UI
+async fn lock_service(
+    app: &AppHandle,
+    state: &AppState,
+    settings: AutoLockSettings,
+    event: Option<SystemLockEvent>,
+) {
+    {
+        let mut service_guard = state.service.lock().await;
+        let Some(service) = service_guard.as_mut() else {
+            return;
+        };
+        match event {
+            Some(event) => {
+                if !service.lock_on_system_event(event, settings.lock_triggers) {
+                    return;
+                }
+                state.auto_lock.mark_locked();
+            }
+            None => service.lock(),
+        }
+    }
+    let reason = match event {
+        Some(event) => event.as_str(),
+        None => {
+            tracing::info!("Vault idle for {}s; locking", settings.inactivity_timeout_secs);
+            "inactivity"
+        }
+    };
+
+    let mut ssh_agent_stopped = false;
+    if settings.stop_ssh_agent {
+        if let Some(handle) = state.agent_handle.lock().await.take() {
+            handle.abort();
+            crate::commands::cleanup_agent_state_files();
+            ssh_agent_stopped = true;
+        }
+    }
+
+    let _ = app.emit_all(
+        "service-locked",
+        ServiceLockedEvent {
+            reason: reason.to_string(),
+            ssh_agent_stopped,
+        },
+    );
+}
@@ -1,35 +1,41 @@
+use crate::auto_lock;
 use crate::types::*;
 use persona_core::*;
 use persona_core::models::CredentialType;
 use persona_core::models::wallet::CryptoWallet;
 use persona_core::models::wallet::BlockchainNetwork;
-use persona_core::storage::{CryptoWalletRepository, Database};
-use tauri::{command, State};
+use persona_core::storage::{BackupSchedule, BackupScheduler, CryptoWalletRepository, Database};
+use persona_ssh_agent::status::{agent_state_dir, read_agent_status, PID_FILE, SOCKET_FILE};
+use tauri::{command, AppHandle, Manager, State};
 use tokio::time::{sleep, Duration};
 use uuid::Uuid;
 use std::str::FromStr;
 use std::collections::HashMap;
-use std::path::PathBuf;
 use std::fs;
+use std::path::Path;
 use std::sync::Arc;
-use data_encoding::{BASE32, BASE32_NOPAD};
-use hmac::{Hmac, Mac};
-use sha1::Sha1;
-use sha2::{Sha256, Sha512};
 
 /// Initialize the Persona service with master password
 #[command]
 pub async fn init_service(
     request: InitRequest,
+    app: AppHandle,
     state: State<'_, AppState>,
 ) -> std::result::Result<ApiResponse<bool>, String> {
-    let db_path = request.db_path.unwrap_or_else(|| {
-        let app_data_dir = dirs::data_dir()
-            .unwrap_or_else(|| std::env::current_dir().unwrap())
-            .join("persona");
-        std::fs::create_dir_all(&app_data_dir).ok();
-        app_data_dir.join("persona.db").to_string_lossy().to_string()
-    });
+    if request.demo {
+        return match PersonaService::demo().await {
+            Ok(mut service) => {
+                forward_vault_events(app, &service);
+                apply_auto_lock(&state, &mut service, AutoLockSettings::default());
+                let mut service_guard = state.service.lock().await;
+                *service_guard = Some(service);
+                Ok(ApiResponse::success(true))
+            }
+            Err(e) => Ok(ApiResponse::error(format!("Failed to open demo vault: {}", e))),
+        };
+    }
+
+    let db_path = resolve_db_path(request.db_path);
 
     // Store db_path
     {
@@ -37,21 +43,25 @@ pub async fn init_service(
         *db_path_guard = Some(db_path.clone());
     }
 
-    match Database::from_file(&db_path).await {
+    match vault_database(&state, &db_path).await {
         Ok(db) => {
-            if let Err(e) = db.migrate().await {
-                return Ok(ApiResponse::error(format!("Database migration failed: {}", e)));
-            }
-
-            match PersonaService::new(db).await {
+            match PersonaService::new(db.clone()).await {
                 Ok(mut service) => {
                     // Check if this is first-time setup or existing user
                     let is_first_time = !service.has_users().await.unwrap_or(false);
 
                     if is_first_time {
                         // First-time setup: initialize user with master password
-                        match service.initialize_user(&request.master_password).await {
+                        match service.initialize_user(&request.master_password, None).await {
                             Ok(_user_id) => {
+                                // New vaults get an anti-phishing phrase for the unlock screen
+                                if let Err(e) = service.reset_verification_phrase().await {
+                                    tracing::warn!("Failed to set verification phrase: {}", e);
+                                }
+                                forward_vault_events(app, &service);
+                                start_scheduled_backups(&state, &db_path, &service).await;
+                                let settings = auto_lock::load_settings(Some(db.clone())).await;
+                                apply_auto_lock(&state, &mut service, settings);
                                 let mut service_guard = state.service.lock().await;
                                 *service_guard = Some(service);
                                 Ok(ApiResponse::success(true))
@@ -64,6 +74,10 @@ pub async fn init_service(
                             Ok(auth_result) => {
                                 match auth_result {
                                     persona_core::AuthResult::Success => {
+                                        forward_vault_events(app, &service);
+                                        start_scheduled_backups(&state, &db_path, &service).await;
+                                        let settings = auto_lock::load_settings(Some(db.clone())).await;
+                                        apply_auto_lock(&state, &mut service, settings);
                                         let mut service_guard = state.service.lock().await;
                                         *service_guard = Some(service);
                                         Ok(ApiResponse::success(true))
@@ -93,18 +107,248 @@ pub async fn init_service(
     }
 }
 
+/// Back up the vault on the schedule in `config.toml` beside it, replacing any earlier task.
+///
+/// Results are logged and published as vault events, so the UI hears about failures too.
+async fn start_scheduled_backups(state: &State<'_, AppState>, db_path: &str, service: &PersonaService) {
+    let mut task = state.backup_task.lock().await;
+    if let Some(previous) = task.take() {
+        previous.abort();
+    }
+    let schedule = match BackupSchedule::for_database(Path::new(db_path)) {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("Scheduled backups disabled: {}", e);
+            return;
+        }
+    };
+    match vault_database(state, db_path).await {
+        Ok(db) => *task = Some(BackupScheduler::new(schedule).spawn(db, Some(service.event_bus()))),
+        Err(e) => tracing::warn!("Scheduled backups disabled: {}", e),
+    }
+}
+
+/// Connection pool for the vault at `db_path`, opened and migrated on first use.
+///
+/// Commands share this pool instead of reopening the file, so their reads and writes go through
+/// one set of WAL connections rather than contending for SQLite's locks.
+async fn vault_database(state: &AppState, db_path: &str) -> persona_core::Result<Database> {
+    let mut guard = state.database.lock().await;
+    if let Some((path, db)) = guard.as_ref() {
+        if path == db_path {
+            return Ok(db.clone());
+        }
+    }
+    let db = Database::from_file(db_path).await?;
+    db.migrate().await?;
+    *guard = Some((db_path.to_string(), db.clone()));
+    Ok(db)
+}
+
+/// Database path from the request, or `persona.db` in the app data directory
+fn resolve_db_path(db_path: Option<String>) -> String {
+    db_path.unwrap_or_else(|| {
+        let app_data_dir = dirs::data_dir()
+            .unwrap_or_else(|| std::env::current_dir().unwrap())
+            .join("persona");
+        std::fs::create_dir_all(&app_data_dir).ok();
+        app_data_dir.join("persona.db").to_string_lossy().to_string()
+    })
+}
+
+/// Anti-phishing phrase for the unlock screen; readable before the vault is unlocked
+#[command]
+pub async fn get_verification_phrase(
+    db_path: Option<String>,
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<Option<String>>, String> {
+    {
+        let service_guard = state.service.lock().await;
+        if let Some(service) = service_guard.as_ref() {
+            return match service.verification_phrase().await {
+                Ok(phrase) => Ok(ApiResponse::success(phrase)),
+                Err(e) => Ok(ApiResponse::error(format!("Failed to read verification phrase: {}", e))),
+            };
+        }
+    }
+
+    let db_path = resolve_db_path(db_path);
+    if !std::path::Path::new(&db_path).exists() {
+        return Ok(ApiResponse::success(None));
+    }
+    let db = match vault_database(&state, &db_path).await {
+        Ok(db) => db,
+        Err(e) => return Ok(ApiResponse::error(format!("Database connection failed: {}", e))),
+    };
+    match PersonaService::new(db).await {
+        Ok(service) => match service.verification_phrase().await {
+            Ok(phrase) => Ok(ApiResponse::success(phrase)),
+            Err(e) => Ok(ApiResponse::error(format!("Failed to read verification phrase: {}", e))),
+        },
+        Err(e) => Ok(ApiResponse::error(format!("Failed to create service: {}", e))),
+    }
+}
+
+/// Replace the verification phrase with a new random one
+#[command]
+pub async fn reset_verification_phrase(
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<String>, String> {
+    let service_guard = state.service.lock().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    match service.reset_verification_phrase().await {
+        Ok(phrase) => Ok(ApiResponse::success(phrase)),
+        Err(e) => Ok(ApiResponse::error(format!("Failed to reset verification phrase: {}", e))),
+    }
+}
+
+/// Fingerprint of the vault, shown when pairing so devices can confirm they share it.
+/// Works before unlock; `None` when no vault exists yet.
+#[command]
+pub async fn get_vault_fingerprint(
+    db_path: Option<String>,
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<Option<VaultFingerprint>>, String> {
+    {
+        let service_guard = state.service.lock().await;
+        if let Some(service) = service_guard.as_ref() {
+            return match service.vault_fingerprint().await {
+                Ok(fingerprint) => Ok(ApiResponse::success(Some(fingerprint))),
+                Err(e) => Ok(ApiResponse::error(format!("Failed to read vault fingerprint: {}", e))),
+            };
+        }
+    }
+
+    let db_path = resolve_db_path(db_path);
+    if !std::path::Path::new(&db_path).exists() {
+        return Ok(ApiResponse::success(None));
+    }
+    let db = match vault_database(&state, &db_path).await {
+        Ok(db) => db,
+        Err(e) => return Ok(ApiResponse::error(format!("Database connection failed: {}", e))),
+    };
+    match PersonaService::new(db).await {
+        Ok(service) => match service.vault_fingerprint().await {
+            Ok(fingerprint) => Ok(ApiResponse::success(Some(fingerprint))),
+            Err(e) => Ok(ApiResponse::error(format!("Failed to read vault fingerprint: {}", e))),
+        },
+        Err(e) => Ok(ApiResponse::error(format!("Failed to create service: {}", e))),
+    }
+}
+
+/// Version, git revision and enabled features of the bundled core
+#[command]
+pub async fn get_build_info() -> std::result::Result<ApiResponse<BuildInfo>, String> {
+    Ok(ApiResponse::success(build_info()))
+}
+
+/// Reclaim free space in the vault database and integrity-check it
+#[command]
+pub async fn vacuum_vault(
+    incremental: bool,
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<VacuumReport>, String> {
+    let service_guard = state.service.lock().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    match service.compact_vault(incremental).await {
+        Ok(report) => Ok(ApiResponse::success(report)),
+        Err(e) => Ok(ApiResponse::error(format!("Failed to vacuum vault: {}", e))),
+    }
+}
+
+/// Forward vault change events to the frontend as `vault-event`.
+/// A lagging receiver emits `{"type": "resync"}` so the UI falls back to a full reload.
+fn forward_vault_events(app: AppHandle, service: &PersonaService) {
+    let mut events = service.subscribe_events();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    let _ = app.emit_all("vault-event", &event);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                    let _ = app.emit_all("vault-event", serde_json::json!({ "type": "resync" }));
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Register a custom credential type schema so its credentials render field-by-field
+#[command]
+pub async fn register_custom_credential_type(
+    schema: CustomCredentialSchema,
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<bool>, String> {
+    let mut service_guard = state.service.lock().await;
+    match service_guard.as_mut() {
+        Some(service) => match service.register_custom_credential_type(schema) {
+            Ok(()) => Ok(ApiResponse::success(true)),
+            Err(e) => Ok(ApiResponse::error(format!("Invalid custom credential type: {}", e))),
+        },
+        None => Ok(ApiResponse::error("Service not initialized".to_string())),
+    }
+}
+
 /// Lock the service
 #[command]
 pub async fn lock_service(state: State<'_, AppState>) -> std::result::Result<ApiResponse<bool>, String> {
     let mut service_guard = state.service.lock().await;
     if let Some(service) = service_guard.as_mut() {
         service.lock();
+        state.auto_lock.mark_locked();
         Ok(ApiResponse::success(true))
     } else {
         Ok(ApiResponse::error("Service not initialized".to_string()))
     }
 }
 
+/// Re-read the default auto-lock policy and apply it to the running lock timer.
+/// Call after the policy changes; the new timeout takes effect without a restart.
+#[command]
+pub async fn reload_auto_lock_policy(
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<AutoLockSettings>, String> {
+    let db_path = state.db_path.lock().await.clone();
+    let mut service_guard = state.service.lock().await;
+    let service = match service_guard.as_mut() {
+        Some(service) => service,
+        None => return Ok(ApiResponse::error("Service not initialized".to_string())),
+    };
+    // The demo vault lives in memory, so a stored path belongs to some other vault
+    let db = match db_path.filter(|_| !service.is_demo()) {
+        Some(db_path) => match vault_database(&state, &db_path).await {
+            Ok(db) => Some(db),
+            Err(e) => {
+                tracing::warn!("Failed to open the vault for its auto-lock policy: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    let settings = auto_lock::load_settings(db).await;
+    apply_auto_lock(&state, service, settings);
+    Ok(ApiResponse::success(settings))
+}
+
+/// Hand auto-lock settings to the lock timer and keep the service's own expiry check in step
+fn apply_auto_lock(state: &AppState, service: &mut PersonaService, settings: AutoLockSettings) {
+    let timeout = match settings.inactivity_timeout_secs {
+        0 => Duration::MAX,
+        secs => Duration::from_secs(secs),
+    };
+    service.set_auto_lock_timeout(timeout);
+    state.auto_lock.apply(settings);
+}
+
 /// Check if service is unlocked
 #[command]
 pub async fn is_service_unlocked(state: State<'_, AppState>) -> std::result::Result<ApiResponse<bool>, String> {
@@ -246,6 +490,9 @@ pub async fn update_identity(
                             .filter(|t| !t.is_empty())
                             .collect();
                     }
+                    if let Some(version) = request.version {
+                        identity.version = version;
+                    }
 
                     match service.update_identity(&identity).await {
                         Ok(updated_identity) => Ok(ApiResponse::success(updated_identity.into())),
@@ -313,37 +560,31 @@ pub async fn create_credential(
 
                     let credential_data = request.credential_data.to_credential_data();
 
-                    match service.create_credential(
+                    // One insert with every field, so a failure never leaves a half-filled credential
+                    let mut credential = Credential::new(
                         identity_uuid,
                         request.name,
                         credential_type,
                         security_level,
-                        &credential_data,
-                    ).await {
-                        Ok(mut credential) => {
-                            if let Some(url) = request.url {
-                                credential.url = Some(url);
-                            }
-                            if let Some(username) = request.username {
-                                credential.username = Some(username);
-                            }
-                            if let Some(notes) = request.notes {
-                                let trimmed = notes.trim().to_string();
-                                credential.notes = if trimmed.is_empty() { None } else { Some(trimmed) };
-                            }
-                            if let Some(tags) = request.tags {
-                                credential.tags = tags
-                                    .into_iter()
-                                    .map(|t| t.trim().to_string())
-                                    .filter(|t| !t.is_empty())
-                                    .collect();
-                            }
+                        Vec::new(),
+                        None,
+                    );
+                    credential.url = request.url;
+                    credential.username = request.username;
+                    if let Some(notes) = request.notes {
+                        let trimmed = notes.trim().to_string();
+                        credential.notes = if trimmed.is_empty() { None } else { Some(trimmed) };
+                    }
+                    if let Some(tags) = request.tags {
+                        credential.tags = tags
+                            .into_iter()
+                            .map(|t| t.trim().to_string())
+                            .filter(|t| !t.is_empty())
+                            .collect();
+                    }
 
-                            match service.update_credential(&credential).await {
-                                Ok(updated_credential) => Ok(ApiResponse::success(updated_credential.into())),
-                                Err(e) => Ok(ApiResponse::error(format!("Failed to update credential: {}", e))),
-                            }
-                        }
+                    match service.create_credential_full(credential, &credential_data).await {
+                        Ok(credential) => Ok(ApiResponse::success(credential.into())),
                         Err(e) => Ok(ApiResponse::error(format!("Failed to create credential: {}", e))),
                     }
                 }
@@ -384,32 +625,54 @@ pub async fn get_credentials_for_identity(
 #[command]
 pub async fn get_credential_data(
     credential_id: String,
+    master_password: Option<SecretString>,
     state: State<'_, AppState>,
 ) -> std::result::Result<ApiResponse<Option<SerializableCredentialData>>, String> {
-    let service_guard = state.service.lock().await;
-    match service_guard.as_ref() {
+    let mut service_guard = state.service.lock().await;
+    match service_guard.as_mut() {
         Some(service) => {
             match Uuid::from_str(&credential_id) {
                 Ok(uuid) => {
-                    match service.get_credential_data(&uuid).await {
-                        Ok(credential_data) => {
-                            let serializable = credential_data.map(|data| SerializableCredentialData {
-                                credential_type: match &data {
-                                    CredentialData::Password(_) => "Password".to_string(),
-                                    CredentialData::CryptoWallet(_) => "CryptoWallet".to_string(),
-                                    CredentialData::SshKey(_) => "SshKey".to_string(),
-                                    CredentialData::ApiKey(_) => "ApiKey".to_string(),
-                                    CredentialData::BankCard(_) => "BankCard".to_string(),
-                                    CredentialData::ServerConfig(_) => "ServerConfig".to_string(),
-                                    CredentialData::TwoFactor(_) => "TwoFactor".to_string(),
-                                    CredentialData::Raw(_) => "Raw".to_string(),
-                                },
-                                data: credential_data_to_json(&data),
-                            });
-                            Ok(ApiResponse::success(serializable))
+                    // Critical entries need Touch ID/Windows Hello/polkit, or the master password
+                    // when no biometric provider is usable
+                    let revealed = match &master_password {
+                        Some(password) => service
+                            .get_credential_data_with_password(&uuid, password)
+                            .await
+                            .map(CredentialReveal::Revealed),
+                        None => {
+                            let unavailable = MockBiometricProvider {
+                                available: false,
+                                ..Default::default()
+                            };
+                            let provider = platform_biometric_provider();
+                            let provider: &dyn BiometricProvider =
+                                provider.as_deref().unwrap_or(&unavailable);
+                            service.get_credential_data_with_biometric(&uuid, provider).await
+                        }
+                    };
+                    let credential_data = match revealed {
+                        Ok(CredentialReveal::Revealed(data)) => data,
+                        Ok(CredentialReveal::PasswordRequired) => {
+                            return Ok(ApiResponse::error(MASTER_PASSWORD_REQUIRED.to_string()))
+                        }
+                        Err(e) => {
+                            return Ok(ApiResponse::error(format!("Failed to get credential data: {}", e)))
                         }
-                        Err(e) => Ok(ApiResponse::error(format!("Failed to get credential data: {}", e))),
+                    };
+
+                    // Custom types with a registered schema render through the core registry
+                    if let Ok(Some(view)) = service.format_custom_credential(&uuid, false).await {
+                        return Ok(ApiResponse::success(Some(SerializableCredentialData {
+                            credential_type: view.type_name.clone(),
+                            data: serde_json::to_value(&view).unwrap_or_default(),
+                        })));
                     }
+                    let serializable = credential_data.map(|data| SerializableCredentialData {
+                        credential_type: data.type_name().to_string(),
+                        data: credential_data_to_json(&data),
+                    });
+                    Ok(ApiResponse::success(serializable))
                 }
                 Err(_) => Ok(ApiResponse::error("Invalid UUID format".to_string())),
             }
@@ -438,44 +701,183 @@ pub async fn get_totp_code(
     let data = credential_data.ok_or_else(|| "Credential not found".to_string())?;
     match data {
         CredentialData::TwoFactor(tf) => {
-            let secret_bytes = decode_totp_secret(&tf.secret_key)?;
-            let now = chrono::Utc::now();
-            let period = tf.period.max(1) as u64;
-            let timestamp = now.timestamp().max(0) as u64;
-            let counter = timestamp / period;
-            let digits = tf.digits.clamp(4, 10) as u32;
-            let code_num = hotp(&secret_bytes, counter, &tf.algorithm)?;
-            let modulo = 10_u32.pow(digits);
-            let value = code_num % modulo;
-            let code = format!("{:0width$}", value, width = digits as usize);
-            let remaining = (period - (timestamp % period)) as u32;
-
-            Ok(ApiResponse::success(TotpCodeResponse {
-                code,
-                remaining_seconds: remaining,
-                period: tf.period.max(1),
-                digits: tf.digits.clamp(4, 10),
-                algorithm: tf.algorithm,
-                issuer: tf.issuer,
-                account_name: tf.account_name,
-            }))
+            // HOTP entries hand out the next code and persist the advanced counter
+            let current = service
+                .two_factor_code(&uuid)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(ApiResponse::success(totp_response(tf, current)))
         }
         _ => Ok(ApiResponse::error("Credential is not a TwoFactor entry".to_string())),
     }
 }
 
-/// Search credentials
+fn totp_response(tf: TwoFactorData, current: persona_core::otp::TotpCode) -> TotpCodeResponse {
+    TotpCodeResponse {
+        code: current.code,
+        remaining_seconds: current.remaining,
+        period: tf.period.max(1),
+        digits: tf.digits.clamp(4, 10),
+        algorithm: tf.algorithm,
+        issuer: tf.issuer,
+        account_name: tf.account_name,
+        otp_type: tf.otp_type,
+    }
+}
+
+/// Check a code entered for a TOTP credential during enrollment; each code is accepted once
+#[command]
+pub async fn verify_totp_code(
+    credential_id: String,
+    code: String,
+    skew_steps: Option<u32>,
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<TotpVerification>, String> {
+    let service_guard = state.service.lock().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let uuid = Uuid::from_str(&credential_id).map_err(|_| "Invalid UUID format".to_string())?;
+    let skew_steps = skew_steps.unwrap_or(persona_core::otp::DEFAULT_VERIFY_SKEW_STEPS);
+    match service.verify_totp(&uuid, &code, skew_steps).await {
+        Ok(verification) => Ok(ApiResponse::success(verification)),
+        Err(e) => Ok(ApiResponse::error(format!("Failed to verify TOTP code: {}", e))),
+    }
+}
+
+/// Generate the current code of the TOTP credential linked to a password credential
+#[command]
+pub async fn get_linked_totp_code(
+    credential_id: String,
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<Option<TotpCodeResponse>>, String> {
+    let service_guard = state.service.lock().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let uuid = Uuid::from_str(&credential_id).map_err(|_| "Invalid UUID format".to_string())?;
+    match service.linked_totp(&uuid).await {
+        Ok(Some(tf)) => {
+            let current = persona_core::otp::totp_code(&tf).map_err(|e| e.to_string())?;
+            Ok(ApiResponse::success(Some(totp_response(tf, current))))
+        }
+        Ok(None) => Ok(ApiResponse::success(None)),
+        Err(e) => Ok(ApiResponse::error(format!("Failed to get linked TOTP: {}", e))),
+    }
+}
+
+/// Link a TOTP credential to a password credential
+#[command]
+pub async fn link_totp(
+    credential_id: String,
+    totp_id: String,
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<SerializableCredential>, String> {
+    let service_guard = state.service.lock().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let uuid = Uuid::from_str(&credential_id).map_err(|_| "Invalid UUID format".to_string())?;
+    let totp_uuid = Uuid::from_str(&totp_id).map_err(|_| "Invalid UUID format".to_string())?;
+    match service.link_totp(&uuid, &totp_uuid).await {
+        Ok(credential) => Ok(ApiResponse::success(credential.into())),
+        Err(e) => Ok(ApiResponse::error(format!("Failed to link TOTP: {}", e))),
+    }
+}
+
+/// Remove the TOTP link from a password credential
+#[command]
+pub async fn unlink_totp(
+    credential_id: String,
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<SerializableCredential>, String> {
+    let service_guard = state.service.lock().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let uuid = Uuid::from_str(&credential_id).map_err(|_| "Invalid UUID format".to_string())?;
+    match service.unlink_totp(&uuid).await {
+        Ok(credential) => Ok(ApiResponse::success(credential.into())),
+        Err(e) => Ok(ApiResponse::error(format!("Failed to unlink TOTP: {}", e))),
+    }
+}
+
+/// Get the security score of a credential
+#[command]
+pub async fn get_credential_security_score(
+    credential_id: String,
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<Option<SerializableSecurityScore>>, String> {
+    let service_guard = state.service.lock().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let uuid = Uuid::from_str(&credential_id).map_err(|_| "Invalid UUID format".to_string())?;
+    match service.credential_security_score(&uuid).await {
+        Ok(score) => Ok(ApiResponse::success(score.map(SerializableSecurityScore::from))),
+        Err(e) => Ok(ApiResponse::error(format!("Failed to score credential: {}", e))),
+    }
+}
+
+/// Get the vault-wide security score
+#[command]
+pub async fn get_vault_security_score(
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<SerializableVaultSecurityScore>, String> {
+    let service_guard = state.service.lock().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    match service.vault_security_score().await {
+        Ok(vault) => Ok(ApiResponse::success(SerializableVaultSecurityScore {
+            score: vault.score,
+            credentials: vault.credentials.into_iter().map(Into::into).collect(),
+        })),
+        Err(e) => Ok(ApiResponse::error(format!("Failed to score vault: {}", e))),
+    }
+}
+
+/// Audit password credentials for reuse, weakness and age; thresholds default to the core's
+#[command]
+pub async fn get_password_audit_report(
+    min_entropy_bits: Option<f64>,
+    max_age_days: Option<i64>,
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<PasswordAuditReport>, String> {
+    let service_guard = state.service.lock().await;
+    let service = service_guard
+        .as_ref()
+        .ok_or_else(|| "Service not initialized".to_string())?;
+
+    let defaults = PasswordAuditOptions::default();
+    let options = PasswordAuditOptions {
+        min_entropy_bits: min_entropy_bits.unwrap_or(defaults.min_entropy_bits),
+        max_age_days: max_age_days.unwrap_or(defaults.max_age_days),
+    };
+    match service.password_audit_report(&options).await {
+        Ok(report) => Ok(ApiResponse::success(report)),
+        Err(e) => Ok(ApiResponse::error(format!("Failed to audit passwords: {}", e))),
+    }
+}
+
+/// Full-text search over credential names, usernames, URLs, notes and tags, best match first
 #[command]
 pub async fn search_credentials(
     query: String,
     state: State<'_, AppState>,
-) -> std::result::Result<ApiResponse<Vec<SerializableCredential>>, String> {
+) -> std::result::Result<ApiResponse<Vec<SerializableSearchHit>>, String> {
     let service_guard = state.service.lock().await;
     match service_guard.as_ref() {
         Some(service) => {
-            match service.search_credentials(&query).await {
-                Ok(credentials) => {
-                    let serializable: Vec<SerializableCredential> = credentials.into_iter().map(|cred| cred.into()).collect();
+            match service.search_credentials_ranked(&query).await {
+                Ok(hits) => {
+                    let serializable: Vec<SerializableSearchHit> = hits.into_iter().map(|hit| hit.into()).collect();
                     Ok(ApiResponse::success(serializable))
                 }
                 Err(e) => Ok(ApiResponse::error(format!("Failed to search credentials: {}", e))),
@@ -485,71 +887,32 @@ pub async fn search_credentials(
     }
 }
 
-fn hotp(secret: &[u8], counter: u64, algorithm: &str) -> std::result::Result<u32, String> {
-    let msg = counter.to_be_bytes();
-    let algo = algorithm.to_ascii_uppercase();
-
-    let hash = if algo == "SHA256" {
-        type HmacSha256 = Hmac<Sha256>;
-        let mut mac = HmacSha256::new_from_slice(secret).map_err(|_| "Invalid secret".to_string())?;
-        mac.update(&msg);
-        mac.finalize().into_bytes().to_vec()
-    } else if algo == "SHA512" {
-        type HmacSha512 = Hmac<Sha512>;
-        let mut mac = HmacSha512::new_from_slice(secret).map_err(|_| "Invalid secret".to_string())?;
-        mac.update(&msg);
-        mac.finalize().into_bytes().to_vec()
-    } else {
-        type HmacSha1 = Hmac<Sha1>;
-        let mut mac = HmacSha1::new_from_slice(secret).map_err(|_| "Invalid secret".to_string())?;
-        mac.update(&msg);
-        mac.finalize().into_bytes().to_vec()
-    };
-
-    let offset = (hash.last().copied().unwrap_or(0) & 0x0f) as usize;
-    if offset + 4 > hash.len() {
-        return Err("Invalid HMAC output".to_string());
-    }
-    let slice = &hash[offset..offset + 4];
-    let binary = ((slice[0] as u32 & 0x7f) << 24)
-        | ((slice[1] as u32) << 16)
-        | ((slice[2] as u32) << 8)
-        | slice[3] as u32;
-    Ok(binary)
-}
-
-fn decode_totp_secret(secret: &str) -> std::result::Result<Vec<u8>, String> {
-    let normalized: String = secret
-        .chars()
-        .filter(|c| !c.is_whitespace())
-        .map(|c| c.to_ascii_uppercase())
-        .collect::<String>()
-        .trim_matches('=')
-        .to_string();
-
-    BASE32_NOPAD
-        .decode(normalized.as_bytes())
-        .or_else(|_| BASE32.decode(normalized.as_bytes()))
-        .map_err(|e| format!("Invalid base32 secret: {}", e))
-}
-
 /// Generate password
 #[command]
 pub async fn generate_password(
     length: usize,
     include_symbols: bool,
     state: State<'_, AppState>,
-) -> std::result::Result<ApiResponse<String>, String> {
+) -> std::result::Result<ApiResponse<GeneratedPassword>, String> {
     let service_guard = state.service.lock().await;
     match service_guard.as_ref() {
         Some(service) => {
-            let password = service.generate_password(length, include_symbols);
-            Ok(ApiResponse::success(password))
+            let generated = service.generate_password_with_entropy(length, include_symbols);
+            Ok(ApiResponse::success(generated))
         }
         None => Ok(ApiResponse::error("Service not initialized".to_string())),
     }
 }
 
+/// Estimate the strength of a password; needs no unlocked vault
+#[command]
+pub async fn check_password_strength(
+    password: String,
+) -> std::result::Result<ApiResponse<PasswordStrength>, String> {
+    let password = SecretString::from(password);
+    Ok(ApiResponse::success(password_strength(password.expose())))
+}
+
 /// Get service statistics
 #[command]
 pub async fn get_statistics(
@@ -676,7 +1039,7 @@ pub async fn start_ssh_agent(
     let state_dir = agent_state_dir().to_string_lossy().to_string();
     let handle = tokio::spawn(async move {
         if let Some(pass) = password {
-            std::env::set_var("PERSONA_MASTER_PASSWORD", pass);
+            std::env::set_var("PERSONA_MASTER_PASSWORD", pass.expose());
         } else {
             std::env::remove_var("PERSONA_MASTER_PASSWORD");
         }
@@ -692,6 +1055,9 @@ pub async fn start_ssh_agent(
 
     sleep(Duration::from_millis(400)).await;
     let status = read_agent_status(true);
+    if let (Some(key_count), Some(service)) = (status.key_count, state.service.lock().await.as_ref()) {
+        service.publish_event(VaultEvent::AgentKeysReloaded { key_count });
+    }
     Ok(ApiResponse::success(status))
 }
 
@@ -783,12 +1149,9 @@ pub async fn wallet_list(
             .ok_or_else(|| "Database path unavailable. Initialize the service first.".to_string())?
     };
 
-    let db = Database::from_file(&db_path)
+    let db = vault_database(&state, &db_path)
         .await
         .map_err(|e| format!("Database connection failed: {}", e))?;
-    db.migrate()
-        .await
-        .map_err(|e| format!("Database migration failed: {}", e))?;
 
     let repo = CryptoWalletRepository::new(Arc::new(db));
 
@@ -807,6 +1170,7 @@ pub async fn wallet_list(
             id: wallet.id.to_string(),
             name: wallet.name,
             network: wallet.network.to_string(),
+            network_kind: wallet.network_kind,
             wallet_type: format!("{:?}", wallet.wallet_type),
             balance: "-".to_string(),
             address_count: wallet.addresses.len(),
@@ -843,12 +1207,9 @@ pub async fn wallet_list_addresses(
             .ok_or_else(|| "Database path unavailable. Initialize the service first.".to_string())?
     };
 
-    let db = Database::from_file(&db_path)
+    let db = vault_database(&state, &db_path)
         .await
         .map_err(|e| format!("Database connection failed: {}", e))?;
-    db.migrate()
-        .await
-        .map_err(|e| format!("Database migration failed: {}", e))?;
 
     let repo = CryptoWalletRepository::new(Arc::new(db));
 
@@ -882,6 +1243,54 @@ pub async fn wallet_list_addresses(
     Ok(ApiResponse::success(WalletAddressesResponse { addresses }))
 }
 
+#[command]
+pub async fn wallet_transaction_history(
+    wallet_id: String,
+    state: State<'_, AppState>,
+) -> std::result::Result<ApiResponse<WalletTransactionHistoryResponse>, String> {
+    let service_unlocked = {
+        let guard = state.service.lock().await;
+        match guard.as_ref() {
+            Some(service) => service.is_unlocked(),
+            None => return Ok(ApiResponse::error("Service not initialized".to_string())),
+        }
+    };
+    if !service_unlocked {
+        return Ok(ApiResponse::error("Service is locked".to_string()));
+    }
+
+    let db_path = {
+        let guard = state.db_path.lock().await;
+        guard
+            .clone()
+            .ok_or_else(|| "Database path unavailable. Initialize the service first.".to_string())?
+    };
+
+    let db = vault_database(&state, &db_path)
+        .await
+        .map_err(|e| format!("Database connection failed: {}", e))?;
+
+    let repo = CryptoWalletRepository::new(Arc::new(db));
+
+    let uuid = Uuid::from_str(&wallet_id).map_err(|_| "Invalid wallet UUID format".to_string())?;
+    let events = repo
+        .get_transaction_history(&uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|event| SerializableWalletTransactionEvent {
+            id: event.id.to_string(),
+            request_id: event.request_id.to_string(),
+            status: event.status.to_string(),
+            transaction_hash: event.transaction_hash,
+            detail: event.detail,
+            created_at: event.created_at.to_rfc3339(),
+        })
+        .collect();
+
+    Ok(ApiResponse::success(WalletTransactionHistoryResponse { events }))
+}
+
 #[command]
 pub async fn wallet_generate(
     identity_id: String,
@@ -928,9 +1337,10 @@ pub async fn wallet_generate(
     let wallet = persona_core::crypto::wallet_import_export::import_from_mnemonic(
         identity_id,
         request.name.clone(),
-        &mnemonic_phrase,
-        "",
+        mnemonic_phrase.expose(),
+        request.passphrase.as_ref().map_or("", |p| p.expose()),
         network,
+        request.network_kind,
         derivation_path,
         address_count,
         &request.password,
@@ -944,12 +1354,9 @@ pub async fn wallet_generate(
             .ok_or_else(|| "Database path unavailable. Initialize the service first.".to_string())?
     };
 
-    let db = Database::from_file(&db_path)
+    let db = vault_database(&state, &db_path)
         .await
         .map_err(|e| format!("Database connection failed: {}", e))?;
-    db.migrate()
-        .await
-        .map_err(|e| format!("Database migration failed: {}", e))?;
     let repo = CryptoWalletRepository::new(Arc::new(db));
 
     let created = repo.create(&wallet).await.map_err(|e| e.to_string())?;
@@ -1000,8 +1407,9 @@ pub async fn wallet_import(
             identity_id,
             request.name.clone(),
             request.data.trim(),
-            "",
+            request.passphrase.as_ref().map_or("", |p| p.expose()),
             network,
+            request.network_kind,
             None,
             address_count,
             &request.password,
@@ -1012,6 +1420,7 @@ pub async fn wallet_import(
             request.name.clone(),
             request.data.trim(),
             network,
+            request.network_kind,
             &request.password,
         )
         .map_err(|e| e.to_string())?,
@@ -1030,12 +1439,9 @@ pub async fn wallet_import(
             .ok_or_else(|| "Database path unavailable. Initialize the service first.".to_string())?
     };
 
-    let db = Database::from_file(&db_path)
+    let db = vault_database(&state, &db_path)
         .await
         .map_err(|e| format!("Database connection failed: {}", e))?;
-    db.migrate()
-        .await
-        .map_err(|e| format!("Database migration failed: {}", e))?;
     let repo = CryptoWalletRepository::new(Arc::new(db));
 
     let created = repo.create(&wallet).await.map_err(|e| e.to_string())?;
@@ -1043,6 +1449,7 @@ pub async fn wallet_import(
         id: created.id.to_string(),
         name: created.name,
         network: created.network.to_string(),
+        network_kind: created.network_kind,
         wallet_type: format!("{:?}", created.wallet_type),
         balance: "-".to_string(),
         address_count: created.addresses.len(),
@@ -1071,11 +1478,6 @@ pub async fn wallet_add_address(
     }
 
     let wallet_id = Uuid::from_str(&wallet_id).map_err(|_| "Invalid wallet UUID format".to_string())?;
-    if password.len() < 8 {
-        return Ok(ApiResponse::error(
-            "Wallet password must be at least 8 characters".to_string(),
-        ));
-    }
 
     let db_path = {
         let guard = state.db_path.lock().await;
@@ -1084,12 +1486,9 @@ pub async fn wallet_add_address(
             .ok_or_else(|| "Database path unavailable. Initialize the service first.".to_string())?
     };
 
-    let db = Database::from_file(&db_path)
+    let db = vault_database(&state, &db_path)
         .await
         .map_err(|e| format!("Database connection failed: {}", e))?;
-    db.migrate()
-        .await
-        .map_err(|e| format!("Database migration failed: {}", e))?;
     let repo = CryptoWalletRepository::new(Arc::new(db));
 
     let wallet: CryptoWallet = match repo.find_by_id(&wallet_id).await.map_err(|e| e.to_string())? {
@@ -1097,9 +1496,53 @@ pub async fn wallet_add_address(
         None => return Ok(ApiResponse::error("Wallet not found".to_string())),
     };
 
+    let next_index = wallet
+        .addresses
+        .iter()
+        .map(|addr| addr.index)
+        .max()
+        .map(|v| v + 1)
+        .unwrap_or(0);
+
+    // Watch-only wallets derive from their xpub; no private key, so no password
     if wallet.watch_only {
+        let Some(xpub) = wallet.extended_public_key.as_deref() else {
+            return Ok(ApiResponse::error(
+                "Watch-only wallet has no extended public key.".to_string(),
+            ));
+        };
+        let (address, address_type) =
+            match persona_core::crypto::address_generator::derive_address_from_xpub(
+                xpub,
+                next_index,
+                &wallet.network,
+                wallet.network_kind,
+            ) {
+                Ok(derived) => derived,
+                Err(e) => return Ok(ApiResponse::error(e.to_string())),
+            };
+
+        let wallet_address = persona_core::models::wallet::WalletAddress {
+            address,
+            address_type,
+            derivation_path: None,
+            index: next_index,
+            used: false,
+            balance: None,
+            last_activity: None,
+            metadata: HashMap::new(),
+            created_at: chrono::Utc::now(),
+        };
+        repo.add_address(&wallet_id, &wallet_address)
+            .await
+            .map_err(|e| e.to_string())?;
+        repo.touch(&wallet_id).await.map_err(|e| e.to_string())?;
+        return Ok(ApiResponse::success(serialize_wallet_address(wallet_address)));
+    }
+
+    if password.len() < 8 {
         return Ok(ApiResponse::error(
-            "Address generation for watch-only wallets is not implemented yet.".to_string(),
+            "Wallet password must be at least 8 characters".to_string(),
         ));
     }
 
@@ -1116,15 +1559,9 @@ pub async fn wallet_add_address(
         wallet
             .derivation_path
             .clone()
-            .unwrap_or_else(|| CryptoWallet::recommended_derivation_path(&wallet.network, 0));
-
-    let next_index = wallet
-        .addresses
-        .iter()
-        .map(|addr| addr.index)
-        .max()
-        .map(|v| v + 1)
-        .unwrap_or(0);
+            .unwrap_or_else(|| {
+                CryptoWallet::recommended_derivation_path(&wallet.network, wallet.network_kind, 0)
+            });
 
     let encrypted_key: persona_core::crypto::wallet_encryption::EncryptedWalletKey =
         serde_json::from_slice(&wallet.encrypted_private_key)
@@ -1132,33 +1569,54 @@ pub async fn wallet_add_address(
     let master_key = persona_core::crypto::wallet_encryption::decrypt_master_key(&encrypted_key, &password)
         .map_err(|e| e.to_string())?;
 
-    let parent = master_key
-        .derive_path(&derivation_path)
-        .map_err(|e| e.to_string())?;
-    let child = parent
-        .derive_child(next_index, false)
-        .map_err(|e| e.to_string())?;
+    // Solana derives ed25519 keys from the seed; the others are children of the BIP-32 base path
+    let child = if wallet.network == BlockchainNetwork::Solana {
+        None
+    } else {
+        let parent = master_key
+            .derive_path(&derivation_path)
+            .map_err(|e| e.to_string())?;
+        Some(
+            parent
+                .derive_child(next_index, false)
+                .map_err(|e| e.to_string())?,
+        )
+    };
 
-    let (address_string, address_type) = match wallet.network {
-        BlockchainNetwork::Bitcoin => (
+    let (address_string, address_type, address_path) = match (&wallet.network, &child) {
+        (BlockchainNetwork::Solana, _) => (
+            persona_core::crypto::address_generator::generate_solana_address(
+                &master_key,
+                next_index,
+            )
+            .map_err(|e| e.to_string())?,
+            persona_core::models::wallet::AddressType::Solana,
+            persona_core::crypto::address_generator::solana_derivation_path(next_index),
+        ),
+        (BlockchainNetwork::Bitcoin, Some(child)) => (
             persona_core::crypto::address_generator::generate_bitcoin_address(
-                &child,
+                child,
                 persona_core::crypto::address_generator::BitcoinAddressType::P2WPKH,
-                false,
+                wallet.network_kind,
             )
             .map_err(|e| e.to_string())?,
             persona_core::models::wallet::AddressType::P2WPKH,
+            format!("{}/{}", derivation_path, next_index),
         ),
-        BlockchainNetwork::Ethereum
-        | BlockchainNetwork::Polygon
-        | BlockchainNetwork::Arbitrum
-        | BlockchainNetwork::Optimism
-        | BlockchainNetwork::BinanceSmartChain => (
-            persona_core::crypto::address_generator::generate_ethereum_address_checksummed(&child)
+        (
+            BlockchainNetwork::Ethereum
+            | BlockchainNetwork::Polygon
+            | BlockchainNetwork::Arbitrum
+            | BlockchainNetwork::Optimism
+            | BlockchainNetwork::BinanceSmartChain,
+            Some(child),
+        ) => (
+            persona_core::crypto::address_generator::generate_ethereum_address_checksummed(child)
                 .map_err(|e| e.to_string())?,
             persona_core::models::wallet::AddressType::Ethereum,
+            format!("{}/{}", derivation_path, next_index),
         ),
-        other => {
+        (other, _) => {
             return Ok(ApiResponse::error(format!(
                 "Address generation not implemented for {}",
                 other
@@ -1169,7 +1627,7 @@ pub async fn wallet_add_address(
     let wallet_address = persona_core::models::wallet::WalletAddress {
         address: address_string,
         address_type,
-        derivation_path: Some(format!("{}/{}", derivation_path, next_index)),
+        derivation_path: Some(address_path),
         index: next_index,
         used: false,
         balance: None,
@@ -1209,12 +1667,9 @@ pub async fn wallet_export(
             .ok_or_else(|| "Database path unavailable. Initialize the service first.".to_string())?
     };
 
-    let db = Database::from_file(&db_path)
+    let db = vault_database(&state, &db_path)
         .await
         .map_err(|e| format!("Database connection failed: {}", e))?;
-    db.migrate()
-        .await
-        .map_err(|e| format!("Database migration failed: {}", e))?;
 
     let repo = CryptoWalletRepository::new(Arc::new(db));
 
@@ -1250,6 +1705,8 @@ pub async fn wallet_export(
                     .ok_or_else(|| "Password required for mnemonic export".to_string())?,
             )
             .map_err(|e| e.to_string())?
+            .expose()
+            .to_string()
         }
         persona_core::crypto::wallet_import_export::ExportFormat::Xpub => {
             persona_core::crypto::wallet_import_export::export_xpub(&wallet)
@@ -1305,84 +1762,12 @@ fn serialize_wallet_address(addr: persona_core::models::wallet::WalletAddress) -
     }
 }
 
-fn agent_state_dir() -> PathBuf {
-    std::env::var("PERSONA_AGENT_STATE_DIR")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| {
-            dirs::home_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join(".persona")
-        })
-}
-
-fn cleanup_agent_state_files() {
+pub(crate) fn cleanup_agent_state_files() {
     let dir = agent_state_dir();
-    for name in &["ssh-agent.sock", "ssh-agent.pid"] {
+    for name in &[SOCKET_FILE, PID_FILE] {
         let path = dir.join(name);
         if path.exists() {
             let _ = fs::remove_file(path);
         }
     }
 }
-
-fn read_agent_status(running_hint: bool) -> SshAgentStatus {
-    let dir = agent_state_dir();
-    let sock_path = dir.join("ssh-agent.sock");
-    let pid_path = dir.join("ssh-agent.pid");
-    let socket_value = if sock_path.exists() {
-        fs::read_to_string(&sock_path).ok().map(|s| s.trim().to_string())
-    } else {
-        None
-    };
-    let pid_value = if pid_path.exists() {
-        fs::read_to_string(&pid_path)
-            .ok()
-            .and_then(|s| s.trim().parse::<u32>().ok())
-    } else {
-        None
-    };
-    let key_count = socket_value
-        .as_deref()
-        .and_then(|sock| query_agent_key_count(sock).ok());
-
-    SshAgentStatus {
-        running: running_hint || socket_value.is_some() || pid_value.is_some(),
-        socket_path: socket_value,
-        pid: pid_value,
-        key_count,
-        state_dir: dir.to_string_lossy().to_string(),
-    }
-}
-
-#[cfg(unix)]
-fn query_agent_key_count(sock_path: &str) -> std::result::Result<usize, String> {
-    use byteorder::{BigEndian, ByteOrder};
-    use std::io::{Read, Write};
-    use std::os::unix::net::UnixStream;
-
-    let mut stream = UnixStream::connect(sock_path)
-        .map_err(|e| format!("Failed to connect to agent: {}", e))?;
-    // request identities: len=1 payload 11
-    let mut pkt = vec![0u8; 5];
-    BigEndian::write_u32(&mut pkt[0..4], 1);
-    pkt[4] = 11;
-    stream.write_all(&pkt).map_err(|e| e.to_string())?;
-    let mut len_buf = [0u8; 4];
-    stream.read_exact(&mut len_buf).map_err(|e| e.to_string())?;
-    let resp_len = BigEndian::read_u32(&len_buf) as usize;
-    let mut resp = vec![0u8; resp_len];
-    stream.read_exact(&mut resp).map_err(|e| e.to_string())?;
-    if resp.is_empty() || resp[0] != 12 {
-        return Err("Unexpected agent response".to_string());
-    }
-    if resp.len() < 5 {
-        return Err("Malformed agent response".to_string());
-    }
-    let count = BigEndian::read_u32(&resp[1..5]) as usize;
-    Ok(count)
-}
-
-#[cfg(not(unix))]
-fn query_agent_key_count(_sock_path: &str) -> std::result::Result<usize, String> {
-    Err("Agent key count not supported on this platform".to_string())
-}
@@ -1,48 +1,84 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod auto_lock;
 mod commands;
 mod types;
 
+use auto_lock::AutoLockTimer;
+use tauri::Manager;
 use types::AppState;
 use tokio::sync::Mutex;
 
 fn main() {
+    let handler = tauri::generate_handler![
+        commands::init_service,
+        commands::lock_service,
+        commands::reload_auto_lock_policy,
+        commands::is_service_unlocked,
+        commands::get_verification_phrase,
+        commands::reset_verification_phrase,
+        commands::get_vault_fingerprint,
+        commands::vacuum_vault,
+        commands::get_build_info,
+        commands::create_identity,
+        commands::get_identities,
+        commands::get_identity,
+        commands::update_identity,
+        commands::delete_identity,
+        commands::create_credential,
+        commands::get_credentials_for_identity,
+        commands::get_credential_data,
+        commands::register_custom_credential_type,
+        commands::get_totp_code,
+        commands::verify_totp_code,
+        commands::get_linked_totp_code,
+        commands::link_totp,
+        commands::unlink_totp,
+        commands::get_credential_security_score,
+        commands::get_vault_security_score,
+        commands::get_password_audit_report,
+        commands::search_credentials,
+        commands::generate_password,
+        commands::check_password_strength,
+        commands::get_statistics,
+        commands::toggle_credential_favorite,
+        commands::delete_credential,
+        commands::get_ssh_agent_status,
+        commands::start_ssh_agent,
+        commands::stop_ssh_agent,
+        commands::get_ssh_keys,
+        commands::wallet_list,
+        commands::wallet_list_addresses,
+        commands::wallet_transaction_history,
+        commands::wallet_generate,
+        commands::wallet_import,
+        commands::wallet_add_address,
+        commands::wallet_export,
+    ];
+
     tauri::Builder::default()
         .manage(AppState {
             service: Mutex::new(None),
             db_path: Mutex::new(None),
+            database: Mutex::new(None),
             agent_handle: Mutex::new(None),
+            backup_task: Mutex::new(None),
+            auto_lock: AutoLockTimer::default(),
+        })
+        .setup(|app| {
+            auto_lock::spawn(app.handle());
+            Ok(())
+        })
+        // Every command the UI sends counts as activity for the auto-lock timer
+        .invoke_handler(move |invoke| {
+            let window = invoke.message.window();
+            window
+                .state::<AppState>()
+                .auto_lock
+                .record_command(invoke.message.command());
+            handler(invoke)
         })
-        .invoke_handler(tauri::generate_handler![
-            commands::init_service,
-            commands::lock_service,
-            commands::is_service_unlocked,
-            commands::create_identity,
-            commands::get_identities,
-            commands::get_identity,
-            commands::update_identity,
-            commands::delete_identity,
-            commands::create_credential,
-            commands::get_credentials_for_identity,
-            commands::get_credential_data,
-            commands::get_totp_code,
-            commands::search_credentials,
-            commands::generate_password,
-            commands::get_statistics,
-            commands::toggle_credential_favorite,
-            commands::delete_credential,
-            commands::get_ssh_agent_status,
-            commands::start_ssh_agent,
-            commands::stop_ssh_agent,
-            commands::get_ssh_keys,
-            commands::wallet_list,
-            commands::wallet_list_addresses,
-            commands::wallet_generate,
-            commands::wallet_import,
-            commands::wallet_add_address,
-            commands::wallet_export,
-        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -3,11 +3,37 @@ use serde::{Deserialize, Serialize};
 use tokio::task::JoinHandle;
 use tokio::sync::Mutex;
 
+use crate::auto_lock::AutoLockTimer;
+
 /// Application state that holds the Persona service
 pub struct AppState {
     pub service: Mutex<Option<PersonaService>>,
     pub db_path: Mutex<Option<String>>,
+    /// Connection pool of the vault at the given path, shared by every command
+    pub database: Mutex<Option<(String, Database)>>,
     pub agent_handle: Mutex<Option<JoinHandle<()>>>,
+    /// Scheduled backups of the open vault, when its config enables them
+    pub backup_task: Mutex<Option<JoinHandle<()>>>,
+    pub auto_lock: AutoLockTimer,
+}
+
+/// Inactivity auto-lock settings taken from the vault's default policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AutoLockSettings {
+    /// Idle seconds before the service locks; 0 disables auto-lock
+    pub inactivity_timeout_secs: u64,
+    /// Also stop the embedded SSH agent when auto-locking
+    pub stop_ssh_agent: bool,
+    /// System events (sleep, screen lock) that lock regardless of idle time
+    pub lock_triggers: LockTriggerSettings,
+}
+
+/// Payload of the `service-locked` event
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceLockedEvent {
+    /// `inactivity`, or the system event that locked the vault (`sleep`, `resume`, `screen_locked`)
+    pub reason: String,
+    pub ssh_agent_stopped: bool,
 }
 
 /// Response structure for API calls
@@ -36,16 +62,23 @@ impl<T> ApiResponse<T> {
     }
 }
 
+/// `ApiResponse` error for a critical credential that needs the master password because no
+/// biometric provider is usable; retry `get_credential_data` with `master_password`
+pub const MASTER_PASSWORD_REQUIRED: &str = "master_password_required";
+
 /// Initialization request
 #[derive(Debug, Deserialize)]
 pub struct InitRequest {
-    pub master_password: String,
+    pub master_password: SecretString,
     pub db_path: Option<String>,
+    /// Open the in-memory demo vault instead of a real database
+    #[serde(default)]
+    pub demo: bool,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct StartAgentRequest {
-    pub master_password: Option<String>,
+    pub master_password: Option<SecretString>,
 }
 
 /// Identity creation request
@@ -68,6 +101,8 @@ pub struct UpdateIdentityRequest {
     pub email: Option<String>,
     pub phone: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// Version the client last read; omitted to overwrite unconditionally
+    pub version: Option<i64>,
 }
 
 /// Credential creation request
@@ -88,38 +123,44 @@ pub struct CreateCredentialRequest {
 #[serde(tag = "type")]
 pub enum CredentialDataRequest {
     Password {
-        password: String,
+        password: SecretString,
         email: Option<String>,
         security_questions: Vec<SecurityQuestionRequest>,
     },
     CryptoWallet {
         wallet_type: String,
-        mnemonic_phrase: Option<String>,
-        private_key: Option<String>,
+        mnemonic_phrase: Option<SecretString>,
+        private_key: Option<SecretString>,
         public_key: String,
         address: String,
         network: String,
     },
     SshKey {
-        private_key: String,
+        private_key: SecretString,
         public_key: String,
         key_type: String,
-        passphrase: Option<String>,
+        passphrase: Option<SecretString>,
     },
     ApiKey {
-        api_key: String,
-        api_secret: Option<String>,
-        token: Option<String>,
+        api_key: SecretString,
+        api_secret: Option<SecretString>,
+        token: Option<SecretString>,
         permissions: Vec<String>,
         expires_at: Option<String>,
     },
     TwoFactor {
-        secret_key: String,
+        secret_key: SecretString,
         issuer: String,
         account_name: String,
         algorithm: String,
         digits: u8,
         period: u32,
+        #[serde(default)]
+        otp_type: OtpType,
+        #[serde(default)]
+        counter: u64,
+        #[serde(default)]
+        time_offset_seconds: i64,
     },
     Raw {
         data: Vec<u8>,
@@ -129,7 +170,7 @@ pub enum CredentialDataRequest {
 #[derive(Debug, Deserialize)]
 pub struct SecurityQuestionRequest {
     pub question: String,
-    pub answer: String,
+    pub answer: SecretString,
 }
 
 /// Serializable versions of core types for frontend
@@ -147,6 +188,7 @@ pub struct SerializableIdentity {
     pub created_at: String,
     pub updated_at: String,
     pub is_active: bool,
+    pub version: i64,
 }
 
 #[derive(Debug, Serialize)]
@@ -165,6 +207,8 @@ pub struct SerializableCredential {
     pub last_accessed: Option<String>,
     pub is_active: bool,
     pub is_favorite: bool,
+    pub linked_totp_id: Option<String>,
+    pub version: i64,
 }
 
 #[derive(Debug, Serialize)]
@@ -173,14 +217,7 @@ pub struct SerializableCredentialData {
     pub data: serde_json::Value,
 }
 
-#[derive(Debug, Serialize)]
-pub struct SshAgentStatus {
-    pub running: bool,
-    pub socket_path: Option<String>,
-    pub pid: Option<u32>,
-    pub key_count: Option<usize>,
-    pub state_dir: String,
-}
+pub use persona_ssh_agent::status::SshAgentStatus;
 
 #[derive(Debug, Serialize)]
 pub struct SshKeySummary {
@@ -208,6 +245,7 @@ impl From<Identity> for SerializableIdentity {
             created_at: identity.created_at.to_rfc3339(),
             updated_at: identity.updated_at.to_rfc3339(),
             is_active: identity.is_active,
+            version: identity.version,
         }
     }
 }
@@ -229,78 +267,127 @@ impl From<Credential> for SerializableCredential {
             last_accessed: credential.last_accessed.map(|dt| dt.to_rfc3339()),
             is_active: credential.is_active,
             is_favorite: credential.is_favorite,
+            linked_totp_id: credential.linked_totp_id.map(|id| id.to_string()),
+            version: credential.version,
+        }
+    }
+}
+
+/// A `search_credentials` result: the credential plus its relevance
+#[derive(Debug, Serialize)]
+pub struct SerializableSearchHit {
+    #[serde(flatten)]
+    pub credential: SerializableCredential,
+    pub score: u32,
+    pub matched_field: Option<CredentialSearchField>,
+}
+
+impl From<CredentialSearchHit> for SerializableSearchHit {
+    fn from(hit: CredentialSearchHit) -> Self {
+        Self {
+            credential: hit.credential.into(),
+            score: hit.score,
+            matched_field: hit.matched_field,
         }
     }
 }
 
-/// Helper function to convert credential data for serialization
+/// Helper function to convert credential data for serialization.
+///
+/// Starts from the canonical core envelope so field names always match storage and export,
+/// then strips the secret fields the UI must fetch separately.
 pub fn credential_data_to_json(data: &CredentialData) -> serde_json::Value {
+    let envelope = match data.to_envelope() {
+        Ok(envelope) => envelope,
+        Err(_) => return serde_json::json!({ "type": data.type_name() }),
+    };
+
+    let mut fields = match envelope.data {
+        serde_json::Value::Object(map) => map,
+        _ => serde_json::Map::new(),
+    };
+
+    let redacted: &[&str] = match data {
+        CredentialData::Password(_) => &[],
+        CredentialData::CryptoWallet(_) => &["mnemonic_phrase", "private_key"],
+        CredentialData::SshKey(_) => &["private_key", "passphrase"],
+        CredentialData::ApiKey(_) => &["api_key", "api_secret", "token"],
+        CredentialData::BankCard(_) => &["card_number", "cvv"],
+        CredentialData::ServerConfig(_) => &["password"],
+        CredentialData::TwoFactor(_) => &["secret_key"],
+        CredentialData::Raw(_) => &[],
+    };
+    for key in redacted {
+        fields.remove(*key);
+    }
+
     match data {
-        CredentialData::Password(pwd_data) => serde_json::json!({
-            "type": "Password",
-            "password": pwd_data.password,
-            "email": pwd_data.email,
-            "security_questions": pwd_data.security_questions
-        }),
-        CredentialData::CryptoWallet(wallet_data) => serde_json::json!({
-            "type": "CryptoWallet",
-            "wallet_type": wallet_data.wallet_type,
-            "public_key": wallet_data.public_key,
-            "address": wallet_data.address,
-            "network": wallet_data.network
-        }),
-        CredentialData::SshKey(ssh_data) => serde_json::json!({
-            "type": "SshKey",
-            "public_key": ssh_data.public_key,
-            "key_type": ssh_data.key_type
-        }),
-        CredentialData::ApiKey(api_data) => serde_json::json!({
-            "type": "ApiKey",
-            "permissions": api_data.permissions,
-            "expires_at": api_data.expires_at
-        }),
-        CredentialData::BankCard(card_data) => serde_json::json!({
-            "type": "BankCard",
-            "cardholder_name": card_data.cardholder_name,
-            "expiry_date": card_data.expiry_date,
-            "bank_name": card_data.bank_name,
-            "card_type": card_data.card_type,
-            "last4": card_data.card_number.chars().filter(|c| c.is_ascii_digit()).collect::<String>().chars().rev().take(4).collect::<String>().chars().rev().collect::<String>(),
-        }),
-        CredentialData::ServerConfig(server_data) => serde_json::json!({
-            "type": "ServerConfig",
-            "hostname": server_data.hostname,
-            "ip_address": server_data.ip_address,
-            "port": server_data.port,
-            "protocol": server_data.protocol,
-            "username": server_data.username,
-            "ssh_key_id": server_data.ssh_key_id,
-            "additional_config": server_data.additional_config
-        }),
-        CredentialData::TwoFactor(tf_data) => serde_json::json!({
-            "type": "TwoFactor",
-            "issuer": tf_data.issuer,
-            "account_name": tf_data.account_name,
-            "algorithm": tf_data.algorithm,
-            "digits": tf_data.digits,
-            "period": tf_data.period
-        }),
-        CredentialData::Raw(_) => serde_json::json!({
-            "type": "Raw",
-            "message": "Binary data"
-        }),
+        CredentialData::BankCard(card_data) => {
+            let digits: Vec<char> = card_data
+                .card_number
+                .expose()
+                .chars()
+                .filter(|c| c.is_ascii_digit())
+                .collect();
+            let last4: String = digits[digits.len().saturating_sub(4)..].iter().collect();
+            fields.insert("last4".to_string(), serde_json::json!(last4));
+        }
+        CredentialData::Raw(_) => {
+            fields.insert("message".to_string(), serde_json::json!("Binary data"));
+        }
+        _ => {}
     }
+
+    fields.insert("type".to_string(), serde_json::json!(envelope.kind));
+    fields.insert(
+        "schema_version".to_string(),
+        serde_json::json!(envelope.schema_version),
+    );
+    serde_json::Value::Object(fields)
 }
 
 #[derive(Debug, Serialize)]
 pub struct TotpCodeResponse {
     pub code: String,
+    /// 0 for HOTP codes, which stay valid until used
     pub remaining_seconds: u32,
     pub period: u32,
     pub digits: u8,
     pub algorithm: String,
     pub issuer: String,
     pub account_name: String,
+    pub otp_type: OtpType,
+}
+
+/// Credential security score with the factors behind it
+#[derive(Debug, Serialize)]
+pub struct SerializableSecurityScore {
+    pub credential_id: String,
+    pub name: String,
+    pub score: u8,
+    pub password_entropy_bits: Option<f64>,
+    pub reuse_count: usize,
+    pub has_two_factor: bool,
+}
+
+impl From<CredentialSecurityScore> for SerializableSecurityScore {
+    fn from(score: CredentialSecurityScore) -> Self {
+        Self {
+            credential_id: score.credential_id.to_string(),
+            name: score.name,
+            score: score.score,
+            password_entropy_bits: score.context.password_entropy_bits,
+            reuse_count: score.context.reuse_count,
+            has_two_factor: score.context.has_two_factor,
+        }
+    }
+}
+
+/// Vault-wide security score
+#[derive(Debug, Serialize)]
+pub struct SerializableVaultSecurityScore {
+    pub score: u8,
+    pub credentials: Vec<SerializableSecurityScore>,
 }
 
 // Wallet types for Tauri commands
@@ -311,6 +398,7 @@ pub struct SerializableWallet {
     pub id: String,
     pub name: String,
     pub network: String,
+    pub network_kind: persona_core::models::wallet::NetworkKind,
     pub wallet_type: String,
     pub balance: String,
     pub address_count: usize,
@@ -343,6 +431,23 @@ pub struct WalletAddressesResponse {
     pub addresses: Vec<SerializableWalletAddress>,
 }
 
+/// One status transition in a wallet transaction's history
+#[derive(Debug, Serialize)]
+pub struct SerializableWalletTransactionEvent {
+    pub id: String,
+    pub request_id: String,
+    pub status: String,
+    pub transaction_hash: Option<String>,
+    pub detail: Option<String>,
+    pub created_at: String,
+}
+
+/// Wallet transaction history response
+#[derive(Debug, Serialize)]
+pub struct WalletTransactionHistoryResponse {
+    pub events: Vec<SerializableWalletTransactionEvent>,
+}
+
 /// Wallet generation request
 #[derive(Debug, Deserialize)]
 pub struct WalletGenerateRequest {
@@ -351,6 +456,11 @@ pub struct WalletGenerateRequest {
     pub wallet_type: String,
     pub password: String,
     pub address_count: Option<usize>,
+    #[serde(default)]
+    pub network_kind: persona_core::models::wallet::NetworkKind,
+    /// Optional BIP-39 passphrase; only used to derive the seed, never stored
+    #[serde(default)]
+    pub passphrase: Option<SecretString>,
 }
 
 /// Wallet generation response (includes mnemonic)
@@ -359,7 +469,7 @@ pub struct WalletGenerateResponse {
     pub wallet_id: String,
     pub name: String,
     pub network: String,
-    pub mnemonic: String,
+    pub mnemonic: SecretString,
     pub first_address: String,
 }
 
@@ -372,6 +482,11 @@ pub struct WalletImportRequest {
     pub data: String,
     pub password: String,
     pub address_count: Option<usize>,
+    #[serde(default)]
+    pub network_kind: persona_core::models::wallet::NetworkKind,
+    /// Optional BIP-39 passphrase for mnemonic imports; never stored
+    #[serde(default)]
+    pub passphrase: Option<SecretString>,
 }
 
 /// Wallet export request
@@ -432,6 +547,9 @@ impl CredentialDataRequest {
                 algorithm,
                 digits,
                 period,
+                otp_type,
+                counter,
+                time_offset_seconds,
             } => CredentialData::TwoFactor(TwoFactorData {
                 secret_key: secret_key.clone(),
                 issuer: issuer.clone(),
@@ -439,6 +557,9 @@ impl CredentialDataRequest {
                 algorithm: algorithm.clone(),
                 digits: *digits,
                 period: *period,
+                otp_type: *otp_type,
+                counter: *counter,
+                time_offset_seconds: *time_offset_seconds,
             }),
             CredentialDataRequest::Raw { data } => {
                 CredentialData::Raw(data.clone())
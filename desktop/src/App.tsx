@@ -195,6 +195,7 @@ const App: React.FC = () => {
 
 const StatisticsView: React.FC = () => {
   const [statistics, setStatistics] = useState<any>(null);
+  const [vaultScore, setVaultScore] = useState<number | null>(null);
 
   useEffect(() => {
     // Load statistics when component mounts
@@ -203,12 +204,16 @@ const StatisticsView: React.FC = () => {
 
   const loadStatistics = async () => {
     try {
-      const response = await import('@/utils/api').then(module =>
-        module.personaAPI.getStatistics()
-      );
+      const { personaAPI } = await import('@/utils/api');
+      const response = await personaAPI.getStatistics();
       if (response.success && response.data) {
         setStatistics(response.data);
       }
+      // Scoring decrypts every password, so it loads after the cheap counters
+      const score = await personaAPI.getVaultSecurityScore();
+      if (score.success && score.data) {
+        setVaultScore(score.data.score);
+      }
     } catch (error) {
       console.error('Failed to load statistics:', error);
     }
@@ -279,6 +284,17 @@ const StatisticsView: React.FC = () => {
         </div>
       </div>
 
+      {vaultScore !== null && (
+        <div className="card p-6">
+          <p className="text-sm font-medium text-gray-600">Vault Security Score</p>
+          <p className="text-2xl font-bold text-gray-900">{vaultScore}/100</p>
+          <p className="text-xs text-gray-500 mt-1">
+            Average of password strength, reuse, rotation age, linked 2FA and https across all
+            credentials
+          </p>
+        </div>
+      )}
+
       {/* Credential Types Breakdown */}
       <div className="grid grid-cols-1 lg:grid-cols-2 gap-6">
         <div className="card p-6">
@@ -56,6 +56,7 @@ const CreateCredentialModal: React.FC<CreateCredentialModalProps> = ({ isOpen, o
   });
 
   const [showPassword, setShowPassword] = useState(false);
+  const [passwordEntropy, setPasswordEntropy] = useState<number | null>(null);
 
   const credentialTypes: CredentialType[] = [
     'Password',
@@ -71,9 +72,10 @@ const CreateCredentialModal: React.FC<CreateCredentialModalProps> = ({ isOpen, o
   const securityLevels: SecurityLevel[] = ['Critical', 'High', 'Medium', 'Low'];
 
   const handleGeneratePassword = async () => {
-    const password = await generatePassword(16, true);
-    if (password) {
-      setCredentialData({ ...credentialData, password });
+    const generated = await generatePassword(16, true);
+    if (generated) {
+      setCredentialData({ ...credentialData, password: generated.password });
+      setPasswordEntropy(generated.entropy_bits);
     }
   };
 
@@ -209,7 +211,10 @@ const CreateCredentialModal: React.FC<CreateCredentialModalProps> = ({ isOpen, o
                   <input
                     type={showPassword ? 'text' : 'password'}
                     value={credentialData.password || ''}
-                    onChange={(e) => setCredentialData({ ...credentialData, password: e.target.value })}
+                    onChange={(e) => {
+                      setCredentialData({ ...credentialData, password: e.target.value });
+                      setPasswordEntropy(null);
+                    }}
                     className="input pr-10"
                     placeholder="Enter password"
                     required
@@ -235,6 +240,11 @@ const CreateCredentialModal: React.FC<CreateCredentialModalProps> = ({ isOpen, o
                   Generate
                 </button>
               </div>
+              {passwordEntropy !== null && (
+                <p className="mt-1 text-xs text-gray-500">
+                  Estimated entropy: {Math.round(passwordEntropy)} bits
+                </p>
+              )}
             </div>
           </div>
         );
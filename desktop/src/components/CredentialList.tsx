@@ -15,7 +15,8 @@ import {
 } from '@heroicons/react/24/outline';
 import { HeartIcon as HeartSolidIcon } from '@heroicons/react/24/solid';
 import { usePersonaService } from '@/hooks/usePersonaService';
-import type { Credential } from '@/types';
+import { MASTER_PASSWORD_REQUIRED } from '@/utils/api';
+import type { Credential, CredentialSecurityScore } from '@/types';
 import { clsx } from 'clsx';
 import toast from 'react-hot-toast';
 import { copyWithAutoClear } from '@/utils/clipboard';
@@ -54,6 +55,12 @@ const getSecurityColor = (level: string) => {
   }
 };
 
+const getScoreColor = (score: number) => {
+  if (score >= 80) return 'text-green-700';
+  if (score >= 50) return 'text-yellow-700';
+  return 'text-red-700';
+};
+
 const getSafeHostname = (url: string) => {
   try {
     return new URL(url).hostname;
@@ -72,19 +79,27 @@ const CredentialList: React.FC<CredentialListProps> = ({ onCreateCredential }) =
   const [selectedCredential, setSelectedCredential] = useState<Credential | null>(null);
   const [showCredentialData, setShowCredentialData] = useState(false);
   const [credentialData, setCredentialData] = useState<any>(null);
+  const [passwordPromptFor, setPasswordPromptFor] = useState<Credential | null>(null);
 
   const filteredCredentials = credentials.filter(cred =>
     cred.name.toLowerCase().includes(searchQuery.toLowerCase()) ||
     cred.credential_type.toLowerCase().includes(searchQuery.toLowerCase())
   );
 
-  const handleCredentialClick = async (credential: Credential) => {
+  const showData = async (credential: Credential, masterPassword?: string) => {
+    const data = await getCredentialData(credential.id, masterPassword);
+    if (data === MASTER_PASSWORD_REQUIRED) {
+      setPasswordPromptFor(credential);
+      return;
+    }
+    setPasswordPromptFor(null);
     setSelectedCredential(credential);
-    const data = await getCredentialData(credential.id);
     setCredentialData(data);
     setShowCredentialData(true);
   };
 
+  const handleCredentialClick = (credential: Credential) => showData(credential);
+
   const copyToClipboard = async (text: string, label: string) => {
     const ok = await copyWithAutoClear(text, 30_000);
     if (ok) {
@@ -156,6 +171,8 @@ const CredentialList: React.FC<CredentialListProps> = ({ onCreateCredential }) =
         <div className="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-4">
           {filteredCredentials.map((credential) => {
             const IconComponent = getCredentialIcon(credential.credential_type);
+  const isTwoFactor = credential.credential_type === 'TwoFactor';
+  const hasTotp = isTwoFactor || Boolean(credential.linked_totp_id);
             return (
               <div
                 key={credential.id}
@@ -206,6 +223,15 @@ const CredentialList: React.FC<CredentialListProps> = ({ onCreateCredential }) =
         </div>
       )}
 
+      {/* Master password fallback for critical credentials without biometrics */}
+      {passwordPromptFor && (
+        <MasterPasswordPrompt
+          credential={passwordPromptFor}
+          onSubmit={(password) => showData(passwordPromptFor, password)}
+          onClose={() => setPasswordPromptFor(null)}
+        />
+      )}
+
       {/* Credential Detail Modal */}
       {showCredentialData && selectedCredential && (
         <CredentialDetailModal
@@ -223,6 +249,61 @@ const CredentialList: React.FC<CredentialListProps> = ({ onCreateCredential }) =
   );
 };
 
+interface MasterPasswordPromptProps {
+  credential: Credential;
+  onSubmit: (password: string) => Promise<void>;
+  onClose: () => void;
+}
+
+const MasterPasswordPrompt: React.FC<MasterPasswordPromptProps> = ({
+  credential,
+  onSubmit,
+  onClose,
+}) => {
+  const [password, setPassword] = useState('');
+  const [isSubmitting, setIsSubmitting] = useState(false);
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!password) return;
+    setIsSubmitting(true);
+    try {
+      await onSubmit(password);
+    } finally {
+      setIsSubmitting(false);
+      setPassword('');
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center p-4 z-50">
+      <form onSubmit={handleSubmit} className="bg-white rounded-lg p-6 w-full max-w-sm">
+        <h2 className="text-lg font-medium text-gray-900">Confirm master password</h2>
+        <p className="text-sm text-gray-500 mt-1 mb-4">
+          Biometric unlock is unavailable. Enter your master password to reveal{' '}
+          <span className="font-medium">{credential.name}</span>.
+        </p>
+        <input
+          type="password"
+          autoFocus
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          className="input w-full"
+          placeholder="Master password"
+        />
+        <div className="flex justify-end gap-2 mt-4">
+          <button type="button" onClick={onClose} className="btn-secondary">
+            Cancel
+          </button>
+          <button type="submit" disabled={isSubmitting || !password} className="btn-primary">
+            Reveal
+          </button>
+        </div>
+      </form>
+    </div>
+  );
+};
+
 interface CredentialDetailModalProps {
   credential: Credential;
   credentialData: any;
@@ -236,7 +317,13 @@ const CredentialDetailModal: React.FC<CredentialDetailModalProps> = ({
   onClose,
   onCopy,
 }) => {
-  const { toggleCredentialFavorite, deleteCredential, getTotpCode } = usePersonaService();
+  const {
+    toggleCredentialFavorite,
+    deleteCredential,
+    getTotpCode,
+    getLinkedTotpCode,
+    getCredentialSecurityScore,
+  } = usePersonaService();
   const [showSensitive, setShowSensitive] = useState(false);
   const [isFavorite, setIsFavorite] = useState(credential.is_favorite);
   const [isTogglingFavorite, setIsTogglingFavorite] = useState(false);
@@ -244,49 +331,65 @@ const CredentialDetailModal: React.FC<CredentialDetailModalProps> = ({
   const [totpCode, setTotpCode] = useState<string | null>(null);
   const [totpRemaining, setTotpRemaining] = useState<number | null>(null);
   const [isTotpLoading, setIsTotpLoading] = useState(false);
+  const [securityScore, setSecurityScore] = useState<CredentialSecurityScore | null>(null);
   const IconComponent = getCredentialIcon(credential.credential_type);
 
   useEffect(() => {
     setIsFavorite(credential.is_favorite);
   }, [credential.id, credential.is_favorite]);
 
+  useEffect(() => {
+    let cancelled = false;
+    setSecurityScore(null);
+    getCredentialSecurityScore(credential.id).then((score) => {
+      if (!cancelled) setSecurityScore(score);
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [credential.id]);
+
   const refreshTotp = useCallback(async () => {
-    if (credential.credential_type !== 'TwoFactor') return;
+    if (!hasTotp) return;
     setIsTotpLoading(true);
     try {
-      const res = await getTotpCode(credential.id);
+      // Password credentials show the code of their linked TOTP entry
+      const res = isTwoFactor
+        ? await getTotpCode(credential.id)
+        : await getLinkedTotpCode(credential.id);
       if (res) {
         setTotpCode(res.code);
-        setTotpRemaining(res.remaining_seconds);
+        // HOTP codes do not expire; counting down would advance the counter on every refresh
+        setTotpRemaining(res.otp_type === 'Hotp' ? null : res.remaining_seconds);
       }
     } finally {
       setIsTotpLoading(false);
     }
-  }, [credential.credential_type, credential.id, getTotpCode]);
+  }, [credential.id, hasTotp, isTwoFactor, getTotpCode, getLinkedTotpCode]);
 
   useEffect(() => {
-    if (credential.credential_type !== 'TwoFactor') {
+    if (!hasTotp) {
       setTotpCode(null);
       setTotpRemaining(null);
       return;
     }
     refreshTotp();
-  }, [credential.id, credential.credential_type, refreshTotp]);
+  }, [credential.id, hasTotp, refreshTotp]);
 
   useEffect(() => {
-    if (credential.credential_type !== 'TwoFactor') return;
+    if (!hasTotp) return;
     if (totpRemaining === null) return;
     const interval = window.setInterval(() => {
       setTotpRemaining((prev) => (prev === null ? null : Math.max(prev - 1, 0)));
     }, 1000);
     return () => window.clearInterval(interval);
-  }, [credential.id, credential.credential_type, totpCode]);
+  }, [credential.id, hasTotp, totpCode]);
 
   useEffect(() => {
-    if (credential.credential_type !== 'TwoFactor') return;
+    if (!hasTotp) return;
     if (totpRemaining !== 0) return;
     refreshTotp();
-  }, [credential.credential_type, refreshTotp, totpRemaining]);
+  }, [hasTotp, refreshTotp, totpRemaining]);
 
   const handleToggleFavorite = async () => {
     if (isTogglingFavorite) return;
@@ -359,6 +462,27 @@ const CredentialDetailModal: React.FC<CredentialDetailModalProps> = ({
                 </button>
               </div>
             </div>
+            {credential.linked_totp_id && (
+              <div>
+                <label className="label text-gray-600">2FA Code</label>
+                <div className="flex items-center gap-2">
+                  <span className="text-lg font-mono tracking-widest">
+                    {totpCode ?? '------'}
+                  </span>
+                  <button
+                    onClick={() => totpCode && onCopy(totpCode, 'TOTP')}
+                    disabled={!totpCode}
+                    className="p-1 hover:bg-gray-100 rounded disabled:opacity-50"
+                    title="Copy code"
+                  >
+                    <DocumentDuplicateIcon className="w-4 h-4 text-gray-400" />
+                  </button>
+                </div>
+                {totpRemaining !== null && (
+                  <p className="mt-1 text-xs text-gray-500">Expires in {totpRemaining}s</p>
+                )}
+              </div>
+            )}
           </div>
         );
 
@@ -546,6 +670,21 @@ const CredentialDetailModal: React.FC<CredentialDetailModalProps> = ({
             )}>
               {credential.security_level}
             </span>
+            {securityScore && (
+              <span
+                className={clsx('text-xs font-medium', getScoreColor(securityScore.score))}
+                title={[
+                  securityScore.reuse_count > 0
+                    ? `Password reused by ${securityScore.reuse_count} other credential(s)`
+                    : null,
+                  securityScore.has_two_factor ? null : 'No linked 2FA',
+                ]
+                  .filter(Boolean)
+                  .join('\n')}
+              >
+                Security score: {securityScore.score}/100
+              </span>
+            )}
             <span className="text-xs text-gray-400">
               Created: {new Date(credential.created_at).toLocaleDateString()}
             </span>
@@ -1,6 +1,6 @@
-import React, { useMemo, useState } from 'react';
+import React, { useEffect, useMemo, useState } from 'react';
 import { usePersonaService } from '@/hooks/usePersonaService';
-import type { Identity, IdentityType } from '@/types';
+import type { BuildInfo, Identity, IdentityType } from '@/types';
 import { PencilSquareIcon, TrashIcon } from '@heroicons/react/24/outline';
 
 interface SettingsModalProps {
@@ -11,8 +11,44 @@ interface SettingsModalProps {
 const identityTypes: IdentityType[] = ['Personal', 'Work', 'Social', 'Financial', 'Gaming'];
 
 const SettingsModal: React.FC<SettingsModalProps> = ({ isOpen, onClose }) => {
-  const { identities, currentIdentity, updateIdentity, deleteIdentity, isLoading } =
-    usePersonaService();
+  const {
+    identities,
+    currentIdentity,
+    updateIdentity,
+    deleteIdentity,
+    getVerificationPhrase,
+    resetVerificationPhrase,
+    vacuumVault,
+    getBuildInfo,
+    isLoading,
+  } = usePersonaService();
+
+  const [verificationPhrase, setVerificationPhrase] = useState<string | null>(null);
+  const [buildInfo, setBuildInfo] = useState<BuildInfo | null>(null);
+
+  useEffect(() => {
+    if (isOpen) {
+      getVerificationPhrase().then(setVerificationPhrase);
+      getBuildInfo().then(setBuildInfo);
+    }
+  }, [isOpen]);
+
+  const handleResetPhrase = async () => {
+    const confirmed = window.confirm(
+      'Generate a new verification phrase? The unlock screen will show the new phrase from now on.',
+    );
+    if (!confirmed) return;
+    const phrase = await resetVerificationPhrase();
+    if (phrase) setVerificationPhrase(phrase);
+  };
+
+  const [isVacuuming, setIsVacuuming] = useState(false);
+
+  const handleVacuum = async () => {
+    setIsVacuuming(true);
+    await vacuumVault();
+    setIsVacuuming(false);
+  };
 
   const [editingId, setEditingId] = useState<string | null>(null);
   const [draft, setDraft] = useState<Partial<Identity>>({});
@@ -78,7 +114,7 @@ const SettingsModal: React.FC<SettingsModalProps> = ({ isOpen, onClose }) => {
         <div className="p-6 border-b border-gray-100 flex items-center justify-between">
           <div>
             <h2 className="text-lg font-semibold text-gray-900">Settings</h2>
-            <p className="text-sm text-gray-500">Manage identities</p>
+            <p className="text-sm text-gray-500">Manage identities and vault security</p>
           </div>
           <button onClick={onClose} className="p-2 hover:bg-gray-100 rounded-lg" title="Close">
             ✕
@@ -248,6 +284,44 @@ const SettingsModal: React.FC<SettingsModalProps> = ({ isOpen, onClose }) => {
               })
             )}
           </div>
+
+          <div className="mt-6 border-t border-gray-100 pt-6">
+            <h3 className="text-sm font-semibold text-gray-900">Verification phrase</h3>
+            <p className="text-xs text-gray-500">
+              Shown on the unlock screen so you can tell a genuine Persona prompt from a fake one.
+            </p>
+            <div className="mt-3 flex items-center justify-between gap-4">
+              <span className="text-sm font-medium text-gray-800">
+                {verificationPhrase ?? 'Not set'}
+              </span>
+              <button type="button" onClick={handleResetPhrase} className="btn-secondary">
+                {verificationPhrase ? 'Reset' : 'Create'}
+              </button>
+            </div>
+          </div>
+
+          <div className="mt-6 border-t border-gray-100 pt-6">
+            <h3 className="text-sm font-semibold text-gray-900">Maintenance</h3>
+            <p className="text-xs text-gray-500">
+              Reclaim space left behind by deleted items and check the vault database for corruption.
+            </p>
+            <div className="mt-3 flex justify-end">
+              <button
+                type="button"
+                onClick={handleVacuum}
+                disabled={isVacuuming}
+                className="btn-secondary"
+              >
+                {isVacuuming ? 'Compacting…' : 'Compact vault'}
+              </button>
+            </div>
+          </div>
+
+          {buildInfo && (
+            <p className="mt-6 text-right text-xs text-gray-400">
+              Persona v{buildInfo.version} ({buildInfo.git_hash})
+            </p>
+          )}
         </div>
       </div>
     </div>
@@ -1,5 +1,5 @@
-import React, { useState } from 'react';
-import { EyeIcon, EyeSlashIcon, KeyIcon } from '@heroicons/react/24/outline';
+import React, { useEffect, useState } from 'react';
+import { EyeIcon, EyeSlashIcon, KeyIcon, ShieldCheckIcon } from '@heroicons/react/24/outline';
 import { usePersonaService } from '@/hooks/usePersonaService';
 
 interface UnlockScreenProps {
@@ -12,7 +12,20 @@ const UnlockScreen: React.FC<UnlockScreenProps> = ({ onUnlock }) => {
   const [dbPath, setDbPath] = useState('');
   const [useCustomPath, setUseCustomPath] = useState(false);
 
-  const { initializeService, isLoading, error } = usePersonaService();
+  const [verificationPhrase, setVerificationPhrase] = useState<string | null>(null);
+
+  const { initializeService, getVerificationPhrase, isLoading, error } = usePersonaService();
+
+  useEffect(() => {
+    let cancelled = false;
+    const path = useCustomPath && dbPath.trim() ? dbPath.trim() : undefined;
+    getVerificationPhrase(path).then((phrase) => {
+      if (!cancelled) setVerificationPhrase(phrase);
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [useCustomPath, dbPath]);
 
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
@@ -38,6 +51,18 @@ const UnlockScreen: React.FC<UnlockScreenProps> = ({ onUnlock }) => {
 
         {/* Unlock Form */}
         <div className="card p-6">
+          {verificationPhrase && (
+            <div className="mb-4 rounded-md border border-primary-200 bg-primary-50 p-3">
+              <div className="flex items-center gap-2">
+                <ShieldCheckIcon className="h-5 w-5 text-primary-600" />
+                <span className="text-sm font-semibold text-primary-800">{verificationPhrase}</span>
+              </div>
+              <p className="mt-1 text-xs text-secondary-600">
+                If this is not your verification phrase, do not enter your master password.
+              </p>
+            </div>
+          )}
+
           <form onSubmit={handleSubmit} className="space-y-4">
             <div>
               <label htmlFor="master-password" className="label text-secondary-700 mb-2 block">
@@ -11,13 +11,19 @@ import {
 import { LoadingSpinner, ErrorDisplay, ErrorBoundary } from '@/components/ErrorHandling';
 import { personaAPI } from '@/utils/api';
 import { usePersonaService } from '@/hooks/usePersonaService';
-import type { WalletAddress, WalletGenerateResponse, WalletSummary } from '@/types';
+import type {
+  WalletAddress,
+  WalletGenerateResponse,
+  WalletSummary,
+  WalletTransactionEvent,
+} from '@/types';
 
 const WalletPanel: React.FC = () => {
   const { currentIdentity } = usePersonaService();
   const [wallets, setWallets] = useState<WalletSummary[]>([]);
   const [selectedWallet, setSelectedWallet] = useState<WalletSummary | null>(null);
   const [addresses, setAddresses] = useState<WalletAddress[]>([]);
+  const [history, setHistory] = useState<WalletTransactionEvent[]>([]);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [showCreateModal, setShowCreateModal] = useState(false);
@@ -38,6 +44,7 @@ const WalletPanel: React.FC = () => {
     name: '',
     network: 'Ethereum',
     password: '',
+    passphrase: '',
     addressCount: 5,
   });
   const [createResult, setCreateResult] = useState<WalletGenerateResponse | null>(null);
@@ -47,6 +54,7 @@ const WalletPanel: React.FC = () => {
     importType: 'mnemonic' as 'mnemonic' | 'private_key',
     data: '',
     password: '',
+    passphrase: '',
     addressCount: 5,
   });
 
@@ -59,6 +67,7 @@ const WalletPanel: React.FC = () => {
   useEffect(() => {
     if (selectedWallet) {
       loadAddresses(selectedWallet.id);
+      loadHistory(selectedWallet.id);
     }
   }, [selectedWallet]);
 
@@ -70,6 +79,7 @@ const WalletPanel: React.FC = () => {
         setWallets([]);
         setSelectedWallet(null);
         setAddresses([]);
+        setHistory([]);
         return;
       }
       const response = await personaAPI.walletList(currentIdentity.id);
@@ -96,6 +106,18 @@ const WalletPanel: React.FC = () => {
     }
   };
 
+  const loadHistory = async (walletId: string) => {
+    try {
+      const response = await personaAPI.walletTransactionHistory(walletId);
+      if (!response.success) {
+        throw new Error(response.error || 'Failed to load transaction history');
+      }
+      setHistory(response.data?.events || []);
+    } catch (err: any) {
+      console.error('Failed to load transaction history:', err);
+    }
+  };
+
   const exportWallet = (walletId: string, walletName: string) => {
     setExportWalletId(walletId);
     setExportWalletName(walletName);
@@ -169,6 +191,7 @@ const WalletPanel: React.FC = () => {
         wallet_type: 'hd',
         password: createForm.password,
         address_count: createForm.addressCount,
+        passphrase: createForm.passphrase || undefined,
       });
       if (!response.success || !response.data) {
         throw new Error(response.error || 'Failed to create wallet');
@@ -193,6 +216,10 @@ const WalletPanel: React.FC = () => {
         data: importForm.data,
         password: importForm.password,
         address_count: importForm.importType === 'mnemonic' ? importForm.addressCount : undefined,
+        passphrase:
+          importForm.importType === 'mnemonic' && importForm.passphrase
+            ? importForm.passphrase
+            : undefined,
       });
       if (!response.success || !response.data) {
         throw new Error(response.error || 'Failed to import wallet');
@@ -204,6 +231,7 @@ const WalletPanel: React.FC = () => {
         importType: 'mnemonic',
         data: '',
         password: '',
+        passphrase: '',
         addressCount: 5,
       });
       await loadWallets();
@@ -509,6 +537,61 @@ const WalletPanel: React.FC = () => {
                 </div>
               )}
             </div>
+
+            <h3 className="text-lg font-semibold text-gray-900 mt-6 mb-4">Transaction History</h3>
+            <div className="overflow-x-auto">
+              <table className="min-w-full divide-y divide-gray-200">
+                <thead>
+                  <tr>
+                    <th className="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">
+                      Time
+                    </th>
+                    <th className="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">
+                      Transaction
+                    </th>
+                    <th className="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">
+                      Status
+                    </th>
+                    <th className="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">
+                      Hash
+                    </th>
+                  </tr>
+                </thead>
+                <tbody className="divide-y divide-gray-200">
+                  {history.map((event) => (
+                    <tr key={event.id}>
+                      <td className="px-4 py-2 text-sm text-gray-900">
+                        {new Date(event.created_at).toLocaleString()}
+                      </td>
+                      <td className="px-4 py-2 text-sm font-mono text-gray-900">
+                        {event.request_id.slice(0, 8)}
+                      </td>
+                      <td className="px-4 py-2">
+                        <span
+                          className={`inline-flex items-center px-2 py-1 rounded-full text-xs font-medium ${
+                            event.status === 'Failed'
+                              ? 'bg-red-100 text-red-800'
+                              : event.status === 'Confirmed'
+                                ? 'bg-green-100 text-green-800'
+                                : 'bg-gray-100 text-gray-800'
+                          }`}
+                          title={event.detail ?? undefined}
+                        >
+                          {event.status}
+                        </span>
+                      </td>
+                      <td className="px-4 py-2 text-sm font-mono text-gray-900 break-all">
+                        {event.transaction_hash ?? '-'}
+                      </td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+
+              {history.length === 0 && (
+                <div className="text-center py-8 text-gray-500">No transactions yet</div>
+              )}
+            </div>
           </div>
         )}
 
@@ -604,6 +687,18 @@ const WalletPanel: React.FC = () => {
                       placeholder="At least 8 characters"
                     />
                   </div>
+                  <div>
+                    <label className="block text-sm font-medium text-gray-700 mb-1">
+                      BIP-39 Passphrase (optional)
+                    </label>
+                    <input
+                      type="password"
+                      value={createForm.passphrase}
+                      onChange={(e) => setCreateForm({ ...createForm, passphrase: e.target.value })}
+                      className="w-full px-3 py-2 border border-gray-300 rounded-lg"
+                      placeholder="Not stored; needed with the recovery phrase to restore"
+                    />
+                  </div>
                   <div className="flex gap-2">
                     <button
                       onClick={() => {
@@ -613,6 +708,7 @@ const WalletPanel: React.FC = () => {
                           name: '',
                           network: 'Ethereum',
                           password: '',
+                          passphrase: '',
                           addressCount: 5,
                         });
                       }}
@@ -650,6 +746,7 @@ const WalletPanel: React.FC = () => {
                         name: '',
                         network: 'Ethereum',
                         password: '',
+                        passphrase: '',
                         addressCount: 5,
                       });
                     }}
@@ -754,6 +851,20 @@ const WalletPanel: React.FC = () => {
                     placeholder="At least 8 characters"
                   />
                 </div>
+                {importForm.importType === 'mnemonic' && (
+                  <div>
+                    <label className="block text-sm font-medium text-gray-700 mb-1">
+                      BIP-39 Passphrase (optional)
+                    </label>
+                    <input
+                      type="password"
+                      value={importForm.passphrase}
+                      onChange={(e) => setImportForm({ ...importForm, passphrase: e.target.value })}
+                      className="w-full px-3 py-2 border border-gray-300 rounded-lg"
+                      placeholder="Leave empty if the phrase has none"
+                    />
+                  </div>
+                )}
                 <div className="flex gap-2">
                   <button
                     onClick={() => {
@@ -764,6 +875,7 @@ const WalletPanel: React.FC = () => {
                         importType: 'mnemonic',
                         data: '',
                         password: '',
+                        passphrase: '',
                         addressCount: 5,
                       });
                     }}
@@ -790,16 +902,25 @@ const WalletPanel: React.FC = () => {
             <div className="bg-white rounded-lg p-6 max-w-md w-full mx-4">
               <h3 className="text-lg font-semibold mb-4">Generate Address</h3>
               <div className="space-y-4">
-                <div className="text-sm text-gray-600">
-                  Enter the wallet password for <span className="font-medium">{selectedWallet?.name}</span>.
-                </div>
-                <input
-                  type="password"
-                  value={addAddressPassword}
-                  onChange={(e) => setAddAddressPassword(e.target.value)}
-                  className="w-full px-3 py-2 border border-gray-300 rounded-lg"
-                  placeholder="Wallet password"
-                />
+                {selectedWallet?.watch_only ? (
+                  <div className="text-sm text-gray-600">
+                    The next address of <span className="font-medium">{selectedWallet?.name}</span> is
+                    derived from its extended public key; no password is needed.
+                  </div>
+                ) : (
+                  <>
+                    <div className="text-sm text-gray-600">
+                      Enter the wallet password for <span className="font-medium">{selectedWallet?.name}</span>.
+                    </div>
+                    <input
+                      type="password"
+                      value={addAddressPassword}
+                      onChange={(e) => setAddAddressPassword(e.target.value)}
+                      className="w-full px-3 py-2 border border-gray-300 rounded-lg"
+                      placeholder="Wallet password"
+                    />
+                  </>
+                )}
                 <div className="flex gap-2">
                   <button
                     onClick={() => {
@@ -813,7 +934,7 @@ const WalletPanel: React.FC = () => {
                   <button
                     onClick={addAddress}
                     className="flex-1 px-4 py-2 bg-indigo-600 text-white rounded-lg hover:bg-indigo-700 transition-colors"
-                    disabled={!addAddressPassword}
+                    disabled={!selectedWallet?.watch_only && !addAddressPassword}
                   >
                     Generate
                   </button>
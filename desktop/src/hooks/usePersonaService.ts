@@ -1,7 +1,17 @@
 import { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { useAppStore } from '@/stores/appStore';
-import { personaAPI } from '@/utils/api';
-import type { Identity } from '@/types';
+import { MASTER_PASSWORD_REQUIRED, personaAPI } from '@/utils/api';
+import type {
+  CredentialSecurityScore,
+  GeneratedPassword,
+  Identity,
+  VacuumReport,
+  BuildInfo,
+  ServiceLockedEvent,
+  VaultEvent,
+  VaultSecurityScore,
+} from '@/types';
 import toast from 'react-hot-toast';
 
 export const usePersonaService = () => {
@@ -32,6 +42,63 @@ export const usePersonaService = () => {
     checkServiceStatus();
   }, []);
 
+  // Keep this window in sync with changes made elsewhere (other windows, agent reloads)
+  useEffect(() => {
+    const unlisten = listen<VaultEvent>('vault-event', ({ payload }) => {
+      const current = useAppStore.getState().currentIdentity;
+      switch (payload.type) {
+        case 'identity_created':
+        case 'identity_updated':
+        case 'identity_deleted':
+          loadIdentities();
+          break;
+        case 'credential_created':
+        case 'credential_updated':
+        case 'credential_deleted':
+          if (current && current.id === payload.identity_id) {
+            loadCredentialsForIdentity(current.id);
+          }
+          break;
+        case 'locked':
+          setUnlocked(false);
+          break;
+        case 'agent_keys_reloaded':
+          loadSshKeys();
+          break;
+        case 'backup_failed':
+          toast.error(`Scheduled backup failed: ${payload.error}`);
+          break;
+        case 'resync':
+          loadIdentities();
+          if (current) {
+            loadCredentialsForIdentity(current.id);
+          }
+          break;
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // The backend locks on idle time, sleep and screen lock; drop back to the unlock screen
+  useEffect(() => {
+    const unlisten = listen<ServiceLockedEvent>('service-locked', ({ payload }) => {
+      setUnlocked(false);
+      if (payload.ssh_agent_stopped) {
+        refreshSshAgentStatus();
+      }
+      toast(
+        payload.reason === 'inactivity'
+          ? 'Vault locked after inactivity'
+          : 'Vault locked with the system',
+      );
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const checkServiceStatus = async () => {
     try {
       const response = await personaAPI.isServiceUnlocked();
@@ -79,6 +146,61 @@ export const usePersonaService = () => {
     }
   };
 
+  const getVerificationPhrase = async (dbPath?: string): Promise<string | null> => {
+    try {
+      const response = await personaAPI.getVerificationPhrase(dbPath);
+      return response.success ? response.data ?? null : null;
+    } catch (err) {
+      return null;
+    }
+  };
+
+  const resetVerificationPhrase = async (): Promise<string | null> => {
+    try {
+      const response = await personaAPI.resetVerificationPhrase();
+      if (response.success && response.data) {
+        toast.success('Verification phrase reset');
+        return response.data;
+      }
+      toast.error(response.error || 'Failed to reset verification phrase');
+      return null;
+    } catch (err) {
+      toast.error('Failed to reset verification phrase');
+      return null;
+    }
+  };
+
+  const getBuildInfo = async (): Promise<BuildInfo | null> => {
+    try {
+      const response = await personaAPI.getBuildInfo();
+      return response.success ? response.data ?? null : null;
+    } catch (err) {
+      return null;
+    }
+  };
+
+  const vacuumVault = async (incremental = false): Promise<VacuumReport | null> => {
+    try {
+      const response = await personaAPI.vacuumVault(incremental);
+      if (!response.success || !response.data) {
+        toast.error(response.error || 'Failed to vacuum vault');
+        return null;
+      }
+      const report = response.data;
+      if (report.integrity_errors.length > 0) {
+        toast.error('Integrity check failed; restore from a backup');
+      } else {
+        const pageSize = report.before.page_size;
+        const reclaimed = Math.max(report.before.page_count - report.after.page_count, 0);
+        toast.success(`Vault compacted (${Math.round((reclaimed * pageSize) / 1024)} KB reclaimed)`);
+      }
+      return report;
+    } catch (err) {
+      toast.error('Failed to vacuum vault');
+      return null;
+    }
+  };
+
   const lockService = async () => {
     try {
       const response = await personaAPI.lockService();
@@ -156,6 +278,7 @@ export const usePersonaService = () => {
         email: identity.email,
         phone: identity.phone,
         tags: identity.tags,
+        version: identity.version,
       });
 
       if (response.success && response.data) {
@@ -257,26 +380,33 @@ export const usePersonaService = () => {
     }
   };
 
-  const generatePassword = async (length: number = 16, includeSymbols: boolean = true) => {
+  const generatePassword = async (
+    length: number = 16,
+    includeSymbols: boolean = true,
+  ): Promise<GeneratedPassword | null> => {
     try {
       const response = await personaAPI.generatePassword(length, includeSymbols);
       if (response.success && response.data) {
         return response.data;
       } else {
         toast.error(response.error || 'Failed to generate password');
-        return '';
+        return null;
       }
     } catch (err) {
       toast.error('Failed to generate password');
-      return '';
+      return null;
     }
   };
 
-  const getCredentialData = async (credentialId: string) => {
+  // Resolves to MASTER_PASSWORD_REQUIRED when a critical credential can't be unlocked with
+  // biometrics; call again with the master password
+  const getCredentialData = async (credentialId: string, masterPassword?: string) => {
     try {
-      const response = await personaAPI.getCredentialData(credentialId);
+      const response = await personaAPI.getCredentialData(credentialId, masterPassword);
       if (response.success) {
         return response.data;
+      } else if (response.error === MASTER_PASSWORD_REQUIRED) {
+        return MASTER_PASSWORD_REQUIRED;
       } else {
         toast.error(response.error || 'Failed to get credential data');
         return null;
@@ -301,6 +431,40 @@ export const usePersonaService = () => {
     }
   };
 
+  const getLinkedTotpCode = async (credentialId: string) => {
+    try {
+      const response = await personaAPI.getLinkedTotpCode(credentialId);
+      return response.success ? response.data ?? null : null;
+    } catch (err) {
+      return null;
+    }
+  };
+
+  const getCredentialSecurityScore = async (
+    credentialId: string,
+  ): Promise<CredentialSecurityScore | null> => {
+    try {
+      const response = await personaAPI.getCredentialSecurityScore(credentialId);
+      return response.success ? response.data ?? null : null;
+    } catch (err) {
+      return null;
+    }
+  };
+
+  const getVaultSecurityScore = async (): Promise<VaultSecurityScore | null> => {
+    try {
+      const response = await personaAPI.getVaultSecurityScore();
+      if (response.success && response.data) {
+        return response.data;
+      }
+      toast.error(response.error || 'Failed to compute security score');
+      return null;
+    } catch (err) {
+      toast.error('Failed to compute security score');
+      return null;
+    }
+  };
+
   const toggleCredentialFavorite = async (credentialId: string) => {
     try {
       const response = await personaAPI.toggleCredentialFavorite(credentialId);
@@ -404,6 +568,10 @@ export const usePersonaService = () => {
     // Actions
     initializeService,
     lockService,
+    getVerificationPhrase,
+    resetVerificationPhrase,
+    vacuumVault,
+    getBuildInfo,
     loadIdentities,
     createIdentity,
     updateIdentity,
@@ -415,6 +583,9 @@ export const usePersonaService = () => {
     generatePassword,
     getCredentialData,
     getTotpCode,
+    getLinkedTotpCode,
+    getCredentialSecurityScore,
+    getVaultSecurityScore,
     toggleCredentialFavorite,
     deleteCredential,
     refreshSshAgentStatus,
@@ -6,7 +6,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/tauri';
-import type { ApiResponse, Identity, Credential } from '@/types';
+import type { ApiResponse, Identity, Credential, GeneratedPassword } from '@/types';
 import { personaAPI } from '@/utils/api';
 
 // Mock Tauri invoke for testing
@@ -85,6 +85,7 @@ describe('Desktop Application Integration Tests', () => {
       created_at: '2023-01-01T00:00:00Z',
       updated_at: '2023-01-01T00:00:00Z',
       is_active: true,
+      version: 1,
     };
 
     it('should create a new identity', async () => {
@@ -147,6 +148,7 @@ describe('Desktop Application Integration Tests', () => {
         created_at: new Date().toISOString(),
         updated_at: new Date().toISOString(),
         is_active: true,
+        version: 2,
       };
 
       const mockResponse: ApiResponse<Identity> = {
@@ -215,6 +217,7 @@ describe('Desktop Application Integration Tests', () => {
       last_accessed: undefined,
       is_active: true,
       is_favorite: false,
+      version: 1,
     };
 
     it('should create a password credential', async () => {
@@ -343,15 +346,15 @@ describe('Desktop Application Integration Tests', () => {
 
   describe('Utility Functions', () => {
     it('should generate a password', async () => {
-      const mockResponse: ApiResponse<string> = {
+      const mockResponse: ApiResponse<GeneratedPassword> = {
         success: true,
-        data: 'GeneratedPassword123!',
+        data: { password: 'GeneratedPassword123!', entropy_bits: 104.9 },
         error: undefined,
       };
 
       mockInvoke.mockResolvedValue(mockResponse);
 
-      const result = await invokeApi<string>('generate_password', {
+      const result = await invokeApi<GeneratedPassword>('generate_password', {
         length: 16,
         include_symbols: true,
       });
@@ -361,7 +364,8 @@ describe('Desktop Application Integration Tests', () => {
         include_symbols: true,
       });
 
-      expect(result.data).toBe('GeneratedPassword123!');
+      expect(result.data?.password).toBe('GeneratedPassword123!');
+      expect(result.data?.entropy_bits).toBeGreaterThan(100);
     });
 
     it('should retrieve service statistics', async () => {
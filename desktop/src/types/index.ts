@@ -17,6 +17,7 @@ export interface Identity {
   created_at: string;
   updated_at: string;
   is_active: boolean;
+  version: number;
 }
 
 export interface Credential {
@@ -34,6 +35,13 @@ export interface Credential {
   last_accessed?: string;
   is_active: boolean;
   is_favorite: boolean;
+  linked_totp_id?: string;
+  version: number;
+}
+
+export interface CredentialSearchHit extends Credential {
+  score: number;
+  matched_field?: 'name' | 'username' | 'url' | 'tags' | 'notes';
 }
 
 export interface CredentialData {
@@ -57,6 +65,7 @@ export interface UpdateIdentityRequest {
   email?: string;
   phone?: string;
   tags?: string[];
+  version?: number;
 }
 
 export interface CreateCredentialRequest {
@@ -76,7 +85,7 @@ export type CredentialDataRequest =
   | { type: 'CryptoWallet'; wallet_type: string; mnemonic_phrase?: string; private_key?: string; public_key: string; address: string; network: string }
   | { type: 'SshKey'; private_key: string; public_key: string; key_type: string; passphrase?: string }
   | { type: 'ApiKey'; api_key: string; api_secret?: string; token?: string; permissions: string[]; expires_at?: string }
-  | { type: 'TwoFactor'; secret_key: string; issuer: string; account_name: string; algorithm: string; digits: number; period: number }
+  | { type: 'TwoFactor'; secret_key: string; issuer: string; account_name: string; algorithm: string; digits: number; period: number; otp_type?: 'Totp' | 'Hotp'; counter?: number; time_offset_seconds?: number }
   | { type: 'Raw'; data: number[] };
 
 export interface SecurityQuestion {
@@ -111,20 +120,77 @@ export interface Statistics {
   security_levels: Record<string, number>;
 }
 
+export interface CredentialSecurityScore {
+  credential_id: string;
+  name: string;
+  /** 0-100: strength, reuse, rotation age, linked 2FA and https */
+  score: number;
+  password_entropy_bits: number | null;
+  reuse_count: number;
+  has_two_factor: boolean;
+}
+
+export interface VaultSecurityScore {
+  score: number;
+  credentials: CredentialSecurityScore[];
+}
+
+/** Credential named in a password audit finding; audits never return passwords */
+export interface AuditedCredential {
+  credential_id: string;
+  identity_id: string;
+  name: string;
+}
+
+export interface PasswordAuditReport {
+  generated_at: string;
+  checked: number;
+  options: { min_entropy_bits: number; max_age_days: number };
+  /** Groups of credentials sharing one password, largest first */
+  reused: AuditedCredential[][];
+  weak: (AuditedCredential & { entropy_bits: number })[];
+  stale: (AuditedCredential & { last_rotated_at: string; age_days: number })[];
+}
+
 export interface TotpCodeResponse {
   code: string;
+  /** 0 for HOTP codes, which stay valid until used */
   remaining_seconds: number;
   period: number;
   digits: number;
   algorithm: string;
   issuer: string;
   account_name: string;
+  otp_type: 'Totp' | 'Hotp';
+}
+
+/** Result of checking an entered TOTP code; `step` is the matched time step */
+export type TotpVerification =
+  | { status: 'accepted'; step: number }
+  | { status: 'replayed'; step: number }
+  | { status: 'rejected' };
+
+export interface GeneratedPassword {
+  password: string;
+  /** Estimated entropy in bits, from the character set and length */
+  entropy_bits: number;
 }
 
+export interface PasswordStrength {
+  /** Upper-bound entropy in bits */
+  entropy_bits: number;
+  /** 0 (very weak) to 4 (very strong) */
+  score: number;
+  warnings: string[];
+}
+
+export type NetworkKind = 'mainnet' | 'testnet' | 'regtest';
+
 export interface WalletSummary {
   id: string;
   name: string;
   network: string;
+  network_kind: NetworkKind;
   wallet_type: string;
   balance: string;
   address_count: number;
@@ -151,12 +217,27 @@ export interface WalletAddressesResponse {
   addresses: WalletAddress[];
 }
 
+export interface WalletTransactionEvent {
+  id: string;
+  request_id: string;
+  status: 'Created' | 'Signed' | 'Broadcast' | 'Confirmed' | 'Failed';
+  transaction_hash?: string | null;
+  detail?: string | null;
+  created_at: string;
+}
+
+export interface WalletTransactionHistoryResponse {
+  events: WalletTransactionEvent[];
+}
+
 export interface WalletGenerateRequest {
   name: string;
   network: string;
   wallet_type: 'hd';
   password: string;
   address_count?: number;
+  network_kind?: NetworkKind;
+  passphrase?: string;
 }
 
 export interface WalletGenerateResponse {
@@ -174,6 +255,8 @@ export interface WalletImportRequest {
   data: string;
   password: string;
   address_count?: number;
+  network_kind?: NetworkKind;
+  passphrase?: string;
 }
 
 export interface WalletExportRequest {
@@ -187,7 +270,66 @@ export type IdentityType = 'Personal' | 'Work' | 'Social' | 'Financial' | 'Gamin
 export type CredentialType = 'Password' | 'CryptoWallet' | 'SshKey' | 'ApiKey' | 'BankCard' | 'GameAccount' | 'ServerConfig' | 'Certificate' | 'TwoFactor';
 export type SecurityLevel = 'Critical' | 'High' | 'Medium' | 'Low';
 
+export type VaultEvent =
+  | { type: 'identity_created' | 'identity_updated' | 'identity_deleted'; identity_id: string }
+  | {
+      type: 'credential_created' | 'credential_updated' | 'credential_deleted';
+      credential_id: string;
+      identity_id: string;
+    }
+  | { type: 'locked' | 'unlocked' | 'resync' }
+  | { type: 'agent_keys_reloaded'; key_count: number }
+  | { type: 'backup_created'; path: string }
+  | { type: 'backup_failed'; error: string };
+
+/** Inactivity auto-lock settings from the vault's default policy */
+export interface AutoLockSettings {
+  /** Idle seconds before the service locks; 0 disables auto-lock */
+  inactivity_timeout_secs: number;
+  stop_ssh_agent: boolean;
+  /** System events that lock regardless of idle time */
+  lock_triggers: { on_sleep: boolean; on_screen_lock: boolean };
+}
+
+/** Payload of the `service-locked` event sent when the backend auto-locks */
+export interface ServiceLockedEvent {
+  reason: 'inactivity' | 'sleep' | 'resume' | 'screen_locked';
+  ssh_agent_stopped: boolean;
+}
+
+export interface SpaceUsage {
+  page_size: number;
+  page_count: number;
+  /** Pages on the freelist, left behind by deletes */
+  free_pages: number;
+}
+
+export interface VacuumReport {
+  incremental: boolean;
+  before: SpaceUsage;
+  after: SpaceUsage;
+  /** Problems reported by the integrity check; empty when the database is sound */
+  integrity_errors: string[];
+}
+
+export interface VaultFingerprint {
+  /** Emoji separated by spaces */
+  emoji: string;
+  /** Names of the same emoji, for reading aloud */
+  words: string[];
+  hex: string;
+}
+
+export interface BuildInfo {
+  version: string;
+  /** Short git revision, or "unknown" for builds outside a checkout */
+  git_hash: string;
+  features: string[];
+}
+
 export interface InitRequest {
   master_password: string;
   db_path?: string;
+  /** Open the in-memory demo vault (fake data, nothing saved) */
+  demo?: boolean;
 }
@@ -4,15 +4,21 @@ import type {
   Identity,
   Credential,
   CredentialData,
+  CredentialSearchHit,
   CreateIdentityRequest,
   UpdateIdentityRequest,
   CreateCredentialRequest,
   Statistics,
   InitRequest,
+  AutoLockSettings,
+  VacuumReport,
+  BuildInfo,
+  VaultFingerprint,
   SshAgentStatus,
   SshAgentKey,
   WalletListResponse,
   WalletAddressesResponse,
+  WalletTransactionHistoryResponse,
   WalletGenerateRequest,
   WalletGenerateResponse,
   WalletImportRequest,
@@ -20,6 +26,12 @@ import type {
   WalletSummary,
   WalletAddress,
   TotpCodeResponse,
+  TotpVerification,
+  GeneratedPassword,
+  PasswordStrength,
+  CredentialSecurityScore,
+  VaultSecurityScore,
+  PasswordAuditReport,
 } from '@/types';
 
 class PersonaAPI {
@@ -31,10 +43,34 @@ class PersonaAPI {
     return invoke('lock_service');
   }
 
+  async reloadAutoLockPolicy(): Promise<ApiResponse<AutoLockSettings>> {
+    return invoke('reload_auto_lock_policy');
+  }
+
   async isServiceUnlocked(): Promise<ApiResponse<boolean>> {
     return invoke('is_service_unlocked');
   }
 
+  async getVerificationPhrase(dbPath?: string): Promise<ApiResponse<string | null>> {
+    return invoke('get_verification_phrase', { db_path: dbPath });
+  }
+
+  async resetVerificationPhrase(): Promise<ApiResponse<string>> {
+    return invoke('reset_verification_phrase');
+  }
+
+  async getVaultFingerprint(dbPath?: string): Promise<ApiResponse<VaultFingerprint | null>> {
+    return invoke('get_vault_fingerprint', { db_path: dbPath });
+  }
+
+  async vacuumVault(incremental = false): Promise<ApiResponse<VacuumReport>> {
+    return invoke('vacuum_vault', { incremental });
+  }
+
+  async getBuildInfo(): Promise<ApiResponse<BuildInfo>> {
+    return invoke('get_build_info');
+  }
+
   async createIdentity(request: CreateIdentityRequest): Promise<ApiResponse<Identity>> {
     return invoke('create_identity', { request });
   }
@@ -63,22 +99,79 @@ class PersonaAPI {
     return invoke('get_credentials_for_identity', { identity_id: identityId });
   }
 
-  async getCredentialData(credentialId: string): Promise<ApiResponse<CredentialData | null>> {
-    return invoke('get_credential_data', { credential_id: credentialId });
+  async getCredentialData(
+    credentialId: string,
+    masterPassword?: string
+  ): Promise<ApiResponse<CredentialData | null>> {
+    return invoke('get_credential_data', {
+      credential_id: credentialId,
+      master_password: masterPassword ?? null,
+    });
   }
 
   async getTotpCode(credentialId: string): Promise<ApiResponse<TotpCodeResponse>> {
     return invoke('get_totp_code', { credential_id: credentialId });
   }
 
-  async searchCredentials(query: string): Promise<ApiResponse<Credential[]>> {
+  async verifyTotpCode(
+    credentialId: string,
+    code: string,
+    skewSteps?: number,
+  ): Promise<ApiResponse<TotpVerification>> {
+    return invoke('verify_totp_code', {
+      credential_id: credentialId,
+      code,
+      skew_steps: skewSteps ?? null,
+    });
+  }
+
+  async getLinkedTotpCode(credentialId: string): Promise<ApiResponse<TotpCodeResponse | null>> {
+    return invoke('get_linked_totp_code', { credential_id: credentialId });
+  }
+
+  async linkTotp(credentialId: string, totpId: string): Promise<ApiResponse<Credential>> {
+    return invoke('link_totp', { credential_id: credentialId, totp_id: totpId });
+  }
+
+  async unlinkTotp(credentialId: string): Promise<ApiResponse<Credential>> {
+    return invoke('unlink_totp', { credential_id: credentialId });
+  }
+
+  async getCredentialSecurityScore(
+    credentialId: string,
+  ): Promise<ApiResponse<CredentialSecurityScore | null>> {
+    return invoke('get_credential_security_score', { credential_id: credentialId });
+  }
+
+  async getVaultSecurityScore(): Promise<ApiResponse<VaultSecurityScore>> {
+    return invoke('get_vault_security_score');
+  }
+
+  async getPasswordAuditReport(
+    minEntropyBits?: number,
+    maxAgeDays?: number,
+  ): Promise<ApiResponse<PasswordAuditReport>> {
+    return invoke('get_password_audit_report', {
+      min_entropy_bits: minEntropyBits ?? null,
+      max_age_days: maxAgeDays ?? null,
+    });
+  }
+
+  async searchCredentials(query: string): Promise<ApiResponse<CredentialSearchHit[]>> {
     return invoke('search_credentials', { query });
   }
 
-  async generatePassword(length: number, includeSymbols: boolean): Promise<ApiResponse<string>> {
+  async generatePassword(
+    length: number,
+    includeSymbols: boolean,
+  ): Promise<ApiResponse<GeneratedPassword>> {
     return invoke('generate_password', { length, include_symbols: includeSymbols });
   }
 
+  async checkPasswordStrength(password: string): Promise<ApiResponse<PasswordStrength>> {
+    return invoke('check_password_strength', { password });
+  }
+
   async getStatistics(): Promise<ApiResponse<Statistics>> {
     return invoke('get_statistics');
   }
@@ -118,6 +211,12 @@ class PersonaAPI {
     return invoke('wallet_list_addresses', { wallet_id: walletId });
   }
 
+  async walletTransactionHistory(
+    walletId: string,
+  ): Promise<ApiResponse<WalletTransactionHistoryResponse>> {
+    return invoke('wallet_transaction_history', { wallet_id: walletId });
+  }
+
   async walletGenerate(
     identityId: string,
     request: WalletGenerateRequest,
@@ -142,3 +241,6 @@ class PersonaAPI {
 }
 
 export const personaAPI = new PersonaAPI();
+
+/** `getCredentialData` error for a critical credential that needs the master password */
+export const MASTER_PASSWORD_REQUIRED = 'master_password_required';
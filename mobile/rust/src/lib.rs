@@ -1,5 +1,45 @@
+use persona_core::{AuthResult, Database, IdentityType, PersonaService, SecretString};
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
+use std::sync::{Mutex, OnceLock};
+use tokio::runtime::Runtime;
+use zeroize::Zeroize;
+
+/// Runtime used to drive the async service from the synchronous FFI entry points
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+/// Service opened by `persona_unlock` or `persona_initialize`, shared by the calls that follow it
+static SERVICE: Mutex<Option<PersonaService>> = Mutex::new(None);
+
+fn runtime() -> Result<&'static Runtime, String> {
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = Runtime::new().map_err(|e| format!("Failed to start runtime: {}", e))?;
+    // Another thread may have won the race; its runtime is used and ours dropped
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// Run `f` against the unlocked service
+fn with_service<T>(f: impl FnOnce(&mut PersonaService) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = SERVICE
+        .lock()
+        .map_err(|_| "Service state is poisoned".to_string())?;
+    match guard.as_mut() {
+        Some(service) if service.is_unlocked() => f(service),
+        _ => Err("Vault is locked; call persona_unlock first".to_string()),
+    }
+}
+
+/// Read a required C string argument
+unsafe fn c_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err(format!("{} cannot be null", name));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| format!("Invalid UTF-8 in {}", name))
+}
 
 /// Initialize the mobile library
 #[no_mangle]
@@ -11,14 +51,22 @@ pub extern "C" fn persona_init() -> i32 {
 /// Get version string
 #[no_mangle]
 pub extern "C" fn persona_version() -> *mut c_char {
-    let version = env!("CARGO_PKG_VERSION");
-    match CString::new(version) {
+    match CString::new(persona_core::VERSION) {
         Ok(c_string) => c_string.into_raw(),
         Err(_) => std::ptr::null_mut(),
     }
 }
 
-/// Free a string allocated by this library
+/// Get version, git revision and enabled features as JSON
+#[no_mangle]
+pub extern "C" fn persona_build_info() -> *mut c_char {
+    serde_json::to_string(&persona_core::build_info())
+        .ok()
+        .and_then(|json| CString::new(json).ok())
+        .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Free a string allocated by this library, overwriting its contents first
 /// # Safety
 /// Caller must pass a pointer returned by this library (e.g., from `persona_version`)
 /// and ensure it is not used after freeing. Passing any other pointer is undefined behavior.
@@ -27,7 +75,7 @@ pub unsafe extern "C" fn persona_free_string(s: *mut c_char) {
     if s.is_null() {
         return;
     }
-    let _ = CString::from_raw(s);
+    CString::from_raw(s).into_bytes_with_nul().zeroize();
 }
 
 /// Error handling
@@ -58,47 +106,273 @@ impl PersonaResult {
     }
 }
 
+impl From<Result<(), String>> for PersonaResult {
+    fn from(result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Self::success(),
+            Err(message) => Self::error(&message),
+        }
+    }
+}
+
 /// Free a PersonaResult
 /// # Safety
 /// The `error_message` pointer inside `PersonaResult` must either be null or allocated
 /// by this library. Caller must ensure it will not be reused after freeing.
 #[no_mangle]
 pub unsafe extern "C" fn persona_free_result(result: PersonaResult) {
-    if !result.error_message.is_null() {
-        let _ = CString::from_raw(result.error_message);
+    persona_free_string(result.error_message);
+}
+
+/// Open and migrate the vault database at `path`
+async fn open_service(path: &str) -> Result<PersonaService, String> {
+    let db = Database::from_file(path)
+        .await
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+    db.migrate()
+        .await
+        .map_err(|e| format!("Failed to run migrations: {}", e))?;
+    PersonaService::new(db)
+        .await
+        .map_err(|e| format!("Failed to create service: {}", e))
+}
+
+/// Make `service` the one later calls run against, replacing any previous one
+fn install_service(service: PersonaService) -> Result<(), String> {
+    let mut guard = SERVICE
+        .lock()
+        .map_err(|_| "Service state is poisoned".to_string())?;
+    *guard = Some(service);
+    Ok(())
+}
+
+/// Read the `db_path` and `master_password` arguments
+unsafe fn vault_args<'a>(
+    db_path: *const c_char,
+    master_password: *const c_char,
+) -> Result<(&'a str, SecretString), String> {
+    let path = c_str(db_path, "Database path")?;
+    let password = c_str(master_password, "Master password")?;
+    Ok((path, SecretString::from(password)))
+}
+
+/// Create a vault at `db_path` with `master_password` as its master password, and leave it
+/// unlocked. Fails if the database already has a vault; use `persona_unlock` for that.
+/// # Safety
+/// `db_path` and `master_password` must be valid null-terminated UTF-8 string pointers.
+/// Caller retains ownership of both.
+#[no_mangle]
+pub unsafe extern "C" fn persona_initialize(
+    db_path: *const c_char,
+    master_password: *const c_char,
+) -> PersonaResult {
+    let (path, password) = match vault_args(db_path, master_password) {
+        Ok(args) => args,
+        Err(message) => return PersonaResult::error(&message),
+    };
+
+    let initialized = runtime().and_then(|runtime| {
+        runtime.block_on(async {
+            let mut service = open_service(path).await?;
+            if service.has_users().await.map_err(|e| e.to_string())? {
+                return Err("Vault is already initialized; call persona_unlock".to_string());
+            }
+            service
+                .initialize_user(&password, None)
+                .await
+                .map_err(|e| format!("Failed to initialize vault: {}", e))?;
+            Ok(service)
+        })
+    });
+    initialized.and_then(install_service).into()
+}
+
+/// Open the vault database at `db_path` and unlock it with the master password.
+///
+/// Fails if there is no vault there yet; create one with `persona_initialize`. The unlocked
+/// service replaces any previous one.
+/// # Safety
+/// `db_path` and `master_password` must be valid null-terminated UTF-8 string pointers.
+/// Caller retains ownership of both.
+#[no_mangle]
+pub unsafe extern "C" fn persona_unlock(
+    db_path: *const c_char,
+    master_password: *const c_char,
+) -> PersonaResult {
+    let (path, password) = match vault_args(db_path, master_password) {
+        Ok(args) => args,
+        Err(message) => return PersonaResult::error(&message),
+    };
+    let no_vault = || format!("No vault at {}; call persona_initialize first", path);
+    // Opening creates the database file, so check first rather than leave an empty one behind
+    if !std::path::Path::new(path).exists() {
+        return PersonaResult::error(&no_vault());
     }
+
+    let unlocked = runtime().and_then(|runtime| {
+        runtime.block_on(async {
+            let mut service = open_service(path).await?;
+            if !service.has_users().await.map_err(|e| e.to_string())? {
+                return Err(no_vault());
+            }
+            match service
+                .authenticate_user(&password)
+                .await
+                .map_err(|e| e.to_string())?
+            {
+                AuthResult::Success => Ok(service),
+                AuthResult::PasswordChangeRequired if service.is_unlocked() => Ok(service),
+                AuthResult::InvalidCredentials => Err("Invalid master password".to_string()),
+                AuthResult::AccountLocked => Err("Account is locked".to_string()),
+                other => Err(format!("Authentication incomplete: {:?}", other)),
+            }
+        })
+    });
+    unlocked.and_then(install_service).into()
 }
 
-// Placeholder functions for mobile integration
-// These would be implemented based on specific mobile platform needs
+/// Lock the vault and drop the service opened by `persona_unlock` or `persona_initialize`
+#[no_mangle]
+pub extern "C" fn persona_lock() {
+    if let Ok(mut guard) = SERVICE.lock() {
+        if let Some(mut service) = guard.take() {
+            service.lock();
+        }
+    }
+}
 
-/// Create a new identity (placeholder)
+/// Create and persist a personal identity in the unlocked vault
 /// # Safety
 /// `name` must be a valid null-terminated UTF-8 string pointer. Caller retains ownership
 /// of the pointer and must not pass null.
 #[no_mangle]
 pub unsafe extern "C" fn persona_create_identity(name: *const c_char) -> PersonaResult {
-    if name.is_null() {
-        return PersonaResult::error("Name cannot be null");
+    let name = match c_str(name, "Name") {
+        Ok(name) => name.trim(),
+        Err(message) => return PersonaResult::error(&message),
+    };
+    if name.is_empty() {
+        return PersonaResult::error("Name cannot be empty");
+    }
+
+    let result = runtime().and_then(|runtime| {
+        with_service(|service| {
+            runtime
+                .block_on(service.create_identity(name.to_string(), IdentityType::Personal))
+                .map(|_| ())
+                .map_err(|e| format!("Failed to create identity: {}", e))
+        })
+    });
+    result.into()
+}
+
+/// List the identities in the unlocked vault as a JSON array.
+/// Returns null if the vault is locked or the identities cannot be read.
+/// The returned string must be released with `persona_free_string`.
+#[no_mangle]
+pub extern "C" fn persona_list_identities() -> *mut c_char {
+    let json = runtime().and_then(|runtime| {
+        with_service(|service| {
+            let identities = runtime
+                .block_on(service.get_identities())
+                .map_err(|e| e.to_string())?;
+            serde_json::to_string(&identities).map_err(|e| e.to_string())
+        })
+    });
+
+    json.ok()
+        .and_then(|json| CString::new(json).ok())
+        .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Normalize credential data JSON into the canonical versioned envelope
+/// (`{"schema_version", "type", "data"}`). Older externally-tagged JSON is accepted.
+/// Returns null if the input cannot be parsed.
+/// # Safety
+/// `json` must be a valid null-terminated UTF-8 string pointer. The returned string must be
+/// released with `persona_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn persona_credential_data_normalize(json: *const c_char) -> *mut c_char {
+    if json.is_null() {
+        return std::ptr::null_mut();
     }
 
-    let name_str = match CStr::from_ptr(name).to_str() {
+    let input = match CStr::from_ptr(json).to_str() {
         Ok(s) => s,
-        Err(_) => return PersonaResult::error("Invalid UTF-8 in name"),
+        Err(_) => return std::ptr::null_mut(),
     };
 
-    // TODO: Implement actual identity creation
-    println!("Creating identity: {}", name_str);
-    PersonaResult::success()
+    let canonical = serde_json::from_str(input)
+        .ok()
+        .and_then(|value| persona_core::models::CredentialData::from_canonical_json(value).ok())
+        .and_then(|data| data.to_canonical_json().ok());
+
+    // The envelope holds secrets; copy it out exactly once and wipe the intermediate
+    let c_string = canonical.and_then(|value| {
+        let mut text = value.to_string();
+        let c_string = CString::new(text.as_bytes()).ok();
+        text.zeroize();
+        c_string
+    });
+    c_string.map_or(std::ptr::null_mut(), CString::into_raw)
 }
 
-/// List identities (placeholder)
-#[no_mangle]
-pub extern "C" fn persona_list_identities() -> *mut c_char {
-    // TODO: Implement actual identity listing
-    let json = r#"[]"#;
-    match CString::new(json) {
-        Ok(c_string) => c_string.into_raw(),
-        Err(_) => std::ptr::null_mut(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initialize_unlock_create_and_list_identities() {
+        let db_path =
+            std::env::temp_dir().join(format!("persona-mobile-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&db_path);
+        let path = CString::new(db_path.to_string_lossy().as_ref()).unwrap();
+        let password = CString::new("mobile-password").unwrap();
+        let name = CString::new("Phone").unwrap();
+
+        unsafe {
+            // Locked: nothing to list or create into
+            assert!(persona_list_identities().is_null());
+            let result = persona_create_identity(name.as_ptr());
+            assert!(!result.success);
+            persona_free_result(result);
+
+            // Unlocking never creates a vault
+            let result = persona_unlock(path.as_ptr(), password.as_ptr());
+            assert!(!result.success);
+            persona_free_result(result);
+            assert!(!db_path.exists());
+
+            // Initializing sets the master password, and only works once
+            let result = persona_initialize(path.as_ptr(), password.as_ptr());
+            assert!(result.success);
+            let other = CString::new("other-password").unwrap();
+            let result = persona_initialize(path.as_ptr(), other.as_ptr());
+            assert!(!result.success);
+            persona_free_result(result);
+            let result = persona_unlock(path.as_ptr(), password.as_ptr());
+            assert!(result.success);
+            let result = persona_create_identity(name.as_ptr());
+            assert!(result.success);
+
+            // Re-opening requires the same password and sees the saved identity
+            persona_lock();
+            let wrong = CString::new("wrong").unwrap();
+            let result = persona_unlock(path.as_ptr(), wrong.as_ptr());
+            assert!(!result.success);
+            persona_free_result(result);
+            let result = persona_unlock(path.as_ptr(), password.as_ptr());
+            assert!(result.success);
+
+            let json = persona_list_identities();
+            assert!(!json.is_null());
+            let identities: serde_json::Value =
+                serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+            persona_free_string(json);
+            assert_eq!(identities.as_array().unwrap().len(), 1);
+            assert_eq!(identities[0]["name"], "Phone");
+            persona_lock();
+        }
+        let _ = std::fs::remove_file(&db_path);
     }
 }
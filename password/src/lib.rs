@@ -0,0 +1,10 @@
+//! Password code shared by `persona-core` and the browser extension's `wasm-crypto` module.
+//!
+//! Both sides must produce identical results, so the code lives here once. Keep this crate's
+//! dependencies small and free of anything that cannot build for `wasm32-unknown-unknown`.
+
+pub mod deterministic;
+pub mod strength;
+
+pub use deterministic::*;
+pub use strength::*;
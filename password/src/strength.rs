@@ -0,0 +1,183 @@
+//! Strength estimation for existing passwords, as shown by the CLI, desktop and browser
+//! extension.
+
+use serde::{Deserialize, Serialize};
+
+/// Length below which a password is flagged as too short
+pub const MIN_RECOMMENDED_LENGTH: usize = 12;
+
+/// Entropy (bits) needed for scores 1 through 4
+const SCORE_THRESHOLDS: [f64; 4] = [28.0, 36.0, 60.0, 80.0];
+
+/// Shortest ascending/descending run (`abcd`, `4321`) reported as a sequence
+const MIN_SEQUENCE_RUN: usize = 4;
+
+/// Shortest run of one repeated character reported as repetition
+const MIN_REPEAT_RUN: usize = 3;
+
+/// Common passwords and keyboard walks, matched case-insensitively as substrings
+const COMMON_PATTERNS: &[&str] = &[
+    "password", "passw0rd", "qwerty", "asdf", "zxcv", "letmein", "welcome", "admin", "iloveyou",
+    "monkey", "dragon", "abc123", "111111", "123123",
+];
+
+/// Strength estimate for a password, as shown by the CLI, desktop and browser extension
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PasswordStrength {
+    /// Upper-bound entropy from [`estimate_entropy_bits`]
+    pub entropy_bits: f64,
+    /// 0 (very weak) to 4 (very strong), after penalties for the warnings below
+    pub score: u8,
+    /// Human-readable problems, empty for a clean password
+    pub warnings: Vec<String>,
+}
+
+/// Entropy in bits of `length` independent picks from `pool_size` equally likely symbols.
+pub fn entropy_bits(pool_size: usize, length: usize) -> f64 {
+    if pool_size < 2 {
+        return 0.0;
+    }
+    length as f64 * (pool_size as f64).log2()
+}
+
+/// Rough entropy estimate, in bits, for an existing (not generated) password.
+///
+/// Assumes each character was drawn from the union of the character classes present and ignores
+/// immediate repeats (`aaaa` counts as one character). Dictionary words are not detected, so this
+/// overestimates human-chosen passwords; treat it as an upper bound.
+pub fn estimate_entropy_bits(password: &str) -> f64 {
+    let (mut lower, mut upper, mut digit, mut symbol, mut other) =
+        (false, false, false, false, false);
+    let mut length = 0;
+    let mut previous = None;
+    for c in password.chars() {
+        match c {
+            'a'..='z' => lower = true,
+            'A'..='Z' => upper = true,
+            '0'..='9' => digit = true,
+            c if c.is_ascii() => symbol = true,
+            _ => other = true,
+        }
+        if previous != Some(c) {
+            length += 1;
+        }
+        previous = Some(c);
+    }
+
+    let pool = [
+        (lower, 26),
+        (upper, 26),
+        (digit, 10),
+        (symbol, 33),
+        (other, 100),
+    ]
+    .iter()
+    .filter(|(present, _)| *present)
+    .map(|(_, size)| size)
+    .sum();
+    entropy_bits(pool, length)
+}
+
+/// Score a password from its entropy, then cap it for the patterns attackers try first.
+///
+/// Common words and sequences cap the score at 1 and short passwords at 2; runs of a repeated
+/// character cost one point.
+pub fn password_strength(password: &str) -> PasswordStrength {
+    let entropy = estimate_entropy_bits(password);
+    let mut score = SCORE_THRESHOLDS.iter().filter(|t| entropy >= **t).count() as u8;
+    let mut warnings = Vec::new();
+
+    if password.chars().count() < MIN_RECOMMENDED_LENGTH {
+        warnings.push(format!(
+            "Too short: use at least {} characters",
+            MIN_RECOMMENDED_LENGTH
+        ));
+        score = score.min(2);
+    }
+
+    let lowered = password.to_lowercase();
+    if COMMON_PATTERNS
+        .iter()
+        .any(|pattern| lowered.contains(pattern))
+    {
+        warnings.push("Contains a common password or keyboard pattern".to_string());
+        score = score.min(1);
+    }
+
+    let chars: Vec<char> = lowered.chars().collect();
+    if has_sequence(&chars) {
+        warnings.push("Contains a predictable sequence like 'abcd' or '1234'".to_string());
+        score = score.min(1);
+    }
+
+    if longest_repeat(&chars) >= MIN_REPEAT_RUN {
+        warnings.push("Contains repeated characters like 'aaa'".to_string());
+        score = score.saturating_sub(1);
+    }
+
+    PasswordStrength {
+        entropy_bits: entropy,
+        score,
+        warnings,
+    }
+}
+
+/// Whether `chars` contains an ascending or descending run of consecutive letters or digits
+fn has_sequence(chars: &[char]) -> bool {
+    chars.windows(MIN_SEQUENCE_RUN).any(|window| {
+        window.iter().all(|c| c.is_ascii_alphanumeric())
+            && [1i32, -1].iter().any(|step| {
+                window
+                    .windows(2)
+                    .all(|pair| pair[1] as i32 - pair[0] as i32 == *step)
+            })
+    })
+}
+
+fn longest_repeat(chars: &[char]) -> usize {
+    chars
+        .chunk_by(|a, b| a == b)
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimated_entropy_rewards_length_and_variety() {
+        assert_eq!(estimate_entropy_bits(""), 0.0);
+        assert!((estimate_entropy_bits("abcdefgh") - 8.0 * 26f64.log2()).abs() < 1e-9);
+        assert_eq!(
+            estimate_entropy_bits("aaaaaaaaaaaa"),
+            estimate_entropy_bits("a")
+        );
+        assert!(estimate_entropy_bits("Tr0ub4dor&3") > estimate_entropy_bits("troubador"));
+        assert!(estimate_entropy_bits("v9#Lq2!xR7@mWz4p") > 100.0);
+    }
+
+    #[test]
+    fn strength_scores_and_warns() {
+        let empty = password_strength("");
+        assert_eq!(empty.score, 0);
+        assert_eq!(empty.warnings.len(), 1);
+
+        let common = password_strength("Password2024!xyz");
+        assert_eq!(common.score, 1);
+        assert!(common.warnings[0].contains("common"));
+
+        let sequence = password_strength("wq1234XXXzpVbr!");
+        assert_eq!(sequence.score, 0);
+        assert_eq!(sequence.warnings.len(), 2);
+
+        let short = password_strength("Tr0ub4dor&3");
+        assert_eq!(short.score, 2);
+        assert!(short.warnings[0].starts_with("Too short"));
+
+        let strong = password_strength("v9#Lq2!xR7@mWz4p");
+        assert_eq!(strong.score, 4);
+        assert!(strong.warnings.is_empty());
+    }
+}
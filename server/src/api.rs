@@ -0,0 +1,700 @@
+//! REST API under `/api/v1`, backed by an unlocked [`PersonaService`].
+//!
+//! `POST /api/v1/unlock` opens the vault with the master password and returns a bearer token;
+//! every other route requires `Authorization: Bearer <token>`. Response bodies use the same
+//! shapes as the desktop app's Tauri commands.
+//!
+//! Each unlock starts an [`auth::session`](persona_core::auth::session) session governed by the
+//! vault's default [`AutoLockPolicy`]. Requests count as activity; a background task locks the
+//! service once the session idles out or hits its absolute timeout, after which the token is
+//! rejected with 401.
+
+use axum::{
+    extract::{Path, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use persona_core::{
+    models::{CredentialData, OtpType},
+    storage::AutoLockPolicyRepository,
+    AuthResult, AutoLockConfig, AutoLockPolicy, AutoLockSecurityLevel, Credential, Database,
+    EncryptionService, Identity, PersonaError, PersonaService, SecretString, SessionManager,
+    TotpVerification,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::AppState;
+
+/// Response envelope shared with the desktop app
+#[derive(Debug, Serialize)]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    pub data: Option<T>,
+    pub error: Option<String>,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn success(data: T) -> Self {
+        Self {
+            success: true,
+            data: Some(data),
+            error: None,
+        }
+    }
+
+    pub fn error(message: String) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(message),
+        }
+    }
+}
+
+/// An error response with its HTTP status
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(ApiResponse::<()>::error(self.1))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<ApiResponse<T>>, ApiError>;
+
+fn internal(e: impl std::fmt::Display) -> ApiError {
+    ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+fn parse_id(id: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(id)
+        .map_err(|_| ApiError(StatusCode::BAD_REQUEST, "Invalid UUID format".to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnlockRequest {
+    pub master_password: SecretString,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnlockResponse {
+    pub token: String,
+    /// Absolute expiry of the session (RFC 3339)
+    pub expires_at: String,
+    /// Idle time after which the vault locks itself
+    pub inactivity_timeout_secs: u64,
+}
+
+/// User id recorded on API sessions; the server holds a single vault
+const SESSION_USER: &str = "api";
+
+/// The bearer token handed out by the last unlock and the session it maps to
+pub(crate) struct ApiSession {
+    token: String,
+    sessions: Arc<SessionManager>,
+    session_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SerializableIdentity {
+    pub id: String,
+    pub name: String,
+    pub identity_type: String,
+    pub description: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub ssh_key: Option<String>,
+    pub gpg_key: Option<String>,
+    pub tags: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub is_active: bool,
+    pub version: i64,
+}
+
+impl From<Identity> for SerializableIdentity {
+    fn from(identity: Identity) -> Self {
+        Self {
+            id: identity.id.to_string(),
+            name: identity.name,
+            identity_type: identity.identity_type.to_string(),
+            description: identity.description,
+            email: identity.email,
+            phone: identity.phone,
+            ssh_key: identity.ssh_key,
+            gpg_key: identity.gpg_key,
+            tags: identity.tags,
+            created_at: identity.created_at.to_rfc3339(),
+            updated_at: identity.updated_at.to_rfc3339(),
+            is_active: identity.is_active,
+            version: identity.version,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SerializableCredential {
+    pub id: String,
+    pub identity_id: String,
+    pub name: String,
+    pub credential_type: String,
+    pub security_level: String,
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub notes: Option<String>,
+    pub tags: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub last_accessed: Option<String>,
+    pub is_active: bool,
+    pub is_favorite: bool,
+    pub linked_totp_id: Option<String>,
+    pub version: i64,
+}
+
+impl From<Credential> for SerializableCredential {
+    fn from(credential: Credential) -> Self {
+        Self {
+            id: credential.id.to_string(),
+            identity_id: credential.identity_id.to_string(),
+            name: credential.name,
+            credential_type: credential.credential_type.to_string(),
+            security_level: credential.security_level.to_string(),
+            url: credential.url,
+            username: credential.username,
+            notes: credential.notes,
+            tags: credential.tags,
+            created_at: credential.created_at.to_rfc3339(),
+            updated_at: credential.updated_at.to_rfc3339(),
+            last_accessed: credential.last_accessed.map(|dt| dt.to_rfc3339()),
+            is_active: credential.is_active,
+            is_favorite: credential.is_favorite,
+            linked_totp_id: credential.linked_totp_id.map(|id| id.to_string()),
+            version: credential.version,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpCodeResponse {
+    pub code: String,
+    /// 0 for HOTP codes, which stay valid until used
+    pub remaining_seconds: u32,
+    pub period: u32,
+    pub digits: u8,
+    pub algorithm: String,
+    pub issuer: String,
+    pub account_name: String,
+    pub otp_type: OtpType,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyTotpRequest {
+    pub code: String,
+}
+
+/// Routes under `/api/v1`
+pub fn router(state: AppState) -> Router<AppState> {
+    let protected = Router::new()
+        .route("/lock", post(lock))
+        .route("/identities", get(list_identities))
+        .route("/identities/:id/credentials", get(list_credentials))
+        .route("/credentials/:id/totp", get(totp_code))
+        .route("/credentials/:id/totp/verify", post(verify_totp))
+        .route_layer(middleware::from_fn_with_state(state, require_token));
+
+    Router::new()
+        .route("/unlock", post(unlock))
+        .merge(protected)
+}
+
+/// Reject requests without the bearer token minted by the last unlock, and record activity
+/// on its session
+async fn require_token(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let mut current = state.session.lock().await;
+    let session = match (presented, current.as_ref()) {
+        (Some(presented), Some(session)) if constant_time_eq(presented, &session.token) => session,
+        _ => {
+            return Err(ApiError(
+                StatusCode::UNAUTHORIZED,
+                "Missing or invalid bearer token".to_string(),
+            ))
+        }
+    };
+    // The watcher may not have run yet; don't serve a request on a session that already timed out
+    if !session.sessions.is_valid(&session.session_id).await
+        || session.sessions.touch(&session.session_id).await.is_err()
+    {
+        end_session(&state, &mut current).await;
+        return Err(ApiError(
+            StatusCode::UNAUTHORIZED,
+            "Session expired; unlock again".to_string(),
+        ));
+    }
+    drop(current);
+    Ok(next.run(request).await)
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+/// Open the configured vault, unlock it and mint a new bearer token
+async fn unlock(
+    State(state): State<AppState>,
+    Json(request): Json<UnlockRequest>,
+) -> ApiResult<UnlockResponse> {
+    if !state.db_path.exists() {
+        return Err(ApiError(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("Database {} does not exist", state.db_path.display()),
+        ));
+    }
+    let db = Database::from_file(state.db_path.as_ref())
+        .await
+        .map_err(internal)?;
+    db.migrate().await.map_err(internal)?;
+    let mut service = PersonaService::new(db.clone()).await.map_err(internal)?;
+
+    match service
+        .authenticate_user(&request.master_password)
+        .await
+        .map_err(internal)?
+    {
+        AuthResult::Success => {}
+        AuthResult::PasswordChangeRequired if service.is_unlocked() => {}
+        AuthResult::AccountLocked => {
+            return Err(ApiError(
+                StatusCode::LOCKED,
+                "Account is locked".to_string(),
+            ))
+        }
+        _ => {
+            return Err(ApiError(
+                StatusCode::UNAUTHORIZED,
+                "Invalid master password".to_string(),
+            ))
+        }
+    }
+
+    let policy = session_policy(&db).await;
+    let sessions = Arc::new(SessionManager::with_config(AutoLockConfig::from(&policy)));
+    let session = sessions.create_session(SESSION_USER.to_string()).await;
+    let token = hex::encode(EncryptionService::generate_key());
+
+    {
+        let mut current = state.session.lock().await;
+        end_session(&state, &mut current).await;
+        *state.service.lock().await = Some(service);
+        *current = Some(ApiSession {
+            token: token.clone(),
+            sessions,
+            session_id: session.id.clone(),
+        });
+    }
+    let interval = Duration::from_secs(policy.background_check_interval_secs.max(1));
+    tokio::spawn(watch_auto_lock(state.clone(), token.clone(), interval));
+
+    Ok(Json(ApiResponse::success(UnlockResponse {
+        token,
+        expires_at: DateTime::<Utc>::from(session.expires_at).to_rfc3339(),
+        inactivity_timeout_secs: policy.inactivity_timeout_secs,
+    })))
+}
+
+/// The vault's default auto-lock policy, falling back to the Medium preset
+async fn session_policy(db: &Database) -> AutoLockPolicy {
+    let repository = AutoLockPolicyRepository::new(Arc::new(db.clone()));
+    match repository.get_default_policy().await {
+        Ok(Some(policy)) => policy,
+        Ok(None) => fallback_policy(),
+        Err(e) => {
+            warn!("Failed to load default auto-lock policy: {}", e);
+            fallback_policy()
+        }
+    }
+}
+
+fn fallback_policy() -> AutoLockPolicy {
+    AutoLockPolicy::new(
+        "Server default".to_string(),
+        AutoLockSecurityLevel::Medium,
+        900,
+    )
+}
+
+/// Lock the service and forget the session; `current` is the held `state.session` guard
+async fn end_session(state: &AppState, current: &mut Option<ApiSession>) {
+    if let Some(session) = current.take() {
+        session.sessions.remove_session(&session.session_id).await;
+    }
+    if let Some(mut service) = state.service.lock().await.take() {
+        service.lock();
+    }
+}
+
+/// Lock the vault once the session behind `token` idles out or expires. Exits early when the
+/// session is ended or replaced by another unlock.
+async fn watch_auto_lock(state: AppState, token: String, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        let mut current = state.session.lock().await;
+        let Some(session) = current.as_ref().filter(|session| session.token == token) else {
+            return;
+        };
+        if !session.sessions.is_valid(&session.session_id).await {
+            info!("API session timed out; locking vault");
+            end_session(&state, &mut current).await;
+            return;
+        }
+    }
+}
+
+/// Lock the vault and revoke the token
+async fn lock(State(state): State<AppState>) -> ApiResult<()> {
+    let mut current = state.session.lock().await;
+    end_session(&state, &mut current).await;
+    Ok(Json(ApiResponse::success(())))
+}
+
+async fn list_identities(State(state): State<AppState>) -> ApiResult<Vec<SerializableIdentity>> {
+    let guard = state.service.lock().await;
+    let service = unlocked(&guard)?;
+    let identities = service.get_identities().await.map_err(internal)?;
+    Ok(Json(ApiResponse::success(
+        identities.into_iter().map(Into::into).collect(),
+    )))
+}
+
+async fn list_credentials(
+    State(state): State<AppState>,
+    Path(identity_id): Path<String>,
+) -> ApiResult<Vec<SerializableCredential>> {
+    let identity_id = parse_id(&identity_id)?;
+    let guard = state.service.lock().await;
+    let service = unlocked(&guard)?;
+    if service
+        .get_identity(&identity_id)
+        .await
+        .map_err(internal)?
+        .is_none()
+    {
+        return Err(ApiError(
+            StatusCode::NOT_FOUND,
+            "Identity not found".to_string(),
+        ));
+    }
+    let credentials = service
+        .get_credentials_for_identity(&identity_id)
+        .await
+        .map_err(internal)?;
+    Ok(Json(ApiResponse::success(
+        credentials.into_iter().map(Into::into).collect(),
+    )))
+}
+
+/// Current code of a TwoFactor credential, without exposing its secret
+async fn totp_code(
+    State(state): State<AppState>,
+    Path(credential_id): Path<String>,
+) -> ApiResult<TotpCodeResponse> {
+    let credential_id = parse_id(&credential_id)?;
+    let guard = state.service.lock().await;
+    let service = unlocked(&guard)?;
+    let data = service
+        .get_credential_data(&credential_id)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, "Credential not found".to_string()))?;
+    let CredentialData::TwoFactor(tf) = data else {
+        return Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            "Credential is not a TwoFactor entry".to_string(),
+        ));
+    };
+    // HOTP entries hand out the next code and persist the advanced counter
+    let current = service
+        .two_factor_code(&credential_id)
+        .await
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(TotpCodeResponse {
+        code: current.code,
+        remaining_seconds: current.remaining,
+        period: tf.period.max(1),
+        digits: tf.digits.clamp(4, 10),
+        algorithm: tf.algorithm,
+        issuer: tf.issuer,
+        account_name: tf.account_name,
+        otp_type: tf.otp_type,
+    })))
+}
+
+/// Check a code entered for a TOTP credential; a code is accepted once per unlock.
+///
+/// The drift tolerance is fixed server-side rather than chosen by the client.
+async fn verify_totp(
+    State(state): State<AppState>,
+    Path(credential_id): Path<String>,
+    Json(request): Json<VerifyTotpRequest>,
+) -> ApiResult<TotpVerification> {
+    let credential_id = parse_id(&credential_id)?;
+    let guard = state.service.lock().await;
+    let service = unlocked(&guard)?;
+    let verification = service
+        .verify_totp(
+            &credential_id,
+            &request.code,
+            persona_core::otp::DEFAULT_VERIFY_SKEW_STEPS,
+        )
+        .await
+        .map_err(|e| match e.downcast_ref::<PersonaError>() {
+            Some(PersonaError::NotFound(_)) => ApiError(StatusCode::NOT_FOUND, e.to_string()),
+            Some(PersonaError::InvalidInput(_)) => ApiError(StatusCode::BAD_REQUEST, e.to_string()),
+            _ => internal(e),
+        })?;
+    Ok(Json(ApiResponse::success(verification)))
+}
+
+/// The unlocked service, or 423 if the vault auto-locked since the token was minted
+fn unlocked(service: &Option<PersonaService>) -> Result<&PersonaService, ApiError> {
+    service
+        .as_ref()
+        .filter(|service| service.is_unlocked())
+        .ok_or_else(|| ApiError(StatusCode::LOCKED, "Vault is locked".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use persona_core::models::{
+        CredentialType, IdentityType, OtpType, SecurityLevel, TwoFactorData,
+    };
+    use tower_05::ServiceExt;
+
+    async fn send(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = match body {
+            Some(body) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        }
+        .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_api_requires_unlock_token() {
+        let dir = std::env::temp_dir().join(format!("persona-api-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("vault.db");
+
+        // Seed a vault with an identity and a TOTP credential
+        let db = Database::from_file(&db_path).await.unwrap();
+        db.migrate().await.unwrap();
+        let mut service = PersonaService::new(db).await.unwrap();
+        service
+            .initialize_user(&SecretString::from("api-password"), None)
+            .await
+            .unwrap();
+        let identity = service
+            .create_identity("Work".to_string(), IdentityType::Work)
+            .await
+            .unwrap();
+        let totp = service
+            .create_credential(
+                identity.id,
+                "GitHub 2FA".to_string(),
+                CredentialType::TwoFactor,
+                SecurityLevel::High,
+                &CredentialData::TwoFactor(TwoFactorData {
+                    secret_key: "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ".into(),
+                    issuer: "GitHub".to_string(),
+                    account_name: "octocat".to_string(),
+                    algorithm: "SHA1".to_string(),
+                    digits: 6,
+                    period: 30,
+                    otp_type: OtpType::Totp,
+                    counter: 0,
+                    time_offset_seconds: 0,
+                }),
+            )
+            .await
+            .unwrap();
+        drop(service);
+
+        let state = AppState::new(db_path);
+        let app = Router::new()
+            .nest("/api/v1", router(state.clone()))
+            .with_state(state);
+
+        let (status, _) = send(&app, "GET", "/api/v1/identities", None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let wrong = serde_json::json!({ "master_password": "wrong" });
+        let (status, body) = send(&app, "POST", "/api/v1/unlock", None, Some(wrong)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["success"], false);
+
+        let right = serde_json::json!({ "master_password": "api-password" });
+        let (status, body) = send(&app, "POST", "/api/v1/unlock", None, Some(right)).await;
+        assert_eq!(status, StatusCode::OK);
+        let token = body["data"]["token"].as_str().unwrap().to_string();
+        assert_eq!(token.len(), 64);
+
+        let (status, _) = send(&app, "GET", "/api/v1/identities", Some("nope"), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, body) = send(&app, "GET", "/api/v1/identities", Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"][0]["name"], "Work");
+
+        let uri = format!("/api/v1/identities/{}/credentials", identity.id);
+        let (status, body) = send(&app, "GET", &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"][0]["name"], "GitHub 2FA");
+        assert!(body["data"][0].get("secret_key").is_none());
+
+        let uri = format!("/api/v1/credentials/{}/totp", totp.id);
+        let (status, body) = send(&app, "GET", &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["code"].as_str().unwrap().len(), 6);
+
+        let code = body["data"]["code"].clone();
+        let uri = format!("/api/v1/credentials/{}/totp/verify", totp.id);
+        let (status, body) = send(
+            &app,
+            "POST",
+            &uri,
+            Some(&token),
+            Some(serde_json::json!({ "code": code })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["status"], "accepted");
+        let (_, body) = send(
+            &app,
+            "POST",
+            &uri,
+            Some(&token),
+            Some(serde_json::json!({ "code": code })),
+        )
+        .await;
+        assert_eq!(body["data"]["status"], "replayed");
+        // Clients cannot widen the drift tolerance
+        let (status, body) = send(
+            &app,
+            "POST",
+            &uri,
+            Some(&token),
+            Some(serde_json::json!({ "code": code, "skew_steps": 4_000_000_000u32 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["status"], "replayed");
+        let missing = format!("/api/v1/credentials/{}/totp/verify", Uuid::new_v4());
+        let (status, _) = send(
+            &app,
+            "POST",
+            &missing,
+            Some(&token),
+            Some(serde_json::json!({ "code": code })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let uri = format!("/api/v1/identities/{}/credentials", Uuid::new_v4());
+        let (status, _) = send(&app, "GET", &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Locking revokes the token
+        let (status, _) = send(&app, "POST", "/api/v1/lock", Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(&app, "GET", "/api/v1/identities", Some(&token), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_session_auto_locks_after_inactivity() {
+        let dir = std::env::temp_dir().join(format!("persona-api-lock-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("vault.db");
+
+        let db = Database::from_file(&db_path).await.unwrap();
+        db.migrate().await.unwrap();
+        let mut policy = AutoLockPolicy::new("Test".to_string(), AutoLockSecurityLevel::High, 1);
+        policy.background_check_interval_secs = 1;
+        let repository = AutoLockPolicyRepository::new(Arc::new(db.clone()));
+        let policy = repository.create(&policy).await.unwrap();
+        repository.set_as_default(&policy.id).await.unwrap();
+        let mut service = PersonaService::new(db).await.unwrap();
+        service
+            .initialize_user(&SecretString::from("api-password"), None)
+            .await
+            .unwrap();
+        drop(service);
+
+        let state = AppState::new(db_path);
+        let app = Router::new()
+            .nest("/api/v1", router(state.clone()))
+            .with_state(state.clone());
+
+        let right = serde_json::json!({ "master_password": "api-password" });
+        let (status, body) = send(&app, "POST", "/api/v1/unlock", None, Some(right)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["inactivity_timeout_secs"], 1);
+        let token = body["data"]["token"].as_str().unwrap().to_string();
+
+        let (status, _) = send(&app, "GET", "/api/v1/identities", Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // The background task locks the service without any further request
+        tokio::time::sleep(Duration::from_millis(3500)).await;
+        assert!(state.service.lock().await.is_none());
+        assert!(state.session.lock().await.is_none());
+
+        let (status, _) = send(&app, "GET", "/api/v1/identities", Some(&token), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,37 +1,232 @@
-use axum::{routing::get, Router};
-use persona_core::RedactedLoggerBuilder;
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use persona_core::{build_info, BuildInfo, Database, PersonaService, RedactedLoggerBuilder};
+use serde::Serialize;
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
 use tower_http::cors::CorsLayer;
-use tracing::{info, Level};
+use tracing::{info, warn, Level};
+
+mod api;
+
+/// Environment variable naming the vault database checked by `/ready`
+const DB_PATH_ENV: &str = "PERSONA_DB_PATH";
+const DEFAULT_DB_PATH: &str = "persona.db";
+
+/// Environment variable selecting the log format; `json` writes one JSON object per log line, for log shippers
+const LOG_FORMAT_ENV: &str = "PERSONA_LOG_FORMAT";
+
+#[derive(Clone)]
+struct AppState {
+    db_path: Arc<PathBuf>,
+    /// Service opened by `/api/v1/unlock`, like the desktop app's `AppState`
+    service: Arc<Mutex<Option<PersonaService>>>,
+    /// Session started by the last unlock; lock this before `service`
+    session: Arc<Mutex<Option<api::ApiSession>>>,
+}
+
+impl AppState {
+    fn new(db_path: PathBuf) -> Self {
+        Self {
+            db_path: Arc::new(db_path),
+            service: Arc::new(Mutex::new(None)),
+            session: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+/// Body of `/ready`
+#[derive(Debug, Serialize)]
+struct Readiness {
+    status: &'static str,
+    db_ok: bool,
+    migrations_pending: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
 
 #[tokio::main]
 async fn main() {
     // Initialize tracing
+    let json_logs = std::env::var(LOG_FORMAT_ENV).is_ok_and(|format| format == "json");
     RedactedLoggerBuilder::new(Level::INFO)
         .include_target(true)
+        .json(json_logs)
         .init()
         .expect("failed to initialize logging");
 
-    // Build our application with a route
-    let app = Router::new()
-        .route("/", get(root))
-        .route("/health", get(health_check))
-        .layer(CorsLayer::permissive());
+    let _ = dotenvy::dotenv();
+    let db_path = std::env::var_os(DB_PATH_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DB_PATH));
+    let app = app(AppState::new(db_path));
 
     // Run it with hyper on localhost:3000
     let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
-    info!("Persona server listening on {}", addr);
+    info!(
+        "Persona server {} listening on {}",
+        persona_core::LONG_VERSION,
+        addr
+    );
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     axum::serve(listener, app).await.unwrap();
 }
 
+/// All routes. Only the public status routes accept cross-origin requests: `/api/v1` hands
+/// out vault contents, so browsers may only call it from the same origin.
+fn app(state: AppState) -> Router {
+    let public = Router::new()
+        .route("/", get(root))
+        .route("/health", get(health_check))
+        .route("/ready", get(readiness))
+        .route("/version", get(version))
+        .layer(CorsLayer::permissive());
+
+    Router::new()
+        .merge(public)
+        .nest("/api/v1", api::router(state.clone()))
+        .with_state(state)
+}
+
 // Basic handler that responds with a static string
 async fn root() -> &'static str {
     "Persona Server"
 }
 
-// Health check endpoint
+// Liveness: the process is up; deliberately touches nothing else
 async fn health_check() -> &'static str {
     "OK"
 }
+
+// Readiness: the database opens, answers a query and is fully migrated
+async fn readiness(State(state): State<AppState>) -> (StatusCode, Json<Readiness>) {
+    let readiness = check_readiness(&state.db_path).await;
+    if let Some(error) = &readiness.error {
+        warn!("Readiness check failed: {}", error);
+    }
+    let code = if readiness.status == "ready" {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(readiness))
+}
+
+async fn check_readiness(db_path: &Path) -> Readiness {
+    let not_ready = |db_ok, migrations_pending, error: String| Readiness {
+        status: "not_ready",
+        db_ok,
+        migrations_pending,
+        error: Some(error),
+    };
+
+    // Opening would create an empty vault, which is never what a probe wants
+    if !db_path.exists() {
+        return not_ready(
+            false,
+            false,
+            format!("database {} does not exist", db_path.display()),
+        );
+    }
+    let db = match Database::from_file(db_path).await {
+        Ok(db) => db,
+        Err(e) => return not_ready(false, false, e.to_string()),
+    };
+    if let Err(e) = sqlx::query("SELECT 1").execute(db.pool()).await {
+        return not_ready(false, false, e.to_string());
+    }
+
+    let applied = match db.schema_version().await {
+        Ok(version) => version.unwrap_or(0),
+        Err(e) => return not_ready(false, false, e.to_string()),
+    };
+    let latest = Database::latest_schema_version();
+    if applied < latest {
+        return not_ready(
+            true,
+            true,
+            format!("schema version {} is behind {}", applied, latest),
+        );
+    }
+
+    Readiness {
+        status: "ready",
+        db_ok: true,
+        migrations_pending: false,
+        error: None,
+    }
+}
+
+// Version, git revision and enabled features
+async fn version() -> Json<BuildInfo> {
+    Json(build_info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_readiness_reports_database_state() {
+        let dir = std::env::temp_dir().join(format!("persona-ready-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("vault.db");
+
+        let missing = check_readiness(&db_path).await;
+        assert_eq!(missing.status, "not_ready");
+        assert!(!missing.db_ok);
+        assert!(!db_path.exists());
+
+        let db = Database::from_file(&db_path).await.unwrap();
+        let pending = check_readiness(&db_path).await;
+        assert!(pending.db_ok);
+        assert!(pending.migrations_pending);
+
+        db.migrate().await.unwrap();
+        let ready = check_readiness(&db_path).await;
+        assert_eq!(ready.status, "ready");
+        assert!(ready.db_ok && !ready.migrations_pending);
+        assert_eq!(
+            serde_json::to_value(&ready).unwrap(),
+            serde_json::json!({ "status": "ready", "db_ok": true, "migrations_pending": false })
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_cors_only_covers_public_routes() {
+        use axum::body::Body;
+        use axum::http::{header, Method, Request};
+        use tower_05::ServiceExt;
+
+        let app = app(AppState::new(PathBuf::from("unused.db")));
+        let preflight = |uri: &str| {
+            Request::builder()
+                .method(Method::OPTIONS)
+                .uri(uri)
+                .header(header::ORIGIN, "https://evil.example")
+                .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let public = app.clone().oneshot(preflight("/version")).await.unwrap();
+        assert!(public
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        for uri in ["/api/v1/unlock", "/api/v1/identities"] {
+            let response = app.clone().oneshot(preflight(uri)).await.unwrap();
+            assert!(
+                !response
+                    .headers()
+                    .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN),
+                "{} allows cross-origin requests",
+                uri
+            );
+        }
+    }
+}